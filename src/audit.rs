@@ -0,0 +1,266 @@
+use crate::condition;
+use crate::properties::Properties;
+use crate::repair;
+use crate::vcxproj::{attribute_value, filters_path, VcxprojFile};
+use regex::Regex;
+use std::collections::HashMap;
+use std::path::{Component, Path, PathBuf};
+
+/// Item types the editing commands know; others are listed but never created or given filters
+const KNOWN_ITEM_TYPES: &[&str] = &[
+    "ProjectConfiguration",
+    "ClCompile",
+    "ClInclude",
+    "None",
+    "Text",
+    "ResourceCompile",
+    "Image",
+    "Natvis",
+    "Manifest",
+    "ProjectReference",
+    "PackageReference",
+];
+
+/// A construct in a project that commands cannot parse or would not edit safely
+#[derive(Debug, Clone)]
+pub struct Finding {
+    /// Stable identifier of the construct, e.g. "wildcard-include"
+    pub kind: &'static str,
+    /// 1-based line of the construct (0 for the file as a whole)
+    pub line: usize,
+    pub detail: String,
+}
+
+/// What a kind of finding means for batch edits
+pub fn describe(kind: &str) -> &'static str {
+    match kind {
+        "unreadable" => "not UTF-8 text; no command can read it",
+        "malformed-xml" => "not well-formed XML; run `repair` first (nothing else was checked)",
+        "missing-filters" => "no .vcxproj.filters file; filter commands have nothing to edit",
+        "wildcard-include" => "wildcard Include; add, delete and rename only see listed files (see `enumerate`)",
+        "remove-item" => "item Remove; files it removes may be added back",
+        "unknown-item-type" => "item type the tool does not know; it is listed but never created or filtered",
+        "conditional-item" => "conditional item or ItemGroup; edits ignore the condition",
+        "custom-condition" => "condition that names no configuration; --config cannot select it",
+        "multi-line-tag" => "tag spanning several lines; line-based edits cannot parse it",
+        "several-elements-per-line" => "several elements on one line; line-based edits only see the first",
+        "item-case" => "item path cased differently from the file on disk; breaks on case-sensitive file systems",
+        "include-dir-case" => "include directory cased differently from the directory on disk",
+        "include-case" => "#include cased differently from the header it finds; breaks with clang or gcc on Linux",
+        _ => "",
+    }
+}
+
+/// Scans a project for constructs the line-based editing commands cannot parse or would not edit safely
+pub fn audit(vcxproj: &VcxprojFile) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    if let Err(problem) = repair::check_well_formed(&vcxproj.content) {
+        findings.push(Finding { kind: "malformed-xml", line: 0, detail: problem });
+        return findings;
+    }
+    let filter_path = filters_path(&vcxproj.path);
+    if !filter_path.exists() {
+        findings.push(Finding { kind: "missing-filters", line: 0, detail: filter_path.display().to_string() });
+    }
+
+    let opening_tag = Regex::new(r"<[A-Za-z_]").unwrap();
+    let mut in_comment = false;
+    let mut in_item_group = false;
+    for (index, line) in vcxproj.content.lines().enumerate() {
+        let code: String = repair::code_ranges(line, &mut in_comment).into_iter().map(|range| &line[range]).collect();
+        let trimmed = code.trim();
+        if trimmed.is_empty() {
+            continue;
+        }
+        let mut found = |kind: &'static str, detail: &str| {
+            findings.push(Finding { kind, line: index + 1, detail: detail.to_string() });
+        };
+
+        if trimmed.rfind('<') > trimmed.rfind('>') {
+            found("multi-line-tag", trimmed);
+        }
+        if opening_tag.find_iter(trimmed).count() > 1 {
+            found("several-elements-per-line", trimmed);
+        }
+
+        let element = trimmed
+            .strip_prefix('<')
+            .and_then(|rest| rest.split(|c: char| c.is_whitespace() || c == '>' || c == '/').next())
+            .unwrap_or_default();
+        let condition = attribute_value(trimmed, "Condition");
+        match element {
+            _ if trimmed.starts_with("</ItemGroup") => in_item_group = false,
+            // A closing tag, the rest of a multi-line tag, or text
+            "" => {}
+            "ItemGroup" => {
+                in_item_group = !trimmed.ends_with("/>");
+                if condition.is_some() {
+                    found("conditional-item", trimmed);
+                }
+            }
+            "PropertyGroup" | "ItemDefinitionGroup" | "ImportGroup"
+                if condition.is_some_and(|c| condition::config_selection(c).is_none()) =>
+            {
+                found("custom-condition", trimmed);
+            }
+            _ if in_item_group => {
+                let include = attribute_value(trimmed, "Include");
+                let remove = attribute_value(trimmed, "Remove");
+                if include.is_none() && remove.is_none() && attribute_value(trimmed, "Update").is_none() {
+                    continue;
+                }
+                if !KNOWN_ITEM_TYPES.contains(&element) {
+                    found("unknown-item-type", trimmed);
+                }
+                if include.is_some_and(|i| i.contains(['*', '?'])) {
+                    found("wildcard-include", trimmed);
+                }
+                if remove.is_some() {
+                    found("remove-item", trimmed);
+                }
+                if condition.is_some() {
+                    found("conditional-item", trimmed);
+                }
+            }
+            _ => {}
+        }
+    }
+    findings
+}
+
+/// Directory listings, so each directory is read once however many paths go through it
+#[derive(Default)]
+struct DiskNames {
+    listings: HashMap<PathBuf, Option<Vec<String>>>,
+}
+
+impl DiskNames {
+    /// `path` with every component spelled as on disk, found ignoring case, or `None` if it doesn't exist.
+    /// An exact match wins over one differing only in case.
+    fn actual(&mut self, path: &Path) -> Option<PathBuf> {
+        let mut actual = PathBuf::new();
+        for component in path.components() {
+            let Component::Normal(name) = component else {
+                actual.push(component);
+                continue;
+            };
+            let name = name.to_string_lossy();
+            let dir = if actual.as_os_str().is_empty() { PathBuf::from(".") } else { actual.clone() };
+            let listing = self.listings.entry(dir.clone()).or_insert_with(|| {
+                std::fs::read_dir(&dir)
+                    .ok()
+                    .map(|entries| entries.flatten().map(|entry| entry.file_name().to_string_lossy().into_owned()).collect())
+            });
+            let found = listing.as_ref()?.iter().find(|entry| **entry == name).or_else(|| {
+                listing.as_ref()?.iter().find(|entry| entry.eq_ignore_ascii_case(&name))
+            })?;
+            actual.push(found);
+        }
+        Some(actual)
+    }
+}
+
+/// Whether two paths differ only in the case of their components
+fn case_differs(written: &Path, actual: &Path) -> bool {
+    written.components().zip(actual.components()).any(|(w, a)| w != a)
+}
+
+/// Finds the paths of a project that only resolve on a case-insensitive file system: item paths
+/// and include directories cased differently from the disk, and `#include` directives in the
+/// project's sources and headers that name a header with other casing, searched the way the
+/// compiler does (the including file's directory for quoted includes, then every configuration's
+/// include directories). Paths that don't exist at all are left to `prune` and `doctor`.
+pub fn audit_case(vcxproj: &VcxprojFile) -> anyhow::Result<Vec<Finding>> {
+    let mut findings = Vec::new();
+    let mut disk = DiskNames::default();
+    let project_dir = vcxproj.path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let line_of = |text: &str| vcxproj.content.lines().position(|line| line.contains(text)).map_or(0, |index| index + 1);
+
+    let properties = Properties::for_project(vcxproj, None);
+    let mut sources = Vec::new();
+    for item in vcxproj.get_items()? {
+        if !matches!(item.item_type.as_str(), "ClCompile" | "ClInclude" | "None" | "Text" | "ResourceCompile" | "Image" | "Natvis" | "Manifest")
+            || item.include.contains(['*', ';'])
+        {
+            continue;
+        }
+        let Ok(written) = properties.resolve_path(project_dir, &item.include) else {
+            continue;
+        };
+        let Some(actual) = disk.actual(&written) else {
+            continue;
+        };
+        if case_differs(&written, &actual) {
+            findings.push(Finding {
+                kind: "item-case",
+                line: line_of(&format!("\"{}\"", item.include)),
+                detail: format!("{} is {} on disk", item.include, actual.display()),
+            });
+        }
+        if matches!(item.item_type.as_str(), "ClCompile" | "ClInclude") {
+            sources.push((item.include, actual));
+        }
+    }
+
+    // Include directories of every configuration, in order and spelled as on disk
+    let mut include_dirs: Vec<PathBuf> = Vec::new();
+    let mut reported: Vec<String> = Vec::new();
+    for setting in vcxproj.get_tool_settings() {
+        if setting.tool != "ClCompile" || setting.name != "AdditionalIncludeDirectories" {
+            continue;
+        }
+        let config = setting.condition.as_deref().map(|c| crate::vcxproj::condition_config(c).into_owned());
+        let properties = Properties::for_project(vcxproj, config.as_deref());
+        for entry in setting.value.split(';').map(str::trim).filter(|entry| !entry.is_empty() && !entry.starts_with("%(")) {
+            let Ok(written) = properties.resolve_path(project_dir, entry) else {
+                continue;
+            };
+            let Some(actual) = disk.actual(&written) else {
+                continue;
+            };
+            if case_differs(&written, &actual) && !reported.iter().any(|r| r == entry) {
+                reported.push(entry.to_string());
+                findings.push(Finding {
+                    kind: "include-dir-case",
+                    line: line_of(entry),
+                    detail: format!("{} is {} on disk", entry, actual.display()),
+                });
+            }
+            if !include_dirs.contains(&actual) {
+                include_dirs.push(actual);
+            }
+        }
+    }
+
+    let directive = Regex::new(r#"^\s*#\s*include\s*([<"])([^>"]+)[>"]"#).unwrap();
+    for (include, path) in &sources {
+        let Ok(bytes) = std::fs::read(path) else {
+            continue;
+        };
+        let text = String::from_utf8_lossy(&bytes);
+        let own_dir = path.parent().map(Path::to_path_buf).unwrap_or_default();
+        for (index, line) in text.lines().enumerate() {
+            let Some(captures) = directive.captures(line) else {
+                continue;
+            };
+            let header = captures[2].trim();
+            let quoted = &captures[1] == "\"";
+            let candidates = quoted.then_some(&own_dir).into_iter().chain(&include_dirs);
+            // The first directory holding the header, in any case, is the one the compiler on Windows uses
+            let Some((written, actual)) = candidates
+                .map(|dir| dir.join(header.replace('\\', "/")))
+                .find_map(|written| disk.actual(&written).map(|actual| (written, actual)))
+            else {
+                continue;
+            };
+            if case_differs(&written, &actual) {
+                findings.push(Finding {
+                    kind: "include-case",
+                    line: 0,
+                    detail: format!("{}:{}: #include {}{}{} is {} on disk", include, index + 1, &captures[1], header, if quoted { "\"" } else { ">" }, actual.display()),
+                });
+            }
+        }
+    }
+    Ok(findings)
+}
@@ -0,0 +1,396 @@
+use crate::vcxproj::Style;
+use regex::Regex;
+
+/// A project or filters file in canonical layout, with a summary of what changed
+#[derive(Debug, Default)]
+pub struct Formatted {
+    pub content: String,
+    pub changes: Vec<String>,
+}
+
+/// Attribute order for every element but ImportGroup, matching what Visual Studio writes.
+/// Attributes not listed keep their relative order after these.
+const ATTRIBUTE_ORDER: &[&str] = &["Include", "Update", "Remove", "Exclude", "Project", "Condition", "Label"];
+
+/// Visual Studio writes `<ImportGroup Label="PropertySheets" Condition="...">`, label first
+const IMPORT_GROUP_ATTRIBUTE_ORDER: &[&str] = &["Label", "Condition"];
+
+/// Elements whose text is a GUID, written in uppercase by Visual Studio
+const GUID_ELEMENTS: &[&str] = &["ProjectGuid", "UniqueIdentifier", "Project"];
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Tag(String),
+    Text(String),
+}
+
+/// Lays out an XML project or filters file canonically:
+/// - every element on its own line, indented to its depth with `style`
+/// - tags split over several lines joined into one
+/// - attributes in Visual Studio's order, separated by single spaces
+/// - self-closing tags spaced as `style` says
+/// - GUIDs in uppercase
+/// - no blank lines or trailing whitespace
+///
+/// Comments, CDATA sections and text spanning several lines are kept as written. The line endings
+/// and the final newline of `content` are kept.
+pub fn format(content: &str, style: &Style) -> Formatted {
+    let mut joined = 0;
+    let mut split = 0;
+    let mut reordered = 0;
+    let mut guids = 0;
+    let mut blank = 0;
+    let mut reindented = 0;
+
+    let lines = join_split_tags(content, &mut joined);
+    let guid = Regex::new(r"^\{?[0-9a-fA-F]{8}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{4}-[0-9a-fA-F]{12}\}?$").unwrap();
+    let mut output: Vec<String> = Vec::with_capacity(lines.len());
+    let mut depth = 0usize;
+    let mut verbatim_until: Option<&str> = None;
+    for (index, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+
+        // Inside a comment or CDATA section spanning lines
+        if let Some(end) = verbatim_until {
+            output.push(line.clone());
+            if trimmed.contains(end) {
+                verbatim_until = None;
+            }
+            continue;
+        }
+        if trimmed.is_empty() {
+            blank += 1;
+            continue;
+        }
+        if index == 0 && trimmed.contains("<?xml") {
+            output.push(line.trim_end().to_string());
+            continue;
+        }
+        for (start, end) in [("<!--", "-->"), ("<![CDATA[", "]]>")] {
+            if let Some(open) = trimmed.find(start) {
+                if !trimmed[open..].contains(end) {
+                    verbatim_until = Some(end);
+                }
+            }
+        }
+        if trimmed.contains("<!--") || trimmed.contains("<![CDATA[") {
+            let line = if trimmed.starts_with("<!--") && verbatim_until.is_none() {
+                format!("{}{}", style.indent.repeat(depth), trimmed)
+            } else {
+                line.clone()
+            };
+            output.push(line);
+            continue;
+        }
+        // The rest of a text value spanning lines, e.g. the last line of a multi-line <Command>
+        if !trimmed.starts_with('<') {
+            output.push(line.clone());
+            depth = depth.saturating_sub(trimmed.matches("</").count());
+            continue;
+        }
+
+        let Some(tokens) = tokenize(trimmed) else {
+            output.push(format!("{}{}", style.indent.repeat(depth), trimmed));
+            continue;
+        };
+        let units = units(tokens);
+        split += units.len() - 1;
+        let single = units.len() == 1;
+        for unit in units {
+            let mut text = String::new();
+            for token in &unit {
+                match token {
+                    Token::Tag(tag) => {
+                        let normalized = normalize_tag(tag, style);
+                        if normalized.as_deref().is_some_and(|n| n != tag && !n.starts_with("</")) {
+                            reordered += 1;
+                        }
+                        text.push_str(normalized.as_deref().unwrap_or(tag));
+                    }
+                    Token::Text(value) => {
+                        let element = match &unit[0] {
+                            Token::Tag(tag) => tag_name(tag),
+                            Token::Text(_) => "",
+                        };
+                        if GUID_ELEMENTS.contains(&element) && guid.is_match(value.trim()) && *value != value.to_uppercase() {
+                            guids += 1;
+                            text.push_str(&value.to_uppercase());
+                        } else {
+                            text.push_str(value);
+                        }
+                    }
+                }
+            }
+            if text.starts_with("</") {
+                depth = depth.saturating_sub(1);
+            }
+            let styled = format!("{}{}", style.indent.repeat(depth), text);
+            if single && text == trimmed && styled != line.trim_end() {
+                reindented += 1;
+            }
+            output.push(styled);
+            let opens = !text.starts_with("</") && !text.starts_with("<?") && !text.starts_with("<!") && !text.ends_with("/>") && !text.contains("</");
+            if opens {
+                depth += 1;
+            }
+        }
+    }
+
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut formatted = output.join(newline);
+    if content.ends_with('\n') {
+        formatted.push_str(newline);
+    }
+
+    let mut changes = Vec::new();
+    for (count, what) in [
+        (reindented, "lines re-indented"),
+        (joined, "tags joined onto one line"),
+        (split, "elements moved onto their own line"),
+        (reordered, "tags with attributes reordered or respaced"),
+        (guids, "GUIDs uppercased"),
+        (blank, "blank lines removed"),
+    ] {
+        if count > 0 {
+            changes.push(format!("{} {}", count, what));
+        }
+    }
+    if changes.is_empty() && formatted != content {
+        changes.push("trailing whitespace or tag spacing normalized".to_string());
+    }
+    Formatted { content: formatted, changes }
+}
+
+/// The lines of `content` with tags that continue over several lines joined with single spaces
+fn join_split_tags(content: &str, joined: &mut usize) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    let mut pending: Option<String> = None;
+    let mut in_verbatim = false;
+    for line in content.lines() {
+        if let Some(mut tag) = pending.take() {
+            tag.push(' ');
+            tag.push_str(line.trim());
+            if open_tag_continues(&tag) {
+                pending = Some(tag);
+            } else {
+                lines.push(tag);
+            }
+            continue;
+        }
+        if in_verbatim || line.contains("<!--") || line.contains("<![CDATA[") {
+            in_verbatim = !line.contains("-->") && !line.contains("]]>");
+        } else if open_tag_continues(line) {
+            *joined += 1;
+            pending = Some(line.trim_end().to_string());
+            continue;
+        }
+        lines.push(line.to_string());
+    }
+    lines.extend(pending);
+    lines
+}
+
+/// Whether `line` ends inside a tag, e.g. `<Import Project="a.props"` with its other attributes on the next line
+fn open_tag_continues(line: &str) -> bool {
+    let mut in_tag = false;
+    let mut quote: Option<char> = None;
+    for c in line.chars() {
+        match (quote, c) {
+            (Some(q), _) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') if in_tag => quote = Some(c),
+            (None, '<') => in_tag = true,
+            (None, '>') => in_tag = false,
+            _ => {}
+        }
+    }
+    in_tag
+}
+
+/// Splits a line into tags and the text between them, or `None` if a tag is not closed on the line
+fn tokenize(line: &str) -> Option<Vec<Token>> {
+    let mut tokens = Vec::new();
+    let mut rest = line;
+    while !rest.is_empty() {
+        if rest.starts_with('<') {
+            let mut quote: Option<char> = None;
+            let end = rest.char_indices().find(|&(_, c)| match (quote, c) {
+                (Some(q), _) if c == q => {
+                    quote = None;
+                    false
+                }
+                (Some(_), _) => false,
+                (None, '"' | '\'') => {
+                    quote = Some(c);
+                    false
+                }
+                (None, '>') => true,
+                _ => false,
+            })?;
+            tokens.push(Token::Tag(rest[..=end.0].to_string()));
+            rest = &rest[end.0 + 1..];
+        } else {
+            let end = rest.find('<').unwrap_or(rest.len());
+            tokens.push(Token::Text(rest[..end].to_string()));
+            rest = &rest[end..];
+        }
+    }
+    Some(tokens)
+}
+
+/// Groups the tokens of a line into what goes on one line each: an element with its text (and
+/// closing tag when on the same line), or a single tag. Whitespace between tags is dropped.
+fn units(tokens: Vec<Token>) -> Vec<Vec<Token>> {
+    let mut units: Vec<Vec<Token>> = Vec::new();
+    for token in tokens {
+        let joins = match (units.last().map(|u| u.as_slice()), &token) {
+            (None, _) => false,
+            (Some(unit), Token::Text(_)) => !matches!(unit.last(), Some(Token::Tag(tag)) if tag.starts_with("</") || tag.ends_with("/>")),
+            // A closing tag stays with its element's opening tag and text
+            (Some(unit), Token::Tag(tag)) if tag.starts_with("</") => match unit {
+                [Token::Tag(open), ..] => !open.starts_with("</") && !open.ends_with("/>") && tag_name(open) == tag_name(tag) && !unit[1..].iter().any(|t| matches!(t, Token::Tag(_))),
+                [Token::Text(_)] => true,
+                _ => false,
+            },
+            _ => false,
+        };
+        match (joins, &token) {
+            (true, _) => units.last_mut().unwrap().push(token),
+            (false, Token::Text(text)) if text.trim().is_empty() => {}
+            _ => units.push(vec![token]),
+        }
+    }
+    // Whitespace-only text at the end of a unit, e.g. before a tag that went to the next line
+    for unit in &mut units {
+        while unit.len() > 1 && matches!(unit.last(), Some(Token::Text(text)) if text.trim().is_empty()) {
+            unit.pop();
+        }
+    }
+    units
+}
+
+/// The element name of a tag: `ClCompile` for `<ClCompile Include="a.c">` or `</ClCompile>`
+fn tag_name(tag: &str) -> &str {
+    let inner = tag.trim_start_matches('<').trim_start_matches('/');
+    let end = inner.find(|c: char| c.is_whitespace() || c == '>' || c == '/').unwrap_or(inner.len());
+    &inner[..end]
+}
+
+/// The attributes of an opening or self-closing tag, or `None` when they cannot be parsed
+fn attributes(tag: &str) -> Option<Vec<(String, String)>> {
+    let attribute = Regex::new(r#"^\s*([^\s=/>]+)\s*=\s*("[^"]*"|'[^']*')"#).unwrap();
+    let inner = tag.trim_end_matches('>').trim_end_matches('/');
+    let mut rest = &inner[1 + tag_name(tag).len()..];
+    let mut attributes = Vec::new();
+    while let Some(captures) = attribute.captures(rest) {
+        attributes.push((captures[1].to_string(), captures[2].to_string()));
+        rest = &rest[captures[0].len()..];
+    }
+    rest.trim().is_empty().then_some(attributes)
+}
+
+/// Rewrites a tag with single spaces between attributes in canonical order and the style's
+/// self-closing spacing. Declarations, comments and tags that cannot be parsed are returned as `None`.
+fn normalize_tag(tag: &str, style: &Style) -> Option<String> {
+    if tag.starts_with("<?") || tag.starts_with("<!") {
+        return None;
+    }
+    let name = tag_name(tag);
+    if tag.starts_with("</") {
+        return Some(format!("</{}>", name));
+    }
+    let mut attributes = attributes(tag)?;
+    let order = if name == "ImportGroup" { IMPORT_GROUP_ATTRIBUTE_ORDER } else { ATTRIBUTE_ORDER };
+    attributes.sort_by_key(|(attribute, _)| order.iter().position(|o| o == attribute).unwrap_or(order.len()));
+
+    let mut normalized = format!("<{}", name);
+    for (attribute, value) in &attributes {
+        normalized.push_str(&format!(" {}={}", attribute, value));
+    }
+    match tag.ends_with("/>") {
+        true if style.self_closing_space => normalized.push_str(" />"),
+        true => normalized.push_str("/>"),
+        false => normalized.push('>'),
+    }
+    Some(normalized)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn style() -> Style {
+        Style { indent: "  ".to_string(), self_closing_space: true }
+    }
+
+    #[test]
+    fn formatting_is_idempotent() {
+        for content in [include_str!("../samples/test.vcxproj"), include_str!("../samples/test.vcxproj.filters")] {
+            let style = Style::detect(content);
+            let once = format(content, &style);
+            let twice = format(&once.content, &style);
+            assert_eq!(twice.content, once.content);
+            assert!(twice.changes.is_empty(), "{:?}", twice.changes);
+        }
+    }
+
+    #[test]
+    fn canonical_content_round_trips_unchanged() {
+        let content = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+            <Project DefaultTargets=\"Build\" xmlns=\"http://schemas.microsoft.com/developer/msbuild/2003\">\n  \
+            <ItemGroup Label=\"ProjectConfigurations\">\n    \
+            <ProjectConfiguration Include=\"Debug|x64\">\n      \
+            <Configuration>Debug</Configuration>\n    \
+            </ProjectConfiguration>\n  \
+            </ItemGroup>\n  \
+            <ImportGroup Label=\"PropertySheets\" Condition=\"'$(Configuration)'=='Debug'\">\n    \
+            <Import Project=\"a.props\" Condition=\"Exists('a.props')\" />\n  \
+            </ImportGroup>\n\
+            </Project>\n";
+        let formatted = format(content, &style());
+        assert_eq!(formatted.content, content);
+        assert!(formatted.changes.is_empty());
+    }
+
+    #[test]
+    fn lays_out_a_messy_file() {
+        let content = "<Project>\n\n\
+            <ItemGroup><ClCompile   Condition=\"'$(Platform)'=='x64'\" Include=\"a.c\"/>\n\
+            <ClInclude\n    Include=\"a.h\" />\n\
+            </ItemGroup>\n\
+            <PropertyGroup><ProjectGuid>{abcdef01-2345-6789-abcd-ef0123456789}</ProjectGuid></PropertyGroup>   \n\
+            </Project>";
+        let expected = "<Project>\n  \
+            <ItemGroup>\n    \
+            <ClCompile Include=\"a.c\" Condition=\"'$(Platform)'=='x64'\" />\n    \
+            <ClInclude Include=\"a.h\" />\n  \
+            </ItemGroup>\n  \
+            <PropertyGroup>\n    \
+            <ProjectGuid>{ABCDEF01-2345-6789-ABCD-EF0123456789}</ProjectGuid>\n  \
+            </PropertyGroup>\n\
+            </Project>";
+        let formatted = format(content, &style());
+        assert_eq!(formatted.content, expected);
+        let reformatted = format(&formatted.content, &style());
+        assert_eq!(reformatted.content, expected);
+        assert!(reformatted.changes.is_empty());
+    }
+
+    #[test]
+    fn keeps_line_endings_comments_and_multi_line_text() {
+        let content = "<Project>\r\n<!-- kept\r\n   as written -->\r\n<PostBuildEvent><Command>copy a b\r\ncopy c d</Command></PostBuildEvent>\r\n</Project>\r\n";
+        let formatted = format(content, &style());
+        assert_eq!(
+            formatted.content,
+            "<Project>\r\n<!-- kept\r\n   as written -->\r\n  <PostBuildEvent>\r\n    <Command>copy a b\r\ncopy c d</Command></PostBuildEvent>\r\n</Project>\r\n"
+        );
+        assert_eq!(format(&formatted.content, &style()).content, formatted.content);
+    }
+
+    #[test]
+    fn self_closing_spacing_follows_the_style() {
+        let tight = Style { indent: "\t".to_string(), self_closing_space: false };
+        let formatted = format("<ItemGroup>\n<None Include=\"a.txt\" />\n</ItemGroup>", &tight);
+        assert_eq!(formatted.content, "<ItemGroup>\n\t<None Include=\"a.txt\"/>\n</ItemGroup>");
+    }
+}
@@ -1,5 +1,7 @@
-use clap::{Parser, Subcommand};
-use std::path::PathBuf;
+use clap::parser::ValueSource;
+use clap::{ArgGroup, ArgMatches, CommandFactory, FromArgMatches, Parser, Subcommand};
+use std::path::{Path, PathBuf};
+use crate::vcxproj::ListPosition;
 
 #[derive(Parser)]
 #[command(name = "vsprojm")]
@@ -8,6 +10,87 @@ use std::path::PathBuf;
 pub struct Cli {
     #[command(subcommand)]
     pub command: Commands,
+    
+    /// Keep the modification time of project files when only per-item metadata changes (avoids full rebuilds on build servers)
+    #[arg(long, global = true)]
+    pub preserve_mtime: bool,    
+    /// Print nothing but errors (the exit status still reports the result)
+    #[arg(short, long, global = true, conflicts_with_all = ["verbose", "log_level"])]
+    pub quiet: bool,
+    
+    /// Print extra detail; repeat (-vv) for trace output
+    #[arg(short, long, global = true, action = clap::ArgAction::Count)]
+    pub verbose: u8,
+    
+    /// Output level (overrides -q and -v)
+    #[arg(long, global = true, value_name = "LEVEL", value_parser = ["off", "error", "warn", "info", "debug", "trace"])]
+    pub log_level: Option<String>,
+    
+    /// Print a JSON object describing what changed (files, filters, configurations, warnings) instead of text
+    #[arg(long, global = true)]
+    pub json: bool,
+    
+    /// Language of the console output (default: from VCPROJM_LANG, LC_ALL, LC_MESSAGES or LANG)
+    #[arg(long, global = true, value_name = "LANG", value_parser = ["en", "ja", "zh"])]
+    pub lang: Option<String>,
+    
+    /// Screen-reader friendly output: no emoji or box drawing, textual labels and lines of at most 80 characters
+    #[arg(long, global = true)]
+    pub plain: bool,
+    
+    /// Value of an MSBuild property such as SolutionDir, used to expand $(...) in paths (repeatable)
+    #[arg(long = "property", global = true, value_name = "NAME=VALUE", value_parser = crate::properties::parse_override)]
+    pub properties: Vec<(String, String)>,
+    
+    /// Before writing, wait until Visual Studio no longer has the solution or project open
+    #[arg(long, global = true)]
+    pub wait_for_vs_close: bool,
+    
+    /// After writing, make sure an open Visual Studio notices the change and explain how to reload
+    #[arg(long, global = true)]
+    pub notify_vs: bool,
+    
+    /// Read the project XML from standard input instead of the --project file (which still names it)
+    #[arg(long, global = true)]
+    pub stdin: bool,
+    
+    /// Write the resulting project XML to standard output instead of the --project file, leaving the disk untouched
+    #[arg(long, global = true, conflicts_with = "json")]
+    pub stdout: bool,
+    
+    /// In pipe mode, read the filters file from this path (e.g. /dev/fd/3) instead of next to the project
+    #[arg(long, global = true, value_name = "PATH")]
+    pub filters_in: Option<PathBuf>,
+    
+    /// With --stdout, write the resulting filters file here (e.g. /dev/fd/4); otherwise filters changes are dropped
+    #[arg(long, global = true, value_name = "PATH", requires = "stdout")]
+    pub filters_out: Option<PathBuf>,
+    
+    /// Save even if the project or filters file was modified by another process since it was loaded;
+    /// for undo/redo/backups restore, restore even if the files were modified after the operation
+    #[arg(long, global = true)]
+    pub force: bool,    
+    /// Read the project and filters files from this git revision (e.g. HEAD~1, a tag, or ":" for the staged version) instead of the working tree
+    #[arg(long, global = true, value_name = "REF", conflicts_with_all = ["stdin", "stdout"])]
+    pub git_ref: Option<String>,
+    
+    /// Give new filters random UniqueIdentifiers instead of ones derived from the filter path
+    #[arg(long, global = true)]
+    pub random_filter_guids: bool,
+    
+    /// With several projects (a repeated or glob --project), stop at the first that fails; with a
+    /// solution, change no project when one fails (the default)
+    #[arg(long, global = true, conflicts_with = "keep_going")]
+    pub fail_fast: bool,
+    
+    /// With several projects (a solution, or a repeated or glob --project), go on with the rest when one fails
+    #[arg(long, global = true)]
+    pub keep_going: bool,
+    
+    /// How many projects to process at once when a command runs on several (a solution, or a
+    /// repeated or glob --project); 1 processes them one after another. Defaults to the number of CPUs
+    #[arg(short = 'j', long, global = true, value_name = "N", value_parser = clap::value_parser!(u16).range(1..))]
+    pub jobs: Option<u16>,
 }
 
 #[derive(Subcommand)]
@@ -16,8 +99,12 @@ pub enum Commands {
     #[command(name = "add", visible_alias = "a")]
     Add {
         /// File extension to add (e.g., "c", "cpp")
-        #[arg(short, long)]
-        extension: String,
+        #[arg(short, long, required_unless_present = "from_file")]
+        extension: Option<String>,
+        
+        /// Add exactly the files listed in this text file instead of scanning: one path or glob per line, relative to the list's directory, "#" starts a comment
+        #[arg(long, value_name = "LIST", conflicts_with_all = ["extension", "directory", "regex", "not"])]
+        from_file: Option<PathBuf>,
         
         /// Path to the .vcxproj file
         #[arg(short, long)]
@@ -39,6 +126,22 @@ pub enum Commands {
         #[arg(short = 'n', long)]
         not: bool,
         
+        /// Skip directories by name (e.g., "third_party") or relative path during the scan (repeatable)
+        #[arg(long = "exclude-dir", value_name = "DIR")]
+        exclude_dirs: Vec<String>,
+        
+        /// Don't skip the default directories (.git, .vs, x64, Debug, Release)
+        #[arg(long)]
+        no_default_excludes: bool,
+        
+        /// Skip files ignored by .gitignore/.ignore files and the global git excludes
+        #[arg(long)]
+        gitignore: bool,
+        
+        /// Put the files matching GLOB into FILTER instead of the filter named after their directory, e.g. "tests/**=Test Files" (repeatable, tried before the [filters] map of .vcprojm/config.toml)
+        #[arg(long = "map", value_name = "GLOB=FILTER")]
+        maps: Vec<String>,
+        
         /// Show what would be done without actually modifying files
         #[arg(long)]
         dryrun: bool,
@@ -63,6 +166,10 @@ pub enum Commands {
         #[arg(short = 'y', long)]
         yes: bool,
         
+        /// Pick which matched files to remove (e.g., "1,3,5-9") before applying
+        #[arg(short, long, conflicts_with = "yes")]
+        interactive: bool,
+        
         /// Filter paths using regex pattern to limit deletion scope
         #[arg(short = 'x', long)]
         regex: Option<String>,
@@ -76,6 +183,58 @@ pub enum Commands {
         dryrun: bool,
     },
     
+    /// Remove items whose files no longer exist on disk, and filters file entries without a project item
+    #[command(name = "prune")]
+    Prune {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be removed without modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Make the .vcxproj.filters file agree with the project: add missing sources and headers, drop stale entries, add filter GUIDs
+    #[command(name = "fix-filters")]
+    FixFilters {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be changed without modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Keep the project in sync with the disk: add new files and remove deleted ones as they change, until Ctrl+C
+    #[command(name = "watch")]
+    Watch {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Comma-separated extensions of the files to keep in sync
+        #[arg(short, long, value_name = "EXTS", default_value = "c,cc,cpp,cxx,h,hh,hpp,hxx,inl")]
+        ext: String,
+        
+        /// Skip directories by name (e.g., "third_party") or relative path (repeatable)
+        #[arg(long = "exclude-dir", value_name = "DIR")]
+        exclude_dirs: Vec<String>,
+        
+        /// Skip files ignored by .gitignore/.ignore files and the global git excludes
+        #[arg(long)]
+        gitignore: bool,
+        
+        /// Put the files matching GLOB into FILTER instead of the filter named after their directory, e.g. "tests/**=Test Files" (repeatable, tried before the [filters] map of .vcprojm/config.toml)
+        #[arg(long = "map", value_name = "GLOB=FILTER")]
+        maps: Vec<String>,
+        
+        /// Milliseconds without further changes before a burst of them (a checkout, a refactoring) is applied
+        #[arg(long, value_name = "MS", default_value_t = 500)]
+        debounce: u64,
+    },
+    
     /// View project structure as it appears in Visual Studio
     #[command(name = "view", visible_alias = "v")]
     View {
@@ -90,6 +249,26 @@ pub enum Commands {
         /// Maximum hierarchy levels to display (0=folders only, default=all levels)
         #[arg(short, long)]
         level: Option<usize>,
+        
+        /// Annotate files with notable per-file settings (excluded from build, PCH creation, extra options)
+        #[arg(long)]
+        show_props: bool,
+        
+        /// Order the filters and files under each filter: "name" mixes them by name, "ext" lists files by extension, "filter" lists filters before files
+        #[arg(long, value_parser = ["name", "ext", "filter"])]
+        sort: Option<String>,
+        
+        /// Show only the files with these comma-separated extensions (e.g. "cpp,h"), and the filters leading to them
+        #[arg(long, value_name = "EXTS")]
+        only_ext: Option<String>,
+        
+        /// Show only the files whose project-relative path matches this regex, and the filters leading to them
+        #[arg(long = "match", value_name = "REGEX")]
+        pattern: Option<String>,
+        
+        /// Mark the files that don't exist on disk, and count them in the summary
+        #[arg(long)]
+        check_disk: bool,
     },
     
     /// Rename folders/filters in the project
@@ -116,7 +295,7 @@ pub enum Commands {
         dryrun: bool,
     },
     
-    /// Add include directory to all configurations
+    /// Add include directory to all configurations (NMakeIncludeSearchPath in Makefile projects)
     #[command(name = "add-incdir", visible_alias = "incdir")]
     AddInclude {
         /// Path to the .vcxproj file
@@ -125,7 +304,10 @@ pub enum Commands {
         
         /// Include directory path
         #[arg(short = 'x', long)]
-        path: String,
+        path: String,        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
     },
     
     /// Add library directory to all configurations
@@ -137,7 +319,10 @@ pub enum Commands {
         
         /// Library directory path
         #[arg(short = 'x', long)]
-        path: String,
+        path: String,        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
     },
     
     /// Add library file to all configurations
@@ -147,8 +332,2128 @@ pub enum Commands {
         #[arg(short, long)]
         project: PathBuf,
         
-        /// Library file name (e.g., "opengl32.lib")
+        /// Library file name (e.g., "opengl32.lib"); "{d}" becomes "d" in Debug configurations and "" otherwise
+        #[arg(short, long, required_unless_present = "debug_name", conflicts_with_all = ["debug_name", "release_name"])]
+        name: Option<String>,
+        
+        /// Library name for Debug configurations (use with --release-name)
+        #[arg(long, requires = "release_name")]
+        debug_name: Option<String>,
+        
+        /// Library name for non-Debug configurations (use with --debug-name)
+        #[arg(long, requires = "debug_name")]
+        release_name: Option<String>,
+        
+        /// Only add to this configuration: "Debug" (every platform) or "Debug|x64"
         #[arg(short, long)]
-        name: String,
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Link order position: front, back, after:<lib> or before:<lib>
+        #[arg(long, default_value = "back")]
+        position: ListPosition,        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Add a preprocessor definition (NMakePreprocessorDefinitions in Makefile projects)
+    #[command(name = "add-define", visible_alias = "define")]
+    AddDefine {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// The definition, e.g. "USE_SSE2" or "VERSION=3"
+        #[arg(short, long)]
+        define: String,
+        
+        /// Only add to this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Add or remove headers force-included into every source file (ForcedIncludeFiles, /FI)
+    #[command(name = "forced-include")]
+    ForcedInclude {
+        #[command(subcommand)]
+        action: ForcedAction,
+    },
+    
+    /// Add or remove assemblies force-referenced by C++/CLI sources (ForcedUsingFiles, /FU)
+    #[command(name = "forced-using")]
+    ForcedUsing {
+        #[command(subcommand)]
+        action: ForcedAction,
+    },
+    
+    /// Find files and filters whose path matches a regex pattern
+    #[command(name = "search", visible_alias = "s")]
+    Search {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+        
+        /// Search every project referenced by a .sln file
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// Regex pattern matched against item paths and filter names (e.g., 'render.*\.cpp')
+        #[arg(short = 'x', long)]
+        pattern: String,
+    },
+    
+    /// Find every project of a solution that contains a file, however its path is written
+    #[command(name = "which")]
+    Which {
+        /// Path to the .sln file
+        #[arg(short, long)]
+        solution: PathBuf,
+        
+        /// The file to look for, relative to the current directory
+        #[arg(short, long)]
+        file: PathBuf,
+    },
+    
+    /// Set per-file compiler settings on all source files matching a glob
+    #[command(name = "set-file-prop", visible_alias = "fprop")]
+    SetFileProp {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Glob pattern matched against item paths (e.g., 'third_party/**/*.cpp')
+        #[arg(short, long)]
+        glob: String,
+        
+        /// Only apply to this configuration (e.g., "Debug|x64"); defaults to all configurations
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Warning level 0-4 (0 turns off all warnings)
+        #[arg(short, long, value_parser = clap::value_parser!(u8).range(0..=4))]
+        warning_level: Option<u8>,
+        
+        /// Optimization setting (e.g., Disabled, MinSpace, MaxSpeed, Full)
+        #[arg(short, long)]
+        optimization: Option<String>,
+        
+        /// Disable code analysis (/analyze) for matching files
+        #[arg(long)]
+        no_analyze: bool,
+        
+        /// Arbitrary metadata as Name=Value (repeatable)
+        #[arg(long = "prop", value_name = "NAME=VALUE")]
+        props: Vec<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
     },
-}
\ No newline at end of file
+    
+    /// Exclude the ClCompile items matching a regex from the build of some configurations (ExcludedFromBuild)
+    #[command(name = "exclude")]
+    Exclude {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Regex matched against item paths (with forward slashes), e.g. "win32_.*\.cpp"
+        #[arg(short, long)]
+        regex: String,
+        
+        /// Only in this configuration: "Debug" (every platform) or "Debug|ARM64"; defaults to all configurations
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "ARM64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Build the items matching a regex again in some configurations, undoing `exclude`
+    #[command(name = "include")]
+    Include {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Regex matched against item paths (with forward slashes), e.g. "win32_.*\.cpp"
+        #[arg(short, long)]
+        regex: String,
+        
+        /// Only in this configuration: "Debug" (every platform) or "Debug|ARM64"; defaults to all configurations
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "ARM64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// List the project's files one per line, for scripts and grep
+    #[command(name = "list", visible_alias = "ls")]
+    List {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Only items of this type, e.g. ClCompile (repeatable, case-insensitive)
+        #[arg(short = 't', long = "item-type")]
+        item_types: Vec<String>,
+        
+        /// Only files in this filter or its subfilters, e.g. "Source Files\Core"
+        #[arg(short, long)]
+        filter: Option<String>,
+        
+        /// Output format: plain (tab-separated), csv or json (also selected by --json)
+        #[arg(long, default_value = "plain", value_parser = ["plain", "csv", "json"])]
+        format: String,
+        
+        /// Add a column with this metadata of each item, e.g. ExcludedFromBuild (repeatable)
+        #[arg(short, long)]
+        metadata: Vec<String>,
+    },
+    
+    /// Show project metrics (item types, filters, extensions, missing files, duplicates)
+    #[command(name = "stats")]
+    Stats {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+    },
+    
+    /// Remove per-file metadata from all items matching a glob
+    #[command(name = "clear-file-props", visible_alias = "fclear")]
+    ClearFileProps {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Glob pattern matched against item paths (e.g., '**/*.cpp')
+        #[arg(short, long)]
+        glob: String,
+        
+        /// Metadata name to remove (repeatable, e.g., PrecompiledHeader)
+        #[arg(long = "prop", value_name = "NAME", required = true)]
+        props: Vec<String>,
+        
+        /// Only remove metadata conditioned on this configuration (e.g., "Debug|x64")
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Undo the last operation recorded in the project's .vcprojm/history journal
+    #[command(name = "undo")]
+    Undo {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be restored without modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Redo the last undone operation
+    #[command(name = "redo")]
+    Redo {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be restored without modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// List, prune or restore the operations recorded in the project's history
+    #[command(name = "backups")]
+    Backups {
+        #[command(subcommand)]
+        action: BackupsAction,
+    },
+    
+    /// Find per-file settings that duplicate the configuration-level value
+    #[command(name = "lint", group(ArgGroup::new("writes").arg("fix")))]
+    Lint {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Remove the redundant per-file settings
+        #[arg(long)]
+        fix: bool,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Check a project for common mistakes: runtimes that don't match the configuration, paths of the wrong platform, lists that drop inherited values, absolute paths
+    #[command(name = "doctor", group(ArgGroup::new("writes").arg("fix")))]
+    Doctor {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Apply the suggested corrections
+        #[arg(long)]
+        fix: bool,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Rewrite absolute item paths, include directories and library directories relative to the project (or a base macro)
+    #[command(name = "normalize-paths")]
+    NormalizePaths {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Write the paths as "$(ProjectDir)..." or "$(SolutionDir)..." (the nearest .sln above the project) instead of plain relative paths
+        #[arg(long, value_name = "MACRO", value_parser = ["$(ProjectDir)", "$(SolutionDir)"])]
+        base: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Expand wildcard Includes into explicit per-file items (or collapse them back)
+    #[command(name = "enumerate")]
+    Enumerate {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Collapse directories whose files are all listed explicitly into wildcard Includes
+        #[arg(long)]
+        to_wildcards: bool,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Print the project's GUID (for scripts), or list, regenerate and deduplicate project GUIDs
+    #[command(name = "guid", args_conflicts_with_subcommands = true, subcommand_negates_reqs = true)]
+    Guid {
+        #[command(subcommand)]
+        action: Option<GuidAction>,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long, required = true)]
+        project: Option<PathBuf>,
+        
+        /// Also print the project name and path, tab-separated
+        #[arg(short, long)]
+        long: bool,
+    },
+    
+    /// Find the .vcxproj files under a directory with a given GUID or project name
+    #[command(name = "lookup")]
+    Lookup {
+        /// Project GUID to look for, with or without braces (case-insensitive)
+        #[arg(short, long, required_unless_present = "name", conflicts_with = "name")]
+        guid: Option<String>,
+        
+        /// Project name to look for (ProjectName property or file stem, case-insensitive)
+        #[arg(short, long)]
+        name: Option<String>,
+        
+        /// Directory to search recursively
+        #[arg(short, long, default_value = ".")]
+        root: PathBuf,
+    },
+    
+    /// Apply or revert a TOML patch of optional local modifications (files, include/lib dirs, libs, settings)
+    #[command(name = "patch")]
+    Patch {
+        #[command(subcommand)]
+        action: PatchAction,
+    },
+    
+    /// Export the filter layout as a reusable template, or apply one to a project
+    #[command(name = "filters")]
+    Filters {
+        #[command(subcommand)]
+        action: FiltersAction,
+    },
+    
+    /// Run a TOML script of commands as one transaction: either every step succeeds or nothing is written
+    #[command(name = "run")]
+    Run {
+        /// Path to the script (.toml)
+        script: PathBuf,
+        
+        /// Run every step with --dryrun, so each one shows what it would change
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Create a new project from a template
+    #[command(name = "new")]
+    New {
+        /// Path of the .vcxproj file to create
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Project template: "console-app" is Visual Studio's Console App, "static-lib" its Static Library, "utility" creates a build-script container with no compiled sources
+        #[arg(short, long, default_value = "utility", value_parser = ["utility", "console-app", "static-lib"])]
+        template: String,
+        
+        /// Project name (defaults to the file name)
+        #[arg(short, long)]
+        name: Option<String>,
+        
+        /// Show what would be created without writing anything
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Create a new project from a template and fill it with the sources of an existing directory tree
+    #[command(name = "generate")]
+    Generate {
+        /// Project name
+        #[arg(short, long)]
+        name: String,
+        
+        /// Project template, as for `new`: "console-app" (or "app"), "static-lib" (or "staticlib") or "utility"
+        #[arg(short, long = "type", default_value = "static-lib", value_parser = ["console-app", "static-lib", "utility", "app", "staticlib"], hide_possible_values = true)]
+        template: String,
+        
+        /// Directory whose files become the project's items, with its subdirectories as filters
+        #[arg(long)]
+        from: PathBuf,
+        
+        /// Comma-separated extensions of the files to include
+        #[arg(long, value_name = "EXTS", default_value = "c,cc,cpp,cxx,h,hh,hpp,hxx,inl")]
+        include_ext: String,
+        
+        /// Path of the .vcxproj file to create (defaults to NAME.vcxproj in the current directory)
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+        
+        /// Skip directories by name (e.g., "third_party") or relative path during the scan (repeatable)
+        #[arg(long = "exclude-dir", value_name = "DIR")]
+        exclude_dirs: Vec<String>,
+        
+        /// Skip files ignored by .gitignore/.ignore files and the global git excludes
+        #[arg(long)]
+        gitignore: bool,
+        
+        /// Show what would be created without writing anything
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Summarize how a project deviates from a freshly generated template: changed or extra settings, custom imports
+    #[command(name = "explain")]
+    Explain {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Template to compare against (defaults to the one matching the project's ConfigurationType)
+        #[arg(short, long, value_parser = ["utility", "console-app", "static-lib"])]
+        template: Option<String>,
+    },
+    
+    /// Change the platform toolset, Windows SDK version and ToolsVersion of a project or of every project in a solution
+    #[command(name = "upgrade-toolset")]
+    UpgradeToolset {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+        
+        /// Upgrade every project referenced by a .sln file
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// PlatformToolset to use (e.g. "v143")
+        #[arg(long)]
+        toolset: Option<String>,
+        
+        /// WindowsTargetPlatformVersion to use (e.g. "10.0")
+        #[arg(long)]
+        sdk: Option<String>,
+        
+        /// ToolsVersion of the <Project> element (e.g. "17.0")
+        #[arg(long)]
+        tools_version: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Set the Windows SDK version (WindowsTargetPlatformVersion) of a project or of every project in a solution
+    #[command(name = "set-sdk")]
+    SetSdk {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+        
+        /// Change every project referenced by a .sln file
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// SDK version, e.g. "10.0.22621.0", or "10.0" for the newest one where the project is built
+        #[arg(long, required_unless_present = "latest", conflicts_with = "latest")]
+        value: Option<String>,
+        
+        /// Use the newest Windows SDK installed on this machine
+        #[arg(long)]
+        latest: bool,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Report which projects use constructs this tool cannot parse or would not edit safely, before batch edits
+    #[command(name = "audit", args_conflicts_with_subcommands = true)]
+    Audit {
+        #[command(subcommand)]
+        check: Option<AuditAction>,
+        
+        /// Directory to search recursively for .vcxproj files (defaults to the current directory)
+        #[arg(short, long)]
+        directory: Option<PathBuf>,
+        
+        /// Audit the projects referenced by a .sln file
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// Audit a single project
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+    },
+    
+    /// Export a project or solution to another format (see --list)
+    #[command(name = "export", group(ArgGroup::new("writes").args(["format_name", "format"])))]
+    Export {
+        /// Format to export to, also accepted as `--format`
+        #[arg(value_name = "FORMAT", required_unless_present_any = ["list", "format"])]
+        format_name: Option<String>,
+        
+        /// Format to export to
+        #[arg(short, long, conflicts_with = "format_name")]
+        format: Option<String>,
+        
+        /// List the available formats and what they support
+        #[arg(long)]
+        list: bool,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+        
+        /// Export every project referenced by a .sln file
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// Directory to write to (defaults to the project's or solution's directory)
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+        
+        /// Configuration to export, for formats that export one (e.g. "Debug|x64")
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Also write build tasks that run MSBuild, for formats that support it (e.g. vscode's tasks.json)
+        #[arg(long)]
+        tasks: bool,
+        
+        /// Show what would be written without writing anything
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Set the C runtime library (/MT, /MD and their debug variants) of the project's configurations
+    #[command(name = "set-runtime")]
+    SetRuntime {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Runtime library: MultiThreaded, MultiThreadedDebug, MultiThreadedDLL or MultiThreadedDebugDLL (or MT, MTd, MD, MDd)
+        #[arg(long)]
+        value: String,
+        
+        /// Only set it in this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Set the optimization and debug information settings of configurations together from a preset
+    #[command(name = "set-optimization")]
+    SetOptimization {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// release-lto (speed with link-time code generation), debug (no optimization) or size (smallest code)
+        #[arg(long, value_parser = ["release-lto", "debug", "size"])]
+        preset: String,
+        
+        /// Only this configuration: "Release" (every platform) or "Release|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Turn MSVC code analysis (/analyze) on or off and choose its rule set, in a project or every project of a solution
+    #[command(name = "code-analysis")]
+    CodeAnalysis {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+        
+        /// Change every project referenced by a .sln file
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// Compile with /analyze (ClCompile EnablePREfast)
+        #[arg(long, value_parser = ["on", "off"])]
+        prefast: Option<String>,
+        
+        /// Run the code analysis of the project on every build (RunCodeAnalysis)
+        #[arg(long, value_parser = ["on", "off"])]
+        run_on_build: Option<String>,
+        
+        /// Rule set to analyze with (CodeAnalysisRuleSet): a .ruleset file, written relative to each project,
+        /// or the name of one shipped with Visual Studio, e.g. "NativeRecommendedRules.ruleset"
+        #[arg(long)]
+        ruleset: Option<String>,
+        
+        /// Only this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Roll out exploit mitigations (Spectre mitigation, Control Flow Guard) to a project or every project of a solution
+    #[command(name = "hardening")]
+    Hardening {
+        #[command(subcommand)]
+        action: HardeningAction,
+    },
+    
+    /// Turn AddressSanitizer (and the libFuzzer runtime) on or off, adjusting the settings it is incompatible with
+    #[command(name = "asan")]
+    Asan {
+        #[command(subcommand)]
+        action: AsanAction,
+    },
+    
+    /// Work with the project's configurations
+    #[command(name = "config")]
+    Config {
+        #[command(subcommand)]
+        action: ConfigAction,
+    },
+    
+    /// Switch the project between an application, a DLL and a static library
+    #[command(name = "set-type")]
+    SetType {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// ConfigurationType: Application, DynamicLibrary, StaticLibrary or Utility (or exe, dll, lib)
+        #[arg(long)]
+        value: String,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Set the character set (CharacterSet) of a project or of every project in a solution
+    #[command(name = "set-charset")]
+    SetCharset {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+        
+        /// Change every project referenced by a .sln file
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// Unicode, MultiByte or NotSet (or mbcs, none)
+        #[arg(long)]
+        value: String,
+        
+        /// Only this configuration: "Debug" (every platform), "Debug|x64" or "all"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Turn standards conformance mode (/permissive-) on or off in a project or every project of a solution
+    #[command(name = "set-conformance")]
+    SetConformance {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+        
+        /// Change every project referenced by a .sln file
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// ConformanceMode: true or false
+        #[arg(long, value_parser = ["true", "false"])]
+        value: String,
+        
+        /// Only this configuration: "Debug" (every platform), "Debug|x64" or "all"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Make builds faster: parallel compilation, incremental linking and /DEBUG:FASTLINK, per configuration
+    #[command(name = "set-build-perf")]
+    SetBuildPerf {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+        
+        /// Change every project referenced by a .sln file
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// Compile source files in parallel (MultiProcessorCompilation, /MP)
+        #[arg(long, value_parser = ["on", "off"])]
+        mp: Option<String>,
+        
+        /// Link incrementally (LinkIncremental)
+        #[arg(long, value_parser = ["on", "off"])]
+        incremental_link: Option<String>,
+        
+        /// Link with /DEBUG:FASTLINK, leaving debug information in the object files (GenerateDebugInformation)
+        #[arg(long, value_parser = ["on", "off"])]
+        debug_fastlink: Option<String>,
+        
+        /// Only this configuration: "Debug" (every platform), "Debug|x64" or "all"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Set the build, rebuild and clean command lines and the output of a Makefile project
+    #[command(name = "set-nmake")]
+    SetNmake {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Build command line (NMakeBuildCommandLine), e.g. "make -C $(ProjectDir)"
+        #[arg(long)]
+        build: Option<String>,
+        
+        /// Rebuild command line (NMakeReBuildCommandLine)
+        #[arg(long)]
+        rebuild: Option<String>,
+        
+        /// Clean command line (NMakeCleanCommandLine)
+        #[arg(long)]
+        clean: Option<String>,
+        
+        /// File the build produces, for debugging (NMakeOutput)
+        #[arg(long)]
+        output: Option<String>,
+        
+        /// Only set for this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Create or edit custom build steps (CustomBuild items) for files such as .proto or .fx run through a generator
+    #[command(name = "custom-build", visible_alias = "cb")]
+    CustomBuild {
+        #[command(subcommand)]
+        action: CustomBuildAction,
+    },
+    
+    /// Edit the per-file settings of HLSL shaders (FXCompile items)
+    #[command(name = "shader")]
+    Shader {
+        #[command(subcommand)]
+        action: ShaderAction,
+    },
+    
+    /// Build .cu files with the CUDA toolkit's build customization
+    #[command(name = "cuda")]
+    Cuda {
+        #[command(subcommand)]
+        action: CudaAction,
+    },
+    
+    /// Set up a Qt VS Tools project (QtMsBuild imports and the Qt modules it links)
+    #[command(name = "qt")]
+    Qt {
+        #[command(subcommand)]
+        action: QtAction,
+    },
+    
+    /// Add Windows resources: a resource script with the files it uses, or an application manifest
+    #[command(name = "resource")]
+    Resource {
+        #[command(subcommand)]
+        action: ResourceAction,
+    },
+    
+    /// Rewrite the project and filters files in a canonical layout without changing what they build
+    #[command(name = "format", visible_alias = "fmt")]
+    Format {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Instead of the canonical layout, merge fragmented ItemGroups of the same item type and remove empty ones
+        #[arg(long)]
+        merge_itemgroups: bool,
+        
+        /// Only check: list the files that are not formatted and exit with status 2 if any (for CI)
+        #[arg(long, conflicts_with = "dryrun")]
+        check: bool,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Sort the entries of each ItemGroup by path in the project and filters files, to keep merges simple
+    #[command(name = "sort")]
+    Sort {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Only check: list the files that are not sorted and exit with status 2 if any (for CI)
+        #[arg(long, conflicts_with = "dryrun")]
+        check: bool,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Move the files under a filter into a new static library project that the project then references
+    #[command(name = "split")]
+    Split {
+        /// Path to the .vcxproj file to split
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Filter whose files (including those of its subfilters) move, e.g. "Engine\Core"
+        #[arg(short, long)]
+        filter: String,
+        
+        /// Path of the static library .vcxproj to create
+        #[arg(short, long)]
+        to: PathBuf,
+        
+        /// Name of the library project (defaults to the file name)
+        #[arg(short, long)]
+        name: Option<String>,
+        
+        /// Show what would be done without writing anything
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Import a shared items project (.vcxitems) so its files build as part of the project
+    #[command(name = "import-shared")]
+    ImportShared {
+        /// Path to the consuming .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Path to the .vcxitems file
+        #[arg(short, long)]
+        items: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Fix structural damage from hand edits or merges: unclosed or stray tags, bare '&', truncated files
+    #[command(name = "repair")]
+    Repair {
+        /// Path to the .vcxproj file (its .filters file is repaired too)
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be fixed without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Enable vcpkg for a project, or wire ports installed in a classic-mode vcpkg into it
+    #[command(name = "vcpkg")]
+    Vcpkg {
+        #[command(subcommand)]
+        action: VcpkgAction,
+    },
+    
+    /// List, add or remove NuGet packages (PackageReference items, or packages.config with build imports)
+    #[command(name = "nuget")]
+    Nuget {
+        #[command(subcommand)]
+        action: NugetAction,
+    },
+    
+    /// List, add or remove property sheet (.props) imports, or extract settings into a new sheet
+    #[command(name = "props")]
+    Props {
+        #[command(subcommand)]
+        action: PropsAction,
+    },
+    
+    /// List the project's <Import> elements in evaluation order, or add or remove one at the right position
+    #[command(name = "imports")]
+    Imports {
+        #[command(subcommand)]
+        action: ImportsAction,
+    },
+    
+    /// Edit the debugger settings of the project's .vcxproj.user file (arguments, working directory, environment)
+    #[command(name = "user")]
+    User {
+        #[command(subcommand)]
+        action: UserAction,
+    },
+    
+    /// Restructure settings across several projects
+    #[command(name = "refactor")]
+    Refactor {
+        #[command(subcommand)]
+        action: RefactorAction,
+    },
+    
+    /// Add projects to a solution or remove them, and inspect or set build dependencies
+    #[command(name = "sln")]
+    Sln {
+        #[command(subcommand)]
+        action: SlnAction,
+    },
+}
+
+impl Commands {
+    /// The project given on the command line (the solution for commands that only take one),
+    /// whose history records what the command changed
+    pub fn project(&self) -> Option<&Path> {
+        match self {
+            Commands::Add { project, .. }
+            | Commands::Delete { project, .. }
+            | Commands::Prune { project, .. }
+            | Commands::FixFilters { project, .. }
+            | Commands::Watch { project, .. }
+            | Commands::View { project, .. }
+            | Commands::Rename { project, .. }
+            | Commands::AddInclude { project, .. }
+            | Commands::AddLibDir { project, .. }
+            | Commands::AddLib { project, .. }
+            | Commands::AddDefine { project, .. }
+            | Commands::SetFileProp { project, .. }
+            | Commands::Exclude { project, .. }
+            | Commands::Include { project, .. }
+            | Commands::List { project, .. }
+            | Commands::Stats { project, .. }
+            | Commands::ClearFileProps { project, .. }
+            | Commands::Undo { project, .. }
+            | Commands::Redo { project, .. }
+            | Commands::Lint { project, .. }
+            | Commands::Doctor { project, .. }
+            | Commands::NormalizePaths { project, .. }
+            | Commands::Enumerate { project, .. }
+            | Commands::New { project, .. }
+            | Commands::Explain { project, .. }
+            | Commands::SetRuntime { project, .. }
+            | Commands::SetOptimization { project, .. }
+            | Commands::SetType { project, .. }
+            | Commands::SetNmake { project, .. }
+            | Commands::Format { project, .. }
+            | Commands::Sort { project, .. }
+            | Commands::Split { project, .. }
+            | Commands::ImportShared { project, .. }
+            | Commands::Repair { project, .. }
+            | Commands::ForcedInclude { action: ForcedAction::Add { project, .. } | ForcedAction::Remove { project, .. } }
+            | Commands::ForcedUsing { action: ForcedAction::Add { project, .. } | ForcedAction::Remove { project, .. } }
+            | Commands::Patch { action: PatchAction::Apply { project, .. } | PatchAction::Revert { project, .. } }
+            | Commands::Filters { action: FiltersAction::Export { project, .. } | FiltersAction::Apply { project, .. } | FiltersAction::Flatten { project, .. } | FiltersAction::PruneEmpty { project, .. } | FiltersAction::Create { project, .. } | FiltersAction::Reroot { project, .. } }
+            | Commands::Backups { action: BackupsAction::List { project, .. } | BackupsAction::Prune { project, .. } | BackupsAction::Restore { project, .. } }
+            | Commands::Vcpkg { action: VcpkgAction::Enable { project, .. } | VcpkgAction::Add { project, .. } }
+            | Commands::Nuget { action: NugetAction::List { project, .. } | NugetAction::Add { project, .. } | NugetAction::Remove { project, .. } }
+            | Commands::Props { action: PropsAction::List { project, .. } | PropsAction::Add { project, .. } | PropsAction::Remove { project, .. } | PropsAction::Create { project, .. } }
+            | Commands::Sln { action: SlnAction::AddProject { project, .. } | SlnAction::Deps { project, .. } }
+            | Commands::CustomBuild { action: CustomBuildAction::Add { project, .. } | CustomBuildAction::Set { project, .. } }
+            | Commands::Shader { action: ShaderAction::Set { project, .. } }
+            | Commands::Cuda { action: CudaAction::Enable { project, .. } }
+            | Commands::Qt { action: QtAction::Enable { project, .. } }
+            | Commands::Resource { action: ResourceAction::Add { project, .. } }
+            | Commands::Config { action: ConfigAction::CopySettings { project, .. } }
+            | Commands::Asan { action: AsanAction::Enable { project, .. } | AsanAction::Disable { project, .. } }
+            | Commands::Imports { action: ImportsAction::List { project, .. } | ImportsAction::Add { project, .. } | ImportsAction::Remove { project, .. } }
+            | Commands::User { action: UserAction::Init { project, .. } | UserAction::Show { project, .. } | UserAction::Set { project, .. } | UserAction::AddPath { project, .. } }
+            | Commands::Audit { check: Some(AuditAction::Case { project }), .. }
+            | Commands::Guid { action: Some(GuidAction::Regen { project, .. }), .. } => Some(project),
+            Commands::Search { project, solution, .. }
+            | Commands::UpgradeToolset { project, solution, .. }
+            | Commands::SetSdk { project, solution, .. }
+            | Commands::Export { project, solution, .. }
+            | Commands::CodeAnalysis { project, solution, .. }
+            | Commands::SetCharset { project, solution, .. }
+            | Commands::SetConformance { project, solution, .. }
+            | Commands::SetBuildPerf { project, solution, .. }
+            | Commands::Hardening { action: HardeningAction::Set { project, solution, .. } }
+            | Commands::Audit { check: None, project, solution, .. } => project.as_deref().or(solution.as_deref()),
+            Commands::Which { solution, .. }
+            | Commands::Sln { action: SlnAction::RemoveProject { solution, .. } | SlnAction::SetDependency { solution, .. } }
+            | Commands::Guid { action: Some(GuidAction::List { solution } | GuidAction::Dedupe { solution, .. }), .. } => Some(solution),
+            Commands::Refactor { action: RefactorAction::ExtractProps { projects, solution, .. } } => projects.first().map(PathBuf::as_path).or(solution.as_deref()),
+            Commands::Generate { project, .. } | Commands::Guid { project, .. } => project.as_deref(),
+            Commands::Lookup { .. } | Commands::Run { .. } => None,
+        }
+    }
+
+}
+
+/// What a command line does to the files, read from the clap definition of its subcommand so
+/// that each command declares it where its arguments are: a command modifies files when it takes
+/// `--dryrun`, and one with a `writes` argument group only when an argument of that group is given
+/// (`lint --fix`, `export <FORMAT>`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Effects {
+    /// Whether the command can modify files, so that `--json` reports what it changed. Query
+    /// commands print their own results instead.
+    pub mutates: bool,
+    /// Whether the command was run with `--dryrun`
+    pub dryrun: bool,
+}
+
+impl Cli {
+    /// Parses the arguments like `parse_from`, along with what the command does to the files
+    pub fn parse_with_effects(args: &[String]) -> (Cli, Effects) {
+        let matches = Cli::command().get_matches_from(args);
+        let cli = Cli::from_arg_matches(&matches).unwrap_or_else(|err| err.exit());
+        (cli, Effects::of(&matches))
+    }
+}
+
+impl Effects {
+    pub fn of(matches: &ArgMatches) -> Effects {
+        let root = Cli::command();
+        let (mut command, mut matches) = (&root, matches);
+        while let Some((name, sub_matches)) = matches.subcommand() {
+            let Some(subcommand) = command.find_subcommand(name) else {
+                break;
+            };
+            (command, matches) = (subcommand, sub_matches);
+        }
+        let takes_dryrun = command.get_arguments().any(|arg| arg.get_id() == "dryrun");
+        let given = |id: &clap::Id| matches.value_source(id.as_str()) == Some(ValueSource::CommandLine);
+        let writes = command
+            .get_groups()
+            .find(|group| group.get_id() == "writes")
+            .is_none_or(|group| group.get_args().any(given));
+        Effects { mutates: takes_dryrun && writes, dryrun: takes_dryrun && matches.get_flag("dryrun") }
+    }
+}
+
+#[derive(Subcommand)]
+pub enum PatchAction {
+    /// Apply a patch file and record what it changed so it can be reverted
+    Apply {
+        /// Path to the patch file (.toml)
+        patch: PathBuf,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Undo exactly what an applied patch changed, keeping unrelated edits
+    Revert {
+        /// Path to the patch file (.toml) that was applied
+        patch: PathBuf,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum FiltersAction {
+    /// Write the project's filter tree and the rules reproducing its file assignments to a JSON layout
+    Export {
+        /// Path of the layout file to write (.json)
+        layout: PathBuf,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+    },
+    
+    /// Create the layout's filters and route the project's files into them
+    Apply {
+        /// Path to the layout file (.json)
+        layout: PathBuf,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Move every file into the top-level filter of its type ("Source Files", "Header Files", ...) and remove the nested filters
+    Flatten {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Remove the filters that hold no files, directly or in a child filter
+    PruneEmpty {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Create an empty filter and its missing parents, e.g. "Docs\Design", for files to come
+    Create {
+        /// Filter to create
+        #[arg(short, long)]
+        name: String,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Put every filter under a new root filter, e.g. "Source Files\Core" → "Engine\Source Files\Core"
+    Reroot {
+        /// Root filter to put the others under (e.g. "Engine" or "Libs\Engine")
+        #[arg(long)]
+        prefix: String,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum BackupsAction {
+    /// List the recorded operations, newest first
+    List {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+    },
+    
+    /// Delete old operations (defaults to the [history] retention in .vcprojm/config.toml)
+    Prune {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Keep only this many of the most recent operations
+        #[arg(long)]
+        keep: Option<usize>,
+        
+        /// Delete operations older than this many days
+        #[arg(long, value_name = "DAYS")]
+        max_age_days: Option<u64>,
+        
+        /// Show what would be deleted without deleting anything
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Restore the files to their state right after operation <ID> (0 = before the oldest operation)
+    Restore {
+        /// Id of the operation, as shown by `backups list`
+        id: usize,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be restored without modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum VcpkgAction {
+    /// Set the vcpkg MSBuild properties (VcpkgEnabled, VcpkgTriplet) and optionally import vcpkg.props/.targets
+    Enable {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Triplet to build dependencies for (e.g. "x64-windows"); defaults to vcpkg's choice per platform
+        #[arg(short, long)]
+        triplet: Option<String>,
+        
+        /// Install dependencies from the vcpkg.json manifest next to the project
+        #[arg(long)]
+        manifest: bool,
+        
+        /// vcpkg root to import vcpkg.props/.targets from (e.g. "$(VCPKG_ROOT)"), instead of relying on `vcpkg integrate install`
+        #[arg(long)]
+        root: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Add the include directory, library directories and libraries of installed ports to every configuration
+    Add {
+        /// Port names (e.g. "fmt zlib")
+        #[arg(required = true)]
+        ports: Vec<String>,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Triplet the ports are installed for (defaults to the project's VcpkgTriplet)
+        #[arg(short, long)]
+        triplet: Option<String>,
+        
+        /// vcpkg root directory (defaults to the VCPKG_ROOT environment variable, written as $(VCPKG_ROOT))
+        #[arg(long)]
+        root: Option<PathBuf>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum NugetAction {
+    /// Show the project's packages and the .props/.targets they import
+    List {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+    },
+    
+    /// Reference a package. Projects with a packages.config get an entry there and imports of the
+    /// package's build\native .props/.targets; others get a <PackageReference>.
+    Add {
+        /// Package id (e.g. "Microsoft.Windows.CppWinRT")
+        package: String,
+        
+        /// Package version
+        #[arg(long)]
+        version: String,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Folder packages are restored into, as written in the imports (e.g. "..\packages");
+        /// uses packages.config mode. Defaults to the folder of the existing package imports.
+        #[arg(long)]
+        packages_dir: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Remove a package's PackageReference, packages.config entry and build imports
+    Remove {
+        /// Package id
+        package: String,
+        
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum PropsAction {
+    /// Show the property sheets imported by each configuration
+    List {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+    },
+    
+    /// Import a property sheet in the PropertySheets group of every configuration (or of --config)
+    Add {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Property sheet path as written in the project, relative to it (e.g. "..\common.props")
+        #[arg(short, long)]
+        sheet: String,
+        
+        /// Only this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Remove a property sheet import from every configuration (or from --config)
+    Remove {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Property sheet path as written in the project
+        #[arg(short, long)]
+        sheet: String,
+        
+        /// Only this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Move settings from the project into a new property sheet and import it instead
+    Create {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Property sheet to create, relative to the project (e.g. "..\common.props")
+        #[arg(short, long)]
+        sheet: String,
+        
+        /// Setting to move as Tool.Name, e.g. "ClCompile.PreprocessorDefinitions" (repeatable)
+        #[arg(long = "setting", required = true)]
+        settings: Vec<String>,
+        
+        /// Only take settings from this configuration: "Debug" (every platform) or "Debug|x64".
+        /// Without it, each setting must have the same value in every configuration.
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum RefactorAction {
+    /// Move include dirs, defines, lib dirs and libs that every project shares into one property sheet
+    #[command(name = "extract-props")]
+    ExtractProps {
+        /// Solution whose projects to deduplicate
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// Project to deduplicate (repeatable, in addition to the solution's projects)
+        #[arg(short, long = "project")]
+        projects: Vec<PathBuf>,
+        
+        /// Property sheet to create (e.g. "build/common.props")
+        #[arg(long)]
+        sheet: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum SlnAction {
+    /// Add a project to a solution, mapping every solution configuration to one of the project's
+    #[command(name = "add-project")]
+    AddProject {
+        /// Path to the .sln file
+        #[arg(short, long)]
+        solution: PathBuf,
+        
+        /// Path to the .vcxproj file to add
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Solution folder to put the project in, e.g. "Libraries" (created if missing)
+        #[arg(short, long)]
+        folder: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Remove a project from a solution with its configuration mappings and the dependencies on it
+    #[command(name = "remove-project")]
+    RemoveProject {
+        /// Path to the .sln file
+        #[arg(short, long)]
+        solution: PathBuf,
+        
+        /// The project's path or its name in the solution
+        #[arg(short, long)]
+        project: String,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Show a project's transitive dependencies and the order they build in
+    #[command(name = "deps")]
+    Deps {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Also follow the solution-level dependencies of this .sln file
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+    },
+    
+    /// Make a project build after others without linking them (solution-level ProjectDependencies)
+    #[command(name = "set-dependency")]
+    SetDependency {
+        /// Path to the .sln file
+        #[arg(short, long)]
+        solution: PathBuf,
+        
+        /// The dependent project's path or its name in the solution
+        #[arg(short, long)]
+        project: String,
+        
+        /// Path or name of a project to build first (repeatable)
+        #[arg(short, long, required = true)]
+        depends_on: Vec<String>,
+        
+        /// Remove the dependencies instead of adding them
+        #[arg(long)]
+        remove: bool,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CustomBuildAction {
+    /// Make files custom build items (adding them to the project if needed) and set their build step
+    Add {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// File to build with the command, e.g. "proto/addressbook.proto" (repeatable)
+        #[arg(short, long, required = true)]
+        file: Vec<PathBuf>,
+        
+        /// Command line to run, e.g. "protoc --cpp_out=$(IntDir) %(Identity)"
+        #[arg(long)]
+        command: String,
+        
+        /// Files the command produces, ';'-separated, e.g. "$(IntDir)%(Filename).pb.cc;$(IntDir)%(Filename).pb.h"
+        #[arg(long)]
+        outputs: String,
+        
+        /// Other files whose changes trigger the command, ';'-separated (AdditionalInputs)
+        #[arg(long)]
+        inputs: Option<String>,
+        
+        /// Message printed when the command runs
+        #[arg(long)]
+        message: Option<String>,
+        
+        /// Filter to show the files in (created if missing); files already in a filter stay there otherwise
+        #[arg(long)]
+        filter: Option<String>,
+        
+        /// Only set the build step for this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Change the build step of the custom build items matching a pattern
+    Set {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Glob pattern for the item paths (e.g. "proto/*.proto" or "**/*.fx")
+        #[arg(short, long)]
+        glob: String,
+        
+        /// Command line to run
+        #[arg(long)]
+        command: Option<String>,
+        
+        /// Files the command produces, ';'-separated
+        #[arg(long)]
+        outputs: Option<String>,
+        
+        /// Other files whose changes trigger the command, ';'-separated (AdditionalInputs)
+        #[arg(long)]
+        inputs: Option<String>,
+        
+        /// Message printed when the command runs
+        #[arg(long)]
+        message: Option<String>,
+        
+        /// Only set the build step for this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ShaderAction {
+    /// Set the entry point, shader type, shader model or object file of the shaders matching a pattern
+    Set {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Glob pattern for the shader paths (e.g. "shaders/*_ps.hlsl")
+        #[arg(short, long)]
+        glob: String,
+        
+        /// Entry point function (EntryPointName), e.g. "PSMain"
+        #[arg(long)]
+        entry_point: Option<String>,
+        
+        /// Shader type (ShaderType)
+        #[arg(long = "type", value_parser = ["Effect", "Vertex", "Pixel", "Geometry", "Hull", "Domain", "Compute", "Library", "Mesh", "Amplification", "RootSignature"])]
+        shader_type: Option<String>,
+        
+        /// Shader model (ShaderModel), e.g. "5.0" or "6.6"
+        #[arg(long)]
+        model: Option<String>,
+        
+        /// Compiled object file (ObjectFileOutput), e.g. "$(OutDir)%(Filename).cso"
+        #[arg(long)]
+        object_file: Option<String>,
+        
+        /// Only set for this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum CudaAction {
+    /// Import the CUDA build customization (.props and .targets), replacing the imports of another version
+    Enable {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// CUDA toolkit version, e.g. "12.4"
+        #[arg(long)]
+        version: String,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum QtAction {
+    /// Make the project a Qt VS Tools project and set its Qt modules (QtModules)
+    Enable {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Comma-separated Qt modules, e.g. "core,gui,widgets"
+        #[arg(short, long)]
+        modules: String,
+        
+        /// Qt installation registered in Qt VS Tools (QtInstall), e.g. "6.5.3_msvc2019_64"
+        #[arg(long)]
+        install: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ResourceAction {
+    /// Add a .rc file (ResourceCompile) with the header, icons, bitmaps and cursors it references, and/or a manifest
+    Add {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Resource script to add, e.g. "app.rc"
+        #[arg(long, required_unless_present = "manifest")]
+        rc: Option<PathBuf>,
+        
+        /// Application manifest to add (Manifest item), e.g. "app.manifest"
+        #[arg(long)]
+        manifest: Option<PathBuf>,
+        
+        /// Filter to put the resources in (headers go to "Header Files")
+        #[arg(long, default_value = "Resource Files")]
+        filter: String,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AuditAction {
+    /// Report item paths, include directories and #include directives cased differently from the files on disk
+    Case {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum GuidAction {
+    /// Give a project a new ProjectGuid, updating the solution and the references of its other projects
+    Regen {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Solution listing the project, updated to the new GUID
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// Also give every filter a new UniqueIdentifier
+        #[arg(long)]
+        filters: bool,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// List the GUIDs of a solution's projects, flagging duplicates and mismatches with the solution
+    List {
+        /// Path to the .sln file
+        #[arg(short, long)]
+        solution: PathBuf,
+    },
+    
+    /// Give each project that shares its ProjectGuid with another one a new GUID, and filters that
+    /// share a UniqueIdentifier within a project new identifiers
+    Dedupe {
+        /// Path to the .sln file
+        #[arg(short, long)]
+        solution: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ConfigAction {
+    /// Copy the compiler, linker and other tool settings of one configuration to another, e.g. after adding a platform
+    #[command(name = "copy-settings")]
+    CopySettings {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Configuration to copy from, e.g. "Debug|x64"
+        #[arg(long)]
+        from: String,
+        
+        /// Configuration to copy to, e.g. "Debug|ARM64"
+        #[arg(long)]
+        to: String,
+        
+        /// Only copy these tool sections, e.g. ClCompile or Link (repeatable, or comma-separated)
+        #[arg(long, value_delimiter = ',')]
+        only: Vec<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ForcedAction {
+    /// Add a file to the list of every configuration (or of --config)
+    Add {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// The file, e.g. "config.h" or "System.dll"
+        #[arg(short, long)]
+        file: String,
+        
+        /// Only add to this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Position in the list, which is the order files are included in: front, back, after:<file> or before:<file>
+        #[arg(long, default_value = "back")]
+        position: ListPosition,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Remove a file from the list of every configuration (or of --config)
+    Remove {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// The file, e.g. "config.h" or "System.dll"
+        #[arg(short, long)]
+        file: String,
+        
+        /// Only remove from this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum AsanAction {
+    /// Set EnableASAN, and turn off runtime checks, incremental linking and Edit and Continue, which ASan can't be combined with
+    Enable {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Only this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Also link the libFuzzer runtime (EnableFuzzer)
+        #[arg(long)]
+        fuzzer: bool,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Remove EnableASAN and EnableFuzzer, restoring Visual Studio's defaults for the settings `enable` turned off
+    Disable {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Only this configuration: "Debug" (every platform) or "Debug|x64"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum HardeningAction {
+    /// Turn Spectre mitigation (SpectreMitigation) and Control Flow Guard (/guard:cf) on or off
+    Set {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: Option<PathBuf>,
+        
+        /// Change every project referenced by a .sln file
+        #[arg(short, long)]
+        solution: Option<PathBuf>,
+        
+        /// Spectre mitigation: on (/Qspectre), load (/Qspectre-load), load-cf (/Qspectre-load-cf) or off
+        #[arg(long, value_parser = ["on", "load", "load-cf", "off"])]
+        spectre: Option<String>,
+        
+        /// Control Flow Guard (ClCompile ControlFlowGuard, /guard:cf)
+        #[arg(long, value_parser = ["on", "off"])]
+        cfg: Option<String>,
+        
+        /// Only this configuration: "Debug" (every platform), "Debug|x64" or "all"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum ImportsAction {
+    /// Show every <Import> in the order MSBuild evaluates them, with its ImportGroup and condition
+    List {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+    },
+    
+    /// Import a .props or .targets file: by default .props in the ExtensionSettings group (after
+    /// Microsoft.Cpp.props) and .targets in the ExtensionTargets group (after Microsoft.Cpp.targets)
+    Add {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Path to import as written in the project, e.g. "..\build\codegen.targets"
+        #[arg(short, long)]
+        import: String,
+        
+        /// Put it at the end of the ImportGroup with this Label, e.g. "PropertySheets"
+        #[arg(long, conflicts_with_all = ["before", "after"])]
+        group: Option<String>,
+        
+        /// Put it right before the import whose path ends with this, e.g. "Microsoft.Cpp.props"
+        #[arg(long, conflicts_with = "after")]
+        before: Option<String>,
+        
+        /// Put it right after the import whose path ends with this, e.g. "Microsoft.Cpp.targets"
+        #[arg(long)]
+        after: Option<String>,
+        
+        /// Only import it when the file exists (Condition="Exists('...')")
+        #[arg(long)]
+        optional: bool,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Remove an import, given its path or file name (the Microsoft.Cpp imports every project needs are kept)
+    Remove {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// The imported path as written, or just its file name, e.g. "codegen.targets"
+        #[arg(short, long)]
+        import: String,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub enum UserAction {
+    /// Create the .vcxproj.user file with a PropertyGroup using the local debugger for every configuration
+    Init {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Show the settings of the .vcxproj.user file by configuration
+    Show {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+    },
+    
+    /// Set what the debugger runs and how, in every configuration (or in --config); an empty value removes a setting
+    Set {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Program to start instead of the project's output (LocalDebuggerCommand)
+        #[arg(long)]
+        command: Option<String>,
+        
+        /// Command line arguments (LocalDebuggerCommandArguments)
+        #[arg(long, allow_hyphen_values = true)]
+        args: Option<String>,
+        
+        /// Working directory, e.g. "$(ProjectDir)" (LocalDebuggerWorkingDirectory)
+        #[arg(long)]
+        working_dir: Option<String>,
+        
+        /// Environment variable as NAME=value (repeatable; replaces LocalDebuggerEnvironment)
+        #[arg(long)]
+        env: Vec<String>,
+        
+        /// Only this configuration: "Debug" (every platform), "Debug|x64" or "all"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+    
+    /// Add a directory to the PATH the debugged program runs with (the PATH line of LocalDebuggerEnvironment)
+    #[command(name = "add-path")]
+    AddPath {
+        /// Path to the .vcxproj file
+        #[arg(short, long)]
+        project: PathBuf,
+        
+        /// Directory to add, e.g. "$(SolutionDir)bin"
+        #[arg(long)]
+        value: String,
+        
+        /// Put it before the other directories (and the inherited %PATH%) instead of after them
+        #[arg(long)]
+        prepend: bool,
+        
+        /// Only this configuration: "Debug" (every platform), "Debug|x64" or "all"
+        #[arg(short, long)]
+        config: Option<String>,
+        
+        /// Only this platform, e.g. "x64" (combines with --config)
+        #[arg(long)]
+        platform: Option<String>,
+        
+        /// Show what would be done without actually modifying files
+        #[arg(long)]
+        dryrun: bool,
+    },
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    
+    /// Runs `test` on a thread with room for building the whole command tree, which overflows the
+    /// default stack of test threads in debug builds
+    pub(crate) fn with_stack(test: impl FnOnce() + Send + 'static) {
+        std::thread::Builder::new().stack_size(32 << 20).spawn(test).unwrap().join().unwrap();
+    }
+    
+    fn effects(args: &str) -> Effects {
+        let args: Vec<&str> = std::iter::once("vsprojm").chain(args.split_whitespace()).collect();
+        Effects::of(&Cli::command().get_matches_from(args))
+    }
+    
+    #[test]
+    fn cli_definition_is_valid() {
+        with_stack(|| {
+            Cli::command().debug_assert();
+        });
+    }
+    
+    #[test]
+    fn commands_taking_dryrun_modify_files() {
+        with_stack(|| {
+            assert_eq!(effects("add -p a.vcxproj -e cpp"), Effects { mutates: true, dryrun: false });
+            assert_eq!(effects("add -p a.vcxproj -e cpp --dryrun"), Effects { mutates: true, dryrun: true });
+            assert_eq!(effects("forced-include add -p a.vcxproj -f pch.h --dryrun"), Effects { mutates: true, dryrun: true });
+            assert_eq!(effects("view -p a.vcxproj"), Effects { mutates: false, dryrun: false });
+            assert_eq!(effects("backups list -p a.vcxproj"), Effects { mutates: false, dryrun: false });
+        });
+    }
+    
+    #[test]
+    fn writes_group_limits_when_a_command_modifies_files() {
+        with_stack(|| {
+            assert_eq!(effects("lint -p a.vcxproj"), Effects { mutates: false, dryrun: false });
+            assert_eq!(effects("lint -p a.vcxproj --fix --dryrun"), Effects { mutates: true, dryrun: true });
+            assert_eq!(effects("doctor -p a.vcxproj --fix"), Effects { mutates: true, dryrun: false });
+            assert_eq!(effects("export --list"), Effects { mutates: false, dryrun: false });
+            assert_eq!(effects("export csv -p a.vcxproj"), Effects { mutates: true, dryrun: false });
+            assert_eq!(effects("export --format csv -p a.vcxproj --dryrun"), Effects { mutates: true, dryrun: true });
+        });
+    }
+}
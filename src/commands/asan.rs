@@ -0,0 +1,117 @@
+use anyhow::Result;
+use crate::i18n::{info, warn};
+use crate::output::{self, Change};
+use crate::vcxproj::{self, VcxprojFile};
+use std::path::PathBuf;
+
+/// Turns AddressSanitizer on or off in the selected configurations. Enabling also turns off what
+/// MSVC rejects with /fsanitize=address: runtime checks (/RTC), incremental linking and Edit and
+/// Continue (/ZI). Disabling removes EnableASAN and EnableFuzzer, and in Debug configurations the
+/// values `enable` wrote, so those settings fall back to Visual Studio's Debug defaults.
+pub fn set_address_sanitizer(project_path: PathBuf, config: Option<String>, enable: bool, fuzzer: bool, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let configs: Vec<String> = vcxproj
+        .get_configurations()
+        .into_iter()
+        .filter(|c| config.as_deref().is_none_or(|selector| vcxproj::config_matches(c, selector)))
+        .collect();
+    if let (Some(selector), true) = (&config, configs.is_empty()) {
+        warn!("⚠️  No configurations match '{}'", selector);
+        return Ok(false);
+    }
+    
+    let mut changed_configs = 0;
+    for config in &configs {
+        let tool_setting = |vcxproj: &VcxprojFile, tool: &str, name: &str| {
+            vcxproj
+                .get_tool_settings()
+                .into_iter()
+                .find(|s| s.tool == tool && s.name == name && s.condition.as_deref().is_some_and(|c| vcxproj::condition_config(c).eq_ignore_ascii_case(config)))
+                .map(|s| s.value)
+        };
+        let debug_config = vcxproj.is_debug_configuration(config);
+        let links = !matches!(vcxproj.get_config_property("ConfigurationType", config).as_deref(), Some("StaticLibrary" | "Utility"));
+        let mut changes: Vec<String> = Vec::new();
+        let describe = |name: &str, previous: Option<&str>, value: &str, reason: &str| {
+            format!("{}: {} → {}{}", name, previous.unwrap_or("(default)"), value, reason)
+        };
+        
+        if enable {
+            let mut properties = vec![("EnableASAN", "true")];
+            if fuzzer {
+                properties.push(("EnableFuzzer", "true"));
+            }
+            for (name, value) in properties {
+                if let Some(previous) = vcxproj.set_config_group_property(name, value, "Configuration", config) {
+                    changes.push(describe(name, previous.as_deref(), value, ""));
+                }
+            }
+            for (previous, value) in vcxproj.set_tool_setting("ClCompile", "BasicRuntimeChecks", "Default", Some(config)).into_iter().map(|(_, p)| (p, "Default")) {
+                changes.push(describe("ClCompile/BasicRuntimeChecks", previous.as_deref(), value, " (/RTC can't be combined with ASan)"));
+            }
+            let format = tool_setting(&vcxproj, "ClCompile", "DebugInformationFormat");
+            if format.as_deref() == Some("EditAndContinue") || (format.is_none() && debug_config) {
+                vcxproj.set_tool_setting("ClCompile", "DebugInformationFormat", "ProgramDatabase", Some(config));
+                changes.push(describe("ClCompile/DebugInformationFormat", format.as_deref(), "ProgramDatabase", " (/ZI can't be combined with ASan)"));
+            }
+            if links {
+                for (_, previous) in vcxproj.set_config_property("LinkIncremental", "false", Some(config))? {
+                    changes.push(describe("LinkIncremental", previous.as_deref(), "false", " (incremental linking can't be combined with ASan)"));
+                }
+            }
+        } else {
+            for name in ["EnableASAN", "EnableFuzzer"] {
+                if let Some(previous) = vcxproj.remove_config_group_property(name, config) {
+                    changes.push(describe(name, Some(&previous), "(default)", ""));
+                }
+            }
+            if debug_config && !changes.is_empty() {
+                for (tool, name, written) in [("ClCompile", "BasicRuntimeChecks", "Default"), ("ClCompile", "DebugInformationFormat", "ProgramDatabase")] {
+                    if tool_setting(&vcxproj, tool, name).as_deref() == Some(written) {
+                        vcxproj.remove_tool_setting(tool, name, Some(config));
+                        changes.push(describe(&format!("{}/{}", tool, name), Some(written), "(default)", ""));
+                    }
+                }
+                if vcxproj.get_config_property("LinkIncremental", config).as_deref() == Some("false") {
+                    vcxproj.remove_config_group_property("LinkIncremental", config);
+                    changes.push(describe("LinkIncremental", Some("false"), "(default)", ""));
+                }
+            }
+        }
+        
+        if changes.is_empty() {
+            continue;
+        }
+        changed_configs += 1;
+        info!("\n🔧 {}:", config);
+        for change in &changes {
+            info!("  {}", change);
+        }
+        if enable && !debug_config {
+            warn!("  ⚠️  {} is a release configuration; ASan slows the program down and shouldn't ship", config);
+        }
+        output::report(Change::ConfigModified, [config]);
+    }
+    if changed_configs == 0 {
+        if enable {
+            info!("✅ No changes - AddressSanitizer is already enabled in every matching configuration");
+        } else {
+            info!("✅ No changes - AddressSanitizer is already disabled in every matching configuration");
+        }
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    if enable {
+        info!("\n✅ Enabled AddressSanitizer in {} configurations of {}", changed_configs, project_path.display());
+    } else {
+        info!("\n✅ Disabled AddressSanitizer in {} configurations of {}", changed_configs, project_path.display());
+    }
+    Ok(true)
+}
@@ -0,0 +1,130 @@
+use anyhow::Result;
+use crate::audit;
+use crate::i18n::info;
+use crate::output;
+use crate::sln::SolutionFile;
+use crate::vcxproj::VcxprojFile;
+use log::debug;
+use std::path::PathBuf;
+use super::{DEFAULT_EXCLUDED_DIRS, is_excluded_dir};
+use walkdir::WalkDir;
+
+/// Reports, per project, the constructs commands cannot parse or would not edit safely, and a summary
+/// of how many projects each kind affects, to plan which parts of a tree batch edits can cover
+pub fn audit_projects(directory: Option<PathBuf>, solution: Option<PathBuf>, project: Option<PathBuf>, json: bool) -> Result<()> {
+    use std::collections::BTreeMap;
+    
+    let project_paths = match (project, solution) {
+        (Some(project), _) => vec![project],
+        (None, Some(solution)) => SolutionFile::load(&solution)?.vcxproj_paths(),
+        (None, None) => {
+            let directory = directory.unwrap_or_else(|| PathBuf::from("."));
+            let excluded_dirs: Vec<String> = DEFAULT_EXCLUDED_DIRS.iter().map(|d| d.to_string()).collect();
+            let mut paths: Vec<PathBuf> = WalkDir::new(&directory)
+                .into_iter()
+                .filter_entry(|e| !is_excluded_dir(e.path(), e.file_type().is_dir(), &directory, &excluded_dirs))
+                .filter_map(|e| e.ok())
+                .filter(|e| e.file_type().is_file() && e.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("vcxproj")))
+                .map(|e| e.into_path())
+                .collect();
+            paths.sort();
+            paths
+        }
+    };
+    
+    let mut results = Vec::new();
+    for path in &project_paths {
+        let findings = match VcxprojFile::load(path) {
+            Ok(vcxproj) => audit::audit(&vcxproj),
+            Err(e) if path.exists() => vec![audit::Finding { kind: "unreadable", line: 0, detail: format!("{:#}", e) }],
+            Err(e) => return Err(e),
+        };
+        results.push((path, findings));
+    }
+    
+    // Kind -> number of projects with at least one such finding
+    let mut affected: BTreeMap<&str, usize> = BTreeMap::new();
+    for (_, findings) in &results {
+        let mut kinds: Vec<&str> = findings.iter().map(|f| f.kind).collect();
+        kinds.sort();
+        kinds.dedup();
+        for kind in kinds {
+            *affected.entry(kind).or_default() += 1;
+        }
+    }
+    let clean = results.iter().filter(|(_, findings)| findings.is_empty()).count();
+    
+    if json {
+        let report = serde_json::json!({
+            "projects": results.iter().map(|(path, findings)| serde_json::json!({
+                "project": path.display().to_string(),
+                "findings": findings.iter().map(|f| serde_json::json!({
+                    "kind": f.kind,
+                    "line": f.line,
+                    "detail": f.detail,
+                })).collect::<Vec<_>>(),
+            })).collect::<Vec<_>>(),
+            "fully_supported": clean,
+            "affected_projects": affected,
+        });
+        output::print_line(serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    
+    info!("🔎 Auditing {} projects", project_paths.len());
+    for (path, findings) in &results {
+        if findings.is_empty() {
+            debug!("✓ {}", path.display());
+            continue;
+        }
+        info!("\n⚠️  {}:", path.display());
+        for finding in findings {
+            match finding.line {
+                0 => info!("  {}: {}", finding.kind, finding.detail),
+                line => info!("  line {}: {}: {}", line, finding.kind, finding.detail),
+            }
+        }
+    }
+    
+    if !affected.is_empty() {
+        info!("\nProjects affected:");
+        for (kind, count) in &affected {
+            info!("  {:<28} {:>5}  {}", kind, count, audit::describe(kind));
+        }
+    }
+    info!("\n✅ {} of {} projects use only constructs every command supports", clean, results.len());
+    Ok(())
+}
+
+/// Reports the paths of a project whose case differs from the files on disk
+pub fn audit_case(project_path: PathBuf, json: bool) -> Result<()> {
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let findings = audit::audit_case(&vcxproj)?;
+    
+    if json {
+        let report = serde_json::json!({
+            "project": project_path.display().to_string(),
+            "findings": findings.iter().map(|f| serde_json::json!({
+                "kind": f.kind,
+                "line": f.line,
+                "detail": f.detail,
+            })).collect::<Vec<_>>(),
+        });
+        output::print_line(serde_json::to_string_pretty(&report)?);
+        return Ok(());
+    }
+    
+    info!("Analyzing project: {}", project_path.display());
+    if findings.is_empty() {
+        info!("✅ Every path matches the case of the file on disk");
+        return Ok(());
+    }
+    for finding in &findings {
+        match finding.line {
+            0 => info!("  {}: {}", finding.kind, finding.detail),
+            line => info!("  line {}: {}: {}", line, finding.kind, finding.detail),
+        }
+    }
+    info!("\n⚠️  {} paths differ in case from the disk; they only resolve on case-insensitive file systems", findings.len());
+    Ok(())
+}
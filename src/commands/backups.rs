@@ -0,0 +1,142 @@
+use anyhow::Result;
+use crate::history;
+use crate::i18n::info;
+use crate::output::{self, Change};
+use std::path::PathBuf;
+
+pub fn undo_last_operation(project_path: PathBuf, undo: bool, force: bool, dryrun: bool) -> Result<bool> {
+    let history_dir = history::history_dir_for(&project_path);
+    let head = history::read_head(&history_dir);
+    let entries = history::list_entries(&history_dir)?;
+
+    let target_id = if undo { head } else { head + 1 };
+    let Some(entry) = entries.iter().find(|e| e.id == target_id) else {
+        if undo {
+            info!("Nothing to undo for {}", project_path.display());
+        } else {
+            info!("Nothing to redo for {}", project_path.display());
+        }
+        return Ok(false);
+    };
+
+    let restored = history::restore(entry, undo, force, dryrun)?;
+    output::report(Change::FileWritten, restored.iter().map(|file| file.display()));
+
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        if undo {
+            info!("Would undo operation #{}: {}", entry.id, entry.command);
+        } else {
+            info!("Would redo operation #{}: {}", entry.id, entry.command);
+        }
+    } else if undo {
+        info!("↩️  Undid operation #{}: {}", entry.id, entry.command);
+    } else {
+        info!("↪️  Redid operation #{}: {}", entry.id, entry.command);
+    }
+    for file in &restored {
+        info!("  - {}", file.display());
+    }
+
+    Ok(true)
+}
+
+pub fn list_backups(project_path: PathBuf) -> Result<()> {
+    let history_dir = history::history_dir_for(&project_path);
+    let head = history::read_head(&history_dir);
+    let entries = history::list_entries(&history_dir)?;
+    if entries.is_empty() {
+        info!("No operations recorded for {}", project_path.display());
+        return Ok(());
+    }
+    
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    info!("🗂️  History for {} ({} operations)\n", project_path.display(), entries.len());
+    for entry in entries.iter().rev() {
+        let marker = if entry.id == head { "→" } else { " " };
+        let state = if entry.id > head { " (undone)" } else { "" };
+        info!(
+            "{} #{:<4} {:>10}  {}{}",
+            marker,
+            entry.id,
+            format_age(now.saturating_sub(entry.timestamp)),
+            entry.command,
+            state
+        );
+    }
+    if head == 0 {
+        info!("→ #0     (every operation is undone)");
+    }
+    Ok(())
+}
+
+/// Formats a duration in seconds as a short "… ago" for listings
+fn format_age(seconds: u64) -> String {
+    match seconds {
+        0..=59 => "just now".to_string(),
+        60..=3599 => format!("{} min ago", seconds / 60),
+        3600..=86399 => format!("{} h ago", seconds / 3600),
+        _ => format!("{} days ago", seconds / 86400),
+    }
+}
+
+pub fn prune_backups(project_path: PathBuf, keep: Option<usize>, max_age_days: Option<u64>, dryrun: bool) -> Result<bool> {
+    let history_dir = history::history_dir_for(&project_path);
+    let retention = if keep.is_some() || max_age_days.is_some() {
+        history::Retention { max_entries: keep, max_age_days }
+    } else {
+        history::Retention::load(&history_dir)?
+    };
+    
+    let pruned = history::prune(&history_dir, retention, dryrun)?;
+    if pruned.is_empty() {
+        info!("✅ Nothing to prune in {}", history_dir.display());
+        return Ok(false);
+    }
+    
+    info!("\n🗑️  Operations to delete:");
+    for entry in &pruned {
+        info!("  - #{} {}", entry.id, entry.command);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("✨ Dry run completed - {} operations would be deleted", pruned.len());
+        return Ok(true);
+    }
+    
+    info!("\n✅ Deleted {} operations from {}", pruned.len(), history_dir.display());
+    Ok(true)
+}
+
+pub fn restore_backup(project_path: PathBuf, id: usize, force: bool, dryrun: bool) -> Result<bool> {
+    let history_dir = history::history_dir_for(&project_path);
+    let head = history::read_head(&history_dir);
+    if head == id {
+        info!("✅ {} is already at operation #{}", project_path.display(), id);
+        return Ok(false);
+    }
+    
+    let (applied, restored) = history::restore_to(&history_dir, id, force, dryrun)?;
+    output::report(Change::FileWritten, restored.iter().map(|file| file.display()));
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        if id < head {
+            info!("Would undo {} operations to restore operation #{}", applied.len(), id);
+        } else {
+            info!("Would redo {} operations to restore operation #{}", applied.len(), id);
+        }
+    } else if id < head {
+        info!("⏪ Undid {} operations to restore operation #{}", applied.len(), id);
+    } else {
+        info!("⏪ Redid {} operations to restore operation #{}", applied.len(), id);
+    }
+    for file in &restored {
+        info!("  - {}", file.display());
+    }
+    Ok(true)
+}
@@ -0,0 +1,700 @@
+use anyhow::Result;
+use crate::i18n::{error, info, tr, warn};
+use crate::output::{self, Change};
+use crate::parallel;
+use crate::pipe;
+use crate::sln::SolutionFile;
+use crate::vcxproj::{self, VcxprojFile};
+use std::path::{Path, PathBuf};
+use super::describe_setting_change;
+
+/// Sets PlatformToolset, WindowsTargetPlatformVersion and ToolsVersion in one project or every project
+/// of a solution, reporting each project's values before and after. Like `edit_project_configs`, the
+/// projects of a solution are upgraded several at once.
+pub fn upgrade_toolset(
+    project: Option<PathBuf>,
+    solution: Option<PathBuf>,
+    toolset: Option<String>,
+    sdk: Option<String>,
+    tools_version: Option<String>,
+    dryrun: bool,
+) -> Result<bool> {
+    if toolset.is_none() && sdk.is_none() && tools_version.is_none() {
+        return Err(anyhow::anyhow!("Nothing to do: pass --toolset, --sdk or --tools-version"));
+    }
+    let mut project_paths: Vec<PathBuf> = project.into_iter().collect();
+    if let Some(solution) = &solution {
+        project_paths.extend(SolutionFile::load(solution)?.vcxproj_paths());
+    }
+    if project_paths.is_empty() {
+        return Err(anyhow::anyhow!("Pass --project or --solution"));
+    }
+    
+    // Distinct previous values, e.g. "v141, v142 (4 PropertyGroups)" when configurations disagreed
+    let before = |changes: &[(Option<String>, Option<String>)]| {
+        let mut values: Vec<&str> = Vec::new();
+        for (_, previous) in changes {
+            let value = previous.as_deref().unwrap_or("(unset)");
+            if !values.contains(&value) {
+                values.push(value);
+            }
+        }
+        let groups = if changes.len() > 1 { format!(" ({} PropertyGroups)", changes.len()) } else { String::new() };
+        format!("{}{}", values.join(", "), groups)
+    };
+    
+    let mut upgraded = Vec::new();
+    let mut failures = Vec::new();
+    parallel::for_each(
+        &project_paths,
+        true,
+        |_, path| {
+            let mut vcxproj = VcxprojFile::load(path)?;
+            let mut report = Vec::new();
+            if let Some(toolset) = &toolset {
+                let changes = vcxproj.set_property_in_groups("PlatformToolset", toolset, "Configuration");
+                if !changes.is_empty() {
+                    report.push(format!("PlatformToolset: {} → {}", before(&changes), toolset));
+                }
+            }
+            if let Some(sdk) = &sdk {
+                let changes = vcxproj.set_property_in_groups("WindowsTargetPlatformVersion", sdk, "Globals");
+                if !changes.is_empty() {
+                    report.push(format!("WindowsTargetPlatformVersion: {} → {}", before(&changes), sdk));
+                }
+            }
+            if let Some(version) = &tools_version {
+                if let Some(previous) = vcxproj.set_tools_version(version) {
+                    report.push(format!("ToolsVersion: {} → {}", previous.as_deref().unwrap_or("(unset)"), version));
+                }
+            }
+            
+            if report.is_empty() {
+                info!("✓ {}: already up to date", path.display());
+                return Ok(None);
+            }
+            info!("📁 {}:", path.display());
+            for line in &report {
+                info!("  {}", line);
+            }
+            Ok(Some(vcxproj))
+        },
+        |k, outcome| match outcome {
+            Ok(vcxproj) => upgraded.extend(vcxproj),
+            Err(err) => failures.push((k, err)),
+        },
+    );
+    let failed = check_project_failures(&project_paths, failures)?;
+    
+    if upgraded.is_empty() {
+        parallel::ProjectsFailed::check(failed, project_paths.len())?;
+        info!("\n✅ No changes - every project already uses these versions");
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        for vcxproj in &upgraded {
+            info!("Would update: {}", vcxproj.path.display());
+        }
+        return parallel::ProjectsFailed::check(failed, project_paths.len()).map(|_| true);
+    }
+    
+    for vcxproj in &upgraded {
+        vcxproj.save()?;
+    }
+    info!("\n✅ Upgraded {} of {} projects", upgraded.len(), project_paths.len());
+    parallel::ProjectsFailed::check(failed, project_paths.len()).map(|_| true)
+}
+
+/// Runtime libraries by RuntimeLibrary value and compiler switch
+pub const RUNTIME_LIBRARIES: &[(&str, &str)] = &[
+    ("MultiThreaded", "MT"),
+    ("MultiThreadedDebug", "MTd"),
+    ("MultiThreadedDLL", "MD"),
+    ("MultiThreadedDebugDLL", "MDd"),
+];
+
+/// Sets ClCompile/RuntimeLibrary in the selected configurations, warning about configurations whose
+/// runtime no longer matches their debug or release libraries
+pub fn set_runtime(project_path: PathBuf, value: String, config: Option<String>, dryrun: bool) -> Result<bool> {
+    let requested = value.trim_start_matches(['/', '-']);
+    let Some(&(runtime, switch)) = RUNTIME_LIBRARIES
+        .iter()
+        .find(|(name, switch)| name.eq_ignore_ascii_case(requested) || *switch == requested)
+    else {
+        return Err(anyhow::anyhow!(
+            "Unknown runtime library '{}': use MultiThreaded, MultiThreadedDebug, MultiThreadedDLL or MultiThreadedDebugDLL",
+            value
+        ));
+    };
+    
+    info!("Setting runtime library {} (/{}) in project: {}", runtime, switch, project_path.display());
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let matching_configs: Vec<String> = vcxproj
+        .get_configurations()
+        .into_iter()
+        .filter(|c| config.as_deref().is_none_or(|selector| vcxproj::config_matches(c, selector)))
+        .collect();
+    if let (Some(selector), true) = (&config, matching_configs.is_empty()) {
+        warn!("⚠️  No configurations match '{}'", selector);
+        return Ok(false);
+    }
+    
+    let changes = vcxproj.set_tool_setting("ClCompile", "RuntimeLibrary", runtime, config.as_deref());
+    if changes.is_empty() {
+        info!("✅ No changes - every matching configuration already uses {}", runtime);
+        return Ok(false);
+    }
+    output::report(Change::ConfigModified, changes.iter().map(|(c, _)| vcxproj::condition_config(c)));
+    
+    let debug_runtime = runtime.contains("Debug");
+    info!("\n🔧 RuntimeLibrary:");
+    for (condition, previous) in &changes {
+        let config = vcxproj::condition_config(condition);
+        info!("  {}: {} → {}", config, previous.as_deref().unwrap_or("(default)"), runtime);
+        let debug_config = vcxproj.is_debug_configuration(&config);
+        if debug_config && !debug_runtime {
+            warn!("  ⚠️  {} is a Debug configuration but {} is a release runtime; debug libraries it links will not match", config, runtime);
+        } else if !debug_config && debug_runtime {
+            warn!("  ⚠️  {} is a release configuration but {} is a debug runtime, which cannot be redistributed", config, runtime);
+        }
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    info!("\n✅ Set the runtime library in {} configurations of {}", changes.len(), project_path.display());
+    Ok(true)
+}
+
+/// ConfigurationType values by their short name
+const CONFIGURATION_TYPES: &[(&str, &str)] = &[
+    ("Application", "exe"),
+    ("DynamicLibrary", "dll"),
+    ("StaticLibrary", "lib"),
+    ("Utility", "utility"),
+];
+
+/// Sets ConfigurationType in every configuration and adjusts the settings that depend on it: a DLL
+/// gets the `<PROJECT>_EXPORTS` define the Visual Studio template uses (removed again when switching
+/// away), and a static library loses the linker's SubSystem. Lists what else may need attention.
+pub fn set_configuration_type(project_path: PathBuf, value: String, dryrun: bool) -> Result<bool> {
+    let Some(&(new_type, _)) = CONFIGURATION_TYPES
+        .iter()
+        .find(|(name, short)| name.eq_ignore_ascii_case(&value) || short.eq_ignore_ascii_case(&value))
+    else {
+        return Err(anyhow::anyhow!(
+            "Unknown configuration type '{}': use Application, DynamicLibrary, StaticLibrary or Utility",
+            value
+        ));
+    };
+    
+    info!("Setting configuration type {} in project: {}", new_type, project_path.display());
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let changes = vcxproj.set_property_in_groups("ConfigurationType", new_type, "Configuration");
+    if changes.is_empty() {
+        info!("✅ No changes - every configuration is already a {}", new_type);
+        return Ok(false);
+    }
+    let was_dll = changes.iter().any(|(_, previous)| previous.as_deref() == Some("DynamicLibrary"));
+    
+    let project_name = vcxproj.project_name();
+    let export_define = format!(
+        "{}_EXPORTS",
+        project_name.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_uppercase() } else { '_' }).collect::<String>()
+    );
+    let mut adjusted = Vec::new();
+    let mut attention = Vec::new();
+    match new_type {
+        "DynamicLibrary" => {
+            let settings = vcxproj.get_tool_settings();
+            for config in vcxproj.get_configurations() {
+                let current = settings
+                    .iter()
+                    .find(|s| {
+                        s.tool == "ClCompile"
+                            && s.name == "PreprocessorDefinitions"
+                            && s.condition.as_deref().is_some_and(|c| vcxproj::condition_matches(c, &config))
+                    })
+                    .map_or("%(PreprocessorDefinitions)", |s| s.value.as_str());
+                let mut defines: Vec<&str> = current.split(';').filter(|d| !d.trim().is_empty()).collect();
+                if defines.iter().any(|d| d.trim() == export_define) {
+                    continue;
+                }
+                let inherited = defines.iter().position(|d| d.trim() == "%(PreprocessorDefinitions)").unwrap_or(defines.len());
+                defines.insert(inherited, &export_define);
+                let defines = defines.join(";");
+                if !vcxproj.set_tool_setting("ClCompile", "PreprocessorDefinitions", &defines, Some(&config)).is_empty() {
+                    adjusted.push(tr!("{}: defined {}", config, export_define));
+                }
+            }
+            attention.push(tr!(
+                "Export the public API with __declspec(dllexport) when {} is defined, and __declspec(dllimport) otherwise",
+                export_define
+            ));
+            attention.push(tr!("Projects linking this one use its import library ($(OutDir)$(TargetName).lib) and need the DLL next to their executable"));
+        }
+        "StaticLibrary" => {
+            for condition in vcxproj.remove_tool_setting("Link", "SubSystem", None) {
+                adjusted.push(tr!("{}: removed Link SubSystem", vcxproj::condition_config(&condition)));
+            }
+            let linker_inputs = vcxproj
+                .get_tool_settings()
+                .into_iter()
+                .any(|s| s.tool == "Link" && (s.name == "AdditionalDependencies" || s.name == "AdditionalLibraryDirectories"));
+            if linker_inputs {
+                attention.push(tr!("Static libraries are not linked: the Link AdditionalDependencies and AdditionalLibraryDirectories are ignored, so projects using this one must link those libraries"));
+            }
+        }
+        "Application" => {
+            attention.push(tr!("An application needs an entry point (main or WinMain) matching its Link SubSystem"));
+        }
+        _ => {}
+    }
+    if was_dll && new_type != "DynamicLibrary" {
+        for define in [export_define.as_str(), "_USRDLL"] {
+            if vcxproj.remove_list_value("PreprocessorDefinitions", define, None) > 0 {
+                adjusted.push(tr!("removed the {} define", define));
+            }
+        }
+        attention.push(tr!("Code using __declspec(dllexport) or dllimport no longer applies and may need cleaning up"));
+    }
+    if let Some(extension) = vcxproj.get_properties().into_iter().find(|p| p.name == "TargetExt") {
+        attention.push(format!("TargetExt is set to '{}' and is not changed", extension.value));
+    }
+    output::report(Change::ConfigModified, changes.iter().filter_map(|(c, _)| c.as_deref()).map(vcxproj::condition_config));
+    
+    info!("\n⚙️  ConfigurationType:");
+    for (condition, previous) in &changes {
+        let config = condition.as_deref().map_or("(all configurations)".into(), vcxproj::condition_config);
+        info!("  {}: {} → {}", config, previous.as_deref().unwrap_or("(unset)"), new_type);
+    }
+    if !adjusted.is_empty() {
+        info!("\n🔧 Adjusted settings:");
+        for change in &adjusted {
+            info!("  - {}", change);
+        }
+    }
+    if !attention.is_empty() {
+        info!("\n📋 May need manual attention:");
+        for note in &attention {
+            warn!("  ⚠️  {}", note);
+        }
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    info!("\n✅ {} is now a {}", project_path.display(), new_type);
+    Ok(true)
+}
+
+/// A setting written by a preset as (tool, name, value). A `None` tool is a property of the
+/// configuration's "Configuration" group, where Microsoft.Cpp.props reads it.
+type PresetSetting = (Option<&'static str>, &'static str, &'static str);
+
+/// The settings of each `set-optimization` preset
+const OPTIMIZATION_PRESETS: &[(&str, &[PresetSetting])] = &[
+    ("release-lto", &[
+        (Some("ClCompile"), "Optimization", "MaxSpeed"),
+        (None, "WholeProgramOptimization", "true"),
+        (Some("ClCompile"), "FunctionLevelLinking", "true"),
+        (Some("ClCompile"), "IntrinsicFunctions", "true"),
+        (Some("ClCompile"), "DebugInformationFormat", "ProgramDatabase"),
+        (Some("Link"), "GenerateDebugInformation", "true"),
+    ]),
+    ("debug", &[
+        (Some("ClCompile"), "Optimization", "Disabled"),
+        (None, "WholeProgramOptimization", "false"),
+        (Some("ClCompile"), "FunctionLevelLinking", "false"),
+        (Some("ClCompile"), "IntrinsicFunctions", "false"),
+        (Some("ClCompile"), "DebugInformationFormat", "ProgramDatabase"),
+        (Some("Link"), "GenerateDebugInformation", "true"),
+    ]),
+    ("size", &[
+        (Some("ClCompile"), "Optimization", "MinSpace"),
+        (Some("ClCompile"), "FavorSizeOrSpeed", "Size"),
+        (None, "WholeProgramOptimization", "true"),
+        (Some("ClCompile"), "FunctionLevelLinking", "true"),
+        (Some("ClCompile"), "IntrinsicFunctions", "false"),
+        (Some("ClCompile"), "DebugInformationFormat", "ProgramDatabase"),
+        (Some("Link"), "GenerateDebugInformation", "true"),
+    ]),
+];
+
+/// Applies an optimization preset to the selected configurations. Linker settings are skipped in
+/// static libraries, which have no link step.
+pub fn set_optimization(project_path: PathBuf, preset: String, config: Option<String>, dryrun: bool) -> Result<bool> {
+    let Some(&(_, settings)) = OPTIMIZATION_PRESETS.iter().find(|(name, _)| *name == preset) else {
+        return Err(anyhow::anyhow!("Unknown preset '{}': use release-lto, debug or size", preset));
+    };
+    
+    info!("Applying optimization preset {} to project: {}", preset, project_path.display());
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let configs: Vec<String> = vcxproj
+        .get_configurations()
+        .into_iter()
+        .filter(|c| config.as_deref().is_none_or(|selector| vcxproj::config_matches(c, selector)))
+        .collect();
+    if let (Some(selector), true) = (&config, configs.is_empty()) {
+        warn!("⚠️  No configurations match '{}'", selector);
+        return Ok(false);
+    }
+    
+    let mut changed_configs = 0;
+    for config in &configs {
+        let static_library = vcxproj.get_config_property("ConfigurationType", config).as_deref() == Some("StaticLibrary");
+        let mut changes = Vec::new();
+        for &(tool, name, value) in settings {
+            let previous = match tool {
+                Some("Link") if static_library => continue,
+                Some(tool) => vcxproj.set_tool_setting(tool, name, value, Some(config)).into_iter().next().map(|(_, previous)| previous),
+                None => vcxproj.set_config_group_property(name, value, "Configuration", config),
+            };
+            if let Some(previous) = previous {
+                let setting = tool.map_or(name.to_string(), |tool| format!("{}/{}", tool, name));
+                changes.push(format!("{}: {} → {}", setting, previous.as_deref().unwrap_or("(default)"), value));
+            }
+        }
+        if changes.is_empty() {
+            continue;
+        }
+        changed_configs += 1;
+        info!("\n🔧 {}:", config);
+        for change in &changes {
+            info!("  {}", change);
+        }
+        let debug_config = vcxproj.is_debug_configuration(config);
+        if debug_config && preset != "debug" {
+            warn!("  ⚠️  {} is a Debug configuration; optimized code is hard to step through", config);
+        } else if !debug_config && preset == "debug" {
+            warn!("  ⚠️  {} is a release configuration; it will no longer be optimized", config);
+        }
+        output::report(Change::ConfigModified, [config]);
+    }
+    if changed_configs == 0 {
+        info!("✅ No changes - every matching configuration already uses the {} preset", preset);
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ Applied the {} preset to {} configurations of {}", preset, changed_configs, project_path.display());
+    Ok(true)
+}
+
+/// Applies `edit` to the selected configurations of a project, or of every project in a solution,
+/// and saves the projects it changed. `edit` returns a description of each change it made, and
+/// `settings` names what is being changed in the summary, e.g. "code analysis settings". The
+/// projects of a solution are edited several at once; one that fails leaves the others unchanged
+/// too, unless `--keep-going`.
+fn edit_project_configs(
+    project: Option<PathBuf>,
+    solution: Option<PathBuf>,
+    config: Option<String>,
+    settings: &str,
+    dryrun: bool,
+    edit: impl Fn(&mut VcxprojFile, &str) -> Result<Vec<String>> + Sync,
+) -> Result<bool> {
+    let mut project_paths: Vec<PathBuf> = project.into_iter().collect();
+    if let Some(solution) = &solution {
+        project_paths.extend(SolutionFile::load(solution)?.vcxproj_paths());
+    }
+    if project_paths.is_empty() {
+        return Err(anyhow::anyhow!("Pass --project or --solution"));
+    }
+    
+    let mut changed = Vec::new();
+    let mut failures = Vec::new();
+    parallel::for_each(
+        &project_paths,
+        true,
+        |_, path| {
+            let mut vcxproj = VcxprojFile::load(path)?;
+            let configs: Vec<String> = vcxproj
+                .get_configurations()
+                .into_iter()
+                .filter(|c| config.as_deref().is_none_or(|selector| vcxproj::config_matches(c, selector)))
+                .collect();
+            if configs.is_empty() {
+                match &config {
+                    Some(selector) => warn!("⚠️  {}: no configurations match '{}'", path.display(), selector),
+                    None => warn!("⚠️  {}: the project has no configurations", path.display()),
+                }
+                return Ok(None);
+            }
+            
+            let mut report = Vec::new();
+            for config in &configs {
+                let changes = edit(&mut vcxproj, config)?;
+                if !changes.is_empty() {
+                    output::report(Change::ConfigModified, [config]);
+                    report.push((config.clone(), changes));
+                }
+            }
+            if report.is_empty() {
+                info!("✓ {}: already up to date", path.display());
+                return Ok(None);
+            }
+            info!("📁 {}:", path.display());
+            for (config, changes) in &report {
+                info!("  🔧 {}:", config);
+                for change in changes {
+                    info!("    {}", change);
+                }
+            }
+            Ok(Some(vcxproj))
+        },
+        |k, outcome| match outcome {
+            Ok(vcxproj) => changed.extend(vcxproj),
+            Err(err) => failures.push((k, err)),
+        },
+    );
+    let failed = check_project_failures(&project_paths, failures)?;
+    
+    if changed.is_empty() {
+        parallel::ProjectsFailed::check(failed, project_paths.len())?;
+        info!("\n✅ No changes - every project already has the requested {}", settings);
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        for vcxproj in &changed {
+            info!("Would update: {}", vcxproj.path.display());
+        }
+        return parallel::ProjectsFailed::check(failed, project_paths.len()).map(|_| true);
+    }
+    
+    for vcxproj in &changed {
+        vcxproj.save()?;
+    }
+    info!("\n✅ Updated {} in {} of {} projects", settings, changed.len(), project_paths.len());
+    parallel::ProjectsFailed::check(failed, project_paths.len()).map(|_| true)
+}
+
+/// Reports the projects of a batch edit that failed, as (index in `project_paths`, error). With a
+/// single project its error is the command's. Otherwise the command fails before saving anything
+/// unless `--keep-going`, which goes on to save the other projects. Returns how many failed.
+fn check_project_failures(project_paths: &[PathBuf], mut failures: Vec<(usize, anyhow::Error)>) -> Result<usize> {
+    if project_paths.len() == 1 {
+        return failures.pop().map_or(Ok(0), |(_, err)| Err(err));
+    }
+    for (k, err) in &failures {
+        error!("❌ {}: {:#}", project_paths[*k].display(), err);
+    }
+    if !failures.is_empty() && !parallel::keep_going() {
+        return Err(anyhow::anyhow!(
+            "{} of {} projects failed - no project was changed (--keep-going updates the others)",
+            failures.len(),
+            project_paths.len()
+        ));
+    }
+    Ok(failures.len())
+}
+
+/// What `code-analysis` changes; `None` leaves a setting alone
+pub struct CodeAnalysisSettings {
+    pub prefast: Option<bool>,
+    pub run_on_build: Option<bool>,
+    pub ruleset: Option<String>,
+}
+
+/// Sets EnablePREfast, RunCodeAnalysis and CodeAnalysisRuleSet in the selected configurations of a
+/// project or of every project in a solution
+pub fn set_code_analysis(project: Option<PathBuf>, solution: Option<PathBuf>, settings: CodeAnalysisSettings, config: Option<String>, dryrun: bool) -> Result<bool> {
+    if settings.prefast.is_none() && settings.run_on_build.is_none() && settings.ruleset.is_none() {
+        return Err(anyhow::anyhow!("Nothing to do: pass --prefast, --run-on-build or --ruleset"));
+    }
+    // A rule set file on disk is referenced relative to each project; anything else is a name
+    // Visual Studio resolves in its own Rule Sets directory
+    let ruleset_file = settings.ruleset.as_deref().map(Path::new).filter(|path| pipe::exists(path));
+    
+    edit_project_configs(project, solution, config, &tr!("code analysis settings"), dryrun, |vcxproj, config| {
+        let mut changes = Vec::new();
+        if let Some(enabled) = settings.prefast {
+            let value = if enabled { "true" } else { "false" };
+            for (_, previous) in vcxproj.set_tool_setting("ClCompile", "EnablePREfast", value, Some(config)) {
+                changes.push(describe_setting_change("ClCompile/EnablePREfast", previous.as_deref(), value));
+            }
+        }
+        if let Some(enabled) = settings.run_on_build {
+            let value = if enabled { "true" } else { "false" };
+            for (_, previous) in vcxproj.set_config_property("RunCodeAnalysis", value, Some(config))? {
+                changes.push(describe_setting_change("RunCodeAnalysis", previous.as_deref(), value));
+            }
+        }
+        let ruleset = match ruleset_file {
+            Some(file) => Some(vcxproj::relative_path(vcxproj.path.parent().unwrap_or_else(|| Path::new(".")), file)),
+            None => settings.ruleset.clone(),
+        };
+        if let Some(ruleset) = &ruleset {
+            for (_, previous) in vcxproj.set_config_property("CodeAnalysisRuleSet", ruleset, Some(config))? {
+                changes.push(describe_setting_change("CodeAnalysisRuleSet", previous.as_deref(), ruleset));
+            }
+        }
+        Ok(changes)
+    })
+}
+
+/// Sets SpectreMitigation (a Configuration property) and ClCompile's ControlFlowGuard in the
+/// selected configurations of a project or of every project in a solution
+pub fn set_hardening(project: Option<PathBuf>, solution: Option<PathBuf>, spectre: Option<String>, cfg: Option<String>, config: Option<String>, dryrun: bool) -> Result<bool> {
+    let spectre = spectre.map(|mode| match mode.as_str() {
+        "on" => "Spectre",
+        "load" => "SpectreLoad",
+        "load-cf" => "SpectreLoadCF",
+        _ => "false",
+    });
+    let cfg = cfg.map(|state| if state == "on" { "Guard" } else { "false" });
+    if spectre.is_none() && cfg.is_none() {
+        return Err(anyhow::anyhow!("Nothing to do: pass --spectre or --cfg"));
+    }
+    
+    let changes_planned = edit_project_configs(project, solution, config, &tr!("hardening settings"), dryrun, |vcxproj, config| {
+        let mut changes = Vec::new();
+        if let Some(value) = spectre {
+            if let Some(previous) = vcxproj.set_config_group_property("SpectreMitigation", value, "Configuration", config) {
+                changes.push(describe_setting_change("SpectreMitigation", previous.as_deref(), value));
+            }
+        }
+        if let Some(value) = cfg {
+            for (_, previous) in vcxproj.set_tool_setting("ClCompile", "ControlFlowGuard", value, Some(config)) {
+                changes.push(describe_setting_change("ClCompile/ControlFlowGuard", previous.as_deref(), value));
+            }
+        }
+        Ok(changes)
+    })?;
+    if changes_planned && spectre.is_some_and(|value| value != "false") {
+        warn!("⚠️  Spectre-mitigated builds link the Spectre-mitigated libraries, which are a separate Visual Studio component (\"MSVC ... Spectre-mitigated libs\")");
+    }
+    Ok(changes_planned)
+}
+
+/// Values of the CharacterSet property, with the short names accepted for them
+const CHARACTER_SETS: &[(&str, &str)] = &[("Unicode", "unicode"), ("MultiByte", "mbcs"), ("NotSet", "none")];
+
+/// Sets CharacterSet in the selected configurations of a project or of every project in a solution,
+/// warning about preprocessor definitions that contradict the new character set
+pub fn set_character_set(project: Option<PathBuf>, solution: Option<PathBuf>, value: String, config: Option<String>, dryrun: bool) -> Result<bool> {
+    let Some(&(charset, _)) = CHARACTER_SETS
+        .iter()
+        .find(|(name, short)| name.eq_ignore_ascii_case(&value) || short.eq_ignore_ascii_case(&value))
+    else {
+        return Err(anyhow::anyhow!("Unknown character set '{}': use Unicode, MultiByte or NotSet", value));
+    };
+    // CharacterSet defines these itself, so a project defining the other set's ones builds with both
+    let conflicting: &[&str] = match charset {
+        "Unicode" => &["_MBCS"],
+        "MultiByte" => &["_UNICODE", "UNICODE"],
+        _ => &["_MBCS", "_UNICODE", "UNICODE"],
+    };
+    
+    edit_project_configs(project, solution, config, &tr!("character set"), dryrun, |vcxproj, config| {
+        let mut changes = Vec::new();
+        if let Some(previous) = vcxproj.set_config_group_property("CharacterSet", charset, "Configuration", config) {
+            changes.push(describe_setting_change("CharacterSet", previous.as_deref(), charset));
+        }
+        let defined: Vec<String> = vcxproj
+            .get_tool_settings()
+            .into_iter()
+            .filter(|s| s.tool == "ClCompile" && s.name == "PreprocessorDefinitions" && s.condition.as_deref().is_none_or(|c| vcxproj::condition_matches(c, config)))
+            .flat_map(|s| s.value.split(';').map(|d| d.trim().to_string()).collect::<Vec<_>>())
+            .filter(|d| conflicting.contains(&d.as_str()))
+            .collect();
+        if !defined.is_empty() {
+            warn!("⚠️  {} {}: defines {}, which contradicts CharacterSet {}", vcxproj.path.display(), config, defined.join(";"), charset);
+        }
+        Ok(changes)
+    })
+}
+
+/// Sets ClCompile's ConformanceMode (/permissive-) in the selected configurations of a project or
+/// of every project in a solution
+pub fn set_conformance_mode(project: Option<PathBuf>, solution: Option<PathBuf>, value: String, config: Option<String>, dryrun: bool) -> Result<bool> {
+    edit_project_configs(project, solution, config, &tr!("conformance mode"), dryrun, |vcxproj, config| {
+        Ok(vcxproj
+            .set_tool_setting("ClCompile", "ConformanceMode", &value, Some(config))
+            .into_iter()
+            .map(|(_, previous)| describe_setting_change("ClCompile/ConformanceMode", previous.as_deref(), &value))
+            .collect())
+    })
+}
+
+/// What `set-build-perf` changes; `None` leaves a setting alone
+pub struct BuildPerfSettings {
+    pub mp: Option<bool>,
+    pub incremental_link: Option<bool>,
+    pub debug_fastlink: Option<bool>,
+}
+
+/// Sets MultiProcessorCompilation, LinkIncremental and the linker's GenerateDebugInformation in the
+/// selected configurations of a project or of every project in a solution, along with the settings
+/// each depends on: /MP can't be combined with /Gm, and Edit and Continue (/ZI) needs incremental linking
+pub fn set_build_perf(project: Option<PathBuf>, solution: Option<PathBuf>, settings: BuildPerfSettings, config: Option<String>, dryrun: bool) -> Result<bool> {
+    if settings.mp.is_none() && settings.incremental_link.is_none() && settings.debug_fastlink.is_none() {
+        return Err(anyhow::anyhow!("Nothing to do: pass --mp, --incremental-link or --debug-fastlink"));
+    }
+    let flag = |enabled: bool| if enabled { "true" } else { "false" };
+    
+    edit_project_configs(project, solution, config, &tr!("build performance settings"), dryrun, |vcxproj, config| {
+        let mut changes = Vec::new();
+        let mut set_tool_setting = |vcxproj: &mut VcxprojFile, tool: &str, name: &str, value: &str| {
+            for (_, previous) in vcxproj.set_tool_setting(tool, name, value, Some(config)) {
+                changes.push(describe_setting_change(&format!("{}/{}", tool, name), previous.as_deref(), value));
+            }
+        };
+        let tool_setting = |vcxproj: &VcxprojFile, tool: &str, name: &str| {
+            vcxproj
+                .get_tool_settings()
+                .into_iter()
+                .find(|s| s.tool == tool && s.name == name && s.condition.as_deref().is_some_and(|c| vcxproj::condition_config(c).eq_ignore_ascii_case(config)))
+                .map(|s| s.value)
+        };
+        let links = !matches!(vcxproj.get_config_property("ConfigurationType", config).as_deref(), Some("StaticLibrary" | "Utility"));
+        
+        if let Some(enabled) = settings.mp {
+            set_tool_setting(vcxproj, "ClCompile", "MultiProcessorCompilation", flag(enabled));
+            if enabled && tool_setting(vcxproj, "ClCompile", "MinimalRebuild").as_deref() == Some("true") {
+                set_tool_setting(vcxproj, "ClCompile", "MinimalRebuild", "false");
+            }
+        }
+        if let Some(enabled) = settings.debug_fastlink.filter(|_| links) {
+            let generate = tool_setting(vcxproj, "Link", "GenerateDebugInformation");
+            if enabled {
+                set_tool_setting(vcxproj, "Link", "GenerateDebugInformation", "DebugFastLink");
+                // Fast linking reads the debug information of the object files, so they need some
+                if tool_setting(vcxproj, "ClCompile", "DebugInformationFormat").as_deref() == Some("None") {
+                    set_tool_setting(vcxproj, "ClCompile", "DebugInformationFormat", "ProgramDatabase");
+                }
+            } else if generate.as_deref() == Some("DebugFastLink") {
+                set_tool_setting(vcxproj, "Link", "GenerateDebugInformation", "true");
+            }
+        }
+        if let Some(enabled) = settings.incremental_link.filter(|_| links) {
+            for (_, previous) in vcxproj.set_config_property("LinkIncremental", flag(enabled), Some(config))? {
+                changes.push(describe_setting_change("LinkIncremental", previous.as_deref(), flag(enabled)));
+            }
+            let format = tool_setting(vcxproj, "ClCompile", "DebugInformationFormat");
+            let edit_and_continue = format.as_deref() == Some("EditAndContinue") || (format.is_none() && vcxproj.is_debug_configuration(config));
+            if !enabled && edit_and_continue {
+                vcxproj.set_tool_setting("ClCompile", "DebugInformationFormat", "ProgramDatabase", Some(config));
+                changes.push(describe_setting_change("ClCompile/DebugInformationFormat", format.as_deref(), "ProgramDatabase"));
+            }
+        }
+        Ok(changes)
+    })
+}
@@ -0,0 +1,227 @@
+use anyhow::Result;
+use crate::i18n::{info, tr, warn};
+use crate::output::{self, Change};
+use crate::pipe;
+use crate::vcxproj::{self, FilterFile, VcxprojFile};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use super::{config_condition, glob_to_regex};
+
+/// Turns `files` into CustomBuild items and sets their build step. Files missing from the project
+/// are added; items of another type (usually None) are converted, keeping their metadata and filter.
+pub fn add_custom_build_items(
+    project_path: PathBuf,
+    files: Vec<PathBuf>,
+    step: &[(&str, Option<String>)],
+    filter: Option<String>,
+    config: Option<String>,
+    dryrun: bool,
+) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let filter_path = vcxproj::filters_path(&project_path);
+    let mut filter_file = if pipe::exists(&filter_path) { Some(FilterFile::load(&filter_path)?) } else { None };
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    let items = vcxproj.get_items()?;
+    
+    let mut includes = Vec::new();
+    let mut changed = false;
+    for file in &files {
+        if !file.exists() {
+            warn!("⚠️  {} does not exist", file.display());
+        }
+        let include = vcxproj::relative_path(project_dir, file);
+        match items.iter().find(|item| vcxproj::same_path(&item.include, &include)) {
+            None => {
+                vcxproj.insert_item_lines("CustomBuild", &[format!("    <CustomBuild Include=\"{}\" />", include)])?;
+                info!("  ➕ {}", include);
+                output::report(Change::FileAdded, [&include]);
+                changed = true;
+            }
+            Some(item) if item.item_type != "CustomBuild" => {
+                vcxproj.set_item_type(&include, "CustomBuild")?;
+                if let Some(filter_file) = &mut filter_file {
+                    filter_file.set_item_type(&include, "CustomBuild");
+                }
+                info!("  🔁 {}: {} → CustomBuild", include, item.item_type);
+                changed = true;
+            }
+            Some(_) => {}
+        }
+        if let (Some(filter_file), Some(filter)) = (&mut filter_file, &filter) {
+            for created in filter_file.ensure_filter(filter, None) {
+                info!("  📁 New filter: {}", created);
+                output::report(Change::FilterCreated, [&created]);
+            }
+            if filter_file.set_file_filter("CustomBuild", &include, filter).is_some() {
+                info!("  📁 {} → {}", include, filter);
+                output::report(Change::FileMoved, [&include]);
+                changed = true;
+            }
+        }
+        includes.push(include);
+    }
+    if filter.is_some() && filter_file.is_none() {
+        warn!("⚠️  {} has no filters file; --filter was ignored", project_path.display());
+    }
+    
+    let pattern: Vec<String> = includes.iter().map(|include| regex::escape(&include.replace('\\', "/"))).collect();
+    let matcher = Regex::new(&format!("(?i)^(?:{})$", pattern.join("|")))?;
+    changed |= !set_custom_build_metadata(&mut vcxproj, &matcher, step, config.as_deref())?.is_empty();
+    if !changed {
+        info!("✅ No changes - the files already have this build step");
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save_with_filters(filter_file.as_ref())?;
+    info!("\n✅ {} custom build items in {}", includes.len(), project_path.display());
+    Ok(true)
+}
+
+/// Changes the build step of the CustomBuild items whose path matches `glob`
+pub fn set_custom_build_steps(project_path: PathBuf, glob: String, step: &[(&str, Option<String>)], config: Option<String>, dryrun: bool) -> Result<bool> {
+    if step.iter().all(|(_, value)| value.is_none()) {
+        return Err(anyhow::anyhow!("Specify at least one of --command, --outputs, --inputs and --message"));
+    }
+    info!("Analyzing project: {}", project_path.display());
+    
+    let matcher = glob_to_regex(&glob)?;
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let matching = vcxproj
+        .get_items()?
+        .iter()
+        .any(|item| item.item_type == "CustomBuild" && matcher.is_match(&item.include.replace('\\', "/")));
+    if !matching {
+        return Err(anyhow::anyhow!("No custom build items match '{}' (create them with custom-build add)", glob));
+    }
+    
+    let modified_files = set_custom_build_metadata(&mut vcxproj, &matcher, step, config.as_deref())?;
+    if modified_files.is_empty() {
+        info!("✅ No changes - the build steps are already set");
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ Updated the build step of {} files in {}", modified_files.len(), project_path.display());
+    Ok(true)
+}
+
+/// Writes the given parts of a custom build step to the CustomBuild items matching `matcher` (see
+/// `set_metadata_per_config`) and lists what changed. Returns the items that changed.
+fn set_custom_build_metadata(vcxproj: &mut VcxprojFile, matcher: &Regex, step: &[(&str, Option<String>)], config: Option<&str>) -> Result<Vec<String>> {
+    // Commands like "a && b > log" have to be escaped to stay valid XML
+    let properties: Vec<(String, String)> = step
+        .iter()
+        .filter_map(|(name, value)| {
+            let value = value.as_ref()?.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;");
+            Some((name.to_string(), value))
+        })
+        .collect();
+    let modified_files = set_metadata_per_config(vcxproj, "CustomBuild", matcher, &properties, config)?;
+    if modified_files.is_empty() {
+        return Ok(modified_files);
+    }
+    
+    info!("\n🔧 Build step{}:", config.map(|c| tr!(" for {}", c)).unwrap_or_default());
+    for (name, value) in step {
+        if let Some(value) = value {
+            info!("  - {} = {}", name, value);
+        }
+    }
+    info!("\n📁 Files affected:");
+    for file in &modified_files {
+        info!("  - {}", file);
+    }
+    output::report(Change::FileModified, &modified_files);
+    Ok(modified_files)
+}
+
+/// Sets metadata on the `item_type` items matching `matcher`, once per configuration matching
+/// `config`, or unconditioned for all of them. Values for all configurations replace the
+/// per-configuration ones, which would otherwise still win. Returns the items that changed.
+fn set_metadata_per_config(vcxproj: &mut VcxprojFile, item_type: &str, matcher: &Regex, properties: &[(String, String)], config: Option<&str>) -> Result<Vec<String>> {
+    let mut modified_files = Vec::new();
+    let conditions = match config {
+        Some(selector) => {
+            let targets: Vec<String> = vcxproj.get_configurations().into_iter().filter(|target| vcxproj::config_matches(target, selector)).collect();
+            if targets.is_empty() {
+                return Err(anyhow::anyhow!("No configuration of {} matches '{}'", vcxproj.path.display(), selector));
+            }
+            targets.iter().map(|target| Some(config_condition(target))).collect()
+        }
+        None => {
+            modified_files = vcxproj.remove_item_metadata_where(|include, metadata| {
+                metadata.condition.is_some()
+                    && properties.iter().any(|(name, _)| *name == metadata.name)
+                    && matcher.is_match(&include.replace('\\', "/"))
+            })?;
+            vec![None]
+        }
+    };
+    for condition in &conditions {
+        for file in vcxproj.set_typed_item_metadata(item_type, matcher, properties, condition.as_deref())? {
+            if !modified_files.contains(&file) {
+                modified_files.push(file);
+            }
+        }
+    }
+    Ok(modified_files)
+}
+
+/// Sets the entry point, type, model or object file of the FXCompile items whose path matches `glob`
+pub fn set_shader_properties(project_path: PathBuf, glob: String, settings: &[(&str, Option<String>)], config: Option<String>, dryrun: bool) -> Result<bool> {
+    let properties: Vec<(String, String)> = settings
+        .iter()
+        .filter_map(|(name, value)| Some((name.to_string(), value.clone()?)))
+        .collect();
+    if properties.is_empty() {
+        return Err(anyhow::anyhow!("Specify at least one of --entry-point, --type, --model and --object-file"));
+    }
+    info!("Analyzing project: {}", project_path.display());
+    
+    let matcher = glob_to_regex(&glob)?;
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let matching = vcxproj
+        .get_items()?
+        .iter()
+        .any(|item| item.item_type == "FXCompile" && matcher.is_match(&item.include.replace('\\', "/")));
+    if !matching {
+        return Err(anyhow::anyhow!("No shaders (FXCompile items) match '{}'", glob));
+    }
+    
+    let modified_files = set_metadata_per_config(&mut vcxproj, "FXCompile", &matcher, &properties, config.as_deref())?;
+    if modified_files.is_empty() {
+        info!("✅ No changes - the shaders already have these settings");
+        return Ok(false);
+    }
+    
+    info!("\n🎨 Shader settings{}:", config.as_ref().map(|c| tr!(" for {}", c)).unwrap_or_default());
+    for (name, value) in &properties {
+        info!("  - {} = {}", name, value);
+    }
+    info!("\n📁 Files affected:");
+    for file in &modified_files {
+        info!("  - {}", file);
+    }
+    output::report(Change::FileModified, &modified_files);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ Updated {} shaders in {}", modified_files.len(), project_path.display());
+    Ok(true)
+}
@@ -0,0 +1,68 @@
+use anyhow::Result;
+use crate::doctor;
+use crate::i18n::info;
+use crate::output::{self, Change};
+use crate::vcxproj::VcxprojFile;
+use std::path::PathBuf;
+
+/// Runs the `doctor` checks and lists the problems grouped by check, with the correction for each
+/// that has an obvious one; `fix` applies those corrections
+pub fn diagnose_project(project_path: PathBuf, fix: bool, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let problems = doctor::diagnose(&vcxproj)?;
+    if problems.is_empty() {
+        info!("✅ No problems found");
+        return Ok(false);
+    }
+    
+    let mut checks: Vec<&str> = Vec::new();
+    for problem in &problems {
+        if !checks.contains(&problem.check) {
+            checks.push(problem.check);
+        }
+    }
+    for check in checks {
+        info!("\n🩺 {} ({}):", doctor::describe(check), check);
+        for problem in problems.iter().filter(|problem| problem.check == check) {
+            let scope = problem.config.as_deref().unwrap_or("all configurations");
+            match &problem.fix {
+                Some(correction) => info!("  - {}: {} → {}", scope, problem.detail, correction.value),
+                None => info!("  - {}: {}", scope, problem.detail),
+            }
+        }
+    }
+    let fixable: Vec<&doctor::Problem> = problems.iter().filter(|problem| problem.fix.is_some()).collect();
+    
+    if fixable.is_empty() {
+        info!("\nFound {} problems, none with an automatic fix", problems.len());
+        return Ok(false);
+    }
+    if !fix {
+        info!("\nFound {} problems, {} with a suggested fix (run with --fix to apply them)", problems.len(), fixable.len());
+        return Ok(!fixable.is_empty());
+    }
+    
+    let mut fixed_configs: Vec<String> = Vec::new();
+    for problem in &fixable {
+        let (Some(correction), Some(config)) = (&problem.fix, &problem.config) else {
+            continue;
+        };
+        if correction.apply(&mut vcxproj, config)? && !fixed_configs.contains(config) {
+            fixed_configs.push(config.clone());
+        }
+    }
+    output::report(Change::ConfigModified, &fixed_configs);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        info!("✨ Dry run completed - {} problems would be fixed", fixable.len());
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    info!("\n✅ Fixed {} problems in {}", fixable.len(), project_path.display());
+    Ok(true)
+}
@@ -0,0 +1,91 @@
+use anyhow::Result;
+use crate::export;
+use crate::i18n::info;
+use crate::sln::SolutionFile;
+use crate::vcxproj::VcxprojFile;
+use std::path::{Path, PathBuf};
+
+/// Runs an exporter from the registry over a project or a solution's projects, or lists the exporters
+#[allow(clippy::too_many_arguments)]
+pub fn export_projects(
+    format: Option<String>,
+    list: bool,
+    project: Option<PathBuf>,
+    solution: Option<PathBuf>,
+    output: Option<PathBuf>,
+    config: Option<String>,
+    tasks: bool,
+    dryrun: bool,
+) -> Result<bool> {
+    let registry = export::Registry::builtin();
+    if list {
+        info!("Available export formats:");
+        for exporter in registry.exporters() {
+            let labels = exporter.capabilities().labels();
+            let labels = if labels.is_empty() { String::new() } else { format!(" [{}]", labels.join(", ")) };
+            info!("  {:<12} {}{}", exporter.name(), exporter.description(), labels);
+        }
+        return Ok(false);
+    }
+    
+    let format = format.unwrap_or_default();
+    let exporter = registry.find(&format).ok_or_else(|| {
+        let names: Vec<&str> = registry.exporters().map(|e| e.name()).collect();
+        anyhow::anyhow!("Unknown export format '{}' (available: {})", format, names.join(", "))
+    })?;
+    let capabilities = exporter.capabilities();
+    let (project_paths, base_dir) = match (project, &solution) {
+        (Some(project), None) => {
+            let dir = project.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+            (vec![project], dir)
+        }
+        (None, Some(solution)) => {
+            if !capabilities.multi_project {
+                return Err(anyhow::anyhow!("The {} exporter handles one project at a time; pass --project", exporter.name()));
+            }
+            let dir = solution.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+            (SolutionFile::load(solution)?.vcxproj_paths(), dir)
+        }
+        _ => return Err(anyhow::anyhow!("Pass either --project or --solution")),
+    };
+    if config.is_some() && !capabilities.per_config {
+        return Err(anyhow::anyhow!("The {} exporter covers every configuration; drop --config", exporter.name()));
+    }
+    if tasks && !capabilities.tasks {
+        return Err(anyhow::anyhow!("The {} exporter writes no build tasks; drop --tasks", exporter.name()));
+    }
+    
+    let mut projects = Vec::new();
+    for path in &project_paths {
+        projects.push(VcxprojFile::load(path)?);
+    }
+    let name = solution.as_ref().unwrap_or(&project_paths[0]).file_stem().unwrap_or_default().to_string_lossy().into_owned();
+    let input = export::ExportInput {
+        name,
+        projects,
+        config,
+        output_dir: output.unwrap_or(base_dir),
+        tasks,
+    };
+    info!("Exporting {} projects as {}", input.projects.len(), exporter.name());
+    let files = exporter.export(&input)?;
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        for file in &files {
+            info!("Would write: {}", input.output_dir.join(&file.path).display());
+        }
+        return Ok(!files.is_empty());
+    }
+    
+    let written = export::write_files(&input.output_dir, &files)?;
+    for path in &written {
+        info!("Wrote {}", path.display());
+    }
+    if written.is_empty() {
+        info!("✅ No changes - the exported files are up to date");
+        return Ok(false);
+    }
+    info!("\n✅ Exported {} files", written.len());
+    Ok(true)
+}
@@ -0,0 +1,213 @@
+use anyhow::Result;
+use crate::i18n::{info, tr};
+use crate::output::{self, Change};
+use crate::vcxproj::VcxprojFile;
+use std::path::PathBuf;
+use super::{config_condition, glob_to_regex, wildcard_covered_files};
+
+pub fn file_properties_from_args(
+    warning_level: Option<u8>,
+    optimization: Option<String>,
+    no_analyze: bool,
+    props: Vec<String>,
+) -> Result<Vec<(String, String)>> {
+    let mut properties = Vec::new();
+    
+    if let Some(level) = warning_level {
+        let value = if level == 0 {
+            "TurnOffAllWarnings".to_string()
+        } else {
+            format!("Level{}", level)
+        };
+        properties.push(("WarningLevel".to_string(), value));
+    }
+    if let Some(optimization) = optimization {
+        properties.push(("Optimization".to_string(), optimization));
+    }
+    if no_analyze {
+        properties.push(("EnablePREfast".to_string(), "false".to_string()));
+    }
+    for prop in props {
+        let (name, value) = prop
+            .split_once('=')
+            .ok_or_else(|| anyhow::anyhow!("Invalid --prop '{}', expected NAME=VALUE", prop))?;
+        properties.push((name.trim().to_string(), value.trim().to_string()));
+    }
+    
+    if properties.is_empty() {
+        return Err(anyhow::anyhow!("No properties specified (use --warning-level, --optimization, --no-analyze or --prop)"));
+    }
+    
+    Ok(properties)
+}
+
+pub fn set_file_properties(
+    project_path: PathBuf,
+    glob: String,
+    config: Option<String>,
+    properties: Vec<(String, String)>,
+    dryrun: bool,
+) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    let matcher = glob_to_regex(&glob)?;
+    let condition = config.as_deref().map(config_condition);
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    
+    // Files that only exist through a wildcard item need an Update item to carry the metadata
+    let covered_files = wildcard_covered_files(&project_path, &vcxproj, &matcher)?;
+    if !covered_files.is_empty() {
+        let added = vcxproj.add_update_items(&covered_files)?;
+        if !added.is_empty() {
+            info!("Adding Update items for {} files covered by wildcards", added.len());
+        }
+    }
+    
+    let modified_files = vcxproj.set_item_metadata(&matcher, &properties, condition.as_deref())?;
+    
+    if modified_files.is_empty() {
+        info!("No files matching '{}' needed changes", glob);
+        return Ok(false);
+    }
+    
+    info!("\n📄 Properties to set{}:", config.as_ref().map(|c| tr!(" for {}", c)).unwrap_or_default());
+    for (name, value) in &properties {
+        info!("  - {} = {}", name, value);
+    }
+    info!("\n📁 Files affected:");
+    for file in &modified_files {
+        info!("  - {}", file);
+    }
+    output::report(Change::FileModified, &modified_files);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        info!("✨ Dry run completed - {} files would be updated", modified_files.len());
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    info!("\n✅ Successfully updated {} files in {}", modified_files.len(), project_path.display());
+    Ok(true)
+}
+
+pub fn clear_file_properties(
+    project_path: PathBuf,
+    glob: String,
+    props: Vec<String>,
+    config: Option<String>,
+    dryrun: bool,
+) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+
+    let matcher = glob_to_regex(&glob)?;
+    let condition = config.as_deref().map(config_condition);
+
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let modified_files = vcxproj.clear_item_metadata(&matcher, &props, condition.as_deref())?;
+
+    if modified_files.is_empty() {
+        info!("No files matching '{}' have {} set", glob, props.join(", "));
+        return Ok(false);
+    }
+
+    info!("\n📄 Properties to remove{}:", config.as_ref().map(|c| tr!(" for {}", c)).unwrap_or_default());
+    for prop in &props {
+        info!("  - {}", prop);
+    }
+    info!("\n📁 Files affected:");
+    for file in &modified_files {
+        info!("  - {}", file);
+    }
+    output::report(Change::FileModified, &modified_files);
+
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        info!("✨ Dry run completed - {} files would be updated", modified_files.len());
+        return Ok(true);
+    }
+
+    vcxproj.save()?;
+    info!("\n✅ Successfully cleared properties from {} files in {}", modified_files.len(), project_path.display());
+    Ok(true)
+}
+
+pub fn lint_redundant_file_settings(project_path: PathBuf, fix: bool, dryrun: bool) -> Result<bool> {
+    use std::collections::HashSet;
+
+    info!("Analyzing project: {}", project_path.display());
+
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let settings = vcxproj.get_tool_settings();
+    let conditions: Vec<String> = vcxproj
+        .get_configurations()
+        .iter()
+        .map(|c| config_condition(c))
+        .collect();
+
+    // The value a configuration inherits for a tool setting: its own group first, then any unconditioned group
+    let effective_value = |tool: &str, name: &str, condition: &str| -> Option<&str> {
+        let lookup = |cond: Option<&str>| {
+            settings
+                .iter()
+                .find(|s| s.tool == tool && s.name == name && s.condition.as_deref() == cond)
+                .map(|s| s.value.as_str())
+        };
+        lookup(Some(condition)).or_else(|| lookup(None))
+    };
+
+    let mut redundant = Vec::new();
+    for item in vcxproj.get_items()? {
+        for metadata in &item.metadata {
+            let is_redundant = match &metadata.condition {
+                Some(condition) => effective_value(&item.item_type, &metadata.name, condition) == Some(metadata.value.as_str()),
+                None => !conditions.is_empty() && conditions.iter().all(|c| {
+                    effective_value(&item.item_type, &metadata.name, c) == Some(metadata.value.as_str())
+                }),
+            };
+
+            if is_redundant {
+                redundant.push((item.include.clone(), metadata.clone()));
+            }
+        }
+    }
+
+    if redundant.is_empty() {
+        info!("✅ No redundant per-file settings found");
+        return Ok(false);
+    }
+
+    info!("\n🔎 Per-file settings identical to the configuration-level value:");
+    for (include, metadata) in &redundant {
+        let scope = metadata.condition.as_deref().unwrap_or("all configurations");
+        info!("  - {}: {} = {} ({})", include, metadata.name, metadata.value, scope);
+    }
+
+    if !fix {
+        info!("\nFound {} redundant settings (run with --fix to remove them)", redundant.len());
+        return Ok(true);
+    }
+
+    let to_remove: HashSet<(String, String, Option<String>)> = redundant
+        .iter()
+        .map(|(include, m)| (include.clone(), m.name.clone(), m.condition.clone()))
+        .collect();
+    vcxproj.remove_item_metadata_where(|include, m| {
+        to_remove.contains(&(include.to_string(), m.name.clone(), m.condition.clone()))
+    })?;
+    output::report(Change::FileModified, redundant.iter().map(|(include, _)| include));
+
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        info!("✨ Dry run completed - {} settings would be removed", redundant.len());
+        return Ok(true);
+    }
+
+    vcxproj.save()?;
+    info!("\n✅ Removed {} redundant settings from {}", redundant.len(), project_path.display());
+    Ok(true)
+}
@@ -0,0 +1,755 @@
+use anyhow::{Context, Result};
+use crate::i18n::{info, tr, warn};
+use crate::layout::FilterMap;
+use crate::output::{self, Change};
+use crate::pipe;
+use crate::properties;
+use crate::vcxproj::{self, FilterFile, NON_FILE_ITEM_TYPES, VcxprojFile};
+use log::{debug, trace};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use super::{check_can_prompt, expand_wildcard_item, glob_to_regex, scan_paths};
+use walkdir::WalkDir;
+
+#[allow(clippy::too_many_arguments)]
+pub fn add_files_to_project(
+    extension: String,
+    project_path: PathBuf,
+    directory: Option<PathBuf>,
+    recursive: bool,
+    regex_pattern: Option<String>,
+    negate: bool,
+    excluded_dirs: Vec<String>,
+    respect_gitignore: bool,
+    maps: &[String],
+    dryrun: bool,
+) -> Result<bool> {
+    // Determine the directory to scan
+    let scan_dir = directory.unwrap_or_else(|| {
+        project_path
+            .parent()
+            .unwrap_or_else(|| std::path::Path::new("."))
+            .to_path_buf()
+    });
+
+    info!("Scanning directory: {}", scan_dir.display());
+    if !excluded_dirs.is_empty() {
+        debug!("Skipping directories: {}", excluded_dirs.join(", "));
+    }
+    if respect_gitignore {
+        debug!("Skipping files ignored by .gitignore");
+    }
+    
+    match (&regex_pattern, negate) {
+        (Some(ref pattern), true) => info!("Looking for *.{} files in paths NOT matching regex: {}", extension, pattern),
+        (Some(ref pattern), false) => info!("Looking for *.{} files in paths matching regex: {}", extension, pattern),
+        (None, true) => info!("Looking for *.{} files (negation has no effect without regex)", extension),
+        (None, false) => info!("Looking for *.{} files", extension),
+    }
+
+    // Compile regex pattern if provided
+    let compiled_regex = if let Some(ref pattern) = regex_pattern {
+        Some(Regex::new(pattern).context("Invalid regex pattern")?)
+    } else {
+        None
+    };
+
+    // Find all files with the specified extension, filtered by path regex if provided
+    let mut files_to_add = Vec::new();
+    let mut scan_relative_paths = Vec::new(); // For filter creation
+    
+    // Excluded and ignored directories are pruned from the walk, so nothing beneath them reaches the regex filter
+    for path in scan_paths(&scan_dir, recursive, &excluded_dirs, respect_gitignore)? {
+        let path = path.as_path();
+        
+        if path.is_file() {
+            // First check if file has the correct extension
+            let has_extension = if let Some(ext) = path.extension() {
+                ext.to_string_lossy().eq_ignore_ascii_case(&extension)
+            } else {
+                false
+            };
+            
+            if !has_extension {
+                trace!("Skipping {} (extension)", path.display());
+                continue;
+            }
+            
+            // Then check if path matches regex (if provided) with negation support
+            let path_matches = if let Some(ref regex) = compiled_regex {
+                // Get the relative path from scan_dir to apply regex against
+                let relative_to_scan = path.strip_prefix(&scan_dir).unwrap_or(path);
+                let path_str = relative_to_scan.to_string_lossy();
+                let regex_matches = regex.is_match(&path_str);
+                
+                if negate {
+                    !regex_matches // Include files that DON'T match the regex
+                } else {
+                    regex_matches // Include files that DO match the regex
+                }
+            } else {
+                true // No regex means all paths match (negation has no effect)
+            };
+            
+            if path_matches {
+                // Calculate path relative to project directory for Visual Studio to find the file
+                let project_relative_path = if let Some(project_dir) = project_path.parent() {
+                    match path.strip_prefix(project_dir) {
+                        Ok(rel) => rel.to_path_buf(),
+                        Err(_) => path.to_path_buf(), // Fallback to absolute path if strip_prefix fails
+                    }
+                } else {
+                    path.to_path_buf()
+                };
+                
+                // Calculate path relative to scan directory for filter hierarchy
+                let scan_relative_path = match path.strip_prefix(&scan_dir) {
+                    Ok(rel) => rel.to_path_buf(),
+                    Err(_) => path.to_path_buf(),
+                };
+                
+                files_to_add.push(project_relative_path);
+                scan_relative_paths.push(scan_relative_path);
+            } else {
+                trace!("Skipping {} (regex)", path.display());
+            }
+        }
+    }
+
+    if files_to_add.is_empty() {
+        if let Some(ref pattern) = regex_pattern {
+            info!("No *.{} files found in paths matching regex '{}' in {}", extension, pattern, scan_dir.display());
+        } else {
+            info!("No *.{} files found in {}", extension, scan_dir.display());
+        }
+        return Ok(false);
+    }
+    register_files(&project_path, files_to_add, scan_relative_paths, maps, dryrun)
+}
+
+/// Adds found files to the project and its filters file (creating it if needed), with filters
+/// assigned by the `--map` and config rules, or else following `scan_relative_paths`. Files already
+/// in the project are skipped.
+fn register_files(project_path: &Path, files_to_add: Vec<PathBuf>, scan_relative_paths: Vec<PathBuf>, maps: &[String], dryrun: bool) -> Result<bool> {
+    let project_dir = project_path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let map = FilterMap::load(project_dir, maps)?;
+    // Files already in the project are skipped, so running the same add again changes nothing
+    let found = files_to_add.len();
+    let listed: std::collections::HashSet<String> = VcxprojFile::load(project_path)?
+        .get_items()?
+        .into_iter()
+        .map(|item| vcxproj::include_key(&item.include))
+        .collect();
+    let (files_to_add, scan_relative_paths): (Vec<PathBuf>, Vec<PathBuf>) = files_to_add
+        .into_iter()
+        .zip(scan_relative_paths)
+        .filter(|(file, _)| !listed.contains(&vcxproj::include_key(&file.to_string_lossy())))
+        .unzip();
+    if found > files_to_add.len() {
+        info!("Skipping {} files already in the project", found - files_to_add.len());
+    }
+    if files_to_add.is_empty() {
+        info!("✅ No changes - every matching file is already in {}", project_path.display());
+        return Ok(false);
+    }
+
+    info!("Found {} files to add:", files_to_add.len());
+    for file in &files_to_add {
+        info!("  - {}", file.display());
+    }
+    output::report(Change::FileAdded, files_to_add.iter().map(|f| vcxproj::include_path(&f.to_string_lossy())));
+
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        
+        let filter_path = vcxproj::filters_path(project_path);
+        if pipe::exists(&filter_path) {
+            info!("Would update filter file: {}", filter_path.display());
+        } else {
+            info!("Would create filter file: {}", filter_path.display());
+        }
+        
+        info!("✨ Dry run completed - {} files would be added", files_to_add.len());
+        return Ok(true);
+    }
+
+    // Update the .vcxproj file and its .vcxproj.filters file (created if missing), written together
+    info!("\nUpdating project file: {}", project_path.display());
+    let mut vcxproj = VcxprojFile::load(project_path)?;
+    vcxproj.add_source_files(&files_to_add)?;
+    let filter_path = vcxproj::filters_path(project_path);
+    let filter_exists = pipe::exists(&filter_path);
+    let filter_file = if filter_exists {
+        info!("Updating filter file: {}", filter_path.display());
+        let mut filter_file = FilterFile::load(&filter_path)?;
+        filter_file.add_source_files_with_hierarchy(&files_to_add, &scan_relative_paths, &map)?;
+        filter_file
+    } else {
+        info!("Filter file not found: {}", filter_path.display());
+        info!("Creating basic filter file...");
+        let content = create_basic_filter_file_with_hierarchy(project_dir, &files_to_add, &scan_relative_paths, &map)?;
+        FilterFile { path: filter_path.clone(), content }
+    };
+    
+    let (project_written, filters_written) = vcxproj.save_with_filters(Some(&filter_file))?;
+    if project_written {
+        info!("Successfully updated {}", project_path.display());
+    } else {
+        info!("No changes to {}", project_path.display());
+    }
+    match (filter_exists, filters_written) {
+        (false, _) => info!("Created {}", filter_path.display()),
+        (true, true) => info!("Successfully updated {}", filter_path.display()),
+        (true, false) => info!("No changes to {}", filter_path.display()),
+    }
+
+    info!("\n✅ Project files updated successfully!");
+    Ok(true)
+}
+
+pub fn create_basic_filter_file_with_hierarchy(project_dir: &Path, project_files: &[PathBuf], scan_relative_files: &[PathBuf], map: &FilterMap) -> Result<String> {
+    let mut content = String::new();
+    content.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    content.push_str("<Project ToolsVersion=\"4.0\" xmlns=\"http://schemas.microsoft.com/developer/msbuild/2003\">\n");
+    
+    // The filters the files go into, sorted so the file is the same on every run
+    let (filter_names, dirs) = vcxproj::assign_filters(project_files, scan_relative_files, map);
+    
+    // Add filters
+    if !dirs.is_empty() {
+        content.push_str("  <ItemGroup>\n");
+        for dir in &dirs {
+            content.push_str(&format!(
+                "    <Filter Include=\"{}\">\n      <UniqueIdentifier>{}</UniqueIdentifier>\n    </Filter>\n",
+                dir, vcxproj::filter_identifier(dir)
+            ));
+        }
+        content.push_str("  </ItemGroup>\n");
+    }
+    
+    // Add files with correct Include paths and filter assignments
+    content.push_str("  <ItemGroup>\n");
+    for (project_file, filter_name) in project_files.iter().zip(&filter_names) {
+        let include_path = vcxproj::include_path(&project_file.to_string_lossy());
+        let item_type = vcxproj::item_type_in(project_dir, &include_path);
+        
+        content.push_str(&format!("    <{} Include=\"{}\">\n", item_type, include_path));
+        content.push_str(&format!("      <Filter>{}</Filter>\n", filter_name));
+        content.push_str(&format!("    </{}>\n", item_type));
+    }
+    content.push_str("  </ItemGroup>\n");
+    
+    content.push_str("</Project>");
+    Ok(content)
+}
+
+
+#[allow(clippy::too_many_arguments)]
+pub fn delete_from_project(
+    project_path: PathBuf,
+    target: Option<String>,
+    extension: Option<String>,
+    yes: bool,
+    interactive: bool,
+    regex_pattern: Option<String>,
+    negate: bool,
+    dryrun: bool,
+) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    // Validate arguments
+    if target.is_none() && extension.is_none() {
+        return Err(anyhow::anyhow!("Either --target or --extension must be specified"));
+    }
+    
+    let target_str = target.as_deref().unwrap_or("");
+    let target_display = if let Some(ref ext) = extension {
+        tr!("all *.{} files", ext)
+    } else {
+        target_str.to_string()
+    };
+    
+    // Load the project file
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    
+    // Compile regex pattern if provided
+    let compiled_regex = if let Some(ref pattern) = regex_pattern {
+        Some(Regex::new(pattern).context("Invalid regex pattern")?)
+    } else {
+        None
+    };
+
+    // A target naming a filter covers exactly the files in that filter and its child filters
+    let filter_path = vcxproj::filters_path(&project_path);
+    let filter_members = if extension.is_none() && pipe::exists(&filter_path) {
+        FilterFile::load(&filter_path)?.files_in_filter_tree(target_str)?
+    } else {
+        None
+    };
+    let in_scope = |file: &str| filter_members.as_ref().is_none_or(|members| members.contains(file));
+    // The project file has no filter information, so every item is matched and narrowed by membership
+    let project_target = if filter_members.is_some() { "" } else { target_str };
+    
+    // Preview what will be deleted
+    let original_content = vcxproj.content.clone();
+    let deleted_files = vcxproj.delete_files(project_target, extension.as_deref(), compiled_regex.as_ref(), negate, in_scope)?;
+    vcxproj.content = original_content; // Restore for confirmation
+    
+    if deleted_files.is_empty() {
+        match (&regex_pattern, negate) {
+            (Some(ref pattern), true) => info!("No files found matching: {} with regex filter NOT matching: {}", target_display, pattern),
+            (Some(ref pattern), false) => info!("No files found matching: {} with regex filter: {}", target_display, pattern),
+            (None, _) => info!("No files found matching: {}", target_display),
+        }
+        return Ok(false);
+    }
+    
+    // Show what will be deleted
+    info!("\n📁 Files to be removed from project:");
+    for file in &deleted_files {
+        info!("  - {}", file);
+    }
+    
+    // Check filter file as well
+    let mut preview_filters = Vec::new();
+    if pipe::exists(&filter_path) {
+        let mut filter_file = FilterFile::load(&filter_path)?;
+        let original_filter_content = filter_file.content.clone();
+        let (_, deleted_filters) = filter_file.delete_files_and_filters(
+            target_str,
+            extension.as_deref(),
+            compiled_regex.as_ref(),
+            negate,
+            in_scope,
+        )?;
+        preview_filters = deleted_filters;
+        filter_file.content = original_filter_content; // Restore for confirmation
+    }
+    
+    if dryrun {
+        output::report(Change::FileRemoved, &deleted_files);
+        output::report(Change::FilterRemoved, &preview_filters);
+    }
+    
+    if !preview_filters.is_empty() {
+        info!("\n📁 Filters to be removed:");
+        for filter in &preview_filters {
+            info!("  - {}", filter);
+        }
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would remove {} files from project file: {}", deleted_files.len(), project_path.display());
+        
+        if pipe::exists(&filter_path) {
+            if !preview_filters.is_empty() {
+                info!("Would remove {} filters from filter file: {}", preview_filters.len(), filter_path.display());
+            }
+            info!("Would update filter file: {}", filter_path.display());
+        }
+        
+        info!("✨ Dry run completed - {} files would be removed", deleted_files.len());
+        return Ok(true);
+    }
+    
+    // Let the user choose the files to remove, or confirm deletion
+    let mut selected_files: Option<std::collections::HashSet<String>> = None;
+    if interactive || !yes {
+        check_can_prompt()?;
+    }
+    if interactive {
+        let Some(picked) = pick_items(&deleted_files)? else {
+            info!("Operation cancelled.");
+            return Ok(false);
+        };
+        selected_files = Some(picked.into_iter().collect());
+    } else if !yes {
+        print!("{}", tr!("\nRemove {} items from project? [y/N]: ", deleted_files.len()));
+        use std::io::{self, Write};
+        io::stdout().flush()?;
+        
+        let mut input = String::new();
+        io::stdin().read_line(&mut input)?;
+        let input = input.trim().to_lowercase();
+        
+        if input != "y" && input != "yes" {
+            info!("Operation cancelled.");
+            return Ok(false);
+        }
+    }
+    
+    // Perform the deletion
+    info!("\nUpdating project file: {}", project_path.display());
+    let is_selected = |file: &str| in_scope(file) && selected_files.as_ref().is_none_or(|selected| selected.contains(file));
+    let removed_files = vcxproj.delete_files(project_target, extension.as_deref(), compiled_regex.as_ref(), negate, is_selected)?;
+    
+    // Update filter file if it exists, saved together with the project
+    let mut filter_file = None;
+    if pipe::exists(&filter_path) {
+        info!("Updating filter file: {}", filter_path.display());
+        let mut filters = FilterFile::load(&filter_path)?;
+        let (_, removed_filters) = filters.delete_files_and_filters(target_str, extension.as_deref(), compiled_regex.as_ref(), negate, is_selected)?;
+        output::report(Change::FilterRemoved, removed_filters);
+        filter_file = Some(filters);
+    }
+    vcxproj.save_with_filters(filter_file.as_ref())?;
+    output::report(Change::FileRemoved, &removed_files);
+    info!("Successfully updated {}", project_path.display());
+    if filter_file.is_some() {
+        info!("Successfully updated {}", filter_path.display());
+    }
+    
+    info!("\n🗑️  Successfully removed {} files from project!\n", removed_files.len());
+    Ok(true)
+}
+
+/// Lists `items` with numbers and lets the user toggle them until the selection is confirmed.
+/// Returns `None` when the user cancels.
+fn pick_items(items: &[String]) -> Result<Option<Vec<String>>> {
+    use std::io::{self, Write};
+    
+    let mut selected = vec![true; items.len()];
+    loop {
+        println!();
+        for (index, item) in items.iter().enumerate() {
+            let mark = if selected[index] { "x" } else { " " };
+            println!("  [{}] {:>3}. {}", mark, index + 1, item);
+        }
+        let count = selected.iter().filter(|s| **s).count();
+        print!(
+            "{}",
+            tr!("\nToggle items (e.g. 1,3,5-9), 'a' all, 'n' none, Enter to remove {} items, 'q' to cancel: ", count)
+        );
+        io::stdout().flush()?;
+        
+        let mut input = String::new();
+        if io::stdin().read_line(&mut input)? == 0 {
+            return Ok(None);
+        }
+        match input.trim().to_lowercase().as_str() {
+            "" => {
+                if count == 0 {
+                    return Ok(None);
+                }
+                let picked = items
+                    .iter()
+                    .zip(&selected)
+                    .filter(|(_, s)| **s)
+                    .map(|(item, _)| item.clone())
+                    .collect();
+                return Ok(Some(picked));
+            }
+            "q" | "quit" => return Ok(None),
+            "a" | "all" => selected.iter_mut().for_each(|s| *s = true),
+            "n" | "none" => selected.iter_mut().for_each(|s| *s = false),
+            selection => match parse_selection(selection, items.len()) {
+                Ok(indices) => indices.into_iter().for_each(|i| selected[i] = !selected[i]),
+                Err(e) => println!("⚠️  {}", e),
+            },
+        }
+    }
+}
+
+/// Parses a selection like "1,3,5-9" into zero-based indices below `count`
+fn parse_selection(selection: &str, count: usize) -> Result<Vec<usize>> {
+    let mut indices = Vec::new();
+    for part in selection.split(',').map(str::trim).filter(|p| !p.is_empty()) {
+        let (start, end) = match part.split_once('-') {
+            Some((start, end)) => (start.trim(), end.trim()),
+            None => (part, part),
+        };
+        let start: usize = start.parse().map_err(|_| anyhow::anyhow!("Invalid selection: {}", part))?;
+        let end: usize = end.parse().map_err(|_| anyhow::anyhow!("Invalid selection: {}", part))?;
+        if start == 0 || end < start || end > count {
+            return Err(anyhow::anyhow!("Selection out of range (1-{}): {}", count, part));
+        }
+        indices.extend(start - 1..end);
+    }
+    Ok(indices)
+}
+
+pub fn enumerate_project_files(project_path: PathBuf, to_wildcards: bool, dryrun: bool) -> Result<bool> {
+    use std::collections::{BTreeMap, HashMap, HashSet};
+    
+    info!("Analyzing project: {}", project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let project_dir = project_path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let normalize = vcxproj::include_key;
+    let before = evaluated_files(project_dir, &vcxproj)?;
+    
+    let changed = if to_wildcards {
+        // Group explicit, metadata-free items by type, directory, and extension
+        let mut groups: BTreeMap<(String, String, String), Vec<String>> = BTreeMap::new();
+        for item in vcxproj.get_items()? {
+            if item.include.contains('*') || item.include.contains("$(") || !item.metadata.is_empty() {
+                continue;
+            }
+            let normalized = normalize(&item.include);
+            let dir = normalized.rsplit_once('\\').map(|(dir, _)| dir.to_string()).unwrap_or_default();
+            let Some(ext) = Path::new(&normalized).extension().map(|e| e.to_string_lossy().to_string()) else {
+                continue;
+            };
+            groups.entry((item.item_type, dir, ext)).or_default().push(item.include);
+        }
+        
+        // Files on disk by directory and extension, to check that a directory is fully covered
+        let mut on_disk: HashMap<(String, String), HashSet<String>> = HashMap::new();
+        for entry in WalkDir::new(project_dir).into_iter().filter_map(|e| e.ok()) {
+            let Ok(relative) = entry.path().strip_prefix(project_dir) else {
+                continue;
+            };
+            if !entry.file_type().is_file() {
+                continue;
+            }
+            let normalized = normalize(&relative.to_string_lossy());
+            let dir = normalized.rsplit_once('\\').map(|(dir, _)| dir.to_string()).unwrap_or_default();
+            let ext = Path::new(&normalized).extension().map(|e| e.to_string_lossy().to_string()).unwrap_or_default();
+            on_disk.entry((dir, ext)).or_default().insert(normalized);
+        }
+        
+        let mut patterns = HashMap::new();
+        for ((item_type, dir, ext), includes) in groups {
+            let listed: HashSet<String> = includes.iter().map(|i| normalize(i)).collect();
+            if listed.len() < 2 || on_disk.get(&(dir.clone(), ext)) != Some(&listed) {
+                continue;
+            }
+            
+            // Keep the directory and extension spelling of the existing items
+            let first = includes[0].replace('/', "\\");
+            let (dir, name) = first.rsplit_once('\\').map(|(d, n)| (Some(d), n)).unwrap_or((None, first.as_str()));
+            let ext = name.rsplit_once('.').map(|(_, e)| e).unwrap_or_default();
+            let pattern = match dir {
+                Some(dir) => format!("{}\\*.{}", dir, ext),
+                None => format!("*.{}", ext),
+            };
+            
+            info!("  {} {} ← {} files", item_type, pattern, includes.len());
+            for include in includes {
+                patterns.insert((item_type.clone(), include), pattern.clone());
+            }
+        }
+        vcxproj.collapse_to_wildcards(&patterns)
+    } else {
+        let mut listed: HashSet<(String, String)> = vcxproj
+            .get_items()?
+            .into_iter()
+            .filter(|item| !item.include.contains('*'))
+            .map(|item| (item.item_type, normalize(&item.include)))
+            .collect();
+        
+        let mut expansions = HashMap::new();
+        for item in vcxproj.get_wildcard_items() {
+            // Files already listed explicitly (or by an earlier wildcard) are not duplicated
+            let files: Vec<String> = expand_wildcard_item(project_dir, &item)?
+                .into_iter()
+                .filter(|file| listed.insert((item.item_type.clone(), normalize(file))))
+                .collect();
+            info!("  {} {} → {} files", item.item_type, item.include, files.len());
+            expansions.insert((item.item_type, item.include), files);
+        }
+        vcxproj.expand_wildcard_items(&expansions)
+    };
+    
+    if changed == 0 {
+        if to_wildcards {
+            info!("No directories are fully listed explicitly; nothing to collapse");
+        } else {
+            info!("No wildcard items found; the project already lists files explicitly");
+        }
+        return Ok(false);
+    }
+    
+    // The rewritten project must resolve to exactly the same files
+    let after = evaluated_files(project_dir, &vcxproj)?;
+    if before != after {
+        return Err(anyhow::anyhow!(
+            "Validation failed: the rewritten project would change the file set ({} added, {} removed); nothing was written",
+            after.difference(&before).count(),
+            before.difference(&after).count()
+        ));
+    }
+    info!("\n✔ Validated: both listings resolve to the same {} files", after.len());
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    if to_wildcards {
+        info!("\n✅ Collapsed {} items into wildcards in {}", changed, project_path.display());
+    } else {
+        info!("\n✅ Expanded {} wildcard items in {}", changed, project_path.display());
+    }
+    Ok(true)
+}
+
+/// The (item type, lowercase path) pairs a project resolves to, with wildcards expanded
+fn evaluated_files(project_dir: &Path, vcxproj: &VcxprojFile) -> Result<std::collections::BTreeSet<(String, String)>> {
+    let mut files = std::collections::BTreeSet::new();
+    for item in vcxproj.get_items()? {
+        if !item.include.contains('*') {
+            files.insert((item.item_type, vcxproj::include_key(&item.include)));
+        }
+    }
+    for item in vcxproj.get_wildcard_items() {
+        for file in expand_wildcard_item(project_dir, &item)? {
+            files.insert((item.item_type.clone(), file.to_lowercase()));
+        }
+    }
+    Ok(files)
+}
+
+/// Removes the items whose files are gone from disk, then the filters file entries left without a
+/// project item (including those of the removed items). Wildcard items and paths using properties
+/// nothing defines are never removed, nor are filters file entries a wildcard still covers.
+pub fn prune_project(project_path: PathBuf, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    let properties = properties::Properties::for_project(&vcxproj, None);
+    
+    let mut missing: Vec<(String, String)> = Vec::new();
+    for item in vcxproj.get_items()? {
+        if NON_FILE_ITEM_TYPES.contains(&item.item_type.as_str()) || item.include.contains(['*', ';']) {
+            continue;
+        }
+        let gone = properties.resolve_path(project_dir, &item.include).is_ok_and(|path| !path.exists());
+        if gone && !missing.iter().any(|(_, include)| vcxproj::same_path(include, &item.include)) {
+            missing.push((item.item_type, item.include));
+        }
+    }
+    let missing_paths: Vec<String> = missing.iter().map(|(_, include)| include.clone()).collect();
+    vcxproj.remove_items(&missing_paths);
+    
+    let filter_path = vcxproj::filters_path(&project_path);
+    let mut filter_file = if pipe::exists(&filter_path) { Some(FilterFile::load(&filter_path)?) } else { None };
+    let mut orphans = Vec::new();
+    if let Some(filter_file) = &mut filter_file {
+        let stale = stale_filter_entries(&vcxproj, filter_file)?;
+        orphans = filter_file.remove_items(&stale);
+    }
+    
+    if missing.is_empty() && orphans.is_empty() {
+        info!("✨ Nothing to prune: every item exists on disk and the filters file matches the project");
+        return Ok(false);
+    }
+    
+    if !missing.is_empty() {
+        info!("\n🗑️  Items whose files no longer exist:");
+        for (item_type, include) in &missing {
+            info!("  - {} ({})", include, item_type);
+        }
+    }
+    if !orphans.is_empty() {
+        info!("\n📁 Filters file entries without a project item:");
+        for include in &orphans {
+            info!("  - {}", include);
+        }
+    }
+    let mut removed = missing_paths;
+    for include in &orphans {
+        if !removed.iter().any(|path| vcxproj::same_path(path, include)) {
+            removed.push(include.clone());
+        }
+    }
+    output::report(Change::FileRemoved, &removed);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("✨ Dry run completed - {} items and {} filters file entries would be removed", missing.len(), orphans.len());
+        return Ok(true);
+    }
+    
+    vcxproj.save_with_filters(filter_file.as_ref())?;
+    info!("\n✅ Pruned {} items and {} filters file entries from {}", missing.len(), orphans.len(), project_path.display());
+    Ok(true)
+}
+
+/// The filters file entries (other than filter definitions) with no matching project item.
+/// Entries a wildcard item of the project still covers are not stale.
+pub fn stale_filter_entries(vcxproj: &VcxprojFile, filter_file: &FilterFile) -> Result<Vec<String>> {
+    use std::collections::HashSet;
+    
+    let normalize = vcxproj::include_key;
+    let items: HashSet<String> = vcxproj.get_items()?.iter().map(|item| normalize(&item.include)).collect();
+    let wildcards = vcxproj
+        .get_wildcard_items()
+        .iter()
+        .flat_map(|item| item.patterns.clone())
+        .filter(|pattern| pattern.contains('*'))
+        .map(|pattern| glob_to_regex(&pattern))
+        .collect::<Result<Vec<_>>>()?;
+    Ok(vcxproj::parse_items(&filter_file.content)
+        .into_iter()
+        .filter(|entry| entry.item_type != "Filter" && !items.contains(&normalize(&entry.include)))
+        .filter(|entry| !wildcards.iter().any(|w| w.is_match(&entry.include.replace('\\', "/"))))
+        .map(|entry| entry.include)
+        .collect())
+}
+
+/// Adds the files named in a list file, e.g. one written by a code generator, without scanning
+/// any directory. Each line is a path or glob relative to the list's directory; blank lines and
+/// lines starting with "#" are skipped. Listed files that don't exist yet are added all the same.
+pub fn add_files_from_list(list_path: PathBuf, project_path: PathBuf, maps: &[String], dryrun: bool) -> Result<bool> {
+    let list = std::fs::read_to_string(&list_path)
+        .with_context(|| format!("Failed to read file list: {}", list_path.display()))?;
+    let base = list_path.parent().filter(|dir| !dir.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    info!("Reading file list: {}", list_path.display());
+    
+    let mut paths: Vec<PathBuf> = Vec::new();
+    for line in list.lines() {
+        let entry = line.trim().trim_matches('"');
+        if entry.is_empty() || entry.starts_with('#') {
+            continue;
+        }
+        let entry = entry.replace('\\', "/");
+        if !entry.contains(['*', '?']) {
+            let path = base.join(&entry);
+            if !path.exists() {
+                warn!("⚠️  {} does not exist yet; adding it anyway", entry);
+            }
+            if !paths.contains(&path) {
+                paths.push(path);
+            }
+            continue;
+        }
+        
+        // A glob is matched below the directories before its first wildcard
+        let literal: Vec<&str> = entry.split('/').take_while(|part| !part.contains(['*', '?'])).collect();
+        let root = base.join(literal.join("/"));
+        let matcher = glob_to_regex(&base.join(&entry).to_string_lossy())?;
+        let mut matched = 0;
+        for found in WalkDir::new(&root).sort_by_file_name() {
+            let Ok(found) = found else { continue };
+            let path = found.into_path();
+            if path.is_file() && matcher.is_match(&path.to_string_lossy().replace('\\', "/")) {
+                matched += 1;
+                if !paths.contains(&path) {
+                    paths.push(path);
+                }
+            }
+        }
+        if matched == 0 {
+            warn!("⚠️  {} matches no files", entry);
+        }
+    }
+    if paths.is_empty() {
+        info!("No files listed in {}", list_path.display());
+        return Ok(false);
+    }
+    
+    // Filters follow the directories below the list's, as they follow the scanned ones for a scan
+    let files_to_add: Vec<PathBuf> = paths.iter().map(|path| PathBuf::from(vcxproj::relative_path(project_dir, path))).collect();
+    let scan_relative_paths: Vec<PathBuf> = paths
+        .iter()
+        .map(|path| path.strip_prefix(base).map(Path::to_path_buf).unwrap_or_else(|_| PathBuf::from(path.file_name().unwrap_or_default())))
+        .collect();
+    register_files(&project_path, files_to_add, scan_relative_paths, maps, dryrun)
+}
@@ -0,0 +1,592 @@
+use anyhow::Result;
+use crate::i18n::{info, tr, warn};
+use crate::layout::{FilterLayout, LayoutFilter, LayoutRule};
+use crate::output::{self, Change};
+use crate::pipe;
+use crate::vcxproj::{self, FilterFile, NON_FILE_ITEM_TYPES, VcxprojFile};
+use std::path::{Path, PathBuf};
+use super::{check_can_prompt, glob_to_regex};
+use super::files::stale_filter_entries;
+
+pub fn rename_filter_in_project(
+    project_path: PathBuf,
+    from: String,
+    to: String,
+    yes: bool,
+    dryrun: bool,
+) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    // Check if filter file exists
+    let filter_path = vcxproj::filters_path(&project_path);
+    if !pipe::exists(&filter_path) {
+        return Err(anyhow::anyhow!("Filter file not found: {}", filter_path.display()));
+    }
+    
+    // Load filter file
+    let mut filter_file = FilterFile::load(&filter_path)?;
+    
+    // Attempt to rename the filter
+    let (target_exists, renamed_files) = filter_file.rename_filter(&from, &to)?;
+    
+    if renamed_files.is_empty() {
+        info!("No files found in filter '{}'", from);
+        return Ok(false);
+    }
+    output::report(Change::FileMoved, renamed_files.iter().map(|(file, _)| file));
+    output::report(Change::FilterRemoved, [&from]);
+    if !target_exists {
+        output::report(Change::FilterCreated, [&to]);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        if target_exists {
+            info!("Would merge filter '{}' into existing filter '{}'", from, to);
+            info!("Files that would be moved from '{}' filter:", from);
+            for (file, filter) in &renamed_files {
+                info!("  - {} → {}", file, filter);
+            }
+        } else {
+            info!("Would rename filter '{}' to '{}'", from, to);
+            info!("Files that would be moved:");
+            for (file, filter) in &renamed_files {
+                info!("  - {} → {}", file, filter);
+            }
+        }
+        info!("Would update filter file: {}", filter_path.display());
+        info!("✨ Dry run completed - {} files would be moved", renamed_files.len());
+        return Ok(true);
+    }
+    
+    if target_exists {
+        // Conflict detected - ask for merge confirmation
+        warn!("⚠️  Conflict detected!");
+        info!("Filter '{}' already exists in the project.", to);
+        info!("Files in '{}' filter:", from);
+        for (file, filter) in &renamed_files {
+            info!("  - {} → {}", file, filter);
+        }
+        
+        if !yes {
+            check_can_prompt()?;
+            print!("{}", tr!("\nMerge '{}' into existing '{}' filter? [y/N]: ", from, to));
+            use std::io::{self, Write};
+            io::stdout().flush()?;
+            
+            let mut input = String::new();
+            io::stdin().read_line(&mut input)?;
+            let input = input.trim().to_lowercase();
+            
+            if input != "y" && input != "yes" {
+                info!("Operation cancelled.");
+                return Ok(false);
+            }
+        }
+        
+        // Reload filter file (since rename_filter modified it) and perform merge
+        let mut filter_file = FilterFile::load(&filter_path)?;
+        let moved_files = filter_file.merge_filters(&from, &to)?;
+        filter_file.save()?;
+        
+        info!("✅ Successfully merged filter '{}' into '{}'", from, to);
+        info!("📁 {} files moved:", moved_files.len());
+        for (file, filter) in &moved_files {
+            info!("  - {} → {}", file, filter);
+        }
+    } else {
+        // Simple rename - no conflict
+        filter_file.save()?;
+        
+        info!("✅ Successfully renamed filter '{}' to '{}'", from, to);
+        info!("📁 {} files moved:", renamed_files.len());
+        for (file, filter) in &renamed_files {
+            info!("  - {} → {}", file, filter);
+        }
+    }
+    
+    info!("Successfully updated {}", filter_path.display());
+    Ok(true)
+}
+
+pub fn export_filter_layout(layout_path: PathBuf, project_path: PathBuf) -> Result<()> {
+    let filter_path = vcxproj::filters_path(&project_path);
+    let filter_file = FilterFile::load(&filter_path)?;
+    
+    let mut layout = FilterLayout {
+        filters: filter_file
+            .get_filter_extensions()
+            .into_iter()
+            .map(|(name, extensions)| LayoutFilter { name, extensions })
+            .collect(),
+        rules: Vec::new(),
+    };
+    
+    // Files grouped by directory, with forward slashes so the rules read like globs
+    let mut by_dir: std::collections::BTreeMap<String, Vec<(String, String)>> = std::collections::BTreeMap::new();
+    for (file, filter) in filter_file.get_item_filters()? {
+        let file = file.replace('\\', "/");
+        let dir = file.rsplit_once('/').map(|(dir, _)| dir.to_string()).unwrap_or_default();
+        by_dir.entry(dir).or_default().push((file, filter));
+    }
+    
+    // Prefer one `dir/**` rule for a whole subtree, then one `dir/*` rule per directory, then per-file rules
+    let mut covered: Vec<String> = Vec::new();
+    for (dir, files) in &by_dir {
+        let prefix = if dir.is_empty() { String::new() } else { format!("{}/", dir) };
+        if covered.iter().any(|c| dir.starts_with(c.as_str())) {
+            continue;
+        }
+        
+        let subtree_filters: std::collections::HashSet<&str> = by_dir
+            .iter()
+            .filter(|(other, _)| *other == dir || other.starts_with(&prefix))
+            .flat_map(|(_, files)| files.iter().map(|(_, filter)| filter.as_str()))
+            .collect();
+        if subtree_filters.len() == 1 && !dir.is_empty() {
+            layout.rules.push(LayoutRule { glob: format!("{}**", prefix), filter: files[0].1.clone() });
+            covered.push(prefix);
+            continue;
+        }
+        
+        let dir_filters: std::collections::HashSet<&str> = files.iter().map(|(_, filter)| filter.as_str()).collect();
+        if dir_filters.len() == 1 {
+            layout.rules.push(LayoutRule { glob: format!("{}*", prefix), filter: files[0].1.clone() });
+        } else {
+            let mut files = files.clone();
+            files.sort();
+            for (file, filter) in files {
+                layout.rules.push(LayoutRule { glob: file, filter });
+            }
+        }
+    }
+    
+    layout.save(&layout_path)?;
+    info!(
+        "✅ Exported {} filters and {} rules from {} to {}",
+        layout.filters.len(),
+        layout.rules.len(),
+        filter_path.display(),
+        layout_path.display()
+    );
+    Ok(())
+}
+
+pub fn apply_filter_layout(layout_path: PathBuf, project_path: PathBuf, dryrun: bool) -> Result<bool> {
+    let layout = FilterLayout::load(&layout_path)?;
+    let rules = layout
+        .rules
+        .iter()
+        .map(|rule| Ok((glob_to_regex(&rule.glob)?, rule.filter.as_str())))
+        .collect::<Result<Vec<_>>>()?;
+    
+    info!("Analyzing project: {}", project_path.display());
+    info!("Applying filter layout: {}", layout_path.display());
+    
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let filter_path = vcxproj::filters_path(&project_path);
+    let mut filter_file = if pipe::exists(&filter_path) {
+        FilterFile::load(&filter_path)?
+    } else {
+        FilterFile::empty(filter_path.clone())
+    };
+    
+    let mut created_filters = Vec::new();
+    for filter in &layout.filters {
+        created_filters.extend(filter_file.ensure_filter(&filter.name, filter.extensions.as_deref()));
+    }
+    
+    let mut moved = Vec::new();
+    for item in vcxproj.get_items()? {
+        if NON_FILE_ITEM_TYPES.contains(&item.item_type.as_str()) || item.include.contains('*') {
+            continue;
+        }
+        let path = item.include.replace('\\', "/");
+        let target = rules
+            .iter()
+            .find(|(regex, _)| regex.is_match(&path))
+            .map(|(_, filter)| *filter)
+            .or_else(|| layout.filter_for_extension(&path));
+        let Some(target) = target else {
+            continue;
+        };
+        
+        created_filters.extend(filter_file.ensure_filter(target, None));
+        if let Some(previous) = filter_file.set_file_filter(&item.item_type, &item.include, target) {
+            moved.push((item.include, previous, target.to_string()));
+        }
+    }
+    
+    if created_filters.is_empty() && moved.is_empty() {
+        info!("✅ The project already matches the layout");
+        return Ok(false);
+    }
+    
+    output::report(Change::FilterCreated, &created_filters);
+    output::report(Change::FileMoved, moved.iter().map(|(file, _, _)| file));
+    
+    if !created_filters.is_empty() {
+        info!("\n📁 Filters to create:");
+        for filter in &created_filters {
+            info!("  + {}", filter);
+        }
+    }
+    if !moved.is_empty() {
+        info!("\n📄 Files to route:");
+        for (file, previous, target) in &moved {
+            info!("  {}: {} → {}", file, previous.as_deref().unwrap_or("(none)"), target);
+        }
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update filter file: {}", filter_path.display());
+        return Ok(true);
+    }
+    
+    filter_file.save()?;
+    info!(
+        "\n✅ Created {} filters and routed {} files in {}",
+        created_filters.len(),
+        moved.len(),
+        filter_path.display()
+    );
+    Ok(true)
+}
+
+/// Moves every file of the filters file into the top-level filter Visual Studio gives its item type
+/// ("Source Files" for types without one) and removes the other filters, keeping the empty
+/// top-level filters Visual Studio creates for new projects. The disk is left alone.
+pub fn flatten_filters(project_path: PathBuf, dryrun: bool) -> Result<bool> {
+    use std::collections::HashSet;
+    
+    info!("Analyzing project: {}", project_path.display());
+    let filter_path = vcxproj::filters_path(&project_path);
+    if !pipe::exists(&filter_path) {
+        return Err(anyhow::anyhow!("Filter file not found: {}", filter_path.display()));
+    }
+    let mut filter_file = FilterFile::load(&filter_path)?;
+    
+    let mut kept: HashSet<String> = DEFAULT_FILTERS.iter().map(|(_, filter, _)| filter.to_lowercase()).collect();
+    let mut created_filters = Vec::new();
+    let mut moved = Vec::new();
+    for entry in vcxproj::parse_items(&filter_file.content) {
+        if entry.item_type == "Filter" || !entry.metadata.iter().any(|m| m.name == "Filter") {
+            continue;
+        }
+        let (target, extensions) = DEFAULT_FILTERS
+            .iter()
+            .find(|(item_type, _, _)| *item_type == entry.item_type)
+            .map_or(("Source Files", DEFAULT_FILTERS[0].2), |(_, filter, extensions)| (*filter, *extensions));
+        kept.insert(target.to_lowercase());
+        created_filters.extend(filter_file.ensure_filter(target, Some(extensions)));
+        if let Some(previous) = filter_file.set_file_filter(&entry.item_type, &entry.include, target) {
+            moved.push((entry.include, previous, target));
+        }
+    }
+    
+    let mut removed_filters = Vec::new();
+    for (filter, _) in filter_file.get_filter_extensions() {
+        if !kept.contains(&filter.to_lowercase()) {
+            removed_filters.extend(filter_file.remove_filter_tree(&filter));
+        }
+    }
+    
+    if moved.is_empty() && removed_filters.is_empty() {
+        info!("✅ The filters are already flat");
+        return Ok(false);
+    }
+    
+    output::report(Change::FileMoved, moved.iter().map(|(file, _, _)| file));
+    output::report(Change::FilterCreated, &created_filters);
+    output::report(Change::FilterRemoved, &removed_filters);
+    
+    if !created_filters.is_empty() {
+        info!("\n📁 Filters to create:");
+        for filter in &created_filters {
+            info!("  + {}", filter);
+        }
+    }
+    if !moved.is_empty() {
+        info!("\n📄 Files to move:");
+        for (file, previous, target) in &moved {
+            info!("  {}: {} → {}", file, previous.as_deref().unwrap_or("(none)"), target);
+        }
+    }
+    if !removed_filters.is_empty() {
+        info!("\n📁 Filters to remove:");
+        for filter in &removed_filters {
+            info!("  - {}", filter);
+        }
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update filter file: {}", filter_path.display());
+        return Ok(true);
+    }
+    
+    filter_file.save()?;
+    info!(
+        "\n✅ Moved {} files and removed {} filters in {}",
+        moved.len(),
+        removed_filters.len(),
+        filter_path.display()
+    );
+    Ok(true)
+}
+
+/// Puts every filter of the project under the root filter `prefix`, leaving the disk alone
+pub fn reroot_filters(project_path: PathBuf, prefix: String, dryrun: bool) -> Result<bool> {
+    let root = vcxproj::include_path(prefix.trim()).trim_matches('\\').to_string();
+    if root.is_empty() {
+        return Err(anyhow::anyhow!("--prefix needs a filter name, e.g. \"Engine\""));
+    }
+    
+    info!("Analyzing project: {}", project_path.display());
+    let filter_path = vcxproj::filters_path(&project_path);
+    if !pipe::exists(&filter_path) {
+        return Err(anyhow::anyhow!("Filter file not found: {}", filter_path.display()));
+    }
+    let mut filter_file = FilterFile::load(&filter_path)?;
+    let existing: Vec<String> = filter_file.get_filter_extensions().into_iter().map(|(filter, _)| filter).collect();
+    let (moved_filters, moved_files) = filter_file.reroot(&root);
+    if moved_filters.is_empty() && moved_files.is_empty() {
+        info!("✅ No filters to put under '{}'", root);
+        return Ok(false);
+    }
+    
+    let created_filters: Vec<String> = filter_file
+        .get_filter_extensions()
+        .into_iter()
+        .map(|(filter, _)| filter)
+        .filter(|filter| !existing.contains(filter))
+        .collect();
+    output::report(Change::FileMoved, &moved_files);
+    output::report(Change::FilterRemoved, moved_filters.iter().map(|(from, _)| from).filter(|from| !created_filters.contains(from)));
+    output::report(Change::FilterCreated, &created_filters);
+    
+    info!("\n📁 Filters to move:");
+    for (from, to) in &moved_filters {
+        info!("  {} → {}", from, to);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update filter file: {}", filter_path.display());
+        info!("✨ Dry run completed - {} filters and {} files would be moved", moved_filters.len(), moved_files.len());
+        return Ok(true);
+    }
+    
+    filter_file.save()?;
+    info!(
+        "\n✅ Moved {} filters and {} files under '{}' in {}",
+        moved_filters.len(),
+        moved_files.len(),
+        root,
+        filter_path.display()
+    );
+    Ok(true)
+}
+
+/// Removes the filters without a file in them or in any of their child filters
+pub fn prune_empty_filters(project_path: PathBuf, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    let filter_path = vcxproj::filters_path(&project_path);
+    if !pipe::exists(&filter_path) {
+        return Err(anyhow::anyhow!("Filter file not found: {}", filter_path.display()));
+    }
+    let mut filter_file = FilterFile::load(&filter_path)?;
+    
+    let mut removed_filters = Vec::new();
+    for (filter, _) in filter_file.get_filter_extensions() {
+        if filter_file.files_in_filter_tree(&filter)?.is_some_and(|files| files.is_empty()) {
+            removed_filters.extend(filter_file.remove_filter_tree(&filter));
+        }
+    }
+    if removed_filters.is_empty() {
+        info!("✅ No empty filters in {}", filter_path.display());
+        return Ok(false);
+    }
+    
+    output::report(Change::FilterRemoved, &removed_filters);
+    info!("\n📁 Empty filters to remove:");
+    for filter in &removed_filters {
+        info!("  - {}", filter);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update filter file: {}", filter_path.display());
+        return Ok(true);
+    }
+    
+    filter_file.save()?;
+    info!("\n✅ Removed {} empty filters from {}", removed_filters.len(), filter_path.display());
+    Ok(true)
+}
+
+/// Defines the filter `name` and its missing parents, creating the filters file if the project has none
+pub fn create_filter(project_path: PathBuf, name: String, dryrun: bool) -> Result<bool> {
+    let name = vcxproj::include_path(name.trim()).trim_matches('\\').to_string();
+    if name.is_empty() {
+        return Err(anyhow::anyhow!("--name needs a filter name, e.g. \"Docs\\Design\""));
+    }
+    
+    let filter_path = vcxproj::filters_path(&project_path);
+    let filter_exists = pipe::exists(&filter_path);
+    let mut filter_file = if filter_exists {
+        FilterFile::load(&filter_path)?
+    } else {
+        FilterFile::empty(filter_path.clone())
+    };
+    let created_filters = filter_file.ensure_filter(&name, None);
+    if created_filters.is_empty() {
+        info!("✅ Filter '{}' already exists in {}", name, filter_path.display());
+        return Ok(false);
+    }
+    
+    output::report(Change::FilterCreated, &created_filters);
+    info!("📁 Filters to create:");
+    for filter in &created_filters {
+        info!("  + {}", filter);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        if filter_exists {
+            info!("Would update filter file: {}", filter_path.display());
+        } else {
+            info!("Would create filter file: {}", filter_path.display());
+        }
+        return Ok(true);
+    }
+    
+    filter_file.save()?;
+    info!("\n✅ Created {} filters in {}", created_filters.len(), filter_path.display());
+    Ok(true)
+}
+
+/// Extensions Visual Studio gives the "Resource Files" filter
+pub const RESOURCE_EXTENSIONS: &str = "rc;ico;cur;bmp;dlg;rc2;rct;bin;rgs;gif;jpg;jpeg;jpe;resx;tiff;tif;png;wav;mfcribbon-ms";
+
+/// Filter Visual Studio (or Qt VS Tools) puts new files of each item type in, with the extensions it gives that filter
+pub const DEFAULT_FILTERS: &[(&str, &str, &str)] = &[
+    ("ClCompile", "Source Files", "cpp;c;cc;cxx;c++;cppm;ixx;def;odl;idl;hpj;bat;asm;asmx"),
+    ("ClInclude", "Header Files", "h;hh;hpp;hxx;h++;hm;inl;inc;ipp;xsd"),
+    ("QtMoc", "Header Files", "h;hh;hpp;hxx;h++;hm;inl;inc;ipp;xsd"),
+    ("QtUic", "Form Files", "ui"),
+    ("QtRcc", "Resource Files", "qrc;rc;ico;cur;bmp;dlg;rc2;rct;bin;rgs;gif;jpg;jpeg;jpe;resx;tiff;tif;png;wav;mfcribbon-ms"),
+    ("ResourceCompile", "Resource Files", RESOURCE_EXTENSIONS),
+    ("Image", "Resource Files", RESOURCE_EXTENSIONS),
+    ("Manifest", "Resource Files", RESOURCE_EXTENSIONS),
+];
+
+/// Brings the filters file in line with the project: sources and headers missing from it get an
+/// entry in an inferred filter, entries without a project item are removed, and filters without a
+/// UniqueIdentifier get one. The filter of a new entry is the one most files from the same
+/// directory are in, else the first filter whose Extensions list its extension, else the default
+/// "Source Files"/"Header Files".
+pub fn fix_filters(project_path: PathBuf, dryrun: bool) -> Result<bool> {
+    use std::collections::BTreeMap;
+    
+    info!("Analyzing project: {}", project_path.display());
+    
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let filter_path = vcxproj::filters_path(&project_path);
+    let mut filter_file = if pipe::exists(&filter_path) {
+        FilterFile::load(&filter_path)?
+    } else {
+        FilterFile::empty(filter_path.clone())
+    };
+    
+    let stale = stale_filter_entries(&vcxproj, &filter_file)?;
+    let removed = filter_file.remove_items(&stale);
+    
+    let directory = |path: &str| path.replace('/', "\\").rsplit_once('\\').map_or(String::new(), |(dir, _)| dir.to_lowercase());
+    let entries = vcxproj::parse_items(&filter_file.content);
+    let file_filters = filter_file.get_item_filters()?;
+    let mut added = Vec::new();
+    let mut created_filters = Vec::new();
+    for item in vcxproj.get_items()? {
+        let Some(&(_, default_filter, default_extensions)) = DEFAULT_FILTERS.iter().find(|(t, _, _)| *t == item.item_type) else {
+            continue;
+        };
+        let listed = entries.iter().any(|e| e.item_type != "Filter" && vcxproj::same_path(&e.include, &item.include));
+        if listed || item.include.contains(['*', ';']) {
+            continue;
+        }
+        
+        let mut siblings: BTreeMap<&str, usize> = BTreeMap::new();
+        for (file, filter) in &file_filters {
+            if directory(file) == directory(&item.include) {
+                *siblings.entry(filter.as_str()).or_default() += 1;
+            }
+        }
+        let extension = Path::new(&item.include.replace('\\', "/"))
+            .extension()
+            .map(|e| e.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let by_extension = filter_file.get_filter_extensions().into_iter().find_map(|(filter, extensions)| {
+            extensions
+                .is_some_and(|list| list.split(';').any(|e| e.trim().trim_start_matches("*.").eq_ignore_ascii_case(&extension)))
+                .then_some(filter)
+        });
+        // The first filter with the most siblings wins ties, so the result does not depend on hash order
+        let by_siblings = siblings.iter().rev().max_by_key(|(_, count)| **count).map(|(filter, _)| filter.to_string());
+        let filter = match by_siblings.or(by_extension) {
+            Some(filter) => filter,
+            None => {
+                created_filters.extend(filter_file.ensure_filter(default_filter, Some(default_extensions)));
+                default_filter.to_string()
+            }
+        };
+        filter_file.set_file_filter(&item.item_type, &item.include, &filter);
+        added.push((item.include, filter));
+    }
+    
+    let identified = filter_file.add_missing_identifiers();
+    
+    if added.is_empty() && removed.is_empty() && identified.is_empty() && created_filters.is_empty() {
+        info!("✨ The filters file matches the project");
+        return Ok(false);
+    }
+    
+    if !added.is_empty() {
+        info!("\n📄 Entries added to the filters file:");
+        for (file, filter) in &added {
+            info!("  + {} → {}", file, filter);
+        }
+    }
+    if !removed.is_empty() {
+        info!("\n🗑️  Entries without a project item:");
+        for file in &removed {
+            info!("  - {}", file);
+        }
+    }
+    if !created_filters.is_empty() {
+        info!("\n📁 Filters created:");
+        for filter in &created_filters {
+            info!("  + {}", filter);
+        }
+    }
+    if !identified.is_empty() {
+        info!("\n🔑 Filters given a UniqueIdentifier:");
+        for filter in &identified {
+            info!("  - {}", filter);
+        }
+    }
+    output::report(Change::FileMoved, added.iter().map(|(file, _)| file));
+    output::report(Change::FileRemoved, &removed);
+    output::report(Change::FilterCreated, &created_filters);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update filter file: {}", filter_path.display());
+        return Ok(true);
+    }
+    
+    filter_file.save()?;
+    info!("\n✅ Updated {}", filter_path.display());
+    Ok(true)
+}
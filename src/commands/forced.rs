@@ -0,0 +1,67 @@
+use anyhow::Result;
+use crate::cli::ForcedAction;
+use crate::i18n::{info, tr};
+use crate::output::{self, Change};
+use crate::vcxproj::{self, VcxprojFile};
+use super::config_selector;
+
+/// Adds a file to or removes it from a ClCompile list of forced files (`ForcedIncludeFiles` or
+/// `ForcedUsingFiles`), keeping the other entries and the inherited value. Returns true when a
+/// `--dryrun` found changes to make.
+pub fn edit_forced_files(element: &str, action: ForcedAction) -> Result<bool> {
+    let (project_path, file, selector, dryrun) = match &action {
+        ForcedAction::Add { project, file, config, platform, dryrun, .. } | ForcedAction::Remove { project, file, config, platform, dryrun } => {
+            (project.clone(), file.clone(), config_selector(config.clone(), platform.clone())?, *dryrun)
+        }
+    };
+    info!("Analyzing project: {}", project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let modified_configs: Vec<String> = match &action {
+        ForcedAction::Add { position, .. } => vcxproj
+            .add_tool_list_value("ClCompile", element, &file, selector.as_deref(), position)?
+            .iter()
+            .map(|condition| vcxproj::condition_config(condition).into_owned())
+            .collect(),
+        ForcedAction::Remove { .. } => {
+            let listed: Vec<String> = vcxproj
+                .get_tool_settings()
+                .into_iter()
+                .filter(|s| s.tool == "ClCompile" && s.name == element && s.value.split(';').any(|entry| entry.trim().eq_ignore_ascii_case(file.trim())))
+                .filter_map(|s| s.condition)
+                .filter(|condition| selector.as_deref().is_none_or(|selector| vcxproj::condition_matches(condition, selector)))
+                .map(|condition| vcxproj::condition_config(&condition).into_owned())
+                .collect();
+            vcxproj.remove_list_value(element, &file, selector.as_deref());
+            listed
+        }
+    };
+    
+    let adding = matches!(action, ForcedAction::Add { .. });
+    if modified_configs.is_empty() {
+        let scope = selector.as_deref().map_or(tr!("every configuration"), |selector| tr!("the {} configurations", selector));
+        if adding {
+            info!("✅ No changes - {} is already in {} of {}", file, element, scope);
+        } else {
+            info!("✅ No changes - {} is not in {} of {}", file, element, scope);
+        }
+        return Ok(false);
+    }
+    if adding {
+        info!("\n➕ Adding {} to {}:", file, element);
+    } else {
+        info!("\n➖ Removing {} from {}:", file, element);
+    }
+    for config in &modified_configs {
+        info!("  - {}", config);
+    }
+    output::report(Change::ConfigModified, &modified_configs);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ Updated {} configurations in {}", modified_configs.len(), project_path.display());
+    Ok(true)
+}
@@ -0,0 +1,202 @@
+use anyhow::{Context, Result};
+use crate::canonical;
+use crate::i18n::{info, tr, warn};
+use crate::pipe;
+use crate::repair;
+use crate::vcxproj::{self, FilterFile, VcxprojFile};
+use std::path::PathBuf;
+
+/// Applies the requested formatting operations to the project and its filters file
+pub fn format_project(project_path: PathBuf, merge_itemgroups: bool, check: bool, dryrun: bool) -> Result<bool> {
+    if !check {
+        info!("Formatting project: {}", project_path.display());
+    }
+    
+    let mut files = vec![VcxprojFile::load(&project_path)?.content];
+    let filter_path = vcxproj::filters_path(&project_path);
+    if pipe::exists(&filter_path) {
+        files.push(FilterFile::load(&filter_path)?.content);
+    }
+    
+    // Each file that changes, with its new content and a line per kind of change
+    let mut changed: Vec<(PathBuf, String, Vec<String>)> = Vec::new();
+    for (path, content) in [&project_path, &filter_path].into_iter().zip(files) {
+        let (formatted, notes) = if merge_itemgroups {
+            let (formatted, summary) = vcxproj::merge_item_groups(&content);
+            let mut notes: Vec<String> = summary
+                .merged
+                .iter()
+                .map(|(item_type, groups, items)| tr!("🔗 {}: merged {} ItemGroups into earlier ones ({} items moved)", item_type, groups, items))
+                .collect();
+            if summary.empty_removed > 0 {
+                notes.push(tr!("🗑️  Removed {} empty ItemGroups", summary.empty_removed));
+            }
+            (formatted, notes)
+        } else {
+            let formatted = canonical::format(&content, &vcxproj::Style::load(path, &content)?);
+            let notes = formatted.changes.iter().map(|change| format!("🧹 {}", change)).collect();
+            (formatted.content, notes)
+        };
+        if formatted != content {
+            changed.push((path.clone(), formatted, notes));
+        }
+    }
+    
+    if check {
+        for (path, _, _) in &changed {
+            warn!("⚠️  {} is not formatted", path.display());
+        }
+        if !changed.is_empty() {
+            let flag = if merge_itemgroups { " --merge-itemgroups" } else { "" };
+            info!("Run 'vsprojm format -p {}{}' to fix", project_path.display(), flag);
+        }
+        return Ok(!changed.is_empty());
+    }
+    
+    for (path, _, notes) in &changed {
+        info!("\n{}:", path.display());
+        for note in notes {
+            info!("  {}", note);
+        }
+    }
+    
+    if changed.is_empty() {
+        if merge_itemgroups {
+            info!("✅ No changes - ItemGroups are already merged");
+        } else {
+            info!("✅ No changes - the files are already in canonical layout");
+        }
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        for (path, _, _) in &changed {
+            info!("Would update: {}", path.display());
+        }
+        return Ok(true);
+    }
+    
+    for (path, content, _) in &changed {
+        if merge_itemgroups {
+            vcxproj::write_file(path, content)
+        } else {
+            vcxproj::write_file_as_is(path, content)
+        }
+        .with_context(|| format!("Failed to write {}", path.display()))?;
+    }
+    info!("\n✅ Formatted {} files; item metadata and conditions are unchanged", changed.len());
+    Ok(true)
+}
+
+/// Repairs structural damage in a project and its filters file, reporting every fix.
+/// Damage the heuristics cannot fix is reported, and is an error when nothing else could be fixed.
+pub fn repair_project(project_path: PathBuf, dryrun: bool) -> Result<bool> {
+    info!("Repairing project: {}", project_path.display());
+    
+    let filter_path = vcxproj::filters_path(&project_path);
+    let mut repaired_files = Vec::new();
+    let mut unrepairable = 0;
+    for path in [project_path.clone(), filter_path] {
+        if path != project_path && !path.exists() {
+            continue;
+        }
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read file: {}", path.display()))?;
+        let repaired = repair::repair(&content);
+        if repaired.fixes.is_empty() {
+            if let Err(problem) = repair::check_well_formed(&content) {
+                warn!("⚠️  {} cannot be repaired automatically: {}", path.display(), problem);
+                unrepairable += 1;
+            }
+            continue;
+        }
+        
+        info!("\n{}:", path.display());
+        for fix in &repaired.fixes {
+            info!("  🩹 {}", fix);
+        }
+        if let Err(problem) = repair::check_well_formed(&repaired.content) {
+            warn!("  ⚠️  Still not well-formed after repair: {}", problem);
+        }
+        repaired_files.push((path, repaired.content));
+    }
+    
+    if repaired_files.is_empty() {
+        if unrepairable > 0 {
+            return Err(anyhow::anyhow!("Found damage that repair cannot fix; restore the file from version control"));
+        }
+        info!("✅ No changes - nothing to repair");
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        for (path, _) in &repaired_files {
+            info!("Would update: {}", path.display());
+        }
+        return Ok(true);
+    }
+    
+    for (path, content) in &repaired_files {
+        vcxproj::write_file(path, content)?;
+    }
+    info!("\n✅ Repaired {} files - review the result (or `undo`) before building", repaired_files.len());
+    Ok(true)
+}
+
+/// Sorts the ItemGroup entries of the project and its filters file (see `vcxproj::sort_item_groups`).
+/// With `check` only the unsorted files are listed, for CI.
+pub fn sort_project(project_path: PathBuf, check: bool, dryrun: bool) -> Result<bool> {
+    if !check {
+        info!("Sorting project: {}", project_path.display());
+    }
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let filter_path = vcxproj::filters_path(&project_path);
+    let mut filter_file = if pipe::exists(&filter_path) { Some(FilterFile::load(&filter_path)?) } else { None };
+    
+    let mut changed = Vec::new();
+    let (content, groups) = vcxproj::sort_item_groups(&vcxproj.content);
+    if groups > 0 {
+        vcxproj.content = content;
+        changed.push((project_path.clone(), groups));
+    }
+    if let Some(filter_file) = &mut filter_file {
+        let (content, groups) = vcxproj::sort_item_groups(&filter_file.content);
+        if groups > 0 {
+            filter_file.content = content;
+            changed.push((filter_path.clone(), groups));
+        }
+    }
+    
+    if check {
+        for (path, groups) in &changed {
+            warn!("⚠️  {} is not sorted ({} ItemGroups out of order)", path.display(), groups);
+        }
+        if !changed.is_empty() {
+            info!("Run 'vsprojm sort -p {}' to fix", project_path.display());
+        }
+        return Ok(!changed.is_empty());
+    }
+    
+    if changed.is_empty() {
+        info!("✅ No changes - every ItemGroup is already sorted");
+        return Ok(false);
+    }
+    for (path, groups) in &changed {
+        info!("  🔤 {}: sorted {} ItemGroups", path.display(), groups);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        for (path, _) in &changed {
+            info!("Would update: {}", path.display());
+        }
+        return Ok(true);
+    }
+    
+    vcxproj.save_with_filters(filter_file.as_ref())?;
+    info!("\n✅ Sorted {} files; item metadata and conditions are unchanged", changed.len());
+    Ok(true)
+}
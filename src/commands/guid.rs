@@ -0,0 +1,312 @@
+use anyhow::{Context, Result};
+use crate::i18n::{info, tr, warn};
+use crate::output::{self, Change};
+use crate::pipe;
+use crate::sln::SolutionFile;
+use crate::vcxproj::{self, FilterFile, VcxprojFile};
+use std::path::{Path, PathBuf};
+use super::{DEFAULT_EXCLUDED_DIRS, is_excluded_dir, path_key};
+use walkdir::WalkDir;
+
+/// Prints the project GUID on its own line so scripts can capture it directly
+pub fn print_project_guid(project_path: PathBuf, long: bool) -> Result<()> {
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let guid = vcxproj
+        .project_guid()
+        .ok_or_else(|| anyhow::anyhow!("No ProjectGuid found in {}", project_path.display()))?;
+    
+    if long {
+        output::print_line(format!("{}\t{}\t{}", guid, vcxproj.project_name(), project_path.display()));
+    } else {
+        output::print_line(guid);
+    }
+    Ok(())
+}
+
+/// Prints the path of every .vcxproj under `root` whose GUID or name matches, one per line.
+/// Fails when nothing matches so scripts can rely on the exit status.
+pub fn lookup_projects(guid: Option<String>, name: Option<String>, root: PathBuf) -> Result<()> {
+    let normalize_guid = |g: &str| g.trim().trim_start_matches('{').trim_end_matches('}').to_lowercase();
+    let wanted_guid = guid.as_deref().map(normalize_guid);
+    let excluded_dirs: Vec<String> = DEFAULT_EXCLUDED_DIRS.iter().map(|d| d.to_string()).collect();
+    
+    let mut found = 0;
+    for entry in WalkDir::new(&root).into_iter().filter_entry(|e| !is_excluded_dir(e.path(), e.file_type().is_dir(), &root, &excluded_dirs)) {
+        let entry = entry.context("Failed to read directory entry")?;
+        let is_project = entry.file_type().is_file()
+            && entry.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("vcxproj"));
+        if !is_project {
+            continue;
+        }
+        
+        let vcxproj = VcxprojFile::load(entry.path())?;
+        let matches = match (&wanted_guid, &name) {
+            (Some(wanted), _) => vcxproj.project_guid().is_some_and(|g| normalize_guid(&g) == *wanted),
+            (None, Some(name)) => vcxproj.project_name().eq_ignore_ascii_case(name),
+            (None, None) => false,
+        };
+        if matches {
+            println!("{}", entry.path().display());
+            found += 1;
+        }
+    }
+    
+    if found == 0 {
+        let what = guid.map(|g| format!("GUID {}", g)).or(name.map(|n| format!("name {}", n))).unwrap_or_default();
+        return Err(anyhow::anyhow!("No project with {} found under {}", what, root.display()));
+    }
+    Ok(())
+}
+
+/// A new GUID in braces, in lowercase when `previous` is written in lowercase
+fn new_guid_like(previous: &str) -> String {
+    let guid = uuid::Uuid::new_v4().to_string();
+    if previous.chars().any(|c| c.is_ascii_lowercase()) {
+        format!("{{{}}}", guid)
+    } else {
+        format!("{{{}}}", guid.to_uppercase())
+    }
+}
+
+/// The .vcxproj projects of a solution, as (path as written in the solution, path on disk)
+fn solution_vcxprojs(solution: &SolutionFile) -> Vec<(String, PathBuf)> {
+    let solution_dir = solution.path.parent().unwrap_or_else(|| Path::new("."));
+    solution
+        .projects()
+        .into_iter()
+        .filter(|p| p.path.to_lowercase().ends_with(".vcxproj"))
+        .map(|p| {
+            let on_disk = solution_dir.join(p.path.replace('\\', "/"));
+            (p.path, on_disk)
+        })
+        .collect()
+}
+
+/// Points the ProjectReferences to `target` in `projects` at its new GUID. Returns each project
+/// changed with the references updated in it.
+fn update_reference_guids(projects: &[PathBuf], target: &Path, guid: &str) -> Result<Vec<(VcxprojFile, Vec<String>)>> {
+    let target = path_key(target);
+    let mut changed = Vec::new();
+    for path in projects {
+        if path_key(path) == target || !pipe::exists(path) {
+            continue;
+        }
+        let mut vcxproj = VcxprojFile::load(path)?;
+        let project_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let updated = vcxproj.set_project_reference_guid(|include| path_key(&project_dir.join(include.replace('\\', "/"))) == target, guid);
+        if !updated.is_empty() {
+            changed.push((vcxproj, updated));
+        }
+    }
+    Ok(changed)
+}
+
+/// Gives a project a new ProjectGuid, and with `filters` its filters new UniqueIdentifiers. The
+/// solution's entry and the ProjectReferences of the solution's other projects follow the new GUID.
+pub fn regenerate_project_guid(project_path: PathBuf, solution_path: Option<PathBuf>, filters: bool, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let previous = vcxproj
+        .project_guid()
+        .ok_or_else(|| anyhow::anyhow!("{} has no ProjectGuid", project_path.display()))?;
+    let guid = new_guid_like(&previous);
+    vcxproj.set_project_guid(&guid);
+    info!("🔑 ProjectGuid: {} → {}", previous, guid);
+    
+    let mut filter_file = None;
+    let filter_path = vcxproj::filters_path(&project_path);
+    if filters && pipe::exists(&filter_path) {
+        let mut file = FilterFile::load(&filter_path)?;
+        let regenerated = file.regenerate_identifiers(false);
+        if !regenerated.is_empty() {
+            info!("🔑 New UniqueIdentifier for {} filters", regenerated.len());
+            filter_file = Some(file);
+        }
+    }
+    
+    let mut solution = None;
+    let mut references = Vec::new();
+    if let Some(solution_path) = &solution_path {
+        let mut file = SolutionFile::load(solution_path)?;
+        let projects = solution_vcxprojs(&file);
+        let entry = projects.iter().find(|(_, on_disk)| path_key(on_disk) == path_key(&project_path)).map(|(written, _)| written.clone());
+        match entry {
+            Some(written) => {
+                file.set_project_guid(&written, &guid.to_uppercase());
+                info!("📝 Updated the entry of {} in {}", written, solution_path.display());
+                solution = Some(file);
+            }
+            None => warn!("⚠️  {} is not in {}", project_path.display(), solution_path.display()),
+        }
+        let paths: Vec<PathBuf> = projects.into_iter().map(|(_, on_disk)| on_disk).collect();
+        references = update_reference_guids(&paths, &project_path, &guid)?;
+        for (referrer, updated) in &references {
+            info!("🔗 {}: ProjectReference {}", referrer.path.display(), updated.join(", "));
+            output::report(Change::FileModified, updated);
+        }
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        return Ok(true);
+    }
+    vcxproj.save_with_filters(filter_file.as_ref())?;
+    if let Some(solution) = &solution {
+        solution.save()?;
+    }
+    for (referrer, _) in &references {
+        referrer.save()?;
+    }
+    info!("\n✅ {} now has ProjectGuid {}", project_path.display(), guid);
+    Ok(true)
+}
+
+/// Lists the GUID of every project of a solution, flagging GUIDs several projects share, entries
+/// whose GUID differs from the project file, and filters sharing a UniqueIdentifier
+pub fn list_solution_guids(solution_path: PathBuf) -> Result<()> {
+    use std::collections::HashMap;
+    
+    let solution = SolutionFile::load(&solution_path)?;
+    let entries = solution.projects();
+    let mut rows = Vec::new();
+    for (written, on_disk) in solution_vcxprojs(&solution) {
+        let entry_guid = entries.iter().find(|p| p.path == written).map(|p| p.guid.clone()).unwrap_or_default();
+        let vcxproj = pipe::exists(&on_disk).then(|| VcxprojFile::load(&on_disk)).transpose()?;
+        let duplicate_filters = match &vcxproj {
+            Some(_) if pipe::exists(&vcxproj::filters_path(&on_disk)) => FilterFile::load(vcxproj::filters_path(&on_disk))?.regenerate_identifiers(true).len(),
+            _ => 0,
+        };
+        let file_guid = vcxproj.as_ref().and_then(|v| v.project_guid());
+        rows.push((written, entry_guid, file_guid, duplicate_filters));
+    }
+    
+    let mut counts: HashMap<String, usize> = HashMap::new();
+    for (_, entry_guid, file_guid, _) in &rows {
+        *counts.entry(file_guid.as_deref().unwrap_or(entry_guid).to_uppercase()).or_default() += 1;
+    }
+    
+    info!("🔑 Project GUIDs of {}:", solution_path.display());
+    let mut problems = 0;
+    for (written, entry_guid, file_guid, duplicate_filters) in &rows {
+        let guid = file_guid.as_deref().unwrap_or(entry_guid);
+        info!("  {}  {}", guid, written);
+        if counts[&guid.to_uppercase()] > 1 {
+            warn!("     ⚠️  shared with another project");
+            problems += 1;
+        }
+        match file_guid {
+            Some(file_guid) if !file_guid.eq_ignore_ascii_case(entry_guid) => {
+                warn!("     ⚠️  the solution lists it as {}", entry_guid);
+                problems += 1;
+            }
+            None => {
+                warn!("     ⚠️  the project file has no ProjectGuid or is missing");
+                problems += 1;
+            }
+            _ => {}
+        }
+        if *duplicate_filters > 0 {
+            warn!("     ⚠️  {} filters reuse the UniqueIdentifier of another filter", duplicate_filters);
+            problems += 1;
+        }
+    }
+    if problems == 0 {
+        info!("\n✅ Every project has its own GUID");
+    } else {
+        info!("\n⚠️  {} problems - `guid dedupe` fixes them", problems);
+    }
+    Ok(())
+}
+
+/// Gives every project of a solution that shares its ProjectGuid with an earlier one a new GUID,
+/// updating the solution's entries and the ProjectReferences to it, brings entries whose GUID differs
+/// from the project file in line, and gives filters sharing a UniqueIdentifier within a project new ones
+pub fn dedupe_solution_guids(solution_path: PathBuf, dryrun: bool) -> Result<bool> {
+    use std::collections::HashSet;
+    
+    info!("Analyzing solution: {}", solution_path.display());
+    let mut solution = SolutionFile::load(&solution_path)?;
+    
+    // (path as written, project, whether it changed)
+    let mut projects = Vec::new();
+    for (written, on_disk) in solution_vcxprojs(&solution) {
+        if !pipe::exists(&on_disk) {
+            warn!("⚠️  Skipping {}: file not found", written);
+            continue;
+        }
+        projects.push((written, VcxprojFile::load(&on_disk)?, false));
+    }
+    
+    let mut seen: HashSet<String> = HashSet::new();
+    let mut solution_changed = false;
+    for index in 0..projects.len() {
+        let Some(mut guid) = projects[index].1.project_guid() else {
+            warn!("⚠️  Skipping {}: no ProjectGuid", projects[index].0);
+            continue;
+        };
+        if !seen.insert(guid.to_uppercase()) {
+            let new_guid = new_guid_like(&guid);
+            projects[index].1.set_project_guid(&new_guid);
+            projects[index].2 = true;
+            info!("🔑 {}: {} → {}", projects[index].0, guid, new_guid);
+            
+            let target = path_key(&projects[index].1.path);
+            for (_, referrer, changed) in projects.iter_mut() {
+                let referrer_dir = referrer.path.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+                let updated = referrer.set_project_reference_guid(|include| path_key(&referrer_dir.join(include.replace('\\', "/"))) == target, &new_guid);
+                if !updated.is_empty() {
+                    info!("  🔗 {}: ProjectReference {}", referrer.path.display(), updated.join(", "));
+                    output::report(Change::FileModified, &updated);
+                    *changed = true;
+                }
+            }
+            seen.insert(new_guid.to_uppercase());
+            guid = new_guid;
+        }
+        
+        let written = &projects[index].0;
+        let entry_guid = solution.projects().into_iter().find(|p| p.path == *written).map(|p| p.guid).unwrap_or_default();
+        if !entry_guid.eq_ignore_ascii_case(&guid) {
+            solution.set_project_guid(written, &guid.to_uppercase());
+            info!("📝 Solution entry {}: {} → {}", written, entry_guid, guid.to_uppercase());
+            solution_changed = true;
+        }
+    }
+    
+    let mut filter_files = Vec::new();
+    for (written, vcxproj, _) in &projects {
+        let filter_path = vcxproj::filters_path(&vcxproj.path);
+        if !pipe::exists(&filter_path) {
+            continue;
+        }
+        let mut filter_file = FilterFile::load(&filter_path)?;
+        let regenerated = filter_file.regenerate_identifiers(true);
+        if !regenerated.is_empty() {
+            info!("🔑 {}: new UniqueIdentifier for filters {}", written, regenerated.join(", "));
+            filter_files.push(filter_file);
+        }
+    }
+    
+    let changed: Vec<&VcxprojFile> = projects.iter().filter(|(_, _, changed)| *changed).map(|(_, vcxproj, _)| vcxproj).collect();
+    if changed.is_empty() && filter_files.is_empty() && !solution_changed {
+        info!("✅ No changes - every project has its own GUID");
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        return Ok(true);
+    }
+    for vcxproj in &changed {
+        vcxproj.save()?;
+    }
+    for filter_file in &filter_files {
+        filter_file.save()?;
+    }
+    if solution_changed {
+        solution.save()?;
+    }
+    info!("\n✅ Updated {} projects and {} filters files{}", changed.len(), filter_files.len(), if solution_changed { tr!(", and the solution") } else { String::new() });
+    Ok(true)
+}
@@ -0,0 +1,178 @@
+use anyhow::Result;
+use crate::i18n::{info, tr, warn};
+use crate::vcxproj::{self, NON_FILE_ITEM_TYPES, VcxprojFile};
+use std::path::{Path, PathBuf};
+use super::group_config;
+
+/// Imports a shared items project into a consuming project
+pub fn import_shared_items(project_path: PathBuf, items: PathBuf, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    if !vcxproj::is_shared_items(&items) || items.to_string_lossy().to_lowercase().ends_with(".filters") {
+        return Err(anyhow::anyhow!("{} is not a shared items project (.vcxitems)", items.display()));
+    }
+    let shared = VcxprojFile::load(&items)?;
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    let import = vcxproj::relative_path(project_dir, &items);
+    if !vcxproj.add_shared_items_import(&import)? {
+        info!("✅ No changes - {} already imports {}", project_path.display(), import);
+        return Ok(false);
+    }
+    
+    let files = shared.get_items()?.into_iter().filter(|item| !NON_FILE_ITEM_TYPES.contains(&item.item_type.as_str())).count();
+    info!("🔗 Importing {} ({} files)", import, files);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ {} now builds the files of {}", project_path.display(), import);
+    Ok(true)
+}
+
+/// The imports of the Visual C++ targets every C++ project has, in the order they must appear
+const CPP_IMPORTS: &[&str] = &["Microsoft.Cpp.Default.props", "Microsoft.Cpp.props", "Microsoft.Cpp.targets"];
+
+/// Whether an imported path is one of `CPP_IMPORTS`
+fn is_cpp_import(project: &str) -> bool {
+    let name = project.rsplit(['\\', '/']).next().unwrap_or(project);
+    CPP_IMPORTS.iter().any(|cpp| cpp.eq_ignore_ascii_case(name))
+}
+
+pub fn list_imports(project_path: PathBuf) -> Result<()> {
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let imports = vcxproj.get_import_elements();
+    if imports.is_empty() {
+        info!("No imports in {}", project_path.display());
+        return Ok(());
+    }
+    
+    info!("📦 Imports of {}, in evaluation order:", project_path.display());
+    for import in &imports {
+        let mut notes = Vec::new();
+        if import.in_group {
+            notes.push(format!("ImportGroup {}", import.group_label.as_deref().unwrap_or("(no label)")));
+        }
+        if let Some(condition) = &import.group_condition {
+            notes.push(group_config(condition).into_owned());
+        }
+        match import.condition.as_deref() {
+            Some(condition) if condition.to_lowercase().starts_with("exists(") => notes.push(tr!("if it exists")),
+            Some(condition) => notes.push(tr!("if {}", condition)),
+            None => {}
+        }
+        if let Some(label) = &import.label {
+            notes.push(format!("Label {}", label));
+        }
+        let notes = if notes.is_empty() { String::new() } else { format!("  ({})", notes.join(", ")) };
+        info!("  {:>4}  {}{}", import.line, import.project, notes);
+    }
+    
+    // The Microsoft.Cpp imports out of order break the build in ways that are hard to trace back
+    let positions: Vec<usize> = CPP_IMPORTS
+        .iter()
+        .filter_map(|cpp| imports.iter().position(|i| i.project.to_lowercase().ends_with(&cpp.to_lowercase())))
+        .collect();
+    if positions.windows(2).any(|pair| pair[0] > pair[1]) {
+        warn!("⚠️  The Microsoft.Cpp imports are out of order; they must come as {}", CPP_IMPORTS.join(", "));
+    }
+    Ok(())
+}
+
+/// Where `imports add` puts an import
+pub enum ImportPosition {
+    /// ExtensionSettings for .props, ExtensionTargets for .targets
+    Default,
+    /// At the end of the ImportGroup with this Label
+    Group(String),
+    /// Right before the import whose path ends with this
+    Before(String),
+    /// Right after the import whose path ends with this
+    After(String),
+}
+
+/// Imports a .props or .targets file at `position`, warning when that puts it where MSBuild evaluates
+/// it too early or too late to have an effect
+pub fn add_import(project_path: PathBuf, import: String, position: ImportPosition, optional: bool, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let lower = import.to_lowercase();
+    let is_targets = lower.ends_with(".targets");
+    let is_props = lower.ends_with(".props");
+    
+    let added = match &position {
+        ImportPosition::Default if is_targets => vcxproj.add_import_to_group(&import, "ExtensionTargets", optional)?,
+        ImportPosition::Default if is_props => vcxproj.add_import_to_group(&import, "ExtensionSettings", optional)?,
+        ImportPosition::Default => {
+            return Err(anyhow::anyhow!("Can't tell where {} goes; pass --group, --before or --after", import));
+        }
+        ImportPosition::Group(label) => vcxproj.add_import_to_group(&import, label, optional)?,
+        ImportPosition::Before(anchor) => vcxproj.add_import_next_to(&import, anchor, false, optional)?,
+        ImportPosition::After(anchor) => vcxproj.add_import_next_to(&import, anchor, true, optional)?,
+    };
+    if !added {
+        info!("✅ No changes - {} is already imported", import);
+        return Ok(false);
+    }
+    
+    let imports = vcxproj.get_import_elements();
+    let index_of = |name: &str| imports.iter().position(|i| i.project.to_lowercase().ends_with(&name.to_lowercase()));
+    let Some(index) = imports.iter().position(|i| i.project.eq_ignore_ascii_case(&import)) else {
+        return Err(anyhow::anyhow!("Failed to find the new import of {}", import));
+    };
+    info!("📦 Import {} at line {}", import, imports[index].line);
+    if let Some(group) = &imports[index].group_label {
+        info!("  in ImportGroup {}", group);
+    }
+    if is_props && index_of("Microsoft.Cpp.targets").is_some_and(|targets| index > targets) {
+        warn!("⚠️  {} comes after Microsoft.Cpp.targets, too late for its properties to affect the build", import);
+    }
+    if is_targets && index_of("Microsoft.Cpp.props").is_some_and(|props| index < props) {
+        warn!("⚠️  {} comes before Microsoft.Cpp.props, so the C++ targets may override what it defines", import);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ Updated project file: {}", project_path.display());
+    Ok(true)
+}
+
+/// Removes the imports of `import`, matched by path or by file name alone. The Microsoft.Cpp
+/// imports every project needs are never removed.
+pub fn remove_import(project_path: PathBuf, import: String, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let by_name = !import.contains(['\\', '/']);
+    let matches = |project: &str| {
+        let name = project.rsplit(['\\', '/']).next().unwrap_or(project);
+        vcxproj::same_path(project, &import) || (by_name && name.eq_ignore_ascii_case(&import))
+    };
+    
+    if let Some(cpp) = vcxproj.get_imports().into_iter().find(|p| matches(p) && is_cpp_import(p)) {
+        return Err(anyhow::anyhow!("{} is one of the imports every C++ project needs; it can't be removed", cpp));
+    }
+    let removed = vcxproj.remove_imports(matches);
+    if removed.is_empty() {
+        warn!("⚠️  {} does not import {}", project_path.display(), import);
+        return Ok(false);
+    }
+    for project in &removed {
+        info!("🗑️  Remove import {}", project);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ Removed {} imports from {}", removed.len(), project_path.display());
+    Ok(true)
+}
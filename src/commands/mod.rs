@@ -0,0 +1,238 @@
+pub mod asan;
+pub mod audit;
+pub mod backups;
+pub mod config;
+pub mod custom_build;
+pub mod doctor;
+pub mod export;
+pub mod file_props;
+pub mod files;
+pub mod filters;
+pub mod forced;
+pub mod format;
+pub mod guid;
+pub mod imports;
+pub mod new;
+pub mod nuget;
+pub mod patch;
+pub mod paths;
+pub mod props;
+pub mod resources;
+pub mod script;
+pub mod settings;
+pub mod sln;
+pub mod split;
+pub mod toolkits;
+pub mod user;
+pub mod vcpkg;
+pub mod view;
+pub mod watch;
+
+use anyhow::{Context, Result};
+use crate::output;
+use crate::vcxproj::{self, VcxprojFile, WildcardItem};
+use regex::Regex;
+use self::props::normalize_lexically;
+use std::path::{Path, PathBuf};
+use walkdir::WalkDir;
+
+/// Combines `--config` and `--platform` into one configuration selector, e.g. "Debug|x64" or "*|x64"
+pub fn config_selector(config: Option<String>, platform: Option<String>) -> Result<Option<String>> {
+    match (config, platform) {
+        // "all" is spelled out in scripts that always pass --config
+        (Some(config), platform) if config.eq_ignore_ascii_case("all") => Ok(platform.map(|platform| format!("*|{}", platform))),
+        (Some(config), Some(_)) if config.contains('|') => {
+            Err(anyhow::anyhow!("--config '{}' already names a platform; drop --platform", config))
+        }
+        (Some(config), Some(platform)) => Ok(Some(format!("{}|{}", config, platform))),
+        (None, Some(platform)) => Ok(Some(format!("*|{}", platform))),
+        (config, None) => Ok(config),
+    }
+}
+
+/// Directories skipped by `add` unless `--no-default-excludes` is given (VCS/IDE state and build output)
+pub const DEFAULT_EXCLUDED_DIRS: &[&str] = &[".git", ".vs", "x64", "Debug", "Release"];
+
+/// Fails when the user can't be asked anything: the output of projects processed at once is held
+/// back until each is done, so the question would never show
+pub fn check_can_prompt() -> Result<()> {
+    if output::is_captured() {
+        return Err(anyhow::anyhow!("Cannot ask for confirmation while several projects are processed at once: pass --yes, or --jobs 1"));
+    }
+    Ok(())
+}
+
+/// Converts a file glob (e.g. `third_party/**/*.cpp`) into a case-insensitive regex
+/// matched against forward-slash separated item paths
+pub fn glob_to_regex(glob: &str) -> Result<Regex> {
+    let glob = glob.replace('\\', "/");
+    let mut pattern = String::from("(?i)^");
+    let mut chars = glob.chars().peekable();
+    
+    while let Some(c) = chars.next() {
+        match c {
+            '*' if chars.peek() == Some(&'*') => {
+                chars.next();
+                if chars.peek() == Some(&'/') {
+                    chars.next();
+                    pattern.push_str("(.*/)?");
+                } else {
+                    pattern.push_str(".*");
+                }
+            }
+            '*' => pattern.push_str("[^/]*"),
+            '?' => pattern.push_str("[^/]"),
+            _ => pattern.push_str(&regex::escape(&c.to_string())),
+        }
+    }
+    pattern.push('$');
+    
+    Regex::new(&pattern).with_context(|| format!("Invalid glob pattern: {}", glob))
+}
+
+/// Builds a configuration condition in the form Visual Studio writes it
+pub fn config_condition(config: &str) -> String {
+    format!("'$(Configuration)|$(Platform)'=='{}'", config)
+}
+
+/// Returns the files on disk matching `matcher` that are included only through a wildcard
+/// ClCompile item (e.g. `src\**\*.cpp`) and have no explicit item of their own
+pub fn wildcard_covered_files(project_path: &Path, vcxproj: &VcxprojFile, matcher: &Regex) -> Result<Vec<String>> {
+    let wildcards = vcxproj.get_wildcard_items();
+    if wildcards.is_empty() {
+        return Ok(Vec::new());
+    }
+    
+    let explicit: std::collections::HashSet<String> = vcxproj
+        .get_items()?
+        .into_iter()
+        .map(|item| item.include.replace('\\', "/").to_lowercase())
+        .collect();
+    
+    let project_dir = project_path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let mut files = Vec::new();
+    for item in &wildcards {
+        // Patterns built from MSBuild properties can't be evaluated here
+        if item.item_type != "ClCompile" || item.include.contains("$(") {
+            continue;
+        }
+        for file in expand_wildcard_item(project_dir, item)? {
+            let normalized = file.replace('\\', "/");
+            if matcher.is_match(&normalized) && !explicit.contains(&normalized.to_lowercase()) {
+                files.push(file);
+            }
+        }
+    }
+    
+    files.sort();
+    files.dedup();
+    Ok(files)
+}
+
+/// Expands a wildcard item against the files on disk. Returns paths relative to the project
+/// directory with backslashes, the way Visual Studio writes them.
+pub fn expand_wildcard_item(project_dir: &Path, item: &WildcardItem) -> Result<Vec<String>> {
+    let to_regex = |pattern: &String| glob_to_regex(&pattern.replace('\\', "/"));
+    if item.include.contains("$(") {
+        return Err(anyhow::anyhow!("Cannot evaluate wildcard that uses MSBuild properties: {}", item.include));
+    }
+    
+    let mut files = Vec::new();
+    let mut patterns = Vec::new();
+    for pattern in &item.patterns {
+        if pattern.contains('*') {
+            patterns.push(to_regex(pattern)?);
+        } else {
+            files.push(pattern.clone());
+        }
+    }
+    let excludes = item.excludes.iter().map(to_regex).collect::<Result<Vec<_>>>()?;
+    
+    for entry in WalkDir::new(project_dir).into_iter().filter_map(|e| e.ok()) {
+        if !entry.file_type().is_file() {
+            continue;
+        }
+        let Ok(relative) = entry.path().strip_prefix(project_dir) else {
+            continue;
+        };
+        let relative = relative.to_string_lossy().replace('\\', "/");
+        if patterns.iter().any(|p| p.is_match(&relative)) && !excludes.iter().any(|e| e.is_match(&relative)) {
+            files.push(vcxproj::include_path(&relative));
+        }
+    }
+    
+    files.sort();
+    Ok(files)
+}
+
+/// Lists every path under `scan_dir` except excluded directories and their contents. With `respect_gitignore`,
+/// paths matched by .gitignore/.ignore files (and the global git excludes) are skipped as well.
+pub fn scan_paths(scan_dir: &Path, recursive: bool, excluded_dirs: &[String], respect_gitignore: bool) -> Result<Vec<PathBuf>> {
+    let max_depth = if recursive { None } else { Some(1) };
+    let mut paths = Vec::new();
+    
+    if respect_gitignore {
+        let scan_root = scan_dir.to_path_buf();
+        let excluded_dirs = excluded_dirs.to_vec();
+        let walker = ignore::WalkBuilder::new(scan_dir)
+            .max_depth(max_depth)
+            .hidden(false)
+            .require_git(false)
+            .filter_entry(move |e| {
+                let is_dir = e.file_type().is_some_and(|t| t.is_dir());
+                !is_excluded_dir(e.path(), is_dir, &scan_root, &excluded_dirs)
+            })
+            .build();
+        for entry in walker {
+            paths.push(entry.context("Failed to read directory entry")?.into_path());
+        }
+    } else {
+        let walker = WalkDir::new(scan_dir)
+            .max_depth(max_depth.unwrap_or(usize::MAX))
+            .into_iter()
+            .filter_entry(|e| !is_excluded_dir(e.path(), e.file_type().is_dir(), scan_dir, excluded_dirs));
+        for entry in walker {
+            paths.push(entry.context("Failed to read directory entry")?.into_path());
+        }
+    }
+    
+    Ok(paths)
+}
+
+/// Whether a directory matches an `--exclude-dir` entry, either by its own name (e.g. "Debug")
+/// or by its path relative to the scan root (e.g. "third_party/zlib"). The scan root itself is never excluded.
+pub fn is_excluded_dir(path: &Path, is_dir: bool, scan_dir: &Path, excluded_dirs: &[String]) -> bool {
+    let relative = path
+        .strip_prefix(scan_dir)
+        .map(|p| p.to_string_lossy().replace('\\', "/"))
+        .unwrap_or_default();
+    if !is_dir || relative.is_empty() {
+        return false;
+    }
+    
+    let name = path.file_name().unwrap_or_default().to_string_lossy();
+    excluded_dirs.iter().any(|dir| {
+        let dir = dir.replace('\\', "/");
+        let dir = dir.trim_matches('/');
+        dir.eq_ignore_ascii_case(&name) || dir.eq_ignore_ascii_case(&relative)
+    })
+}
+
+/// The configuration a PropertySheets group condition applies to, for display
+pub fn group_config(condition: &str) -> std::borrow::Cow<'_, str> {
+    if condition.is_empty() { "All configurations".into() } else { vcxproj::condition_config(condition) }
+}
+
+/// Identifies a file however its path is written: absolute, without "." and "..", and lowercase
+/// since Windows paths are case-insensitive
+pub fn path_key(path: &Path) -> String {
+    let absolute = std::fs::canonicalize(path).unwrap_or_else(|_| {
+        normalize_lexically(&std::env::current_dir().unwrap_or_default().join(path))
+    });
+    absolute.display().to_string().to_lowercase()
+}
+
+/// "name: previous → value" for a setting a command changed
+pub fn describe_setting_change(name: &str, previous: Option<&str>, value: &str) -> String {
+    format!("{}: {} → {}", name, previous.unwrap_or("(default)"), value)
+}
@@ -0,0 +1,355 @@
+use anyhow::{Context, Result};
+use crate::history;
+use crate::i18n::info;
+use crate::layout::FilterMap;
+use crate::output::{self, Change};
+use crate::vcxproj::{self, FilterFile, VcxprojFile};
+use log::trace;
+use std::path::{Path, PathBuf};
+use super::scan_paths;
+
+pub fn create_project(project_path: PathBuf, template: &str, name: Option<String>, dryrun: bool) -> Result<bool> {
+    let filter_path = vcxproj::filters_path(&project_path);
+    for path in [&project_path, &filter_path] {
+        if path.exists() {
+            return Err(anyhow::anyhow!("{} already exists", path.display()));
+        }
+    }
+    
+    let name = name.unwrap_or_else(|| project_path.file_stem().unwrap_or_default().to_string_lossy().into_owned());
+    let guid = uuid::Uuid::new_v4();
+    let content = project_template(template, &name, &guid)?;
+    let filter_content = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n\
+        <Project ToolsVersion=\"4.0\" xmlns=\"http://schemas.microsoft.com/developer/msbuild/2003\">\n\
+        </Project>";
+    
+    info!("Creating {} project '{}' {{{}}}", template, name, guid);
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would create project file: {}", project_path.display());
+        info!("Would create filter file: {}", filter_path.display());
+        return Ok(true);
+    }
+    
+    if let Some(dir) = project_path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    history::record(&project_path, &content)?;
+    history::record(&filter_path, filter_content)?;
+    history::replace_files(&[
+        (project_path.clone(), Some(content)),
+        (filter_path.clone(), Some(filter_content.to_string())),
+    ])?;
+    output::report(Change::FileWritten, [project_path.display(), filter_path.display()]);
+    
+    info!("Created {}", project_path.display());
+    info!("Created {}", filter_path.display());
+    info!("\n✅ Project created successfully!");
+    Ok(true)
+}
+
+/// The project file `new` generates for a template
+pub fn project_template(template: &str, name: &str, guid: &uuid::Uuid) -> Result<String> {
+    match template {
+        "utility" => Ok(utility_project_template(name, guid)),
+        "console-app" => Ok(console_app_project_template(name, guid)),
+        "static-lib" => Ok(static_library_project_template(name, guid, &STANDARD_CONFIGURATIONS, "v143", "10.0")),
+        _ => Err(anyhow::anyhow!("Unknown template '{}'", template)),
+    }
+}
+
+/// The configurations of Visual Studio's C++ project templates
+const STANDARD_CONFIGURATIONS: [&str; 4] = ["Debug|Win32", "Release|Win32", "Debug|x64", "Release|x64"];
+
+/// A Utility-type project: configurations and toolset but no compiler or linker settings, for
+/// projects that only hold scripts, docs or custom build steps
+fn utility_project_template(name: &str, guid: &uuid::Uuid) -> String {
+    let mut content = String::new();
+    content.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    content.push_str("<Project DefaultTargets=\"Build\" xmlns=\"http://schemas.microsoft.com/developer/msbuild/2003\">\n");
+    
+    let configs = ["Debug", "Release"];
+    content.push_str("  <ItemGroup Label=\"ProjectConfigurations\">\n");
+    for config in configs {
+        content.push_str(&format!(
+            "    <ProjectConfiguration Include=\"{0}|x64\">\n      <Configuration>{0}</Configuration>\n      <Platform>x64</Platform>\n    </ProjectConfiguration>\n",
+            config
+        ));
+    }
+    content.push_str("  </ItemGroup>\n");
+    
+    content.push_str("  <PropertyGroup Label=\"Globals\">\n");
+    content.push_str("    <VCProjectVersion>17.0</VCProjectVersion>\n");
+    content.push_str(&format!("    <ProjectGuid>{{{}}}</ProjectGuid>\n", guid));
+    content.push_str(&format!("    <RootNamespace>{}</RootNamespace>\n", name));
+    content.push_str("  </PropertyGroup>\n");
+    content.push_str("  <Import Project=\"$(VCTargetsPath)\\Microsoft.Cpp.Default.props\" />\n");
+    for config in configs {
+        content.push_str(&format!(
+            "  <PropertyGroup Condition=\"'$(Configuration)|$(Platform)'=='{}|x64'\" Label=\"Configuration\">\n    <ConfigurationType>Utility</ConfigurationType>\n    <PlatformToolset>v143</PlatformToolset>\n  </PropertyGroup>\n",
+            config
+        ));
+    }
+    content.push_str("  <Import Project=\"$(VCTargetsPath)\\Microsoft.Cpp.props\" />\n");
+    content.push_str("  <Import Project=\"$(VCTargetsPath)\\Microsoft.Cpp.targets\" />\n");
+    content.push_str("</Project>");
+    content
+}
+
+/// Visual Studio's Console App: Debug and Release for Win32 and x64, with the compiler and linker
+/// settings the IDE writes for a new project
+fn console_app_project_template(name: &str, guid: &uuid::Uuid) -> String {
+    let configs = STANDARD_CONFIGURATIONS;
+    let condition = |config: &str| format!("'$(Configuration)|$(Platform)'=='{}'", config);
+    let mut content = String::new();
+    content.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    content.push_str("<Project DefaultTargets=\"Build\" xmlns=\"http://schemas.microsoft.com/developer/msbuild/2003\">\n");
+    
+    content.push_str("  <ItemGroup Label=\"ProjectConfigurations\">\n");
+    for config in configs {
+        let (configuration, platform) = config.split_once('|').unwrap();
+        content.push_str(&format!(
+            "    <ProjectConfiguration Include=\"{}\">\n      <Configuration>{}</Configuration>\n      <Platform>{}</Platform>\n    </ProjectConfiguration>\n",
+            config, configuration, platform
+        ));
+    }
+    content.push_str("  </ItemGroup>\n");
+    
+    content.push_str("  <PropertyGroup Label=\"Globals\">\n");
+    content.push_str("    <VCProjectVersion>17.0</VCProjectVersion>\n");
+    content.push_str("    <Keyword>Win32Proj</Keyword>\n");
+    content.push_str(&format!("    <ProjectGuid>{{{}}}</ProjectGuid>\n", guid));
+    content.push_str(&format!("    <RootNamespace>{}</RootNamespace>\n", name));
+    content.push_str("    <WindowsTargetPlatformVersion>10.0</WindowsTargetPlatformVersion>\n");
+    content.push_str("  </PropertyGroup>\n");
+    content.push_str("  <Import Project=\"$(VCTargetsPath)\\Microsoft.Cpp.Default.props\" />\n");
+    for config in configs {
+        let debug = config.starts_with("Debug");
+        content.push_str(&format!("  <PropertyGroup Condition=\"{}\" Label=\"Configuration\">\n", condition(config)));
+        content.push_str("    <ConfigurationType>Application</ConfigurationType>\n");
+        content.push_str(&format!("    <UseDebugLibraries>{}</UseDebugLibraries>\n", debug));
+        content.push_str("    <PlatformToolset>v143</PlatformToolset>\n");
+        if !debug {
+            content.push_str("    <WholeProgramOptimization>true</WholeProgramOptimization>\n");
+        }
+        content.push_str("    <CharacterSet>Unicode</CharacterSet>\n");
+        content.push_str("  </PropertyGroup>\n");
+    }
+    content.push_str("  <Import Project=\"$(VCTargetsPath)\\Microsoft.Cpp.props\" />\n");
+    content.push_str("  <ImportGroup Label=\"ExtensionSettings\">\n  </ImportGroup>\n");
+    content.push_str("  <ImportGroup Label=\"Shared\">\n  </ImportGroup>\n");
+    for config in configs {
+        content.push_str(&format!("  <ImportGroup Label=\"PropertySheets\" Condition=\"{}\">\n", condition(config)));
+        content.push_str("    <Import Project=\"$(UserRootDir)\\Microsoft.Cpp.$(Platform).user.props\" Condition=\"exists('$(UserRootDir)\\Microsoft.Cpp.$(Platform).user.props')\" Label=\"LocalAppDataPlatform\" />\n");
+        content.push_str("  </ImportGroup>\n");
+    }
+    content.push_str("  <PropertyGroup Label=\"UserMacros\" />\n");
+    for config in configs {
+        let debug = config.starts_with("Debug");
+        let defines = format!(
+            "{}{};_CONSOLE;%(PreprocessorDefinitions)",
+            if config.ends_with("|Win32") { "WIN32;" } else { "" },
+            if debug { "_DEBUG" } else { "NDEBUG" }
+        );
+        content.push_str(&format!("  <ItemDefinitionGroup Condition=\"{}\">\n", condition(config)));
+        content.push_str("    <ClCompile>\n      <WarningLevel>Level3</WarningLevel>\n");
+        if !debug {
+            content.push_str("      <FunctionLevelLinking>true</FunctionLevelLinking>\n      <IntrinsicFunctions>true</IntrinsicFunctions>\n");
+        }
+        content.push_str("      <SDLCheck>true</SDLCheck>\n");
+        content.push_str(&format!("      <PreprocessorDefinitions>{}</PreprocessorDefinitions>\n", defines));
+        content.push_str("      <ConformanceMode>true</ConformanceMode>\n    </ClCompile>\n");
+        content.push_str("    <Link>\n      <SubSystem>Console</SubSystem>\n");
+        if !debug {
+            content.push_str("      <EnableCOMDATFolding>true</EnableCOMDATFolding>\n      <OptimizeReferences>true</OptimizeReferences>\n");
+        }
+        content.push_str("      <GenerateDebugInformation>true</GenerateDebugInformation>\n    </Link>\n");
+        content.push_str("  </ItemDefinitionGroup>\n");
+    }
+    content.push_str("  <ItemGroup>\n  </ItemGroup>\n");
+    content.push_str("  <Import Project=\"$(VCTargetsPath)\\Microsoft.Cpp.targets\" />\n");
+    content.push_str("  <ImportGroup Label=\"ExtensionTargets\">\n  </ImportGroup>\n");
+    content.push_str("</Project>");
+    content
+}
+
+/// Visual Studio's Static Library without a precompiled header, for `configs` (configurations
+/// containing "Debug" get debug settings) with the given toolset and Windows SDK version
+pub fn static_library_project_template(name: &str, guid: &uuid::Uuid, configs: &[&str], toolset: &str, sdk: &str) -> String {
+    let condition = |config: &str| format!("'$(Configuration)|$(Platform)'=='{}'", config);
+    let mut content = String::new();
+    content.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    content.push_str("<Project DefaultTargets=\"Build\" xmlns=\"http://schemas.microsoft.com/developer/msbuild/2003\">\n");
+    
+    content.push_str("  <ItemGroup Label=\"ProjectConfigurations\">\n");
+    for config in configs {
+        let (configuration, platform) = config.split_once('|').unwrap_or((config, "x64"));
+        content.push_str(&format!(
+            "    <ProjectConfiguration Include=\"{}\">\n      <Configuration>{}</Configuration>\n      <Platform>{}</Platform>\n    </ProjectConfiguration>\n",
+            config, configuration, platform
+        ));
+    }
+    content.push_str("  </ItemGroup>\n");
+    
+    content.push_str("  <PropertyGroup Label=\"Globals\">\n");
+    content.push_str("    <VCProjectVersion>17.0</VCProjectVersion>\n");
+    content.push_str("    <Keyword>Win32Proj</Keyword>\n");
+    content.push_str(&format!("    <ProjectGuid>{{{}}}</ProjectGuid>\n", guid));
+    content.push_str(&format!("    <RootNamespace>{}</RootNamespace>\n", name));
+    content.push_str(&format!("    <WindowsTargetPlatformVersion>{}</WindowsTargetPlatformVersion>\n", sdk));
+    content.push_str("  </PropertyGroup>\n");
+    content.push_str("  <Import Project=\"$(VCTargetsPath)\\Microsoft.Cpp.Default.props\" />\n");
+    for config in configs {
+        let debug = config.contains("Debug");
+        content.push_str(&format!("  <PropertyGroup Condition=\"{}\" Label=\"Configuration\">\n", condition(config)));
+        content.push_str("    <ConfigurationType>StaticLibrary</ConfigurationType>\n");
+        content.push_str(&format!("    <UseDebugLibraries>{}</UseDebugLibraries>\n", debug));
+        content.push_str(&format!("    <PlatformToolset>{}</PlatformToolset>\n", toolset));
+        if !debug {
+            content.push_str("    <WholeProgramOptimization>true</WholeProgramOptimization>\n");
+        }
+        content.push_str("    <CharacterSet>Unicode</CharacterSet>\n");
+        content.push_str("  </PropertyGroup>\n");
+    }
+    content.push_str("  <Import Project=\"$(VCTargetsPath)\\Microsoft.Cpp.props\" />\n");
+    content.push_str("  <ImportGroup Label=\"ExtensionSettings\">\n  </ImportGroup>\n");
+    content.push_str("  <ImportGroup Label=\"Shared\">\n  </ImportGroup>\n");
+    for config in configs {
+        content.push_str(&format!("  <ImportGroup Label=\"PropertySheets\" Condition=\"{}\">\n", condition(config)));
+        content.push_str("    <Import Project=\"$(UserRootDir)\\Microsoft.Cpp.$(Platform).user.props\" Condition=\"exists('$(UserRootDir)\\Microsoft.Cpp.$(Platform).user.props')\" Label=\"LocalAppDataPlatform\" />\n");
+        content.push_str("  </ImportGroup>\n");
+    }
+    content.push_str("  <PropertyGroup Label=\"UserMacros\" />\n");
+    for config in configs {
+        let debug = config.contains("Debug");
+        let defines = format!(
+            "{}{};_LIB;%(PreprocessorDefinitions)",
+            if config.ends_with("|Win32") { "WIN32;" } else { "" },
+            if debug { "_DEBUG" } else { "NDEBUG" }
+        );
+        content.push_str(&format!("  <ItemDefinitionGroup Condition=\"{}\">\n", condition(config)));
+        content.push_str("    <ClCompile>\n      <WarningLevel>Level3</WarningLevel>\n");
+        if !debug {
+            content.push_str("      <FunctionLevelLinking>true</FunctionLevelLinking>\n      <IntrinsicFunctions>true</IntrinsicFunctions>\n");
+        }
+        content.push_str("      <SDLCheck>true</SDLCheck>\n");
+        content.push_str(&format!("      <PreprocessorDefinitions>{}</PreprocessorDefinitions>\n", defines));
+        content.push_str("      <ConformanceMode>true</ConformanceMode>\n    </ClCompile>\n");
+        content.push_str("    <Link>\n      <SubSystem>\n      </SubSystem>\n");
+        if !debug {
+            content.push_str("      <EnableCOMDATFolding>true</EnableCOMDATFolding>\n      <OptimizeReferences>true</OptimizeReferences>\n");
+        }
+        content.push_str("      <GenerateDebugInformation>true</GenerateDebugInformation>\n    </Link>\n");
+        content.push_str("  </ItemDefinitionGroup>\n");
+    }
+    content.push_str("  <ItemGroup>\n  </ItemGroup>\n");
+    content.push_str("  <Import Project=\"$(VCTargetsPath)\\Microsoft.Cpp.targets\" />\n");
+    content.push_str("  <ImportGroup Label=\"ExtensionTargets\">\n  </ImportGroup>\n");
+    content.push_str("</Project>");
+    content
+}
+
+/// Creates a project from a template and adds every file under `from` with one of `extensions`,
+/// with a filter per subdirectory, so an existing codebase gets a project in one step
+#[allow(clippy::too_many_arguments)]
+pub fn generate_project(
+    project_path: PathBuf,
+    template: &str,
+    name: String,
+    from: PathBuf,
+    extensions: &str,
+    excluded_dirs: Vec<String>,
+    respect_gitignore: bool,
+    dryrun: bool,
+) -> Result<bool> {
+    let template = match template {
+        "app" => "console-app",
+        "staticlib" => "static-lib",
+        template => template,
+    };
+    let filter_path = vcxproj::filters_path(&project_path);
+    for path in [&project_path, &filter_path] {
+        if path.exists() {
+            return Err(anyhow::anyhow!("{} already exists", path.display()));
+        }
+    }
+    if !from.is_dir() {
+        return Err(anyhow::anyhow!("Source directory not found: {}", from.display()));
+    }
+    let extensions: Vec<String> = extensions
+        .split(',')
+        .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect();
+    if extensions.is_empty() {
+        return Err(anyhow::anyhow!("No extensions given with --include-ext"));
+    }
+    
+    info!("Scanning directory: {}", from.display());
+    info!("Looking for {} files", extensions.iter().map(|ext| format!("*.{}", ext)).collect::<Vec<_>>().join(", "));
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    let mut files = Vec::new();
+    let mut scan_relative_paths = Vec::new();
+    for path in scan_paths(&from, true, &excluded_dirs, respect_gitignore)? {
+        let included = path.is_file()
+            && path.extension().is_some_and(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()));
+        if !included {
+            trace!("Skipping {} (extension)", path.display());
+            continue;
+        }
+        scan_relative_paths.push(path.strip_prefix(&from).unwrap_or(&path).to_path_buf());
+        files.push(PathBuf::from(vcxproj::relative_path(project_dir, &path)));
+    }
+    // Walk order depends on the file system; sorted, the project lists files like Solution Explorer does
+    let mut found: Vec<(PathBuf, PathBuf)> = files.into_iter().zip(scan_relative_paths).collect();
+    found.sort_by_key(|(_, relative)| relative.to_string_lossy().to_lowercase());
+    let (files, scan_relative_paths): (Vec<PathBuf>, Vec<PathBuf>) = found.into_iter().unzip();
+    if files.is_empty() {
+        return Err(anyhow::anyhow!("No {} files found in {}", extensions.join("/"), from.display()));
+    }
+    
+    let guid = uuid::Uuid::new_v4();
+    info!("Creating {} project '{}' {{{}}}", template, name, guid);
+    let mut vcxproj = VcxprojFile { path: project_path.clone(), content: project_template(template, &name, &guid)? };
+    vcxproj.add_source_files(&files)?;
+    let mut filters = FilterFile::empty(filter_path.clone());
+    filters.add_source_files_with_hierarchy(&files, &scan_relative_paths, &FilterMap::default())?;
+    
+    let has_headers = vcxproj.get_items()?.iter().any(|item| item.item_type == "ClInclude" || item.item_type == "QtMoc");
+    let include_dir = format!("$(ProjectDir){}", vcxproj::relative_path(project_dir, &from));
+    let include_configs = if has_headers { vcxproj.add_include_directory(&include_dir)? } else { Vec::new() };
+    
+    info!("Found {} files to add:", files.len());
+    for file in &files {
+        info!("  - {}", file.display());
+    }
+    output::report(Change::FileAdded, files.iter().map(|f| vcxproj::include_path(&f.to_string_lossy())));
+    if !include_configs.is_empty() {
+        info!("📁 Include directory: {}", include_dir);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would create project file: {}", project_path.display());
+        info!("Would create filter file: {}", filter_path.display());
+        return Ok(true);
+    }
+    
+    if !project_dir.as_os_str().is_empty() {
+        std::fs::create_dir_all(project_dir)
+            .with_context(|| format!("Failed to create directory: {}", project_dir.display()))?;
+    }
+    history::record(&project_path, &vcxproj.content)?;
+    history::record(&filter_path, &filters.content)?;
+    history::replace_files(&[
+        (project_path.clone(), Some(vcxproj.content)),
+        (filter_path.clone(), Some(filters.content)),
+    ])?;
+    output::report(Change::FileWritten, [project_path.display(), filter_path.display()]);
+    
+    info!("Created {}", project_path.display());
+    info!("Created {}", filter_path.display());
+    info!("\n✅ Project generated with {} files!", files.len());
+    Ok(true)
+}
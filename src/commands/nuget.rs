@@ -0,0 +1,177 @@
+use anyhow::Result;
+use crate::i18n::{info, tr, warn};
+use crate::nuget::{self, PackagesConfig};
+use crate::vcxproj::VcxprojFile;
+use std::path::{Path, PathBuf};
+
+pub fn list_nuget_packages(project_path: PathBuf) -> Result<()> {
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let config_path = PackagesConfig::path_for(&project_path);
+    let config = if config_path.exists() { Some(PackagesConfig::load(&config_path)?) } else { None };
+    
+    let references = vcxproj.get_package_references();
+    let config_packages = config.as_ref().map(|c| c.packages()).unwrap_or_default();
+    if references.is_empty() && config_packages.is_empty() {
+        info!("No NuGet packages in {}", project_path.display());
+        return Ok(());
+    }
+    
+    if !references.is_empty() {
+        info!("📦 PackageReference:");
+        for (id, version) in &references {
+            info!("  - {} {}", id, version.as_deref().unwrap_or("(no version)"));
+        }
+    }
+    if !config_packages.is_empty() {
+        info!("📦 packages.config:");
+        let imports = vcxproj.get_imports();
+        for (id, version) in &config_packages {
+            let package_imports: Vec<&String> = imports.iter().filter(|p| nuget::is_package_import(p, id)).collect();
+            if package_imports.is_empty() {
+                info!("  - {} {} ⚠️  not imported", id, version);
+            } else {
+                info!("  - {} {}", id, version);
+            }
+            for import in package_imports {
+                info!("      imports {}", import);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Adds a NuGet package: a `<PackageReference>` item, or for packages.config projects an entry there
+/// plus imports of the restored package's build\native .props and .targets
+pub fn add_nuget_package(project_path: PathBuf, id: String, version: String, packages_dir: Option<String>, dryrun: bool) -> Result<bool> {
+    info!("Adding NuGet package {} {} to project: {}", id, version, project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let config_path = PackagesConfig::path_for(&project_path);
+    let mut changes = Vec::new();
+    let mut config = None;
+    
+    if packages_dir.is_none() && !config_path.exists() {
+        let existing = vcxproj.get_package_references().into_iter().find(|(p, _)| p.eq_ignore_ascii_case(&id));
+        match existing {
+            Some((_, Some(current))) if current == version => {}
+            existing => {
+                if existing.is_some() {
+                    vcxproj.remove_items(std::slice::from_ref(&id));
+                }
+                let line = format!("    <PackageReference Include=\"{}\" Version=\"{}\" />", id, version);
+                vcxproj.insert_item_lines("PackageReference", &[line])?;
+                changes.push(tr!("add PackageReference {} {}", id, version));
+            }
+        }
+    } else {
+        // Without --packages-dir, follow where the existing package imports point
+        let packages_dir = match packages_dir {
+            Some(dir) => dir,
+            None => vcxproj
+                .get_imports()
+                .iter()
+                .find_map(|p| p.to_lowercase().find("packages\\").map(|index| p[..index + "packages".len()].to_string()))
+                .ok_or_else(|| anyhow::anyhow!("Pass --packages-dir (e.g. ..\\packages): no existing package imports to follow"))?,
+        };
+        let mut packages_config = if config_path.exists() {
+            PackagesConfig::load(&config_path)?
+        } else {
+            PackagesConfig::new(config_path.clone())
+        };
+        if packages_config.set_package(&id, &version)? {
+            changes.push(tr!("add packages.config entry {} {}", id, version));
+        }
+        
+        // A version change points the imports at the new package folder
+        let build_dir = format!("{}\\{}.{}\\build\\native", packages_dir.trim_end_matches(['\\', '/']), id, version);
+        let props = format!("{}\\{}.props", build_dir, id);
+        let targets = format!("{}\\{}.targets", build_dir, id);
+        let stale: Vec<String> = vcxproj
+            .get_imports()
+            .into_iter()
+            .filter(|p| nuget::is_package_import(p, &id) && *p != props && *p != targets)
+            .collect();
+        for old in vcxproj.remove_imports(|p| stale.iter().any(|s| s == p)) {
+            changes.push(tr!("remove import {}", old));
+        }
+        
+        let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+        let restored = |path: &str| project_dir.join(path.replace('\\', "/")).exists();
+        if !restored(&props) && !restored(&targets) {
+            warn!("⚠️  {} is not restored yet, so only its .targets is imported; run `nuget restore` and add it again if it also has a .props", build_dir);
+        }
+        if restored(&props) && vcxproj.add_import(&props, "Microsoft.Cpp.Default.props", false)? {
+            changes.push(tr!("add import {}", props));
+        }
+        if (restored(&targets) || !restored(&props)) && vcxproj.add_import_to_group(&targets, "ExtensionTargets", true)? {
+            changes.push(tr!("add import {}", targets));
+        }
+        config = Some(packages_config);
+    }
+    
+    if changes.is_empty() {
+        info!("✅ No changes - {} {} is already referenced", id, version);
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        for change in &changes {
+            info!("Would {}", change);
+        }
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    if let Some(config) = &config {
+        config.save()?;
+    }
+    info!("✅ Added NuGet package {} {}:", id, version);
+    for change in &changes {
+        info!("  - {}", change);
+    }
+    Ok(true)
+}
+
+pub fn remove_nuget_package(project_path: PathBuf, id: String, dryrun: bool) -> Result<bool> {
+    info!("Removing NuGet package {} from project: {}", id, project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let mut changes = Vec::new();
+    if vcxproj.get_package_references().iter().any(|(p, _)| p.eq_ignore_ascii_case(&id)) {
+        vcxproj.remove_items(std::slice::from_ref(&id));
+        changes.push(tr!("remove PackageReference {}", id));
+    }
+    for import in vcxproj.remove_imports(|p| nuget::is_package_import(p, &id)) {
+        changes.push(tr!("remove import {}", import));
+    }
+    
+    let config_path = PackagesConfig::path_for(&project_path);
+    let mut config = if config_path.exists() { Some(PackagesConfig::load(&config_path)?) } else { None };
+    if config.as_mut().is_some_and(|config| config.remove_package(&id)) {
+        changes.push(tr!("remove packages.config entry {}", id));
+    }
+    
+    if changes.is_empty() {
+        warn!("⚠️  {} is not referenced by {}", id, project_path.display());
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        for change in &changes {
+            info!("Would {}", change);
+        }
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    if let Some(config) = &config {
+        config.save()?;
+    }
+    info!("✅ Removed NuGet package {}:", id);
+    for change in &changes {
+        info!("  - {}", change);
+    }
+    Ok(true)
+}
@@ -0,0 +1,304 @@
+use anyhow::Result;
+use crate::i18n::{info, warn};
+use crate::output::{self, Change};
+use crate::patch::{self, ListAddition, PatchFile, PatchState, SettingChange};
+use crate::vcxproj::{self, ListPosition, VcxprojFile};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use super::{config_condition, glob_to_regex};
+
+pub fn apply_patch(patch_path: PathBuf, project_path: PathBuf, dryrun: bool) -> Result<bool> {
+    let patch = PatchFile::load(&patch_path)?;
+    let state_path = PatchState::path_for(&project_path, &patch.name);
+    if state_path.exists() {
+        return Err(anyhow::anyhow!(
+            "Patch '{}' is already applied to {} (revert it first)",
+            patch.name,
+            project_path.display()
+        ));
+    }
+    
+    info!("Analyzing project: {}", project_path.display());
+    info!("Applying patch: {}", patch.name);
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let mut state = PatchState { name: patch.name.clone(), ..Default::default() };
+    
+    // Removals run first so a patch can swap one file for another
+    state.removed_items = vcxproj.remove_items(&patch.remove_files);
+    for file in &patch.remove_files {
+        let removed = state.removed_items.iter().any(|(_, lines)| {
+            lines.first()
+                .and_then(|line| vcxproj::attribute_value(line, "Include"))
+                .is_some_and(|include| vcxproj::same_path(include, file))
+        });
+        if !removed {
+            warn!("  ⚠️  {} is not in the project, skipping", file);
+        }
+    }
+    
+    let existing: std::collections::HashSet<String> = vcxproj
+        .get_items()?
+        .into_iter()
+        .map(|item| vcxproj::include_key(&item.include))
+        .collect();
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    let mut new_items = Vec::new();
+    for file in &patch.add_files {
+        let include = vcxproj::include_path(file);
+        if existing.contains(&vcxproj::include_key(&include)) {
+            warn!("  ⚠️  {} is already in the project, skipping", include);
+            continue;
+        }
+        new_items.push((vcxproj::item_type_in(project_dir, &include).to_string(), include.clone()));
+        state.added_files.push(include);
+    }
+    if !new_items.is_empty() {
+        vcxproj.add_labeled_item_group(&patch::item_group_label(&patch.name), &new_items)?;
+    }
+    
+    // Only the configurations that didn't list a value yet are recorded, so reverting leaves the
+    // values the project already had
+    let addition = |value: &String, conditions: Vec<String>| {
+        if conditions.is_empty() {
+            warn!("  ⚠️  {} is already in every configuration, skipping", value);
+            return None;
+        }
+        Some(ListAddition { value: value.clone(), conditions })
+    };
+    for dir in &patch.include_dirs {
+        state.include_dirs.extend(addition(dir, vcxproj.add_include_directory(dir)?));
+    }
+    for dir in &patch.lib_dirs {
+        state.lib_dirs.extend(addition(dir, vcxproj.add_library_directory(dir, None)?));
+    }
+    for lib in &patch.libs {
+        state.libs.extend(addition(lib, vcxproj.add_library_dependency(lib, None, &ListPosition::Back)?));
+    }
+    
+    for setting in &patch.settings {
+        let matcher = glob_to_regex(&setting.glob)?;
+        let condition = setting.config.as_deref().map(config_condition);
+        let items_before = vcxproj.get_items()?;
+        
+        for file in vcxproj.set_item_metadata(&matcher, &setting.props, condition.as_deref())? {
+            let item = items_before.iter().find(|item| item.include == file);
+            for (name, value) in &setting.props {
+                let previous = item
+                    .and_then(|item| item.metadata.iter().find(|m| m.name == *name && m.condition == condition))
+                    .map(|m| m.value.clone());
+                if previous.as_deref() == Some(value.as_str()) {
+                    continue;
+                }
+                state.settings.push(SettingChange {
+                    file: file.clone(),
+                    name: name.clone(),
+                    condition: condition.clone(),
+                    value: value.clone(),
+                    previous,
+                });
+            }
+        }
+    }
+    
+    if state.is_empty() {
+        info!("Nothing to apply - the project already has everything patch '{}' declares", patch.name);
+        return Ok(false);
+    }
+    
+    info!("\n📦 Patch changes:");
+    print_patch_changes(&state, false);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        info!("Would record patch state: {}", state_path.display());
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    state.save(&state_path)?;
+    info!("\n✅ Applied patch '{}' to {}", patch.name, project_path.display());
+    Ok(true)
+}
+
+pub fn revert_patch(patch_path: PathBuf, project_path: PathBuf, dryrun: bool) -> Result<bool> {
+    let patch = PatchFile::load(&patch_path)?;
+    let state_path = PatchState::path_for(&project_path, &patch.name);
+    if !state_path.exists() {
+        return Err(anyhow::anyhow!("Patch '{}' is not applied to {}", patch.name, project_path.display()));
+    }
+    let state = PatchState::load(&state_path)?;
+    
+    info!("Analyzing project: {}", project_path.display());
+    info!("Reverting patch: {}", state.name);
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    
+    // Settings are restored newest first, and only where they still hold the patch's value
+    for change in state.settings.iter().rev() {
+        let matcher = Regex::new(&format!("(?i)^{}$", regex::escape(&change.file.replace('\\', "/"))))?;
+        let current = vcxproj
+            .get_items()?
+            .into_iter()
+            .find(|item| item.include.eq_ignore_ascii_case(&change.file))
+            .and_then(|item| item.metadata.into_iter().find(|m| m.name == change.name && m.condition == change.condition))
+            .map(|m| m.value);
+        if current.as_deref() != Some(change.value.as_str()) {
+            warn!("  ⚠️  {} {} was changed after the patch was applied, leaving it", change.file, change.name);
+            continue;
+        }
+        
+        match &change.previous {
+            Some(previous) => {
+                vcxproj.set_item_metadata(&matcher, &[(change.name.clone(), previous.clone())], change.condition.as_deref())?;
+            }
+            None => {
+                vcxproj.remove_item_metadata_where(|include, metadata| {
+                    matcher.is_match(&include.replace('\\', "/"))
+                        && metadata.name == change.name
+                        && metadata.condition == change.condition
+                })?;
+            }
+        }
+    }
+    
+    if !state.added_files.is_empty() && !vcxproj.remove_labeled_item_group(&patch::item_group_label(&state.name)) {
+        warn!("  ⚠️  The ItemGroup added by the patch is gone, skipping added files");
+    }
+    
+    let lists = [
+        ("AdditionalIncludeDirectories", &state.include_dirs),
+        ("AdditionalLibraryDirectories", &state.lib_dirs),
+        ("AdditionalDependencies", &state.libs),
+    ];
+    for (element, additions) in lists {
+        for addition in additions {
+            let removed: usize = addition
+                .conditions
+                .iter()
+                .map(|condition| vcxproj.remove_list_value(element, &addition.value, Some(&vcxproj::condition_config(condition))))
+                .sum();
+            if removed == 0 {
+                warn!("  ⚠️  {} is no longer in {}, skipping", addition.value, element);
+            }
+        }
+    }
+    
+    let existing: std::collections::HashSet<String> = vcxproj
+        .get_items()?
+        .into_iter()
+        .map(|item| item.include.to_lowercase())
+        .collect();
+    for (item_type, lines) in &state.removed_items {
+        let include = lines.first().and_then(|line| vcxproj::attribute_value(line, "Include")).unwrap_or_default();
+        if existing.contains(&include.to_lowercase()) {
+            warn!("  ⚠️  {} was added back after the patch was applied, skipping", include);
+            continue;
+        }
+        vcxproj.insert_item_lines(item_type, lines)?;
+    }
+    
+    info!("\n📦 Reverting:");
+    print_patch_changes(&state, true);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        info!("Would remove patch state: {}", state_path.display());
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    PatchState::remove(&state_path)?;
+    info!("\n✅ Reverted patch '{}' from {}", state.name, project_path.display());
+    Ok(true)
+}
+
+/// Lists the changes recorded in a patch state, as applied or (with `reverting`) as undone
+fn print_patch_changes(state: &PatchState, reverting: bool) {
+    let (added, removed) = if reverting { ("-", "+") } else { ("+", "-") };
+    let removed_includes = state
+        .removed_items
+        .iter()
+        .filter_map(|(_, lines)| lines.first().and_then(|line| vcxproj::attribute_value(line, "Include")));
+    let (added_change, removed_change) = if reverting {
+        (Change::FileRemoved, Change::FileAdded)
+    } else {
+        (Change::FileAdded, Change::FileRemoved)
+    };
+    output::report(added_change, &state.added_files);
+    output::report(removed_change, removed_includes);
+    output::report(Change::FileModified, state.settings.iter().map(|change| &change.file));
+    for file in &state.added_files {
+        info!("  {} {}", added, file);
+    }
+    for (_, lines) in &state.removed_items {
+        let include = lines.first().and_then(|line| vcxproj::attribute_value(line, "Include")).unwrap_or_default();
+        info!("  {} {}", removed, include);
+    }
+    for dir in &state.include_dirs {
+        info!("  {} include dir: {}", added, dir.value);
+    }
+    for dir in &state.lib_dirs {
+        info!("  {} library dir: {}", added, dir.value);
+    }
+    for lib in &state.libs {
+        info!("  {} library: {}", added, lib.value);
+    }
+    for change in &state.settings {
+        let previous = change.previous.as_deref().unwrap_or("unset");
+        let (from, to) = if reverting { (change.value.as_str(), previous) } else { (previous, change.value.as_str()) };
+        info!("  ~ {}: {} = {} (was {})", change.file, change.name, to, from);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::history::tests::TempDir;
+    use std::fs;
+    
+    const PATCH: &str = r#"
+name = "tracing"
+add_files = ["src/trace.cpp"]
+remove_files = ["src/helper.c"]
+include_dirs = ["third_party/tracy/public"]
+libs = ["TracyClient.lib"]
+
+[[settings]]
+glob = "repo1/**/*.c"
+config = "Debug|x64"
+props = { PreprocessorDefinitions = "TRACY_ENABLE;%(PreprocessorDefinitions)" }
+"#;
+    
+    #[test]
+    fn revert_undoes_everything_apply_changed() {
+        let dir = TempDir::new("patch");
+        let project = dir.0.join("test.vcxproj");
+        let patch = dir.0.join("tracing.toml");
+        let original = include_str!("../../samples/test.vcxproj");
+        fs::write(&project, original).unwrap();
+        fs::write(&patch, PATCH).unwrap();
+        
+        assert!(apply_patch(patch.clone(), project.clone(), false).unwrap());
+        let applied = fs::read_to_string(&project).unwrap();
+        assert!(applied.contains(r#"<ClCompile Include="src\trace.cpp" />"#));
+        assert!(!applied.contains(r#"<ClCompile Include="src\helper.c" />"#));
+        assert!(applied.contains("third_party/tracy/public"));
+        assert!(applied.contains("TracyClient.lib"));
+        assert!(applied.contains("TRACY_ENABLE"));
+        assert!(PatchState::path_for(&project, "tracing").exists());
+        assert!(apply_patch(patch.clone(), project.clone(), false).is_err(), "a patch applies only once");
+        
+        assert!(revert_patch(patch, project.clone(), false).unwrap());
+        // Removed items come back at the end of their ItemGroup, so compare the lines in any order
+        let sorted_lines = |text: &str| {
+            let mut lines: Vec<String> = text.lines().map(str::to_string).collect();
+            lines.sort();
+            lines
+        };
+        assert_eq!(sorted_lines(&fs::read_to_string(&project).unwrap()), sorted_lines(original));
+        assert!(!PatchState::path_for(&project, "tracing").exists());
+    }
+}
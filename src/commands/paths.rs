@@ -0,0 +1,103 @@
+use anyhow::{Context, Result};
+use crate::doctor;
+use crate::i18n::{info, warn};
+use crate::output::{self, Change};
+use crate::pipe;
+use crate::vcxproj::{self, FilterFile, VcxprojFile};
+use std::path::{Path, PathBuf};
+
+/// Elements holding `;`-separated directories that `normalize-paths` rewrites
+const DIRECTORY_LISTS: &[&str] = &["AdditionalIncludeDirectories", "AdditionalLibraryDirectories", "IncludePath", "LibraryPath"];
+
+/// Rewrites the absolute paths of a project and its filters file relative to the project directory,
+/// or behind `$(ProjectDir)`/`$(SolutionDir)` with `base`, so the project builds wherever it is
+/// checked out. Paths on another drive, and Windows paths when running elsewhere, are left as they are.
+pub fn normalize_paths(project_path: PathBuf, base: Option<String>, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    let project_dir = project_path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let (base_dir, prefix) = match base.as_deref() {
+        Some("$(SolutionDir)") => {
+            let project_dir = std::fs::canonicalize(project_dir)
+                .with_context(|| format!("Failed to resolve project directory: {}", project_dir.display()))?;
+            let solution_dir = project_dir
+                .ancestors()
+                .find(|dir| {
+                    std::fs::read_dir(dir).is_ok_and(|entries| {
+                        entries.flatten().any(|entry| entry.path().extension().is_some_and(|ext| ext.eq_ignore_ascii_case("sln")))
+                    })
+                })
+                .ok_or_else(|| anyhow::anyhow!("No .sln file found above {} for $(SolutionDir)", project_dir.display()))?;
+            info!("Solution directory: {}", solution_dir.display());
+            (solution_dir.to_path_buf(), "$(SolutionDir)")
+        }
+        Some(prefix) => (project_dir.to_path_buf(), prefix),
+        None => (project_dir.to_path_buf(), ""),
+    };
+    
+    let mut unresolved: Vec<String> = Vec::new();
+    let mut rewrite = |path: &str| -> Option<String> {
+        if !doctor::is_absolute(path) {
+            return None;
+        }
+        let trailing = if path.ends_with(['\\', '/']) { "\\" } else { "" };
+        let relative = Path::new(path)
+            .is_absolute()
+            .then(|| vcxproj::relative_path(&base_dir, Path::new(path.trim_end_matches(['\\', '/']))))
+            .filter(|relative| !doctor::is_absolute(relative));
+        match relative {
+            Some(relative) if relative.is_empty() => Some(if prefix.is_empty() { ".".to_string() } else { prefix.to_string() }),
+            Some(relative) => Some(format!("{}{}{}", prefix, relative, trailing)),
+            None => {
+                if !unresolved.iter().any(|p| p == path) {
+                    unresolved.push(path.to_string());
+                }
+                None
+            }
+        }
+    };
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let (content, rewritten) = vcxproj::rewrite_paths(&vcxproj.content, DIRECTORY_LISTS, &mut rewrite);
+    vcxproj.content = content;
+    let filter_path = vcxproj::filters_path(&project_path);
+    let mut filter_file = if pipe::exists(&filter_path) { Some(FilterFile::load(&filter_path)?) } else { None };
+    let mut filters_changed = false;
+    if let Some(filter_file) = &mut filter_file {
+        let (content, filter_rewritten) = vcxproj::rewrite_paths(&filter_file.content, &[], &mut rewrite);
+        filter_file.content = content;
+        filters_changed = !filter_rewritten.is_empty();
+    }
+    
+    for path in &unresolved {
+        warn!("⚠️  Cannot make {} relative to {} (another drive, or not a path on this system)", path, base_dir.display());
+    }
+    if rewritten.is_empty() && !filters_changed {
+        info!("✅ No changes - no absolute paths to rewrite");
+        return Ok(false);
+    }
+    
+    let mut seen: Vec<&(String, String)> = Vec::new();
+    info!("\n📂 Paths rewritten:");
+    for change in &rewritten {
+        if !seen.contains(&change) {
+            info!("  {} → {}", change.0, change.1);
+            seen.push(change);
+        }
+    }
+    let items: Vec<String> = vcxproj.get_items()?.into_iter().map(|item| item.include).collect();
+    output::report(Change::FileModified, seen.iter().filter(|(_, new)| items.contains(new)).map(|(_, new)| new));
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        if filters_changed {
+            info!("Would update filter file: {}", filter_path.display());
+        }
+        return Ok(true);
+    }
+    
+    vcxproj.save_with_filters(filter_file.as_ref())?;
+    info!("\n✅ Rewrote {} absolute paths in {}", rewritten.len(), project_path.display());
+    Ok(true)
+}
@@ -0,0 +1,369 @@
+use anyhow::{Context, Result};
+use crate::i18n::{info, warn};
+use crate::output::{self, Change};
+use crate::properties;
+use crate::props;
+use crate::sln::SolutionFile;
+use crate::vcxproj::{self, VcxprojFile};
+use log::debug;
+use std::path::{Path, PathBuf};
+use super::{config_condition, group_config};
+
+pub fn list_property_sheets(project_path: PathBuf) -> Result<()> {
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let sheets = vcxproj.get_property_sheets();
+    if sheets.is_empty() {
+        info!("No property sheets imported by {}", project_path.display());
+        return Ok(());
+    }
+    
+    let mut current = None;
+    for (condition, sheet) in &sheets {
+        let config = group_config(condition.as_deref().unwrap_or_default());
+        if current.as_ref() != Some(&config) {
+            info!("📄 {}:", config);
+            current = Some(config);
+        }
+        // Visual Studio imports the per-user sheet in every project
+        if sheet.contains("$(UserRootDir)") {
+            info!("  - {} (user settings)", sheet);
+        } else {
+            info!("  - {}", sheet);
+        }
+    }
+    Ok(())
+}
+
+pub fn add_property_sheet(project_path: PathBuf, sheet: String, config: Option<String>, dryrun: bool) -> Result<bool> {
+    info!("Importing property sheet '{}' in project: {}", sheet, project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    match properties::Properties::for_project(&vcxproj, None).resolve_path(project_dir, &sheet) {
+        Ok(path) if !path.exists() => warn!("⚠️  {} does not exist (paths are relative to the project)", sheet),
+        Err(unknown) => debug!("Not checking {}: unknown properties {}", sheet, unknown.join(", ")),
+        Ok(_) => {}
+    }
+    let changed = vcxproj.add_property_sheet(&sheet, config.as_deref())?;
+    if changed.is_empty() {
+        info!("✅ No changes - every matching configuration already imports {}", sheet);
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        for condition in &changed {
+            info!("Would import in: {}", group_config(condition));
+        }
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    output::report(Change::ConfigModified, changed.iter().map(|c| group_config(c)));
+    info!("✅ Imported {} in {} property sheet groups:", sheet, changed.len());
+    for condition in &changed {
+        info!("  - {}", group_config(condition));
+    }
+    Ok(true)
+}
+
+pub fn remove_property_sheet(project_path: PathBuf, sheet: String, config: Option<String>, dryrun: bool) -> Result<bool> {
+    info!("Removing property sheet '{}' from project: {}", sheet, project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let changed = vcxproj.remove_property_sheet(&sheet, config.as_deref());
+    if changed.is_empty() {
+        warn!("⚠️  {} is not imported by any matching configuration", sheet);
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        for condition in &changed {
+            info!("Would remove from: {}", group_config(condition));
+        }
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    output::report(Change::ConfigModified, changed.iter().map(|c| group_config(c)));
+    info!("✅ Removed {} from {} property sheet groups:", sheet, changed.len());
+    for condition in &changed {
+        info!("  - {}", group_config(condition));
+    }
+    Ok(true)
+}
+
+/// Moves settings out of the project's ItemDefinitionGroups into a new property sheet that the
+/// same configurations then import, so other projects can share them
+pub fn create_property_sheet(project_path: PathBuf, sheet: String, settings: Vec<String>, config: Option<String>, dryrun: bool) -> Result<bool> {
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    let sheet_path = project_dir.join(sheet.replace('\\', "/"));
+    if sheet_path.exists() {
+        return Err(anyhow::anyhow!("{} already exists; use `props add` to import it", sheet_path.display()));
+    }
+    info!("Extracting {} settings into property sheet: {}", settings.len(), sheet_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let configs: Vec<String> = vcxproj
+        .get_configurations()
+        .into_iter()
+        .filter(|c| config.as_deref().is_none_or(|selector| vcxproj::config_matches(c, selector)))
+        .collect();
+    if configs.is_empty() {
+        return Err(anyhow::anyhow!("No configurations match '{}'", config.unwrap_or_default()));
+    }
+    
+    // Every selected configuration must agree on a value, or the sheet would change the build
+    let tool_settings = vcxproj.get_tool_settings();
+    let mut extracted = Vec::new();
+    for setting in &settings {
+        let (tool, name) = props::parse_setting_name(setting)?;
+        let mut values: Vec<(&str, &str)> = Vec::new();
+        for config in &configs {
+            let value = tool_settings
+                .iter()
+                .filter(|s| s.tool == tool && s.name == name)
+                .find(|s| s.condition.as_deref().is_none_or(|c| vcxproj::condition_config(c) == config.as_str()))
+                .ok_or_else(|| anyhow::anyhow!("{}.{} is not set in {}", tool, name, config))?;
+            values.push((config, &value.value));
+        }
+        if values.iter().any(|(_, value)| *value != values[0].1) {
+            let listing: Vec<String> = values.iter().map(|(config, value)| format!("  {}: {}", config, value)).collect();
+            return Err(anyhow::anyhow!(
+                "{}.{} differs between configurations; pick one with --config:\n{}",
+                tool,
+                name,
+                listing.join("\n")
+            ));
+        }
+        extracted.push(vcxproj::ToolSetting { condition: None, tool: tool.clone(), name: name.clone(), value: values[0].1.to_string() });
+        vcxproj.remove_tool_setting(&tool, &name, config.as_deref());
+    }
+    let imported = vcxproj.add_property_sheet(&sheet, config.as_deref())?;
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would create {} with:", sheet_path.display());
+        for setting in &extracted {
+            info!("  {}.{} = {}", setting.tool, setting.name, setting.value);
+        }
+        info!("Would import it in {} configurations instead", imported.len());
+        return Ok(true);
+    }
+    
+    vcxproj::write_file(&sheet_path, &props::sheet_content(&extracted))?;
+    vcxproj.save()?;
+    output::report(Change::ConfigModified, configs.iter());
+    info!("✅ Created {} with {} settings:", sheet_path.display(), extracted.len());
+    for setting in &extracted {
+        info!("  - {}.{} = {}", setting.tool, setting.name, setting.value);
+    }
+    info!("Imported it in {} property sheet groups and removed the settings from {} configurations", imported.len(), configs.len());
+    Ok(true)
+}
+
+/// List settings `refactor extract-props` looks for in every project, and whether their entries are paths
+const SHARED_LIST_SETTINGS: &[(&str, &str, bool)] = &[
+    ("ClCompile", "AdditionalIncludeDirectories", true),
+    ("ClCompile", "PreprocessorDefinitions", false),
+    ("Link", "AdditionalLibraryDirectories", true),
+    ("Link", "AdditionalDependencies", false),
+];
+
+/// Entries of one list setting shared by every project that has the configuration
+struct SharedEntries {
+    config: String,
+    tool: &'static str,
+    name: &'static str,
+    /// The entries as written in the sheet
+    sheet_entries: Vec<String>,
+    /// Per project index, the entries as written in that project
+    project_entries: Vec<(usize, Vec<String>)>,
+}
+
+/// Collapses "." and ".." in a path without touching the file system
+pub fn normalize_lexically(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            std::path::Component::CurDir => {}
+            std::path::Component::ParentDir => {
+                normalized.pop();
+            }
+            other => normalized.push(other),
+        }
+    }
+    normalized
+}
+
+/// Finds the include dirs, defines, lib dirs and libs that all projects share per configuration,
+/// moves them into a new property sheet, and imports that sheet in every project.
+/// Only lists that inherit (`%(...)`) are considered, so removing the shared entries keeps the build the same.
+pub fn extract_shared_props(solution: Option<PathBuf>, projects: Vec<PathBuf>, sheet: PathBuf, dryrun: bool) -> Result<bool> {
+    use std::collections::{BTreeSet, HashMap};
+    
+    let mut project_paths = projects;
+    if let Some(solution) = &solution {
+        project_paths.extend(SolutionFile::load(solution)?.vcxproj_paths());
+    }
+    if project_paths.len() < 2 {
+        return Err(anyhow::anyhow!("Need at least two projects: pass --solution or several --project"));
+    }
+    if sheet.exists() {
+        return Err(anyhow::anyhow!("{} already exists", sheet.display()));
+    }
+    info!("Looking for settings shared by {} projects", project_paths.len());
+    
+    let sheet_dir = sheet.parent().filter(|d| !d.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let mut vcxprojs = Vec::new();
+    for path in &project_paths {
+        vcxprojs.push(VcxprojFile::load(path)?);
+    }
+    
+    // An entry's identity: relative paths are resolved against their project, so "..\\include" in
+    // two projects only matches when both name the same folder
+    let entry_key = |project_dir: &Path, entry: &str, is_path: bool| -> String {
+        let native = entry.replace('\\', "/");
+        if is_path && !entry.contains("$(") && !Path::new(&native).is_absolute() && entry.get(1..2) != Some(":") {
+            let dir = std::fs::canonicalize(project_dir).unwrap_or_else(|_| project_dir.to_path_buf());
+            normalize_lexically(&dir.join(native)).display().to_string().to_lowercase()
+        } else if is_path {
+            native.trim_end_matches('/').to_lowercase()
+        } else {
+            entry.to_string()
+        }
+    };
+    
+    // (configuration, tool, name) -> per project, its entries keyed by identity
+    type Lists = HashMap<(String, &'static str, &'static str), Vec<(String, String)>>;
+    let mut configs: Vec<String> = Vec::new();
+    let mut project_lists: Vec<Lists> = Vec::new();
+    for vcxproj in &vcxprojs {
+        let project_dir = vcxproj.path.parent().unwrap_or_else(|| Path::new("."));
+        for config in vcxproj.get_configurations() {
+            if !configs.contains(&config) {
+                configs.push(config);
+            }
+        }
+        let mut lists = Lists::new();
+        for setting in vcxproj.get_tool_settings() {
+            let Some(&(tool, name, is_path)) = SHARED_LIST_SETTINGS.iter().find(|(t, n, _)| *t == setting.tool && *n == setting.name) else {
+                continue;
+            };
+            let (Some(condition), true) = (&setting.condition, setting.value.contains(&format!("%({})", name))) else {
+                continue;
+            };
+            let entries = setting
+                .value
+                .split(';')
+                .map(str::trim)
+                .filter(|e| !e.is_empty() && !e.starts_with("%("))
+                .map(|e| (entry_key(project_dir, e, is_path), e.to_string()))
+                .collect();
+            lists.insert((vcxproj::condition_config(condition).to_string(), tool, name), entries);
+        }
+        project_lists.push(lists);
+    }
+    
+    let mut shared = Vec::new();
+    for config in &configs {
+        for &(tool, name, is_path) in SHARED_LIST_SETTINGS {
+            let key = (config.clone(), tool, name);
+            let holders: Vec<usize> = (0..vcxprojs.len())
+                .filter(|&i| vcxprojs[i].get_configurations().contains(config))
+                .collect();
+            if holders.len() < 2 || holders.iter().any(|&i| !project_lists[i].contains_key(&key)) {
+                continue;
+            }
+            
+            let first = &project_lists[holders[0]][&key];
+            let common: Vec<&(String, String)> = first
+                .iter()
+                .filter(|(id, _)| holders.iter().all(|&i| project_lists[i][&key].iter().any(|(other, _)| other == id)))
+                .collect();
+            if common.is_empty() {
+                continue;
+            }
+            
+            let sheet_entries = common
+                .iter()
+                .map(|(id, text)| {
+                    // Resolved relative paths are written relative to the sheet itself
+                    if is_path && Path::new(id).is_absolute() && !text.contains("$(") && text.get(1..2) != Some(":") && !text.starts_with(['\\', '/']) {
+                        format!("$(MSBuildThisFileDirectory){}", vcxproj::relative_path(sheet_dir, Path::new(id)))
+                    } else {
+                        text.clone()
+                    }
+                })
+                .collect();
+            let project_entries = holders
+                .iter()
+                .map(|&i| {
+                    let texts = project_lists[i][&key]
+                        .iter()
+                        .filter(|(id, _)| common.iter().any(|(shared, _)| shared == id))
+                        .map(|(_, text)| text.clone())
+                        .collect();
+                    (i, texts)
+                })
+                .collect();
+            shared.push(SharedEntries { config: config.clone(), tool, name, sheet_entries, project_entries });
+        }
+    }
+    
+    if shared.is_empty() {
+        info!("✅ No changes - the projects share no include dirs, defines, lib dirs or libs");
+        return Ok(false);
+    }
+    
+    let mut sheet_settings = Vec::new();
+    let mut touched = BTreeSet::new();
+    for entries in &shared {
+        info!(
+            "🔗 {} {}.{}: {} ({} projects)",
+            entries.config,
+            entries.tool,
+            entries.name,
+            entries.sheet_entries.join(";"),
+            entries.project_entries.len()
+        );
+        sheet_settings.push(vcxproj::ToolSetting {
+            condition: Some(config_condition(&entries.config)),
+            tool: entries.tool.to_string(),
+            name: entries.name.to_string(),
+            value: format!("{};%({})", entries.sheet_entries.join(";"), entries.name),
+        });
+        for (i, texts) in &entries.project_entries {
+            for text in texts {
+                vcxprojs[*i].remove_list_value(entries.name, text, Some(&entries.config));
+            }
+            touched.insert(*i);
+        }
+    }
+    for &i in &touched {
+        let project_dir = vcxprojs[i].path.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+        vcxprojs[i].add_property_sheet(&vcxproj::relative_path(&project_dir, &sheet), None)?;
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would create: {}", sheet.display());
+        for &i in &touched {
+            info!("Would update: {}", vcxprojs[i].path.display());
+        }
+        return Ok(true);
+    }
+    
+    // Projects first: the history entry lives next to the first file written, where `undo` looks
+    for &i in &touched {
+        vcxprojs[i].save()?;
+    }
+    if let Some(dir) = sheet.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    vcxproj::write_file(&sheet, &props::sheet_content(&sheet_settings))?;
+    info!("\n✅ Created {} and imported it in {} projects", sheet.display(), touched.len());
+    Ok(true)
+}
@@ -0,0 +1,104 @@
+use anyhow::{Context, Result};
+use crate::i18n::{info, warn};
+use crate::output::{self, Change};
+use crate::pipe;
+use crate::vcxproj::{self, FilterFile, VcxprojFile};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use super::filters::{DEFAULT_FILTERS, RESOURCE_EXTENSIONS};
+
+/// Adds a resource script with the files it references and/or a manifest, each as the item type
+/// Visual Studio gives it, in `filter` (headers in "Header Files"). Files already in the project are left alone.
+pub fn add_resources(project_path: PathBuf, rc: Option<PathBuf>, manifest: Option<PathBuf>, filter: String, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    let mut files = Vec::new();
+    if let Some(rc) = rc {
+        let content = read_resource_script(&rc)?;
+        let rc_dir = rc.parent().unwrap_or_else(|| Path::new("."));
+        files.push(rc.clone());
+        for reference in resource_script_references(&content) {
+            let path = rc_dir.join(&reference);
+            if path.exists() {
+                files.push(path);
+            } else if !reference.to_lowercase().ends_with(".h") {
+                // Headers like winres.h come from the SDK; other missing files would break the build
+                warn!("⚠️  {} references {}, which does not exist", rc.display(), reference);
+            }
+        }
+    }
+    files.extend(manifest);
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let filter_path = vcxproj::filters_path(&project_path);
+    let mut filter_file = if pipe::exists(&filter_path) { Some(FilterFile::load(&filter_path)?) } else { None };
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    let items = vcxproj.get_items()?;
+    let mut added = Vec::new();
+    for file in &files {
+        let include = vcxproj::relative_path(project_dir, file);
+        if items.iter().any(|item| vcxproj::same_path(&item.include, &include)) || added.contains(&include) {
+            info!("  ✔️  {} is already in the project", include);
+            continue;
+        }
+        let item_type = vcxproj::item_type_for(&include);
+        vcxproj.insert_item_lines(item_type, &[format!("    <{} Include=\"{}\" />", item_type, include)])?;
+        let (file_filter, extensions) = match DEFAULT_FILTERS.iter().find(|(t, _, _)| *t == item_type) {
+            Some(&(_, header_filter, extensions)) if item_type == "ClInclude" => (header_filter.to_string(), extensions),
+            _ => (filter.clone(), RESOURCE_EXTENSIONS),
+        };
+        info!("  ➕ {} ({}, {})", include, item_type, file_filter);
+        if let Some(filter_file) = &mut filter_file {
+            for created in filter_file.ensure_filter(&file_filter, Some(extensions)) {
+                output::report(Change::FilterCreated, [&created]);
+            }
+            filter_file.set_file_filter(item_type, &include, &file_filter);
+        }
+        added.push(include);
+    }
+    if added.is_empty() {
+        info!("✅ No changes - the resources are already in the project");
+        return Ok(false);
+    }
+    output::report(Change::FileAdded, &added);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save_with_filters(filter_file.as_ref())?;
+    info!("\n✅ Added {} resource files to {}", added.len(), project_path.display());
+    Ok(true)
+}
+
+/// The text of a resource script, which Visual Studio saves as UTF-16 with a byte order mark
+fn read_resource_script(path: &Path) -> Result<String> {
+    let bytes = std::fs::read(path).with_context(|| format!("Failed to read {}", path.display()))?;
+    let utf16 = |bytes: &[u8], from: fn([u8; 2]) -> u16| {
+        let units: Vec<u16> = bytes.chunks_exact(2).map(|pair| from([pair[0], pair[1]])).collect();
+        String::from_utf16_lossy(&units)
+    };
+    Ok(match bytes.as_slice() {
+        [0xFF, 0xFE, rest @ ..] => utf16(rest, u16::from_le_bytes),
+        [0xFE, 0xFF, rest @ ..] => utf16(rest, u16::from_be_bytes),
+        _ => String::from_utf8_lossy(&bytes).trim_start_matches('\u{feff}').to_string(),
+    })
+}
+
+/// The files a resource script includes (`#include "resource.h"`) or embeds as icons, bitmaps and
+/// cursors (`IDI_APP ICON "res\\app.ico"`), relative to the script
+fn resource_script_references(content: &str) -> Vec<String> {
+    let include = Regex::new(r#"^\s*#\s*include\s+"([^"]+)""#).unwrap();
+    let resource = Regex::new(r#"^\s*\w+\s+(?:ICON|BITMAP|CURSOR)\s+(?:\w+\s+)*"([^"]+)""#).unwrap();
+    let mut references: Vec<String> = Vec::new();
+    for line in content.lines() {
+        if let Some(captures) = include.captures(line).or_else(|| resource.captures(line)) {
+            let reference = captures[1].replace("\\\\", "/").replace('\\', "/");
+            if !references.contains(&reference) {
+                references.push(reference);
+            }
+        }
+    }
+    references
+}
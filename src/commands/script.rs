@@ -0,0 +1,198 @@
+use anyhow::{Context, Result};
+use clap::Parser;
+use crate::cli::{BackupsAction, Cli, Commands, FiltersAction};
+use crate::history;
+use crate::i18n::{error, info};
+use crate::run;
+use crate::vcxproj;
+use std::path::PathBuf;
+
+pub fn run_script(script_path: PathBuf, dryrun: bool) -> Result<bool> {
+    let content = std::fs::read_to_string(&script_path)
+        .with_context(|| format!("Failed to read script: {}", script_path.display()))?;
+    let script: toml::Table = content
+        .parse()
+        .with_context(|| format!("Invalid script: {}", script_path.display()))?;
+    let project = script.get("project").and_then(toml::Value::as_str);
+    let steps = script
+        .get("steps")
+        .and_then(toml::Value::as_array)
+        .ok_or_else(|| anyhow::anyhow!("{} has no [[steps]]", script_path.display()))?;
+    
+    // Every step is parsed up front so a typo fails before anything is written
+    let mut commands = Vec::new();
+    for (index, step) in steps.iter().enumerate() {
+        let args = script_step_args(step, project, dryrun).with_context(|| format!("Invalid step {}", index + 1))?;
+        let cli = Cli::try_parse_from(std::iter::once("vsprojm".to_string()).chain(args.iter().cloned()))
+            .map_err(|e| anyhow::anyhow!("Invalid step {} ({}):\n{}", index + 1, args.join(" "), e))?;
+        if let Some(reason) = script_step_restriction(&cli.command) {
+            return Err(anyhow::anyhow!("Step {} ({}): {}", index + 1, args[0], reason));
+        }
+        commands.push((args, cli.command, cli.force));
+    }
+    
+    info!("Running script: {} ({} steps)", script_path.display(), commands.len());
+    let total = commands.len();
+    let forced = vcxproj::forced();
+    let mut changes_planned = false;
+    for (index, (args, command, force)) in commands.into_iter().enumerate() {
+        info!("\n▶ Step {}/{}: {}", index + 1, total, args.join(" "));
+        vcxproj::set_force(forced || force);
+        match run(command) {
+            Ok(planned) => changes_planned |= planned,
+            Err(err) => {
+                let restored = history::rollback()?;
+                error!("\n❌ Step {} failed - restored {} files, nothing was changed", index + 1, restored.len());
+                return Err(err.context(format!("Script step {} ({}) failed", index + 1, args[0])));
+            }
+        }
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        return Ok(changes_planned);
+    }
+    
+    info!("\n✅ Script completed - {} steps applied", total);
+    Ok(true)
+}
+
+/// Why a command can't be a script step. A failed script is restored from the journal of its
+/// writes, so a step must not write around it, and the steps run unattended.
+fn script_step_restriction(command: &Commands) -> Option<&'static str> {
+    match command {
+        Commands::Run { .. } => Some("scripts cannot run other scripts"),
+        Commands::Watch { .. } => Some("watch never finishes"),
+        Commands::Undo { .. }
+        | Commands::Redo { .. }
+        | Commands::Backups { action: BackupsAction::Prune { .. } | BackupsAction::Restore { .. } } => {
+            Some("the history cannot be changed from a script")
+        }
+        Commands::Filters { action: FiltersAction::Export { .. } } => {
+            Some("the exported layout is written outside the journal, so it could not be restored")
+        }
+        Commands::Delete { interactive: true, .. } => Some("--interactive cannot be answered in a script"),
+        Commands::Delete { yes: false, .. } | Commands::Rename { yes: false, .. } => {
+            Some("it may ask for confirmation, add `yes = true`")
+        }
+        _ => None,
+    }
+}
+
+/// Converts one script step into command-line arguments. A step is a table with a single
+/// `command = arguments` entry where the arguments are either a table of long flags
+/// (`{ extension = "cpp", yes = true, prop = ["a", "b"] }`) or an array of raw arguments
+/// (`["apply", "tracing.toml"]`). The script's `project` is passed to every command that takes
+/// `--project` unless the step names its own, and `--dryrun` to every command that takes it when
+/// the script is previewed.
+fn script_step_args(step: &toml::Value, project: Option<&str>, dryrun: bool) -> Result<Vec<String>> {
+    use toml::Value;
+    
+    let (name, value) = step
+        .as_table()
+        .filter(|t| t.len() == 1)
+        .and_then(|t| t.iter().next())
+        .ok_or_else(|| anyhow::anyhow!("each step must be a single `command = arguments` entry"))?;
+    
+    let mut args = vec![name.to_string()];
+    match value {
+        Value::Array(items) => {
+            for item in items {
+                args.push(toml_scalar(item)?);
+            }
+        }
+        Value::Table(flags) => {
+            for (key, value) in flags {
+                let key = key.replace('_', "-");
+                match value {
+                    Value::Boolean(true) => args.push(format!("--{}", key)),
+                    Value::Boolean(false) => {}
+                    Value::Array(values) => {
+                        for value in values {
+                            args.push(format!("--{}", key));
+                            args.push(toml_scalar(value)?);
+                        }
+                    }
+                    value => {
+                        args.push(format!("--{}", key));
+                        args.push(toml_scalar(value)?);
+                    }
+                }
+            }
+        }
+        value => args.push(toml_scalar(value)?),
+    }
+    
+    // Walk down nested subcommands (e.g. `patch apply`) to see which arguments the command takes
+    let mut command = <Cli as clap::CommandFactory>::command();
+    for arg in &args {
+        match command.find_subcommand(arg) {
+            Some(sub) => command = sub.clone(),
+            None => break,
+        }
+    }
+    let takes = |id: &str| command.get_arguments().any(|a| a.get_id() == id);
+    let has_project = args.iter().any(|a| a == "--project" || a == "-p");
+    if let (Some(project), false, true) = (project, has_project, takes("project")) {
+        args.push("--project".to_string());
+        args.push(project.to_string());
+    }
+    if dryrun && takes("dryrun") && !args.iter().any(|a| a == "--dryrun") {
+        args.push("--dryrun".to_string());
+    }
+    
+    Ok(args)
+}
+
+fn toml_scalar(value: &toml::Value) -> Result<String> {
+    match value {
+        toml::Value::String(s) => Ok(s.clone()),
+        toml::Value::Integer(n) => Ok(n.to_string()),
+        toml::Value::Float(n) => Ok(n.to_string()),
+        toml::Value::Boolean(b) => Ok(b.to_string()),
+        _ => Err(anyhow::anyhow!("expected a string, number or boolean argument")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::tests::with_stack;
+    
+    fn step_args(step: &str, project: Option<&str>, dryrun: bool) -> Result<Vec<String>> {
+        script_step_args(&toml::Value::Table(step.parse().unwrap()), project, dryrun)
+    }
+    
+    #[test]
+    fn flags_become_long_options() {
+        with_stack(|| {
+            let args = step_args(r#"add = { directory = "src", extension = ["c", "h"], recursive = false, yes = true }"#, Some("App.vcxproj"), false).unwrap();
+            assert_eq!(args, ["add", "--directory", "src", "--extension", "c", "--extension", "h", "--yes", "--project", "App.vcxproj"]);
+            
+            let args = step_args("view = { files_only = true, level = 2 }", None, false).unwrap();
+            assert_eq!(args, ["view", "--files-only", "--level", "2"]);
+        });
+    }
+    
+    #[test]
+    fn project_and_dryrun_go_to_commands_that_take_them() {
+        with_stack(|| {
+            // `patch apply` is found below `patch`, so it gets both
+            let args = step_args(r#"patch = ["apply", "tracing.toml"]"#, Some("App.vcxproj"), true).unwrap();
+            assert_eq!(args, ["patch", "apply", "tracing.toml", "--project", "App.vcxproj", "--dryrun"]);
+            
+            // A step's own project wins, and `view` has no --dryrun
+            let args = step_args(r#"view = { project = "Other.vcxproj" }"#, Some("App.vcxproj"), true).unwrap();
+            assert_eq!(args, ["view", "--project", "Other.vcxproj"]);
+        });
+    }
+    
+    #[test]
+    fn malformed_steps_are_rejected() {
+        with_stack(|| {
+            assert!(step_args(r#"add = { extension = "c" }
+view = {}"#, None, false).is_err());
+            assert!(step_args("add = { extension = { c = true } }", None, false).is_err());
+        });
+    }
+}
@@ -0,0 +1,363 @@
+use anyhow::{Context, Result};
+use crate::i18n::{info, tr, warn};
+use crate::output::{self, Change};
+use crate::vcxproj::{self, ListPosition, VcxprojFile};
+use regex::Regex;
+use std::path::PathBuf;
+use super::{config_condition, wildcard_covered_files};
+
+pub fn add_include_directory(project_path: PathBuf, include_path: String, dryrun: bool) -> Result<bool> {
+    info!("Adding include directory '{}' to project: {}", include_path, project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    if vcxproj.is_makefile_project() {
+        return add_nmake_list_value(vcxproj, "NMakeIncludeSearchPath", &include_path, None, &tr!("include directory"), dryrun);
+    }
+    let modified_configs = vcxproj.add_include_directory(&include_path)?;
+    output::report(Change::ConfigModified, modified_configs.iter().map(|c| vcxproj::condition_config(c)));
+    if dryrun && !modified_configs.is_empty() {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would add the include directory to {} configurations:", modified_configs.len());
+        for config in &modified_configs {
+            info!("  - {}", config);
+        }
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    
+    if modified_configs.is_empty() && vcxproj.content.contains("<ItemDefinitionGroup Condition=") {
+        info!("✅ No changes - every configuration already has this include directory");
+    } else if modified_configs.is_empty() {
+        warn!("⚠️  No configurations found to modify");
+    } else {
+        info!("✅ Successfully added include directory to {} configurations:", modified_configs.len());
+        for config in &modified_configs {
+            info!("  - {}", config);
+        }
+    }
+    
+    Ok(!modified_configs.is_empty())
+}
+
+pub fn add_library_directory(project_path: PathBuf, lib_path: String, dryrun: bool) -> Result<bool> {
+    info!("Adding library directory '{}' to project: {}", lib_path, project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let modified_configs = vcxproj.add_library_directory(&lib_path, None)?;
+    output::report(Change::ConfigModified, modified_configs.iter().map(|c| vcxproj::condition_config(c)));
+    if dryrun && !modified_configs.is_empty() {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would add the library directory to {} configurations:", modified_configs.len());
+        for config in &modified_configs {
+            info!("  - {}", config);
+        }
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    
+    if modified_configs.is_empty() && vcxproj.content.contains("<ItemDefinitionGroup Condition=") {
+        info!("✅ No changes - every configuration already has this library directory");
+    } else if modified_configs.is_empty() {
+        warn!("⚠️  No configurations found to modify");
+    } else {
+        info!("✅ Successfully added library directory to {} configurations:", modified_configs.len());
+        for config in &modified_configs {
+            info!("  - {}", config);
+        }
+    }
+    
+    Ok(!modified_configs.is_empty())
+}
+
+/// Adds a preprocessor definition to ClCompile, or to NMakePreprocessorDefinitions in a Makefile project
+pub fn add_preprocessor_definition(project_path: PathBuf, define: String, config: Option<String>, dryrun: bool) -> Result<bool> {
+    info!("Adding preprocessor definition '{}' to project: {}", define, project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    if vcxproj.is_makefile_project() {
+        return add_nmake_list_value(vcxproj, "NMakePreprocessorDefinitions", &define, config.as_deref(), &tr!("definition"), dryrun);
+    }
+    let modified_configs = vcxproj.add_tool_list_value("ClCompile", "PreprocessorDefinitions", &define, config.as_deref(), &ListPosition::Back)?;
+    output::report(Change::ConfigModified, modified_configs.iter().map(|c| vcxproj::condition_config(c)));
+    if dryrun && !modified_configs.is_empty() {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would add the definition to {} configurations:", modified_configs.len());
+        for config in &modified_configs {
+            info!("  - {}", vcxproj::condition_config(config));
+        }
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    
+    if modified_configs.is_empty() && vcxproj.content.contains("<ItemDefinitionGroup Condition=") {
+        info!("✅ No changes - every configuration already has this definition");
+    } else if modified_configs.is_empty() {
+        warn!("⚠️  No configurations found to modify");
+    } else {
+        info!("✅ Successfully added definition to {} configurations:", modified_configs.len());
+        for config in &modified_configs {
+            info!("  - {}", vcxproj::condition_config(config));
+        }
+    }
+    
+    Ok(!modified_configs.is_empty())
+}
+
+/// Adds an include directory or definition to a Makefile project, whose IntelliSense reads them
+/// from NMake* properties since there is no ClCompile
+fn add_nmake_list_value(mut vcxproj: VcxprojFile, property: &str, value: &str, config: Option<&str>, what: &str, dryrun: bool) -> Result<bool> {
+    info!("🛠️  Makefile project - using {}", property);
+    let modified_configs = vcxproj.add_config_list_value(property, value, config)?;
+    output::report(Change::ConfigModified, &modified_configs);
+    if dryrun && !modified_configs.is_empty() {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would add the {} to {} configurations:", what, modified_configs.len());
+        for config in &modified_configs {
+            info!("  - {}", config);
+        }
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    
+    if modified_configs.is_empty() && !vcxproj.get_configurations().is_empty() {
+        info!("✅ No changes - every configuration already has this {}", what);
+    } else if modified_configs.is_empty() {
+        warn!("⚠️  No configurations found to modify");
+    } else {
+        info!("✅ Successfully added {} to {} configurations:", what, modified_configs.len());
+        for config in &modified_configs {
+            info!("  - {}", config);
+        }
+    }
+    Ok(!modified_configs.is_empty())
+}
+
+/// Sets the NMake command lines and output of a Makefile project
+pub fn set_nmake_properties(project_path: PathBuf, properties: &[(&str, Option<String>)], config: Option<String>, dryrun: bool) -> Result<bool> {
+    if properties.iter().all(|(_, value)| value.is_none()) {
+        return Err(anyhow::anyhow!("Specify at least one of --build, --rebuild, --clean and --output"));
+    }
+    info!("Analyzing project: {}", project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    if !vcxproj.is_makefile_project() {
+        return Err(anyhow::anyhow!(
+            "{} is not a Makefile project (ConfigurationType Makefile); its NMake settings would be ignored",
+            project_path.display()
+        ));
+    }
+    let mut modified_configs: Vec<String> = Vec::new();
+    for (name, value) in properties {
+        let Some(value) = value else {
+            continue;
+        };
+        for (target, previous) in vcxproj.set_config_property(name, value, config.as_deref())? {
+            match previous {
+                Some(previous) => info!("  ✏️  {} {}: {} → {}", target, name, previous, value),
+                None => info!("  ➕ {} {}: {}", target, name, value),
+            }
+            if !modified_configs.contains(&target) {
+                modified_configs.push(target);
+            }
+        }
+    }
+    if modified_configs.is_empty() {
+        info!("✅ No changes - the NMake settings are already set");
+        return Ok(false);
+    }
+    output::report(Change::ConfigModified, &modified_configs);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ Updated the NMake settings of {} configurations", modified_configs.len());
+    Ok(true)
+}
+
+/// Adds `debug_name` to Debug configurations and `release_name` to the others (the same name for
+/// a library without debug variant)
+pub fn add_library_dependency(
+    project_path: PathBuf,
+    debug_name: String,
+    release_name: String,
+    config: Option<String>,
+    position: ListPosition,
+    dryrun: bool,
+) -> Result<bool> {
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let mut modified_configs = Vec::new();
+    if debug_name == release_name {
+        info!("Adding library dependency '{}' to project: {}", release_name, project_path.display());
+        modified_configs = vcxproj.add_library_dependency(&release_name, config.as_deref(), &position)?;
+    } else {
+        info!(
+            "Adding library dependency '{}' (Debug) / '{}' (Release) to project: {}",
+            debug_name,
+            release_name,
+            project_path.display()
+        );
+        let targets: Vec<String> = vcxproj
+            .get_configurations()
+            .into_iter()
+            .filter(|c| config.as_deref().is_none_or(|selector| vcxproj::config_matches(c, selector)))
+            .collect();
+        for target in targets {
+            let lib_name = if vcxproj.is_debug_configuration(&target) { &debug_name } else { &release_name };
+            modified_configs.extend(vcxproj.add_library_dependency(lib_name, Some(&target), &position)?);
+        }
+    }
+    output::report(Change::ConfigModified, modified_configs.iter().map(|c| vcxproj::condition_config(c)));
+    if dryrun && !modified_configs.is_empty() {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would add the library dependency to {} configurations:", modified_configs.len());
+        for config in &modified_configs {
+            info!("  - {}", config);
+        }
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    
+    let matching_configs = vcxproj
+        .get_configurations()
+        .iter()
+        .any(|c| config.as_deref().is_none_or(|selector| vcxproj::config_matches(c, selector)));
+    if modified_configs.is_empty() && matching_configs && vcxproj.content.contains("<ItemDefinitionGroup Condition=") {
+        info!("✅ No changes - every matching configuration already has this library dependency");
+    } else if let (Some(selector), false) = (&config, matching_configs) {
+        warn!("⚠️  No configurations match '{}'", selector);
+    } else if modified_configs.is_empty() {
+        warn!("⚠️  No configurations found to modify");
+    } else {
+        info!("✅ Successfully added library dependency to {} configurations:", modified_configs.len());
+        for config in &modified_configs {
+            info!("  - {}", config);
+        }
+    }
+    
+    Ok(!modified_configs.is_empty())
+}
+
+/// Copies the tool settings of configuration `from` to `to`, warning about copied values that name
+/// the source platform
+pub fn copy_config_settings(project_path: PathBuf, from: String, to: String, only: Vec<String>, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let configs = vcxproj.get_configurations();
+    let find = |name: &str| {
+        configs
+            .iter()
+            .find(|c| c.eq_ignore_ascii_case(name))
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("No configuration '{}' in {} (it has {})", name, project_path.display(), configs.join(", ")))
+    };
+    let (from, to) = (find(&from)?, find(&to)?);
+    if from == to {
+        return Err(anyhow::anyhow!("--from and --to are the same configuration"));
+    }
+    
+    let changes = vcxproj.copy_item_definitions(&from, &to, &only)?;
+    if changes.is_empty() {
+        info!("✅ No changes - {} already has the settings of {}", to, from);
+        return Ok(false);
+    }
+    info!("📋 Copying settings from {} to {}:", from, to);
+    for (tool, change) in &changes {
+        info!("  🔧 {}: {}", tool, change);
+    }
+    output::report(Change::ConfigModified, [&to]);
+    
+    // Paths and names of the source platform rarely suit the target one
+    let (from_platform, to_platform) = (from.split('|').nth(1).unwrap_or_default(), to.split('|').nth(1).unwrap_or_default());
+    if !from_platform.eq_ignore_ascii_case(to_platform) {
+        let segment = Regex::new(&format!(r"(?i)(^|[\\/;_.\-]){}([\\/;_.\-]|$)", regex::escape(from_platform)))?;
+        for setting in vcxproj.get_tool_settings() {
+            let in_target = setting.condition.as_deref().is_some_and(|c| vcxproj::condition_config(c).eq_ignore_ascii_case(&to));
+            if in_target && changes.iter().any(|(tool, _)| *tool == setting.tool) && segment.is_match(&setting.value) {
+                warn!("  ⚠️  {}/{} mentions {}: {}", setting.tool, setting.name, from_platform, setting.value);
+            }
+        }
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ Copied {} tool sections to {}", changes.len(), to);
+    Ok(true)
+}
+
+/// Excludes the ClCompile items matching `regex` from the build of the configurations chosen by
+/// `selector` (all without one), with one conditioned `ExcludedFromBuild` per configuration as Visual
+/// Studio writes it, or with `exclude` false removes those exclusions again
+pub fn set_excluded_from_build(project_path: PathBuf, regex: String, selector: Option<String>, exclude: bool, dryrun: bool) -> Result<bool> {
+    use std::collections::BTreeSet;
+    
+    info!("Analyzing project: {}", project_path.display());
+    let matcher = Regex::new(&regex).with_context(|| format!("Invalid regex '{}'", regex))?;
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let configs: Vec<String> = vcxproj
+        .get_configurations()
+        .into_iter()
+        .filter(|config| selector.as_deref().is_none_or(|selector| vcxproj::config_matches(config, selector)))
+        .collect();
+    if configs.is_empty() {
+        return Err(anyhow::anyhow!("No configuration of {} matches '{}'", project_path.display(), selector.unwrap_or_default()));
+    }
+    
+    let mut modified_files = BTreeSet::new();
+    if exclude {
+        // Files that only exist through a wildcard item need an Update item to carry the metadata
+        let covered_files = wildcard_covered_files(&project_path, &vcxproj, &matcher)?;
+        if !covered_files.is_empty() {
+            let added = vcxproj.add_update_items(&covered_files)?;
+            if !added.is_empty() {
+                info!("Adding Update items for {} files covered by wildcards", added.len());
+            }
+        }
+        let excluded = [("ExcludedFromBuild".to_string(), "true".to_string())];
+        for config in &configs {
+            modified_files.extend(vcxproj.set_item_metadata(&matcher, &excluded, Some(&config_condition(config)))?);
+        }
+    } else {
+        let removed = vcxproj.remove_item_metadata_where(|include, metadata| {
+            metadata.name == "ExcludedFromBuild"
+                && matcher.is_match(&include.replace('\\', "/"))
+                && match metadata.condition.as_deref() {
+                    Some(condition) => configs.iter().any(|config| vcxproj::condition_config(condition).eq_ignore_ascii_case(config)),
+                    None => selector.is_none(),
+                }
+        })?;
+        modified_files.extend(removed);
+        
+        // An exclusion without a condition applies to every configuration, so it can't be undone for some of them
+        for item in vcxproj.get_items()?.into_iter().filter(|item| matcher.is_match(&item.include.replace('\\', "/"))) {
+            if item.metadata.iter().any(|m| m.name == "ExcludedFromBuild" && m.condition.is_none() && m.value.eq_ignore_ascii_case("true")) {
+                warn!("⚠️  {} is excluded from every configuration; run `include` without --config to build it again", item.include);
+            }
+        }
+    }
+    
+    if modified_files.is_empty() {
+        info!("✅ No changes - no file matching '{}' needed changes", regex);
+        return Ok(false);
+    }
+    info!("\n{} {}:", if exclude { "🚫 Excluded from the build of" } else { "🔨 Built again in" }, configs.join(", "));
+    for file in &modified_files {
+        info!("  - {}", file);
+    }
+    output::report(Change::FileModified, &modified_files);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ Updated {} files in {}", modified_files.len(), project_path.display());
+    Ok(true)
+}
@@ -0,0 +1,395 @@
+use anyhow::Result;
+use crate::i18n::{info, warn};
+use crate::output::{self, Change};
+use crate::pipe;
+use crate::properties;
+use crate::sln::{self, SolutionFile};
+use crate::vcxproj::{self, FilterFile, NON_FILE_ITEM_TYPES, VcxprojFile};
+use std::path::{Path, PathBuf};
+use super::{expand_wildcard_item, path_key};
+
+/// Adds a project to a solution. Returns false when it is already there.
+pub fn add_project_to_solution(solution_path: PathBuf, project_path: PathBuf, folder: Option<String>, dryrun: bool) -> Result<bool> {
+    info!("Analyzing solution: {}", solution_path.display());
+    
+    let mut solution = SolutionFile::load(&solution_path)?;
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let guid = vcxproj
+        .project_guid()
+        .ok_or_else(|| anyhow::anyhow!("{} has no ProjectGuid", project_path.display()))?;
+    let guid = format!("{{{}}}", guid.trim_matches(['{', '}']).to_uppercase());
+    let solution_dir = solution_path.parent().unwrap_or_else(|| Path::new("."));
+    let path = vcxproj::relative_path(solution_dir, &project_path);
+    
+    if let Some(existing) = solution
+        .projects()
+        .into_iter()
+        .find(|p| p.guid.eq_ignore_ascii_case(&guid) || vcxproj::same_path(&p.path, &path))
+    {
+        info!("ℹ️  {} is already in the solution as '{}' ({})", project_path.display(), existing.name, existing.path);
+        return Ok(false);
+    }
+    
+    let project = sln::SolutionProject {
+        type_guid: sln::VCXPROJ_TYPE_GUID.to_string(),
+        name: vcxproj.project_name(),
+        path,
+        guid,
+    };
+    let mappings = solution.add_project(&project, folder.as_deref(), &vcxproj.get_configurations());
+    
+    info!("➕ Adding '{}' {} ({})", project.name, project.guid, project.path);
+    if let Some(folder) = &folder {
+        info!("  📁 In solution folder: {}", folder);
+    }
+    for mapping in &mappings {
+        if mapping.ends_with("(not built)") {
+            warn!("  ⚠️  {}", mapping);
+        } else {
+            info!("  🔧 {}", mapping);
+        }
+    }
+    output::report(Change::FileAdded, [&project.path]);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update solution file: {}", solution_path.display());
+        return Ok(true);
+    }
+    solution.save()?;
+    info!("\n✅ Added {} to {}", project.name, solution_path.display());
+    Ok(true)
+}
+
+/// A project of a solution given by its path (relative to the current directory or as written in
+/// the solution) or by its name
+fn find_solution_project(solution: &SolutionFile, project: &str) -> Result<sln::SolutionProject> {
+    let solution_dir = solution.path.parent().unwrap_or_else(|| Path::new("."));
+    let path = vcxproj::relative_path(solution_dir, Path::new(project));
+    let projects: Vec<sln::SolutionProject> = solution
+        .projects()
+        .into_iter()
+        .filter(|p| !p.type_guid.eq_ignore_ascii_case(sln::SOLUTION_FOLDER_TYPE_GUID))
+        .collect();
+    projects
+        .iter()
+        .find(|p| vcxproj::same_path(&p.path, &path) || vcxproj::same_path(&p.path, project))
+        .or_else(|| projects.iter().find(|p| p.name.eq_ignore_ascii_case(project)))
+        .cloned()
+        .ok_or_else(|| anyhow::anyhow!("No project '{}' in {}", project, solution.path.display()))
+}
+
+/// Removes a project, given by path or name, from a solution
+pub fn remove_project_from_solution(solution_path: PathBuf, project: String, dryrun: bool) -> Result<bool> {
+    info!("Analyzing solution: {}", solution_path.display());
+    
+    let mut solution = SolutionFile::load(&solution_path)?;
+    let found = find_solution_project(&solution, &project)?;
+    
+    solution.remove_project(&found.guid);
+    info!("➖ Removing '{}' {} ({})", found.name, found.guid, found.path);
+    output::report(Change::FileRemoved, [&found.path]);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update solution file: {}", solution_path.display());
+        return Ok(true);
+    }
+    solution.save()?;
+    info!("\n✅ Removed {} from {}", found.name, solution_path.display());
+    Ok(true)
+}
+
+/// Reports the projects of a solution that contain `file`. Item paths are expanded and resolved
+/// against their project, so `..\\common\\logger.cpp` and `$(SolutionDir)src\\common\\logger.cpp`
+/// both match. Projects with a different file of the same name are listed too, since those are a
+/// common source of duplicate symbols and confusing ownership.
+pub fn which_projects(solution_path: PathBuf, file: PathBuf) -> Result<()> {
+    info!("Searching solution: {}", solution_path.display());
+    
+    let wanted = path_key(&file);
+    let file_name = file.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+    if !file.exists() {
+        warn!("⚠️  {} does not exist; matching its path anyway", file.display());
+    }
+    
+    let mut owners = 0;
+    let mut compiled = 0;
+    let mut namesakes = Vec::new();
+    for project_path in SolutionFile::load(&solution_path)?.vcxproj_paths() {
+        if !project_path.exists() {
+            warn!("⚠️  Project not found: {}", project_path.display());
+            continue;
+        }
+        let vcxproj = VcxprojFile::load(&project_path)?;
+        let properties = properties::Properties::for_project(&vcxproj, None);
+        let project_dir = project_path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+        let filter_path = vcxproj::filters_path(&project_path);
+        let file_filters = if pipe::exists(&filter_path) {
+            FilterFile::load(&filter_path)?.get_item_filters()?
+        } else {
+            Default::default()
+        };
+        
+        // (item type, Include as written or the file a wildcard matched, the file it names, per-item metadata)
+        let mut items: Vec<(String, String, String, Vec<vcxproj::ItemMetadata>)> = Vec::new();
+        for item in vcxproj.get_items()? {
+            if NON_FILE_ITEM_TYPES.contains(&item.item_type.as_str()) || item.include.contains('*') {
+                continue;
+            }
+            if let Ok(path) = properties.resolve_path(project_dir, &item.include) {
+                items.push((item.item_type, item.include, path_key(&path), item.metadata));
+            }
+        }
+        let mut wildcard_matches = std::collections::HashMap::new();
+        for item in vcxproj.get_wildcard_items().iter().filter(|item| !item.include.contains("$(")) {
+            for path in expand_wildcard_item(project_dir, item)? {
+                wildcard_matches.insert(path.clone(), item.include.clone());
+                items.push((item.item_type.clone(), path.clone(), path_key(&project_dir.join(path.replace('\\', "/"))), Vec::new()));
+            }
+        }
+        
+        let mut found = false;
+        for (item_type, include, path, metadata) in &items {
+            if *path == wanted {
+                if !found {
+                    info!("\n📁 {}", project_path.display());
+                    found = true;
+                    owners += 1;
+                }
+                match wildcard_matches.get(include) {
+                    Some(wildcard) => info!("  📄 {} (from {})", include, wildcard),
+                    None => info!("  📄 {}", include),
+                }
+                info!("     type: {}", item_type);
+                let filter = file_filters.iter().find(|(f, _)| vcxproj::same_path(f, include)).map(|(_, filter)| filter.as_str());
+                info!("     filter: {}", filter.unwrap_or("(none)"));
+                let excluded: Vec<String> = metadata
+                    .iter()
+                    .filter(|m| m.name == "ExcludedFromBuild" && m.value.eq_ignore_ascii_case("true"))
+                    .map(|m| m.condition.as_deref().map_or("all configurations".to_string(), |c| vcxproj::condition_config(c).into_owned()))
+                    .collect();
+                if !excluded.is_empty() {
+                    info!("     excluded from build: {}", excluded.join(", "));
+                } else if item_type == "ClCompile" {
+                    compiled += 1;
+                }
+            } else if path.rsplit(['/', '\\']).next() == Some(file_name.as_str()) {
+                namesakes.push(format!("{}: {}", project_path.display(), include));
+            }
+        }
+    }
+    
+    if owners == 0 {
+        info!("\nNo project contains {}", file.display());
+    } else {
+        info!("\n⚡︎ {} in {} projects", file.display(), owners);
+    }
+    if compiled > 1 {
+        warn!("⚠️  Compiled by {} projects; linking them together gives duplicate symbols", compiled);
+    }
+    if !namesakes.is_empty() {
+        info!("\n📋 Other files named {}:", file.file_name().unwrap_or_default().to_string_lossy());
+        for namesake in &namesakes {
+            info!("  - {}", namesake);
+        }
+    }
+    Ok(())
+}
+
+/// A project in a dependency graph
+struct DependencyNode {
+    name: String,
+    path: PathBuf,
+    /// The nodes it builds after, each with whether the dependency is declared in the solution
+    /// rather than by a ProjectReference
+    dependencies: Vec<(usize, bool)>,
+    missing: bool,
+}
+
+/// The projects `roots` depend on, directly or not, through ProjectReferences and, with a
+/// solution, its ProjectDependencies sections. The roots are the first nodes.
+fn dependency_graph(roots: &[PathBuf], solution: Option<&SolutionFile>) -> Result<Vec<DependencyNode>> {
+    let mut nodes: Vec<DependencyNode> = Vec::new();
+    let mut index: std::collections::HashMap<String, usize> = std::collections::HashMap::new();
+    let mut intern = |nodes: &mut Vec<DependencyNode>, path: PathBuf| -> usize {
+        *index.entry(path_key(&path)).or_insert_with(|| {
+            nodes.push(DependencyNode {
+                name: path.file_stem().unwrap_or_default().to_string_lossy().into_owned(),
+                path,
+                dependencies: Vec::new(),
+                missing: false,
+            });
+            nodes.len() - 1
+        })
+    };
+    for root in roots {
+        intern(&mut nodes, root.clone());
+    }
+    
+    let solution_projects = solution.map(|s| s.projects()).unwrap_or_default();
+    let solution_dir = solution.and_then(|s| s.path.parent()).unwrap_or_else(|| Path::new("."));
+    let mut next = 0;
+    while next < nodes.len() {
+        let path = nodes[next].path.clone();
+        if !path.exists() {
+            nodes[next].missing = true;
+            next += 1;
+            continue;
+        }
+        let vcxproj = VcxprojFile::load(&path)?;
+        nodes[next].name = vcxproj.project_name();
+        let project_dir = path.parent().unwrap_or_else(|| Path::new("."));
+        let mut dependencies = Vec::new();
+        for item in vcxproj.get_items()?.into_iter().filter(|item| item.item_type == "ProjectReference") {
+            dependencies.push((intern(&mut nodes, project_dir.join(item.include.replace('\\', "/"))), false));
+        }
+        if let (Some(solution), Some(guid)) = (solution, vcxproj.project_guid()) {
+            for dependency in solution.dependencies(&guid) {
+                if let Some(project) = solution_projects.iter().find(|p| p.guid.eq_ignore_ascii_case(&dependency)) {
+                    dependencies.push((intern(&mut nodes, solution_dir.join(project.path.replace('\\', "/"))), true));
+                }
+            }
+        }
+        dependencies.dedup_by_key(|(node, _)| *node);
+        nodes[next].dependencies = dependencies;
+        next += 1;
+    }
+    Ok(nodes)
+}
+
+/// The nodes reachable from `root` with every project after the ones it depends on, or the
+/// projects of a dependency cycle
+fn build_order(nodes: &[DependencyNode], root: usize) -> std::result::Result<Vec<usize>, Vec<usize>> {
+    fn visit(nodes: &[DependencyNode], node: usize, state: &mut [u8], path: &mut Vec<usize>, order: &mut Vec<usize>) -> std::result::Result<(), Vec<usize>> {
+        match state[node] {
+            2 => return Ok(()),
+            1 => {
+                let start = path.iter().position(|&n| n == node).unwrap_or(0);
+                let mut cycle = path[start..].to_vec();
+                cycle.push(node);
+                return Err(cycle);
+            }
+            _ => {}
+        }
+        state[node] = 1;
+        path.push(node);
+        for &(dependency, _) in &nodes[node].dependencies {
+            visit(nodes, dependency, state, path, order)?;
+        }
+        path.pop();
+        state[node] = 2;
+        order.push(node);
+        Ok(())
+    }
+    
+    let mut order = Vec::new();
+    visit(nodes, root, &mut vec![0; nodes.len()], &mut Vec::new(), &mut order)?;
+    Ok(order)
+}
+
+/// Prints a project's dependency tree and the order its dependencies build in
+pub fn show_dependencies(project: PathBuf, solution: Option<PathBuf>) -> Result<()> {
+    info!("Analyzing project: {}", project.display());
+    
+    let solution = solution.map(SolutionFile::load).transpose()?;
+    let nodes = dependency_graph(std::slice::from_ref(&project), solution.as_ref())?;
+    let glyphs = vcxproj::tree_glyphs();
+    let label = |node: &DependencyNode| format!("{}{} ({})", glyphs.project, node.name, vcxproj::relative_path(Path::new("."), &node.path));
+    
+    fn print_tree(nodes: &[DependencyNode], node: usize, prefix: &str, shown: &mut Vec<bool>, label: &dyn Fn(&DependencyNode) -> String) {
+        let glyphs = vcxproj::tree_glyphs();
+        let count = nodes[node].dependencies.len();
+        for (i, &(dependency, from_solution)) in nodes[node].dependencies.iter().enumerate() {
+            let last = i + 1 == count;
+            let mut line = format!("{}{}{}", prefix, if last { glyphs.last_branch } else { glyphs.branch }, label(&nodes[dependency]));
+            if from_solution {
+                line.push_str(" [solution dependency]");
+            }
+            if nodes[dependency].missing {
+                line.push_str(" [missing]");
+            } else if shown[dependency] && !nodes[dependency].dependencies.is_empty() {
+                line.push_str(" (see above)");
+            }
+            output::print_line(line);
+            if !shown[dependency] {
+                shown[dependency] = true;
+                let prefix = format!("{}{}", prefix, if last { glyphs.blank } else { glyphs.pipe });
+                print_tree(nodes, dependency, &prefix, shown, label);
+            }
+        }
+    }
+    
+    output::print_line(label(&nodes[0]));
+    let mut shown = vec![false; nodes.len()];
+    shown[0] = true;
+    print_tree(&nodes, 0, "", &mut shown, &label);
+    
+    for node in nodes.iter().filter(|node| node.missing) {
+        warn!("⚠️  Referenced project not found: {}", node.path.display());
+    }
+    match build_order(&nodes, 0) {
+        Ok(order) => {
+            output::print_line("\nBuild order:");
+            for (step, &node) in order.iter().enumerate() {
+                output::print_line(format!("  {}. {}", step + 1, nodes[node].name));
+            }
+            Ok(())
+        }
+        Err(cycle) => {
+            let names: Vec<&str> = cycle.iter().map(|&node| nodes[node].name.as_str()).collect();
+            Err(anyhow::anyhow!("Dependency cycle: {}", names.join(" → ")))
+        }
+    }
+}
+
+/// Adds or removes solution-level build dependencies of a project. Adding refuses dependencies
+/// that would make a cycle, which Visual Studio cannot build.
+pub fn set_solution_dependencies(solution_path: PathBuf, project: String, depends_on: Vec<String>, remove: bool, dryrun: bool) -> Result<bool> {
+    info!("Analyzing solution: {}", solution_path.display());
+    
+    let mut solution = SolutionFile::load(&solution_path)?;
+    let solution_dir = solution_path.parent().unwrap_or_else(|| Path::new("."));
+    let dependent = find_solution_project(&solution, &project)?;
+    let dependent_key = path_key(&solution_dir.join(dependent.path.replace('\\', "/")));
+    let mut changed = Vec::new();
+    for name in &depends_on {
+        let dependency = find_solution_project(&solution, name)?;
+        if dependency.guid.eq_ignore_ascii_case(&dependent.guid) {
+            return Err(anyhow::anyhow!("A project cannot depend on itself: {}", dependent.name));
+        }
+        if remove {
+            if solution.remove_dependency(&dependent.guid, &dependency.guid) {
+                info!("➖ {} no longer builds after {}", dependent.name, dependency.name);
+                changed.push(dependency.name);
+            } else {
+                info!("ℹ️  {} has no solution dependency on {}", dependent.name, dependency.name);
+            }
+            continue;
+        }
+        
+        let dependency_path = solution_dir.join(dependency.path.replace('\\', "/"));
+        let nodes = dependency_graph(std::slice::from_ref(&dependency_path), Some(&solution))?;
+        if nodes.iter().any(|node| path_key(&node.path) == dependent_key) {
+            return Err(anyhow::anyhow!("{} already depends on {}; the dependency would make a cycle", dependency.name, dependent.name));
+        }
+        if solution.add_dependency(&dependent.guid, &dependency.guid) {
+            info!("➕ {} builds after {}", dependent.name, dependency.name);
+            changed.push(dependency.name);
+        } else {
+            info!("ℹ️  {} already depends on {}", dependent.name, dependency.name);
+        }
+    }
+    
+    if changed.is_empty() {
+        return Ok(false);
+    }
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update solution file: {}", solution_path.display());
+        return Ok(true);
+    }
+    solution.save()?;
+    info!("\n✅ Updated the dependencies of {} in {}", dependent.name, solution_path.display());
+    Ok(true)
+}
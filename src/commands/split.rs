@@ -0,0 +1,149 @@
+use anyhow::{Context, Result};
+use crate::history;
+use crate::i18n::{info, tr, warn};
+use crate::output::{self, Change};
+use crate::pipe;
+use crate::vcxproj::{self, FilterFile, NON_FILE_ITEM_TYPES, VcxprojFile};
+use std::path::{Path, PathBuf};
+use super::filters::DEFAULT_FILTERS;
+use super::new::static_library_project_template;
+
+/// Moves the items under `filter` into a new static library project at `to` and adds a
+/// ProjectReference to it. The library gets the project's configurations, toolset and SDK version;
+/// moved items keep their metadata, their paths are rebased to the library's directory, and the
+/// subfilters below `filter` become the library's filters.
+pub fn split_project(project_path: PathBuf, filter: String, to: PathBuf, name: Option<String>, dryrun: bool) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    
+    let library_filter_path = vcxproj::filters_path(&to);
+    for path in [&to, &library_filter_path] {
+        if path.exists() {
+            return Err(anyhow::anyhow!("{} already exists", path.display()));
+        }
+    }
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let filter_path = vcxproj::filters_path(&project_path);
+    if !pipe::exists(&filter_path) {
+        return Err(anyhow::anyhow!("{} has no filters file, so there is no filter to split out", project_path.display()));
+    }
+    let mut filter_file = FilterFile::load(&filter_path)?;
+    let filter = filter.replace('/', "\\").trim_matches('\\').to_string();
+    let files = filter_file
+        .files_in_filter_tree(&filter)?
+        .ok_or_else(|| anyhow::anyhow!("Filter '{}' not found in {}", filter, filter_path.display()))?;
+    let file_filters = filter_file.get_item_filters()?;
+    let moved: Vec<vcxproj::ProjectItem> = vcxproj
+        .get_items()?
+        .into_iter()
+        .filter(|item| !NON_FILE_ITEM_TYPES.contains(&item.item_type.as_str()))
+        .filter(|item| files.iter().any(|file| vcxproj::same_path(file, &item.include)))
+        .collect();
+    if moved.is_empty() {
+        return Err(anyhow::anyhow!("No project items are in filter '{}'", filter));
+    }
+    
+    let name = name.unwrap_or_else(|| to.file_stem().unwrap_or_default().to_string_lossy().into_owned());
+    let guid = uuid::Uuid::new_v4();
+    let configs = vcxproj.get_configurations();
+    let toolset = vcxproj.get_property("PlatformToolset").unwrap_or_else(|| "v143".to_string());
+    let sdk = vcxproj.get_property("WindowsTargetPlatformVersion").unwrap_or_else(|| "10.0".to_string());
+    let mut library = VcxprojFile {
+        path: to.clone(),
+        content: static_library_project_template(&name, &guid, &configs.iter().map(String::as_str).collect::<Vec<_>>(), &toolset, &sdk),
+    };
+    let mut library_filters = FilterFile::empty(library_filter_path.clone());
+    
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    let library_dir = to.parent().unwrap_or_else(|| Path::new("."));
+    let rebase = |include: &str| {
+        if include.contains("$(") {
+            include.to_string()
+        } else {
+            vcxproj::relative_path(library_dir, &project_dir.join(include.replace('\\', "/")))
+        }
+    };
+    
+    let paths: Vec<String> = moved.iter().map(|item| item.include.clone()).collect();
+    let mut moves = Vec::new();
+    for (item_type, mut lines) in vcxproj.remove_items(&paths) {
+        let include = vcxproj::attribute_value(lines[0].trim(), "Include").unwrap_or_default().to_string();
+        let rebased = rebase(&include);
+        lines[0] = lines[0].replacen(&format!("Include=\"{}\"", include), &format!("Include=\"{}\"", rebased), 1);
+        library.insert_item_lines(&item_type, &lines)?;
+        
+        // Subfilters keep their place below the split filter; its own files go to the usual filter for their type
+        let current = file_filters.iter().find(|(file, _)| vcxproj::same_path(file, &include)).map(|(_, f)| f.as_str()).unwrap_or_default();
+        let below = current.get(filter.len()..).unwrap_or_default().trim_start_matches('\\');
+        let default = DEFAULT_FILTERS.iter().find(|(t, _, _)| *t == item_type);
+        let (library_filter, extensions) = match default {
+            _ if !below.is_empty() => (below.to_string(), None),
+            Some((_, default_filter, extensions)) => (default_filter.to_string(), Some(*extensions)),
+            None => (filter.rsplit('\\').next().unwrap_or(&filter).to_string(), None),
+        };
+        library_filters.ensure_filter(&library_filter, extensions);
+        library_filters.set_file_filter(&item_type, &rebased, &library_filter);
+        moves.push((include, rebased));
+    }
+    filter_file.remove_items(&files.iter().cloned().collect::<Vec<_>>());
+    let removed_filters = filter_file.remove_filter_tree(&filter);
+    
+    let reference = vcxproj::relative_path(project_dir, &to);
+    vcxproj.insert_item_lines(
+        "ProjectReference",
+        &[
+            format!("    <ProjectReference Include=\"{}\">", reference),
+            format!("      <Project>{{{}}}</Project>", guid),
+            "    </ProjectReference>".to_string(),
+        ],
+    )?;
+    
+    let mut attention = vec![
+        tr!("Include directories, defines and other compiler settings of {} were not copied to {}", project_path.display(), to.display()),
+        tr!("Add {} to the solutions that build {}", to.display(), project_path.display()),
+    ];
+    let uses_pch = vcxproj.get_tool_settings().iter().any(|s| s.tool == "ClCompile" && s.name == "PrecompiledHeader" && s.value == "Use");
+    if uses_pch {
+        attention.push(tr!("{} uses a precompiled header; the moved sources may include it", project_path.display()));
+    }
+    
+    info!("Splitting '{}' into static library '{}' {{{}}}", filter, name, guid);
+    info!("\n📦 Files moved to {}:", to.display());
+    for (include, rebased) in &moves {
+        if include == rebased {
+            info!("  - {}", include);
+        } else {
+            info!("  - {} → {}", include, rebased);
+        }
+    }
+    info!("\n🔗 {} references {}", project_path.display(), reference);
+    info!("\n📋 May need manual attention:");
+    for note in &attention {
+        warn!("  ⚠️  {}", note);
+    }
+    output::report(Change::FileRemoved, moves.iter().map(|(include, _)| include));
+    output::report(Change::FilterRemoved, &removed_filters);
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would create project file: {}", to.display());
+        info!("Would create filter file: {}", library_filter_path.display());
+        info!("Would update project file: {}", project_path.display());
+        info!("Would update filter file: {}", filter_path.display());
+        return Ok(true);
+    }
+    
+    if let Some(dir) = to.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+        std::fs::create_dir_all(dir)
+            .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+    }
+    history::record(&to, &library.content)?;
+    history::record(&library_filter_path, &library_filters.content)?;
+    history::replace_files(&[
+        (to.clone(), Some(library.content.clone())),
+        (library_filter_path.clone(), Some(library_filters.content.clone())),
+    ])?;
+    output::report(Change::FileWritten, [to.display(), library_filter_path.display()]);
+    vcxproj.save_with_filters(Some(&filter_file))?;
+    info!("\n✅ Moved {} files into {}", moves.len(), to.display());
+    Ok(true)
+}
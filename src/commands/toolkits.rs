@@ -0,0 +1,111 @@
+use anyhow::Result;
+use crate::i18n::{info, tr};
+use crate::vcxproj::{self, VcxprojFile};
+use regex::Regex;
+use std::path::PathBuf;
+
+/// Imports the build customization of CUDA `version` into the ExtensionSettings and ExtensionTargets
+/// groups, in place of any other version's, so .cu files (CudaCompile items) build with nvcc
+pub fn enable_cuda(project_path: PathBuf, version: String, dryrun: bool) -> Result<bool> {
+    if !Regex::new(r"^\d+\.\d+$").unwrap().is_match(&version) {
+        return Err(anyhow::anyhow!("Invalid CUDA version '{}', expected MAJOR.MINOR such as 12.4", version));
+    }
+    info!("Analyzing project: {}", project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let customization = Regex::new(r"(?i)\\BuildCustomizations\\CUDA (\d+\.\d+)\.(props|targets)$").unwrap();
+    let other_version = |project: &str| customization.captures(project).is_some_and(|captures| captures[1] != version);
+    let mut changes = Vec::new();
+    for removed in vcxproj.remove_imports(other_version) {
+        changes.push(tr!("remove import {}", removed));
+    }
+    for (extension, label) in [("props", "ExtensionSettings"), ("targets", "ExtensionTargets")] {
+        let import = format!("$(VCTargetsPath)\\BuildCustomizations\\CUDA {}.{}", version, extension);
+        if vcxproj.add_import_to_group(&import, label, false)? {
+            changes.push(tr!("add import {}", import));
+        }
+    }
+    if changes.is_empty() {
+        info!("✅ No changes - CUDA {} is already enabled", version);
+        return Ok(false);
+    }
+    for change in &changes {
+        info!("  - {}", change);
+    }
+    let sources = vcxproj.get_items()?.iter().filter(|item| item.item_type == "CudaCompile").count();
+    if sources == 0 {
+        info!("💡 No CudaCompile items yet; add .cu files with `add -e cu`");
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ {} builds with CUDA {}", project_path.display(), version);
+    Ok(true)
+}
+
+/// The Globals Keyword Qt VS Tools recognizes its MSBuild-based projects by
+const QT_KEYWORD: &str = "QtVS_v304";
+
+/// Makes the project a Qt VS Tools project: the QtVS_v304 keyword, the QtMsBuild imports around
+/// Microsoft.Cpp.props and .targets, and a QtSettings group (before Microsoft.Cpp.props, which
+/// qt.props reads it after) with the Qt modules and optionally the Qt installation
+pub fn enable_qt(project_path: PathBuf, modules: String, install: Option<String>, dryrun: bool) -> Result<bool> {
+    let modules: Vec<String> = modules
+        .split([',', ';'])
+        .map(|module| module.trim().to_lowercase())
+        .filter(|module| !module.is_empty())
+        .collect();
+    if modules.is_empty() {
+        return Err(anyhow::anyhow!("No Qt modules given, e.g. --modules core,gui,widgets"));
+    }
+    info!("Analyzing project: {}", project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let mut changes = Vec::new();
+    if vcxproj.get_property("Keyword").as_deref() != Some(QT_KEYWORD) {
+        vcxproj.set_labeled_properties("Globals", &[("Keyword", QT_KEYWORD)])?;
+        changes.push(tr!("set Keyword to {}", QT_KEYWORD));
+    }
+    if vcxproj.add_import("$(QtMsBuild)\\qt_defaults.props", "Microsoft.Cpp.props", false)? {
+        changes.push("add import $(QtMsBuild)\\qt_defaults.props".to_string());
+    }
+    if vcxproj.add_labeled_property_group("QtSettings", "Microsoft.Cpp.props")? {
+        changes.push("add the QtSettings property group".to_string());
+    }
+    let modules = modules.join(";");
+    let settings = [("QtModules", Some(modules.clone())), ("QtInstall", install)];
+    for (name, value) in settings.iter().filter_map(|(name, value)| Some((name, value.as_ref()?))) {
+        for (condition, previous) in vcxproj.set_property_in_groups(name, value, "QtSettings") {
+            let config = condition.as_deref().map(|c| format!(" ({})", vcxproj::condition_config(c))).unwrap_or_default();
+            match previous {
+                Some(previous) => changes.push(tr!("{}{}: {} → {}", name, config, previous, value)),
+                None => changes.push(tr!("{}{}: {}", name, config, value)),
+            }
+        }
+    }
+    for (import, anchor) in [("$(QtMsBuild)\\qt.props", "Microsoft.Cpp.props"), ("$(QtMsBuild)\\qt.targets", "Microsoft.Cpp.targets")] {
+        if vcxproj.add_import(import, anchor, true)? {
+            changes.push(tr!("add import {}", import));
+        }
+    }
+    if changes.is_empty() {
+        info!("✅ No changes - Qt is already set up with {}", modules);
+        return Ok(false);
+    }
+    for change in &changes {
+        info!("  - {}", change);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update project file: {}", project_path.display());
+        return Ok(true);
+    }
+    vcxproj.save()?;
+    info!("\n✅ {} uses Qt modules {}", project_path.display(), modules.replace(';', ", "));
+    Ok(true)
+}
@@ -0,0 +1,157 @@
+use anyhow::Result;
+use crate::i18n::{info, warn};
+use crate::output::{self, Change};
+use crate::user;
+use crate::vcxproj::{self, VcxprojFile};
+use std::path::PathBuf;
+use super::describe_setting_change;
+
+/// Creates the project's .vcxproj.user file with a PropertyGroup for each configuration that uses
+/// the local Windows debugger, ready for `user set` or hand editing
+pub fn init_user_file(project_path: PathBuf, dryrun: bool) -> Result<bool> {
+    let path = user::user_path(&project_path);
+    if path.exists() {
+        info!("✅ No changes - {} already exists", path.display());
+        return Ok(false);
+    }
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let mut user_file = user::UserFile::new(path);
+    for config in vcxproj.get_configurations() {
+        user_file.set("DebuggerFlavor", "WindowsLocalDebugger", &config);
+        output::report(Change::ConfigModified, [&config]);
+    }
+    
+    if dryrun {
+        info!("🔍 DRY RUN - Would create {}:", user_file.path.display());
+        info!("{}", user_file.content);
+        return Ok(true);
+    }
+    user_file.save()?;
+    info!("✅ Created {}", user_file.path.display());
+    Ok(true)
+}
+
+pub fn show_user_file(project_path: PathBuf) -> Result<()> {
+    let path = user::user_path(&project_path);
+    if !path.exists() {
+        info!("{} has no user file ({}); `user init` creates one", project_path.display(), path.display());
+        return Ok(());
+    }
+    let user_file = user::UserFile::load(&path)?;
+    let properties = user_file.properties();
+    if properties.is_empty() {
+        info!("No settings in {}", path.display());
+        return Ok(());
+    }
+    
+    let mut current = None;
+    for property in &properties {
+        let config = property.config.as_deref().unwrap_or("All configurations");
+        if current != Some(config) {
+            info!("👤 {}:", config);
+            current = Some(config);
+        }
+        let mut lines = property.value.lines();
+        info!("  {}: {}", property.name, lines.next().unwrap_or_default());
+        for line in lines {
+            info!("  {}  {}", " ".repeat(property.name.len()), line);
+        }
+    }
+    Ok(())
+}
+
+/// Applies `edit` to the selected configurations of the project's .vcxproj.user file, creating the
+/// file when needed, and saves it. `edit` returns a description of each change it made.
+fn edit_user_file(project_path: PathBuf, config: Option<String>, dryrun: bool, mut edit: impl FnMut(&mut user::UserFile, &str) -> Vec<String>) -> Result<bool> {
+    info!("Analyzing project: {}", project_path.display());
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let mut user_file = user::UserFile::for_project(&project_path)?;
+    let configs: Vec<String> = vcxproj
+        .get_configurations()
+        .into_iter()
+        .filter(|c| config.as_deref().is_none_or(|selector| vcxproj::config_matches(c, selector)))
+        .collect();
+    if let (Some(selector), true) = (&config, configs.is_empty()) {
+        warn!("⚠️  No configurations match '{}'", selector);
+        return Ok(false);
+    }
+    
+    let mut changed_configs = 0;
+    for config in &configs {
+        let changes = edit(&mut user_file, config);
+        if changes.is_empty() {
+            continue;
+        }
+        // Visual Studio only reads the Local Windows Debugger settings when it is the selected debugger
+        if user::DEBUGGER_PROPERTIES.iter().any(|name| user_file.get(name, config).is_some()) {
+            user_file.set("DebuggerFlavor", "WindowsLocalDebugger", config);
+        }
+        changed_configs += 1;
+        info!("\n🔧 {}:", config);
+        for change in &changes {
+            info!("  {}", change);
+        }
+        output::report(Change::ConfigModified, [config]);
+    }
+    if changed_configs == 0 {
+        info!("✅ No changes - the debugger settings are already set");
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update user file: {}", user_file.path.display());
+        return Ok(true);
+    }
+    user_file.save()?;
+    info!("\n✅ Updated debugger settings in {} configurations of {}", changed_configs, user_file.path.display());
+    Ok(true)
+}
+
+/// A user file value on one line, with the variables of the environment separated by ␤
+fn single_line(value: &str) -> String {
+    value.replace('\n', " ␤ ")
+}
+
+/// Sets (or with an empty value removes) the debugger settings of `user::DEBUGGER_PROPERTIES`, given
+/// in that order, in the selected configurations of the project's .vcxproj.user file
+pub fn set_user_debugger(project_path: PathBuf, values: [Option<String>; 4], config: Option<String>, dryrun: bool) -> Result<bool> {
+    if values.iter().all(Option::is_none) {
+        return Err(anyhow::anyhow!("Nothing to do: pass --command, --args, --working-dir or --env"));
+    }
+    edit_user_file(project_path, config, dryrun, |user_file, config| {
+        let mut changes = Vec::new();
+        for (name, value) in user::DEBUGGER_PROPERTIES.iter().zip(&values) {
+            match value.as_deref() {
+                None => {}
+                Some("") => {
+                    if let Some(previous) = user_file.remove(name, config) {
+                        changes.push(format!("{}: {} → (removed)", name, single_line(&previous)));
+                    }
+                }
+                Some(value) => {
+                    if let Some(previous) = user_file.set(name, value, config) {
+                        changes.push(describe_setting_change(name, previous.as_deref().map(single_line).as_deref(), &single_line(value)));
+                    }
+                }
+            }
+        }
+        changes
+    })
+}
+
+/// Adds a directory to the PATH of the debugged program (in LocalDebuggerEnvironment) in the
+/// selected configurations of the project's .vcxproj.user file
+pub fn add_user_path(project_path: PathBuf, directory: String, prepend: bool, config: Option<String>, dryrun: bool) -> Result<bool> {
+    edit_user_file(project_path, config, dryrun, |user_file, config| {
+        let environment = user_file.get("LocalDebuggerEnvironment", config).unwrap_or_default();
+        let Some(updated) = user::add_to_path(&environment, &directory, prepend) else {
+            return Vec::new();
+        };
+        user_file.set("LocalDebuggerEnvironment", &updated, config);
+        let path = |environment: &str| {
+            environment.lines().find(|line| user::is_path_variable(line)).and_then(|line| line.split_once('=')).map(|(_, value)| value.to_string())
+        };
+        vec![describe_setting_change("PATH", path(&environment).as_deref(), &path(&updated).unwrap_or_default())]
+    })
+}
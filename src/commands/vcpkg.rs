@@ -0,0 +1,165 @@
+use anyhow::{Context, Result};
+use crate::i18n::{info, tr};
+use crate::output::{self, Change};
+use crate::vcxproj::{self, ListPosition, VcxprojFile};
+use std::path::PathBuf;
+
+/// Turns on vcpkg's MSBuild integration for a project. Without `root` the project relies on the
+/// user-wide integration (`vcpkg integrate install`); with it, vcpkg.props and vcpkg.targets are imported.
+pub fn enable_vcpkg(project_path: PathBuf, triplet: Option<String>, manifest: bool, root: Option<String>, dryrun: bool) -> Result<bool> {
+    info!("Enabling vcpkg in project: {}", project_path.display());
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let mut properties = vec![("VcpkgEnabled", "true")];
+    if let Some(triplet) = &triplet {
+        properties.push(("VcpkgTriplet", triplet));
+    }
+    if manifest {
+        properties.push(("VcpkgEnableManifest", "true"));
+    }
+    let mut changes: Vec<String> = vcxproj
+        .set_labeled_properties("Vcpkg", &properties)?
+        .into_iter()
+        .map(|name| tr!("Set {}", name))
+        .collect();
+    
+    if let Some(root) = &root {
+        let scripts = format!("{}\\scripts\\buildsystems\\msbuild", root.trim_end_matches(['\\', '/']));
+        let props = format!("{}\\vcpkg.props", scripts);
+        let targets = format!("{}\\vcpkg.targets", scripts);
+        // vcpkg.props must be evaluated before the C++ props, vcpkg.targets after the C++ targets
+        if vcxproj.add_import(&props, "Microsoft.Cpp.props", false)? {
+            changes.push(tr!("Import {}", props));
+        }
+        if vcxproj.add_import(&targets, "Microsoft.Cpp.targets", true)? {
+            changes.push(tr!("Import {}", targets));
+        }
+    }
+    
+    if changes.is_empty() {
+        info!("✅ No changes - vcpkg is already enabled");
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would update {}:", project_path.display());
+        for change in &changes {
+            info!("  - {}", change);
+        }
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    info!("✅ vcpkg enabled:");
+    for change in &changes {
+        info!("  - {}", change);
+    }
+    if root.is_none() {
+        info!("Dependencies are found through the user-wide integration (`vcpkg integrate install`); pass --root to import vcpkg from a specific checkout");
+    }
+    Ok(true)
+}
+
+/// Wires ports installed in a classic-mode vcpkg into a project: the triplet's include directory in
+/// every configuration, and its `lib` (or `debug\lib`) directory and libraries in release (or debug) ones
+pub fn add_vcpkg_ports(project_path: PathBuf, ports: Vec<String>, triplet: Option<String>, root: Option<PathBuf>, dryrun: bool) -> Result<bool> {
+    // A root taken from the environment is written as $(VCPKG_ROOT) so the project stays portable
+    let (root, root_in_project) = match root {
+        Some(root) => {
+            let written = root.display().to_string();
+            (root, written)
+        }
+        None => {
+            let root = std::env::var_os("VCPKG_ROOT")
+                .ok_or_else(|| anyhow::anyhow!("Pass --root or set VCPKG_ROOT to the vcpkg directory"))?;
+            (PathBuf::from(root), "$(VCPKG_ROOT)".to_string())
+        }
+    };
+    
+    let mut vcxproj = VcxprojFile::load(&project_path)?;
+    let triplet = triplet
+        .or_else(|| vcxproj.get_property("VcpkgTriplet"))
+        .ok_or_else(|| anyhow::anyhow!("Pass --triplet (the project has no VcpkgTriplet property)"))?;
+    info!("Adding vcpkg ports {} ({}) to project: {}", ports.join(", "), triplet, project_path.display());
+    
+    let info_dir = root.join("installed").join("vcpkg").join("info");
+    let list_files: Vec<PathBuf> = std::fs::read_dir(&info_dir)
+        .with_context(|| format!("No classic-mode vcpkg installation at {}", root.display()))?
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .collect();
+    
+    let mut release_libs = Vec::new();
+    let mut debug_libs = Vec::new();
+    for port in &ports {
+        // Each installed package lists its files in info/<port>_<version>_<triplet>.list
+        let suffix = format!("_{}.list", triplet);
+        let list = list_files
+            .iter()
+            .find(|path| {
+                let name = path.file_name().unwrap_or_default().to_string_lossy();
+                name.strip_suffix(&suffix)
+                    .and_then(|rest| rest.rsplit_once('_'))
+                    .is_some_and(|(name, _)| name.eq_ignore_ascii_case(port))
+            })
+            .ok_or_else(|| anyhow::anyhow!("Port '{}' is not installed for {} (run `vcpkg install {}:{}`)", port, triplet, port, triplet))?;
+        let content = std::fs::read_to_string(list)
+            .with_context(|| format!("Failed to read {}", list.display()))?;
+        
+        // Only libraries directly in lib/ are meant to be linked (lib/manual-link/ is opt-in)
+        for entry in content.lines().map(str::trim) {
+            let Some(path) = entry.strip_prefix(&format!("{}/", triplet)) else {
+                continue;
+            };
+            let (libs, file) = match (path.strip_prefix("debug/lib/"), path.strip_prefix("lib/")) {
+                (Some(file), _) => (&mut debug_libs, file),
+                (None, Some(file)) => (&mut release_libs, file),
+                _ => continue,
+            };
+            if !file.contains('/') && file.to_lowercase().ends_with(".lib") && !libs.iter().any(|l: &String| l == file) {
+                libs.push(file.to_string());
+            }
+        }
+    }
+    
+    let installed = format!("{}\\installed\\{}", root_in_project.trim_end_matches(['\\', '/']), triplet);
+    let mut modified_configs = vcxproj.add_include_directory(&format!("{}\\include", installed))?;
+    for config in vcxproj.get_configurations() {
+        let (lib_dir, libs) = if vcxproj.is_debug_configuration(&config) {
+            (format!("{}\\debug\\lib", installed), &debug_libs)
+        } else {
+            (format!("{}\\lib", installed), &release_libs)
+        };
+        modified_configs.extend(vcxproj.add_library_directory(&lib_dir, Some(&config))?);
+        for lib in libs {
+            modified_configs.extend(vcxproj.add_library_dependency(lib, Some(&config), &ListPosition::Back)?);
+        }
+    }
+    let mut configs: Vec<String> = modified_configs.iter().map(|c| vcxproj::condition_config(c).into_owned()).collect();
+    configs.sort();
+    configs.dedup();
+    
+    if configs.is_empty() {
+        info!("✅ No changes - every configuration already uses these ports");
+        return Ok(false);
+    }
+    
+    if dryrun {
+        info!("\n🔍 DRY RUN - No files were modified");
+        info!("Would add include and library paths for {} to {} configurations:", ports.join(", "), configs.len());
+        for config in &configs {
+            info!("  - {}", config);
+        }
+        return Ok(true);
+    }
+    
+    vcxproj.save()?;
+    output::report(Change::ConfigModified, configs.iter());
+    info!("✅ Added include and library paths for {} to {} configurations:", ports.join(", "), configs.len());
+    for config in &configs {
+        info!("  - {}", config);
+    }
+    info!("Libraries: {} (release), {} (debug)", release_libs.join(";"), debug_libs.join(";"));
+    Ok(true)
+}
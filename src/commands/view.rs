@@ -0,0 +1,528 @@
+use anyhow::{Context, Result};
+use crate::export;
+use crate::i18n::{info, tr, warn};
+use crate::output;
+use crate::pipe;
+use crate::properties;
+use crate::sln::SolutionFile;
+use crate::vcxproj::{self, FilterFile, NON_FILE_ITEM_TYPES, ProjectStructure, VcxprojFile};
+use regex::Regex;
+use std::path::{Path, PathBuf};
+use super::new::project_template;
+
+/// Prints the project tree. `only_ext` and `pattern` narrow it to the matching files and the
+/// filters leading to them, and `check_disk` marks the files missing on disk.
+#[allow(clippy::too_many_arguments)]
+pub fn view_project_structure(
+    project_path: PathBuf,
+    files_only: bool,
+    level: Option<usize>,
+    show_props: bool,
+    sort: Option<String>,
+    only_ext: Option<String>,
+    pattern: Option<String>,
+    check_disk: bool,
+) -> Result<()> {
+    // Load and parse the project structure
+    let mut structure = ProjectStructure::from_project(&project_path)?;
+    
+    let total_files = structure.files.len();
+    let extensions: Option<Vec<String>> = only_ext.map(|exts| {
+        exts.split(',').map(|ext| ext.trim().trim_start_matches('.').to_lowercase()).filter(|ext| !ext.is_empty()).collect()
+    });
+    let regex = match &pattern {
+        Some(pattern) => Some(Regex::new(pattern).context("Invalid regex pattern")?),
+        None => None,
+    };
+    let selective = extensions.is_some() || regex.is_some();
+    if selective {
+        structure.retain_files(|file| {
+            let extension = Path::new(&file.path.replace('\\', "/")).extension().map(|ext| ext.to_string_lossy().to_lowercase());
+            extensions.as_ref().is_none_or(|exts| extension.is_some_and(|ext| exts.contains(&ext)))
+                && regex.as_ref().is_none_or(|regex| regex.is_match(&file.path))
+        });
+    }
+    
+    // Paths are checked after property expansion; wildcards and paths using properties nothing
+    // defines cannot be checked
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    if check_disk {
+        let vcxproj = VcxprojFile::load(&project_path)?;
+        let properties = properties::Properties::for_project(&vcxproj, None);
+        structure.missing = structure
+            .files
+            .iter()
+            .filter(|file| !file.path.contains('*'))
+            .filter(|file| properties.resolve_path(project_dir, &file.path).is_ok_and(|path| !path.exists()))
+            .map(|file| file.path.clone())
+            .collect();
+    }
+    
+    // Display the tree structure (extensions always shown), unless no file matched
+    let tree_output = if selective && structure.files.is_empty() {
+        String::new()
+    } else {
+        structure.display_tree(files_only, true, level, show_props, sort.as_deref())
+    };
+    if !tree_output.is_empty() {
+        info!("{}", tree_output.trim_end_matches('\n'));
+    }
+    
+    // Show summary
+    let file_count = structure.files.len();
+    let filter_count = structure.filters.len();
+    
+    let missing = if check_disk { tr!(", {} missing on disk", structure.missing.len()) } else { String::new() };
+    if selective {
+        info!("⚡︎ Project summary: {} of {} files match{}\n", file_count, total_files, missing);
+    } else if file_count == 0 && filter_count == 0 {
+        info!("⚡︎ Project summary: Empty project\n");
+    } else if !files_only && filter_count > 0 {
+        info!("⚡︎ Project summary: {} files, {} filters{}\n", file_count, filter_count, missing);
+    } else {
+        info!("⚡︎ Project summary: {} files{}\n", file_count, missing);
+    }
+    
+    // Files of imported shared items projects build with this project but are listed in their own file
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    if let Some(modules) = vcxproj.get_property("QtModules") {
+        info!("🧩 Qt modules: {}", modules.replace(';', ", "));
+    }
+    for import in vcxproj.get_shared_items_imports() {
+        let items_path = project_dir.join(import.replace('\\', "/"));
+        match ProjectStructure::from_project(&items_path) {
+            Ok(shared) => info!("🔗 Shared items: {} ({} files)", import, shared.files.len()),
+            Err(_) => warn!("⚠️  Shared items project not found: {}", import),
+        }
+    }
+    
+    Ok(())
+}
+
+pub fn search_projects(
+    project_path: Option<PathBuf>,
+    solution_path: Option<PathBuf>,
+    pattern: String,
+) -> Result<()> {
+    let projects = match (project_path, solution_path) {
+        (Some(project), None) => vec![project],
+        (None, Some(solution)) => {
+            info!("Searching solution: {}", solution.display());
+            SolutionFile::load(&solution)?.vcxproj_paths()
+        }
+        (Some(_), Some(_)) => {
+            return Err(anyhow::anyhow!("Specify either --project or --solution, not both"));
+        }
+        (None, None) => {
+            return Err(anyhow::anyhow!("Either --project or --solution must be specified"));
+        }
+    };
+    
+    let regex = Regex::new(&pattern).context("Invalid regex pattern")?;
+    let mut total_files = 0;
+    let mut total_filters = 0;
+    
+    for project in &projects {
+        let (files, filters) = search_project(project, &regex)?;
+        total_files += files;
+        total_filters += filters;
+    }
+    
+    if total_files == 0 && total_filters == 0 {
+        info!("No files or filters found matching: {}", pattern);
+    } else {
+        info!("\n⚡︎ Search summary: {} files, {} filters matched\n", total_files, total_filters);
+    }
+    
+    Ok(())
+}
+
+fn search_project(project_path: &Path, regex: &Regex) -> Result<(usize, usize)> {
+    let vcxproj = VcxprojFile::load(project_path)?;
+    let filter_path = vcxproj::filters_path(project_path);
+    let (file_filters, all_filters) = if pipe::exists(&filter_path) {
+        let filter_file = FilterFile::load(&filter_path)?;
+        (filter_file.get_item_filters()?, filter_file.get_all_filters()?)
+    } else {
+        (Default::default(), Default::default())
+    };
+    
+    let matched_items: Vec<_> = vcxproj.get_items()?
+        .into_iter()
+        .filter(|item| regex.is_match(&item.include))
+        .collect();
+    
+    let mut matched_filters: Vec<_> = all_filters
+        .iter()
+        .filter(|(name, _)| regex.is_match(name))
+        .collect();
+    matched_filters.sort_by_key(|(name, _)| name.as_str());
+    
+    if matched_items.is_empty() && matched_filters.is_empty() {
+        return Ok((0, 0));
+    }
+    
+    info!("\n📁 {}", project_path.display());
+    for item in &matched_items {
+        info!("  📄 {}", item.include);
+        info!("     type: {}", item.item_type);
+        if let Some(filter) = file_filters.get(&item.include) {
+            info!("     filter: {}", filter);
+        }
+        for metadata in item.metadata.iter().filter(|m| m.condition.is_some()) {
+            info!(
+                "     {} = {} when {}",
+                metadata.name,
+                metadata.value,
+                metadata.condition.as_deref().unwrap_or_default()
+            );
+        }
+    }
+    for (name, files) in &matched_filters {
+        info!("  📁 filter: {} ({} files)", name, files.len());
+    }
+    
+    Ok((matched_items.len(), matched_filters.len()))
+}
+
+pub fn show_project_stats(project_path: PathBuf, json: bool) -> Result<()> {
+    use std::collections::BTreeMap;
+    
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let items = vcxproj.get_items()?;
+    let configurations = vcxproj.get_configurations();
+    let project_dir = project_path.parent().unwrap_or_else(|| Path::new("."));
+    let properties = properties::Properties::for_project(&vcxproj, None);
+    
+    let filter_path = vcxproj::filters_path(&project_path);
+    let file_filters = if pipe::exists(&filter_path) {
+        FilterFile::load(&filter_path)?.get_item_filters()?
+    } else {
+        Default::default()
+    };
+    
+    let mut item_types: BTreeMap<String, usize> = BTreeMap::new();
+    let mut extensions: BTreeMap<String, usize> = BTreeMap::new();
+    let mut filters: BTreeMap<String, usize> = BTreeMap::new();
+    let mut seen: BTreeMap<(String, String), usize> = BTreeMap::new();
+    let mut missing_files = Vec::new();
+    
+    for item in &items {
+        *item_types.entry(item.item_type.clone()).or_default() += 1;
+        
+        let extension = Path::new(&item.include.replace('\\', "/"))
+            .extension()
+            .map(|e| format!(".{}", e.to_string_lossy().to_lowercase()))
+            .unwrap_or_else(|| "(none)".to_string());
+        *extensions.entry(extension).or_default() += 1;
+        
+        let filter = file_filters.get(&item.include).cloned().unwrap_or_else(|| "(no filter)".to_string());
+        *filters.entry(filter).or_default() += 1;
+        
+        *seen.entry((item.item_type.clone(), item.include.to_lowercase())).or_default() += 1;
+        
+        // Wildcards and paths using properties nothing defines cannot be checked
+        if item.include.contains('*') {
+            continue;
+        }
+        if properties.resolve_path(project_dir, &item.include).is_ok_and(|path| !path.exists()) {
+            missing_files.push(item.include.clone());
+        }
+    }
+    
+    let duplicates: Vec<String> = seen
+        .iter()
+        .filter(|(_, count)| **count > 1)
+        .map(|((item_type, include), count)| format!("{} {} (x{})", item_type, include, count))
+        .collect();
+    
+    if json {
+        let stats = serde_json::json!({
+            "project": project_path.display().to_string(),
+            "total_items": items.len(),
+            "item_types": item_types,
+            "extensions": extensions,
+            "filters": filters,
+            "configurations": configurations,
+            "missing_files": missing_files,
+            "duplicates": duplicates,
+        });
+        output::print_line(serde_json::to_string_pretty(&stats)?);
+        return Ok(());
+    }
+    
+    info!("📊 Project statistics: {}\n", project_path.display());
+    
+    info!("Item types:");
+    for (item_type, count) in &item_types {
+        info!("  {:<32} {:>6}", item_type, count);
+    }
+    
+    info!("\nExtensions:");
+    for (extension, count) in &extensions {
+        info!("  {:<32} {:>6}", extension, count);
+    }
+    
+    info!("\nFiles per filter:");
+    for (filter, count) in &filters {
+        info!("  {:<32} {:>6}", filter, count);
+    }
+    
+    info!("\nConfigurations: {}", configurations.len());
+    for config in &configurations {
+        info!("  - {}", config);
+    }
+    
+    info!("\nMissing on disk: {}", missing_files.len());
+    for file in &missing_files {
+        info!("  - {}", file);
+    }
+    
+    info!("\nDuplicate entries: {}", duplicates.len());
+    for duplicate in &duplicates {
+        info!("  - {}", duplicate);
+    }
+    
+    info!("\n⚡︎ Project summary: {} items, {} configurations\n", items.len(), configurations.len());
+    Ok(())
+}
+
+/// Properties that name the project rather than configure it, so they always differ from a template
+const IDENTITY_PROPERTIES: &[&str] = &["ProjectGuid", "RootNamespace", "ProjectName"];
+
+/// Summarizes how a project deviates from what `new` generates for its template: properties and tool
+/// settings that differ, were added or were removed, imports, configurations and per-file settings
+pub fn explain_project(project_path: PathBuf, template: Option<String>) -> Result<()> {
+    use std::collections::HashMap;
+    
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let configs = vcxproj.get_configurations();
+    let template = template.unwrap_or_else(|| {
+        let configuration_type = configs.first().and_then(|config| vcxproj.get_config_property("ConfigurationType", config));
+        match configuration_type.as_deref() {
+            Some("Utility") => "utility",
+            Some("StaticLibrary") => "static-lib",
+            _ => "console-app",
+        }
+        .to_string()
+    });
+    let baseline = VcxprojFile {
+        path: project_path.clone(),
+        content: project_template(&template, "", &uuid::Uuid::nil())?,
+    };
+    let baseline_configs = baseline.get_configurations();
+    info!("Comparing {} with a new {} project", project_path.display(), template);
+    
+    let scope = |condition: &Option<String>| condition.as_deref().map_or("All configurations".to_string(), |c| vcxproj::condition_config(c).into_owned());
+    // Settings of configurations only one side has are covered by the configuration lists
+    let comparable = |scope: &str| {
+        scope == "All configurations"
+            || !(configs.iter().any(|c| c == scope) ^ baseline_configs.iter().any(|c| c == scope))
+    };
+    let mut deviations = 0;
+    
+    let extra_configs: Vec<&String> = configs.iter().filter(|c| !baseline_configs.contains(c)).collect();
+    let missing_configs: Vec<&String> = baseline_configs.iter().filter(|c| !configs.contains(c)).collect();
+    if !extra_configs.is_empty() || !missing_configs.is_empty() {
+        info!("\n🧩 Configurations:");
+        for config in &extra_configs {
+            info!("  + {}", config);
+        }
+        for config in &missing_configs {
+            info!("  - {}", config);
+        }
+        deviations += extra_configs.len() + missing_configs.len();
+    }
+    
+    // (scope, name) -> value, keeping the first definition like the comparison of tool settings below
+    let mut template_properties: HashMap<(String, String), String> = HashMap::new();
+    for property in baseline.get_properties() {
+        template_properties.entry((scope(&property.condition), property.name)).or_insert(property.value);
+    }
+    let mut lines = Vec::new();
+    let mut seen = Vec::new();
+    for property in vcxproj.get_properties() {
+        let key = (scope(&property.condition), property.name.clone());
+        if IDENTITY_PROPERTIES.contains(&property.name.as_str()) || seen.contains(&key) || !comparable(&key.0) {
+            continue;
+        }
+        match template_properties.get(&key) {
+            Some(expected) if *expected == property.value => {}
+            Some(expected) => lines.push(format!("  ~ {} {}: {} (template: {})", key.0, key.1, property.value, expected)),
+            None => lines.push(format!("  + {} {}: {}", key.0, key.1, property.value)),
+        }
+        seen.push(key);
+    }
+    for property in baseline.get_properties() {
+        let key = (scope(&property.condition), property.name.clone());
+        if !IDENTITY_PROPERTIES.contains(&property.name.as_str()) && !seen.contains(&key) && configs_cover(&configs, &key.0) {
+            lines.push(format!("  - {} {} (template: {})", key.0, key.1, property.value));
+            seen.push(key);
+        }
+    }
+    if !lines.is_empty() {
+        info!("\n⚙️  Properties:");
+        deviations += lines.len();
+        lines.iter().for_each(|line| info!("{}", line));
+    }
+    
+    let mut template_settings: HashMap<(String, String, String), String> = HashMap::new();
+    for setting in baseline.get_tool_settings() {
+        template_settings.entry((scope(&setting.condition), setting.tool, setting.name)).or_insert(setting.value);
+    }
+    let mut lines = Vec::new();
+    let mut seen = Vec::new();
+    for setting in vcxproj.get_tool_settings() {
+        let key = (scope(&setting.condition), setting.tool.clone(), setting.name.clone());
+        if seen.contains(&key) || !comparable(&key.0) {
+            continue;
+        }
+        match template_settings.get(&key) {
+            Some(expected) if *expected == setting.value => {}
+            Some(expected) => lines.push(format!("  ~ {} {}.{}: {} (template: {})", key.0, key.1, key.2, setting.value, expected)),
+            None => lines.push(format!("  + {} {}.{}: {}", key.0, key.1, key.2, setting.value)),
+        }
+        seen.push(key);
+    }
+    for setting in baseline.get_tool_settings() {
+        let key = (scope(&setting.condition), setting.tool.clone(), setting.name.clone());
+        if !seen.contains(&key) && configs_cover(&configs, &key.0) {
+            lines.push(format!("  - {} {}.{} (template: {})", key.0, key.1, key.2, setting.value));
+            seen.push(key);
+        }
+    }
+    if !lines.is_empty() {
+        info!("\n🔧 Tool settings:");
+        deviations += lines.len();
+        lines.iter().for_each(|line| info!("{}", line));
+    }
+    
+    let imports = vcxproj.get_imports();
+    let template_imports = baseline.get_imports();
+    let has = |list: &[String], import: &String| list.iter().any(|i| i.eq_ignore_ascii_case(import));
+    let mut lines: Vec<String> = Vec::new();
+    for import in &imports {
+        if !has(&template_imports, import) && !lines.iter().any(|l| l.ends_with(import.as_str())) {
+            lines.push(format!("  + {}", import));
+        }
+    }
+    for import in &template_imports {
+        if !has(&imports, import) && !lines.iter().any(|l| l.ends_with(import.as_str())) {
+            lines.push(format!("  - {}", import));
+        }
+    }
+    if !lines.is_empty() {
+        info!("\n📦 Imports:");
+        deviations += lines.len();
+        lines.iter().for_each(|line| info!("{}", line));
+    }
+    
+    let items = vcxproj.get_items()?;
+    let customized: Vec<&vcxproj::ProjectItem> = items
+        .iter()
+        .filter(|item| item.item_type != "ProjectConfiguration" && item.metadata.iter().any(|m| m.name != "Filter"))
+        .collect();
+    if !customized.is_empty() {
+        info!("\n📄 {} files carry per-file settings:", customized.len());
+        for item in customized.iter().take(10) {
+            let names: Vec<&str> = item.metadata.iter().map(|m| m.name.as_str()).collect();
+            info!("  {} {}: {}", item.item_type, item.include, names.join(", "));
+        }
+        if customized.len() > 10 {
+            info!("  ... and {} more", customized.len() - 10);
+        }
+        deviations += customized.len();
+    }
+    
+    if deviations == 0 {
+        info!("\n✅ No deviations from the {} template", template);
+    } else {
+        info!("\n📋 {} deviations from the {} template", deviations, template);
+    }
+    Ok(())
+}
+
+/// Whether a template setting's scope applies to the project: every configuration, or one the project has
+fn configs_cover(configs: &[String], scope: &str) -> bool {
+    scope == "All configurations" || configs.iter().any(|c| c == scope)
+}
+
+/// Prints the project's file items one per line (or as CSV/JSON): path, then item type, filter and
+/// the requested metadata. Metadata set per configuration is shown as "Debug|x64=value;...".
+pub fn list_project_files(project_path: PathBuf, item_types: Vec<String>, filter: Option<String>, format: &str, metadata: Vec<String>) -> Result<()> {
+    let vcxproj = VcxprojFile::load(&project_path)?;
+    let filter_path = vcxproj::filters_path(&project_path);
+    let file_filters = if pipe::exists(&filter_path) {
+        FilterFile::load(&filter_path)?.get_item_filters()?
+    } else {
+        Default::default()
+    };
+    let wanted_filter = filter.as_deref().map(|f| f.replace('/', "\\").trim_matches('\\').to_lowercase());
+    
+    let mut rows = Vec::new();
+    for item in vcxproj.get_items()? {
+        let wanted_type = if item_types.is_empty() {
+            !NON_FILE_ITEM_TYPES.contains(&item.item_type.as_str())
+        } else {
+            item_types.iter().any(|t| t.eq_ignore_ascii_case(&item.item_type))
+        };
+        let item_filter = file_filters.get(&item.include).cloned().unwrap_or_default();
+        let in_filter = wanted_filter.as_deref().is_none_or(|wanted| {
+            let lower = item_filter.to_lowercase();
+            lower == wanted || lower.starts_with(&format!("{}\\", wanted))
+        });
+        if !wanted_type || !in_filter {
+            continue;
+        }
+        
+        let values: Vec<String> = metadata
+            .iter()
+            .map(|name| {
+                let entries: Vec<_> = item.metadata.iter().filter(|m| m.name.eq_ignore_ascii_case(name)).collect();
+                match entries.as_slice() {
+                    [entry] if entry.condition.is_none() => entry.value.clone(),
+                    entries => entries
+                        .iter()
+                        .map(|m| format!("{}={}", m.condition.as_deref().map_or("*".into(), vcxproj::condition_config), m.value))
+                        .collect::<Vec<_>>()
+                        .join(";"),
+                }
+            })
+            .collect();
+        rows.push((item, item_filter, values));
+    }
+    
+    match format {
+        "json" => {
+            let files: Vec<_> = rows
+                .iter()
+                .map(|(item, filter, values)| {
+                    let metadata: serde_json::Map<String, serde_json::Value> =
+                        metadata.iter().zip(values).map(|(name, value)| (name.clone(), value.clone().into())).collect();
+                    serde_json::json!({
+                        "path": item.include,
+                        "item_type": item.item_type,
+                        "filter": filter,
+                        "metadata": metadata,
+                    })
+                })
+                .collect();
+            output::print_line(serde_json::to_string_pretty(&files)?);
+        }
+        "csv" => {
+            let header = ["path", "item_type", "filter"].into_iter().chain(metadata.iter().map(String::as_str));
+            output::print_line(header.map(export::csv_field).collect::<Vec<_>>().join(","));
+            for (item, filter, values) in &rows {
+                let row = [&item.include, &item.item_type, filter].into_iter().chain(values);
+                output::print_line(row.map(|v| export::csv_field(v)).collect::<Vec<_>>().join(","));
+            }
+        }
+        _ => {
+            for (item, _, values) in &rows {
+                let row = std::iter::once(&item.include).chain(values);
+                output::print_line(row.map(String::as_str).collect::<Vec<_>>().join("\t"));
+            }
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,174 @@
+use anyhow::{Context, Result};
+use crate::history;
+use crate::i18n::{error, info, warn};
+use crate::layout::FilterMap;
+use crate::output::{self, Change};
+use crate::pipe;
+use crate::properties;
+use crate::vcxproj::{self, FilterFile, NON_FILE_ITEM_TYPES, VcxprojFile};
+use log::{debug, trace};
+use std::path::{Path, PathBuf};
+use super::{glob_to_regex, scan_paths};
+use super::files::create_basic_filter_file_with_hierarchy;
+
+/// Watches the project's directory and applies every burst of file creations, deletions and
+/// renames once it settles: `sync_watched_files` adds and removes the items. Each sync is its own
+/// history entry, so it can be undone; a failed sync is rolled back and watching goes on.
+pub fn watch_project(project_path: PathBuf, extensions: &str, excluded_dirs: Vec<String>, respect_gitignore: bool, maps: &[String], debounce_ms: u64) -> Result<()> {
+    use notify::event::ModifyKind;
+    use notify::{EventKind, RecursiveMode, Watcher};
+    use std::sync::mpsc::{self, RecvTimeoutError};
+    
+    let extensions: Vec<String> = extensions
+        .split(',')
+        .map(|ext| ext.trim().trim_start_matches('.').to_lowercase())
+        .filter(|ext| !ext.is_empty())
+        .collect();
+    if extensions.is_empty() {
+        return Err(anyhow::anyhow!("No extensions given with --ext"));
+    }
+    let project_dir = project_path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let command = std::env::args().skip(1).collect::<Vec<_>>().join(" ");
+    
+    let sync = || {
+        match sync_watched_files(&project_path, &extensions, &excluded_dirs, respect_gitignore, maps) {
+            Ok(_) => history::finish()?,
+            Err(err) => {
+                history::rollback()?;
+                error!("❌ [{}] Sync failed: {:#}", clock_time(), err);
+            }
+        }
+        history::begin(command.clone(), Some(project_path.clone()));
+        Ok::<_, anyhow::Error>(())
+    };
+    
+    // Catch up with what changed while nobody was watching
+    sync()?;
+    
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender).context("Failed to start the file watcher")?;
+    watcher
+        .watch(project_dir, RecursiveMode::Recursive)
+        .with_context(|| format!("Failed to watch {}", project_dir.display()))?;
+    info!("👀 Watching {} for {} files - press Ctrl+C to stop", project_dir.display(), extensions.iter().map(|ext| format!("*.{}", ext)).collect::<Vec<_>>().join(", "));
+    
+    // Creations, deletions and renames of watched files (or of directories, which have no
+    // extension) change the item list; content edits and the tool's own writes don't
+    let relevant = |event: &notify::Event| {
+        matches!(event.kind, EventKind::Create(_) | EventKind::Remove(_) | EventKind::Modify(ModifyKind::Name(_)) | EventKind::Any)
+            && event.paths.iter().any(|path| {
+                !path.components().any(|c| c.as_os_str() == ".vcprojm")
+                    && path.extension().is_none_or(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()))
+            })
+    };
+    let debounce = std::time::Duration::from_millis(debounce_ms);
+    loop {
+        match receiver.recv() {
+            Ok(Ok(event)) if relevant(&event) => trace!("{:?} {:?}", event.kind, event.paths),
+            Ok(Ok(_)) => continue,
+            Ok(Err(err)) => {
+                warn!("⚠️  File watcher error: {}", err);
+                continue;
+            }
+            Err(_) => return Ok(()),
+        }
+        loop {
+            match receiver.recv_timeout(debounce) {
+                Ok(_) => {}
+                Err(RecvTimeoutError::Timeout) => break,
+                Err(RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+        sync()?;
+    }
+}
+
+/// One `watch` pass: adds the files with a watched extension that the project doesn't list yet,
+/// with filters following their directories, and removes the items with a watched extension whose
+/// files are gone. Returns whether the project changed.
+fn sync_watched_files(project_path: &Path, extensions: &[String], excluded_dirs: &[String], respect_gitignore: bool, maps: &[String]) -> Result<bool> {
+    use std::collections::HashSet;
+    
+    let project_dir = project_path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let watched = |path: &Path| path.extension().is_some_and(|ext| extensions.contains(&ext.to_string_lossy().to_lowercase()));
+    
+    let map = FilterMap::load(project_dir, maps)?;
+    let mut vcxproj = VcxprojFile::load(project_path)?;
+    let properties = properties::Properties::for_project(&vcxproj, None);
+    let items = vcxproj.get_items()?;
+    let listed: HashSet<String> = items.iter().map(|item| vcxproj::include_key(&item.include)).collect();
+    let wildcards = vcxproj
+        .get_wildcard_items()
+        .iter()
+        .flat_map(|item| item.patterns.clone())
+        .filter(|pattern| pattern.contains('*'))
+        .map(|pattern| glob_to_regex(&pattern))
+        .collect::<Result<Vec<_>>>()?;
+    
+    let mut removed: Vec<String> = Vec::new();
+    for item in &items {
+        if NON_FILE_ITEM_TYPES.contains(&item.item_type.as_str()) || item.include.contains(['*', ';']) || !watched(Path::new(&item.include)) {
+            continue;
+        }
+        let gone = properties.resolve_path(project_dir, &item.include).is_ok_and(|path| !path.exists());
+        if gone && !removed.iter().any(|include| vcxproj::same_path(include, &item.include)) {
+            removed.push(item.include.clone());
+        }
+    }
+    
+    let mut files_to_add = Vec::new();
+    for path in scan_paths(project_dir, true, excluded_dirs, respect_gitignore)? {
+        if !path.is_file() || !watched(&path) {
+            continue;
+        }
+        let relative = path.strip_prefix(project_dir).unwrap_or(&path).to_path_buf();
+        let include = vcxproj::include_path(&relative.to_string_lossy());
+        if listed.contains(&vcxproj::include_key(&include)) || wildcards.iter().any(|w| w.is_match(&include.replace('\\', "/"))) {
+            continue;
+        }
+        files_to_add.push(relative);
+    }
+    
+    if removed.is_empty() && files_to_add.is_empty() {
+        debug!("{} is in sync", project_path.display());
+        return Ok(false);
+    }
+    let now = clock_time();
+    for file in &files_to_add {
+        info!("[{}] ➕ {}", now, vcxproj::include_path(&file.to_string_lossy()));
+    }
+    for include in &removed {
+        info!("[{}] ➖ {}", now, include);
+    }
+    output::report(Change::FileAdded, files_to_add.iter().map(|f| vcxproj::include_path(&f.to_string_lossy())));
+    output::report(Change::FileRemoved, &removed);
+    
+    vcxproj.remove_items(&removed);
+    vcxproj.add_source_files(&files_to_add)?;
+    
+    let filter_path = vcxproj::filters_path(project_path);
+    let filter_file = if pipe::exists(&filter_path) {
+        let mut filter_file = FilterFile::load(&filter_path)?;
+        filter_file.remove_items(&removed);
+        filter_file.add_source_files_with_hierarchy(&files_to_add, &files_to_add, &map)?;
+        Some(filter_file)
+    } else if !files_to_add.is_empty() {
+        let content = create_basic_filter_file_with_hierarchy(project_dir, &files_to_add, &files_to_add, &map)?;
+        Some(FilterFile { path: filter_path, content })
+    } else {
+        None
+    };
+    vcxproj.save_with_filters(filter_file.as_ref())?;
+    info!("[{}] ✅ {}: {} added, {} removed", now, project_path.display(), files_to_add.len(), removed.len());
+    Ok(true)
+}
+
+/// The current UTC time of day as HH:MM:SS, for the `watch` activity log
+fn clock_time() -> String {
+    let seconds = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
+        % 86_400;
+    format!("{:02}:{:02}:{:02}", seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
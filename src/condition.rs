@@ -0,0 +1,432 @@
+/// A parsed MSBuild condition, e.g. `'$(Configuration)|$(Platform)'=='Debug|x64'`
+#[derive(Debug, Clone, PartialEq)]
+pub enum Expr {
+    /// Two operands compared with `==`, `!=`, `<`, `>`, `<=` or `>=`
+    Compare { left: String, op: String, right: String },
+    /// A lone operand such as `true` or `$(UseDebugLibraries)`
+    Value(String),
+    /// A function such as `Exists('file.props')`, which cannot be evaluated without the file system
+    Call(String, Vec<String>),
+    Not(Box<Expr>),
+    And(Box<Expr>, Box<Expr>),
+    Or(Box<Expr>, Box<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    /// A quoted operand, without its quotes
+    Quoted(String),
+    /// An unquoted operand, keyword or function name
+    Word(String),
+    Operator(String),
+    Not,
+    Open,
+    Close,
+    Comma,
+}
+
+fn tokenize(condition: &str) -> Result<Vec<Token>, String> {
+    let mut tokens = Vec::new();
+    let chars: Vec<char> = condition.chars().collect();
+    let mut i = 0;
+    while i < chars.len() {
+        let c = chars[i];
+        match c {
+            _ if c.is_whitespace() => i += 1,
+            '\'' | '"' => {
+                let end = chars[i + 1..]
+                    .iter()
+                    .position(|&q| q == c)
+                    .ok_or_else(|| format!("unterminated string in: {}", condition))?;
+                tokens.push(Token::Quoted(chars[i + 1..i + 1 + end].iter().collect()));
+                i += end + 2;
+            }
+            '(' => {
+                tokens.push(Token::Open);
+                i += 1;
+            }
+            ')' => {
+                tokens.push(Token::Close);
+                i += 1;
+            }
+            ',' => {
+                tokens.push(Token::Comma);
+                i += 1;
+            }
+            '=' | '!' | '<' | '>' => {
+                let two = chars.get(i + 1) == Some(&'=');
+                match (c, two) {
+                    ('!', false) => tokens.push(Token::Not),
+                    ('=', false) => return Err(format!("expected '==' in: {}", condition)),
+                    _ => tokens.push(Token::Operator(chars[i..i + 1 + two as usize].iter().collect())),
+                }
+                i += 1 + two as usize;
+            }
+            _ => {
+                // An unquoted word, which may hold a property like $(Platform) including its parentheses
+                let start = i;
+                let mut depth = 0;
+                while i < chars.len() {
+                    let c = chars[i];
+                    if c == '$' && chars.get(i + 1) == Some(&'(') {
+                        depth += 1;
+                        i += 2;
+                        continue;
+                    }
+                    if depth > 0 {
+                        depth -= usize::from(c == ')');
+                    } else if c.is_whitespace() || "'\"()=!<>,".contains(c) {
+                        break;
+                    }
+                    i += 1;
+                }
+                tokens.push(Token::Word(chars[start..i].iter().collect()));
+            }
+        }
+    }
+    Ok(tokens)
+}
+
+struct Parser {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self.tokens.get(self.position).cloned();
+        self.position += 1;
+        token
+    }
+
+    fn keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn or(&mut self) -> Result<Expr, String> {
+        let mut expr = self.and()?;
+        while self.keyword("or") {
+            self.position += 1;
+            expr = Expr::Or(Box::new(expr), Box::new(self.and()?));
+        }
+        Ok(expr)
+    }
+
+    fn and(&mut self) -> Result<Expr, String> {
+        let mut expr = self.unary()?;
+        while self.keyword("and") {
+            self.position += 1;
+            expr = Expr::And(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    fn unary(&mut self) -> Result<Expr, String> {
+        match self.peek() {
+            Some(Token::Not) => {
+                self.position += 1;
+                Ok(Expr::Not(Box::new(self.unary()?)))
+            }
+            Some(Token::Open) => {
+                self.position += 1;
+                let expr = self.or()?;
+                match self.next() {
+                    Some(Token::Close) => Ok(expr),
+                    _ => Err("expected ')'".to_string()),
+                }
+            }
+            _ => self.comparison(),
+        }
+    }
+
+    fn comparison(&mut self) -> Result<Expr, String> {
+        let left = match self.next() {
+            Some(Token::Word(name)) if self.peek() == Some(&Token::Open) => {
+                self.position += 1;
+                let mut args = Vec::new();
+                loop {
+                    match self.next() {
+                        Some(Token::Close) => break,
+                        Some(Token::Comma) => {}
+                        Some(Token::Quoted(arg) | Token::Word(arg)) => args.push(arg),
+                        _ => return Err(format!("unterminated call to {}", name)),
+                    }
+                }
+                return Ok(Expr::Call(name, args));
+            }
+            Some(Token::Quoted(value) | Token::Word(value)) => value,
+            token => return Err(format!("expected a value, found {:?}", token)),
+        };
+        if let Some(Token::Operator(op)) = self.peek().cloned() {
+            self.position += 1;
+            let right = match self.next() {
+                Some(Token::Quoted(value) | Token::Word(value)) => value,
+                token => return Err(format!("expected a value after {}, found {:?}", op, token)),
+            };
+            return Ok(Expr::Compare { left, op, right });
+        }
+        Ok(Expr::Value(left))
+    }
+}
+
+/// Parses an MSBuild condition: comparisons, `!`, `and`, `or`, parentheses and function calls.
+/// The condition may be taken straight from the attribute, with XML entities like `&quot;` still in it.
+pub fn parse(condition: &str) -> Result<Expr, String> {
+    let condition = condition
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&");
+    let condition = condition.as_str();
+    let mut parser = Parser { tokens: tokenize(condition)?, position: 0 };
+    let expr = parser.or()?;
+    match parser.peek() {
+        None => Ok(expr),
+        Some(token) => Err(format!("unexpected {:?} in: {}", token, condition)),
+    }
+}
+
+/// Replaces every `$(Name)` in `text` with its value, or returns `None` when a property is unknown
+pub fn expand(text: &str, property: &dyn Fn(&str) -> Option<String>) -> Option<String> {
+    let mut expanded = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find("$(") {
+        let end = start + rest[start..].find(')')?;
+        expanded.push_str(&rest[..start]);
+        expanded.push_str(&property(rest[start + 2..end].trim())?);
+        rest = &rest[end + 1..];
+    }
+    expanded.push_str(rest);
+    Some(expanded)
+}
+
+/// Evaluates a condition with the given property values. Returns `None` when the result depends on
+/// something unknown, such as an unset property or a file's existence.
+pub fn evaluate(expr: &Expr, property: &dyn Fn(&str) -> Option<String>) -> Option<bool> {
+    match expr {
+        Expr::Compare { left, op, right } => {
+            let (left, right) = (expand(left, property)?, expand(right, property)?);
+            match op.as_str() {
+                // MSBuild compares strings case-insensitively
+                "==" => Some(left.eq_ignore_ascii_case(&right)),
+                "!=" => Some(!left.eq_ignore_ascii_case(&right)),
+                _ => {
+                    let (left, right): (f64, f64) = (left.trim().parse().ok()?, right.trim().parse().ok()?);
+                    match op.as_str() {
+                        "<" => Some(left < right),
+                        ">" => Some(left > right),
+                        "<=" => Some(left <= right),
+                        _ => Some(left >= right),
+                    }
+                }
+            }
+        }
+        Expr::Value(value) => match expand(value, property)?.trim().to_ascii_lowercase().as_str() {
+            "true" => Some(true),
+            "false" => Some(false),
+            _ => None,
+        },
+        Expr::Call(..) => None,
+        Expr::Not(inner) => evaluate(inner, property).map(|value| !value),
+        Expr::And(left, right) => match (evaluate(left, property), evaluate(right, property)) {
+            (Some(false), _) | (_, Some(false)) => Some(false),
+            (Some(true), Some(true)) => Some(true),
+            _ => None,
+        },
+        Expr::Or(left, right) => match (evaluate(left, property), evaluate(right, property)) {
+            (Some(true), _) | (_, Some(true)) => Some(true),
+            (Some(false), Some(false)) => Some(false),
+            _ => None,
+        },
+    }
+}
+
+/// The configuration a condition selects. Either part is `None` when the condition leaves it open,
+/// e.g. `'$(Configuration)'=='Debug'` selects Debug on every platform.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigSelection {
+    pub configuration: Option<String>,
+    pub platform: Option<String>,
+}
+
+impl ConfigSelection {
+    /// Reads a configuration name or selector: "Debug|x64", "Debug" or "*|x64"
+    pub fn from_name(name: &str) -> Self {
+        let part = |part: Option<&str>| part.map(str::trim).filter(|p| !p.is_empty() && *p != "*").map(str::to_string);
+        let mut parts = name.splitn(2, '|');
+        Self { configuration: part(parts.next()), platform: part(parts.next()) }
+    }
+
+    /// "Debug|x64", or "Debug" / "*|x64" when a part is open
+    pub fn name(&self) -> String {
+        match (&self.configuration, &self.platform) {
+            (Some(configuration), Some(platform)) => format!("{}|{}", configuration, platform),
+            (Some(configuration), None) => configuration.clone(),
+            (None, Some(platform)) => format!("*|{}", platform),
+            (None, None) => "*".to_string(),
+        }
+    }
+
+    /// Whether this selection and `selector` (see `from_name`) can refer to the same configuration
+    pub fn matches(&self, selector: &str) -> bool {
+        let selector = Self::from_name(selector);
+        let part = |mine: &Option<String>, wanted: &Option<String>| match (mine, wanted) {
+            (Some(mine), Some(wanted)) => mine.eq_ignore_ascii_case(wanted),
+            _ => true,
+        };
+        part(&self.configuration, &selector.configuration) && part(&self.platform, &selector.platform)
+    }
+}
+
+/// The configuration selected by a condition made of `==` comparisons of `$(Configuration)` and
+/// `$(Platform)` joined with `and`, in any order, quoting or spacing. Returns `None` for conditions
+/// that test anything else, which no configuration selector can target.
+pub fn config_selection(condition: &str) -> Option<ConfigSelection> {
+    fn collect(expr: &Expr, selection: &mut ConfigSelection) -> Option<()> {
+        match expr {
+            Expr::And(left, right) => {
+                collect(left, selection)?;
+                collect(right, selection)
+            }
+            Expr::Compare { left, op, right } if op == "==" => {
+                let (template, value) = if left.contains("$(") { (left, right) } else { (right, left) };
+                if value.contains("$(") {
+                    return None;
+                }
+                let (names, values): (Vec<&str>, Vec<&str>) = (template.split('|').collect(), value.split('|').collect());
+                if names.len() != values.len() {
+                    return None;
+                }
+                for (name, value) in names.into_iter().zip(values) {
+                    let slot = match name.trim() {
+                        name if name.eq_ignore_ascii_case("$(Configuration)") => &mut selection.configuration,
+                        name if name.eq_ignore_ascii_case("$(Platform)") => &mut selection.platform,
+                        _ => return None,
+                    };
+                    match slot {
+                        Some(existing) if !existing.eq_ignore_ascii_case(value.trim()) => return None,
+                        _ => *slot = Some(value.trim().to_string()),
+                    }
+                }
+                Some(())
+            }
+            _ => None,
+        }
+    }
+
+    let mut selection = ConfigSelection::default();
+    collect(&parse(condition).ok()?, &mut selection)?;
+    (selection != ConfigSelection::default()).then_some(selection)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn properties(name: &str) -> Option<String> {
+        match name {
+            "Configuration" => Some("Debug".to_string()),
+            "Platform" => Some("x64".to_string()),
+            "UseDebugLibraries" => Some("true".to_string()),
+            "WindowsTargetPlatformVersion" => Some("10.0".to_string()),
+            _ => None,
+        }
+    }
+
+    fn eval(condition: &str) -> Option<bool> {
+        evaluate(&parse(condition).unwrap(), &properties)
+    }
+
+    fn compare(left: &str, op: &str, right: &str) -> Expr {
+        Expr::Compare { left: left.to_string(), op: op.to_string(), right: right.to_string() }
+    }
+
+    #[test]
+    fn parses_single_and_double_quotes_and_entities() {
+        let expected = compare("$(Configuration)|$(Platform)", "==", "Debug|x64");
+        assert_eq!(parse("'$(Configuration)|$(Platform)'=='Debug|x64'"), Ok(expected.clone()));
+        assert_eq!(parse("\"$(Configuration)|$(Platform)\" == \"Debug|x64\""), Ok(expected.clone()));
+        assert_eq!(parse("&apos;$(Configuration)|$(Platform)&apos;==&apos;Debug|x64&apos;"), Ok(expected));
+        assert_eq!(parse("'a b' != ''"), Ok(compare("a b", "!=", "")));
+    }
+
+    #[test]
+    fn parses_unquoted_properties_with_parentheses() {
+        assert_eq!(parse("$(UseDebugLibraries)"), Ok(Expr::Value("$(UseDebugLibraries)".to_string())));
+        assert_eq!(parse("$(Platform)==x64"), Ok(compare("$(Platform)", "==", "x64")));
+    }
+
+    #[test]
+    fn and_binds_tighter_than_or() {
+        let a = || Box::new(compare("a", "==", "1"));
+        let b = || Box::new(compare("b", "==", "2"));
+        let c = || Box::new(compare("c", "==", "3"));
+        assert_eq!(parse("'a'=='1' or 'b'=='2' and 'c'=='3'"), Ok(Expr::Or(a(), Box::new(Expr::And(b(), c())))));
+        assert_eq!(parse("'a'=='1' AND 'b'=='2' Or 'c'=='3'"), Ok(Expr::Or(Box::new(Expr::And(a(), b())), c())));
+        assert_eq!(parse("'a'=='1' and ('b'=='2' or 'c'=='3')"), Ok(Expr::And(a(), Box::new(Expr::Or(b(), c())))));
+    }
+
+    #[test]
+    fn parses_function_calls() {
+        assert_eq!(parse("Exists('$(VCTargetsPath)\\my.props')"), Ok(Expr::Call("Exists".to_string(), vec!["$(VCTargetsPath)\\my.props".to_string()])));
+        assert_eq!(
+            parse("!Exists('a.props') and '$(Platform)'=='x64'"),
+            Ok(Expr::And(Box::new(Expr::Not(Box::new(Expr::Call("Exists".to_string(), vec!["a.props".to_string()])))), Box::new(compare("$(Platform)", "==", "x64"))))
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_conditions() {
+        assert!(parse("'Debug").is_err());
+        assert!(parse("'a' = 'b'").is_err());
+        assert!(parse("('a'=='b'").is_err());
+        assert!(parse("'a'=='b' 'c'").is_err());
+        assert!(parse("Exists('a'").is_err());
+    }
+
+    #[test]
+    fn evaluates_comparisons_case_insensitively() {
+        assert_eq!(eval("'$(Configuration)|$(Platform)'=='debug|X64'"), Some(true));
+        assert_eq!(eval("'$(Configuration)'!='Release'"), Some(true));
+        assert_eq!(eval("'$(WindowsTargetPlatformVersion)' >= '8.1'"), Some(true));
+        assert_eq!(eval("'$(WindowsTargetPlatformVersion)' < '8.1'"), Some(false));
+        assert_eq!(eval("$(UseDebugLibraries)"), Some(true));
+        assert_eq!(eval("!$(UseDebugLibraries)"), Some(false));
+    }
+
+    #[test]
+    fn unknown_parts_leave_the_result_open_unless_decided_by_the_rest() {
+        assert_eq!(eval("'$(Unknown)'=='x'"), None);
+        assert_eq!(eval("Exists('a.props')"), None);
+        assert_eq!(eval("Exists('a.props') and '$(Platform)'=='Win32'"), Some(false));
+        assert_eq!(eval("Exists('a.props') or '$(Platform)'=='x64'"), Some(true));
+        assert_eq!(eval("Exists('a.props') and '$(Platform)'=='x64'"), None);
+    }
+
+    #[test]
+    fn selects_configurations_in_any_order_and_spelling() {
+        let debug_x64 = ConfigSelection { configuration: Some("Debug".to_string()), platform: Some("x64".to_string()) };
+        assert_eq!(config_selection("'$(Configuration)|$(Platform)'=='Debug|x64'"), Some(debug_x64.clone()));
+        assert_eq!(config_selection("'$(Platform)|$(Configuration)'=='x64|Debug'"), Some(debug_x64.clone()));
+        assert_eq!(config_selection("'$(Configuration)'=='Debug' and '$(Platform)'=='x64'"), Some(debug_x64));
+        assert_eq!(config_selection("'$(Configuration)'=='Debug'").map(|s| s.name()), Some("Debug".to_string()));
+        assert_eq!(config_selection("'$(Configuration)'=='Debug' or '$(Platform)'=='x64'"), None);
+        assert_eq!(config_selection("'$(Configuration)'=='Debug' and '$(Configuration)'=='Release'"), None);
+        assert_eq!(config_selection("Exists('a.props')"), None);
+    }
+
+    #[test]
+    fn selection_matches_selectors() {
+        let debug_x64 = ConfigSelection::from_name("Debug|x64");
+        assert!(debug_x64.matches("debug"));
+        assert!(debug_x64.matches("*|X64"));
+        assert!(!debug_x64.matches("Release"));
+        assert!(ConfigSelection::from_name("*|Win32").matches("Release|Win32"));
+        assert_eq!(ConfigSelection::from_name("*|x64").name(), "*|x64");
+    }
+}
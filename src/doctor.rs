@@ -0,0 +1,154 @@
+use crate::vcxproj::{self, VcxprojFile};
+use regex::Regex;
+
+/// Tool settings holding a list that should end with `%(Name)` to keep what property sheets and
+/// the defaults contribute
+const INHERITED_LISTS: &[(&str, &str)] = &[
+    ("ClCompile", "AdditionalIncludeDirectories"),
+    ("ClCompile", "PreprocessorDefinitions"),
+    ("Link", "AdditionalDependencies"),
+    ("Link", "AdditionalLibraryDirectories"),
+    ("Lib", "AdditionalDependencies"),
+    ("Lib", "AdditionalLibraryDirectories"),
+];
+
+/// Tool settings and properties holding directories, checked for the wrong platform and for absolute paths
+const DIRECTORY_SETTINGS: &[(Option<&str>, &str)] = &[
+    (Some("ClCompile"), "AdditionalIncludeDirectories"),
+    (Some("Link"), "AdditionalLibraryDirectories"),
+    (Some("Lib"), "AdditionalLibraryDirectories"),
+    (None, "IncludePath"),
+    (None, "LibraryPath"),
+];
+
+/// A likely mistake in a project, with the correction when there is an obvious one
+#[derive(Debug, Clone)]
+pub struct Problem {
+    /// Stable identifier of the check, e.g. "debug-release-runtime"
+    pub check: &'static str,
+    /// The configuration (e.g. "Debug|x64") it was found in, `None` for the whole project
+    pub config: Option<String>,
+    pub detail: String,
+    pub fix: Option<Fix>,
+}
+
+/// Sets a tool setting (e.g. ClCompile/RuntimeLibrary), or a property when `tool` is `None`, in one configuration
+#[derive(Debug, Clone)]
+pub struct Fix {
+    pub tool: Option<String>,
+    pub name: String,
+    pub value: String,
+}
+
+impl Fix {
+    /// Applies the fix to `config` of the project. Returns whether it changed anything.
+    pub fn apply(&self, vcxproj: &mut VcxprojFile, config: &str) -> anyhow::Result<bool> {
+        Ok(match &self.tool {
+            Some(tool) => !vcxproj.set_tool_setting(tool, &self.name, &self.value, Some(config)).is_empty(),
+            None => !vcxproj.set_config_property(&self.name, &self.value, Some(config))?.is_empty(),
+        })
+    }
+}
+
+/// What a check looks for
+pub fn describe(check: &str) -> &'static str {
+    match check {
+        "debug-release-runtime" => "Debug configuration linking a release C runtime",
+        "release-debug-runtime" => "release configuration linking a debug C runtime",
+        "wrong-platform-path" => "library or include path of another platform",
+        "missing-inheritance" => "list that drops the values inherited from property sheets and defaults",
+        "absolute-path" => "absolute path that won't exist on other machines",
+        _ => "",
+    }
+}
+
+/// The platform of a configuration name, e.g. "x64" for "Debug|x64"
+fn platform(config: &str) -> &str {
+    config.split_once('|').map_or("", |(_, platform)| platform)
+}
+
+/// Whether a path is absolute on Windows or Unix, such as `C:\libs`, `\\server\share` or `/opt/lib`
+pub fn is_absolute(path: &str) -> bool {
+    let bytes = path.as_bytes();
+    (bytes.len() >= 3 && bytes[0].is_ascii_alphabetic() && bytes[1] == b':' && matches!(bytes[2], b'\\' | b'/'))
+        || path.starts_with("\\\\")
+        || path.starts_with('/')
+}
+
+/// Runs every check on the project
+pub fn diagnose(vcxproj: &VcxprojFile) -> anyhow::Result<Vec<Problem>> {
+    let mut problems = Vec::new();
+    let settings = vcxproj.get_tool_settings();
+    let properties = vcxproj.get_properties();
+    let x86_segment = Regex::new(r"(?i)(^|[\\/;])(x86|win32)([\\/;]|$)").unwrap();
+    let x64_segment = Regex::new(r"(?i)(^|[\\/;])(x64|amd64)([\\/;]|$)").unwrap();
+
+    // (tool, name, config, value) of every configuration's tool settings and properties
+    let values: Vec<(Option<&str>, &str, Option<String>, &str)> = settings
+        .iter()
+        .map(|s| (Some(s.tool.as_str()), s.name.as_str(), s.condition.as_deref().map(|c| vcxproj::condition_config(c).into_owned()), s.value.as_str()))
+        .chain(properties.iter().map(|p| (None, p.name.as_str(), p.condition.as_deref().map(|c| vcxproj::condition_config(c).into_owned()), p.value.as_str())))
+        .collect();
+
+    for (tool, name, config, value) in &values {
+        let fix = |value: String| config.as_ref().map(|_| Fix { tool: tool.map(str::to_string), name: name.to_string(), value });
+        let mut found = |check: &'static str, detail: String, fix: Option<Fix>| {
+            problems.push(Problem { check, config: config.clone(), detail, fix });
+        };
+
+        if *tool == Some("ClCompile") && *name == "RuntimeLibrary" {
+            if let Some(config) = config.as_ref().filter(|_| crate::commands::config::RUNTIME_LIBRARIES.iter().any(|(runtime, _)| runtime == value)) {
+                let debug_runtime = value.contains("Debug");
+                match (vcxproj.is_debug_configuration(config), debug_runtime) {
+                    (true, false) => {
+                        let runtime = value.replacen("MultiThreaded", "MultiThreadedDebug", 1);
+                        found("debug-release-runtime", format!("RuntimeLibrary is {}", value), fix(runtime));
+                    }
+                    (false, true) => {
+                        let runtime = value.replacen("Debug", "", 1);
+                        found("release-debug-runtime", format!("RuntimeLibrary is {}", value), fix(runtime));
+                    }
+                    _ => {}
+                }
+            }
+        }
+
+        if DIRECTORY_SETTINGS.contains(&(*tool, *name)) {
+            let wrong = match config.as_deref().map(platform) {
+                Some(platform) if platform.eq_ignore_ascii_case("x64") => Some((&x86_segment, "x64")),
+                Some(platform) if platform.eq_ignore_ascii_case("Win32") || platform.eq_ignore_ascii_case("x86") => Some((&x64_segment, "x86")),
+                _ => None,
+            };
+            if let Some((segment, replacement)) = wrong {
+                let entries: Vec<&str> = value.split(';').filter(|entry| segment.is_match(entry)).collect();
+                if !entries.is_empty() {
+                    let corrected = segment.replace_all(value, format!("${{1}}{}${{3}}", replacement)).into_owned();
+                    found("wrong-platform-path", format!("{} has {}", name, entries.join(";")), fix(corrected));
+                }
+            }
+            let absolute: Vec<&str> = value.split(';').map(str::trim).filter(|entry| is_absolute(entry)).collect();
+            if !absolute.is_empty() {
+                found("absolute-path", format!("{} has {}", name, absolute.join(";")), None);
+            }
+        }
+
+        if let Some(tool) = tool.filter(|tool| INHERITED_LISTS.contains(&(*tool, *name))) {
+            let inherited = format!("%({})", name);
+            if !value.trim().is_empty() && !value.contains(&inherited) {
+                found("missing-inheritance", format!("{}/{} doesn't end with {}", tool, name, inherited), fix(format!("{};{}", value.trim_end_matches(';'), inherited)));
+            }
+        }
+    }
+
+    for item in vcxproj.get_items()? {
+        if is_absolute(&item.include) {
+            problems.push(Problem {
+                check: "absolute-path",
+                config: None,
+                detail: format!("{} item {}", item.item_type, item.include),
+                fix: None,
+            });
+        }
+    }
+    Ok(problems)
+}
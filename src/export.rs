@@ -0,0 +1,1195 @@
+use anyhow::{Context, Result};
+use crate::history;
+use crate::i18n::warn;
+use crate::output::{self, Change};
+use crate::properties::Properties;
+use crate::vcxproj::{condition_matches, filters_path, relative_path, FilterFile, ProjectItem, VcxprojFile, NON_FILE_ITEM_TYPES};
+use serde_json::json;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// What an exporter supports, shown by `export --list` and checked before it runs
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Capabilities {
+    /// Combines several projects (e.g. a solution's) into one export
+    pub multi_project: bool,
+    /// Exports the single configuration selected with `--config`
+    pub per_config: bool,
+    /// Can also write build tasks invoking MSBuild (`--tasks`)
+    pub tasks: bool,
+}
+
+impl Capabilities {
+    /// Short labels for `export --list`, e.g. ["solutions", "per-config"]
+    pub fn labels(&self) -> Vec<&'static str> {
+        let mut labels = Vec::new();
+        if self.multi_project {
+            labels.push("solutions");
+        }
+        if self.per_config {
+            labels.push("per-config");
+        }
+        if self.tasks {
+            labels.push("tasks");
+        }
+        labels
+    }
+}
+
+/// The loaded projects an exporter works from
+pub struct ExportInput {
+    /// Name of the solution, or of the project when exporting one
+    pub name: String,
+    pub projects: Vec<VcxprojFile>,
+    /// Configuration to export (e.g. "Debug|x64"), only set for exporters with `per_config`
+    pub config: Option<String>,
+    /// Directory the exported files are written to
+    pub output_dir: PathBuf,
+    /// Also write build tasks, for exporters that can (`--tasks`)
+    pub tasks: bool,
+}
+
+/// A file produced by an exporter, relative to the output directory
+#[derive(Debug)]
+pub struct ExportedFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// A target format of `vcprojm export`. New formats implement this and are added to
+/// `Registry::builtin`, or registered at runtime, without touching command dispatch.
+pub trait Exporter {
+    /// Name selected with `--format`, e.g. "csv"
+    fn name(&self) -> &'static str;
+    /// One line for `export --list`
+    fn description(&self) -> &'static str;
+    fn capabilities(&self) -> Capabilities;
+    fn export(&self, input: &ExportInput) -> Result<Vec<ExportedFile>>;
+}
+
+/// The exporters available to `vcprojm export`
+#[derive(Default)]
+pub struct Registry {
+    exporters: Vec<Box<dyn Exporter>>,
+}
+
+impl Registry {
+    /// The exporters shipped with vcprojm
+    pub fn builtin() -> Self {
+        let mut registry = Self::default();
+        registry.register(Box::new(CsvExporter));
+        registry.register(Box::new(NinjaExporter));
+        registry.register(Box::new(PremakeExporter));
+        registry.register(Box::new(MesonExporter));
+        registry.register(Box::new(BazelExporter));
+        registry.register(Box::new(ClangFlagsExporter));
+        registry.register(Box::new(VscodeExporter));
+        registry
+    }
+
+    /// Adds an exporter, replacing an earlier one with the same name
+    pub fn register(&mut self, exporter: Box<dyn Exporter>) {
+        self.exporters.retain(|existing| existing.name() != exporter.name());
+        self.exporters.push(exporter);
+    }
+
+    pub fn find(&self, name: &str) -> Option<&dyn Exporter> {
+        self.exporters
+            .iter()
+            .find(|exporter| exporter.name().eq_ignore_ascii_case(name))
+            .map(|exporter| exporter.as_ref())
+    }
+
+    pub fn exporters(&self) -> impl Iterator<Item = &dyn Exporter> {
+        self.exporters.iter().map(|exporter| exporter.as_ref())
+    }
+}
+
+/// Writes exported files into `output_dir`, journaled like every other write so `undo` removes them
+pub fn write_files(output_dir: &Path, files: &[ExportedFile]) -> Result<Vec<PathBuf>> {
+    let mut written = Vec::new();
+    for file in files {
+        let path = output_dir.join(&file.path);
+        if fs::read_to_string(&path).is_ok_and(|current| current == file.content) {
+            continue;
+        }
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create directory: {}", dir.display()))?;
+        }
+        history::record(&path, &file.content)?;
+        history::replace_file(&path, &file.content)
+            .with_context(|| format!("Failed to write {}", path.display()))?;
+        output::report(Change::FileWritten, [path.display()]);
+        written.push(path);
+    }
+    Ok(written)
+}
+
+/// One row per file item: project, item type, path and Solution Explorer filter
+struct CsvExporter;
+
+impl Exporter for CsvExporter {
+    fn name(&self) -> &'static str {
+        "csv"
+    }
+
+    fn description(&self) -> &'static str {
+        "files.csv with one row per file: project, item type, path and filter (with --config, only files it builds)"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities { multi_project: true, per_config: true, ..Capabilities::default() }
+    }
+
+    fn export(&self, input: &ExportInput) -> Result<Vec<ExportedFile>> {
+        let mut content = String::from("project,item_type,path,filter\n");
+        for vcxproj in &input.projects {
+            let filter_path = filters_path(&vcxproj.path);
+            let filters = if filter_path.exists() {
+                FilterFile::load(&filter_path)?.get_item_filters()?
+            } else {
+                Default::default()
+            };
+            let project = vcxproj.path.file_stem().unwrap_or_default().to_string_lossy();
+            for item in vcxproj.get_items()? {
+                if NON_FILE_ITEM_TYPES.contains(&item.item_type.as_str()) {
+                    continue;
+                }
+                if input.config.as_deref().is_some_and(|config| excluded_from_build(&item, config)) {
+                    continue;
+                }
+                let filter = filters.get(&item.include).map(String::as_str).unwrap_or_default();
+                let row = [project.as_ref(), &item.item_type, &item.include, filter].map(csv_field);
+                content.push_str(&row.join(","));
+                content.push('\n');
+            }
+        }
+        Ok(vec![ExportedFile { path: PathBuf::from("files.csv"), content }])
+    }
+}
+
+/// Quotes a CSV field when it contains a separator, quote or line break
+pub fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+/// Whether an item is excluded from the build of `config`
+fn excluded_from_build(item: &ProjectItem, config: &str) -> bool {
+    item.metadata.iter().any(|m| {
+        m.name == "ExcludedFromBuild"
+            && m.value.trim().eq_ignore_ascii_case("true")
+            && m.condition.as_deref().is_none_or(|c| condition_matches(c, config))
+    })
+}
+
+/// The compiler and linker settings of one configuration as cl.exe, link.exe and lib.exe switches.
+/// Paths are resolved against the project directory with `$(...)` expanded.
+#[derive(Debug, Default)]
+pub struct BuildSettings {
+    /// ConfigurationType, e.g. "Application" or "StaticLibrary"
+    pub configuration_type: String,
+    /// The file the build produces, e.g. `<OutDir>/App.exe`
+    pub target: PathBuf,
+    pub int_dir: PathBuf,
+    pub defines: Vec<String>,
+    pub include_dirs: Vec<PathBuf>,
+    pub forced_includes: Vec<PathBuf>,
+    /// Every other compiler switch, e.g. "/W4" or "/std:c++20"
+    pub compile_flags: Vec<String>,
+    /// Linker (or librarian, for static libraries) switches other than libraries and library paths
+    pub link_flags: Vec<String>,
+    pub libraries: Vec<String>,
+    pub library_dirs: Vec<PathBuf>,
+    /// Properties that could not be expanded, e.g. ones only defined in imported .props files
+    pub unknown_properties: Vec<String>,
+}
+
+/// Compiler switches for ClCompile settings with a fixed set of values
+const COMPILE_SWITCHES: &[(&str, &str, &str)] = &[
+    ("WarningLevel", "TurnOffAllWarnings", "/W0"),
+    ("WarningLevel", "Level1", "/W1"),
+    ("WarningLevel", "Level2", "/W2"),
+    ("WarningLevel", "Level3", "/W3"),
+    ("WarningLevel", "Level4", "/W4"),
+    ("WarningLevel", "EnableAllWarnings", "/Wall"),
+    ("TreatWarningAsError", "true", "/WX"),
+    ("Optimization", "Disabled", "/Od"),
+    ("Optimization", "MinSpace", "/O1"),
+    ("Optimization", "MaxSpeed", "/O2"),
+    ("Optimization", "Full", "/Ox"),
+    ("IntrinsicFunctions", "true", "/Oi"),
+    ("FunctionLevelLinking", "true", "/Gy"),
+    ("DebugInformationFormat", "OldStyle", "/Z7"),
+    ("DebugInformationFormat", "ProgramDatabase", "/Zi"),
+    ("DebugInformationFormat", "EditAndContinue", "/ZI"),
+    ("SDLCheck", "true", "/sdl"),
+    ("ConformanceMode", "true", "/permissive-"),
+    ("BufferSecurityCheck", "false", "/GS-"),
+    ("ControlFlowGuard", "Guard", "/guard:cf"),
+    ("ExceptionHandling", "Sync", "/EHsc"),
+    ("ExceptionHandling", "SyncCThrow", "/EHs"),
+    ("ExceptionHandling", "Async", "/EHa"),
+    ("LanguageStandard", "stdcpp14", "/std:c++14"),
+    ("LanguageStandard", "stdcpp17", "/std:c++17"),
+    ("LanguageStandard", "stdcpp20", "/std:c++20"),
+    ("LanguageStandard", "stdcpplatest", "/std:c++latest"),
+    ("LanguageStandard_C", "stdc11", "/std:c11"),
+    ("LanguageStandard_C", "stdc17", "/std:c17"),
+    ("CompileAs", "CompileAsC", "/TC"),
+    ("CompileAs", "CompileAsCpp", "/TP"),
+];
+
+/// Linker switches for Link settings with a fixed set of values
+const LINK_SWITCHES: &[(&str, &str, &str)] = &[
+    ("SubSystem", "Console", "/SUBSYSTEM:CONSOLE"),
+    ("SubSystem", "Windows", "/SUBSYSTEM:WINDOWS"),
+    ("GenerateDebugInformation", "true", "/DEBUG"),
+    ("GenerateDebugInformation", "DebugFull", "/DEBUG:FULL"),
+    ("GenerateDebugInformation", "DebugFastLink", "/DEBUG:FASTLINK"),
+    ("EnableCOMDATFolding", "true", "/OPT:ICF"),
+    ("OptimizeReferences", "true", "/OPT:REF"),
+];
+
+impl BuildSettings {
+    /// The settings `config` (e.g. "Release|x64") builds with: the project's PropertyGroups and
+    /// ItemDefinitionGroups for it, with Visual Studio's defaults for what they leave out
+    pub fn resolve(vcxproj: &VcxprojFile, config: &str) -> Self {
+        let properties = Properties::for_project(vcxproj, Some(config));
+        let project_dir = vcxproj.path.parent().unwrap_or_else(|| Path::new("."));
+        let tool_settings: Vec<_> = vcxproj
+            .get_tool_settings()
+            .into_iter()
+            .filter(|s| s.condition.as_deref().is_none_or(|c| condition_matches(c, config)))
+            .collect();
+        let value = |tool: &str, name: &str| {
+            tool_settings.iter().rev().find(|s| s.tool == tool && s.name == name).map(|s| unescape(s.value.trim()))
+        };
+        // A list like "a;b;%(PreprocessorDefinitions)" replaces the one before it, which %(Name) brings back in
+        let list = |tool: &str, name: &str| {
+            let inherited = format!("%({})", name);
+            let mut values: Vec<String> = Vec::new();
+            for setting in tool_settings.iter().filter(|s| s.tool == tool && s.name == name) {
+                let mut next = Vec::new();
+                for part in unescape(&setting.value).split(';').map(str::trim).filter(|p| !p.is_empty()) {
+                    if part.eq_ignore_ascii_case(&inherited) {
+                        next.append(&mut values);
+                    } else {
+                        next.push(part.to_string());
+                    }
+                }
+                values = next;
+            }
+            values
+        };
+
+        let mut settings = Self { configuration_type: properties.get("ConfigurationType").unwrap_or_else(|| "Application".to_string()), ..Self::default() };
+        let mut expand = |text: &str| {
+            let (expanded, unknown) = properties.expand(text);
+            for name in unknown {
+                if !settings.unknown_properties.contains(&name) {
+                    settings.unknown_properties.push(name);
+                }
+            }
+            expanded
+        };
+        let mut path = |text: &str| {
+            let expanded = expand(text).replace('\\', "/");
+            let absolute = expanded.starts_with('/') || expanded.get(1..2) == Some(":");
+            if absolute { PathBuf::from(expanded) } else { project_dir.join(expanded) }
+        };
+
+        let target_name = properties.get("TargetName").unwrap_or_else(|| vcxproj.project_name());
+        let target_ext = properties.get("TargetExt").unwrap_or_else(|| {
+            match settings.configuration_type.as_str() {
+                "StaticLibrary" => ".lib",
+                "DynamicLibrary" => ".dll",
+                _ => ".exe",
+            }
+            .to_string()
+        });
+        let out_dir = path(&properties.get("OutDir").unwrap_or_default());
+        let int_dir = path(&properties.get("IntDir").unwrap_or_default());
+        let include_dirs: Vec<PathBuf> = list("ClCompile", "AdditionalIncludeDirectories").iter().map(|dir| path(dir)).collect();
+        let forced_includes: Vec<PathBuf> = list("ClCompile", "ForcedIncludeFiles").iter().map(|file| path(file)).collect();
+        let library_dirs: Vec<PathBuf> = list("Link", "AdditionalLibraryDirectories").iter().map(|dir| path(dir)).collect();
+
+        let mut compile_flags = Vec::new();
+        for (name, wanted, switch) in COMPILE_SWITCHES {
+            // Visual Studio compiles with /EHsc unless ExceptionHandling says otherwise
+            let set = value("ClCompile", name).or_else(|| (*name == "ExceptionHandling").then(|| "Sync".to_string()));
+            if set.is_some_and(|set| set.eq_ignore_ascii_case(wanted)) {
+                compile_flags.push(switch.to_string());
+            }
+        }
+        if let Some(runtime) = value("ClCompile", "RuntimeLibrary") {
+            if let Some((_, switch)) = crate::commands::config::RUNTIME_LIBRARIES.iter().find(|(name, _)| name.eq_ignore_ascii_case(&runtime)) {
+                compile_flags.push(format!("/{}", switch));
+            }
+        }
+        let whole_program = properties.get("WholeProgramOptimization").is_some_and(|v| v.eq_ignore_ascii_case("true"));
+        if whole_program {
+            compile_flags.push("/GL".to_string());
+        }
+        compile_flags.extend(list("ClCompile", "DisableSpecificWarnings").iter().map(|warning| format!("/wd{}", warning)));
+        let mut defines = list("ClCompile", "PreprocessorDefinitions");
+        match properties.get("CharacterSet").as_deref() {
+            Some("Unicode") => defines.extend(["UNICODE".to_string(), "_UNICODE".to_string()]),
+            Some("MultiByte") => defines.push("_MBCS".to_string()),
+            _ => {}
+        }
+
+        let tool = if settings.configuration_type == "StaticLibrary" { "Lib" } else { "Link" };
+        let mut link_flags = Vec::new();
+        if tool == "Link" {
+            for (name, wanted, switch) in LINK_SWITCHES {
+                if value("Link", name).is_some_and(|set| set.eq_ignore_ascii_case(wanted)) {
+                    link_flags.push(switch.to_string());
+                }
+            }
+            if settings.configuration_type == "DynamicLibrary" {
+                link_flags.push("/DLL".to_string());
+            }
+        }
+        if whole_program {
+            link_flags.push("/LTCG".to_string());
+        }
+        let libraries = list(tool, "AdditionalDependencies");
+
+        let options = |options: Option<String>| -> Vec<String> {
+            let options = options.unwrap_or_default();
+            options.split_whitespace().filter(|option| !option.eq_ignore_ascii_case("%(AdditionalOptions)")).map(str::to_string).collect()
+        };
+        compile_flags.extend(options(value("ClCompile", "AdditionalOptions")).iter().map(|option| expand(option)));
+        link_flags.extend(options(value(tool, "AdditionalOptions")).iter().map(|option| expand(option)));
+
+        let defines = defines.iter().map(|define| expand(define)).collect();
+        let libraries = libraries.iter().map(|library| expand(library)).collect();
+        Self {
+            target: out_dir.join(format!("{}{}", target_name, target_ext)),
+            int_dir,
+            defines,
+            include_dirs,
+            forced_includes,
+            compile_flags,
+            link_flags,
+            libraries,
+            library_dirs,
+            ..settings
+        }
+    }
+}
+
+/// The configuration of `vcxproj` to export: `config` as the project spells it, or else its first one
+fn export_config(vcxproj: &VcxprojFile, config: Option<&str>) -> Result<String> {
+    let configs = vcxproj.get_configurations();
+    match config {
+        Some(config) => configs
+            .iter()
+            .find(|c| c.eq_ignore_ascii_case(config))
+            .cloned()
+            .with_context(|| format!("{} has no configuration {} (it has {})", vcxproj.path.display(), config, configs.join(", "))),
+        None => configs.first().cloned().with_context(|| format!("{} declares no configurations", vcxproj.path.display())),
+    }
+}
+
+/// The projects among `projects` that `vcxproj` has a ProjectReference to, by index
+fn referenced_projects(vcxproj: &VcxprojFile, projects: &[VcxprojFile]) -> Result<Vec<usize>> {
+    let project_dir = vcxproj.path.parent().unwrap_or_else(|| Path::new("."));
+    let canonical = |path: &Path| fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    let mut references = Vec::new();
+    for item in vcxproj.get_items()?.into_iter().filter(|item| item.item_type == "ProjectReference") {
+        let referenced = canonical(&project_dir.join(item.include.replace('\\', "/")));
+        if let Some(index) = projects.iter().position(|project| canonical(&project.path) == referenced) {
+            references.push(index);
+        }
+    }
+    Ok(references)
+}
+
+/// Whether a project compiles only C sources, so build systems that need a language get the right one
+fn compiles_only_c(items: &[ProjectItem]) -> bool {
+    let mut sources = items.iter().filter(|item| item.item_type == "ClCompile").peekable();
+    sources.peek().is_some() && sources.all(|item| item.include.to_lowercase().ends_with(".c"))
+}
+
+/// Undoes the XML escaping of a project file value
+fn unescape(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+/// build.ninja that compiles the project's sources with cl.exe and links them with link.exe (or
+/// lib.exe for static libraries), for quick command-line builds from a Developer Command Prompt
+struct NinjaExporter;
+
+impl Exporter for NinjaExporter {
+    fn name(&self) -> &'static str {
+        "ninja"
+    }
+
+    fn description(&self) -> &'static str {
+        "build.ninja calling cl.exe, rc.exe and link.exe/lib.exe with one configuration's settings"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities { per_config: true, ..Capabilities::default() }
+    }
+
+    fn export(&self, input: &ExportInput) -> Result<Vec<ExportedFile>> {
+        let vcxproj = input.projects.first().context("No project to export")?;
+        let config = export_config(vcxproj, input.config.as_deref())?;
+        let settings = BuildSettings::resolve(vcxproj, &config);
+        let link_rule = match settings.configuration_type.as_str() {
+            "Application" | "DynamicLibrary" => "link",
+            "StaticLibrary" => "lib",
+            other => anyhow::bail!("{} is a {} project in {}, which has nothing for cl.exe to build", vcxproj.path.display(), other, config),
+        };
+        if !settings.unknown_properties.is_empty() {
+            warn!("⚠️  Properties left unexpanded (pass them with --property NAME=VALUE): {}", settings.unknown_properties.join(", "));
+        }
+
+        let project_dir = vcxproj.path.parent().unwrap_or_else(|| Path::new("."));
+        let relative = |path: &Path| ninja_escape(&relative_to(&input.output_dir, path));
+        let quoted = |switch: &str, path: &Path| {
+            let path = relative_to(&input.output_dir, path);
+            if path.contains(' ') { format!("\"{}{}\"", switch, path) } else { format!("{}{}", switch, path) }
+        };
+        let mut cflags = vec!["/nologo".to_string()];
+        cflags.extend(settings.compile_flags.iter().cloned());
+        if settings.compile_flags.iter().any(|flag| flag == "/Zi" || flag == "/ZI") {
+            // Compilations run in parallel and share one PDB
+            cflags.push("/FS".to_string());
+            cflags.push(quoted("/Fd", &settings.int_dir.join("vc.pdb")));
+        }
+        let defines: Vec<String> = settings.defines.iter().map(|define| format!("/D{}", define)).collect();
+        let mut includes: Vec<String> = settings.include_dirs.iter().map(|dir| quoted("/I", dir)).collect();
+        includes.extend(settings.forced_includes.iter().map(|file| quoted("/FI", file)));
+        let mut ldflags = vec!["/nologo".to_string()];
+        ldflags.extend(settings.link_flags.iter().cloned());
+        ldflags.extend(settings.library_dirs.iter().map(|dir| quoted("/LIBPATH:", dir)));
+
+        let mut content = String::new();
+        content.push_str(&format!("# Generated by vcprojm from {} ({})\n", vcxproj.path.file_name().unwrap_or_default().to_string_lossy(), config));
+        content.push_str("ninja_required_version = 1.3\n\n");
+        for (name, values) in [("cflags", &cflags), ("defines", &defines), ("includes", &includes), ("ldflags", &ldflags), ("libs", &settings.libraries)] {
+            content.push_str(&format!("{} = {}\n", name, values.iter().map(|v| v.replace('$', "$$")).collect::<Vec<_>>().join(" ")));
+        }
+        content.push_str("\nrule cc\n");
+        content.push_str("  command = cl.exe $cflags $defines $includes /showIncludes /c $in /Fo$out\n");
+        content.push_str("  deps = msvc\n");
+        content.push_str("  description = CC $in\n");
+        content.push_str("\nrule rc\n");
+        content.push_str("  command = rc.exe /nologo $defines $includes /fo $out $in\n");
+        content.push_str("  description = RC $in\n");
+        content.push_str(&format!("\nrule {}\n", link_rule));
+        content.push_str(&format!("  command = {}.exe $ldflags /OUT:$out @$out.rsp\n", link_rule));
+        content.push_str("  rspfile = $out.rsp\n");
+        content.push_str("  rspfile_content = $in_newline $libs\n");
+        content.push_str(&format!("  description = {} $out\n\n", link_rule.to_uppercase()));
+
+        let mut sources = Vec::new();
+        for item in vcxproj.get_items()? {
+            let (rule, extension) = match item.item_type.as_str() {
+                "ClCompile" => ("cc", "obj"),
+                "ResourceCompile" if link_rule == "link" => ("rc", "res"),
+                _ => continue,
+            };
+            if !excluded_from_build(&item, &config) {
+                sources.push((rule, extension, item.include));
+            }
+        }
+        // Objects are named after their source as MSBuild does, or after its whole path when two sources share a name
+        let stem = |include: &str| Path::new(&include.replace('\\', "/")).file_stem().unwrap_or_default().to_string_lossy().into_owned();
+        let mut objects = Vec::new();
+        for (rule, extension, include) in &sources {
+            let shared = sources.iter().filter(|(_, e, other)| e == extension && stem(other).eq_ignore_ascii_case(&stem(include))).count() > 1;
+            let name = if shared { include.replace("..", "__").replace(['\\', '/', ':'], "_") } else { stem(include) };
+            let object = relative(&settings.int_dir.join(Path::new(&name).with_extension(extension)));
+            let source = project_dir.join(include.replace('\\', "/"));
+            content.push_str(&format!("build {}: {} {}\n", object, rule, relative(&source)));
+            objects.push(object);
+        }
+        if objects.is_empty() {
+            anyhow::bail!("{} has no sources to compile in {}", vcxproj.path.display(), config);
+        }
+        let target = relative(&settings.target);
+        content.push_str(&format!("\nbuild {}: {} {}\n", target, link_rule, objects.join(" ")));
+        content.push_str(&format!("\ndefault {}\n", target));
+        Ok(vec![ExportedFile { path: PathBuf::from("build.ninja"), content }])
+    }
+}
+
+/// `path` relative to `dir` as `relative_path` gives it, but Windows paths with a drive (e.g. from an
+/// expanded `$(VULKAN_SDK)`) stay absolute on every host
+fn relative_to(dir: &Path, path: &Path) -> String {
+    let text = path.to_string_lossy();
+    if text.get(1..2) == Some(":") {
+        return text.replace('/', "\\");
+    }
+    relative_path(dir, path)
+}
+
+/// Escapes a path for a ninja build line, where spaces, colons and dollars are special
+fn ninja_escape(path: &str) -> String {
+    path.replace('$', "$$").replace(' ', "$ ").replace(':', "$:")
+}
+
+/// premake5.lua with a workspace holding every project, its files and each configuration's settings
+struct PremakeExporter;
+
+/// Compiler switches premake has a setting for, e.g. /W4 is `warnings "Extra"`
+const PREMAKE_SETTINGS: &[(&str, &str)] = &[
+    ("/W0", "warnings \"Off\""),
+    ("/W3", "warnings \"Default\""),
+    ("/W4", "warnings \"Extra\""),
+    ("/Wall", "warnings \"Everything\""),
+    ("/WX", "flags { \"FatalWarnings\" }"),
+    ("/Od", "optimize \"Off\""),
+    ("/O1", "optimize \"Size\""),
+    ("/O2", "optimize \"Speed\""),
+    ("/Ox", "optimize \"Full\""),
+    ("/Oi", "intrinsics \"On\""),
+    ("/Gy", "functionlevellinking \"On\""),
+    ("/Z7", "symbols \"On\""),
+    ("/Zi", "symbols \"On\""),
+    ("/ZI", "symbols \"On\""),
+    ("/MT", "staticruntime \"On\"\n    runtime \"Release\""),
+    ("/MTd", "staticruntime \"On\"\n    runtime \"Debug\""),
+    ("/MD", "staticruntime \"Off\"\n    runtime \"Release\""),
+    ("/MDd", "staticruntime \"Off\"\n    runtime \"Debug\""),
+    ("/std:c++14", "cppdialect \"C++14\""),
+    ("/std:c++17", "cppdialect \"C++17\""),
+    ("/std:c++20", "cppdialect \"C++20\""),
+    ("/std:c++latest", "cppdialect \"C++latest\""),
+    ("/std:c11", "cdialect \"C11\""),
+    ("/std:c17", "cdialect \"C17\""),
+    ("/sdl", "sdlchecks \"On\""),
+    ("/permissive-", "conformancemode \"On\""),
+    ("/GS-", "buffersecuritycheck \"Off\""),
+    ("/guard:cf", "controlflowguard \"On\""),
+    ("/GL", "flags { \"LinkTimeOptimization\" }"),
+    ("/TC", "compileas \"C\""),
+    ("/TP", "compileas \"C++\""),
+    // premake's default
+    ("/EHsc", ""),
+];
+
+impl Exporter for PremakeExporter {
+    fn name(&self) -> &'static str {
+        "premake"
+    }
+
+    fn description(&self) -> &'static str {
+        "premake5.lua with each project's files, references and per-configuration settings"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities { multi_project: true, ..Capabilities::default() }
+    }
+
+    fn export(&self, input: &ExportInput) -> Result<Vec<ExportedFile>> {
+        let path = |path: &Path| match relative_to(&input.output_dir, path).replace('\\', "/") {
+            path if path.is_empty() => ".".to_string(),
+            path => path,
+        };
+        let mut configurations: Vec<String> = Vec::new();
+        let mut platforms: Vec<String> = Vec::new();
+        for vcxproj in &input.projects {
+            for config in vcxproj.get_configurations() {
+                let (configuration, platform) = config.split_once('|').unwrap_or((&config, "Win32"));
+                for (list, name) in [(&mut configurations, configuration), (&mut platforms, platform)] {
+                    if !list.iter().any(|existing| existing == name) {
+                        list.push(name.to_string());
+                    }
+                }
+            }
+        }
+
+        let mut lua = format!("-- Generated by vcprojm from {}\n\n", input.name);
+        lua.push_str(&format!("workspace {}\n", double_quoted(&input.name)));
+        lua.push_str(&format!("  configurations {}\n", lua_list(&configurations)));
+        lua.push_str(&format!("  platforms {}\n", lua_list(&platforms)));
+        for platform in &platforms {
+            let architecture = match platform.as_str() {
+                "Win32" => "x86",
+                "x64" => "x86_64",
+                "ARM64" => "ARM64",
+                _ => continue,
+            };
+            lua.push_str(&format!("  filter \"platforms:{}\"\n    architecture \"{}\"\n", platform, architecture));
+        }
+        lua.push_str("  filter {}\n");
+
+        for vcxproj in &input.projects {
+            let project_dir = vcxproj.path.parent().unwrap_or_else(|| Path::new("."));
+            let items = vcxproj.get_items()?;
+            lua.push_str(&format!("\nproject {}\n", double_quoted(&vcxproj.project_name())));
+            lua.push_str(&format!("  language \"{}\"\n", if compiles_only_c(&items) { "C" } else { "C++" }));
+            lua.push_str(&format!("  location {}\n", double_quoted(&path(project_dir))));
+            let files: Vec<String> = items
+                .iter()
+                .filter(|item| ["ClCompile", "ClInclude", "ResourceCompile", "None", "Text"].contains(&item.item_type.as_str()))
+                .map(|item| path(&project_dir.join(item.include.replace('\\', "/"))))
+                .collect();
+            lua.push_str(&format!("  files {}\n", lua_list(&files)));
+            let references: Vec<String> = referenced_projects(vcxproj, &input.projects)?
+                .into_iter()
+                .map(|index| input.projects[index].project_name())
+                .collect();
+            if !references.is_empty() {
+                lua.push_str(&format!("  links {}\n", lua_list(&references)));
+            }
+
+            for config in vcxproj.get_configurations() {
+                let settings = BuildSettings::resolve(vcxproj, &config);
+                if !settings.unknown_properties.is_empty() {
+                    warn!("⚠️  {} ({}): properties left unexpanded: {}", vcxproj.project_name(), config, settings.unknown_properties.join(", "));
+                }
+                let (configuration, platform) = config.split_once('|').unwrap_or((&config, "Win32"));
+                lua.push_str(&format!("  filter {{ \"configurations:{}\", \"platforms:{}\" }}\n", configuration, platform));
+                let windowed = settings.link_flags.iter().any(|flag| flag == "/SUBSYSTEM:WINDOWS");
+                let kind = match settings.configuration_type.as_str() {
+                    "Application" if windowed => "WindowedApp",
+                    "Application" => "ConsoleApp",
+                    "DynamicLibrary" => "SharedLib",
+                    "StaticLibrary" => "StaticLib",
+                    "Makefile" => "Makefile",
+                    _ => "Utility",
+                };
+                lua.push_str(&format!("    kind \"{}\"\n", kind));
+                if let (Some(dir), Some(name)) = (settings.target.parent(), settings.target.file_stem()) {
+                    lua.push_str(&format!("    targetdir {}\n", double_quoted(&path(dir))));
+                    if name.to_string_lossy() != vcxproj.project_name() {
+                        lua.push_str(&format!("    targetname {}\n", double_quoted(&name.to_string_lossy())));
+                    }
+                }
+                lua.push_str(&format!("    objdir {}\n", double_quoted(&path(&settings.int_dir))));
+                let paths = |paths: &[PathBuf]| paths.iter().map(|p| path(p)).collect::<Vec<_>>();
+                let mut build_options = Vec::new();
+                let mut disabled_warnings = Vec::new();
+                let mut mapped: Vec<&str> = Vec::new();
+                for flag in &settings.compile_flags {
+                    match PREMAKE_SETTINGS.iter().find(|(switch, _)| switch == flag) {
+                        Some((_, setting)) if !setting.is_empty() && !mapped.contains(setting) => mapped.push(setting),
+                        Some(_) => {}
+                        None => match flag.strip_prefix("/wd") {
+                            Some(warning) => disabled_warnings.push(warning.to_string()),
+                            None => build_options.push(flag.clone()),
+                        },
+                    }
+                }
+                let link_options: Vec<String> = settings
+                    .link_flags
+                    .iter()
+                    .filter(|flag| !flag.starts_with("/DEBUG") && !flag.starts_with("/SUBSYSTEM:") && *flag != "/DLL" && *flag != "/LTCG")
+                    .cloned()
+                    .collect();
+                for (setting, values) in [
+                    ("defines", settings.defines.clone()),
+                    ("includedirs", paths(&settings.include_dirs)),
+                    ("forceincludes", paths(&settings.forced_includes)),
+                    ("disablewarnings", disabled_warnings),
+                    ("buildoptions", build_options),
+                    ("libdirs", paths(&settings.library_dirs)),
+                    ("links", settings.libraries.clone()),
+                    ("linkoptions", link_options),
+                ] {
+                    if !values.is_empty() {
+                        lua.push_str(&format!("    {} {}\n", setting, lua_list(&values)));
+                    }
+                }
+                for setting in mapped {
+                    lua.push_str(&format!("    {}\n", setting));
+                }
+                let excluded: Vec<String> = items
+                    .iter()
+                    .filter(|item| excluded_from_build(item, &config))
+                    .map(|item| path(&project_dir.join(item.include.replace('\\', "/"))))
+                    .collect();
+                if !excluded.is_empty() {
+                    lua.push_str(&format!("    removefiles {}\n", lua_list(&excluded)));
+                }
+            }
+            lua.push_str("  filter {}\n");
+        }
+        Ok(vec![ExportedFile { path: PathBuf::from("premake5.lua"), content: lua }])
+    }
+}
+
+/// A double-quoted string, as Lua and Starlark write them
+fn double_quoted(value: &str) -> String {
+    format!("\"{}\"", value.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// A Lua table of strings, e.g. `{ "a", "b" }`
+fn lua_list(values: &[String]) -> String {
+    format!("{{ {} }}", values.iter().map(|value| double_quoted(value)).collect::<Vec<_>>().join(", "))
+}
+
+/// meson.build with a target per project for one configuration, linked to the projects it references
+struct MesonExporter;
+
+/// Compiler switches Meson has a built-in option for, e.g. /W4 is `warning_level=3`
+const MESON_OPTIONS: &[(&str, &str)] = &[
+    ("/W2", "warning_level=1"),
+    ("/W3", "warning_level=2"),
+    ("/W4", "warning_level=3"),
+    ("/Wall", "warning_level=everything"),
+    ("/WX", "werror=true"),
+    ("/Od", "optimization=0"),
+    ("/O1", "optimization=s"),
+    ("/O2", "optimization=2"),
+    ("/Ox", "optimization=3"),
+    ("/Z7", "debug=true"),
+    ("/Zi", "debug=true"),
+    ("/ZI", "debug=true"),
+    ("/MT", "b_vscrt=mt"),
+    ("/MTd", "b_vscrt=mtd"),
+    ("/MD", "b_vscrt=md"),
+    ("/MDd", "b_vscrt=mdd"),
+    ("/std:c++14", "cpp_std=c++14"),
+    ("/std:c++17", "cpp_std=c++17"),
+    ("/std:c++20", "cpp_std=c++20"),
+    ("/std:c++latest", "cpp_std=c++latest"),
+    ("/std:c11", "c_std=c11"),
+    ("/std:c17", "c_std=c17"),
+    ("/GL", "b_lto=true"),
+    // Meson's default
+    ("/EHsc", ""),
+];
+
+impl Exporter for MesonExporter {
+    fn name(&self) -> &'static str {
+        "meson"
+    }
+
+    fn description(&self) -> &'static str {
+        "meson.build with a target per project, built with one configuration's settings"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities { multi_project: true, per_config: true, ..Capabilities::default() }
+    }
+
+    fn export(&self, input: &ExportInput) -> Result<Vec<ExportedFile>> {
+        let relative = |path: &Path| relative_to(&input.output_dir, path).replace('\\', "/");
+        let path = |path: &Path| meson_string(&relative(path));
+        let mut references = Vec::new();
+        for vcxproj in &input.projects {
+            references.push(referenced_projects(vcxproj, &input.projects)?);
+        }
+        let mut languages: Vec<&str> = Vec::new();
+        let mut targets = String::new();
+        let mut variables: Vec<String> = vec![String::new(); input.projects.len()];
+        for index in dependency_order(&references) {
+            let vcxproj = &input.projects[index];
+            let config = export_config(vcxproj, input.config.as_deref())?;
+            let settings = BuildSettings::resolve(vcxproj, &config);
+            if !settings.unknown_properties.is_empty() {
+                warn!("⚠️  {} ({}): properties left unexpanded: {}", vcxproj.project_name(), config, settings.unknown_properties.join(", "));
+            }
+            let function = match settings.configuration_type.as_str() {
+                "Application" => "executable",
+                "DynamicLibrary" => "shared_library",
+                "StaticLibrary" => "static_library",
+                _ => {
+                    warn!("⚠️  Skipping {}: a {} project has no Meson target", vcxproj.project_name(), settings.configuration_type);
+                    continue;
+                }
+            };
+            let project_dir = vcxproj.path.parent().unwrap_or_else(|| Path::new("."));
+            let items = vcxproj.get_items()?;
+            let sources: Vec<String> = items
+                .iter()
+                .filter(|item| item.item_type == "ClCompile" && !excluded_from_build(item, &config))
+                .map(|item| path(&project_dir.join(item.include.replace('\\', "/"))))
+                .collect();
+            let mut project_languages = Vec::new();
+            for item in items.iter().filter(|item| item.item_type == "ClCompile") {
+                let language = if item.include.to_lowercase().ends_with(".c") { "c" } else { "cpp" };
+                if !project_languages.contains(&language) {
+                    project_languages.push(language);
+                }
+            }
+            for language in &project_languages {
+                if !languages.contains(language) {
+                    languages.push(language);
+                }
+            }
+
+            let mut options: Vec<&str> = Vec::new();
+            let mut args: Vec<String> = settings.defines.iter().map(|define| meson_string(&format!("-D{}", define))).collect();
+            for flag in &settings.compile_flags {
+                match MESON_OPTIONS.iter().find(|(switch, _)| switch == flag) {
+                    Some((_, option)) if !option.is_empty() && !options.contains(option) => options.push(option),
+                    Some(_) => {}
+                    None => args.push(meson_string(flag)),
+                }
+            }
+            args.extend(settings.forced_includes.iter().map(|file| meson_string(&format!("/FI{}", relative(file)))));
+            let mut link_args: Vec<String> = settings
+                .link_flags
+                .iter()
+                .filter(|flag| !flag.starts_with("/DEBUG") && !flag.starts_with("/SUBSYSTEM:") && *flag != "/DLL" && *flag != "/LTCG")
+                .map(|flag| meson_string(flag))
+                .collect();
+            link_args.extend(settings.library_dirs.iter().map(|dir| meson_string(&format!("/LIBPATH:{}", relative(dir)))));
+            link_args.extend(settings.libraries.iter().map(|library| meson_string(library)));
+
+            let variable = meson_identifier(&vcxproj.project_name());
+            targets.push_str(&format!("\n# {} ({})\n", vcxproj.path.file_name().unwrap_or_default().to_string_lossy(), config));
+            targets.push_str(&format!("{} = {}({},\n", variable, function, meson_string(&vcxproj.project_name())));
+            targets.push_str(&format!("  [{}],\n", sources.join(", ")));
+            let includes: Vec<String> = settings.include_dirs.iter().map(|dir| path(dir)).collect();
+            if !includes.is_empty() {
+                targets.push_str(&format!("  include_directories: include_directories({}),\n", includes.join(", ")));
+            }
+            if !args.is_empty() {
+                for language in &project_languages {
+                    targets.push_str(&format!("  {}_args: [{}],\n", language, args.join(", ")));
+                }
+            }
+            if !link_args.is_empty() {
+                targets.push_str(&format!("  link_args: [{}],\n", link_args.join(", ")));
+            }
+            let link_with: Vec<&str> = references[index].iter().map(|&r| variables[r].as_str()).filter(|v| !v.is_empty()).collect();
+            if !link_with.is_empty() {
+                targets.push_str(&format!("  link_with: [{}],\n", link_with.join(", ")));
+            }
+            if function == "executable" && settings.link_flags.iter().any(|flag| flag == "/SUBSYSTEM:WINDOWS") {
+                targets.push_str("  win_subsystem: 'windows',\n");
+            }
+            if !options.is_empty() {
+                let options: Vec<String> = options.iter().map(|option| meson_string(option)).collect();
+                targets.push_str(&format!("  override_options: [{}],\n", options.join(", ")));
+            }
+            targets.push_str(")\n");
+            variables[index] = variable;
+        }
+        if targets.is_empty() {
+            anyhow::bail!("None of the projects builds an executable or library");
+        }
+
+        let mut content = format!("# Generated by vcprojm from {}\n", input.name);
+        let languages: Vec<String> = languages.iter().map(|language| meson_string(language)).collect();
+        content.push_str(&format!("project({}, [{}])\n", meson_string(&input.name), languages.join(", ")));
+        content.push_str(&targets);
+        Ok(vec![ExportedFile { path: PathBuf::from("meson.build"), content }])
+    }
+}
+
+/// Project indexes ordered so every project comes after those it references; cycles keep their order
+fn dependency_order(references: &[Vec<usize>]) -> Vec<usize> {
+    let mut order: Vec<usize> = Vec::new();
+    while order.len() < references.len() {
+        let ready = (0..references.len())
+            .filter(|index| !order.contains(index))
+            .find(|&index| references[index].iter().all(|r| order.contains(r) || *r == index))
+            .or_else(|| (0..references.len()).find(|index| !order.contains(index)));
+        order.extend(ready);
+    }
+    order
+}
+
+/// A single-quoted Meson string
+fn meson_string(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+/// A Meson variable name for a project, e.g. "my_lib" for "My-Lib"
+fn meson_identifier(name: &str) -> String {
+    let identifier: String = name.chars().map(|c| if c.is_ascii_alphanumeric() { c.to_ascii_lowercase() } else { '_' }).collect();
+    if identifier.starts_with(|c: char| c.is_ascii_digit()) { format!("_{}", identifier) } else { identifier }
+}
+
+/// BUILD.bazel skeleton with a cc_library or cc_binary for a project, for one configuration
+struct BazelExporter;
+
+impl Exporter for BazelExporter {
+    fn name(&self) -> &'static str {
+        "bazel"
+    }
+
+    fn description(&self) -> &'static str {
+        "BUILD.bazel with a cc_library/cc_binary target, its deps taken from ProjectReferences"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities { per_config: true, ..Capabilities::default() }
+    }
+
+    fn export(&self, input: &ExportInput) -> Result<Vec<ExportedFile>> {
+        let vcxproj = input.projects.first().context("No project to export")?;
+        let config = export_config(vcxproj, input.config.as_deref())?;
+        let settings = BuildSettings::resolve(vcxproj, &config);
+        if !settings.unknown_properties.is_empty() {
+            warn!("⚠️  Properties left unexpanded (pass them with --property NAME=VALUE): {}", settings.unknown_properties.join(", "));
+        }
+        let (rule, shared) = match settings.configuration_type.as_str() {
+            "Application" => ("cc_binary", false),
+            "DynamicLibrary" => ("cc_binary", true),
+            "StaticLibrary" => ("cc_library", false),
+            other => anyhow::bail!("{} is a {} project in {}, which has no Bazel equivalent", vcxproj.path.display(), other, config),
+        };
+
+        // Bazel paths are relative to the package, i.e. the directory of BUILD.bazel, and cannot leave it
+        let mut unrepresented: Vec<String> = Vec::new();
+        let mut package_path = |path: &Path, what: &str| {
+            let relative = relative_to(&input.output_dir, path).replace('\\', "/");
+            if relative.starts_with("../") || relative.get(1..2) == Some(":") {
+                unrepresented.push(format!("{} outside the package: {}", what, relative));
+                return None;
+            }
+            Some(if relative.is_empty() { ".".to_string() } else { relative })
+        };
+        let project_dir = vcxproj.path.parent().unwrap_or_else(|| Path::new("."));
+        let mut srcs = Vec::new();
+        let mut hdrs = Vec::new();
+        for item in vcxproj.get_items()? {
+            let list = match item.item_type.as_str() {
+                "ClCompile" if !excluded_from_build(&item, &config) => &mut srcs,
+                // Binaries have no public headers, so theirs are sources too
+                "ClInclude" if rule == "cc_binary" => &mut srcs,
+                "ClInclude" => &mut hdrs,
+                _ => continue,
+            };
+            list.extend(package_path(&project_dir.join(item.include.replace('\\', "/")), "file"));
+        }
+        let includes: Vec<String> = settings.include_dirs.iter().filter_map(|dir| package_path(dir, "include directory")).collect();
+
+        // Bazel's compilation mode decides warnings, optimization, debug info and the runtime library
+        for flag in &settings.compile_flags {
+            if flag != "/EHsc" {
+                unrepresented.push(format!("compiler option {}", flag));
+            }
+        }
+        for flag in settings.link_flags.iter().filter(|flag| !flag.starts_with("/SUBSYSTEM:CONSOLE") && *flag != "/DLL") {
+            unrepresented.push(format!("linker option {}", flag));
+        }
+        for dir in &settings.library_dirs {
+            unrepresented.push(format!("library directory {}", relative_to(&input.output_dir, dir).replace('\\', "/")));
+        }
+        for file in &settings.forced_includes {
+            unrepresented.push(format!("forced include {}", relative_to(&input.output_dir, file).replace('\\', "/")));
+        }
+
+        // References become labels from the workspace root, the nearest directory with a WORKSPACE or MODULE.bazel
+        let absolute = |path: &Path| {
+            let path = if path.as_os_str().is_empty() { Path::new(".") } else { path };
+            fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+        };
+        let output_dir = absolute(&input.output_dir);
+        let workspace = output_dir
+            .ancestors()
+            .find(|dir| ["WORKSPACE", "WORKSPACE.bazel", "MODULE.bazel"].iter().any(|marker| dir.join(marker).exists()))
+            .unwrap_or(&output_dir);
+        let mut deps = Vec::new();
+        for item in vcxproj.get_items()?.into_iter().filter(|item| item.item_type == "ProjectReference") {
+            let reference = project_dir.join(item.include.replace('\\', "/"));
+            let name = VcxprojFile::load(&reference)
+                .map(|referenced| referenced.project_name())
+                .unwrap_or_else(|_| reference.file_stem().unwrap_or_default().to_string_lossy().into_owned());
+            let package = absolute(reference.parent().unwrap_or_else(|| Path::new(".")));
+            match package.strip_prefix(workspace) {
+                Ok(package) => deps.push(format!("//{}:{}", package.to_string_lossy().replace('\\', "/"), name)),
+                Err(_) => unrepresented.push(format!("reference outside the workspace: {}", item.include)),
+            }
+        }
+
+        if !unrepresented.is_empty() {
+            warn!("⚠️  Not represented in BUILD.bazel (listed in a comment there):");
+            for what in &unrepresented {
+                warn!("  - {}", what);
+            }
+        }
+        let name = vcxproj.project_name();
+        let mut content = format!("# Generated by vcprojm from {} ({})\n", vcxproj.path.file_name().unwrap_or_default().to_string_lossy(), config);
+        if !unrepresented.is_empty() {
+            content.push_str("#\n# Not represented:\n");
+            for what in &unrepresented {
+                content.push_str(&format!("#   {}\n", what));
+            }
+        }
+        let name = if shared { format!("{}.dll", name) } else { name };
+        content.push_str(&format!("\n{}(\n    name = {},\n", rule, double_quoted(&name)));
+        for (attribute, values) in [
+            ("srcs", &srcs),
+            ("hdrs", &hdrs),
+            ("includes", &includes),
+            ("defines", &settings.defines),
+            ("linkopts", &settings.libraries),
+            ("deps", &deps),
+        ] {
+            if values.is_empty() {
+                continue;
+            }
+            content.push_str(&format!("    {} = [\n", attribute));
+            for value in values {
+                content.push_str(&format!("        {},\n", double_quoted(value)));
+            }
+            content.push_str("    ],\n");
+        }
+        if shared {
+            content.push_str("    linkshared = True,\n");
+        }
+        if rule == "cc_library" {
+            content.push_str("    visibility = [\"//visibility:public\"],\n");
+        }
+        content.push_str(")\n");
+        Ok(vec![ExportedFile { path: PathBuf::from("BUILD.bazel"), content }])
+    }
+}
+
+/// compile_flags.txt for clangd: the include paths, defines and language standard of one configuration
+struct ClangFlagsExporter;
+
+impl Exporter for ClangFlagsExporter {
+    fn name(&self) -> &'static str {
+        "clang-flags"
+    }
+
+    fn description(&self) -> &'static str {
+        "compile_flags.txt for clangd with one configuration's -I, -D, -include and -std flags"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities { per_config: true, ..Capabilities::default() }
+    }
+
+    fn export(&self, input: &ExportInput) -> Result<Vec<ExportedFile>> {
+        let vcxproj = input.projects.first().context("No project to export")?;
+        let config = export_config(vcxproj, input.config.as_deref())?;
+        let settings = BuildSettings::resolve(vcxproj, &config);
+        if !settings.unknown_properties.is_empty() {
+            warn!("⚠️  Properties left unexpanded (pass them with --property NAME=VALUE): {}", settings.unknown_properties.join(", "));
+        }
+
+        // clangd reads one flag or argument per line, with paths relative to the file
+        let path = |path: &Path| match relative_to(&input.output_dir, path).replace('\\', "/") {
+            path if path.is_empty() => ".".to_string(),
+            path => path,
+        };
+        let mut flags = Vec::new();
+        // A C++ standard wins over a C one, since the file applies to every source
+        let standard = |prefix: &str| settings.compile_flags.iter().find_map(|flag| flag.strip_prefix(prefix));
+        match (standard("/std:c++"), standard("/std:c")) {
+            (Some("latest"), _) => flags.push("-std=c++2b".to_string()),
+            (Some(version), _) => flags.push(format!("-std=c++{}", version)),
+            (None, Some(version)) => flags.push(format!("-std=c{}", version)),
+            (None, None) => {}
+        }
+        flags.extend(settings.defines.iter().map(|define| format!("-D{}", define)));
+        flags.extend(settings.include_dirs.iter().map(|dir| format!("-I{}", path(dir))));
+        for file in &settings.forced_includes {
+            flags.push("-include".to_string());
+            flags.push(path(file));
+        }
+
+        let mut content = flags.join("\n");
+        content.push('\n');
+        Ok(vec![ExportedFile { path: PathBuf::from("compile_flags.txt"), content }])
+    }
+}
+
+/// .vscode/c_cpp_properties.json for the C/C++ extension, with an IntelliSense configuration per
+/// project configuration, and with `--tasks` a tasks.json building them with MSBuild
+struct VscodeExporter;
+
+impl Exporter for VscodeExporter {
+    fn name(&self) -> &'static str {
+        "vscode"
+    }
+
+    fn description(&self) -> &'static str {
+        ".vscode/c_cpp_properties.json with include paths, defines and standards (all configurations unless --config)"
+    }
+
+    fn capabilities(&self) -> Capabilities {
+        Capabilities { per_config: true, tasks: true, ..Capabilities::default() }
+    }
+
+    fn export(&self, input: &ExportInput) -> Result<Vec<ExportedFile>> {
+        let vcxproj = input.projects.first().context("No project to export")?;
+        let configs = match &input.config {
+            Some(config) => vec![export_config(vcxproj, Some(config))?],
+            None => vcxproj.get_configurations(),
+        };
+        // Paths in the workspace are written from ${workspaceFolder}, the directory holding .vscode
+        let path = |path: &Path| match relative_to(&input.output_dir, path).replace('\\', "/") {
+            path if path.get(1..2) == Some(":") => path,
+            path if path.is_empty() => "${workspaceFolder}".to_string(),
+            path => format!("${{workspaceFolder}}/{}", path),
+        };
+
+        let mut configurations = Vec::new();
+        let mut tasks = Vec::new();
+        for config in &configs {
+            let settings = BuildSettings::resolve(vcxproj, config);
+            if !settings.unknown_properties.is_empty() {
+                warn!("⚠️  {}: properties left unexpanded (pass them with --property NAME=VALUE): {}", config, settings.unknown_properties.join(", "));
+            }
+            let (configuration, platform) = config.split_once('|').unwrap_or((config, "Win32"));
+            let architecture = match platform.to_lowercase().as_str() {
+                "win32" => "x86",
+                "arm64" => "arm64",
+                "arm" => "arm",
+                _ => "x64",
+            };
+            let standard = |prefix: &str| settings.compile_flags.iter().find_map(|flag| flag.strip_prefix(prefix));
+            // MSVC compiles C++14 unless told otherwise
+            let cpp_standard = match standard("/std:c++") {
+                Some("latest") => "c++23".to_string(),
+                Some(version) => format!("c++{}", version),
+                None => "c++14".to_string(),
+            };
+            let mut entry = json!({
+                "name": config,
+                "includePath": settings.include_dirs.iter().map(|dir| path(dir)).collect::<Vec<_>>(),
+                "defines": settings.defines,
+                "cppStandard": cpp_standard,
+                "intelliSenseMode": format!("windows-msvc-{}", architecture),
+                "compilerPath": "cl.exe",
+            });
+            if let Some(version) = standard("/std:c") {
+                entry["cStandard"] = json!(format!("c{}", version));
+            }
+            if !settings.forced_includes.is_empty() {
+                entry["forcedInclude"] = json!(settings.forced_includes.iter().map(|file| path(file)).collect::<Vec<_>>());
+            }
+            configurations.push(entry);
+
+            let mut task = json!({
+                "label": format!("Build {}", config),
+                "type": "shell",
+                "command": "msbuild",
+                "args": [path(&vcxproj.path), format!("/p:Configuration={}", configuration), format!("/p:Platform={}", platform), "/m"],
+                "problemMatcher": "$msCompile",
+                "group": "build",
+            });
+            if tasks.is_empty() {
+                task["group"] = json!({ "kind": "build", "isDefault": true });
+            }
+            tasks.push(task);
+        }
+
+        let properties = json!({ "configurations": configurations, "version": 4 });
+        let mut files = vec![ExportedFile {
+            path: Path::new(".vscode").join("c_cpp_properties.json"),
+            content: serde_json::to_string_pretty(&properties)? + "\n",
+        }];
+        if input.tasks {
+            let tasks = json!({ "version": "2.0.0", "tasks": tasks });
+            files.push(ExportedFile { path: Path::new(".vscode").join("tasks.json"), content: serde_json::to_string_pretty(&tasks)? + "\n" });
+        }
+        Ok(files)
+    }
+}
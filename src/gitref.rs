@@ -0,0 +1,108 @@
+use anyhow::{Context, Result};
+use log::debug;
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+/// The git revision project files are read from (`--git-ref`), with the content already fetched
+/// for each path (`None` when the path doesn't exist at that revision)
+#[derive(Debug)]
+struct GitRef {
+    revision: String,
+    blobs: BTreeMap<PathBuf, Option<String>>,
+}
+
+static GIT_REF: Mutex<Option<GitRef>> = Mutex::new(None);
+
+/// Reads project and filters files from `revision` (e.g. HEAD~1, a tag, or ":" for the index)
+/// instead of the working tree
+pub fn enable(revision: Option<String>) {
+    *GIT_REF.lock().unwrap() = revision.map(|revision| GitRef { revision, blobs: BTreeMap::new() });
+}
+
+/// Whether `--git-ref` is in effect
+pub fn is_enabled() -> bool {
+    GIT_REF.lock().unwrap().is_some()
+}
+
+/// The object name of `path` at `revision`: `HEAD:./foo.vcxproj`, or `:./foo.vcxproj` for the index
+fn object_name(revision: &str, file_name: &str) -> String {
+    if revision == ":" {
+        format!(":./{}", file_name)
+    } else {
+        format!("{}:./{}", revision, file_name)
+    }
+}
+
+/// The content of `path` at `revision`, or `None` if the revision doesn't have it
+fn show(revision: &str, path: &Path) -> Result<Option<String>> {
+    let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+    let object = object_name(revision, &file_name);
+    debug!("Reading {} in {}", object, dir.display());
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(["cat-file", "blob", &object])
+        .output()
+        .context("Failed to run git (is it installed and on PATH?)")?;
+    if output.status.success() {
+        return Ok(Some(String::from_utf8_lossy(&output.stdout).into_owned()));
+    }
+
+    // A failure for a revision that exists means the file isn't in it
+    let revision_exists = revision == ":"
+        || Command::new("git")
+            .arg("-C")
+            .arg(dir)
+            .args(["rev-parse", "--verify", "--quiet", &format!("{}^{{commit}}", revision)])
+            .output()
+            .is_ok_and(|output| output.status.success());
+    if revision_exists {
+        return Ok(None);
+    }
+    Err(anyhow::anyhow!(
+        "Cannot read {} from git revision '{}': {}",
+        path.display(),
+        revision,
+        String::from_utf8_lossy(&output.stderr).trim()
+    ))
+}
+
+/// The content of `path` at the `--git-ref` revision, or `None` to read it from the working tree
+pub fn read(path: &Path) -> Option<Result<String>> {
+    let mut guard = GIT_REF.lock().unwrap();
+    let git_ref = guard.as_mut()?;
+    if !git_ref.blobs.contains_key(path) {
+        match show(&git_ref.revision, path) {
+            Ok(blob) => git_ref.blobs.insert(path.to_path_buf(), blob),
+            Err(err) => return Some(Err(err)),
+        };
+    }
+    Some(match &git_ref.blobs[path] {
+        Some(content) => Ok(content.clone()),
+        None => Err(anyhow::anyhow!("{} does not exist in git revision '{}'", path.display(), git_ref.revision)),
+    })
+}
+
+/// Whether `path` exists at the `--git-ref` revision, or `None` without one
+pub fn exists(path: &Path) -> Option<bool> {
+    is_enabled().then(|| read(path).is_some_and(|content| content.is_ok()))
+}
+
+/// Fails for writes to project files while they are read from a git revision, since saving that
+/// content would overwrite the working tree with an old version
+pub fn check_writable(path: &Path) -> Result<()> {
+    let Some(git_ref) = GIT_REF.lock().unwrap().as_ref().map(|git_ref| git_ref.revision.clone()) else {
+        return Ok(());
+    };
+    let name = path.to_string_lossy().to_lowercase();
+    if [".vcxproj", ".vcxproj.filters", ".vcxitems", ".vcxitems.filters"].iter().any(|ext| name.ends_with(ext)) {
+        return Err(anyhow::anyhow!(
+            "Project files are read from git revision '{}' (--git-ref); commands that modify them cannot be used with it",
+            git_ref
+        ));
+    }
+    Ok(())
+}
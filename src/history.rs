@@ -0,0 +1,558 @@
+use anyhow::{Context, Result};
+use log::debug;
+use std::fs;
+use std::cell::RefCell;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Directory (next to the project file) that holds the operation journal
+pub const HISTORY_DIR: &str = ".vcprojm/history";
+
+/// Per-project settings file, next to the history directory
+pub const CONFIG_FILE: &str = ".vcprojm/config.toml";
+
+/// Entries kept when the config sets no `max_entries`
+pub const DEFAULT_MAX_ENTRIES: usize = 100;
+
+/// A file written by the current command, with its content before and after the write
+/// (`None` when the file did not exist before, or was removed by the command)
+struct FileSnapshot {
+    path: PathBuf,
+    before: Option<String>,
+    after: Option<String>,
+}
+
+/// The files a mutating command has written so far, recorded as one history entry by `finish`
+pub struct PendingOperation {
+    command: String,
+    /// The project given on the command line, whose history records the operation
+    project: Option<PathBuf>,
+    files: Vec<FileSnapshot>,
+}
+
+impl PendingOperation {
+    /// The files written (or removed)
+    pub fn written_files(&self) -> Vec<PathBuf> {
+        self.files.iter().map(|f| f.path.clone()).collect()
+    }
+}
+
+#[derive(Debug)]
+pub struct HistoryEntry {
+    pub id: usize,
+    pub dir: PathBuf,
+    pub command: String,
+    /// Seconds since the Unix epoch when the operation was recorded
+    pub timestamp: u64,
+    pub files: Vec<PathBuf>,
+}
+
+/// How many history entries to keep, from the `[history]` table of `.vcprojm/config.toml`:
+///
+/// ```toml
+/// [history]
+/// max_entries = 50     # keep the 50 most recent operations
+/// max_age_days = 30    # and drop operations older than 30 days
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct Retention {
+    pub max_entries: Option<usize>,
+    pub max_age_days: Option<u64>,
+}
+
+impl Retention {
+    /// Reads the retention policy for the history in `history_dir` (defaults when there is no config)
+    pub fn load(history_dir: &Path) -> Result<Self> {
+        let mut retention = Retention { max_entries: Some(DEFAULT_MAX_ENTRIES), max_age_days: None };
+        let config_path = history_dir
+            .parent()
+            .and_then(Path::parent)
+            .unwrap_or_else(|| Path::new("."))
+            .join(CONFIG_FILE);
+        let Ok(content) = fs::read_to_string(&config_path) else {
+            return Ok(retention);
+        };
+        let config: toml::Table = content
+            .parse()
+            .with_context(|| format!("Invalid config file: {}", config_path.display()))?;
+
+        let Some(history) = config.get("history") else {
+            return Ok(retention);
+        };
+        let history = history
+            .as_table()
+            .ok_or_else(|| anyhow::anyhow!("[history] must be a table in {}", config_path.display()))?;
+        for (key, value) in history {
+            let number = value
+                .as_integer()
+                .and_then(|n| u64::try_from(n).ok())
+                .ok_or_else(|| anyhow::anyhow!("history.{} must be a non-negative integer in {}", key, config_path.display()))?;
+            match key.as_str() {
+                "max_entries" => retention.max_entries = Some(number as usize),
+                "max_age_days" => retention.max_age_days = Some(number),
+                _ => return Err(anyhow::anyhow!("Unknown key 'history.{}' in {}", key, config_path.display())),
+            }
+        }
+        Ok(retention)
+    }
+}
+
+thread_local! {
+    /// The operation of the command running on this thread: projects processed in parallel each
+    /// journal their own
+    static PENDING: RefCell<Option<PendingOperation>> = const { RefCell::new(None) };
+}
+
+/// Starts journaling a mutating command. Writes recorded until `finish` form one history entry,
+/// kept in the history of `project` so `undo -p <project>` finds it wherever the files were written.
+pub fn begin(command: String, project: Option<PathBuf>) {
+    PENDING.with_borrow_mut(|pending| *pending = Some(PendingOperation { command, project, files: Vec::new() }));
+}
+
+/// Ends the pending operation without recording it yet, so operations run in parallel can be
+/// recorded in a predictable order with `finish_operation`
+pub fn take() -> Option<PendingOperation> {
+    PENDING.with_borrow_mut(Option::take)
+}
+
+/// Records that `path` is about to be overwritten with `new_content`.
+/// Must be called before the write so the previous content can still be read from disk.
+pub fn record(path: &Path, new_content: &str) -> Result<()> {
+    record_snapshot(path, Some(new_content))
+}
+
+/// Records that `path` is about to be deleted. Must be called before the file is removed.
+pub fn record_removal(path: &Path) -> Result<()> {
+    record_snapshot(path, None)
+}
+
+fn record_snapshot(path: &Path, new_content: Option<&str>) -> Result<()> {
+    // In pipe mode (`--stdout`) the project never touches the disk, so there is nothing to undo
+    if crate::pipe::takes(path) {
+        return Ok(());
+    }
+    PENDING.with_borrow_mut(|pending| match pending.as_mut() {
+        Some(operation) => snapshot(operation, path, new_content),
+        None => Ok(()),
+    })
+}
+
+fn snapshot(operation: &mut PendingOperation, path: &Path, new_content: Option<&str>) -> Result<()> {
+    let parent = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let path = fs::canonicalize(parent)
+        .map(|dir| dir.join(path.file_name().unwrap_or_default()))
+        .unwrap_or_else(|_| path.to_path_buf());
+
+    // A file saved twice in one command keeps its original "before" snapshot
+    if let Some(existing) = operation.files.iter_mut().find(|f| f.path == path) {
+        existing.after = new_content.map(str::to_string);
+        return Ok(());
+    }
+
+    let before = if path.exists() {
+        Some(fs::read_to_string(&path)
+            .with_context(|| format!("Failed to snapshot file: {}", path.display()))?)
+    } else {
+        None
+    };
+
+    operation.files.push(FileSnapshot {
+        path,
+        before,
+        after: new_content.map(str::to_string),
+    });
+    Ok(())
+}
+
+/// Writes the pending operation (if it changed any files) to the history of its project
+pub fn finish() -> Result<()> {
+    match take() {
+        Some(operation) => finish_operation(operation),
+        None => Ok(()),
+    }
+}
+
+/// Writes an operation ended with `take` to the history, if it changed any files
+pub fn finish_operation(operation: PendingOperation) -> Result<()> {
+    if operation.files.is_empty() {
+        return Ok(());
+    }
+
+    // Commands that take no project keep the history next to the first file they wrote
+    let history_dir = history_dir_for(operation.project.as_deref().unwrap_or(&operation.files[0].path));
+    fs::create_dir_all(&history_dir)
+        .with_context(|| format!("Failed to create history directory: {}", history_dir.display()))?;
+
+    // Recording a new operation discards any undone entries that could have been redone
+    let head = read_head(&history_dir);
+    for entry in list_entries(&history_dir)? {
+        if entry.id > head {
+            fs::remove_dir_all(&entry.dir)
+                .with_context(|| format!("Failed to remove history entry: {}", entry.dir.display()))?;
+        }
+    }
+
+    let id = head + 1;
+    let entry_dir = history_dir.join(format!("{:04}", id));
+    fs::create_dir_all(&entry_dir)
+        .with_context(|| format!("Failed to create history entry: {}", entry_dir.display()))?;
+
+    let timestamp = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let mut meta = format!("command: {}\ntimestamp: {}\n", operation.command, timestamp);
+
+    for (index, file) in operation.files.iter().enumerate() {
+        meta.push_str(&format!("file: {}\n", file.path.display()));
+        if let Some(before) = &file.before {
+            fs::write(entry_dir.join(format!("{}.before", index)), before)?;
+        }
+        if let Some(after) = &file.after {
+            fs::write(entry_dir.join(format!("{}.after", index)), after)?;
+        }
+    }
+
+    fs::write(entry_dir.join("entry.txt"), meta)
+        .with_context(|| format!("Failed to write history entry: {}", entry_dir.display()))?;
+    debug!("Recorded history entry #{} in {}", id, history_dir.display());
+    write_head(&history_dir, id)?;
+
+    let pruned = prune(&history_dir, Retention::load(&history_dir)?, false)?;
+    if !pruned.is_empty() {
+        debug!("Pruned {} old history entries", pruned.len());
+    }
+    Ok(())
+}
+
+/// Restores every file written since `begin` to its previous content (removing files that did not exist)
+/// and drops the pending operation, so a failed or previewed multi-step command leaves nothing behind.
+/// Returns the restored paths.
+pub fn rollback() -> Result<Vec<PathBuf>> {
+    let Some(operation) = take() else {
+        return Ok(Vec::new());
+    };
+
+    let changes: Vec<(PathBuf, Option<String>)> = operation
+        .files
+        .into_iter()
+        .map(|file| (file.path, file.before))
+        .collect();
+    replace_files(&changes)?;
+    Ok(changes.into_iter().map(|(path, _)| path).collect())
+}
+
+/// Suffix of the temporary file a write is prepared in before it replaces the real file
+const STAGING_SUFFIX: &str = ".vcprojm-tmp";
+
+/// Writes `content` to `path` through a temporary file renamed over it, so readers (and a crash)
+/// only ever see the old or the new file, never a partially written one
+pub fn replace_file(path: &Path, content: &str) -> Result<()> {
+    replace_files(&[(path.to_path_buf(), Some(content.to_string()))])
+}
+
+/// Applies several file changes as one transaction, in two phases: every new content is first
+/// written to a temporary file next to its target, and only once all of them are prepared are they
+/// renamed into place (`None` deletes the file). A failure while preparing leaves every target
+/// untouched, and a failed rename puts back the targets already replaced.
+pub fn replace_files(changes: &[(PathBuf, Option<String>)]) -> Result<()> {
+    for (path, _) in changes {
+        crate::gitref::check_writable(path)?;
+        crate::pipe::check_writable(path)?;
+    }
+    // (temporary file, target, the target's previous content)
+    let mut staged = Vec::new();
+    let discard = |staged: &[(PathBuf, &PathBuf, Option<String>)]| {
+        for (staging, _, _) in staged {
+            let _ = fs::remove_file(staging);
+        }
+    };
+    for (path, content) in changes {
+        let Some(content) = content else {
+            continue;
+        };
+        if crate::pipe::takes(path) {
+            crate::pipe::write(path, content);
+            continue;
+        }
+        let mut staging = path.clone().into_os_string();
+        staging.push(STAGING_SUFFIX);
+        let staging = PathBuf::from(staging);
+        let previous = fs::read_to_string(path).ok();
+        if let Err(err) = fs::write(&staging, content) {
+            let _ = fs::remove_file(&staging);
+            discard(&staged);
+            return Err(err).with_context(|| format!("Failed to write file: {}", path.display()));
+        }
+        staged.push((staging, path, previous));
+    }
+
+    for (k, (staging, path, _)) in staged.iter().enumerate() {
+        if let Err(err) = fs::rename(staging, path) {
+            for (_, replaced, previous) in &staged[..k] {
+                let _ = match previous {
+                    Some(previous) => fs::write(replaced, previous),
+                    None => fs::remove_file(replaced),
+                };
+            }
+            discard(&staged[k..]);
+            return Err(err).with_context(|| format!("Failed to replace file: {}", path.display()));
+        }
+    }
+    for (path, content) in changes {
+        if content.is_none() && path.exists() && !crate::pipe::takes(path) {
+            fs::remove_file(path)
+                .with_context(|| format!("Failed to remove file: {}", path.display()))?;
+        }
+    }
+    Ok(())
+}
+
+/// Returns the history directory for a project file
+pub fn history_dir_for(project_path: &Path) -> PathBuf {
+    project_path
+        .parent()
+        .unwrap_or_else(|| Path::new("."))
+        .join(HISTORY_DIR)
+}
+
+/// Returns the id of the most recently applied entry (0 when everything has been undone)
+pub fn read_head(history_dir: &Path) -> usize {
+    fs::read_to_string(history_dir.join("HEAD"))
+        .ok()
+        .and_then(|s| s.trim().parse().ok())
+        .unwrap_or(0)
+}
+
+fn write_head(history_dir: &Path, id: usize) -> Result<()> {
+    fs::write(history_dir.join("HEAD"), format!("{}\n", id))
+        .with_context(|| format!("Failed to update history head in {}", history_dir.display()))
+}
+
+/// Lists all history entries ordered by id
+pub fn list_entries(history_dir: &Path) -> Result<Vec<HistoryEntry>> {
+    let mut entries = Vec::new();
+    if !history_dir.exists() {
+        return Ok(entries);
+    }
+
+    for dir_entry in fs::read_dir(history_dir)
+        .with_context(|| format!("Failed to read history directory: {}", history_dir.display()))?
+    {
+        let dir = dir_entry?.path();
+        let Some(id) = dir.file_name().and_then(|n| n.to_str()).and_then(|n| n.parse().ok()) else {
+            continue;
+        };
+        let Ok(meta) = fs::read_to_string(dir.join("entry.txt")) else {
+            continue;
+        };
+
+        let mut entry = HistoryEntry {
+            id,
+            dir: dir.clone(),
+            command: String::new(),
+            timestamp: 0,
+            files: Vec::new(),
+        };
+        for line in meta.lines() {
+            if let Some(command) = line.strip_prefix("command: ") {
+                entry.command = command.to_string();
+            } else if let Some(timestamp) = line.strip_prefix("timestamp: ") {
+                entry.timestamp = timestamp.trim().parse().unwrap_or_default();
+            } else if let Some(file) = line.strip_prefix("file: ") {
+                entry.files.push(PathBuf::from(file));
+            }
+        }
+        entries.push(entry);
+    }
+
+    entries.sort_by_key(|e| e.id);
+    Ok(entries)
+}
+
+/// Restores the files of `entry` to their "before" (undo) or "after" (redo) state.
+/// Unless `force` is set, refuses when the files were changed since the entry was applied.
+/// With `dryrun` only checks, and returns the paths that would be restored.
+pub fn restore(entry: &HistoryEntry, undo: bool, force: bool, dryrun: bool) -> Result<Vec<PathBuf>> {
+    let (expected_suffix, target_suffix) = if undo { ("after", "before") } else { ("before", "after") };
+
+    if !force {
+        for (index, file) in entry.files.iter().enumerate() {
+            let expected = fs::read_to_string(entry.dir.join(format!("{}.{}", index, expected_suffix))).ok();
+            let current = fs::read_to_string(file).ok();
+            if expected != current {
+                return Err(anyhow::anyhow!(
+                    "{} was modified after operation #{} (use --force to overwrite)",
+                    file.display(),
+                    entry.id
+                ));
+            }
+        }
+    }
+
+    // A missing snapshot means the file did not exist in the state being restored
+    let mut changes = Vec::new();
+    for (index, file) in entry.files.iter().enumerate() {
+        let snapshot = entry.dir.join(format!("{}.{}", index, target_suffix));
+        let content = if snapshot.exists() {
+            Some(fs::read_to_string(&snapshot)
+                .with_context(|| format!("Failed to read snapshot: {}", snapshot.display()))?)
+        } else {
+            None
+        };
+        changes.push((file.clone(), content));
+    }
+    if dryrun {
+        return Ok(changes.into_iter().map(|(path, _)| path).collect());
+    }
+    replace_files(&changes)?;
+    let restored = changes.into_iter().map(|(path, _)| path).collect();
+
+    let history_dir = entry.dir.parent().unwrap_or_else(|| Path::new("."));
+    write_head(history_dir, if undo { entry.id - 1 } else { entry.id })?;
+    Ok(restored)
+}
+
+/// Deletes the oldest entries beyond `retention`, or only lists them with `dryrun`.
+/// Returns the pruned entries.
+pub fn prune(history_dir: &Path, retention: Retention, dryrun: bool) -> Result<Vec<HistoryEntry>> {
+    let entries = list_entries(history_dir)?;
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default();
+    let excess = retention
+        .max_entries
+        .map_or(0, |max| entries.len().saturating_sub(max));
+
+    let mut pruned = Vec::new();
+    for (index, entry) in entries.into_iter().enumerate() {
+        let too_old = retention
+            .max_age_days
+            .is_some_and(|days| now.saturating_sub(entry.timestamp) > days * 24 * 60 * 60);
+        if index < excess || too_old {
+            pruned.push(entry);
+        }
+    }
+
+    if !dryrun {
+        for entry in &pruned {
+            fs::remove_dir_all(&entry.dir)
+                .with_context(|| format!("Failed to remove history entry: {}", entry.dir.display()))?;
+        }
+    }
+    Ok(pruned)
+}
+
+/// Undoes or redoes entries one at a time until the files are in the state right after entry `id`
+/// (0 = before the oldest entry). Returns the entries that were applied and the restored paths,
+/// or with `dryrun` those that would be.
+pub fn restore_to(history_dir: &Path, id: usize, force: bool, dryrun: bool) -> Result<(Vec<usize>, Vec<PathBuf>)> {
+    let entries = list_entries(history_dir)?;
+    let mut head = read_head(history_dir);
+    let find = |id: usize| {
+        entries
+            .iter()
+            .find(|e| e.id == id)
+            .ok_or_else(|| anyhow::anyhow!("History entry #{} is no longer available (it may have been pruned)", id))
+    };
+    if id > entries.last().map_or(0, |e| e.id) {
+        return Err(anyhow::anyhow!("No operation #{} in {}", id, history_dir.display()));
+    }
+    // Every entry between the head and the target is needed, so check before touching any file
+    for needed in head.min(id) + 1..=head.max(id) {
+        find(needed)?;
+    }
+
+    let mut applied = Vec::new();
+    let mut restored: Vec<PathBuf> = Vec::new();
+    while head != id {
+        let (entry, undo) = if head > id { (find(head)?, true) } else { (find(head + 1)?, false) };
+        // A dry run leaves the files as they are, so only the first step can be checked against them
+        for path in restore(entry, undo, force || (dryrun && !applied.is_empty()), dryrun)? {
+            if !restored.contains(&path) {
+                restored.push(path);
+            }
+        }
+        applied.push(entry.id);
+        head = if undo { entry.id - 1 } else { entry.id };
+    }
+    Ok((applied, restored))
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A fresh directory under the system temp dir, removed again when dropped
+    pub(crate) struct TempDir(pub(crate) PathBuf);
+
+    impl TempDir {
+        pub(crate) fn new(name: &str) -> Self {
+            let dir = std::env::temp_dir().join(format!("vcprojm-{}-{}", name, std::process::id()));
+            let _ = fs::remove_dir_all(&dir);
+            fs::create_dir_all(&dir).unwrap();
+            TempDir(dir)
+        }
+
+        /// The names of the files left in the directory, sorted
+        fn files(&self) -> Vec<String> {
+            let mut files: Vec<String> = fs::read_dir(&self.0).unwrap().map(|e| e.unwrap().file_name().to_string_lossy().into_owned()).collect();
+            files.sort();
+            files
+        }
+    }
+
+    impl Drop for TempDir {
+        fn drop(&mut self) {
+            let _ = fs::remove_dir_all(&self.0);
+        }
+    }
+
+    #[test]
+    fn replace_files_writes_and_removes_every_file() {
+        let dir = TempDir::new("replace");
+        let (a, b, c) = (dir.0.join("a.vcxproj"), dir.0.join("b.vcxproj"), dir.0.join("c.vcxproj"));
+        fs::write(&a, "old a").unwrap();
+        fs::write(&c, "old c").unwrap();
+
+        replace_files(&[(a.clone(), Some("new a".to_string())), (b.clone(), Some("new b".to_string())), (c.clone(), None)]).unwrap();
+        assert_eq!(fs::read_to_string(&a).unwrap(), "new a");
+        assert_eq!(fs::read_to_string(&b).unwrap(), "new b");
+        assert_eq!(dir.files(), ["a.vcxproj", "b.vcxproj"]);
+    }
+
+    #[test]
+    fn replace_files_failing_to_stage_changes_nothing() {
+        let dir = TempDir::new("stage");
+        let a = dir.0.join("a.vcxproj");
+        fs::write(&a, "old a").unwrap();
+
+        let missing = dir.0.join("missing").join("b.vcxproj");
+        let result = replace_files(&[(a.clone(), Some("new a".to_string())), (missing, Some("new b".to_string()))]);
+        assert!(result.is_err());
+        assert_eq!(fs::read_to_string(&a).unwrap(), "old a");
+        assert_eq!(dir.files(), ["a.vcxproj"]);
+    }
+
+    #[test]
+    fn replace_files_failing_midway_restores_the_files_already_replaced() {
+        let dir = TempDir::new("rename");
+        let (a, b, c) = (dir.0.join("a.vcxproj"), dir.0.join("b.vcxproj"), dir.0.join("c.vcxproj"));
+        fs::write(&a, "old a").unwrap();
+        // A non-empty directory where the last file goes: its content can be staged, but not renamed into place
+        fs::create_dir_all(c.join("inside")).unwrap();
+
+        let result = replace_files(&[
+            (a.clone(), Some("new a".to_string())),
+            (b.clone(), Some("new b".to_string())),
+            (c.clone(), Some("new c".to_string())),
+        ]);
+        let message = format!("{:#}", result.unwrap_err());
+        assert!(message.starts_with(&format!("Failed to replace file: {}", c.display())), "{}", message);
+        assert_eq!(fs::read_to_string(&a).unwrap(), "old a");
+        assert!(!b.exists());
+        assert!(c.join("inside").is_dir());
+        assert_eq!(dir.files(), ["a.vcxproj", "c.vcxproj"]);
+    }
+}
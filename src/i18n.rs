@@ -0,0 +1,1638 @@
+use std::fmt::Display;
+use std::sync::atomic::{AtomicU8, Ordering};
+
+/// Language of the console output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[repr(u8)]
+pub enum Locale {
+    En,
+    Ja,
+    Zh,
+}
+
+static LOCALE: AtomicU8 = AtomicU8::new(Locale::En as u8);
+
+pub fn set_locale(locale: Locale) {
+    LOCALE.store(locale as u8, Ordering::Relaxed);
+}
+
+pub fn locale() -> Locale {
+    match LOCALE.load(Ordering::Relaxed) {
+        1 => Locale::Ja,
+        2 => Locale::Zh,
+        _ => Locale::En,
+    }
+}
+
+/// The locale selected by `--lang`, else by VCPROJM_LANG or the usual LC_ALL, LC_MESSAGES and LANG
+/// (e.g. "ja_JP.UTF-8"), falling back to English
+pub fn locale_from(lang: Option<&str>) -> Locale {
+    let from_env = || {
+        ["VCPROJM_LANG", "LC_ALL", "LC_MESSAGES", "LANG"]
+            .iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+    };
+    let lang = lang.map(str::to_string).or_else(from_env).unwrap_or_default().to_lowercase();
+    if lang.starts_with("ja") {
+        Locale::Ja
+    } else if lang.starts_with("zh") {
+        Locale::Zh
+    } else {
+        Locale::En
+    }
+}
+
+/// Translations of the console messages, keyed by the English format string used at the call site.
+/// A translation may reorder the arguments with positional placeholders (`{1} ... {0}`); messages
+/// without an entry are printed in English.
+static CATALOG: &[(&str, &str, &str)] = &[
+    (
+        "\n🔍 DRY RUN - No files were modified",
+        "\n🔍 ドライラン - ファイルは変更されていません",
+        "\n🔍 试运行 - 未修改任何文件",
+    ),
+    (
+        "❌ Command failed - restored {} files it had already written",
+        "❌ コマンドが失敗しました - 書き込み済みの {} 個のファイルを元に戻しました",
+        "❌ 命令失败 - 已恢复其已写入的 {} 个文件",
+    ),
+    ("Operation cancelled.", "操作をキャンセルしました。", "操作已取消。"),
+    (
+        "\nRemove {} items from project? [y/N]: ",
+        "\n{} 個の項目をプロジェクトから削除しますか? [y/N]: ",
+        "\n要从项目中移除 {} 个项吗？[y/N]: ",
+    ),
+    (
+        "\nToggle items (e.g. 1,3,5-9), 'a' all, 'n' none, Enter to remove {} items, 'q' to cancel: ",
+        "\n項目を切り替え (例: 1,3,5-9)、'a' ですべて選択、'n' で選択解除、Enter で {} 個の項目を削除、'q' でキャンセル: ",
+        "\n切换项 (例如 1,3,5-9)，'a' 全选，'n' 全不选，回车移除 {} 个项，'q' 取消: ",
+    ),
+    (
+        "\nMerge '{}' into existing '{}' filter? [y/N]: ",
+        "\n'{}' を既存のフィルター '{}' に統合しますか? [y/N]: ",
+        "\n要将 '{}' 合并到现有筛选器 '{}' 中吗？[y/N]: ",
+    ),
+    ("Analyzing project: {}", "プロジェクトを解析中: {}", "正在分析项目：{}"),
+    ("Scanning directory: {}", "ディレクトリをスキャン中: {}", "正在扫描目录：{}"),
+    ("Searching solution: {}", "ソリューションを検索中: {}", "正在搜索解决方案：{}"),
+    ("Looking for *.{} files", "*.{} ファイルを検索中", "正在查找 *.{} 文件"),
+    (
+        "Skipping {} files already in the project",
+        "プロジェクトに既にある {} 個のファイルをスキップします",
+        "跳过项目中已有的 {} 个文件",
+    ),
+    ("Found {} files to add:", "追加するファイルが {} 個見つかりました:", "找到 {} 个要添加的文件："),
+    ("No files found matching: {}", "一致するファイルが見つかりません: {}", "未找到匹配的文件：{}"),
+    (
+        "No files or filters found matching: {}",
+        "一致するファイルやフィルターが見つかりません: {}",
+        "未找到匹配的文件或筛选器：{}",
+    ),
+    (
+        "✅ No changes - every matching file is already in {}",
+        "✅ 変更なし - 一致するファイルはすべて既に {} にあります",
+        "✅ 无变化 - 所有匹配的文件均已在 {} 中",
+    ),
+    ("Would update project file: {}", "プロジェクトファイルを更新予定: {}", "将更新项目文件：{}"),
+    ("Would update filter file: {}", "フィルターファイルを更新予定: {}", "将更新筛选器文件：{}"),
+    ("Would create filter file: {}", "フィルターファイルを作成予定: {}", "将创建筛选器文件：{}"),
+    ("Would create project file: {}", "プロジェクトファイルを作成予定: {}", "将创建项目文件：{}"),
+    ("Would update: {}", "更新予定: {}", "将更新：{}"),
+    ("Would create: {}", "作成予定: {}", "将创建：{}"),
+    ("Would write: {}", "書き込み予定: {}", "将写入：{}"),
+    ("\nUpdating project file: {}", "\nプロジェクトファイルを更新中: {}", "\n正在更新项目文件：{}"),
+    ("Updating filter file: {}", "フィルターファイルを更新中: {}", "正在更新筛选器文件：{}"),
+    ("Filter file not found: {}", "フィルターファイルが見つかりません: {}", "未找到筛选器文件：{}"),
+    ("Creating basic filter file...", "基本のフィルターファイルを作成中...", "正在创建基本筛选器文件..."),
+    ("Successfully updated {}", "{} を更新しました", "已成功更新 {}"),
+    ("Created {}", "{} を作成しました", "已创建 {}"),
+    ("Wrote {}", "{} を書き込みました", "已写入 {}"),
+    ("No changes to {}", "{} に変更はありません", "{} 没有变化"),
+    ("\n📁 Files affected:", "\n📁 対象ファイル:", "\n📁 受影响的文件："),
+    (
+        "\n📁 Files to be removed from project:",
+        "\n📁 プロジェクトから削除されるファイル:",
+        "\n📁 将从项目中移除的文件：",
+    ),
+    (
+        "✨ Dry run completed - {} files would be added",
+        "✨ ドライラン完了 - {} 個のファイルが追加されます",
+        "✨ 试运行完成 - 将添加 {} 个文件",
+    ),
+    (
+        "✨ Dry run completed - {} files would be removed",
+        "✨ ドライラン完了 - {} 個のファイルが削除されます",
+        "✨ 试运行完成 - 将移除 {} 个文件",
+    ),
+    (
+        "✨ Dry run completed - {} files would be moved",
+        "✨ ドライラン完了 - {} 個のファイルが移動されます",
+        "✨ 试运行完成 - 将移动 {} 个文件",
+    ),
+    (
+        "✨ Dry run completed - {} files would be updated",
+        "✨ ドライラン完了 - {} 個のファイルが更新されます",
+        "✨ 试运行完成 - 将更新 {} 个文件",
+    ),
+    (
+        "\n✅ Project files updated successfully!",
+        "\n✅ プロジェクトファイルを更新しました！",
+        "\n✅ 项目文件更新成功！",
+    ),
+    (
+        "\n🗑️  Successfully removed {} files from project!\n",
+        "\n🗑️  プロジェクトから {} 個のファイルを削除しました！\n",
+        "\n🗑️  已成功从项目中移除 {} 个文件！\n",
+    ),
+    ("⚠️  Conflict detected!", "⚠️  競合が検出されました！", "⚠️  检测到冲突！"),
+    (
+        "Filter '{}' already exists in the project.",
+        "フィルター '{}' はプロジェクトに既に存在します。",
+        "筛选器 '{}' 已存在于项目中。",
+    ),
+    (
+        "✅ Successfully merged filter '{}' into '{}'",
+        "✅ フィルター '{}' を '{}' に統合しました",
+        "✅ 已成功将筛选器 '{}' 合并到 '{}'",
+    ),
+    (
+        "✅ Successfully renamed filter '{}' to '{}'",
+        "✅ フィルター '{}' の名前を '{}' に変更しました",
+        "✅ 已成功将筛选器 '{}' 重命名为 '{}'",
+    ),
+    (
+        "⚠️  No configurations found to modify",
+        "⚠️  変更する構成が見つかりません",
+        "⚠️  未找到要修改的配置",
+    ),
+    ("⚠️  No configurations match '{}'", "⚠️  '{}' に一致する構成はありません", "⚠️  没有与 '{}' 匹配的配置"),
+    ("Nothing to undo for {}", "{} には元に戻せる操作がありません", "{} 没有可撤销的操作"),
+    ("Nothing to redo for {}", "{} にはやり直せる操作がありません", "{} 没有可重做的操作"),
+    ("↩️  Undid operation #{}: {}", "↩️  操作 #{} を元に戻しました: {}", "↩️  已撤销操作 #{}：{}"),
+    ("↪️  Redid operation #{}: {}", "↪️  操作 #{} をやり直しました: {}", "↪️  已重做操作 #{}：{}"),
+    ("No operations recorded for {}", "{} の操作は記録されていません", "{} 没有记录任何操作"),
+    (
+        "🗂️  History for {} ({} operations)\n",
+        "🗂️  {} の履歴 ({} 件の操作)\n",
+        "🗂️  {} 的历史记录 ({} 个操作)\n",
+    ),
+    (
+        "→ #0     (every operation is undone)",
+        "→ #0     (すべての操作が元に戻されています)",
+        "→ #0     (所有操作均已撤销)",
+    ),
+    (
+        "✨ Dry run completed - {} operations would be deleted",
+        "✨ ドライラン完了 - {} 件の操作が削除されます",
+        "✨ 试运行完成 - 将删除 {} 个操作",
+    ),
+    (
+        "\n✅ Deleted {} operations from {}",
+        "\n✅ {1} から {0} 件の操作を削除しました",
+        "\n✅ 已从 {1} 删除 {0} 个操作",
+    ),
+    ("✅ {} is already at operation #{}", "✅ {} は既に操作 #{} の状態です", "✅ {} 已处于操作 #{}"),
+    ("Running script: {} ({} steps)", "スクリプトを実行中: {} ({} ステップ)", "正在运行脚本：{} ({} 个步骤)"),
+    ("\n▶ Step {}/{}: {}", "\n▶ ステップ {}/{}: {}", "\n▶ 步骤 {}/{}：{}"),
+    (
+        "\n❌ Step {} failed - restored {} files, nothing was changed",
+        "\n❌ ステップ {} が失敗しました - {} 個のファイルを元に戻しました (何も変更されていません)",
+        "\n❌ 步骤 {} 失败 - 已恢复 {} 个文件，未做任何更改",
+    ),
+    (
+        "\n✅ Script completed - {} steps applied",
+        "\n✅ スクリプト完了 - {} ステップを適用しました",
+        "\n✅ 脚本完成 - 已应用 {} 个步骤",
+    ),
+    (
+        "Creating {} project '{}' {{{}}}",
+        "{} プロジェクト '{}' {{{}}} を作成中",
+        "正在创建 {} 项目 '{}' {{{}}}",
+    ),
+    ("\n✅ Project created successfully!", "\n✅ プロジェクトを作成しました！", "\n✅ 项目创建成功！"),
+    ("Formatting project: {}", "プロジェクトを整形中: {}", "正在格式化项目：{}"),
+    ("Repairing project: {}", "プロジェクトを修復中: {}", "正在修复项目：{}"),
+    ("✅ No changes - nothing to repair", "✅ 変更なし - 修復する箇所はありません", "✅ 无变化 - 无需修复"),
+    ("🔎 Auditing {} projects", "🔎 {} 個のプロジェクトを監査中", "🔎 正在审查 {} 个项目"),
+    (
+        "\n✅ {} of {} projects use only constructs every command supports",
+        "\n✅ {1} 個中 {0} 個のプロジェクトは、すべてのコマンドが対応する構文のみを使用しています",
+        "\n✅ {1} 个项目中有 {0} 个仅使用所有命令都支持的结构",
+    ),
+    ("Available export formats:", "利用可能なエクスポート形式:", "可用的导出格式："),
+    ("Exporting {} projects as {}", "{} 個のプロジェクトを {} としてエクスポート中", "正在将 {} 个项目导出为 {}"),
+    (
+        "✅ No changes - the exported files are up to date",
+        "✅ 変更なし - エクスポート済みのファイルは最新です",
+        "✅ 无变化 - 导出的文件已是最新",
+    ),
+    ("\n✅ Exported {} files", "\n✅ {} 個のファイルをエクスポートしました", "\n✅ 已导出 {} 个文件"),
+    (
+        "⏳ Waiting for Visual Studio to close ({} is in use) - press Ctrl+C to cancel",
+        "⏳ Visual Studio の終了を待っています ({} が使用中) - Ctrl+C でキャンセル",
+        "⏳ 正在等待 Visual Studio 关闭 ({} 正在使用中) - 按 Ctrl+C 取消",
+    ),
+    (
+        "Visual Studio was closed, continuing",
+        "Visual Studio が終了しました。続行します",
+        "Visual Studio 已关闭，继续执行",
+    ),
+    (
+        "\n🔄 If the solution is open, Visual Studio will offer to reload:",
+        "\n🔄 ソリューションを開いている場合、Visual Studio が再読み込みを提案します:",
+        "\n🔄 如果解决方案已打开，Visual Studio 会提示重新加载：",
+    ),
+    (
+        "Choose \"Reload All\" (or close and reopen the solution if no prompt appears).",
+        "「すべて再読み込み」を選んでください (確認が表示されない場合はソリューションを開き直してください)。",
+        "请选择“全部重新加载”(如果没有出现提示，请关闭并重新打开解决方案)。",
+    ),
+    (
+        "  ⚠️  {} is a release configuration; ASan slows the program down and shouldn't ship",
+        "  ⚠️  {} はリリース構成です。ASan はプログラムを遅くするため、出荷するべきではありません",
+        "  ⚠️  {} 是发布配置；ASan 会拖慢程序，不应随产品发布",
+    ),
+    (
+        "✅ No changes - AddressSanitizer is already enabled in every matching configuration",
+        "✅ 変更なし - 一致するすべての構成で AddressSanitizer は既に有効です",
+        "✅ 无更改 - 所有匹配的配置均已启用 AddressSanitizer",
+    ),
+    (
+        "✅ No changes - AddressSanitizer is already disabled in every matching configuration",
+        "✅ 変更なし - 一致するすべての構成で AddressSanitizer は既に無効です",
+        "✅ 无更改 - 所有匹配的配置均已禁用 AddressSanitizer",
+    ),
+    (
+        "\n✅ Enabled AddressSanitizer in {} configurations of {}",
+        "\n✅ {1} の {0} 個の構成で AddressSanitizer を有効にしました",
+        "\n✅ 已在 {1} 的 {0} 个配置中启用 AddressSanitizer",
+    ),
+    (
+        "\n✅ Disabled AddressSanitizer in {} configurations of {}",
+        "\n✅ {1} の {0} 個の構成で AddressSanitizer を無効にしました",
+        "\n✅ 已在 {1} 的 {0} 个配置中禁用 AddressSanitizer",
+    ),
+    ("  line {}: {}: {}", "  {} 行目: {}: {}", "  第 {} 行：{}：{}"),
+    ("\nProjects affected:", "\n影響を受けるプロジェクト:", "\n受影响的项目："),
+    (
+        "✅ Every path matches the case of the file on disk",
+        "✅ すべてのパスがディスク上のファイルの大文字・小文字と一致しています",
+        "✅ 所有路径的大小写均与磁盘上的文件一致",
+    ),
+    (
+        "\n⚠️  {} paths differ in case from the disk; they only resolve on case-insensitive file systems",
+        "\n⚠️  {} 個のパスの大文字・小文字がディスクと異なります。大文字・小文字を区別しないファイル システムでのみ解決されます",
+        "\n⚠️  {} 个路径的大小写与磁盘不一致；它们只能在不区分大小写的文件系统上解析",
+    ),
+    ("Would undo operation #{}: {}", "操作 #{} を元に戻します: {}", "将撤销操作 #{}：{}"),
+    ("Would redo operation #{}: {}", "操作 #{} をやり直します: {}", "将重做操作 #{}：{}"),
+    ("✅ Nothing to prune in {}", "✅ {} に削除するものはありません", "✅ {} 中没有需要清理的内容"),
+    ("\n🗑️  Operations to delete:", "\n🗑️  削除する操作:", "\n🗑️  要删除的操作："),
+    (
+        "Would undo {} operations to restore operation #{}",
+        "{0} 個の操作を元に戻して操作 #{1} に復元します",
+        "将撤销 {0} 个操作以恢复到操作 #{1}",
+    ),
+    (
+        "Would redo {} operations to restore operation #{}",
+        "{0} 個の操作をやり直して操作 #{1} に復元します",
+        "将重做 {0} 个操作以恢复到操作 #{1}",
+    ),
+    (
+        "⏪ Undid {} operations to restore operation #{}",
+        "⏪ {0} 個の操作を元に戻して操作 #{1} に復元しました",
+        "⏪ 已撤销 {0} 个操作，恢复到操作 #{1}",
+    ),
+    (
+        "⏪ Redid {} operations to restore operation #{}",
+        "⏪ {0} 個の操作をやり直して操作 #{1} に復元しました",
+        "⏪ 已重做 {0} 个操作，恢复到操作 #{1}",
+    ),
+    ("✓ {}: already up to date", "✓ {}: 既に最新です", "✓ {}：已是最新"),
+    (
+        "\n✅ No changes - every project already uses these versions",
+        "\n✅ 変更なし - すべてのプロジェクトが既にこれらのバージョンを使用しています",
+        "\n✅ 无更改 - 所有项目均已使用这些版本",
+    ),
+    (
+        "\n✅ Upgraded {} of {} projects",
+        "\n✅ {1} 個中 {0} 個のプロジェクトをアップグレードしました",
+        "\n✅ 已升级 {1} 个项目中的 {0} 个",
+    ),
+    (
+        "Setting runtime library {} (/{}) in project: {}",
+        "ランタイム ライブラリ {} (/{}) をプロジェクトに設定中: {}",
+        "正在为项目设置运行库 {} (/{})：{}",
+    ),
+    (
+        "✅ No changes - every matching configuration already uses {}",
+        "✅ 変更なし - 一致するすべての構成が既に {} を使用しています",
+        "✅ 无更改 - 所有匹配的配置均已使用 {}",
+    ),
+    ("\n🔧 RuntimeLibrary:", "\n🔧 RuntimeLibrary:", "\n🔧 RuntimeLibrary："),
+    (
+        "  ⚠️  {} is a Debug configuration but {} is a release runtime; debug libraries it links will not match",
+        "  ⚠️  {} はデバッグ構成ですが、{} はリリース ランタイムです。リンクするデバッグ ライブラリと一致しません",
+        "  ⚠️  {} 是 Debug 配置，但 {} 是发布版运行库；它链接的调试库将不匹配",
+    ),
+    (
+        "  ⚠️  {} is a release configuration but {} is a debug runtime, which cannot be redistributed",
+        "  ⚠️  {} はリリース構成ですが、{} は再頒布できないデバッグ ランタイムです",
+        "  ⚠️  {} 是发布配置，但 {} 是不可再分发的调试版运行库",
+    ),
+    (
+        "\n✅ Set the runtime library in {} configurations of {}",
+        "\n✅ {1} の {0} 個の構成でランタイム ライブラリを設定しました",
+        "\n✅ 已在 {1} 的 {0} 个配置中设置运行库",
+    ),
+    (
+        "Setting configuration type {} in project: {}",
+        "構成の種類 {} をプロジェクトに設定中: {}",
+        "正在为项目设置配置类型 {}：{}",
+    ),
+    (
+        "✅ No changes - every configuration is already a {}",
+        "✅ 変更なし - すべての構成が既に {} です",
+        "✅ 无更改 - 所有配置均已是 {}",
+    ),
+    ("{}: defined {}", "{}: {} を定義しました", "{}：已定义 {}"),
+    (
+        "Export the public API with __declspec(dllexport) when {} is defined, and __declspec(dllimport) otherwise",
+        "{} が定義されているときは __declspec(dllexport)、それ以外は __declspec(dllimport) で公開 API をエクスポートしてください",
+        "在定义了 {} 时用 __declspec(dllexport) 导出公共 API，否则使用 __declspec(dllimport)",
+    ),
+    (
+        "Projects linking this one use its import library ($(OutDir)$(TargetName).lib) and need the DLL next to their executable",
+        "このプロジェクトをリンクするプロジェクトはインポート ライブラリ ($(OutDir)$(TargetName).lib) を使用し、実行可能ファイルの隣に DLL が必要です",
+        "链接此项目的项目会使用其导入库 ($(OutDir)$(TargetName).lib)，并需要将 DLL 放在其可执行文件旁边",
+    ),
+    ("{}: removed Link SubSystem", "{}: Link SubSystem を削除しました", "{}：已删除 Link SubSystem"),
+    (
+        "Static libraries are not linked: the Link AdditionalDependencies and AdditionalLibraryDirectories are ignored, so projects using this one must link those libraries",
+        "スタティック ライブラリはリンクされません。Link の AdditionalDependencies と AdditionalLibraryDirectories は無視されるため、このプロジェクトを使用するプロジェクトがそれらのライブラリをリンクする必要があります",
+        "静态库不会被链接：Link 的 AdditionalDependencies 和 AdditionalLibraryDirectories 会被忽略，因此使用此项目的项目必须自行链接这些库",
+    ),
+    (
+        "An application needs an entry point (main or WinMain) matching its Link SubSystem",
+        "アプリケーションには Link SubSystem に合ったエントリ ポイント (main または WinMain) が必要です",
+        "应用程序需要与其 Link SubSystem 相匹配的入口点 (main 或 WinMain)",
+    ),
+    ("removed the {} define", "定義 {} を削除しました", "已删除定义 {}"),
+    (
+        "Code using __declspec(dllexport) or dllimport no longer applies and may need cleaning up",
+        "__declspec(dllexport) や dllimport を使うコードは不要になったため、整理が必要な場合があります",
+        "使用 __declspec(dllexport) 或 dllimport 的代码已不再适用，可能需要清理",
+    ),
+    ("\n⚙️  ConfigurationType:", "\n⚙️  ConfigurationType:", "\n⚙️  ConfigurationType："),
+    ("\n🔧 Adjusted settings:", "\n🔧 調整した設定:", "\n🔧 已调整的设置："),
+    ("\n📋 May need manual attention:", "\n📋 手動での確認が必要な場合があります:", "\n📋 可能需要手动处理："),
+    ("\n✅ {} is now a {}", "\n✅ {} は {} になりました", "\n✅ {} 现在是 {}"),
+    (
+        "Applying optimization preset {} to project: {}",
+        "最適化プリセット {} をプロジェクトに適用中: {}",
+        "正在为项目应用优化预设 {}：{}",
+    ),
+    (
+        "  ⚠️  {} is a Debug configuration; optimized code is hard to step through",
+        "  ⚠️  {} はデバッグ構成です。最適化されたコードはステップ実行しにくくなります",
+        "  ⚠️  {} 是 Debug 配置；优化后的代码难以单步调试",
+    ),
+    (
+        "  ⚠️  {} is a release configuration; it will no longer be optimized",
+        "  ⚠️  {} はリリース構成です。最適化されなくなります",
+        "  ⚠️  {} 是发布配置；它将不再被优化",
+    ),
+    (
+        "✅ No changes - every matching configuration already uses the {} preset",
+        "✅ 変更なし - 一致するすべての構成が既に {} プリセットを使用しています",
+        "✅ 无更改 - 所有匹配的配置均已使用 {} 预设",
+    ),
+    (
+        "\n✅ Applied the {} preset to {} configurations of {}",
+        "\n✅ {2} の {1} 個の構成に {0} プリセットを適用しました",
+        "\n✅ 已将 {0} 预设应用到 {2} 的 {1} 个配置",
+    ),
+    ("⚠️  {}: no configurations match '{}'", "⚠️  {}: '{}' に一致する構成がありません", "⚠️  {}：没有与 '{}' 匹配的配置"),
+    ("⚠️  {}: the project has no configurations", "⚠️  {}: プロジェクトに構成がありません", "⚠️  {}：项目中没有配置"),
+    (
+        "\n✅ No changes - every project already has the requested {}",
+        "\n✅ 変更なし - すべてのプロジェクトに要求された{}が既にあります",
+        "\n✅ 无更改 - 所有项目均已具有所要求的{}",
+    ),
+    (
+        "\n✅ Updated {} in {} of {} projects",
+        "\n✅ {2} 個中 {1} 個のプロジェクトで{0}を更新しました",
+        "\n✅ 已在 {2} 个项目中的 {1} 个中更新{0}",
+    ),
+    ("code analysis settings", "コード分析の設定", "代码分析设置"),
+    ("hardening settings", "セキュリティ強化の設定", "安全强化设置"),
+    (
+        "⚠️  Spectre-mitigated builds link the Spectre-mitigated libraries, which are a separate Visual Studio component (\"MSVC ... Spectre-mitigated libs\")",
+        "⚠️  Spectre 軽減ビルドは Spectre 軽減ライブラリをリンクします。これは Visual Studio の別コンポーネントです (\"MSVC ... Spectre-mitigated libs\")",
+        "⚠️  Spectre 缓解版本会链接 Spectre 缓解库，这是一个单独的 Visual Studio 组件 (\"MSVC ... Spectre-mitigated libs\")",
+    ),
+    ("character set", "文字セット", "字符集"),
+    (
+        "⚠️  {} {}: defines {}, which contradicts CharacterSet {}",
+        "⚠️  {} {}: {} を定義していますが、CharacterSet {} と矛盾します",
+        "⚠️  {} {}：定义了 {}，与 CharacterSet {} 相矛盾",
+    ),
+    ("conformance mode", "準拠モード", "符合模式"),
+    ("build performance settings", "ビルド パフォーマンスの設定", "生成性能设置"),
+    ("⚠️  {} does not exist", "⚠️  {} は存在しません", "⚠️  {} 不存在"),
+    ("  🔁 {}: {} → CustomBuild", "  🔁 {}: {} → CustomBuild", "  🔁 {}：{} → CustomBuild"),
+    ("  📁 New filter: {}", "  📁 新しいフィルター: {}", "  📁 新筛选器：{}"),
+    (
+        "⚠️  {} has no filters file; --filter was ignored",
+        "⚠️  {} にはフィルター ファイルがありません。--filter は無視されました",
+        "⚠️  {} 没有筛选器文件；已忽略 --filter",
+    ),
+    (
+        "✅ No changes - the files already have this build step",
+        "✅ 変更なし - ファイルには既にこのビルド ステップがあります",
+        "✅ 无更改 - 这些文件已具有此生成步骤",
+    ),
+    ("\n✅ {} custom build items in {}", "\n✅ {1} のカスタム ビルド項目: {0} 個", "\n✅ {1} 中的自定义生成项：{0} 个"),
+    (
+        "✅ No changes - the build steps are already set",
+        "✅ 変更なし - ビルド ステップは既に設定されています",
+        "✅ 无更改 - 生成步骤已设置",
+    ),
+    (
+        "\n✅ Updated the build step of {} files in {}",
+        "\n✅ {1} の {0} 個のファイルのビルド ステップを更新しました",
+        "\n✅ 已更新 {1} 中 {0} 个文件的生成步骤",
+    ),
+    ("\n🔧 Build step{}:", "\n🔧 ビルド ステップ{}:", "\n🔧 生成步骤{}："),
+    (" for {}", " ({} 用)", " (用于 {})"),
+    (
+        "✅ No changes - the shaders already have these settings",
+        "✅ 変更なし - シェーダーには既にこれらの設定があります",
+        "✅ 无更改 - 着色器已具有这些设置",
+    ),
+    ("\n🎨 Shader settings{}:", "\n🎨 シェーダー設定{}:", "\n🎨 着色器设置{}："),
+    ("\n✅ Updated {} shaders in {}", "\n✅ {1} の {0} 個のシェーダーを更新しました", "\n✅ 已更新 {1} 中的 {0} 个着色器"),
+    ("✅ No problems found", "✅ 問題は見つかりませんでした", "✅ 未发现问题"),
+    (
+        "\nFound {} problems, none with an automatic fix",
+        "\n{} 個の問題が見つかりました。自動修正できるものはありません",
+        "\n发现 {} 个问题，均无法自动修复",
+    ),
+    (
+        "\nFound {} problems, {} with a suggested fix (run with --fix to apply them)",
+        "\n{} 個の問題が見つかり、{} 個に修正案があります (--fix を付けて実行すると適用されます)",
+        "\n发现 {} 个问题，其中 {} 个有建议的修复 (使用 --fix 运行以应用)",
+    ),
+    (
+        "✨ Dry run completed - {} problems would be fixed",
+        "✨ ドライラン完了 - {} 個の問題が修正されます",
+        "✨ 试运行完成 - 将修复 {} 个问题",
+    ),
+    ("\n✅ Fixed {} problems in {}", "\n✅ {1} の {0} 個の問題を修正しました", "\n✅ 已修复 {1} 中的 {0} 个问题"),
+    (
+        "Adding Update items for {} files covered by wildcards",
+        "ワイルドカードに含まれる {} 個のファイルに Update 項目を追加中",
+        "正在为通配符覆盖的 {} 个文件添加 Update 项",
+    ),
+    ("No files matching '{}' needed changes", "'{}' に一致するファイルに変更は必要ありませんでした", "与 '{}' 匹配的文件无需更改"),
+    ("\n📄 Properties to set{}:", "\n📄 設定するプロパティ{}:", "\n📄 要设置的属性{}："),
+    (
+        "\n✅ Successfully updated {} files in {}",
+        "\n✅ {1} の {0} 個のファイルを更新しました",
+        "\n✅ 已成功更新 {1} 中的 {0} 个文件",
+    ),
+    (
+        "No files matching '{}' have {} set",
+        "'{}' に一致するファイルで {} が設定されているものはありません",
+        "与 '{}' 匹配的文件均未设置 {}",
+    ),
+    ("\n📄 Properties to remove{}:", "\n📄 削除するプロパティ{}:", "\n📄 要删除的属性{}："),
+    (
+        "\n✅ Successfully cleared properties from {} files in {}",
+        "\n✅ {1} の {0} 個のファイルからプロパティを削除しました",
+        "\n✅ 已成功清除 {1} 中 {0} 个文件的属性",
+    ),
+    ("✅ No redundant per-file settings found", "✅ 冗長なファイルごとの設定は見つかりませんでした", "✅ 未发现冗余的单文件设置"),
+    (
+        "\n🔎 Per-file settings identical to the configuration-level value:",
+        "\n🔎 構成レベルの値と同じファイルごとの設定:",
+        "\n🔎 与配置级别值相同的单文件设置：",
+    ),
+    (
+        "\nFound {} redundant settings (run with --fix to remove them)",
+        "\n{} 個の冗長な設定が見つかりました (--fix を付けて実行すると削除されます)",
+        "\n发现 {} 个冗余设置 (使用 --fix 运行以删除)",
+    ),
+    (
+        "✨ Dry run completed - {} settings would be removed",
+        "✨ ドライラン完了 - {} 個の設定が削除されます",
+        "✨ 试运行完成 - 将删除 {} 个设置",
+    ),
+    (
+        "\n✅ Removed {} redundant settings from {}",
+        "\n✅ {1} から {0} 個の冗長な設定を削除しました",
+        "\n✅ 已从 {1} 中删除 {0} 个冗余设置",
+    ),
+    (
+        "Looking for *.{} files in paths NOT matching regex: {}",
+        "正規表現に一致しないパスで *.{} ファイルを検索中: {}",
+        "正在与正则表达式不匹配的路径中查找 *.{} 文件：{}",
+    ),
+    (
+        "Looking for *.{} files in paths matching regex: {}",
+        "正規表現に一致するパスで *.{} ファイルを検索中: {}",
+        "正在与正则表达式匹配的路径中查找 *.{} 文件：{}",
+    ),
+    (
+        "Looking for *.{} files (negation has no effect without regex)",
+        "*.{} ファイルを検索中 (正規表現がないため否定は無効です)",
+        "正在查找 *.{} 文件 (未指定正则表达式，取反无效)",
+    ),
+    (
+        "No *.{} files found in paths matching regex '{}' in {}",
+        "{2} で正規表現 '{1}' に一致するパスに *.{0} ファイルが見つかりません",
+        "在 {2} 中与正则表达式 '{1}' 匹配的路径里未找到 *.{0} 文件",
+    ),
+    ("No *.{} files found in {}", "{1} に *.{0} ファイルが見つかりません", "在 {1} 中未找到 *.{0} 文件"),
+    ("all *.{} files", "すべての *.{} ファイル", "所有 *.{} 文件"),
+    (
+        "No files found matching: {} with regex filter NOT matching: {}",
+        "一致するファイルが見つかりません: {} (正規表現フィルターに一致しないもの: {})",
+        "未找到匹配的文件：{} (正则表达式筛选为不匹配：{})",
+    ),
+    (
+        "No files found matching: {} with regex filter: {}",
+        "一致するファイルが見つかりません: {} (正規表現フィルター: {})",
+        "未找到匹配的文件：{} (正则表达式筛选：{})",
+    ),
+    ("\n📁 Filters to be removed:", "\n📁 削除されるフィルター:", "\n📁 将被删除的筛选器："),
+    (
+        "Would remove {} files from project file: {}",
+        "プロジェクト ファイルから {} 個のファイルを削除します: {}",
+        "将从项目文件中删除 {} 个文件：{}",
+    ),
+    (
+        "Would remove {} filters from filter file: {}",
+        "フィルター ファイルから {} 個のフィルターを削除します: {}",
+        "将从筛选器文件中删除 {} 个筛选器：{}",
+    ),
+    ("  {} {} ← {} files", "  {} {} ← {} 個のファイル", "  {} {} ← {} 个文件"),
+    ("  {} {} → {} files", "  {} {} → {} 個のファイル", "  {} {} → {} 个文件"),
+    (
+        "No directories are fully listed explicitly; nothing to collapse",
+        "すべてのファイルが明示的に列挙されているディレクトリはありません。まとめるものはありません",
+        "没有目录的文件被完整地显式列出；无可合并的内容",
+    ),
+    (
+        "No wildcard items found; the project already lists files explicitly",
+        "ワイルドカード項目が見つかりません。プロジェクトは既にファイルを明示的に列挙しています",
+        "未找到通配符项；项目已显式列出文件",
+    ),
+    (
+        "\n✔ Validated: both listings resolve to the same {} files",
+        "\n✔ 検証済み: どちらの一覧も同じ {} 個のファイルに解決されます",
+        "\n✔ 已验证：两种列表解析出相同的 {} 个文件",
+    ),
+    (
+        "\n✅ Collapsed {} items into wildcards in {}",
+        "\n✅ {1} で {0} 個の項目をワイルドカードにまとめました",
+        "\n✅ 已将 {1} 中的 {0} 个项合并为通配符",
+    ),
+    (
+        "\n✅ Expanded {} wildcard items in {}",
+        "\n✅ {1} で {0} 個のワイルドカード項目を展開しました",
+        "\n✅ 已展开 {1} 中的 {0} 个通配符项",
+    ),
+    (
+        "✨ Nothing to prune: every item exists on disk and the filters file matches the project",
+        "✨ 削除するものはありません: すべての項目がディスク上に存在し、フィルター ファイルはプロジェクトと一致しています",
+        "✨ 无需清理：所有项都存在于磁盘上，且筛选器文件与项目一致",
+    ),
+    ("\n🗑️  Items whose files no longer exist:", "\n🗑️  ファイルが存在しなくなった項目:", "\n🗑️  文件已不存在的项："),
+    (
+        "\n📁 Filters file entries without a project item:",
+        "\n📁 プロジェクト項目のないフィルター ファイルのエントリ:",
+        "\n📁 没有对应项目项的筛选器文件条目：",
+    ),
+    (
+        "✨ Dry run completed - {} items and {} filters file entries would be removed",
+        "✨ ドライラン完了 - {} 個の項目と {} 個のフィルター ファイル エントリが削除されます",
+        "✨ 试运行完成 - 将删除 {} 个项和 {} 个筛选器文件条目",
+    ),
+    (
+        "\n✅ Pruned {} items and {} filters file entries from {}",
+        "\n✅ {2} から {0} 個の項目と {1} 個のフィルター ファイル エントリを削除しました",
+        "\n✅ 已从 {2} 中清理 {0} 个项和 {1} 个筛选器文件条目",
+    ),
+    ("Reading file list: {}", "ファイル一覧を読み込み中: {}", "正在读取文件列表：{}"),
+    ("⚠️  {} does not exist yet; adding it anyway", "⚠️  {} はまだ存在しませんが、追加します", "⚠️  {} 尚不存在；仍将添加"),
+    ("⚠️  {} matches no files", "⚠️  {} に一致するファイルはありません", "⚠️  {} 未匹配任何文件"),
+    ("No files listed in {}", "{} にファイルが記載されていません", "{} 中未列出任何文件"),
+    ("No files found in filter '{}'", "フィルター '{}' にファイルが見つかりません", "在筛选器 '{}' 中未找到文件"),
+    (
+        "Would merge filter '{}' into existing filter '{}'",
+        "フィルター '{}' を既存のフィルター '{}' に統合します",
+        "将把筛选器 '{}' 合并到现有筛选器 '{}'",
+    ),
+    ("Files that would be moved from '{}' filter:", "'{}' フィルターから移動されるファイル:", "将从 '{}' 筛选器移出的文件："),
+    ("Would rename filter '{}' to '{}'", "フィルター '{}' の名前を '{}' に変更します", "将把筛选器 '{}' 重命名为 '{}'"),
+    ("Files that would be moved:", "移動されるファイル:", "将被移动的文件："),
+    ("Files in '{}' filter:", "'{}' フィルター内のファイル:", "'{}' 筛选器中的文件："),
+    ("📁 {} files moved:", "📁 {} 個のファイルを移動しました:", "📁 已移动 {} 个文件："),
+    (
+        "✅ Exported {} filters and {} rules from {} to {}",
+        "✅ {2} から {3} に {0} 個のフィルターと {1} 個のルールをエクスポートしました",
+        "✅ 已从 {2} 导出 {0} 个筛选器和 {1} 条规则到 {3}",
+    ),
+    ("Applying filter layout: {}", "フィルター レイアウトを適用中: {}", "正在应用筛选器布局：{}"),
+    ("✅ The project already matches the layout", "✅ プロジェクトは既にレイアウトと一致しています", "✅ 项目已与布局一致"),
+    ("\n📁 Filters to create:", "\n📁 作成するフィルター:", "\n📁 要创建的筛选器："),
+    ("\n📄 Files to route:", "\n📄 振り分けるファイル:", "\n📄 要归类的文件："),
+    (
+        "\n✅ Created {} filters and routed {} files in {}",
+        "\n✅ {2} で {0} 個のフィルターを作成し、{1} 個のファイルを振り分けました",
+        "\n✅ 已在 {2} 中创建 {0} 个筛选器并归类 {1} 个文件",
+    ),
+    ("✅ The filters are already flat", "✅ フィルターは既にフラットです", "✅ 筛选器已是扁平结构"),
+    ("\n📄 Files to move:", "\n📄 移動するファイル:", "\n📄 要移动的文件："),
+    ("\n📁 Filters to remove:", "\n📁 削除するフィルター:", "\n📁 要删除的筛选器："),
+    (
+        "\n✅ Moved {} files and removed {} filters in {}",
+        "\n✅ {2} で {0} 個のファイルを移動し、{1} 個のフィルターを削除しました",
+        "\n✅ 已在 {2} 中移动 {0} 个文件并删除 {1} 个筛选器",
+    ),
+    ("✅ No filters to put under '{}'", "✅ '{}' の下に移すフィルターはありません", "✅ 没有要放到 '{}' 下的筛选器"),
+    ("\n📁 Filters to move:", "\n📁 移動するフィルター:", "\n📁 要移动的筛选器："),
+    (
+        "✨ Dry run completed - {} filters and {} files would be moved",
+        "✨ ドライラン完了 - {} 個のフィルターと {} 個のファイルが移動されます",
+        "✨ 试运行完成 - 将移动 {} 个筛选器和 {} 个文件",
+    ),
+    (
+        "\n✅ Moved {} filters and {} files under '{}' in {}",
+        "\n✅ {3} で {0} 個のフィルターと {1} 個のファイルを '{2}' の下に移動しました",
+        "\n✅ 已在 {3} 中将 {0} 个筛选器和 {1} 个文件移到 '{2}' 下",
+    ),
+    ("✅ No empty filters in {}", "✅ {} に空のフィルターはありません", "✅ {} 中没有空筛选器"),
+    ("\n📁 Empty filters to remove:", "\n📁 削除する空のフィルター:", "\n📁 要删除的空筛选器："),
+    (
+        "\n✅ Removed {} empty filters from {}",
+        "\n✅ {1} から {0} 個の空のフィルターを削除しました",
+        "\n✅ 已从 {1} 中删除 {0} 个空筛选器",
+    ),
+    ("✅ Filter '{}' already exists in {}", "✅ フィルター '{}' は {} に既に存在します", "✅ 筛选器 '{}' 已存在于 {}"),
+    ("📁 Filters to create:", "📁 作成するフィルター:", "📁 要创建的筛选器："),
+    ("\n✅ Created {} filters in {}", "\n✅ {1} に {0} 個のフィルターを作成しました", "\n✅ 已在 {1} 中创建 {0} 个筛选器"),
+    ("✨ The filters file matches the project", "✨ フィルター ファイルはプロジェクトと一致しています", "✨ 筛选器文件与项目一致"),
+    ("\n📄 Entries added to the filters file:", "\n📄 フィルター ファイルに追加したエントリ:", "\n📄 已添加到筛选器文件的条目："),
+    ("\n🗑️  Entries without a project item:", "\n🗑️  プロジェクト項目のないエントリ:", "\n🗑️  没有对应项目项的条目："),
+    ("\n📁 Filters created:", "\n📁 作成したフィルター:", "\n📁 已创建的筛选器："),
+    (
+        "\n🔑 Filters given a UniqueIdentifier:",
+        "\n🔑 UniqueIdentifier を付与したフィルター:",
+        "\n🔑 已分配 UniqueIdentifier 的筛选器：",
+    ),
+    ("\n✅ Updated {}", "\n✅ {} を更新しました", "\n✅ 已更新 {}"),
+    ("every configuration", "すべての構成", "所有配置"),
+    ("the {} configurations", "{} 構成", "{} 配置"),
+    (
+        "✅ No changes - {} is already in {} of {}",
+        "✅ 変更なし - {0} は{2}の {1} に既に含まれています",
+        "✅ 无更改 - {0} 已在{2}的 {1} 中",
+    ),
+    (
+        "✅ No changes - {} is not in {} of {}",
+        "✅ 変更なし - {0} は{2}の {1} に含まれていません",
+        "✅ 无更改 - {0} 不在{2}的 {1} 中",
+    ),
+    ("\n➕ Adding {} to {}:", "\n➕ {0} を {1} に追加中:", "\n➕ 正在将 {0} 添加到 {1}："),
+    ("\n➖ Removing {} from {}:", "\n➖ {0} を {1} から削除中:", "\n➖ 正在从 {1} 中删除 {0}："),
+    ("\n✅ Updated {} configurations in {}", "\n✅ {1} の {0} 個の構成を更新しました", "\n✅ 已更新 {1} 中的 {0} 个配置"),
+    (
+        "🔗 {}: merged {} ItemGroups into earlier ones ({} items moved)",
+        "🔗 {}: {} 個の ItemGroup を前のグループに統合しました ({} 個の項目を移動)",
+        "🔗 {}：已将 {} 个 ItemGroup 合并到之前的组中 (移动了 {} 个项)",
+    ),
+    ("🗑️  Removed {} empty ItemGroups", "🗑️  空の ItemGroup を {} 個削除しました", "🗑️  已删除 {} 个空 ItemGroup"),
+    ("⚠️  {} is not formatted", "⚠️  {} は整形されていません", "⚠️  {} 未格式化"),
+    (
+        "Run 'vsprojm format -p {}{}' to fix",
+        "修正するには 'vsprojm format -p {}{}' を実行してください",
+        "运行 'vsprojm format -p {}{}' 进行修复",
+    ),
+    (
+        "✅ No changes - ItemGroups are already merged",
+        "✅ 変更なし - ItemGroup は既に統合されています",
+        "✅ 无更改 - ItemGroup 已合并",
+    ),
+    (
+        "✅ No changes - the files are already in canonical layout",
+        "✅ 変更なし - ファイルは既に正規のレイアウトです",
+        "✅ 无更改 - 文件已是规范布局",
+    ),
+    (
+        "\n✅ Formatted {} files; item metadata and conditions are unchanged",
+        "\n✅ {} 個のファイルを整形しました。項目のメタデータと条件は変わっていません",
+        "\n✅ 已格式化 {} 个文件；项元数据和条件均未改变",
+    ),
+    ("⚠️  {} cannot be repaired automatically: {}", "⚠️  {} は自動で修復できません: {}", "⚠️  {} 无法自动修复：{}"),
+    (
+        "  ⚠️  Still not well-formed after repair: {}",
+        "  ⚠️  修復後も整形式ではありません: {}",
+        "  ⚠️  修复后仍不是格式正确的 XML：{}",
+    ),
+    (
+        "\n✅ Repaired {} files - review the result (or `undo`) before building",
+        "\n✅ {} 個のファイルを修復しました - ビルドする前に結果を確認してください (または `undo`)",
+        "\n✅ 已修复 {} 个文件 - 生成前请检查结果 (或执行 `undo`)",
+    ),
+    ("Sorting project: {}", "プロジェクトを並べ替え中: {}", "正在排序项目：{}"),
+    (
+        "⚠️  {} is not sorted ({} ItemGroups out of order)",
+        "⚠️  {} は並べ替えられていません ({} 個の ItemGroup の順序が違います)",
+        "⚠️  {} 未排序 ({} 个 ItemGroup 顺序不对)",
+    ),
+    (
+        "Run 'vsprojm sort -p {}' to fix",
+        "修正するには 'vsprojm sort -p {}' を実行してください",
+        "运行 'vsprojm sort -p {}' 进行修复",
+    ),
+    (
+        "✅ No changes - every ItemGroup is already sorted",
+        "✅ 変更なし - すべての ItemGroup は既に並べ替えられています",
+        "✅ 无更改 - 所有 ItemGroup 均已排序",
+    ),
+    (
+        "  🔤 {}: sorted {} ItemGroups",
+        "  🔤 {}: {} 個の ItemGroup を並べ替えました",
+        "  🔤 {}：已排序 {} 个 ItemGroup",
+    ),
+    (
+        "\n✅ Sorted {} files; item metadata and conditions are unchanged",
+        "\n✅ {} 個のファイルを並べ替えました。項目のメタデータと条件は変わっていません",
+        "\n✅ 已排序 {} 个文件；项元数据和条件均未改变",
+    ),
+    ("🔑 ProjectGuid: {} → {}", "🔑 ProjectGuid: {} → {}", "🔑 ProjectGuid：{} → {}"),
+    (
+        "🔑 New UniqueIdentifier for {} filters",
+        "🔑 {} 個のフィルターに新しい UniqueIdentifier",
+        "🔑 为 {} 个筛选器生成新的 UniqueIdentifier",
+    ),
+    ("📝 Updated the entry of {} in {}", "📝 {1} 内の {0} のエントリを更新しました", "📝 已更新 {1} 中 {0} 的条目"),
+    ("⚠️  {} is not in {}", "⚠️  {} は {} にありません", "⚠️  {} 不在 {} 中"),
+    ("🔗 {}: ProjectReference {}", "🔗 {}: ProjectReference {}", "🔗 {}：ProjectReference {}"),
+    (
+        "\n✅ {} now has ProjectGuid {}",
+        "\n✅ {} の ProjectGuid は {} になりました",
+        "\n✅ {} 的 ProjectGuid 现在是 {}",
+    ),
+    ("🔑 Project GUIDs of {}:", "🔑 {} のプロジェクト GUID:", "🔑 {} 的项目 GUID："),
+    ("     ⚠️  shared with another project", "     ⚠️  別のプロジェクトと共有されています", "     ⚠️  与另一个项目共用"),
+    (
+        "     ⚠️  the solution lists it as {}",
+        "     ⚠️  ソリューションには {} として記載されています",
+        "     ⚠️  解决方案中记录为 {}",
+    ),
+    (
+        "     ⚠️  the project file has no ProjectGuid or is missing",
+        "     ⚠️  プロジェクト ファイルに ProjectGuid がないか、ファイルがありません",
+        "     ⚠️  项目文件没有 ProjectGuid 或文件缺失",
+    ),
+    (
+        "     ⚠️  {} filters reuse the UniqueIdentifier of another filter",
+        "     ⚠️  {} 個のフィルターが別のフィルターの UniqueIdentifier を再利用しています",
+        "     ⚠️  {} 个筛选器重用了另一个筛选器的 UniqueIdentifier",
+    ),
+    ("\n✅ Every project has its own GUID", "\n✅ すべてのプロジェクトに固有の GUID があります", "\n✅ 每个项目都有自己的 GUID"),
+    (
+        "\n⚠️  {} problems - `guid dedupe` fixes them",
+        "\n⚠️  {} 個の問題 - `guid dedupe` で修正できます",
+        "\n⚠️  {} 个问题 - `guid dedupe` 可修复",
+    ),
+    ("Analyzing solution: {}", "ソリューションを解析中: {}", "正在分析解决方案：{}"),
+    ("⚠️  Skipping {}: file not found", "⚠️  {} をスキップします: ファイルが見つかりません", "⚠️  跳过 {}：找不到文件"),
+    (
+        "⚠️  Skipping {}: no ProjectGuid",
+        "⚠️  {} をスキップします: ProjectGuid がありません",
+        "⚠️  跳过 {}：没有 ProjectGuid",
+    ),
+    ("  🔗 {}: ProjectReference {}", "  🔗 {}: ProjectReference {}", "  🔗 {}：ProjectReference {}"),
+    ("📝 Solution entry {}: {} → {}", "📝 ソリューションのエントリ {}: {} → {}", "📝 解决方案条目 {}：{} → {}"),
+    (
+        "🔑 {}: new UniqueIdentifier for filters {}",
+        "🔑 {}: フィルター {} に新しい UniqueIdentifier",
+        "🔑 {}：为筛选器 {} 生成新的 UniqueIdentifier",
+    ),
+    (
+        "✅ No changes - every project has its own GUID",
+        "✅ 変更なし - すべてのプロジェクトに固有の GUID があります",
+        "✅ 无更改 - 每个项目都有自己的 GUID",
+    ),
+    (
+        "\n✅ Updated {} projects and {} filters files{}",
+        "\n✅ {} 個のプロジェクトと {} 個のフィルター ファイルを更新しました{}",
+        "\n✅ 已更新 {} 个项目和 {} 个筛选器文件{}",
+    ),
+    (", and the solution", "、およびソリューション", "，以及解决方案"),
+    ("✅ No changes - {} already imports {}", "✅ 変更なし - {} は既に {} をインポートしています", "✅ 无更改 - {} 已导入 {}"),
+    ("🔗 Importing {} ({} files)", "🔗 {} をインポート中 ({} 個のファイル)", "🔗 正在导入 {} ({} 个文件)"),
+    ("\n✅ {} now builds the files of {}", "\n✅ {} は {} のファイルをビルドするようになりました", "\n✅ {} 现在会生成 {} 的文件"),
+    ("No imports in {}", "{} にインポートはありません", "{} 中没有导入"),
+    ("📦 Imports of {}, in evaluation order:", "📦 {} のインポート (評価順):", "📦 {} 的导入 (按求值顺序)："),
+    ("if it exists", "存在する場合", "如果存在"),
+    ("if {}", "{} の場合", "当 {} 时"),
+    (
+        "⚠️  The Microsoft.Cpp imports are out of order; they must come as {}",
+        "⚠️  Microsoft.Cpp のインポートの順序が正しくありません。{} の順である必要があります",
+        "⚠️  Microsoft.Cpp 的导入顺序不正确；必须按 {} 的顺序",
+    ),
+    ("✅ No changes - {} is already imported", "✅ 変更なし - {} は既にインポートされています", "✅ 无更改 - {} 已被导入"),
+    ("📦 Import {} at line {}", "📦 {} を {} 行目にインポート", "📦 在第 {1} 行导入 {0}"),
+    ("  in ImportGroup {}", "  ImportGroup {} 内", "  位于 ImportGroup {} 中"),
+    (
+        "⚠️  {} comes after Microsoft.Cpp.targets, too late for its properties to affect the build",
+        "⚠️  {} は Microsoft.Cpp.targets の後にあるため、そのプロパティはビルドに反映されません",
+        "⚠️  {} 位于 Microsoft.Cpp.targets 之后，太晚了，其属性无法影响生成",
+    ),
+    (
+        "⚠️  {} comes before Microsoft.Cpp.props, so the C++ targets may override what it defines",
+        "⚠️  {} は Microsoft.Cpp.props の前にあるため、C++ ターゲットがその定義を上書きする可能性があります",
+        "⚠️  {} 位于 Microsoft.Cpp.props 之前，C++ 目标可能会覆盖它定义的内容",
+    ),
+    ("\n✅ Updated project file: {}", "\n✅ プロジェクト ファイルを更新しました: {}", "\n✅ 已更新项目文件：{}"),
+    ("⚠️  {} does not import {}", "⚠️  {} は {} をインポートしていません", "⚠️  {} 没有导入 {}"),
+    ("🗑️  Remove import {}", "🗑️  インポート {} を削除", "🗑️  删除导入 {}"),
+    ("\n✅ Removed {} imports from {}", "\n✅ {1} から {0} 個のインポートを削除しました", "\n✅ 已从 {1} 中删除 {0} 个导入"),
+    ("Looking for {} files", "{} ファイルを検索中", "正在查找 {} 文件"),
+    ("📁 Include directory: {}", "📁 インクルード ディレクトリ: {}", "📁 包含目录：{}"),
+    (
+        "\n✅ Project generated with {} files!",
+        "\n✅ {} 個のファイルでプロジェクトを生成しました!",
+        "\n✅ 已生成包含 {} 个文件的项目！",
+    ),
+    ("No NuGet packages in {}", "{} に NuGet パッケージはありません", "{} 中没有 NuGet 包"),
+    ("📦 PackageReference:", "📦 PackageReference:", "📦 PackageReference："),
+    ("📦 packages.config:", "📦 packages.config:", "📦 packages.config："),
+    ("  - {} {} ⚠️  not imported", "  - {} {} ⚠️  インポートされていません", "  - {} {} ⚠️  未导入"),
+    ("      imports {}", "      {} をインポート", "      导入 {}"),
+    (
+        "Adding NuGet package {} {} to project: {}",
+        "NuGet パッケージ {} {} をプロジェクトに追加中: {}",
+        "正在向项目添加 NuGet 包 {} {}：{}",
+    ),
+    ("add PackageReference {} {}", "PackageReference {} {} を追加", "添加 PackageReference {} {}"),
+    (
+        "add packages.config entry {} {}",
+        "packages.config のエントリ {} {} を追加",
+        "添加 packages.config 条目 {} {}",
+    ),
+    ("remove import {}", "インポート {} を削除", "删除导入 {}"),
+    (
+        "⚠️  {} is not restored yet, so only its .targets is imported; run `nuget restore` and add it again if it also has a .props",
+        "⚠️  {} はまだ復元されていないため、.targets のみをインポートします。.props もある場合は `nuget restore` を実行してから再度追加してください",
+        "⚠️  {} 尚未还原，因此只导入了其 .targets；如果它还有 .props，请运行 `nuget restore` 后重新添加",
+    ),
+    ("add import {}", "インポート {} を追加", "添加导入 {}"),
+    (
+        "✅ No changes - {} {} is already referenced",
+        "✅ 変更なし - {} {} は既に参照されています",
+        "✅ 无更改 - 已引用 {} {}",
+    ),
+    ("Would {}", "実行予定: {}", "将执行：{}"),
+    ("✅ Added NuGet package {} {}:", "✅ NuGet パッケージ {} {} を追加しました:", "✅ 已添加 NuGet 包 {} {}："),
+    (
+        "Removing NuGet package {} from project: {}",
+        "NuGet パッケージ {} をプロジェクトから削除中: {}",
+        "正在从项目中删除 NuGet 包 {}：{}",
+    ),
+    ("remove PackageReference {}", "PackageReference {} を削除", "删除 PackageReference {}"),
+    ("remove packages.config entry {}", "packages.config のエントリ {} を削除", "删除 packages.config 条目 {}"),
+    ("⚠️  {} is not referenced by {}", "⚠️  {} は {} から参照されていません", "⚠️  {} 未被 {} 引用"),
+    ("✅ Removed NuGet package {}:", "✅ NuGet パッケージ {} を削除しました:", "✅ 已删除 NuGet 包 {}："),
+    ("Applying patch: {}", "パッチを適用中: {}", "正在应用补丁：{}"),
+    (
+        "  ⚠️  {} is not in the project, skipping",
+        "  ⚠️  {} はプロジェクトにないため、スキップします",
+        "  ⚠️  {} 不在项目中，跳过",
+    ),
+    (
+        "  ⚠️  {} is already in the project, skipping",
+        "  ⚠️  {} は既にプロジェクトにあるため、スキップします",
+        "  ⚠️  {} 已在项目中，跳过",
+    ),
+    (
+        "  ⚠️  {} is already in every configuration, skipping",
+        "  ⚠️  {} は既にすべての構成にあるため、スキップします",
+        "  ⚠️  {} 已在所有配置中，跳过",
+    ),
+    (
+        "Nothing to apply - the project already has everything patch '{}' declares",
+        "適用するものはありません - プロジェクトには既にパッチ '{}' の内容がすべてあります",
+        "无需应用 - 项目已包含补丁 '{}' 声明的全部内容",
+    ),
+    ("\n📦 Patch changes:", "\n📦 パッチの変更:", "\n📦 补丁更改："),
+    ("Would record patch state: {}", "パッチの状態を記録します: {}", "将记录补丁状态：{}"),
+    ("\n✅ Applied patch '{}' to {}", "\n✅ パッチ '{}' を {} に適用しました", "\n✅ 已将补丁 '{}' 应用到 {}"),
+    ("Reverting patch: {}", "パッチを元に戻し中: {}", "正在还原补丁：{}"),
+    (
+        "  ⚠️  {} {} was changed after the patch was applied, leaving it",
+        "  ⚠️  {} {} はパッチ適用後に変更されたため、そのままにします",
+        "  ⚠️  {} {} 在应用补丁后被修改过，保留不动",
+    ),
+    (
+        "  ⚠️  The ItemGroup added by the patch is gone, skipping added files",
+        "  ⚠️  パッチが追加した ItemGroup がなくなっているため、追加したファイルはスキップします",
+        "  ⚠️  补丁添加的 ItemGroup 已不存在，跳过已添加的文件",
+    ),
+    (
+        "  ⚠️  {} is no longer in {}, skipping",
+        "  ⚠️  {} は {} にもうないため、スキップします",
+        "  ⚠️  {} 已不在 {} 中，跳过",
+    ),
+    (
+        "  ⚠️  {} was added back after the patch was applied, skipping",
+        "  ⚠️  {} はパッチ適用後に再び追加されたため、スキップします",
+        "  ⚠️  {} 在应用补丁后又被加回，跳过",
+    ),
+    ("\n📦 Reverting:", "\n📦 元に戻す内容:", "\n📦 正在还原："),
+    ("Would remove patch state: {}", "パッチの状態を削除します: {}", "将删除补丁状态：{}"),
+    ("\n✅ Reverted patch '{}' from {}", "\n✅ パッチ '{}' を {} から元に戻しました", "\n✅ 已从 {1} 还原补丁 '{0}'"),
+    ("  {} include dir: {}", "  {} インクルード ディレクトリ: {}", "  {} 包含目录：{}"),
+    ("  {} library dir: {}", "  {} ライブラリ ディレクトリ: {}", "  {} 库目录：{}"),
+    ("  {} library: {}", "  {} ライブラリ: {}", "  {} 库：{}"),
+    ("  ~ {}: {} = {} (was {})", "  ~ {}: {} = {} (変更前 {})", "  ~ {}：{} = {} (原为 {})"),
+    ("Solution directory: {}", "ソリューション ディレクトリ: {}", "解决方案目录：{}"),
+    (
+        "⚠️  Cannot make {} relative to {} (another drive, or not a path on this system)",
+        "⚠️  {} を {} からの相対パスにできません (別のドライブか、このシステムのパスではありません)",
+        "⚠️  无法将 {} 转换为相对于 {} 的路径 (位于其他驱动器，或不是此系统上的路径)",
+    ),
+    (
+        "✅ No changes - no absolute paths to rewrite",
+        "✅ 変更なし - 書き換える絶対パスはありません",
+        "✅ 无更改 - 没有需要改写的绝对路径",
+    ),
+    ("\n📂 Paths rewritten:", "\n📂 書き換えたパス:", "\n📂 已改写的路径："),
+    (
+        "\n✅ Rewrote {} absolute paths in {}",
+        "\n✅ {1} の {0} 個の絶対パスを書き換えました",
+        "\n✅ 已改写 {1} 中的 {0} 个绝对路径",
+    ),
+    ("No property sheets imported by {}", "{} がインポートしているプロパティ シートはありません", "{} 没有导入任何属性表"),
+    ("  - {} (user settings)", "  - {} (ユーザー設定)", "  - {} (用户设置)"),
+    (
+        "Importing property sheet '{}' in project: {}",
+        "プロパティ シート '{}' をプロジェクトにインポート中: {}",
+        "正在向项目导入属性表 '{}'：{}",
+    ),
+    (
+        "⚠️  {} does not exist (paths are relative to the project)",
+        "⚠️  {} は存在しません (パスはプロジェクトからの相対パスです)",
+        "⚠️  {} 不存在 (路径相对于项目)",
+    ),
+    (
+        "✅ No changes - every matching configuration already imports {}",
+        "✅ 変更なし - 一致するすべての構成が既に {} をインポートしています",
+        "✅ 无更改 - 所有匹配的配置均已导入 {}",
+    ),
+    ("Would import in: {}", "インポート先: {}", "将导入到：{}"),
+    (
+        "✅ Imported {} in {} property sheet groups:",
+        "✅ {} を {} 個のプロパティ シート グループにインポートしました:",
+        "✅ 已将 {} 导入到 {} 个属性表组：",
+    ),
+    (
+        "Removing property sheet '{}' from project: {}",
+        "プロパティ シート '{}' をプロジェクトから削除中: {}",
+        "正在从项目中删除属性表 '{}'：{}",
+    ),
+    (
+        "⚠️  {} is not imported by any matching configuration",
+        "⚠️  {} は一致するどの構成でもインポートされていません",
+        "⚠️  没有任何匹配的配置导入 {}",
+    ),
+    ("Would remove from: {}", "削除元: {}", "将从以下位置删除：{}"),
+    (
+        "✅ Removed {} from {} property sheet groups:",
+        "✅ {} を {} 個のプロパティ シート グループから削除しました:",
+        "✅ 已从 {1} 个属性表组中删除 {0}：",
+    ),
+    (
+        "Extracting {} settings into property sheet: {}",
+        "{} 個の設定をプロパティ シートに抽出中: {}",
+        "正在将 {} 个设置提取到属性表：{}",
+    ),
+    ("Would create {} with:", "{} を次の内容で作成します:", "将创建 {}，内容为："),
+    ("Would import it in {} configurations instead", "代わりに {} 個の構成でインポートします", "改为在 {} 个配置中导入它"),
+    ("✅ Created {} with {} settings:", "✅ {} を {} 個の設定で作成しました:", "✅ 已创建 {}，包含 {} 个设置："),
+    (
+        "Imported it in {} property sheet groups and removed the settings from {} configurations",
+        "{} 個のプロパティ シート グループでインポートし、{} 個の構成から設定を削除しました",
+        "已在 {} 个属性表组中导入，并从 {} 个配置中删除了这些设置",
+    ),
+    ("Looking for settings shared by {} projects", "{} 個のプロジェクトに共通する設定を検索中", "正在查找 {} 个项目共有的设置"),
+    (
+        "✅ No changes - the projects share no include dirs, defines, lib dirs or libs",
+        "✅ 変更なし - プロジェクトに共通するインクルード ディレクトリ、定義、ライブラリ ディレクトリ、ライブラリはありません",
+        "✅ 无更改 - 这些项目没有共同的包含目录、定义、库目录或库",
+    ),
+    ("🔗 {} {}.{}: {} ({} projects)", "🔗 {} {}.{}: {} ({} 個のプロジェクト)", "🔗 {} {}.{}：{} ({} 个项目)"),
+    (
+        "\n✅ Created {} and imported it in {} projects",
+        "\n✅ {} を作成し、{} 個のプロジェクトでインポートしました",
+        "\n✅ 已创建 {} 并在 {} 个项目中导入",
+    ),
+    (
+        "⚠️  {} references {}, which does not exist",
+        "⚠️  {} は存在しない {} を参照しています",
+        "⚠️  {} 引用了不存在的 {}",
+    ),
+    ("  ✔️  {} is already in the project", "  ✔️  {} は既にプロジェクトにあります", "  ✔️  {} 已在项目中"),
+    (
+        "✅ No changes - the resources are already in the project",
+        "✅ 変更なし - リソースは既にプロジェクトにあります",
+        "✅ 无更改 - 资源已在项目中",
+    ),
+    (
+        "\n✅ Added {} resource files to {}",
+        "\n✅ {1} に {0} 個のリソース ファイルを追加しました",
+        "\n✅ 已向 {1} 添加 {0} 个资源文件",
+    ),
+    (
+        "Adding include directory '{}' to project: {}",
+        "インクルード ディレクトリ '{}' をプロジェクトに追加中: {}",
+        "正在向项目添加包含目录 '{}'：{}",
+    ),
+    ("include directory", "インクルード ディレクトリ", "包含目录"),
+    (
+        "Would add the include directory to {} configurations:",
+        "{} 個の構成にインクルード ディレクトリを追加します:",
+        "将向 {} 个配置添加该包含目录：",
+    ),
+    (
+        "✅ No changes - every configuration already has this include directory",
+        "✅ 変更なし - すべての構成に既にこのインクルード ディレクトリがあります",
+        "✅ 无更改 - 所有配置均已包含此包含目录",
+    ),
+    (
+        "✅ Successfully added include directory to {} configurations:",
+        "✅ {} 個の構成にインクルード ディレクトリを追加しました:",
+        "✅ 已成功向 {} 个配置添加包含目录：",
+    ),
+    (
+        "Adding library directory '{}' to project: {}",
+        "ライブラリ ディレクトリ '{}' をプロジェクトに追加中: {}",
+        "正在向项目添加库目录 '{}'：{}",
+    ),
+    (
+        "Would add the library directory to {} configurations:",
+        "{} 個の構成にライブラリ ディレクトリを追加します:",
+        "将向 {} 个配置添加该库目录：",
+    ),
+    (
+        "✅ No changes - every configuration already has this library directory",
+        "✅ 変更なし - すべての構成に既にこのライブラリ ディレクトリがあります",
+        "✅ 无更改 - 所有配置均已包含此库目录",
+    ),
+    (
+        "✅ Successfully added library directory to {} configurations:",
+        "✅ {} 個の構成にライブラリ ディレクトリを追加しました:",
+        "✅ 已成功向 {} 个配置添加库目录：",
+    ),
+    (
+        "Adding preprocessor definition '{}' to project: {}",
+        "プリプロセッサ定義 '{}' をプロジェクトに追加中: {}",
+        "正在向项目添加预处理器定义 '{}'：{}",
+    ),
+    ("definition", "定義", "定义"),
+    ("Would add the definition to {} configurations:", "{} 個の構成に定義を追加します:", "将向 {} 个配置添加该定义："),
+    (
+        "✅ No changes - every configuration already has this definition",
+        "✅ 変更なし - すべての構成に既にこの定義があります",
+        "✅ 无更改 - 所有配置均已包含此定义",
+    ),
+    (
+        "✅ Successfully added definition to {} configurations:",
+        "✅ {} 個の構成に定義を追加しました:",
+        "✅ 已成功向 {} 个配置添加定义：",
+    ),
+    (
+        "🛠️  Makefile project - using {}",
+        "🛠️  Makefile プロジェクト - {} を使用します",
+        "🛠️  Makefile 项目 - 使用 {}",
+    ),
+    ("Would add the {} to {} configurations:", "{1} 個の構成に{0}を追加します:", "将向 {1} 个配置添加该{0}："),
+    (
+        "✅ No changes - every configuration already has this {}",
+        "✅ 変更なし - すべての構成に既にこの{}があります",
+        "✅ 无更改 - 所有配置均已包含此{}",
+    ),
+    (
+        "✅ Successfully added {} to {} configurations:",
+        "✅ {1} 個の構成に{0}を追加しました:",
+        "✅ 已成功向 {1} 个配置添加{0}：",
+    ),
+    (
+        "✅ No changes - the NMake settings are already set",
+        "✅ 変更なし - NMake の設定は既に設定されています",
+        "✅ 无更改 - NMake 设置已设置",
+    ),
+    (
+        "\n✅ Updated the NMake settings of {} configurations",
+        "\n✅ {} 個の構成の NMake 設定を更新しました",
+        "\n✅ 已更新 {} 个配置的 NMake 设置",
+    ),
+    (
+        "Adding library dependency '{}' to project: {}",
+        "ライブラリの依存関係 '{}' をプロジェクトに追加中: {}",
+        "正在向项目添加库依赖项 '{}'：{}",
+    ),
+    (
+        "Adding library dependency '{}' (Debug) / '{}' (Release) to project: {}",
+        "ライブラリの依存関係 '{}' (Debug) / '{}' (Release) をプロジェクトに追加中: {}",
+        "正在向项目添加库依赖项 '{}' (Debug) / '{}' (Release)：{}",
+    ),
+    (
+        "Would add the library dependency to {} configurations:",
+        "{} 個の構成にライブラリの依存関係を追加します:",
+        "将向 {} 个配置添加该库依赖项：",
+    ),
+    (
+        "✅ No changes - every matching configuration already has this library dependency",
+        "✅ 変更なし - 一致するすべての構成に既にこのライブラリの依存関係があります",
+        "✅ 无更改 - 所有匹配的配置均已包含此库依赖项",
+    ),
+    (
+        "✅ Successfully added library dependency to {} configurations:",
+        "✅ {} 個の構成にライブラリの依存関係を追加しました:",
+        "✅ 已成功向 {} 个配置添加库依赖项：",
+    ),
+    (
+        "✅ No changes - {} already has the settings of {}",
+        "✅ 変更なし - {} には既に {} の設定があります",
+        "✅ 无更改 - {} 已具有 {} 的设置",
+    ),
+    ("📋 Copying settings from {} to {}:", "📋 {} から {} に設定をコピー中:", "📋 正在将设置从 {} 复制到 {}："),
+    ("  ⚠️  {}/{} mentions {}: {}", "  ⚠️  {}/{} が {} に言及しています: {}", "  ⚠️  {}/{} 提到了 {}：{}"),
+    (
+        "\n✅ Copied {} tool sections to {}",
+        "\n✅ {} 個のツール セクションを {} にコピーしました",
+        "\n✅ 已将 {} 个工具节复制到 {}",
+    ),
+    (
+        "⚠️  {} is excluded from every configuration; run `include` without --config to build it again",
+        "⚠️  {} はすべての構成で除外されています。再びビルドするには --config なしで `include` を実行してください",
+        "⚠️  {} 已从所有配置中排除；要重新生成它，请不带 --config 运行 `include`",
+    ),
+    (
+        "✅ No changes - no file matching '{}' needed changes",
+        "✅ 変更なし - '{}' に一致するファイルに変更は必要ありませんでした",
+        "✅ 无更改 - 与 '{}' 匹配的文件无需更改",
+    ),
+    ("\n✅ Updated {} files in {}", "\n✅ {1} の {0} 個のファイルを更新しました", "\n✅ 已更新 {1} 中的 {0} 个文件"),
+    (
+        "ℹ️  {} is already in the solution as '{}' ({})",
+        "ℹ️  {} は既に '{}' ({}) としてソリューションにあります",
+        "ℹ️  {} 已作为 '{}' ({}) 在解决方案中",
+    ),
+    ("➕ Adding '{}' {} ({})", "➕ '{}' {} ({}) を追加中", "➕ 正在添加 '{}' {} ({})"),
+    ("  📁 In solution folder: {}", "  📁 ソリューション フォルダー: {}", "  📁 位于解决方案文件夹：{}"),
+    ("Would update solution file: {}", "ソリューション ファイルを更新します: {}", "将更新解决方案文件：{}"),
+    ("\n✅ Added {} to {}", "\n✅ {} を {} に追加しました", "\n✅ 已将 {} 添加到 {}"),
+    ("➖ Removing '{}' {} ({})", "➖ '{}' {} ({}) を削除中", "➖ 正在删除 '{}' {} ({})"),
+    ("\n✅ Removed {} from {}", "\n✅ {} を {} から削除しました", "\n✅ 已从 {1} 中删除 {0}"),
+    (
+        "⚠️  {} does not exist; matching its path anyway",
+        "⚠️  {} は存在しませんが、パスで照合します",
+        "⚠️  {} 不存在；仍按其路径匹配",
+    ),
+    ("⚠️  Project not found: {}", "⚠️  プロジェクトが見つかりません: {}", "⚠️  找不到项目：{}"),
+    ("  📄 {} (from {})", "  📄 {} ({} から)", "  📄 {} (来自 {})"),
+    ("     type: {}", "     種類: {}", "     类型：{}"),
+    ("     filter: {}", "     フィルター: {}", "     筛选器：{}"),
+    ("     excluded from build: {}", "     ビルドから除外: {}", "     从生成中排除：{}"),
+    ("\nNo project contains {}", "\n{} を含むプロジェクトはありません", "\n没有项目包含 {}"),
+    ("\n⚡︎ {} in {} projects", "\n⚡︎ {} は {} 個のプロジェクトにあります", "\n⚡︎ {} 位于 {} 个项目中"),
+    (
+        "⚠️  Compiled by {} projects; linking them together gives duplicate symbols",
+        "⚠️  {} 個のプロジェクトでコンパイルされます。それらを一緒にリンクするとシンボルが重複します",
+        "⚠️  由 {} 个项目编译；将它们链接在一起会导致符号重复",
+    ),
+    ("\n📋 Other files named {}:", "\n📋 {} という名前の他のファイル:", "\n📋 其他名为 {} 的文件："),
+    ("⚠️  Referenced project not found: {}", "⚠️  参照先のプロジェクトが見つかりません: {}", "⚠️  找不到引用的项目：{}"),
+    ("➖ {} no longer builds after {}", "➖ {} は {} の後にビルドされなくなります", "➖ {} 不再在 {} 之后生成"),
+    (
+        "ℹ️  {} has no solution dependency on {}",
+        "ℹ️  {} には {} へのソリューションの依存関係がありません",
+        "ℹ️  {} 在解决方案中不依赖于 {}",
+    ),
+    ("➕ {} builds after {}", "➕ {} は {} の後にビルドされます", "➕ {} 在 {} 之后生成"),
+    ("ℹ️  {} already depends on {}", "ℹ️  {} は既に {} に依存しています", "ℹ️  {} 已依赖于 {}"),
+    (
+        "\n✅ Updated the dependencies of {} in {}",
+        "\n✅ {1} 内の {0} の依存関係を更新しました",
+        "\n✅ 已更新 {1} 中 {0} 的依赖项",
+    ),
+    (
+        "Include directories, defines and other compiler settings of {} were not copied to {}",
+        "{} のインクルード ディレクトリ、定義、その他のコンパイラ設定は {} にコピーされていません",
+        "{} 的包含目录、定义和其他编译器设置未复制到 {}",
+    ),
+    (
+        "Add {} to the solutions that build {}",
+        "{1} をビルドするソリューションに {0} を追加してください",
+        "请将 {0} 添加到生成 {1} 的解决方案中",
+    ),
+    (
+        "{} uses a precompiled header; the moved sources may include it",
+        "{} はプリコンパイル済みヘッダーを使用しています。移動したソースがそれをインクルードしている可能性があります",
+        "{} 使用预编译头；移动的源文件可能包含它",
+    ),
+    (
+        "Splitting '{}' into static library '{}' {{{}}}",
+        "'{}' をスタティック ライブラリ '{}' {{{}}} に分割中",
+        "正在将 '{}' 拆分为静态库 '{}' {{{}}}",
+    ),
+    ("\n📦 Files moved to {}:", "\n📦 {} に移動したファイル:", "\n📦 移动到 {} 的文件："),
+    ("\n🔗 {} references {}", "\n🔗 {} は {} を参照します", "\n🔗 {} 引用 {}"),
+    ("\n✅ Moved {} files into {}", "\n✅ {} 個のファイルを {} に移動しました", "\n✅ 已将 {} 个文件移动到 {}"),
+    (
+        "✅ No changes - CUDA {} is already enabled",
+        "✅ 変更なし - CUDA {} は既に有効です",
+        "✅ 无更改 - CUDA {} 已启用",
+    ),
+    (
+        "💡 No CudaCompile items yet; add .cu files with `add -e cu`",
+        "💡 CudaCompile 項目はまだありません。`add -e cu` で .cu ファイルを追加してください",
+        "💡 尚无 CudaCompile 项；请使用 `add -e cu` 添加 .cu 文件",
+    ),
+    ("\n✅ {} builds with CUDA {}", "\n✅ {} は CUDA {} でビルドします", "\n✅ {} 使用 CUDA {} 生成"),
+    ("set Keyword to {}", "Keyword を {} に設定", "将 Keyword 设置为 {}"),
+    (
+        "✅ No changes - Qt is already set up with {}",
+        "✅ 変更なし - Qt は既に {} で設定されています",
+        "✅ 无更改 - Qt 已使用 {} 设置",
+    ),
+    ("\n✅ {} uses Qt modules {}", "\n✅ {} は Qt モジュール {} を使用します", "\n✅ {} 使用 Qt 模块 {}"),
+    ("✅ No changes - {} already exists", "✅ 変更なし - {} は既に存在します", "✅ 无更改 - {} 已存在"),
+    ("🔍 DRY RUN - Would create {}:", "🔍 ドライラン - {} を作成します:", "🔍 试运行 - 将创建 {}："),
+    ("✅ Created {}", "✅ {} を作成しました", "✅ 已创建 {}"),
+    (
+        "{} has no user file ({}); `user init` creates one",
+        "{} にはユーザー ファイル ({}) がありません。`user init` で作成できます",
+        "{} 没有用户文件 ({})；`user init` 可创建一个",
+    ),
+    ("No settings in {}", "{} に設定はありません", "{} 中没有设置"),
+    (
+        "✅ No changes - the debugger settings are already set",
+        "✅ 変更なし - デバッガーの設定は既に設定されています",
+        "✅ 无更改 - 调试器设置已设置",
+    ),
+    ("Would update user file: {}", "ユーザー ファイルを更新します: {}", "将更新用户文件：{}"),
+    (
+        "\n✅ Updated debugger settings in {} configurations of {}",
+        "\n✅ {1} の {0} 個の構成でデバッガーの設定を更新しました",
+        "\n✅ 已更新 {1} 中 {0} 个配置的调试器设置",
+    ),
+    ("Enabling vcpkg in project: {}", "プロジェクトで vcpkg を有効化中: {}", "正在为项目启用 vcpkg：{}"),
+    ("Set {}", "{} を設定", "设置 {}"),
+    ("Import {}", "{} をインポート", "导入 {}"),
+    ("✅ No changes - vcpkg is already enabled", "✅ 変更なし - vcpkg は既に有効です", "✅ 无更改 - vcpkg 已启用"),
+    ("Would update {}:", "{} を更新します:", "将更新 {}："),
+    ("✅ vcpkg enabled:", "✅ vcpkg を有効にしました:", "✅ 已启用 vcpkg："),
+    (
+        "Dependencies are found through the user-wide integration (`vcpkg integrate install`); pass --root to import vcpkg from a specific checkout",
+        "依存関係はユーザー全体の統合 (`vcpkg integrate install`) から検出されます。特定のチェックアウトから vcpkg をインポートするには --root を指定してください",
+        "依赖项通过用户范围的集成 (`vcpkg integrate install`) 查找；要从特定的签出导入 vcpkg，请传入 --root",
+    ),
+    (
+        "Adding vcpkg ports {} ({}) to project: {}",
+        "vcpkg ポート {} ({}) をプロジェクトに追加中: {}",
+        "正在向项目添加 vcpkg 端口 {} ({})：{}",
+    ),
+    (
+        "✅ No changes - every configuration already uses these ports",
+        "✅ 変更なし - すべての構成が既にこれらのポートを使用しています",
+        "✅ 无更改 - 所有配置均已使用这些端口",
+    ),
+    (
+        "Would add include and library paths for {} to {} configurations:",
+        "{1} 個の構成に {0} のインクルード パスとライブラリ パスを追加します:",
+        "将向 {1} 个配置添加 {0} 的包含路径和库路径：",
+    ),
+    (
+        "✅ Added include and library paths for {} to {} configurations:",
+        "✅ {1} 個の構成に {0} のインクルード パスとライブラリ パスを追加しました:",
+        "✅ 已向 {1} 个配置添加 {0} 的包含路径和库路径：",
+    ),
+    ("Libraries: {} (release), {} (debug)", "ライブラリ: {} (リリース)、{} (デバッグ)", "库：{} (发布)，{} (调试)"),
+    (", {} missing on disk", "、ディスク上にないもの {} 個", "，磁盘上缺失 {} 个"),
+    (
+        "⚡︎ Project summary: {} of {} files match{}\n",
+        "⚡︎ プロジェクトの概要: {1} 個中 {0} 個のファイルが一致{2}\n",
+        "⚡︎ 项目摘要：{1} 个文件中有 {0} 个匹配{2}\n",
+    ),
+    ("⚡︎ Project summary: Empty project\n", "⚡︎ プロジェクトの概要: 空のプロジェクト\n", "⚡︎ 项目摘要：空项目\n"),
+    (
+        "⚡︎ Project summary: {} files, {} filters{}\n",
+        "⚡︎ プロジェクトの概要: {} 個のファイル、{} 個のフィルター{}\n",
+        "⚡︎ 项目摘要：{} 个文件，{} 个筛选器{}\n",
+    ),
+    ("⚡︎ Project summary: {} files{}\n", "⚡︎ プロジェクトの概要: {} 個のファイル{}\n", "⚡︎ 项目摘要：{} 个文件{}\n"),
+    ("🧩 Qt modules: {}", "🧩 Qt モジュール: {}", "🧩 Qt 模块：{}"),
+    ("🔗 Shared items: {} ({} files)", "🔗 共有項目: {} ({} 個のファイル)", "🔗 共享项：{} ({} 个文件)"),
+    ("⚠️  Shared items project not found: {}", "⚠️  共有項目プロジェクトが見つかりません: {}", "⚠️  找不到共享项项目：{}"),
+    (
+        "\n⚡︎ Search summary: {} files, {} filters matched\n",
+        "\n⚡︎ 検索の概要: {} 個のファイル、{} 個のフィルターが一致\n",
+        "\n⚡︎ 搜索摘要：匹配 {} 个文件，{} 个筛选器\n",
+    ),
+    ("     {} = {} when {}", "     {2} の場合 {0} = {1}", "     当 {2} 时 {0} = {1}"),
+    ("  📁 filter: {} ({} files)", "  📁 フィルター: {} ({} 個のファイル)", "  📁 筛选器：{} ({} 个文件)"),
+    ("📊 Project statistics: {}\n", "📊 プロジェクトの統計: {}\n", "📊 项目统计：{}\n"),
+    ("Item types:", "項目の種類:", "项类型："),
+    ("\nExtensions:", "\n拡張子:", "\n扩展名："),
+    ("\nFiles per filter:", "\nフィルターごとのファイル数:", "\n各筛选器中的文件数："),
+    ("\nConfigurations: {}", "\n構成: {}", "\n配置：{}"),
+    ("\nMissing on disk: {}", "\nディスク上にないもの: {}", "\n磁盘上缺失：{}"),
+    ("\nDuplicate entries: {}", "\n重複したエントリ: {}", "\n重复条目：{}"),
+    (
+        "\n⚡︎ Project summary: {} items, {} configurations\n",
+        "\n⚡︎ プロジェクトの概要: {} 個の項目、{} 個の構成\n",
+        "\n⚡︎ 项目摘要：{} 个项，{} 个配置\n",
+    ),
+    ("Comparing {} with a new {} project", "{} を新しい {} プロジェクトと比較中", "正在将 {} 与新的 {} 项目进行比较"),
+    ("\n🧩 Configurations:", "\n🧩 構成:", "\n🧩 配置："),
+    ("\n⚙️  Properties:", "\n⚙️  プロパティ:", "\n⚙️  属性："),
+    ("\n🔧 Tool settings:", "\n🔧 ツールの設定:", "\n🔧 工具设置："),
+    ("\n📦 Imports:", "\n📦 インポート:", "\n📦 导入："),
+    ("\n📄 {} files carry per-file settings:", "\n📄 ファイルごとの設定を持つ {} 個のファイル:", "\n📄 {} 个文件带有单文件设置："),
+    ("  ... and {} more", "  ... ほか {} 個", "  ... 以及另外 {} 个"),
+    ("\n✅ No deviations from the {} template", "\n✅ {} テンプレートとの違いはありません", "\n✅ 与 {} 模板没有差异"),
+    (
+        "\n📋 {} deviations from the {} template",
+        "\n📋 {1} テンプレートとの違いが {0} 個あります",
+        "\n📋 与 {1} 模板有 {0} 处差异",
+    ),
+    ("❌ [{}] Sync failed: {:#}", "❌ [{}] 同期に失敗しました: {:#}", "❌ [{}] 同步失败：{:#}"),
+    (
+        "👀 Watching {} for {} files - press Ctrl+C to stop",
+        "👀 {} の {} ファイルを監視中 - Ctrl+C で停止",
+        "👀 正在监视 {} 中的 {} 文件 - 按 Ctrl+C 停止",
+    ),
+    ("⚠️  File watcher error: {}", "⚠️  ファイル監視のエラー: {}", "⚠️  文件监视器错误：{}"),
+    ("[{}] ✅ {}: {} added, {} removed", "[{}] ✅ {}: {} 個を追加、{} 個を削除", "[{}] ✅ {}：添加 {} 个，删除 {} 个"),
+    (
+        "⚠️  Properties left unexpanded (pass them with --property NAME=VALUE): {}",
+        "⚠️  展開されていないプロパティ (--property NAME=VALUE で指定してください): {}",
+        "⚠️  未展开的属性 (请使用 --property NAME=VALUE 传入)：{}",
+    ),
+    (
+        "⚠️  {} ({}): properties left unexpanded: {}",
+        "⚠️  {} ({}): 展開されていないプロパティ: {}",
+        "⚠️  {} ({})：未展开的属性：{}",
+    ),
+    (
+        "⚠️  Skipping {}: a {} project has no Meson target",
+        "⚠️  {} をスキップします: {} プロジェクトには Meson のターゲットがありません",
+        "⚠️  跳过 {}：{} 项目没有对应的 Meson 目标",
+    ),
+    (
+        "⚠️  Not represented in BUILD.bazel (listed in a comment there):",
+        "⚠️  BUILD.bazel で表現できないもの (そこにコメントとして記載):",
+        "⚠️  BUILD.bazel 中无法表示的内容 (已在其中以注释列出)：",
+    ),
+    (
+        "⚠️  {}: properties left unexpanded (pass them with --property NAME=VALUE): {}",
+        "⚠️  {}: 展開されていないプロパティ (--property NAME=VALUE で指定してください): {}",
+        "⚠️  {}：未展开的属性 (请使用 --property NAME=VALUE 传入)：{}",
+    ),
+    (
+        "\n📋 {} projects: {} changed, {} unchanged, {} failed",
+        "\n📋 {} 個のプロジェクト: 変更 {}、変更なし {}、失敗 {}",
+        "\n📋 {} 个项目：已更改 {}，未更改 {}，失败 {}",
+    ),
+    (
+        "  ⏭️  {} not processed after the failure (--keep-going processes them)",
+        "  ⏭️  失敗後に {} 個を処理しませんでした (--keep-going で処理されます)",
+        "  ⏭️  失败后有 {} 个未处理 (使用 --keep-going 可继续处理)",
+    ),
+    ("Newest installed Windows SDK: {}", "インストールされている最新の Windows SDK: {}", "已安装的最新 Windows SDK：{}"),
+];
+
+/// The translation of `message` for the current locale, or `None` to print it as written
+pub fn translated(message: &str) -> Option<&'static str> {
+    let locale = locale();
+    if locale == Locale::En {
+        return None;
+    }
+    CATALOG.iter().find(|(en, _, _)| *en == message).map(|(_, ja, zh)| match locale {
+        Locale::Ja => *ja,
+        _ => *zh,
+    })
+}
+
+/// Fills a translated format string with the call site's arguments. Supports the subset of
+/// `format!` syntax the messages use: `{}`, positional `{N}`, `{:#}`, `{:<W}` / `{:>W}` and `{{` / `}}`.
+pub fn format(template: &str, args: &[&dyn Display]) -> String {
+    let mut out = String::with_capacity(template.len());
+    let mut next = 0;
+    let mut chars = template.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '{' if chars.peek() == Some(&'{') => {
+                chars.next();
+                out.push('{');
+            }
+            '}' if chars.peek() == Some(&'}') => {
+                chars.next();
+                out.push('}');
+            }
+            '{' => {
+                let spec: String = chars.by_ref().take_while(|&c| c != '}').collect();
+                let (position, format) = spec.split_once(':').unwrap_or((&spec, ""));
+                let index = position.trim().parse().unwrap_or_else(|_| {
+                    next += 1;
+                    next - 1
+                });
+                let value = match args.get(index) {
+                    // `{:#}` prints an error with its causes
+                    Some(arg) if format == "#" => format!("{:#}", arg),
+                    Some(arg) => arg.to_string(),
+                    None => String::new(),
+                };
+                let width = format.get(1..).and_then(|w| w.parse::<usize>().ok()).unwrap_or(0);
+                match format.chars().next() {
+                    Some('>') => out.push_str(&format!("{:>width$}", value)),
+                    Some('<') => out.push_str(&format!("{:<width$}", value)),
+                    _ => out.push_str(&value),
+                }
+            }
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+/// `format!` for a console message, translated when the catalog has it in the current locale
+macro_rules! tr {
+    ($fmt:literal $(, $arg:expr)* $(,)?) => {
+        match $crate::i18n::translated($fmt) {
+            Some(template) => $crate::i18n::format(template, &[$(&$arg as &dyn std::fmt::Display),*]),
+            None => format!($fmt $(, $arg)*),
+        }
+    };
+}
+
+/// `log::info!` with the message translated; the English format string stays the catalog key
+macro_rules! info {
+    ($($message:tt)+) => {
+        log::info!("{}", $crate::i18n::tr!($($message)+))
+    };
+}
+
+/// `log::warn!` with the message translated
+macro_rules! warning {
+    ($($message:tt)+) => {
+        log::warn!("{}", $crate::i18n::tr!($($message)+))
+    };
+}
+
+/// `log::error!` with the message translated
+macro_rules! error {
+    ($($message:tt)+) => {
+        log::error!("{}", $crate::i18n::tr!($($message)+))
+    };
+}
+
+// `warn` is also a built-in attribute, so the macro is defined under another name
+pub(crate) use {error, info, tr, warning as warn};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    /// The format string of every `info!`, `warn!`, `error!` and `tr!` call in the sources
+    fn messages() -> Vec<(PathBuf, String)> {
+        let mut paths = vec![PathBuf::from(concat!(env!("CARGO_MANIFEST_DIR"), "/src"))];
+        let mut messages = Vec::new();
+        while let Some(path) = paths.pop() {
+            if path.is_dir() {
+                paths.extend(std::fs::read_dir(&path).unwrap().map(|entry| entry.unwrap().path()));
+                continue;
+            }
+            if path.extension().is_none_or(|ext| ext != "rs") || path.ends_with("i18n.rs") {
+                continue;
+            }
+            let source = std::fs::read_to_string(&path).unwrap();
+            for (start, _) in source.match_indices("!(") {
+                let name = source[..start].rsplit(|c: char| !(c.is_alphanumeric() || c == '_' || c == ':')).next().unwrap();
+                let Some(literal) = source[start + 2..].trim_start().strip_prefix('"') else {
+                    continue;
+                };
+                if !["info", "warn", "error", "tr"].contains(&name) {
+                    continue;
+                }
+                let mut message = String::new();
+                let mut chars = literal.chars();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => match chars.next() {
+                            Some('n') => message.push('\n'),
+                            Some(escaped) => message.push(escaped),
+                            None => break,
+                        },
+                        c => message.push(c),
+                    }
+                }
+                messages.push((path.clone(), message));
+            }
+        }
+        messages
+    }
+
+    /// The argument index and format spec of each placeholder, in a canonical order
+    fn placeholders(template: &str) -> Vec<(usize, String)> {
+        let mut found = Vec::new();
+        let mut next = 0;
+        for part in template.replace("{{", "").replace("}}", "").split('{').skip(1) {
+            let spec = part.split('}').next().unwrap();
+            let (position, format) = spec.split_once(':').unwrap_or((spec, ""));
+            let index = position.parse().unwrap_or_else(|_| {
+                next += 1;
+                next - 1
+            });
+            found.push((index, format.to_string()));
+        }
+        found.sort();
+        found
+    }
+
+    #[test]
+    fn every_message_is_in_the_catalog() {
+        let messages = messages();
+        assert!(messages.len() > 500, "found only {} messages", messages.len());
+        // Messages made only of placeholders and punctuation read the same in every language
+        let has_words = |message: &str| {
+            let mut in_placeholder = false;
+            message.chars().any(|c| {
+                in_placeholder = (in_placeholder || c == '{') && c != '}';
+                !in_placeholder && c.is_ascii_alphabetic()
+            })
+        };
+        let missing: Vec<String> = messages
+            .iter()
+            .filter(|(_, message)| has_words(message) && !CATALOG.iter().any(|(en, _, _)| en == message))
+            .map(|(path, message)| format!("{}: {:?}", path.display(), message))
+            .collect();
+        assert!(missing.is_empty(), "messages missing from the catalog:\n{}", missing.join("\n"));
+    }
+
+    #[test]
+    fn translations_keep_the_placeholders() {
+        for (en, ja, zh) in CATALOG {
+            assert_eq!(CATALOG.iter().filter(|(other, _, _)| other == en).count(), 1, "{:?} is in the catalog twice", en);
+            for translation in [ja, zh] {
+                assert_eq!(placeholders(translation), placeholders(en), "{:?} → {:?}", en, translation);
+                assert_eq!(translation.starts_with('\n'), en.starts_with('\n'), "{:?} → {:?}", en, translation);
+            }
+        }
+    }
+
+    #[test]
+    fn format_fills_placeholders() {
+        assert_eq!(format("{} of {}", &[&1, &"two"]), "1 of two");
+        assert_eq!(format("{1} の {0}", &[&"a", &"b"]), "b の a");
+        assert_eq!(format("[{:<4}|{:>4}]", &[&"ab", &7]), "[ab  |   7]");
+        assert_eq!(format("{{{}}} {{}}", &[&"guid"]), "{guid} {}");
+        assert_eq!(format("{} {}", &[&"only"]), "only ");
+    }
+
+    #[test]
+    fn format_prints_error_causes_with_alternate_flag() {
+        let error = anyhow::anyhow!("file not found").context("cannot load project");
+        assert_eq!(format("{:#}", &[&error]), "cannot load project: file not found");
+        assert_eq!(format("{}", &[&error]), "cannot load project");
+    }
+
+    #[test]
+    fn tr_falls_back_to_english_and_translates() {
+        set_locale(Locale::Ja);
+        let translated = tr!("Analyzing project: {}", "a.vcxproj");
+        let untranslated = tr!("{} → {}", 1, 2);
+        set_locale(Locale::En);
+        assert_eq!(translated, "プロジェクトを解析中: a.vcxproj");
+        assert_eq!(untranslated, "1 → 2");
+        assert_eq!(tr!("Analyzing project: {}", "a.vcxproj"), "Analyzing project: a.vcxproj");
+    }
+
+    #[test]
+    fn locale_from_lang_codes() {
+        assert_eq!(locale_from(Some("ja_JP.UTF-8")), Locale::Ja);
+        assert_eq!(locale_from(Some("zh-CN")), Locale::Zh);
+        assert_eq!(locale_from(Some("C")), Locale::En);
+    }
+}
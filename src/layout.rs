@@ -0,0 +1,180 @@
+use anyhow::{Context, Result};
+use regex::Regex;
+use serde_json::{json, Value};
+use std::fs;
+use std::path::Path;
+
+/// A reusable Solution Explorer taxonomy: the filter tree plus rules routing files into it.
+///
+/// ```json
+/// {
+///   "filters": [
+///     { "name": "Source Files", "extensions": "cpp;c;cc;cxx" },
+///     { "name": "Source Files\\Core" }
+///   ],
+///   "rules": [
+///     { "glob": "src/core/**", "filter": "Source Files\\Core" }
+///   ]
+/// }
+/// ```
+///
+/// Files are routed by the first matching rule, then by the `extensions` of a filter.
+#[derive(Debug, Default)]
+pub struct FilterLayout {
+    pub filters: Vec<LayoutFilter>,
+    pub rules: Vec<LayoutRule>,
+}
+
+#[derive(Debug, Clone)]
+pub struct LayoutFilter {
+    pub name: String,
+    /// `;`-separated extensions Visual Studio uses for new files, e.g. "cpp;c"
+    pub extensions: Option<String>,
+}
+
+/// Routes files whose project-relative path matches `glob` into `filter`
+#[derive(Debug, Clone)]
+pub struct LayoutRule {
+    pub glob: String,
+    pub filter: String,
+}
+
+impl FilterLayout {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read filter layout: {}", path.display()))?;
+        let json: Value = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid filter layout: {}", path.display()))?;
+
+        let mut layout = FilterLayout::default();
+        for filter in json["filters"].as_array().into_iter().flatten() {
+            let name = filter["name"]
+                .as_str()
+                .ok_or_else(|| anyhow::anyhow!("Every entry in 'filters' needs a \"name\" ({})", path.display()))?;
+            layout.filters.push(LayoutFilter {
+                name: crate::vcxproj::include_path(name),
+                extensions: filter["extensions"].as_str().map(str::to_string),
+            });
+        }
+        for rule in json["rules"].as_array().into_iter().flatten() {
+            let (Some(glob), Some(filter)) = (rule["glob"].as_str(), rule["filter"].as_str()) else {
+                return Err(anyhow::anyhow!("Every entry in 'rules' needs \"glob\" and \"filter\" ({})", path.display()));
+            };
+            layout.rules.push(LayoutRule {
+                glob: glob.to_string(),
+                filter: crate::vcxproj::include_path(filter),
+            });
+        }
+
+        Ok(layout)
+    }
+
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let json = json!({
+            "filters": self.filters.iter().map(|filter| match &filter.extensions {
+                Some(extensions) => json!({ "name": filter.name, "extensions": extensions }),
+                None => json!({ "name": filter.name }),
+            }).collect::<Vec<_>>(),
+            "rules": self.rules.iter().map(|rule| json!({
+                "glob": rule.glob,
+                "filter": rule.filter,
+            })).collect::<Vec<_>>(),
+        });
+
+        fs::write(path, serde_json::to_string_pretty(&json)? + "\n")
+            .with_context(|| format!("Failed to write filter layout: {}", path.display()))
+    }
+
+    /// The filter for a file by extension alone, from the filters' `extensions` lists
+    pub fn filter_for_extension(&self, path: &str) -> Option<&str> {
+        let extension = path.rsplit_once('.')?.1;
+        self.filters
+            .iter()
+            .find(|filter| {
+                filter
+                    .extensions
+                    .as_deref()
+                    .is_some_and(|exts| exts.split(';').any(|e| e.trim().eq_ignore_ascii_case(extension)))
+            })
+            .map(|filter| filter.name.as_str())
+    }
+}
+
+/// Rules `add` and `watch` assign the filter of new files with, instead of mirroring their
+/// directory: the `--map GLOB=FILTER` options first, then the `[filters]` table of `.vcprojm/config.toml`:
+///
+/// ```toml
+/// [filters]
+/// map = [
+///   { glob = "tests/**", filter = "Test Files" },
+///   { glob = "*.h", filter = "Header Files" },
+///   { glob = "src/render/**", filter = "Engine\\Rendering" },
+/// ]
+/// ```
+///
+/// Globs are matched against the project-relative path of a file, or against its name alone when
+/// they have no `/`. The first matching rule wins.
+#[derive(Debug, Default)]
+pub struct FilterMap {
+    rules: Vec<(Regex, bool, String)>,
+}
+
+impl FilterMap {
+    /// The `--map` rules followed by those of the config file in `project_dir`
+    pub fn load(project_dir: &Path, maps: &[String]) -> Result<Self> {
+        let mut rules = Vec::new();
+        for map in maps {
+            let (glob, filter) = map
+                .split_once('=')
+                .filter(|(glob, filter)| !glob.trim().is_empty() && !filter.trim().is_empty())
+                .ok_or_else(|| anyhow::anyhow!("Invalid --map '{}': expected GLOB=FILTER, e.g. \"tests/**=Test Files\"", map))?;
+            rules.push(LayoutRule { glob: glob.trim().to_string(), filter: crate::vcxproj::include_path(filter.trim()) });
+        }
+
+        let config_path = project_dir.join(crate::history::CONFIG_FILE);
+        if let Ok(content) = fs::read_to_string(&config_path) {
+            let config: toml::Table = content
+                .parse()
+                .with_context(|| format!("Invalid config file: {}", config_path.display()))?;
+            if let Some(filters) = config.get("filters") {
+                let filters = filters
+                    .as_table()
+                    .ok_or_else(|| anyhow::anyhow!("[filters] must be a table in {}", config_path.display()))?;
+                for (key, value) in filters {
+                    if key != "map" {
+                        return Err(anyhow::anyhow!("Unknown key 'filters.{}' in {}", key, config_path.display()));
+                    }
+                    let entries = value
+                        .as_array()
+                        .ok_or_else(|| anyhow::anyhow!("filters.map must be an array of {{ glob, filter }} in {}", config_path.display()))?;
+                    for entry in entries {
+                        let glob = entry.get("glob").and_then(toml::Value::as_str);
+                        let filter = entry.get("filter").and_then(toml::Value::as_str);
+                        let (Some(glob), Some(filter)) = (glob, filter) else {
+                            return Err(anyhow::anyhow!("Every entry in filters.map needs \"glob\" and \"filter\" ({})", config_path.display()));
+                        };
+                        rules.push(LayoutRule { glob: glob.to_string(), filter: crate::vcxproj::include_path(filter) });
+                    }
+                }
+            }
+        }
+
+        let rules = rules
+            .into_iter()
+            .map(|rule| Ok((crate::commands::glob_to_regex(&rule.glob)?, !rule.glob.contains(['/', '\\']), rule.filter)))
+            .collect::<Result<_>>()?;
+        Ok(Self { rules })
+    }
+
+    /// The filter of the first rule matching a project-relative path
+    pub fn filter_for(&self, path: &str) -> Option<&str> {
+        let path = path.replace('\\', "/");
+        let name = path.rsplit('/').next().unwrap_or(&path);
+        self.rules
+            .iter()
+            .find(|(regex, name_only, _)| regex.is_match(if *name_only { name } else { &path }))
+            .map(|(_, _, filter)| filter.as_str())
+    }
+}
@@ -1,573 +1,669 @@
+mod audit;
+mod canonical;
 mod cli;
+mod commands;
+mod condition;
+mod doctor;
+mod export;
+mod gitref;
+mod history;
+mod i18n;
+mod layout;
+mod nuget;
+mod output;
+mod parallel;
+mod patch;
+mod pipe;
+mod properties;
+mod props;
+mod repair;
+mod sln;
+mod user;
 mod vcxproj;
+mod vs;
 
-use anyhow::{Context, Result};
+use anyhow::Result;
 use clap::Parser;
-use regex::Regex;
-use std::path::PathBuf;
+use i18n::{error, info};
+use std::path::{Path, PathBuf};
 use walkdir::WalkDir;
 
-use cli::{Cli, Commands};
-use vcxproj::{FilterFile, VcxprojFile, ProjectStructure};
-
+use cli::{AsanAction, AuditAction, BackupsAction, Cli, Commands, ConfigAction, CudaAction, CustomBuildAction, FiltersAction, ForcedAction, GuidAction, HardeningAction, ImportsAction, NugetAction, PatchAction, PropsAction, QtAction, RefactorAction, ResourceAction, ShaderAction, SlnAction, UserAction, VcpkgAction};
 fn main() -> Result<()> {
-    let cli = Cli::parse();
-
-    match cli.command {
-        Commands::Add { extension, project, directory, recursive, regex, not, dryrun } => {
-            add_files_to_project(extension, project, directory, recursive, regex, not, dryrun)?;
-        }
-        Commands::Delete { project, target, extension, yes, regex, not, dryrun } => {
-            delete_from_project(project, target, extension, yes, regex, not, dryrun)?;
-        }
-        Commands::View { project, files_only, level } => {
-            view_project_structure(project, files_only, level)?;
-        }
-        Commands::Rename { project, from, to, yes, dryrun } => {
-            rename_filter_in_project(project, from, to, yes, dryrun)?;
-        }
-        Commands::AddInclude { project, path } => {
-            add_include_directory(project, path)?;
-        }
-        Commands::AddLibDir { project, path } => {
-            add_library_directory(project, path)?;
-        }
-        Commands::AddLib { project, name } => {
-            add_library_dependency(project, name)?;
-        }
+    let invocations = project_invocations(&std::env::args().collect::<Vec<_>>())?;
+    let (cli, effects) = Cli::parse_with_effects(&invocations[0]);
+    // Query commands print their own results; everything else reports what it changed
+    let report = cli.json && effects.mutates;
+    output::init(output::level_from_flags(cli.quiet, cli.verbose, cli.log_level.as_deref()), cli.json, report);
+    output::set_plain(cli.plain);
+    properties::set_overrides(cli.properties);
+    // The JSON report is read by scripts, so its warnings stay in English
+    i18n::set_locale(if cli.json { i18n::Locale::En } else { i18n::locale_from(cli.lang.as_deref()) });
+    vcxproj::set_preserve_mtime(cli.preserve_mtime);
+    vcxproj::set_force(cli.force);
+    vcxproj::set_random_filter_guids(cli.random_filter_guids);
+    vs::set_wait_for_close(cli.wait_for_vs_close);
+    parallel::set_keep_going(cli.keep_going);
+    parallel::init(cli.jobs.map(usize::from))?;
+    if cli.filters_in.is_some() && !cli.stdin && !cli.stdout {
+        return Err(anyhow::anyhow!("--filters-in only applies with --stdin or --stdout"));
     }
-
-    Ok(())
-}
-
-fn add_files_to_project(
-    extension: String,
-    project_path: PathBuf,
-    directory: Option<PathBuf>,
-    recursive: bool,
-    regex_pattern: Option<String>,
-    negate: bool,
-    dryrun: bool,
-) -> Result<()> {
-    // Determine the directory to scan
-    let scan_dir = directory.unwrap_or_else(|| {
-        project_path
-            .parent()
-            .unwrap_or_else(|| std::path::Path::new("."))
-            .to_path_buf()
-    });
-
-    println!("Scanning directory: {}", scan_dir.display());
+    pipe::enable(cli.stdin, cli.stdout, cli.command.project(), cli.filters_in, cli.filters_out)?;
+    gitref::enable(cli.git_ref);
     
-    match (&regex_pattern, negate) {
-        (Some(ref pattern), true) => println!("Looking for *.{} files in paths NOT matching regex: {}", extension, pattern),
-        (Some(ref pattern), false) => println!("Looking for *.{} files in paths matching regex: {}", extension, pattern),
-        (None, true) => println!("Looking for *.{} files (negation has no effect without regex)", extension),
-        (None, false) => println!("Looking for *.{} files", extension),
-    }
-
-    // Compile regex pattern if provided
-    let compiled_regex = if let Some(ref pattern) = regex_pattern {
-        Some(Regex::new(pattern).context("Invalid regex pattern")?)
+    let args: Vec<String> = invocations[0].iter().skip(1).cloned().collect();
+    
+    let report_name = if report {
+        let command = <Cli as clap::CommandFactory>::command();
+        args.iter().find_map(|arg| command.find_subcommand(arg).map(|sub| sub.get_name().to_string()))
     } else {
         None
     };
-
-    // Find all files with the specified extension, filtered by path regex if provided
-    let mut files_to_add = Vec::new();
-    let mut scan_relative_paths = Vec::new(); // For filter creation
+    let dryrun = effects.dryrun;
     
-    let walker = if recursive {
-        WalkDir::new(&scan_dir)
+    let changes_planned = if invocations.len() > 1 {
+        if cli.stdin || cli.stdout {
+            return Err(anyhow::anyhow!("--stdin and --stdout take a single --project"));
+        }
+        run_each_project(&invocations, cli.notify_vs)?
     } else {
-        WalkDir::new(&scan_dir).max_depth(1)
+        run_journaled(cli.command, args.join(" "), cli.notify_vs)?.0
     };
-
-    for entry in walker {
-        let entry = entry.context("Failed to read directory entry")?;
-        let path = entry.path();
-        
-        if path.is_file() {
-            // First check if file has the correct extension
-            let has_extension = if let Some(ext) = path.extension() {
-                ext.to_string_lossy().eq_ignore_ascii_case(&extension)
-            } else {
-                false
-            };
-            
-            if !has_extension {
-                continue;
-            }
-            
-            // Then check if path matches regex (if provided) with negation support
-            let path_matches = if let Some(ref regex) = compiled_regex {
-                // Get the relative path from scan_dir to apply regex against
-                let relative_to_scan = path.strip_prefix(&scan_dir).unwrap_or(path);
-                let path_str = relative_to_scan.to_string_lossy();
-                let regex_matches = regex.is_match(&path_str);
-                
-                if negate {
-                    !regex_matches // Include files that DON'T match the regex
-                } else {
-                    regex_matches // Include files that DO match the regex
-                }
-            } else {
-                true // No regex means all paths match (negation has no effect)
-            };
-            
-            if path_matches {
-                // Calculate path relative to project directory for Visual Studio to find the file
-                let project_relative_path = if let Some(project_dir) = project_path.parent() {
-                    match path.strip_prefix(project_dir) {
-                        Ok(rel) => rel.to_path_buf(),
-                        Err(_) => path.to_path_buf(), // Fallback to absolute path if strip_prefix fails
-                    }
-                } else {
-                    path.to_path_buf()
-                };
-                
-                // Calculate path relative to scan directory for filter hierarchy
-                let scan_relative_path = match path.strip_prefix(&scan_dir) {
-                    Ok(rel) => rel.to_path_buf(),
-                    Err(_) => path.to_path_buf(),
-                };
-                
-                files_to_add.push(project_relative_path);
-                scan_relative_paths.push(scan_relative_path);
-            }
-        }
+    pipe::finish()?;
+    if let Some(command) = report_name {
+        output::print_report(&command, dryrun)?;
     }
+    if changes_planned {
+        std::process::exit(EXIT_CHANGES_PLANNED);
+    }
+    Ok(())
+}
+
+/// Runs a command as one operation of the history: every file it writes is journaled so it can be
+/// undone, and restored when the command fails. Returns whether a `--dryrun` would have modified
+/// the project, and how many files the command wrote.
+fn run_journaled(command: Commands, command_line: String, notify_vs: bool) -> Result<(bool, usize)> {
+    let (changes_planned, operation) = run_operation(command, command_line);
+    finish_operation(changes_planned, operation, notify_vs)
+}
 
-    if files_to_add.is_empty() {
-        if let Some(ref pattern) = regex_pattern {
-            println!("No *.{} files found in paths matching regex '{}' in {}", extension, pattern, scan_dir.display());
-        } else {
-            println!("No *.{} files found in {}", extension, scan_dir.display());
+/// The first half of `run_journaled`, which can run on a worker thread: runs the command and
+/// restores the files it wrote if it failed, leaving its operation to `finish_operation`
+fn run_operation(command: Commands, command_line: String) -> (Result<bool>, Option<history::PendingOperation>) {
+    history::begin(command_line, command.project().map(Path::to_path_buf));
+    let changes_planned = parallel::catch_panic(|| run(command));
+    // A command touching several files either applies completely or not at all, unless it went on
+    // past the projects it failed on
+    if changes_planned.as_ref().is_err_and(|err| !err.is::<parallel::ProjectsFailed>()) {
+        match history::rollback() {
+            Ok(restored) if !restored.is_empty() => {
+                error!("❌ Command failed - restored {} files it had already written", restored.len());
+            }
+            Ok(_) => {}
+            Err(err) => return (Err(err), None),
         }
-        return Ok(());
     }
+    (changes_planned, history::take())
+}
 
-    println!("Found {} files to add:", files_to_add.len());
-    for file in &files_to_add {
-        println!("  - {}", file.display());
+/// The second half of `run_journaled`: records the operation in the history and tells Visual
+/// Studio to reload the files it wrote
+fn finish_operation(changes_planned: Result<bool>, operation: Option<history::PendingOperation>, notify_vs: bool) -> Result<(bool, usize)> {
+    // Empty when the command failed and its files were restored
+    let written = operation.as_ref().map(history::PendingOperation::written_files).unwrap_or_default();
+    if notify_vs {
+        vs::notify_reload(&written)?;
     }
-
-    if dryrun {
-        println!("\n🔍 DRY RUN - No files were modified");
-        println!("Would update project file: {}", project_path.display());
-        
-        let filter_path = project_path.with_extension("vcxproj.filters");
-        if filter_path.exists() {
-            println!("Would update filter file: {}", filter_path.display());
-        } else {
-            println!("Would create filter file: {}", filter_path.display());
-        }
-        
-        println!("✨ Dry run completed - {} files would be added", files_to_add.len());
-        return Ok(());
+    if let Some(operation) = operation {
+        history::finish_operation(operation)?;
     }
+    Ok((changes_planned?, written.len()))
+}
 
-    // Load and update the .vcxproj file
-    println!("\nUpdating project file: {}", project_path.display());
-    let mut vcxproj = VcxprojFile::load(&project_path)?;
-    vcxproj.add_source_files(&files_to_add)?;
-    vcxproj.save()?;
-    println!("Successfully updated {}", project_path.display());
-
-    // Update the .vcxproj.filters file if it exists
-    let filter_path = project_path.with_extension("vcxproj.filters");
-    if filter_path.exists() {
-        println!("Updating filter file: {}", filter_path.display());
-        let mut filter_file = FilterFile::load(&filter_path)?;
-        filter_file.add_source_files_with_hierarchy(&files_to_add, &scan_relative_paths)?;
-        filter_file.save()?;
-        println!("Successfully updated {}", filter_path.display());
-    } else {
-        println!("Filter file not found: {}", filter_path.display());
-        println!("Creating basic filter file...");
-        
-        // Create a basic filter file
-        let filter_content = create_basic_filter_file_with_hierarchy(&files_to_add, &scan_relative_paths)?;
-        std::fs::write(&filter_path, filter_content)
-            .context("Failed to create filter file")?;
-        println!("Created {}", filter_path.display());
-    }
+/// Whether a `--project` value is a glob rather than a path
+fn is_project_glob(value: &str) -> bool {
+    value.contains(['*', '?'])
+}
 
-    println!("\n✅ Project files updated successfully!");
-    Ok(())
+/// The .vcxproj files matching a glob such as `libs/**/*.vcxproj`, sorted, skipping the
+/// directories `add` skips by default (build output, .git, ...)
+fn expand_project_glob(pattern: &str) -> Result<Vec<PathBuf>> {
+    let pattern = pattern.replace('\\', "/");
+    let pattern = pattern.strip_prefix("./").unwrap_or(&pattern);
+    let regex = commands::glob_to_regex(pattern)?;
+    // Walk from the directories before the first wildcard
+    let base: Vec<&str> = pattern.split('/').take_while(|part| !is_project_glob(part)).collect();
+    let base = if base.is_empty() { PathBuf::from(".") } else { PathBuf::from(base.join("/")) };
+    
+    let excluded_dirs: Vec<String> = commands::DEFAULT_EXCLUDED_DIRS.iter().map(|d| d.to_string()).collect();
+    let mut projects: Vec<PathBuf> = WalkDir::new(&base)
+        .into_iter()
+        .filter_entry(|e| !commands::is_excluded_dir(e.path(), e.file_type().is_dir(), &base, &excluded_dirs))
+        .filter_map(|e| e.ok())
+        .filter(|e| e.file_type().is_file())
+        .map(|e| e.into_path())
+        .filter(|path| {
+            let path = path.to_string_lossy().replace('\\', "/");
+            regex.is_match(path.strip_prefix("./").unwrap_or(&path))
+        })
+        .collect();
+    projects.sort();
+    Ok(projects)
 }
 
-fn create_basic_filter_file_with_hierarchy(project_files: &[PathBuf], scan_relative_files: &[PathBuf]) -> Result<String> {
-    use std::collections::HashSet;
-    let mut content = String::new();
-    content.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
-    content.push_str("<Project ToolsVersion=\"4.0\" xmlns=\"http://schemas.microsoft.com/developer/msbuild/2003\">\n");
-    
-    // Collect unique directories using scan_relative_files
-    let mut dirs = HashSet::new();
-    for file in scan_relative_files {
-        if let Some(parent) = file.parent() {
-            let filter_name = parent.to_string_lossy().replace('/', "\\");
-            if !filter_name.is_empty() {
-                dirs.insert(filter_name);
+/// The command lines to run: the one given, or with a repeated or glob `--project` on a command that
+/// takes one project, one per project with that `--project`. A command taking several projects
+/// (like `refactor extract-props`) gets the glob's matches in its own command line instead.
+fn project_invocations(args: &[String]) -> Result<Vec<Vec<String>>> {
+    // (index of the flag, index of its value) of every --project
+    let mut occurrences = Vec::new();
+    let mut k = 1;
+    while k < args.len() {
+        match args[k].as_str() {
+            "-p" | "--project" if k + 1 < args.len() => {
+                occurrences.push((k, k + 1));
+                k += 1;
             }
+            arg if arg.starts_with("--project=") => occurrences.push((k, k)),
+            _ => {}
         }
+        k += 1;
     }
-    
-    // Add filters
-    if !dirs.is_empty() {
-        content.push_str("  <ItemGroup>\n");
-        for dir in &dirs {
-            let uuid = uuid::Uuid::new_v4();
-            content.push_str(&format!(
-                "    <Filter Include=\"{}\">\n      <UniqueIdentifier>{{{}}}</UniqueIdentifier>\n    </Filter>\n",
-                dir, uuid.to_string().to_uppercase()
-            ));
-        }
-        content.push_str("  </ItemGroup>\n");
+    let value = |(flag, value): (usize, usize)| if flag == value { &args[flag]["--project=".len()..] } else { args[value].as_str() };
+    if occurrences.len() < 2 && !occurrences.iter().any(|&o| is_project_glob(value(o))) {
+        return Ok(vec![args.to_vec()]);
     }
     
-    // Add files with correct Include paths and filter assignments
-    content.push_str("  <ItemGroup>\n");
-    for (i, project_file) in project_files.iter().enumerate() {
-        let scan_relative_file = &scan_relative_files[i];
-        let include_path = project_file.to_string_lossy().replace('/', "\\");
-        
-        content.push_str(&format!("    <ClCompile Include=\"{}\">\n", include_path));
-        
-        if let Some(parent) = scan_relative_file.parent() {
-            let filter_name = parent.to_string_lossy().replace('/', "\\");
-            if !filter_name.is_empty() {
-                content.push_str(&format!("      <Filter>{}</Filter>\n", filter_name));
-            } else {
-                content.push_str("      <Filter>Source Files</Filter>\n");
-            }
-        } else {
-            content.push_str("      <Filter>Source Files</Filter>\n");
+    // The subcommand the arguments select, to find out how many projects it takes
+    let mut command = <Cli as clap::CommandFactory>::command();
+    for arg in &args[1..] {
+        match command.find_subcommand(arg) {
+            Some(sub) => command = sub.clone(),
+            None if arg.starts_with('-') => continue,
+            None => break,
         }
-        
-        content.push_str("    </ClCompile>\n");
-    }
-    content.push_str("  </ItemGroup>\n");
-    
-    content.push_str("</Project>");
-    Ok(content)
-}
-
-
-fn delete_from_project(
-    project_path: PathBuf,
-    target: Option<String>,
-    extension: Option<String>,
-    yes: bool,
-    regex_pattern: Option<String>,
-    negate: bool,
-    dryrun: bool,
-) -> Result<()> {
-    println!("Analyzing project: {}", project_path.display());
-    
-    // Validate arguments
-    if target.is_none() && extension.is_none() {
-        return Err(anyhow::anyhow!("Either --target or --extension must be specified"));
     }
-    
-    let target_str = target.as_deref().unwrap_or("");
-    let target_display = if let Some(ref ext) = extension {
-        format!("all *.{} files", ext)
-    } else {
-        target_str.to_string()
-    };
-    
-    // Load the project file
-    let mut vcxproj = VcxprojFile::load(&project_path)?;
-    
-    // Compile regex pattern if provided
-    let compiled_regex = if let Some(ref pattern) = regex_pattern {
-        Some(Regex::new(pattern).context("Invalid regex pattern")?)
-    } else {
-        None
+    let Some(project_arg) = command.get_arguments().find(|a| a.get_long() == Some("project")) else {
+        return Ok(vec![args.to_vec()]);
     };
-
-    // Preview what will be deleted
-    let original_content = vcxproj.content.clone();
-    let all_deleted_files = vcxproj.delete_files(target_str, extension.as_deref())?;
-    vcxproj.content = original_content; // Restore for confirmation
     
-    // Apply regex filtering if provided with negation support
-    let deleted_files: Vec<String> = if let Some(ref regex) = compiled_regex {
-        all_deleted_files.into_iter()
-            .filter(|file_path| {
-                let regex_matches = regex.is_match(file_path);
-                if negate {
-                    !regex_matches // Delete files that DON'T match the regex
-                } else {
-                    regex_matches // Delete files that DO match the regex
-                }
-            })
-            .collect()
-    } else {
-        all_deleted_files
-    };
-    
-    if deleted_files.is_empty() {
-        match (&regex_pattern, negate) {
-            (Some(ref pattern), true) => println!("No files found matching: {} with regex filter NOT matching: {}", target_display, pattern),
-            (Some(ref pattern), false) => println!("No files found matching: {} with regex filter: {}", target_display, pattern),
-            (None, _) => println!("No files found matching: {}", target_display),
+    let mut projects = Vec::new();
+    for &occurrence in &occurrences {
+        let value = value(occurrence);
+        if !is_project_glob(value) {
+            projects.push(value.to_string());
+            continue;
         }
-        return Ok(());
-    }
-    
-    // Show what will be deleted
-    println!("\n📁 Files to be removed from project:");
-    for file in &deleted_files {
-        println!("  - {}", file);
-    }
-    
-    // Check filter file as well
-    let filter_path = project_path.with_extension("vcxproj.filters");
-    let mut preview_filters = Vec::new();
-    if filter_path.exists() {
-        let mut filter_file = FilterFile::load(&filter_path)?;
-        let original_filter_content = filter_file.content.clone();
-        let (_, all_deleted_filters) = filter_file.delete_files_and_filters(target_str, extension.as_deref())?;
-        // Apply the same regex filtering to filters (optional, may not be needed)
-        preview_filters = all_deleted_filters;
-        filter_file.content = original_filter_content; // Restore for confirmation
-    }
-    
-    if !preview_filters.is_empty() {
-        println!("\n📁 Filters to be removed:");
-        for filter in &preview_filters {
-            println!("  - {}", filter);
+        let matches = expand_project_glob(value)?;
+        if matches.is_empty() {
+            return Err(anyhow::anyhow!("No projects match '{}'", value));
         }
+        projects.extend(matches.into_iter().map(|p| p.to_string_lossy().into_owned()));
     }
-    
-    if dryrun {
-        println!("\n🔍 DRY RUN - No files were modified");
-        println!("Would remove {} files from project file: {}", deleted_files.len(), project_path.display());
-        
-        if filter_path.exists() {
-            if !preview_filters.is_empty() {
-                println!("Would remove {} filters from filter file: {}", preview_filters.len(), filter_path.display());
+    // A project matched twice (by a glob and by name) is processed once
+    let mut seen = std::collections::HashSet::new();
+    projects.retain(|project| seen.insert(project.clone()));
+    
+    // Every --project replaced by the given ones, where the first of them was
+    let with_projects = |projects: &[String]| {
+        let mut line = Vec::new();
+        for (k, arg) in args.iter().enumerate() {
+            if occurrences.iter().any(|&(flag, value)| k == value && flag != value) {
+                continue;
+            }
+            if k == occurrences[0].0 {
+                line.extend(projects.iter().flat_map(|project| ["--project".to_string(), project.clone()]));
+            } else if !occurrences.iter().any(|&(flag, _)| k == flag) {
+                line.push(arg.clone());
             }
-            println!("Would update filter file: {}", filter_path.display());
-        }
-        
-        println!("✨ Dry run completed - {} files would be removed", deleted_files.len());
-        return Ok(());
-    }
-    
-    // Confirm deletion
-    if !yes {
-        print!("\nRemove {} items from project? [y/N]: ", deleted_files.len());
-        use std::io::{self, Write};
-        io::stdout().flush()?;
-        
-        let mut input = String::new();
-        io::stdin().read_line(&mut input)?;
-        let input = input.trim().to_lowercase();
-        
-        if input != "y" && input != "yes" {
-            println!("Operation cancelled.");
-            return Ok(());
         }
+        line
+    };
+    if matches!(project_arg.get_action(), clap::ArgAction::Append) {
+        return Ok(vec![with_projects(&projects)]);
     }
-    
-    // Perform the deletion
-    println!("\nUpdating project file: {}", project_path.display());
-    vcxproj.delete_files(target_str, extension.as_deref())?;
-    vcxproj.save()?;
-    println!("Successfully updated {}", project_path.display());
-    
-    // Update filter file if it exists
-    if filter_path.exists() {
-        println!("Updating filter file: {}", filter_path.display());
-        let mut filter_file = FilterFile::load(&filter_path)?;
-        filter_file.delete_files_and_filters(target_str, extension.as_deref())?;
-        filter_file.save()?;
-        println!("Successfully updated {}", filter_path.display());
-    }
-    
-    println!("\n🗑️  Successfully removed {} files from project!\n", deleted_files.len());
-    Ok(())
+    Ok(projects.iter().map(|project| with_projects(std::slice::from_ref(project))).collect())
 }
 
-fn view_project_structure(
-    project_path: PathBuf,
-    files_only: bool,
-    level: Option<usize>,
-) -> Result<()> {
-    // Load and parse the project structure
-    let structure = ProjectStructure::from_project(&project_path)?;
-    
-    // Display the tree structure (extensions always shown)
-    let tree_output = structure.display_tree(files_only, true, level);
-    print!("{}", tree_output);
-    
-    // Show summary
-    let file_count = structure.files.len();
-    let filter_count = structure.filters.len();
-    
-    if file_count == 0 && filter_count == 0 {
-        println!("⚡︎ Project summary: Empty project\n");
-    } else if !files_only && filter_count > 0 {
-        println!("⚡︎ Project summary: {} files, {} filters\n", file_count, filter_count);
-    } else {
-        println!("⚡︎ Project summary: {} files\n", file_count);
+/// Whether a command line names a file besides its project (a solution, a layout, an output file),
+/// which the command could write for each project, so the projects can't be processed at once
+fn names_other_files(line: &[String]) -> bool {
+    let cli = <Cli as clap::CommandFactory>::command();
+    let Ok(root) = cli.clone().try_get_matches_from(line) else {
+        return true;
+    };
+    let (mut command, mut matches) = (&cli, &root);
+    while let Some((name, sub_matches)) = matches.subcommand() {
+        let Some(sub) = command.find_subcommand(name) else {
+            break;
+        };
+        (command, matches) = (sub, sub_matches);
     }
-    
-    Ok(())
+    let names_files = command
+        .get_arguments()
+        .filter(|arg| arg.get_id() != "project" && arg.get_value_hint() == clap::ValueHint::AnyPath)
+        .any(|arg| matches.value_source(arg.get_id().as_str()) == Some(clap::parser::ValueSource::CommandLine));
+    names_files
 }
 
-fn rename_filter_in_project(
-    project_path: PathBuf,
-    from: String,
-    to: String,
-    yes: bool,
-    dryrun: bool,
-) -> Result<()> {
-    println!("Analyzing project: {}", project_path.display());
+/// Runs the command once per project, each as its own operation of the history, and summarizes
+/// the outcome. Projects are processed several at once (`--jobs`) unless the command names another
+/// file they could all write, with the output of each printed in order. The first failure stops
+/// the run unless `--keep-going`. Returns whether a `--dryrun` would have modified any project.
+fn run_each_project(invocations: &[Vec<String>], notify_vs: bool) -> Result<bool> {
+    use std::sync::atomic::{AtomicBool, Ordering};
+    
+    let project_of = |line: &[String]| line.iter().position(|arg| arg == "--project").map(|k| line[k + 1].clone()).unwrap_or_default();
+    let keep_going = parallel::keep_going();
+    let stop = AtomicBool::new(false);
+    let (mut changed, mut unchanged, mut failed) = (0, 0, Vec::new());
+    let mut changes_planned = false;
+    parallel::for_each(
+        invocations,
+        !names_other_files(&invocations[0]),
+        |k, line| {
+            // Projects already started when one fails still complete
+            if stop.load(Ordering::Relaxed) {
+                return Ok(None);
+            }
+            info!("\n📁 {} ({}/{})", project_of(line), k + 1, invocations.len());
+            let (outcome, operation) = match Cli::try_parse_from(line) {
+                Ok(cli) => run_operation(cli.command, line[1..].join(" ")),
+                Err(err) => (Err(err.into()), None),
+            };
+            if outcome.is_err() && !keep_going {
+                stop.store(true, Ordering::Relaxed);
+            }
+            Ok(Some((outcome, operation)))
+        },
+        |k, outcome| {
+            let project = project_of(&invocations[k]);
+            let outcome = outcome.and_then(|outcome| {
+                outcome.map(|(outcome, operation)| finish_operation(outcome, operation, notify_vs)).transpose()
+            });
+            match outcome {
+                Ok(Some((planned, written))) => {
+                    changes_planned |= planned;
+                    if planned || written > 0 {
+                        changed += 1;
+                    } else {
+                        unchanged += 1;
+                    }
+                }
+                Ok(None) => {}
+                Err(err) => {
+                    error!("❌ {}: {:#}", project, err);
+                    failed.push(project);
+                }
+            }
+        },
+    );
     
-    // Check if filter file exists
-    let filter_path = project_path.with_extension("vcxproj.filters");
-    if !filter_path.exists() {
-        return Err(anyhow::anyhow!("Filter file not found: {}", filter_path.display()));
+    let skipped = invocations.len() - changed - unchanged - failed.len();
+    info!("\n📋 {} projects: {} changed, {} unchanged, {} failed", invocations.len(), changed, unchanged, failed.len());
+    for project in &failed {
+        info!("  ❌ {}", project);
     }
-    
-    // Load filter file
-    let mut filter_file = FilterFile::load(&filter_path)?;
-    
-    // Attempt to rename the filter
-    let (target_exists, renamed_files) = filter_file.rename_filter(&from, &to)?;
-    
-    if renamed_files.is_empty() {
-        println!("No files found in filter '{}'", from);
-        return Ok(());
+    if skipped > 0 {
+        info!("  ⏭️  {} not processed after the failure (--keep-going processes them)", skipped);
     }
-    
-    if dryrun {
-        println!("\n🔍 DRY RUN - No files were modified");
-        if target_exists {
-            println!("Would merge filter '{}' into existing filter '{}'", from, to);
-            println!("Files that would be moved from '{}' filter:", from);
-            for file in &renamed_files {
-                println!("  - {} → {}", file, to);
+    parallel::ProjectsFailed::check(failed.len(), invocations.len())?;
+    Ok(changes_planned)
+}
+
+/// Exit status of a `--dryrun` that found changes to make, so CI can detect an out-of-sync project
+/// (0 = nothing to do, 1 = error, like `diff` and `terraform plan -detailed-exitcode`)
+const EXIT_CHANGES_PLANNED: i32 = 2;
+
+/// Runs a command. Returns true when a `--dryrun` command would have modified the project.
+fn run(command: Commands) -> Result<bool> {
+    let mut changes_planned = false;
+    match command {
+        Commands::Add { from_file: Some(list), project, maps, dryrun, .. } => {
+            changes_planned = commands::files::add_files_from_list(list, project, &maps, dryrun)? && dryrun;
+        }
+        Commands::Add { extension, from_file: None, project, directory, recursive, regex, not, exclude_dirs, no_default_excludes, gitignore, maps, dryrun } => {
+            let mut excluded_dirs: Vec<String> = if no_default_excludes {
+                Vec::new()
+            } else {
+                commands::DEFAULT_EXCLUDED_DIRS.iter().map(|d| d.to_string()).collect()
+            };
+            excluded_dirs.extend(exclude_dirs);
+            changes_planned = commands::files::add_files_to_project(extension.unwrap_or_default(), project, directory, recursive, regex, not, excluded_dirs, gitignore, &maps, dryrun)? && dryrun;
+        }
+        Commands::Delete { project, target, extension, yes, interactive, regex, not, dryrun } => {
+            changes_planned = commands::files::delete_from_project(project, target, extension, yes, interactive, regex, not, dryrun)? && dryrun;
+        }
+        Commands::FixFilters { project, dryrun } => {
+            changes_planned = commands::filters::fix_filters(project, dryrun)? && dryrun;
+        }
+        Commands::Prune { project, dryrun } => {
+            changes_planned = commands::files::prune_project(project, dryrun)? && dryrun;
+        }
+        Commands::Watch { project, ext, exclude_dirs, gitignore, maps, debounce } => {
+            let mut excluded_dirs: Vec<String> = commands::DEFAULT_EXCLUDED_DIRS.iter().map(|d| d.to_string()).collect();
+            excluded_dirs.extend(exclude_dirs);
+            commands::watch::watch_project(project, &ext, excluded_dirs, gitignore, &maps, debounce)?;
+        }
+        Commands::View { project, files_only, level, show_props, sort, only_ext, pattern, check_disk } => {
+            commands::view::view_project_structure(project, files_only, level, show_props, sort, only_ext, pattern, check_disk)?;
+        }
+        Commands::Rename { project, from, to, yes, dryrun } => {
+            changes_planned = commands::filters::rename_filter_in_project(project, from, to, yes, dryrun)? && dryrun;
+        }
+        Commands::AddInclude { project, path, dryrun } => {
+            changes_planned = commands::settings::add_include_directory(project, path, dryrun)? && dryrun;
+        }
+        Commands::AddLibDir { project, path, dryrun } => {
+            changes_planned = commands::settings::add_library_directory(project, path, dryrun)? && dryrun;
+        }
+        Commands::AddLib { project, name, debug_name, release_name, config, platform, position, dryrun } => {
+            // "{d}" in the name expands to the debug ("fmtd.lib") and release ("fmt.lib") variants
+            let (debug_name, release_name) = match (name, debug_name, release_name) {
+                (Some(name), _, _) => (name.replace("{d}", "d"), name.replace("{d}", "")),
+                (None, Some(debug_name), Some(release_name)) => (debug_name, release_name),
+                _ => return Err(anyhow::anyhow!("Specify --name, or both --debug-name and --release-name")),
+            };
+            changes_planned = commands::settings::add_library_dependency(project, debug_name, release_name, commands::config_selector(config, platform)?, position, dryrun)? && dryrun;
+        }
+        Commands::AddDefine { project, define, config, platform, dryrun } => {
+            changes_planned = commands::settings::add_preprocessor_definition(project, define, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::ForcedInclude { action: action @ (ForcedAction::Add { dryrun, .. } | ForcedAction::Remove { dryrun, .. }) } => {
+            changes_planned = commands::forced::edit_forced_files("ForcedIncludeFiles", action)? && dryrun;
+        }
+        Commands::ForcedUsing { action: action @ (ForcedAction::Add { dryrun, .. } | ForcedAction::Remove { dryrun, .. }) } => {
+            changes_planned = commands::forced::edit_forced_files("ForcedUsingFiles", action)? && dryrun;
+        }
+        Commands::Search { project, solution, pattern } => {
+            commands::view::search_projects(project, solution, pattern)?;
+        }
+        Commands::Which { solution, file } => {
+            commands::sln::which_projects(solution, file)?;
+        }
+        Commands::SetFileProp { project, glob, config, warning_level, optimization, no_analyze, props, dryrun } => {
+            let properties = commands::file_props::file_properties_from_args(warning_level, optimization, no_analyze, props)?;
+            changes_planned = commands::file_props::set_file_properties(project, glob, config, properties, dryrun)? && dryrun;
+        }
+        Commands::List { project, item_types, filter, format, metadata } => {
+            let format = if output::is_json() { "json".to_string() } else { format };
+            commands::view::list_project_files(project, item_types, filter, &format, metadata)?;
+        }
+        Commands::Stats { project } => {
+            commands::view::show_project_stats(project, output::is_json())?;
+        }
+        Commands::Exclude { project, regex, config, platform, dryrun } => {
+            changes_planned = commands::settings::set_excluded_from_build(project, regex, commands::config_selector(config, platform)?, true, dryrun)? && dryrun;
+        }
+        Commands::Include { project, regex, config, platform, dryrun } => {
+            changes_planned = commands::settings::set_excluded_from_build(project, regex, commands::config_selector(config, platform)?, false, dryrun)? && dryrun;
+        }
+        Commands::ClearFileProps { project, glob, props, config, dryrun } => {
+            changes_planned = commands::file_props::clear_file_properties(project, glob, props, config, dryrun)? && dryrun;
+        }
+        Commands::Undo { project, dryrun } => {
+            changes_planned = commands::backups::undo_last_operation(project, true, vcxproj::forced(), dryrun)? && dryrun;
+        }
+        Commands::Redo { project, dryrun } => {
+            changes_planned = commands::backups::undo_last_operation(project, false, vcxproj::forced(), dryrun)? && dryrun;
+        }
+        Commands::Backups { action } => match action {
+            BackupsAction::List { project } => {
+                commands::backups::list_backups(project)?;
+            }
+            BackupsAction::Prune { project, keep, max_age_days, dryrun } => {
+                changes_planned = commands::backups::prune_backups(project, keep, max_age_days, dryrun)? && dryrun;
             }
-        } else {
-            println!("Would rename filter '{}' to '{}'", from, to);
-            println!("Files that would be moved:");
-            for file in &renamed_files {
-                println!("  - {} → {}", file, to);
+            BackupsAction::Restore { id, project, dryrun } => {
+                changes_planned = commands::backups::restore_backup(project, id, vcxproj::forced(), dryrun)? && dryrun;
             }
+        },
+        Commands::Lint { project, fix, dryrun } => {
+            // Without --fix, lint is a check: finding something to remove counts as planned changes
+            changes_planned = commands::file_props::lint_redundant_file_settings(project, fix, dryrun)? && (dryrun || !fix);
         }
-        println!("Would update filter file: {}", filter_path.display());
-        println!("✨ Dry run completed - {} files would be moved", renamed_files.len());
-        return Ok(());
-    }
-    
-    if target_exists {
-        // Conflict detected - ask for merge confirmation
-        println!("⚠️  Conflict detected!");
-        println!("Filter '{}' already exists in the project.", to);
-        println!("Files in '{}' filter:", from);
-        for file in &renamed_files {
-            println!("  - {}", file);
-        }
-        
-        if !yes {
-            print!("\nMerge '{}' into existing '{}' filter? [y/N]: ", from, to);
-            use std::io::{self, Write};
-            io::stdout().flush()?;
-            
-            let mut input = String::new();
-            io::stdin().read_line(&mut input)?;
-            let input = input.trim().to_lowercase();
-            
-            if input != "y" && input != "yes" {
-                println!("Operation cancelled.");
-                return Ok(());
+        Commands::Doctor { project, fix, dryrun } => {
+            changes_planned = commands::doctor::diagnose_project(project, fix, dryrun)? && (dryrun || !fix);
+        }
+        Commands::NormalizePaths { project, base, dryrun } => {
+            changes_planned = commands::paths::normalize_paths(project, base, dryrun)? && dryrun;
+        }
+        Commands::Enumerate { project, to_wildcards, dryrun } => {
+            changes_planned = commands::files::enumerate_project_files(project, to_wildcards, dryrun)? && dryrun;
+        }
+        Commands::Guid { action: None, project, long } => {
+            commands::guid::print_project_guid(project.unwrap_or_default(), long)?;
+        }
+        Commands::Guid { action: Some(GuidAction::Regen { project, solution, filters, dryrun }), .. } => {
+            changes_planned = commands::guid::regenerate_project_guid(project, solution, filters, dryrun)? && dryrun;
+        }
+        Commands::Guid { action: Some(GuidAction::List { solution }), .. } => {
+            commands::guid::list_solution_guids(solution)?;
+        }
+        Commands::Guid { action: Some(GuidAction::Dedupe { solution, dryrun }), .. } => {
+            changes_planned = commands::guid::dedupe_solution_guids(solution, dryrun)? && dryrun;
+        }
+        Commands::Lookup { guid, name, root } => {
+            commands::guid::lookup_projects(guid, name, root)?;
+        }
+        Commands::Patch { action } => match action {
+            PatchAction::Apply { patch, project, dryrun } => {
+                changes_planned = commands::patch::apply_patch(patch, project, dryrun)? && dryrun;
             }
+            PatchAction::Revert { patch, project, dryrun } => {
+                changes_planned = commands::patch::revert_patch(patch, project, dryrun)? && dryrun;
+            }
+        },
+        Commands::Filters { action } => match action {
+            FiltersAction::Export { layout, project } => {
+                commands::filters::export_filter_layout(layout, project)?;
+            }
+            FiltersAction::Apply { layout, project, dryrun } => {
+                changes_planned = commands::filters::apply_filter_layout(layout, project, dryrun)? && dryrun;
+            }
+            FiltersAction::Flatten { project, dryrun } => {
+                changes_planned = commands::filters::flatten_filters(project, dryrun)? && dryrun;
+            }
+            FiltersAction::Reroot { prefix, project, dryrun } => {
+                changes_planned = commands::filters::reroot_filters(project, prefix, dryrun)? && dryrun;
+            }
+            FiltersAction::PruneEmpty { project, dryrun } => {
+                changes_planned = commands::filters::prune_empty_filters(project, dryrun)? && dryrun;
+            }
+            FiltersAction::Create { name, project, dryrun } => {
+                changes_planned = commands::filters::create_filter(project, name, dryrun)? && dryrun;
+            }
+        },
+        Commands::Run { script, dryrun } => {
+            changes_planned = commands::script::run_script(script, dryrun)? && dryrun;
         }
-        
-        // Reload filter file (since rename_filter modified it) and perform merge
-        let mut filter_file = FilterFile::load(&filter_path)?;
-        let moved_files = filter_file.merge_filters(&from, &to)?;
-        filter_file.save()?;
-        
-        println!("✅ Successfully merged filter '{}' into '{}'", from, to);
-        println!("📁 {} files moved:", moved_files.len());
-        for file in &moved_files {
-            println!("  - {} → {}", file, to);
+        Commands::New { project, template, name, dryrun } => {
+            changes_planned = commands::new::create_project(project, &template, name, dryrun)? && dryrun;
         }
-    } else {
-        // Simple rename - no conflict
-        filter_file.save()?;
-        
-        println!("✅ Successfully renamed filter '{}' to '{}'", from, to);
-        println!("📁 {} files moved:", renamed_files.len());
-        for file in &renamed_files {
-            println!("  - {} → {}", file, to);
+        Commands::Generate { name, template, from, include_ext, project, exclude_dirs, gitignore, dryrun } => {
+            let mut excluded_dirs: Vec<String> = commands::DEFAULT_EXCLUDED_DIRS.iter().map(|d| d.to_string()).collect();
+            excluded_dirs.extend(exclude_dirs);
+            let project = project.unwrap_or_else(|| PathBuf::from(format!("{}.vcxproj", name)));
+            changes_planned = commands::new::generate_project(project, &template, name, from, &include_ext, excluded_dirs, gitignore, dryrun)? && dryrun;
         }
-    }
-    
-    println!("Successfully updated {}", filter_path.display());
-    Ok(())
-}
-
-fn add_include_directory(project_path: PathBuf, include_path: String) -> Result<()> {
-    println!("Adding include directory '{}' to project: {}", include_path, project_path.display());
-    
-    let mut vcxproj = VcxprojFile::load(&project_path)?;
-    let modified_configs = vcxproj.add_include_directory(&include_path)?;
-    vcxproj.save()?;
-    
-    if modified_configs.is_empty() {
-        println!("⚠️  No configurations found to modify");
-    } else {
-        println!("✅ Successfully added include directory to {} configurations:", modified_configs.len());
-        for config in &modified_configs {
-            println!("  - {}", config);
+        Commands::Explain { project, template } => {
+            commands::view::explain_project(project, template)?;
         }
-    }
-    
-    Ok(())
-}
-
-fn add_library_directory(project_path: PathBuf, lib_path: String) -> Result<()> {
-    println!("Adding library directory '{}' to project: {}", lib_path, project_path.display());
-    
-    let mut vcxproj = VcxprojFile::load(&project_path)?;
-    let modified_configs = vcxproj.add_library_directory(&lib_path)?;
-    vcxproj.save()?;
-    
-    if modified_configs.is_empty() {
-        println!("⚠️  No configurations found to modify");
-    } else {
-        println!("✅ Successfully added library directory to {} configurations:", modified_configs.len());
-        for config in &modified_configs {
-            println!("  - {}", config);
+        Commands::UpgradeToolset { project, solution, toolset, sdk, tools_version, dryrun } => {
+            changes_planned = commands::config::upgrade_toolset(project, solution, toolset, sdk, tools_version, dryrun)? && dryrun;
         }
-    }
-    
-    Ok(())
-}
-
-fn add_library_dependency(project_path: PathBuf, lib_name: String) -> Result<()> {
-    println!("Adding library dependency '{}' to project: {}", lib_name, project_path.display());
-    
-    let mut vcxproj = VcxprojFile::load(&project_path)?;
-    let modified_configs = vcxproj.add_library_dependency(&lib_name)?;
-    vcxproj.save()?;
-    
-    if modified_configs.is_empty() {
-        println!("⚠️  No configurations found to modify");
-    } else {
-        println!("✅ Successfully added library dependency to {} configurations:", modified_configs.len());
-        for config in &modified_configs {
-            println!("  - {}", config);
+        Commands::SetSdk { project, solution, value, latest: _, dryrun } => {
+            // clap requires --value unless --latest is given
+            let sdk = match value {
+                Some(value) => value,
+                None => {
+                    let latest_sdk = vs::latest_windows_sdk()?;
+                    info!("Newest installed Windows SDK: {}", latest_sdk);
+                    latest_sdk
+                }
+            };
+            changes_planned = commands::config::upgrade_toolset(project, solution, None, Some(sdk), None, dryrun)? && dryrun;
+        }
+        Commands::Audit { check: Some(AuditAction::Case { project }), .. } => {
+            commands::audit::audit_case(project, output::is_json())?;
+        }
+        Commands::Audit { check: None, directory, solution, project } => {
+            commands::audit::audit_projects(directory, solution, project, output::is_json())?;
+        }
+        Commands::Export { format_name, format, list, project, solution, output, config, tasks, dryrun } => {
+            changes_planned = commands::export::export_projects(format.or(format_name), list, project, solution, output, config, tasks, dryrun)? && dryrun;
+        }
+        Commands::SetRuntime { project, value, config, platform, dryrun } => {
+            changes_planned = commands::config::set_runtime(project, value, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::Config { action: ConfigAction::CopySettings { project, from, to, only, dryrun } } => {
+            changes_planned = commands::settings::copy_config_settings(project, from, to, only, dryrun)? && dryrun;
+        }
+        Commands::SetOptimization { project, preset, config, platform, dryrun } => {
+            changes_planned = commands::config::set_optimization(project, preset, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::CodeAnalysis { project, solution, prefast, run_on_build, ruleset, config, platform, dryrun } => {
+            let settings = commands::config::CodeAnalysisSettings { prefast: prefast.map(|v| v == "on"), run_on_build: run_on_build.map(|v| v == "on"), ruleset };
+            changes_planned = commands::config::set_code_analysis(project, solution, settings, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::SetCharset { project, solution, value, config, platform, dryrun } => {
+            changes_planned = commands::config::set_character_set(project, solution, value, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::SetConformance { project, solution, value, config, platform, dryrun } => {
+            changes_planned = commands::config::set_conformance_mode(project, solution, value, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::SetBuildPerf { project, solution, mp, incremental_link, debug_fastlink, config, platform, dryrun } => {
+            let settings = commands::config::BuildPerfSettings {
+                mp: mp.map(|v| v == "on"),
+                incremental_link: incremental_link.map(|v| v == "on"),
+                debug_fastlink: debug_fastlink.map(|v| v == "on"),
+            };
+            changes_planned = commands::config::set_build_perf(project, solution, settings, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::Hardening { action: HardeningAction::Set { project, solution, spectre, cfg, config, platform, dryrun } } => {
+            changes_planned = commands::config::set_hardening(project, solution, spectre, cfg, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::Asan { action: AsanAction::Enable { project, config, platform, fuzzer, dryrun } } => {
+            changes_planned = commands::asan::set_address_sanitizer(project, commands::config_selector(config, platform)?, true, fuzzer, dryrun)? && dryrun;
+        }
+        Commands::Asan { action: AsanAction::Disable { project, config, platform, dryrun } } => {
+            changes_planned = commands::asan::set_address_sanitizer(project, commands::config_selector(config, platform)?, false, false, dryrun)? && dryrun;
+        }
+        Commands::SetType { project, value, dryrun } => {
+            changes_planned = commands::config::set_configuration_type(project, value, dryrun)? && dryrun;
+        }
+        Commands::SetNmake { project, build, rebuild, clean, output, config, platform, dryrun } => {
+            let commands = [("NMakeBuildCommandLine", build), ("NMakeReBuildCommandLine", rebuild), ("NMakeCleanCommandLine", clean), ("NMakeOutput", output)];
+            changes_planned = commands::settings::set_nmake_properties(project, &commands, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::CustomBuild { action: CustomBuildAction::Add { project, file, command, outputs, inputs, message, filter, config, platform, dryrun } } => {
+            let step = [("Command", Some(command)), ("Outputs", Some(outputs)), ("AdditionalInputs", inputs), ("Message", message)];
+            changes_planned = commands::custom_build::add_custom_build_items(project, file, &step, filter, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::CustomBuild { action: CustomBuildAction::Set { project, glob, command, outputs, inputs, message, config, platform, dryrun } } => {
+            let step = [("Command", command), ("Outputs", outputs), ("AdditionalInputs", inputs), ("Message", message)];
+            changes_planned = commands::custom_build::set_custom_build_steps(project, glob, &step, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::Shader { action: ShaderAction::Set { project, glob, entry_point, shader_type, model, object_file, config, platform, dryrun } } => {
+            let settings = [("EntryPointName", entry_point), ("ShaderType", shader_type), ("ShaderModel", model), ("ObjectFileOutput", object_file)];
+            changes_planned = commands::custom_build::set_shader_properties(project, glob, &settings, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::Cuda { action: CudaAction::Enable { project, version, dryrun } } => {
+            changes_planned = commands::toolkits::enable_cuda(project, version, dryrun)? && dryrun;
+        }
+        Commands::Qt { action: QtAction::Enable { project, modules, install, dryrun } } => {
+            changes_planned = commands::toolkits::enable_qt(project, modules, install, dryrun)? && dryrun;
+        }
+        Commands::Resource { action: ResourceAction::Add { project, rc, manifest, filter, dryrun } } => {
+            changes_planned = commands::resources::add_resources(project, rc, manifest, filter, dryrun)? && dryrun;
+        }
+        Commands::Format { project, merge_itemgroups, check, dryrun } => {
+            changes_planned = commands::format::format_project(project, merge_itemgroups, check, dryrun || check)? && (dryrun || check);
+        }
+        Commands::Sort { project, check, dryrun } => {
+            changes_planned = commands::format::sort_project(project, check, dryrun || check)? && (dryrun || check);
+        }
+        Commands::Split { project, filter, to, name, dryrun } => {
+            changes_planned = commands::split::split_project(project, filter, to, name, dryrun)? && dryrun;
+        }
+        Commands::ImportShared { project, items, dryrun } => {
+            changes_planned = commands::imports::import_shared_items(project, items, dryrun)? && dryrun;
+        }
+        Commands::Repair { project, dryrun } => {
+            changes_planned = commands::format::repair_project(project, dryrun)? && dryrun;
+        }
+        Commands::Vcpkg { action: VcpkgAction::Enable { project, triplet, manifest, root, dryrun } } => {
+            changes_planned = commands::vcpkg::enable_vcpkg(project, triplet, manifest, root, dryrun)? && dryrun;
+        }
+        Commands::Vcpkg { action: VcpkgAction::Add { ports, project, triplet, root, dryrun } } => {
+            changes_planned = commands::vcpkg::add_vcpkg_ports(project, ports, triplet, root, dryrun)? && dryrun;
+        }
+        Commands::Nuget { action: NugetAction::List { project } } => {
+            commands::nuget::list_nuget_packages(project)?;
+        }
+        Commands::Nuget { action: NugetAction::Add { package, version, project, packages_dir, dryrun } } => {
+            changes_planned = commands::nuget::add_nuget_package(project, package, version, packages_dir, dryrun)? && dryrun;
+        }
+        Commands::Nuget { action: NugetAction::Remove { package, project, dryrun } } => {
+            changes_planned = commands::nuget::remove_nuget_package(project, package, dryrun)? && dryrun;
+        }
+        Commands::Props { action: PropsAction::List { project } } => {
+            commands::props::list_property_sheets(project)?;
+        }
+        Commands::Props { action: PropsAction::Add { project, sheet, config, platform, dryrun } } => {
+            changes_planned = commands::props::add_property_sheet(project, sheet, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::Props { action: PropsAction::Remove { project, sheet, config, platform, dryrun } } => {
+            changes_planned = commands::props::remove_property_sheet(project, sheet, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::Props { action: PropsAction::Create { project, sheet, settings, config, dryrun } } => {
+            changes_planned = commands::props::create_property_sheet(project, sheet, settings, config, dryrun)? && dryrun;
+        }
+        Commands::Imports { action: ImportsAction::List { project } } => {
+            commands::imports::list_imports(project)?;
+        }
+        Commands::Imports { action: ImportsAction::Add { project, import, group, before, after, optional, dryrun } } => {
+            let position = match (group, before, after) {
+                (Some(group), _, _) => commands::imports::ImportPosition::Group(group),
+                (None, Some(anchor), _) => commands::imports::ImportPosition::Before(anchor),
+                (None, None, Some(anchor)) => commands::imports::ImportPosition::After(anchor),
+                (None, None, None) => commands::imports::ImportPosition::Default,
+            };
+            changes_planned = commands::imports::add_import(project, import, position, optional, dryrun)? && dryrun;
+        }
+        Commands::Imports { action: ImportsAction::Remove { project, import, dryrun } } => {
+            changes_planned = commands::imports::remove_import(project, import, dryrun)? && dryrun;
+        }
+        Commands::User { action: UserAction::Init { project, dryrun } } => {
+            changes_planned = commands::user::init_user_file(project, dryrun)? && dryrun;
+        }
+        Commands::User { action: UserAction::Show { project } } => {
+            commands::user::show_user_file(project)?;
+        }
+        Commands::User { action: UserAction::Set { project, command, args, working_dir, env, config, platform, dryrun } } => {
+            let env = (!env.is_empty()).then(|| env.join("\n"));
+            let values = [command, args, working_dir, env];
+            changes_planned = commands::user::set_user_debugger(project, values, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::User { action: UserAction::AddPath { project, value, prepend, config, platform, dryrun } } => {
+            changes_planned = commands::user::add_user_path(project, value, prepend, commands::config_selector(config, platform)?, dryrun)? && dryrun;
+        }
+        Commands::Refactor { action: RefactorAction::ExtractProps { solution, projects, sheet, dryrun } } => {
+            changes_planned = commands::props::extract_shared_props(solution, projects, sheet, dryrun)? && dryrun;
+        }
+        Commands::Sln { action: SlnAction::AddProject { solution, project, folder, dryrun } } => {
+            changes_planned = commands::sln::add_project_to_solution(solution, project, folder, dryrun)? && dryrun;
+        }
+        Commands::Sln { action: SlnAction::RemoveProject { solution, project, dryrun } } => {
+            changes_planned = commands::sln::remove_project_from_solution(solution, project, dryrun)? && dryrun;
+        }
+        Commands::Sln { action: SlnAction::Deps { project, solution } } => {
+            commands::sln::show_dependencies(project, solution)?;
+        }
+        Commands::Sln { action: SlnAction::SetDependency { solution, project, depends_on, remove, dryrun } } => {
+            changes_planned = commands::sln::set_solution_dependencies(solution, project, depends_on, remove, dryrun)? && dryrun;
         }
     }
-    
-    Ok(())
+
+    Ok(changes_planned)
 }
@@ -0,0 +1,99 @@
+use anyhow::{Context, Result};
+use crate::vcxproj::{attribute_value, write_file};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The `packages.config` of a project restored the classic way into a packages folder
+#[derive(Debug)]
+pub struct PackagesConfig {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+impl PackagesConfig {
+    /// Location of the packages.config next to `project_path`
+    pub fn path_for(project_path: &Path) -> PathBuf {
+        project_path.parent().unwrap_or_else(|| Path::new(".")).join("packages.config")
+    }
+
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read packages.config: {}", path.display()))?;
+
+        Ok(Self { path, content })
+    }
+
+    /// An empty packages.config, for the first package of a project
+    pub fn new(path: PathBuf) -> Self {
+        let content = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<packages>\n</packages>\n".to_string();
+        Self { path, content }
+    }
+
+    /// The id and version of every `<package>` entry
+    pub fn packages(&self) -> Vec<(String, String)> {
+        self.content
+            .lines()
+            .map(str::trim)
+            .filter(|line| line.starts_with("<package "))
+            .filter_map(|line| {
+                let id = attribute_value(line, "id")?;
+                Some((id.to_string(), attribute_value(line, "version").unwrap_or_default().to_string()))
+            })
+            .collect()
+    }
+
+    /// Adds a package entry, or updates its version. Returns false when it is already listed at `version`.
+    pub fn set_package(&mut self, id: &str, version: &str) -> Result<bool> {
+        let entry = format!("  <package id=\"{}\" version=\"{}\" targetFramework=\"native\" />", id, version);
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        match lines.iter().position(|line| is_package(line, id)) {
+            Some(k) if attribute_value(lines[k].trim(), "version") == Some(version) => return Ok(false),
+            Some(k) => lines[k] = entry,
+            None => {
+                let end = lines
+                    .iter()
+                    .rposition(|line| line.trim().starts_with("</packages>"))
+                    .ok_or_else(|| anyhow::anyhow!("No closing </packages> tag in {}", self.path.display()))?;
+                lines.insert(end, entry);
+            }
+        }
+
+        self.content = lines.join("\n") + "\n";
+        Ok(true)
+    }
+
+    /// Removes a package entry. Returns false when it is not listed.
+    pub fn remove_package(&mut self, id: &str) -> bool {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let count = lines.len();
+        lines.retain(|line| !is_package(line, id));
+        if lines.len() == count {
+            return false;
+        }
+
+        self.content = lines.join("\n") + "\n";
+        true
+    }
+
+    pub fn save(&self) -> Result<bool> {
+        write_file(&self.path, &self.content)
+            .with_context(|| format!("Failed to write packages.config: {}", self.path.display()))
+    }
+}
+
+fn is_package(line: &str, id: &str) -> bool {
+    let trimmed = line.trim();
+    trimmed.starts_with("<package ") && attribute_value(trimmed, "id").is_some_and(|p| p.eq_ignore_ascii_case(id))
+}
+
+/// Whether an imported path is a build file of package `id`, e.g.
+/// `..\packages\Microsoft.Windows.CppWinRT.2.0.230706.1\build\native\Microsoft.Windows.CppWinRT.targets`
+pub fn is_package_import(project: &str, id: &str) -> bool {
+    let file_name = project.rsplit(['\\', '/']).next().unwrap_or(project);
+    let stem = file_name
+        .strip_suffix(".props")
+        .or_else(|| file_name.strip_suffix(".targets"))
+        .unwrap_or_default();
+    stem.eq_ignore_ascii_case(id) && project.replace('/', "\\").to_lowercase().contains("\\build\\")
+}
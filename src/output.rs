@@ -0,0 +1,327 @@
+use log::{Level, LevelFilter, Log, Metadata, Record};
+use serde_json::json;
+use std::cell::RefCell;
+use std::io::Write;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+
+/// Presents the tool's log records as console output: progress and results (info) and warnings go to
+/// stdout unchanged, errors to stderr, and debug/trace details to stderr prefixed with their level.
+/// Records from dependencies are dropped so `--verbose` only shows this tool's own details.
+/// With `--json` the text output of commands that change files is replaced by the command's
+/// `Report`, which collects the warnings.
+struct Presenter;
+
+static PRESENTER: Presenter = Presenter;
+
+static JSON: AtomicBool = AtomicBool::new(false);
+
+static REPORTING: AtomicBool = AtomicBool::new(false);
+
+static PLAIN: AtomicBool = AtomicBool::new(false);
+
+/// Longest line printed with `--plain`; longer messages wrap at spaces
+const PLAIN_WIDTH: usize = 80;
+
+static REPORT: Mutex<Report> = Mutex::new(Report::new());
+
+thread_local! {
+    /// Output held back by `capture` on this thread
+    static CAPTURED: RefCell<Option<Vec<Captured>>> = const { RefCell::new(None) };
+}
+
+/// A piece of output held back by `capture`
+#[derive(Debug)]
+enum Captured {
+    Record(Level, String),
+    Line(String),
+    Report(Change, Vec<String>),
+}
+
+/// What a command printed and reported while its output was captured, to be printed with `print`
+#[derive(Debug, Default)]
+pub struct CapturedOutput(Vec<Captured>);
+
+/// What a command changed (or would change with `--dryrun`), printed as one JSON object with `--json`
+#[derive(Debug)]
+struct Report {
+    files_added: Vec<String>,
+    files_removed: Vec<String>,
+    files_modified: Vec<String>,
+    files_moved: Vec<String>,
+    filters_created: Vec<String>,
+    filters_removed: Vec<String>,
+    configs_modified: Vec<String>,
+    files_written: Vec<String>,
+    warnings: Vec<String>,
+}
+
+/// A kind of change reported to the `--json` result
+#[derive(Debug, Clone, Copy)]
+pub enum Change {
+    /// Project items added
+    FileAdded,
+    /// Project items removed
+    FileRemoved,
+    /// Project items whose metadata changed
+    FileModified,
+    /// Project items moved to another filter
+    FileMoved,
+    FilterCreated,
+    FilterRemoved,
+    /// Configurations (e.g. "Debug|x64") whose settings changed
+    ConfigModified,
+    /// Files written to disk
+    FileWritten,
+}
+
+impl Report {
+    const fn new() -> Self {
+        Self {
+            files_added: Vec::new(),
+            files_removed: Vec::new(),
+            files_modified: Vec::new(),
+            files_moved: Vec::new(),
+            filters_created: Vec::new(),
+            filters_removed: Vec::new(),
+            configs_modified: Vec::new(),
+            files_written: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    fn list(&mut self, change: Change) -> &mut Vec<String> {
+        match change {
+            Change::FileAdded => &mut self.files_added,
+            Change::FileRemoved => &mut self.files_removed,
+            Change::FileModified => &mut self.files_modified,
+            Change::FileMoved => &mut self.files_moved,
+            Change::FilterCreated => &mut self.filters_created,
+            Change::FilterRemoved => &mut self.filters_removed,
+            Change::ConfigModified => &mut self.configs_modified,
+            Change::FileWritten => &mut self.files_written,
+        }
+    }
+}
+
+impl Log for Presenter {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.level() <= log::max_level() && metadata.target().starts_with(env!("CARGO_CRATE_NAME"))
+    }
+
+    fn log(&self, record: &Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+        if let Some(Captured::Record(level, text)) = hold_back(Captured::Record(record.level(), record.args().to_string())) {
+            present(level, &text);
+        }
+    }
+
+    fn flush(&self) {
+        let _ = std::io::stdout().flush();
+    }
+}
+
+/// Prints a log record's message
+fn present(level: Level, text: &str) {
+    match level {
+        Level::Info if is_reporting() => {}
+        Level::Warn if is_reporting() => {
+            let warning = text.trim().trim_start_matches("⚠️").trim_start();
+            REPORT.lock().unwrap().warnings.push(warning.to_string());
+        }
+        // In pipe mode stdout carries the project, so messages go to stderr
+        Level::Info | Level::Warn if crate::pipe::is_stdout() => eprintln!("{}", message(text)),
+        Level::Info | Level::Warn => println!("{}", message(text)),
+        Level::Error => eprintln!("{}", message(text)),
+        level => eprintln!("[{}] {}", level.as_str().to_lowercase(), message(text)),
+    }
+}
+
+/// Keeps `output` for `capture` when this thread's output is captured, otherwise hands it back to
+/// be printed right away
+fn hold_back(output: Captured) -> Option<Captured> {
+    CAPTURED.with_borrow_mut(|captured| match captured {
+        Some(captured) => {
+            captured.push(output);
+            None
+        }
+        None => Some(output),
+    })
+}
+
+/// Runs `f` with this thread's output (log records, result lines and report items) held back, so
+/// work done in parallel can be printed in a fixed order afterwards
+pub fn capture<T>(f: impl FnOnce() -> T) -> (T, CapturedOutput) {
+    let outer = CAPTURED.with_borrow_mut(|captured| captured.replace(Vec::new()));
+    let result = f();
+    let output = CAPTURED.with_borrow_mut(|captured| std::mem::replace(captured, outer)).unwrap_or_default();
+    (result, CapturedOutput(output))
+}
+
+/// Whether this thread's output is being captured, which rules out prompting the user
+pub fn is_captured() -> bool {
+    CAPTURED.with_borrow(Option::is_some)
+}
+
+impl CapturedOutput {
+    /// Prints (or, inside another `capture`, passes on) the output in the order it was produced
+    pub fn print(self) {
+        for output in self.0 {
+            match hold_back(output) {
+                Some(Captured::Record(level, text)) => present(level, &text),
+                Some(Captured::Line(line)) => println!("{}", line),
+                Some(Captured::Report(change, items)) => report(change, items),
+                None => {}
+            }
+        }
+    }
+}
+
+/// Prints a line of a command's result (not a message) on stdout, held back like the messages while
+/// output is captured
+pub fn print_line(line: impl Into<String>) {
+    if let Some(Captured::Line(line)) = hold_back(Captured::Line(line.into())) {
+        println!("{}", line);
+    }
+}
+
+/// Installs the presenter. `Error` is `--quiet` (nothing but errors), `Info` the default output.
+/// `json` is the `--json` flag; `report` replaces the text output with the JSON report printed by
+/// `print_report`, for commands that change files.
+pub fn init(level: LevelFilter, json: bool, report: bool) {
+    // Only fails when a logger is already installed, which leaves that one in place
+    let _ = log::set_logger(&PRESENTER);
+    // Warnings are still needed for the report when the text output is off
+    log::set_max_level(if report { level.max(LevelFilter::Warn) } else { level });
+    JSON.store(json, Ordering::Relaxed);
+    REPORTING.store(report, Ordering::Relaxed);
+}
+
+/// Whether `--json` output was requested
+pub fn is_json() -> bool {
+    JSON.load(Ordering::Relaxed)
+}
+
+/// Whether the text output is replaced by the `--json` report
+fn is_reporting() -> bool {
+    REPORTING.load(Ordering::Relaxed)
+}
+
+/// Screen-reader friendly output (`--plain`): no emoji or box drawing, textual labels and bounded lines
+pub fn set_plain(plain: bool) {
+    PLAIN.store(plain, Ordering::Relaxed);
+}
+
+pub fn is_plain() -> bool {
+    PLAIN.load(Ordering::Relaxed)
+}
+
+/// The text of a record, rewritten with `--plain`
+fn message(text: &str) -> String {
+    if is_plain() {
+        plain_text(text)
+    } else {
+        text.to_string()
+    }
+}
+
+/// Rewrites a message for `--plain`: warning and error symbols become words, other symbols and box
+/// drawing are dropped, arrows become ASCII, alignment padding is collapsed and long lines wrap
+fn plain_text(message: &str) -> String {
+    message.split('\n').map(plain_line).collect::<Vec<_>>().join("\n")
+}
+
+fn plain_line(line: &str) -> String {
+    let indent: String = line.chars().take_while(|c| *c == ' ').collect();
+    let mut text = String::new();
+    for c in line[indent.len()..].chars() {
+        match c {
+            '⚠' => text.push_str("Warning:"),
+            '❌' => text.push_str("Error:"),
+            '→' => text.push_str("->"),
+            '←' => text.push_str("<-"),
+            // Box drawing and block elements
+            '\u{2500}'..='\u{259F}' => text.push(' '),
+            // Other arrows, technical symbols, geometric shapes, dingbats, emoji and their modifiers
+            '\u{2190}'..='\u{21FF}'
+            | '\u{2300}'..='\u{23FF}'
+            | '\u{25A0}'..='\u{27BF}'
+            | '\u{2B00}'..='\u{2BFF}'
+            | '\u{1F000}'..='\u{1FAFF}'
+            | '\u{FE0E}'
+            | '\u{FE0F}'
+            | '\u{200D}' => {}
+            c => text.push(c),
+        }
+    }
+    let words: Vec<&str> = text.split_whitespace().collect();
+    if words.is_empty() {
+        return String::new();
+    }
+
+    let mut lines = vec![indent.clone()];
+    for word in words {
+        let current = lines.last_mut().unwrap();
+        let length = current.chars().count();
+        if length > indent.len() && length + 1 + word.chars().count() > PLAIN_WIDTH {
+            lines.push(format!("{}  {}", indent, word));
+        } else {
+            if length > indent.len() {
+                current.push(' ');
+            }
+            current.push_str(word);
+        }
+    }
+    lines.join("\n")
+}
+
+/// Adds items to the `--json` report (ignored without it). Items already reported are kept once.
+pub fn report<T: ToString>(change: Change, items: impl IntoIterator<Item = T>) {
+    if !is_reporting() {
+        return;
+    }
+    let items: Vec<String> = items.into_iter().map(|item| item.to_string()).collect();
+    let Some(Captured::Report(change, items)) = hold_back(Captured::Report(change, items)) else {
+        return;
+    };
+    let mut report = REPORT.lock().unwrap();
+    let list = report.list(change);
+    for item in items {
+        if !list.contains(&item) {
+            list.push(item);
+        }
+    }
+}
+
+/// Prints the collected report as a single JSON object on stdout
+pub fn print_report(command: &str, dryrun: bool) -> anyhow::Result<()> {
+    let report = REPORT.lock().unwrap();
+    let json = json!({
+        "command": command,
+        "dryrun": dryrun,
+        "files_added": report.files_added,
+        "files_removed": report.files_removed,
+        "files_modified": report.files_modified,
+        "files_moved": report.files_moved,
+        "filters_created": report.filters_created,
+        "filters_removed": report.filters_removed,
+        "configs_modified": report.configs_modified,
+        "files_written": report.files_written,
+        "warnings": report.warnings,
+    });
+    println!("{}", serde_json::to_string_pretty(&json)?);
+    Ok(())
+}
+
+/// The level selected by the global `-q`, `-v`/`-vv` and `--log-level` flags (the explicit level wins)
+pub fn level_from_flags(quiet: bool, verbose: u8, log_level: Option<&str>) -> LevelFilter {
+    match (log_level, quiet, verbose) {
+        (Some(level), _, _) => level.parse().unwrap_or(LevelFilter::Info),
+        (None, true, _) => LevelFilter::Error,
+        (None, false, 0) => LevelFilter::Info,
+        (None, false, 1) => LevelFilter::Debug,
+        (None, false, _) => LevelFilter::Trace,
+    }
+}
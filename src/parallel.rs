@@ -0,0 +1,111 @@
+use anyhow::{Context, Result};
+use rayon::prelude::*;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc;
+
+static KEEP_GOING: AtomicBool = AtomicBool::new(false);
+
+/// Go on with the other projects when one fails (`--keep-going`) instead of stopping at the first
+pub fn set_keep_going(keep_going: bool) {
+    KEEP_GOING.store(keep_going, Ordering::Relaxed);
+}
+
+/// Whether `--keep-going` was given
+pub fn keep_going() -> bool {
+    KEEP_GOING.load(Ordering::Relaxed)
+}
+
+/// Stack size of the worker threads, which run whole commands: the default of spawned threads is
+/// too small for the dispatch of a debug build
+const WORKER_STACK_SIZE: usize = 8 * 1024 * 1024;
+
+/// Starts the worker threads, processing up to `jobs` projects at once (`--jobs`), or one per CPU
+pub fn init(jobs: Option<usize>) -> Result<()> {
+    rayon::ThreadPoolBuilder::new()
+        .num_threads(jobs.unwrap_or(0))
+        .stack_size(WORKER_STACK_SIZE)
+        .build_global()
+        .context("Failed to start the worker threads")
+}
+
+/// The error of a command that went on past the projects it failed on (`--keep-going`): unlike other
+/// errors it doesn't undo what the command wrote for the other projects
+#[derive(Debug)]
+pub struct ProjectsFailed {
+    pub failed: usize,
+    pub total: usize,
+}
+
+impl std::fmt::Display for ProjectsFailed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} of {} projects failed", self.failed, self.total)
+    }
+}
+
+impl std::error::Error for ProjectsFailed {}
+
+impl ProjectsFailed {
+    /// Fails when any of the `total` projects failed
+    pub fn check(failed: usize, total: usize) -> Result<()> {
+        if failed > 0 {
+            return Err(Self { failed, total }.into());
+        }
+        Ok(())
+    }
+}
+
+/// Runs `f`, turning a panic (a bug an unusual project runs into) into an error, so it only fails
+/// the project it happened in
+pub fn catch_panic<T>(f: impl FnOnce() -> Result<T>) -> Result<T> {
+    std::panic::catch_unwind(std::panic::AssertUnwindSafe(f)).unwrap_or_else(|panic| {
+        let message = panic
+            .downcast_ref::<&str>()
+            .map(|message| message.to_string())
+            .or_else(|| panic.downcast_ref::<String>().cloned())
+            .unwrap_or_default();
+        Err(anyhow::anyhow!("Internal error: {}", message))
+    })
+}
+
+/// Runs `process` on every item, several at once on the worker threads when `parallel`, and hands
+/// each result to `finish` in the order of the items, right after printing what `process` logged for
+/// it, so the output reads as if they had been processed one after another. They are processed one
+/// after another on this thread with `--jobs 1`, or when this is already a worker: the history
+/// journal and the captured output are per thread, and a worker waiting for the items could be
+/// handed another one.
+pub fn for_each<T: Sync, R: Send>(
+    items: &[T],
+    parallel: bool,
+    process: impl Fn(usize, &T) -> Result<R> + Sync,
+    mut finish: impl FnMut(usize, Result<R>),
+) {
+    if !parallel || items.len() < 2 || rayon::current_num_threads() < 2 || rayon::current_thread_index().is_some() {
+        for (k, item) in items.iter().enumerate() {
+            finish(k, catch_panic(|| process(k, item)));
+        }
+        return;
+    }
+
+    let process = &process;
+    let (sender, receiver) = mpsc::channel();
+    std::thread::scope(|scope| {
+        scope.spawn(move || {
+            items.par_iter().enumerate().for_each_with(sender, |sender, (k, item)| {
+                let _ = sender.send((k, crate::output::capture(|| catch_panic(|| process(k, item)))));
+            });
+        });
+
+        // Results arrive as they complete; each is printed once every item before it has been
+        let mut done = BTreeMap::new();
+        let mut next = 0;
+        for (k, result) in receiver {
+            done.insert(k, result);
+            while let Some((result, output)) = done.remove(&next) {
+                output.print();
+                finish(next, result);
+                next += 1;
+            }
+        }
+    });
+}
@@ -0,0 +1,306 @@
+use anyhow::{Context, Result};
+use serde_json::{json, Value};
+use crate::history;
+use crate::output::{self, Change};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Directory (next to the project file) that records what each applied patch changed
+pub const PATCH_STATE_DIR: &str = ".vcprojm/patches";
+
+/// A set of optional local modifications declared in a TOML patch file:
+///
+/// ```toml
+/// name = "tracing"
+/// add_files = ["src/trace.cpp"]
+/// remove_files = ["src/trace_stub.cpp"]
+/// include_dirs = ["third_party/tracy/public"]
+/// lib_dirs = ["third_party/tracy/lib"]
+/// libs = ["TracyClient.lib"]
+///
+/// [[settings]]
+/// glob = "src/**/*.cpp"
+/// config = "Debug|x64"
+/// props = { PreprocessorDefinitions = "TRACY_ENABLE;%(PreprocessorDefinitions)" }
+/// ```
+#[derive(Debug, Default)]
+pub struct PatchFile {
+    pub name: String,
+    pub add_files: Vec<String>,
+    pub remove_files: Vec<String>,
+    pub include_dirs: Vec<String>,
+    pub lib_dirs: Vec<String>,
+    pub libs: Vec<String>,
+    pub settings: Vec<PatchSetting>,
+}
+
+/// Per-file metadata set on every ClCompile item matching `glob`
+#[derive(Debug)]
+pub struct PatchSetting {
+    pub glob: String,
+    pub config: Option<String>,
+    pub props: Vec<(String, String)>,
+}
+
+/// What applying a patch changed, so that reverting undoes exactly that and leaves unrelated edits alone
+#[derive(Debug, Default)]
+pub struct PatchState {
+    pub name: String,
+    /// Files added in the patch's labeled ItemGroup
+    pub added_files: Vec<String>,
+    /// Item type and the exact lines of every item the patch removed
+    pub removed_items: Vec<(String, Vec<String>)>,
+    pub include_dirs: Vec<ListAddition>,
+    pub lib_dirs: Vec<ListAddition>,
+    pub libs: Vec<ListAddition>,
+    pub settings: Vec<SettingChange>,
+}
+
+/// A value a patch added to a list such as AdditionalIncludeDirectories, with the conditions of the
+/// configurations that didn't list it before: reverting removes it from those only
+#[derive(Debug, Clone)]
+pub struct ListAddition {
+    pub value: String,
+    pub conditions: Vec<String>,
+}
+
+/// One per-file metadata value set by a patch, with the value it replaced (`None` if it was unset)
+#[derive(Debug, Clone)]
+pub struct SettingChange {
+    pub file: String,
+    pub name: String,
+    pub condition: Option<String>,
+    pub value: String,
+    pub previous: Option<String>,
+}
+
+/// Label of the ItemGroup that holds the files added by a patch
+pub fn item_group_label(patch_name: &str) -> String {
+    format!("vcprojm-patch:{}", patch_name)
+}
+
+impl PatchFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref();
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read patch file: {}", path.display()))?;
+        let table: toml::Table = content
+            .parse()
+            .with_context(|| format!("Invalid patch file: {}", path.display()))?;
+
+        let mut patch = PatchFile {
+            name: path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default(),
+            ..Default::default()
+        };
+        for (key, value) in &table {
+            match key.as_str() {
+                "name" => patch.name = toml_string(key, value)?,
+                "add_files" => patch.add_files = toml_string_list(key, value)?,
+                "remove_files" => patch.remove_files = toml_string_list(key, value)?,
+                "include_dirs" => patch.include_dirs = toml_string_list(key, value)?,
+                "lib_dirs" => patch.lib_dirs = toml_string_list(key, value)?,
+                "libs" => patch.libs = toml_string_list(key, value)?,
+                "settings" => {
+                    let settings = value
+                        .as_array()
+                        .ok_or_else(|| anyhow::anyhow!("'settings' must be an array of tables ([[settings]])"))?;
+                    for setting in settings {
+                        patch.settings.push(parse_setting(setting)?);
+                    }
+                }
+                _ => return Err(anyhow::anyhow!("Unknown key '{}' in patch file {}", key, path.display())),
+            }
+        }
+
+        if patch.name.is_empty() || patch.name.contains(['/', '\\']) {
+            return Err(anyhow::anyhow!("Invalid patch name '{}'", patch.name));
+        }
+        Ok(patch)
+    }
+}
+
+fn parse_setting(value: &toml::Value) -> Result<PatchSetting> {
+    let table = value
+        .as_table()
+        .ok_or_else(|| anyhow::anyhow!("Each [[settings]] entry must be a table"))?;
+
+    let glob = table
+        .get("glob")
+        .map(|v| toml_string("glob", v))
+        .transpose()?
+        .ok_or_else(|| anyhow::anyhow!("[[settings]] entry is missing 'glob'"))?;
+    let config = table.get("config").map(|v| toml_string("config", v)).transpose()?;
+
+    let mut props = Vec::new();
+    if let Some(prop_table) = table.get("props") {
+        let prop_table = prop_table
+            .as_table()
+            .ok_or_else(|| anyhow::anyhow!("'props' for '{}' must be a table of Name = \"value\"", glob))?;
+        for (name, value) in prop_table {
+            props.push((name.clone(), toml_string(name, value)?));
+        }
+    }
+    if props.is_empty() {
+        return Err(anyhow::anyhow!("[[settings]] entry for '{}' has no props", glob));
+    }
+
+    for key in table.keys() {
+        if !["glob", "config", "props"].contains(&key.as_str()) {
+            return Err(anyhow::anyhow!("Unknown key '{}' in [[settings]] entry for '{}'", key, glob));
+        }
+    }
+
+    Ok(PatchSetting { glob, config, props })
+}
+
+fn toml_string(key: &str, value: &toml::Value) -> Result<String> {
+    value
+        .as_str()
+        .map(|s| s.to_string())
+        .ok_or_else(|| anyhow::anyhow!("'{}' must be a string", key))
+}
+
+fn toml_string_list(key: &str, value: &toml::Value) -> Result<Vec<String>> {
+    value
+        .as_array()
+        .ok_or_else(|| anyhow::anyhow!("'{}' must be an array of strings", key))?
+        .iter()
+        .map(|v| toml_string(key, v))
+        .collect()
+}
+
+impl PatchState {
+    /// Location of the state file recording `patch_name` applied to `project_path`
+    pub fn path_for(project_path: &Path, patch_name: &str) -> PathBuf {
+        let project_name = project_path.file_stem().unwrap_or_default().to_string_lossy();
+        project_path
+            .parent()
+            .unwrap_or_else(|| Path::new("."))
+            .join(PATCH_STATE_DIR)
+            .join(format!("{}.{}.json", project_name, patch_name))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added_files.is_empty()
+            && self.removed_items.is_empty()
+            && self.include_dirs.is_empty()
+            && self.lib_dirs.is_empty()
+            && self.libs.is_empty()
+            && self.settings.is_empty()
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let content = fs::read_to_string(path)
+            .with_context(|| format!("Failed to read patch state: {}", path.display()))?;
+        let json: Value = serde_json::from_str(&content)
+            .with_context(|| format!("Invalid patch state: {}", path.display()))?;
+
+        let strings = |key: &str| -> Vec<String> {
+            json[key]
+                .as_array()
+                .map(|a| a.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+                .unwrap_or_default()
+        };
+        let additions = |key: &str| -> Vec<ListAddition> {
+            json[key]
+                .as_array()
+                .map(|a| {
+                    a.iter()
+                        .map(|addition| ListAddition {
+                            value: addition["value"].as_str().unwrap_or_default().to_string(),
+                            conditions: addition["conditions"]
+                                .as_array()
+                                .map(|c| c.iter().filter_map(|v| v.as_str().map(str::to_string)).collect())
+                                .unwrap_or_default(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default()
+        };
+        let removed_items = json["removed_items"]
+            .as_array()
+            .map(|items| {
+                items
+                    .iter()
+                    .map(|item| {
+                        let lines = item["lines"].as_array().map(|l| {
+                            l.iter().filter_map(|v| v.as_str().map(str::to_string)).collect()
+                        });
+                        (item["item_type"].as_str().unwrap_or_default().to_string(), lines.unwrap_or_default())
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        let settings = json["settings"]
+            .as_array()
+            .map(|changes| {
+                changes
+                    .iter()
+                    .map(|change| SettingChange {
+                        file: change["file"].as_str().unwrap_or_default().to_string(),
+                        name: change["name"].as_str().unwrap_or_default().to_string(),
+                        condition: change["condition"].as_str().map(str::to_string),
+                        value: change["value"].as_str().unwrap_or_default().to_string(),
+                        previous: change["previous"].as_str().map(str::to_string),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+
+        Ok(Self {
+            name: json["name"].as_str().unwrap_or_default().to_string(),
+            added_files: strings("added_files"),
+            removed_items,
+            include_dirs: additions("include_dirs"),
+            lib_dirs: additions("lib_dirs"),
+            libs: additions("libs"),
+            settings,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let json = json!({
+            "name": self.name,
+            "added_files": self.added_files,
+            "removed_items": self.removed_items.iter().map(|(item_type, lines)| json!({
+                "item_type": item_type,
+                "lines": lines,
+            })).collect::<Vec<_>>(),
+            "include_dirs": list_additions_json(&self.include_dirs),
+            "lib_dirs": list_additions_json(&self.lib_dirs),
+            "libs": list_additions_json(&self.libs),
+            "settings": self.settings.iter().map(|change| json!({
+                "file": change.file,
+                "name": change.name,
+                "condition": change.condition,
+                "value": change.value,
+                "previous": change.previous,
+            })).collect::<Vec<_>>(),
+        });
+        let content = serde_json::to_string_pretty(&json)?;
+
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir)
+                .with_context(|| format!("Failed to create patch state directory: {}", dir.display()))?;
+        }
+        history::record(path, &content)?;
+        history::replace_file(path, &content)
+            .with_context(|| format!("Failed to write patch state: {}", path.display()))?;
+        output::report(Change::FileWritten, [path.display()]);
+        Ok(())
+    }
+
+    /// Deletes the state file once the patch has been reverted
+    pub fn remove(path: &Path) -> Result<()> {
+        history::record_removal(path)?;
+        fs::remove_file(path)
+            .with_context(|| format!("Failed to remove patch state: {}", path.display()))
+    }
+}
+
+fn list_additions_json(additions: &[ListAddition]) -> Vec<Value> {
+    additions
+        .iter()
+        .map(|addition| json!({ "value": addition.value, "conditions": addition.conditions }))
+        .collect()
+}
@@ -0,0 +1,168 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// The project and filters files of pipe mode (`--stdin`/`--stdout`), kept in memory so the
+/// tool can run as a filter in pipelines and pre-commit hooks
+#[derive(Debug)]
+struct Pipe {
+    stdin: bool,
+    stdout: bool,
+    /// The project file: as read from stdin (or from disk on first load), then as last written
+    project: Option<String>,
+    /// The `--project` path; every other file is read from and written to disk as usual
+    project_path: PathBuf,
+    /// The filters file as last written, once a command has written it
+    filters: Option<String>,
+    /// Where the filters file is read from instead of next to the project, e.g. /dev/fd/3
+    filters_in: Option<PathBuf>,
+    /// Where the filters file goes after a `--stdout` run; without it, filters changes are dropped
+    filters_out: Option<PathBuf>,
+}
+
+static PIPE: Mutex<Option<Pipe>> = Mutex::new(None);
+
+/// Turns pipe mode on for the project at `project`, the command's `--project`. With `stdin` the
+/// project XML is read from standard input right away.
+pub fn enable(stdin: bool, stdout: bool, project: Option<&Path>, filters_in: Option<PathBuf>, filters_out: Option<PathBuf>) -> Result<()> {
+    if !stdin && !stdout {
+        return Ok(());
+    }
+    let Some(project_path) = project.filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("vcxproj"))) else {
+        return Err(anyhow::anyhow!("--stdin and --stdout take a single --project naming a .vcxproj file"));
+    };
+    let project_path = std::path::absolute(project_path).unwrap_or_else(|_| project_path.to_path_buf());
+    let mut project = None;
+    if stdin {
+        let mut content = String::new();
+        std::io::stdin().read_to_string(&mut content).context("Failed to read the project from stdin")?;
+        project = Some(content);
+    }
+    *PIPE.lock().unwrap() = Some(Pipe { stdin, stdout, project, project_path, filters_in, filters_out, filters: None });
+    Ok(())
+}
+
+/// Whether the pipe writes to stdout, so progress messages must go to stderr
+pub fn is_stdout() -> bool {
+    PIPE.lock().unwrap().as_ref().is_some_and(|pipe| pipe.stdout)
+}
+
+/// Whether `path` and `other` name the same file, however each is spelled
+fn same_path(path: &Path, other: &Path) -> bool {
+    std::path::absolute(path).is_ok_and(|path| path == other)
+}
+
+impl Pipe {
+    fn is_project(&self, path: &Path) -> bool {
+        same_path(path, &self.project_path)
+    }
+
+    fn is_filters(&self, path: &Path) -> bool {
+        same_path(path, &crate::vcxproj::filters_path(&self.project_path))
+    }
+}
+
+/// Fails for a write to a project, filters or solution file other than the piped project's:
+/// `--stdout` returns a single project, so a command that writes a second one cannot run in pipe mode
+pub fn check_writable(path: &Path) -> Result<()> {
+    let guard = PIPE.lock().unwrap();
+    let Some(pipe) = guard.as_ref() else {
+        return Ok(());
+    };
+    let name = path.to_string_lossy().to_lowercase();
+    let is_project_file = [".vcxproj", ".vcxproj.filters", ".vcxitems", ".vcxitems.filters", ".sln"].iter().any(|ext| name.ends_with(ext));
+    if is_project_file && !pipe.is_project(path) && !pipe.is_filters(path) {
+        return Err(anyhow::anyhow!(
+            "This command writes {} besides the --project; it cannot be used with --stdin or --stdout",
+            path.display()
+        ));
+    }
+    Ok(())
+}
+
+/// The content of `path` when pipe mode provides it, or `None` to read it from disk
+pub fn read(path: &Path) -> Option<Result<String>> {
+    let mut guard = PIPE.lock().unwrap();
+    let pipe = guard.as_mut()?;
+    if pipe.is_project(path) {
+        if pipe.project.is_none() {
+            // With only --stdout, the project is read from disk once and then lives in memory
+            match fs::read_to_string(path) {
+                Ok(content) => pipe.project = Some(content),
+                Err(err) => return Some(Err(err).with_context(|| format!("Failed to read vcxproj file: {}", path.display()))),
+            }
+        }
+        return pipe.project.clone().map(Ok);
+    }
+    if pipe.is_filters(path) {
+        if let Some(filters) = &pipe.filters {
+            return Some(Ok(filters.clone()));
+        }
+        let filters_in = pipe.filters_in.as_ref()?;
+        let content = fs::read_to_string(filters_in).with_context(|| format!("Failed to read filters file: {}", filters_in.display()));
+        if let Ok(content) = &content {
+            pipe.filters = Some(content.clone());
+        }
+        return Some(content);
+    }
+    None
+}
+
+/// Whether `path` exists, counting the files pipe mode provides and those at the `--git-ref` revision
+pub fn exists(path: &Path) -> bool {
+    if let Some(exists) = crate::gitref::exists(path) {
+        return exists;
+    }
+    let provided = PIPE.lock().unwrap().as_ref().is_some_and(|pipe| {
+        (pipe.is_project(path) && pipe.stdin) || (pipe.is_filters(path) && (pipe.filters.is_some() || pipe.filters_in.is_some()))
+    });
+    provided || path.exists()
+}
+
+/// Whether writes to `path` are kept in memory rather than written to disk
+pub fn takes(path: &Path) -> bool {
+    PIPE.lock().unwrap().as_ref().is_some_and(|pipe| pipe.stdout && (pipe.is_project(path) || pipe.is_filters(path)))
+}
+
+/// Keeps new content for `path` in memory. Returns whether it differs from what was there, or
+/// `None` when the write is for the disk. Writes to the project also update what later loads see.
+pub fn write(path: &Path, content: &str) -> Option<bool> {
+    let mut guard = PIPE.lock().unwrap();
+    let pipe = guard.as_mut()?;
+    let slot = if pipe.is_project(path) {
+        &mut pipe.project
+    } else if pipe.is_filters(path) {
+        &mut pipe.filters
+    } else {
+        return None;
+    };
+    let changed = slot.as_deref() != Some(content);
+    *slot = Some(content.to_string());
+    pipe.stdout.then_some(changed)
+}
+
+/// Ends a successful `--stdout` run: prints the project, changed or not, and writes the filters
+/// file to `--filters-out` if one was given
+pub fn finish() -> Result<()> {
+    let guard = PIPE.lock().unwrap();
+    let Some(pipe) = guard.as_ref().filter(|pipe| pipe.stdout) else {
+        return Ok(());
+    };
+    if let Some(project) = &pipe.project {
+        let mut stdout = std::io::stdout();
+        stdout.write_all(project.as_bytes()).and_then(|_| stdout.flush()).context("Failed to write the project to stdout")?;
+    }
+    if let Some(filters_out) = &pipe.filters_out {
+        let filters = match (&pipe.filters, &pipe.filters_in) {
+            (Some(filters), _) => Some(filters.clone()),
+            (None, Some(filters_in)) => fs::read_to_string(filters_in).ok(),
+            (None, None) => fs::read_to_string(crate::vcxproj::filters_path(&pipe.project_path)).ok(),
+        };
+        if let Some(filters) = filters {
+            fs::write(filters_out, filters).with_context(|| format!("Failed to write filters file: {}", filters_out.display()))?;
+        }
+    }
+    Ok(())
+}
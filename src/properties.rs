@@ -0,0 +1,159 @@
+use crate::condition;
+use crate::vcxproj::VcxprojFile;
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::path::{Path, MAIN_SEPARATOR};
+use std::sync::Mutex;
+
+/// Property values given with `--property NAME=VALUE`, which take precedence over everything else
+static OVERRIDES: Mutex<Vec<(String, String)>> = Mutex::new(Vec::new());
+
+/// Nesting limit for properties defined in terms of others, which also stops self-references
+const MAX_DEPTH: usize = 16;
+
+/// Sets the `--property NAME=VALUE` overrides
+pub fn set_overrides(overrides: Vec<(String, String)>) {
+    *OVERRIDES.lock().unwrap() = overrides;
+}
+
+/// Parses a `--property` argument
+pub fn parse_override(arg: &str) -> Result<(String, String), String> {
+    match arg.split_once('=') {
+        Some((name, value)) if !name.trim().is_empty() => Ok((name.trim().to_string(), value.to_string())),
+        _ => Err(format!("expected NAME=VALUE, got '{}'", arg)),
+    }
+}
+
+/// MSBuild property values for expanding `$(Name)` in a project's paths. In order of precedence:
+/// `--property` overrides, the project's own PropertyGroups for the configuration, defaults derived
+/// from the project and solution locations, and environment variables (as MSBuild does).
+#[derive(Debug, Default)]
+pub struct Properties {
+    /// Keyed by lowercase name, since MSBuild property names are case-insensitive
+    values: HashMap<String, String>,
+}
+
+impl Properties {
+    /// The properties of `vcxproj` built as `config` (e.g. "Debug|x64"); without one, the
+    /// `--property Configuration=...`/`Platform=...` overrides or else the first configuration is used
+    pub fn for_project(vcxproj: &VcxprojFile, config: Option<&str>) -> Self {
+        let mut properties = Self::default();
+        let overrides = OVERRIDES.lock().unwrap().clone();
+        let overridden = |name: &str| overrides.iter().rev().find(|(n, _)| n.eq_ignore_ascii_case(name)).map(|(_, v)| v.clone());
+
+        let first_config = vcxproj.get_configurations().into_iter().next().unwrap_or_default();
+        let config = config.unwrap_or(&first_config);
+        let (configuration, platform) = config.split_once('|').unwrap_or((config, ""));
+        let configuration = overridden("Configuration").unwrap_or_else(|| configuration.to_string());
+        let platform = overridden("Platform").unwrap_or_else(|| platform.to_string());
+
+        let absolute = vcxproj.path.canonicalize().unwrap_or_else(|_| vcxproj.path.clone());
+        let project_dir = absolute.parent().unwrap_or_else(|| Path::new("."));
+        let solution = find_solution(project_dir);
+        let solution_dir = solution.as_deref().and_then(Path::parent).unwrap_or(project_dir);
+        let project_name = vcxproj.project_name();
+        let with_separator = |dir: &Path| format!("{}{}", dir.display(), MAIN_SEPARATOR);
+        let file_name = |path: &Path| path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let file_stem = |path: &Path| path.file_stem().unwrap_or_default().to_string_lossy().into_owned();
+
+        let mut defaults = vec![
+            ("Configuration", configuration.clone()),
+            ("Platform", platform.clone()),
+            ("ProjectDir", with_separator(project_dir)),
+            ("ProjectPath", absolute.display().to_string()),
+            ("ProjectFileName", file_name(&absolute)),
+            ("ProjectName", project_name.clone()),
+            ("TargetName", project_name),
+            ("MSBuildProjectDirectory", project_dir.display().to_string()),
+            ("MSBuildProjectFullPath", absolute.display().to_string()),
+            ("MSBuildProjectName", file_stem(&absolute)),
+            ("MSBuildThisFileDirectory", with_separator(project_dir)),
+            ("SolutionDir", with_separator(solution_dir)),
+            // Visual Studio's defaults; Win32 builds go straight into $(Configuration)
+            ("OutDir", if platform.eq_ignore_ascii_case("Win32") { "$(SolutionDir)$(Configuration)\\" } else { "$(SolutionDir)$(Platform)\\$(Configuration)\\" }.to_string()),
+            ("IntDir", if platform.eq_ignore_ascii_case("Win32") { "$(Configuration)\\" } else { "$(Platform)\\$(Configuration)\\" }.to_string()),
+        ];
+        if let Some(solution) = &solution {
+            defaults.push(("SolutionPath", solution.display().to_string()));
+            defaults.push(("SolutionFileName", file_name(solution)));
+            defaults.push(("SolutionName", file_stem(solution)));
+        }
+        for (name, value) in defaults {
+            properties.values.insert(name.to_lowercase(), value);
+        }
+
+        // The project's own values for this configuration, e.g. an OutDir set in its PropertyGroups
+        let selects = |name: &str| match name {
+            _ if name.eq_ignore_ascii_case("Configuration") => Some(configuration.clone()),
+            _ if name.eq_ignore_ascii_case("Platform") => Some(platform.clone()),
+            _ => None,
+        };
+        for property in vcxproj.get_properties() {
+            let applies = property.condition.as_deref().is_none_or(|c| {
+                condition::parse(c).is_ok_and(|expr| condition::evaluate(&expr, &selects) == Some(true))
+            });
+            if applies {
+                properties.values.insert(property.name.to_lowercase(), property.value);
+            }
+        }
+
+        for (name, value) in overrides {
+            properties.values.insert(name.to_lowercase(), value);
+        }
+        properties
+    }
+
+    /// The value of a property with the properties it refers to expanded, if it is known
+    pub fn get(&self, name: &str) -> Option<String> {
+        self.resolve(name, 0)
+    }
+
+    fn resolve(&self, name: &str, depth: usize) -> Option<String> {
+        if depth > MAX_DEPTH {
+            return None;
+        }
+        let value = self
+            .values
+            .get(&name.to_lowercase())
+            .cloned()
+            .or_else(|| std::env::var(name).ok())?;
+        condition::expand(&value, &|inner| self.resolve(inner, depth + 1))
+    }
+
+    /// Expands every `$(Name)` in `text`. Unknown properties, and property functions like
+    /// `$([System.IO.Path]::Combine(...))`, are left as written and returned as the second value.
+    pub fn expand(&self, text: &str) -> (String, Vec<String>) {
+        let unknown = RefCell::new(Vec::new());
+        let expanded = condition::expand(text, &|name| {
+            Some(self.get(name).unwrap_or_else(|| {
+                unknown.borrow_mut().push(name.to_string());
+                format!("$({})", name)
+            }))
+        })
+        .unwrap_or_else(|| text.to_string());
+        (expanded, unknown.into_inner())
+    }
+
+    /// Expands `path` and resolves it against `base`, or returns the names of the properties it could not expand
+    pub fn resolve_path(&self, base: &Path, path: &str) -> Result<std::path::PathBuf, Vec<String>> {
+        let (expanded, unknown) = self.expand(path);
+        if !unknown.is_empty() {
+            return Err(unknown);
+        }
+        Ok(base.join(expanded.replace('\\', "/")))
+    }
+}
+
+/// The nearest .sln at or above `dir`, whose directory becomes $(SolutionDir)
+fn find_solution(dir: &Path) -> Option<std::path::PathBuf> {
+    dir.ancestors().find_map(|dir| {
+        let mut solutions: Vec<_> = std::fs::read_dir(dir)
+            .ok()?
+            .filter_map(|entry| entry.ok())
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|ext| ext.eq_ignore_ascii_case("sln")))
+            .collect();
+        solutions.sort();
+        solutions.into_iter().next()
+    })
+}
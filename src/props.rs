@@ -0,0 +1,55 @@
+use anyhow::Result;
+use crate::vcxproj::ToolSetting;
+
+/// Splits a setting name given as "Tool.Name" (e.g. "ClCompile.PreprocessorDefinitions")
+pub fn parse_setting_name(setting: &str) -> Result<(String, String)> {
+    setting
+        .split_once(['.', '/'])
+        .filter(|(tool, name)| !tool.is_empty() && !name.is_empty())
+        .map(|(tool, name)| (tool.to_string(), name.to_string()))
+        .ok_or_else(|| anyhow::anyhow!("Invalid setting '{}': expected Tool.Name, e.g. ClCompile.PreprocessorDefinitions", setting))
+}
+
+/// The content of a property sheet in the layout Visual Studio's Property Manager creates, holding
+/// `settings` in one ItemDefinitionGroup per condition (unconditioned settings apply to every
+/// configuration importing the sheet)
+pub fn sheet_content(settings: &[ToolSetting]) -> String {
+    let mut content = String::new();
+    content.push_str("<?xml version=\"1.0\" encoding=\"utf-8\"?>\n");
+    content.push_str("<Project ToolsVersion=\"4.0\" xmlns=\"http://schemas.microsoft.com/developer/msbuild/2003\">\n");
+    content.push_str("  <ImportGroup Label=\"PropertySheets\" />\n");
+    content.push_str("  <PropertyGroup Label=\"UserMacros\" />\n");
+    content.push_str("  <PropertyGroup />\n");
+
+    let mut conditions: Vec<Option<&str>> = Vec::new();
+    for setting in settings {
+        if !conditions.contains(&setting.condition.as_deref()) {
+            conditions.push(setting.condition.as_deref());
+        }
+    }
+    for condition in conditions {
+        match condition {
+            Some(condition) => content.push_str(&format!("  <ItemDefinitionGroup Condition=\"{}\">\n", condition)),
+            None => content.push_str("  <ItemDefinitionGroup>\n"),
+        }
+        let group: Vec<&ToolSetting> = settings.iter().filter(|s| s.condition.as_deref() == condition).collect();
+        let mut tools: Vec<&str> = Vec::new();
+        for setting in &group {
+            if !tools.contains(&setting.tool.as_str()) {
+                tools.push(&setting.tool);
+            }
+        }
+        for tool in tools {
+            content.push_str(&format!("    <{}>\n", tool));
+            for setting in group.iter().filter(|s| s.tool == tool) {
+                content.push_str(&format!("      <{}>{}</{}>\n", setting.name, setting.value, setting.name));
+            }
+            content.push_str(&format!("    </{}>\n", tool));
+        }
+        content.push_str("  </ItemDefinitionGroup>\n");
+    }
+
+    content.push_str("  <ItemGroup />\n");
+    content.push_str("</Project>\n");
+    content
+}
@@ -0,0 +1,306 @@
+use quick_xml::events::Event;
+use quick_xml::Reader;
+use regex::Regex;
+use std::ops::Range;
+
+/// A project or filters file after heuristic repair, with a description of every fix applied
+#[derive(Debug, Default)]
+pub struct Repaired {
+    pub content: String,
+    pub fixes: Vec<String>,
+}
+
+/// An element opened but not closed yet while scanning the file
+struct OpenElement {
+    name: String,
+    indent: String,
+    line: usize,
+    /// Indented deeper than its parent, so the indentation tells where it should have ended
+    nested: bool,
+}
+
+/// Fixes common hand-editing and merge damage, line by line:
+/// - a file cut off mid-tag loses the partial tag
+/// - a bare `&` becomes `&amp;`
+/// - an element left open is closed where the next line at its indentation (or its parent's closing tag) shows it ended
+/// - a closing tag without a matching open element, like a duplicated `</Project>`, is removed
+/// - elements still open at the end of a truncated file are closed there
+pub fn repair(content: &str) -> Repaired {
+    let mut fixes = Vec::new();
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+
+    // A file cut off mid-tag ends with a `<` that is never closed
+    if let Some(last) = lines.iter().rposition(|l| !l.trim().is_empty()) {
+        let line = &lines[last];
+        if let Some(open) = line.rfind('<').filter(|&open| line.rfind('>').is_none_or(|close| close < open)) {
+            fixes.push(format!("Removed truncated text at the end of line {}: {}", last + 1, line[open..].trim()));
+            let kept = line[..open].trim_end().to_string();
+            if kept.trim().is_empty() {
+                lines.truncate(last);
+            } else {
+                lines[last] = kept;
+                lines.truncate(last + 1);
+            }
+        }
+    }
+
+    let entity = Regex::new(r"^&(?:amp|lt|gt|quot|apos|#[0-9]+|#x[0-9a-fA-F]+);").unwrap();
+    let mut in_comment = false;
+    for (index, line) in lines.iter_mut().enumerate() {
+        let mut bare: Vec<usize> = Vec::new();
+        for range in code_ranges(line, &mut in_comment) {
+            bare.extend(
+                line[range.clone()]
+                    .match_indices('&')
+                    .map(|(i, _)| range.start + i)
+                    .filter(|&i| !entity.is_match(&line[i..])),
+            );
+        }
+        if !bare.is_empty() {
+            fixes.push(format!("Escaped {} bare '&' on line {}", bare.len(), index + 1));
+            for &i in bare.iter().rev() {
+                line.insert_str(i + 1, "amp;");
+            }
+        }
+    }
+
+    let tag = Regex::new(r#"<(/?)([A-Za-z_][\w.:-]*)(?:\s+[^\s=/>]+\s*=\s*(?:"[^"]*"|'[^']*'))*\s*(/?)>"#).unwrap();
+    let mut output: Vec<String> = Vec::new();
+    let mut stack: Vec<OpenElement> = Vec::new();
+    let mut in_comment = false;
+    for (index, line) in lines.iter().enumerate() {
+        let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+        let mut rebuilt = String::new();
+        let mut cursor = 0;
+        let mut first_tag = true;
+        for range in code_ranges(line, &mut in_comment) {
+            for captures in tag.captures_iter(&line[range.clone()]) {
+                let whole = captures.get(0).unwrap();
+                let (start, end) = (range.start + whole.start(), range.start + whole.end());
+                let name = &captures[2];
+                let at_line_start = first_tag && line[..start].trim().is_empty();
+                first_tag = false;
+                rebuilt.push_str(&line[cursor..start]);
+                cursor = start;
+
+                if &captures[1] == "/" {
+                    match stack.iter().rposition(|open| open.name == name) {
+                        Some(position) => {
+                            while stack.len() > position + 1 {
+                                let open = stack.pop().unwrap();
+                                fixes.push(format!("Closed <{}> from line {} before </{}> on line {}", open.name, open.line + 1, name, index + 1));
+                                if at_line_start {
+                                    output.push(format!("{}</{}>", open.indent, open.name));
+                                } else {
+                                    rebuilt.push_str(&format!("</{}>", open.name));
+                                }
+                            }
+                            stack.pop();
+                        }
+                        None => {
+                            fixes.push(format!("Removed stray </{}> on line {}", name, index + 1));
+                            cursor = end;
+                        }
+                    }
+                    continue;
+                }
+
+                // A sibling (or an uncle) starting at or left of an open element's indentation means it was never closed.
+                // The root is never closed this way, so files without indentation are left alone.
+                if at_line_start {
+                    while stack.len() > 1 {
+                        let top = stack.last().unwrap();
+                        if !top.nested || top.indent.len() < indent.len() || top.line == index {
+                            break;
+                        }
+                        let open = stack.pop().unwrap();
+                        fixes.push(format!("Closed <{}> from line {} before line {}", open.name, open.line + 1, index + 1));
+                        output.push(format!("{}</{}>", open.indent, open.name));
+                    }
+                }
+                if &captures[3] != "/" {
+                    let nested = stack.last().is_none_or(|parent| indent.len() > parent.indent.len());
+                    stack.push(OpenElement { name: name.to_string(), indent: indent.clone(), line: index, nested });
+                }
+            }
+        }
+        rebuilt.push_str(&line[cursor..]);
+
+        // A line that held nothing but a stray closing tag is dropped
+        if rebuilt != *line && rebuilt.trim().is_empty() {
+            continue;
+        }
+        output.push(rebuilt);
+    }
+
+    while let Some(open) = stack.pop() {
+        fixes.push(format!("Closed <{}> from line {} at the end of the file", open.name, open.line + 1));
+        output.push(format!("{}</{}>", open.indent, open.name));
+    }
+
+    let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+    let mut repaired = output.join(newline);
+    if content.ends_with('\n') {
+        repaired.push_str(newline);
+    }
+    Repaired { content: repaired, fixes }
+}
+
+/// The parts of a line outside comments and the XML declaration, tracking comments that span lines
+pub fn code_ranges(line: &str, in_comment: &mut bool) -> Vec<Range<usize>> {
+    let mut ranges = Vec::new();
+    let mut position = 0;
+    while position < line.len() {
+        if *in_comment {
+            match line[position..].find("-->") {
+                Some(end) => {
+                    *in_comment = false;
+                    position += end + 3;
+                }
+                None => break,
+            }
+            continue;
+        }
+        let rest = &line[position..];
+        let (comment, declaration) = (rest.find("<!--"), rest.find("<?"));
+        match (comment, declaration) {
+            (Some(start), _) if declaration.is_none_or(|d| start < d) => {
+                ranges.push(position..position + start);
+                *in_comment = true;
+                position += start + 4;
+            }
+            (_, Some(start)) => {
+                ranges.push(position..position + start);
+                position += rest[start..].find("?>").map_or(rest.len(), |end| start + end + 2);
+            }
+            _ => {
+                ranges.push(position..line.len());
+                break;
+            }
+        }
+    }
+    ranges
+}
+
+/// Checks that `content` is well-formed XML with a single root element, describing the first problem found
+pub fn check_well_formed(content: &str) -> Result<(), String> {
+    let content = content.trim_start_matches('\u{feff}');
+    let mut reader = Reader::from_str(content);
+    let mut depth = 0usize;
+    let mut roots = 0;
+    loop {
+        let position = reader.buffer_position();
+        let line = || content[..position.min(content.len())].matches('\n').count() + 1;
+        let event = reader.read_event().map_err(|e| format!("line {}: {}", line(), e))?;
+        match &event {
+            Event::Start(element) | Event::Empty(element) => {
+                if depth == 0 {
+                    roots += 1;
+                }
+                if roots > 1 {
+                    return Err(format!("line {}: more than one root element", line()));
+                }
+                for attribute in element.attributes() {
+                    let attribute = attribute.map_err(|e| format!("line {}: {}", line(), e))?;
+                    attribute.unescape_value().map_err(|e| format!("line {}: {}", line(), e))?;
+                }
+                if matches!(event, Event::Start(_)) {
+                    depth += 1;
+                }
+            }
+            Event::End(_) => depth = depth.saturating_sub(1),
+            Event::Text(text) => {
+                let text = text.unescape().map_err(|e| format!("line {}: {}", line(), e))?;
+                if depth == 0 && !text.trim().is_empty() {
+                    return Err(format!("line {}: text outside the root element", line()));
+                }
+            }
+            Event::Eof => break,
+            _ => {}
+        }
+    }
+
+    match (roots, depth) {
+        (0, _) => Err("no root element".to_string()),
+        (_, 0) => Ok(()),
+        (_, open) => Err(format!("{} elements are not closed at the end of the file", open)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn repaired(content: &str) -> Repaired {
+        let repaired = repair(content);
+        assert_eq!(check_well_formed(&repaired.content), Ok(()), "{}", repaired.content);
+        repaired
+    }
+
+    #[test]
+    fn leaves_well_formed_files_alone() {
+        for content in [include_str!("../samples/test.vcxproj"), include_str!("../samples/test.vcxproj.filters")] {
+            let repaired = repaired(content);
+            assert_eq!(repaired.content, content);
+            assert!(repaired.fixes.is_empty(), "{:?}", repaired.fixes);
+        }
+    }
+
+    #[test]
+    fn escapes_bare_ampersands_outside_comments() {
+        let content = "<Project>\n  <!-- a & b -->\n  <Command>a &amp;&amp; b & c</Command>\n</Project>\n";
+        let repaired = repaired(content);
+        assert_eq!(repaired.content, "<Project>\n  <!-- a & b -->\n  <Command>a &amp;&amp; b &amp; c</Command>\n</Project>\n");
+        assert_eq!(repaired.fixes, ["Escaped 1 bare '&' on line 3"]);
+    }
+
+    #[test]
+    fn closes_an_element_where_its_indentation_ends() {
+        let content = "<Project>\n  <ItemGroup>\n    <ClCompile Include=\"a.c\" />\n  <ItemGroup>\n    <ClInclude Include=\"a.h\" />\n  </ItemGroup>\n</Project>\n";
+        let repaired = repaired(content);
+        assert_eq!(
+            repaired.content,
+            "<Project>\n  <ItemGroup>\n    <ClCompile Include=\"a.c\" />\n  </ItemGroup>\n  <ItemGroup>\n    <ClInclude Include=\"a.h\" />\n  </ItemGroup>\n</Project>\n"
+        );
+        assert_eq!(repaired.fixes, ["Closed <ItemGroup> from line 2 before line 4"]);
+    }
+
+    #[test]
+    fn removes_stray_closing_tags() {
+        let repaired = repaired("<Project>\n  <ItemGroup>\n  </ItemGroup>\n  </ItemGroup>\n</Project>\n</Project>\n");
+        assert_eq!(repaired.content, "<Project>\n  <ItemGroup>\n  </ItemGroup>\n</Project>\n");
+        assert_eq!(repaired.fixes, ["Removed stray </ItemGroup> on line 4", "Removed stray </Project> on line 6"]);
+    }
+
+    #[test]
+    fn closes_a_truncated_file() {
+        let repaired = repaired("<Project>\n  <ItemGroup>\n    <ClCompile Include=\"a.c\" />\n    <ClCompile Inc");
+        assert_eq!(repaired.content, "<Project>\n  <ItemGroup>\n    <ClCompile Include=\"a.c\" />\n  </ItemGroup>\n</Project>");
+        assert_eq!(
+            repaired.fixes,
+            [
+                "Removed truncated text at the end of line 4: <ClCompile Inc",
+                "Closed <ItemGroup> from line 2 at the end of the file",
+                "Closed <Project> from line 1 at the end of the file",
+            ]
+        );
+    }
+
+    #[test]
+    fn repairing_twice_changes_nothing_more() {
+        let content = "<Project>\r\n  <ItemGroup>\r\n    <None Include=\"a&b.txt\" />\r\n  <PropertyGroup>\r\n  </PropertyGroup>\r\n</Project>\r\n</Project>\r\n";
+        let once = repaired(content);
+        assert!(once.content.contains("\r\n") && !once.content.replace("\r\n", "").contains('\n'));
+        let twice = repaired(&once.content);
+        assert_eq!(twice.content, once.content);
+        assert!(twice.fixes.is_empty(), "{:?}", twice.fixes);
+    }
+
+    #[test]
+    fn describes_what_is_not_well_formed() {
+        assert_eq!(check_well_formed("<Project>\n  <ItemGroup>\n</Project>").map_err(|e| e.starts_with("line 3")), Err(true));
+        assert_eq!(check_well_formed("<A/>\n<B/>"), Err("line 2: more than one root element".to_string()));
+        assert_eq!(check_well_formed("<A>"), Err("1 elements are not closed at the end of the file".to_string()));
+        assert_eq!(check_well_formed(""), Err("no root element".to_string()));
+    }
+}
@@ -0,0 +1,419 @@
+use anyhow::{Context, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Project type GUID of C++ projects
+pub const VCXPROJ_TYPE_GUID: &str = "{8BC9CEB8-8B4A-11D0-8D11-00A0C91BC942}";
+
+/// Project type GUID of solution folders
+pub const SOLUTION_FOLDER_TYPE_GUID: &str = "{2150E333-8FDC-42A3-9474-1A3956D46DE8}";
+
+/// Global sections in the order Visual Studio writes them
+const SECTION_ORDER: &[&str] = &[
+    "SolutionConfigurationPlatforms",
+    "ProjectConfigurationPlatforms",
+    "SolutionProperties",
+    "NestedProjects",
+    "ExtensibilityGlobals",
+];
+
+/// A `Project(...) = ...` entry of a solution: a project or a solution folder
+#[derive(Debug, Clone)]
+pub struct SolutionProject {
+    pub type_guid: String,
+    pub name: String,
+    /// Path relative to the solution, as written (the folder name for solution folders)
+    pub path: String,
+    pub guid: String,
+}
+
+#[derive(Debug)]
+pub struct SolutionFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+impl SolutionFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read solution file: {}", path.display()))?;
+
+        Ok(Self { path, content })
+    }
+
+    /// Returns the on-disk paths of all .vcxproj projects referenced by the solution
+    pub fn vcxproj_paths(&self) -> Vec<PathBuf> {
+        let solution_dir = self.path.parent().unwrap_or_else(|| Path::new("."));
+        let mut paths = Vec::new();
+
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            if !trimmed.starts_with("Project(\"") {
+                continue;
+            }
+
+            // Project("{TYPE-GUID}") = "Name", "path\to\project.vcxproj", "{PROJECT-GUID}"
+            let Some((_, values)) = trimmed.split_once('=') else {
+                continue;
+            };
+            let fields: Vec<&str> = values
+                .split(',')
+                .map(|f| f.trim().trim_matches('"'))
+                .collect();
+
+            if fields.len() == 3 && fields[1].to_lowercase().ends_with(".vcxproj") {
+                paths.push(solution_dir.join(fields[1].replace('\\', "/")));
+            }
+        }
+
+        paths
+    }
+
+    /// Every `Project(...)` entry, projects and solution folders alike
+    pub fn projects(&self) -> Vec<SolutionProject> {
+        self.content.lines().filter_map(parse_project_line).collect()
+    }
+
+    /// The solution's configurations, e.g. "Debug|x64"
+    pub fn configurations(&self) -> Vec<String> {
+        self.section_lines("SolutionConfigurationPlatforms")
+            .iter()
+            .filter_map(|line| line.split_once('=').map(|(name, _)| name.trim().to_string()))
+            .collect()
+    }
+
+    /// Adds a project, optionally inside a solution folder such as "Libraries" or "Libraries\\Third Party"
+    /// (created as needed), and maps each solution configuration to one of `project_configs`. A solution
+    /// without configurations gets those of the project. Returns the mappings written, as
+    /// "Debug|x86 → Debug|Win32", with "(not built)" for those without a matching configuration.
+    pub fn add_project(&mut self, project: &SolutionProject, folder: Option<&str>, project_configs: &[String]) -> Vec<String> {
+        let mut lines: Vec<String> = self.content.lines().map(str::to_string).collect();
+        let global = lines.iter().position(|l| l.trim() == "Global").unwrap_or(lines.len());
+        lines.splice(global..global, project_block(project));
+        self.set_lines(lines);
+
+        if self.section_lines("SolutionConfigurationPlatforms").is_empty() {
+            let configs: Vec<String> = project_configs
+                .iter()
+                .map(|c| solution_config_name(c))
+                .map(|c| format!("\t\t{} = {}", c, c))
+                .collect();
+            self.append_to_section("SolutionConfigurationPlatforms", "preSolution", configs);
+        }
+        let mut mappings = Vec::new();
+        let mut entries = Vec::new();
+        for config in self.configurations() {
+            let (mapped, built) = map_configuration(&config, project_configs);
+            entries.push(format!("\t\t{}.{}.ActiveCfg = {}", project.guid, config, mapped));
+            if built {
+                entries.push(format!("\t\t{}.{}.Build.0 = {}", project.guid, config, mapped));
+                mappings.push(format!("{} → {}", config, mapped));
+            } else {
+                mappings.push(format!("{} → {} (not built)", config, mapped));
+            }
+        }
+        self.append_to_section("ProjectConfigurationPlatforms", "postSolution", entries);
+
+        if let Some(folder) = folder {
+            let parent = self.ensure_folder(folder);
+            self.append_to_section("NestedProjects", "preSolution", vec![format!("\t\t{} = {}", project.guid, parent)]);
+        }
+        mappings
+    }
+
+    /// Removes a project with its configuration mappings, its place in a solution folder and the
+    /// dependencies other projects declare on it
+    pub fn remove_project(&mut self, guid: &str) {
+        let mut lines = Vec::new();
+        let mut in_block = false;
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            if parse_project_line(line).is_some_and(|p| p.guid.eq_ignore_ascii_case(guid)) {
+                in_block = true;
+                continue;
+            }
+            if in_block {
+                in_block = trimmed != "EndProject";
+                continue;
+            }
+            // "{GUID}.Debug|x64.ActiveCfg = ...", "{GUID} = {FOLDER}" and dependencies "{GUID} = {GUID}"
+            let key = trimmed.split(['.', ' ', '=']).next().unwrap_or_default();
+            if key.eq_ignore_ascii_case(guid) {
+                continue;
+            }
+            lines.push(line.to_string());
+        }
+        self.set_lines(lines);
+    }
+
+    /// Gives the project at `path` (as written in the solution) the GUID `guid`, along with its
+    /// configuration mappings, its place in a solution folder and the dependencies on it. When other
+    /// entries share the old GUID, as copied projects do, only this entry changes and it gets copies of
+    /// the old GUID's mappings and nesting. Returns the previous GUID, or `None` when the solution
+    /// doesn't list the project.
+    pub fn set_project_guid(&mut self, path: &str, guid: &str) -> Option<String> {
+        let projects = self.projects();
+        let previous = projects.iter().find(|p| crate::vcxproj::same_path(&p.path, path))?.guid.clone();
+        let shared = projects.iter().filter(|p| p.guid.eq_ignore_ascii_case(&previous)).count() > 1;
+        let mut lines: Vec<String> = Vec::new();
+        let mut in_global = false;
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            in_global |= trimmed == "Global";
+            let own_entry = parse_project_line(line).is_some_and(|p| crate::vcxproj::same_path(&p.path, path));
+            let key = trimmed.split(['.', ' ', '=']).next().unwrap_or_default();
+            if !shared || own_entry {
+                lines.push(replace_ignore_case(line, &previous, guid));
+                continue;
+            }
+            lines.push(line.to_string());
+            if in_global && key.eq_ignore_ascii_case(&previous) {
+                let copy = replace_ignore_case(line, &previous, guid);
+                if !lines.contains(&copy) {
+                    lines.push(copy);
+                }
+            }
+        }
+        self.set_lines(lines);
+        Some(previous)
+    }
+
+    /// The GUIDs of the projects `guid` depends on through its `ProjectDependencies` section, which
+    /// orders the build without linking
+    pub fn dependencies(&self, guid: &str) -> Vec<String> {
+        let mut dependencies = Vec::new();
+        let mut in_project = false;
+        let mut in_section = false;
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            if let Some(project) = parse_project_line(line) {
+                in_project = project.guid.eq_ignore_ascii_case(guid);
+            } else if trimmed == "EndProject" {
+                in_project = false;
+            } else if in_project && trimmed.starts_with("ProjectSection(ProjectDependencies)") {
+                in_section = true;
+            } else if trimmed == "EndProjectSection" {
+                in_section = false;
+            } else if in_section {
+                if let Some((dependency, _)) = trimmed.split_once('=') {
+                    dependencies.push(dependency.trim().to_string());
+                }
+            }
+        }
+        dependencies
+    }
+
+    /// Adds `dependency` to the `ProjectDependencies` section of `guid`, creating the section as
+    /// needed. Returns false when the dependency is already there.
+    pub fn add_dependency(&mut self, guid: &str, dependency: &str) -> bool {
+        if self.dependencies(guid).iter().any(|d| d.eq_ignore_ascii_case(dependency)) {
+            return false;
+        }
+        let mut lines: Vec<String> = self.content.lines().map(str::to_string).collect();
+        let Some(start) = lines.iter().position(|l| parse_project_line(l).is_some_and(|p| p.guid.eq_ignore_ascii_case(guid))) else {
+            return false;
+        };
+        let end = start + lines[start..].iter().position(|l| l.trim() == "EndProject").unwrap_or(0);
+        let entry = format!("\t\t{} = {}", dependency, dependency);
+        match lines[start..end].iter().position(|l| l.trim().starts_with("ProjectSection(ProjectDependencies)")) {
+            Some(section) => {
+                let section_end = start + section + lines[start + section..].iter().position(|l| l.trim() == "EndProjectSection").unwrap_or(0);
+                lines.insert(section_end, entry);
+            }
+            None => {
+                lines.splice(end..end, [
+                    "\tProjectSection(ProjectDependencies) = postProject".to_string(),
+                    entry,
+                    "\tEndProjectSection".to_string(),
+                ]);
+            }
+        }
+        self.set_lines(lines);
+        true
+    }
+
+    /// Removes `dependency` from the `ProjectDependencies` section of `guid`, and the section when it
+    /// becomes empty. Returns false when there was no such dependency.
+    pub fn remove_dependency(&mut self, guid: &str, dependency: &str) -> bool {
+        if !self.dependencies(guid).iter().any(|d| d.eq_ignore_ascii_case(dependency)) {
+            return false;
+        }
+        let mut lines: Vec<String> = Vec::new();
+        let mut in_project = false;
+        let mut in_section = false;
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            if let Some(project) = parse_project_line(line) {
+                in_project = project.guid.eq_ignore_ascii_case(guid);
+            } else if in_project && trimmed.starts_with("ProjectSection(ProjectDependencies)") {
+                in_section = true;
+            } else if in_section && trimmed == "EndProjectSection" {
+                in_section = false;
+                if lines.last().is_some_and(|l| l.trim().starts_with("ProjectSection(ProjectDependencies)")) {
+                    lines.pop();
+                    continue;
+                }
+            } else if in_section && trimmed.split('=').next().is_some_and(|d| d.trim().eq_ignore_ascii_case(dependency)) {
+                continue;
+            }
+            lines.push(line.to_string());
+        }
+        self.set_lines(lines);
+        true
+    }
+
+    pub fn save(&self) -> Result<bool> {
+        crate::vcxproj::write_file_as_is(&self.path, &self.content)
+            .with_context(|| format!("Failed to write solution file: {}", self.path.display()))
+    }
+
+    /// The GUID of the solution folder at `folder`, creating it and its parents as needed
+    fn ensure_folder(&mut self, folder: &str) -> String {
+        let mut parent: Option<String> = None;
+        for name in folder.split(['\\', '/']).filter(|n| !n.is_empty()) {
+            let nested = self.nested_projects();
+            let existing = self.projects().into_iter().find(|p| {
+                p.type_guid.eq_ignore_ascii_case(SOLUTION_FOLDER_TYPE_GUID)
+                    && p.name.eq_ignore_ascii_case(name)
+                    && nested.iter().find(|(child, _)| child.eq_ignore_ascii_case(&p.guid)).map(|(_, parent)| parent) == parent.as_ref()
+            });
+            let guid = match existing {
+                Some(existing) => existing.guid,
+                None => {
+                    let folder = SolutionProject {
+                        type_guid: SOLUTION_FOLDER_TYPE_GUID.to_string(),
+                        name: name.to_string(),
+                        path: name.to_string(),
+                        guid: format!("{{{}}}", uuid::Uuid::new_v4().to_string().to_uppercase()),
+                    };
+                    let mut lines: Vec<String> = self.content.lines().map(str::to_string).collect();
+                    let global = lines.iter().position(|l| l.trim() == "Global").unwrap_or(lines.len());
+                    lines.splice(global..global, project_block(&folder));
+                    self.set_lines(lines);
+                    if let Some(parent) = &parent {
+                        self.append_to_section("NestedProjects", "preSolution", vec![format!("\t\t{} = {}", folder.guid, parent)]);
+                    }
+                    folder.guid
+                }
+            };
+            parent = Some(guid);
+        }
+        parent.unwrap_or_default()
+    }
+
+    /// (child, parent) GUID pairs of the NestedProjects section
+    fn nested_projects(&self) -> Vec<(String, String)> {
+        self.section_lines("NestedProjects")
+            .iter()
+            .filter_map(|line| line.split_once('=').map(|(child, parent)| (child.trim().to_string(), parent.trim().to_string())))
+            .collect()
+    }
+
+    /// The entries of a `GlobalSection(name)`, without the section's own lines
+    fn section_lines(&self, name: &str) -> Vec<String> {
+        let header = format!("GlobalSection({})", name);
+        self.content
+            .lines()
+            .skip_while(|line| !line.trim().starts_with(&header))
+            .skip(1)
+            .take_while(|line| line.trim() != "EndGlobalSection")
+            .map(str::to_string)
+            .collect()
+    }
+
+    /// Appends entries to a `GlobalSection(name)`, creating the section in Visual Studio's order when missing
+    fn append_to_section(&mut self, name: &str, timing: &str, entries: Vec<String>) {
+        let mut lines: Vec<String> = self.content.lines().map(str::to_string).collect();
+        let header = format!("GlobalSection({})", name);
+        let index = match lines.iter().position(|line| line.trim().starts_with(&header)) {
+            Some(start) => start + lines[start..].iter().position(|l| l.trim() == "EndGlobalSection").unwrap_or(0),
+            None => {
+                if !lines.iter().any(|l| l.trim() == "Global") {
+                    lines.extend(["Global".to_string(), "EndGlobal".to_string()]);
+                }
+                let later = &SECTION_ORDER[SECTION_ORDER.iter().position(|s| *s == name).map_or(SECTION_ORDER.len(), |i| i + 1)..];
+                let index = lines
+                    .iter()
+                    .position(|line| {
+                        let trimmed = line.trim();
+                        trimmed == "EndGlobal" || later.iter().any(|s| trimmed.starts_with(&format!("GlobalSection({})", s)))
+                    })
+                    .unwrap_or(lines.len());
+                lines.splice(index..index, [format!("\t{} = {}", header, timing), "\tEndGlobalSection".to_string()]);
+                index + 1
+            }
+        };
+        lines.splice(index..index, entries);
+        self.set_lines(lines);
+    }
+
+    /// Replaces the content with `lines`, keeping the file's line endings and final newline
+    fn set_lines(&mut self, lines: Vec<String>) {
+        let newline = if self.content.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut content = lines.join(newline);
+        if self.content.ends_with('\n') {
+            content.push_str(newline);
+        }
+        self.content = content;
+    }
+}
+
+/// Parses `Project("{TYPE-GUID}") = "Name", "path\to\project.vcxproj", "{PROJECT-GUID}"`
+fn parse_project_line(line: &str) -> Option<SolutionProject> {
+    let trimmed = line.trim();
+    let type_guid = trimmed.strip_prefix("Project(\"")?.split('"').next()?.to_string();
+    let (_, values) = trimmed.split_once('=')?;
+    let fields: Vec<&str> = values.split(',').map(|f| f.trim().trim_matches('"')).collect();
+    let [name, path, guid] = fields[..] else {
+        return None;
+    };
+    Some(SolutionProject { type_guid, name: name.to_string(), path: path.to_string(), guid: guid.to_string() })
+}
+
+/// `line` with every occurrence of `from` replaced by `to`, ignoring ASCII case (GUIDs are written in either)
+fn replace_ignore_case(line: &str, from: &str, to: &str) -> String {
+    let upper = line.to_ascii_uppercase();
+    let from = from.to_ascii_uppercase();
+    let mut result = String::new();
+    let mut rest = 0;
+    for (index, _) in upper.match_indices(&from) {
+        result.push_str(&line[rest..index]);
+        result.push_str(to);
+        rest = index + from.len();
+    }
+    result.push_str(&line[rest..]);
+    result
+}
+
+fn project_block(project: &SolutionProject) -> [String; 2] {
+    [
+        format!("Project(\"{}\") = \"{}\", \"{}\", \"{}\"", project.type_guid, project.name, project.path, project.guid),
+        "EndProject".to_string(),
+    ]
+}
+
+/// The solution configuration Visual Studio creates for a project configuration: Win32 is called x86
+fn solution_config_name(project_config: &str) -> String {
+    match project_config.split_once('|') {
+        Some((config, platform)) if platform.eq_ignore_ascii_case("Win32") => format!("{}|x86", config),
+        _ => project_config.to_string(),
+    }
+}
+
+/// The project configuration a solution configuration builds, and whether it is built at all. Like
+/// Visual Studio, a solution configuration without an exact match maps to a project configuration of
+/// the same name (or the first one) that is not built.
+fn map_configuration(solution_config: &str, project_configs: &[String]) -> (String, bool) {
+    if let Some(exact) = project_configs.iter().find(|c| solution_config_name(c).eq_ignore_ascii_case(solution_config)) {
+        return (exact.clone(), true);
+    }
+    let name = solution_config.split('|').next().unwrap_or_default();
+    let fallback = project_configs
+        .iter()
+        .find(|c| c.split('|').next().unwrap_or_default().eq_ignore_ascii_case(name))
+        .or(project_configs.first())
+        .cloned()
+        .unwrap_or_else(|| solution_config.to_string());
+    (fallback, false)
+}
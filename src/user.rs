@@ -0,0 +1,263 @@
+use anyhow::{Context, Result};
+use crate::vcxproj::{attribute_value, condition_config};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// The debugger settings `user set` edits: the program, its arguments, working directory and environment
+pub const DEBUGGER_PROPERTIES: &[&str] = &[
+    "LocalDebuggerCommand",
+    "LocalDebuggerCommandArguments",
+    "LocalDebuggerWorkingDirectory",
+    "LocalDebuggerEnvironment",
+];
+
+/// Whether an environment line sets PATH (`PATH=...`, in any case)
+pub fn is_path_variable(line: &str) -> bool {
+    line.split_once('=').is_some_and(|(name, _)| name.trim().eq_ignore_ascii_case("PATH"))
+}
+
+/// Adds `directory` to the PATH of a debugger environment (one `NAME=value` per line): to the end
+/// of its PATH line, or to the front with `prepend` so its DLLs win over others of the same name.
+/// Without a PATH line one is added that extends the inherited one: `PATH=%PATH%;directory`.
+/// Returns `None` when PATH already has the directory.
+pub fn add_to_path(environment: &str, directory: &str, prepend: bool) -> Option<String> {
+    let key = |entry: &str| entry.trim().trim_end_matches(['\\', '/']).replace('/', "\\").to_lowercase();
+    let mut lines: Vec<String> = environment.lines().filter(|line| !line.trim().is_empty()).map(str::to_string).collect();
+    match lines.iter_mut().find(|line| is_path_variable(line)) {
+        Some(line) => {
+            let (name, value) = line.split_once('=').unwrap_or_default();
+            let mut entries: Vec<&str> = value.split(';').filter(|entry| !entry.trim().is_empty()).collect();
+            if entries.iter().any(|entry| key(entry) == key(directory)) {
+                return None;
+            }
+            if prepend {
+                entries.insert(0, directory);
+            } else {
+                entries.push(directory);
+            }
+            *line = format!("{}={}", name, entries.join(";"));
+        }
+        None if prepend => lines.push(format!("PATH={};%PATH%", directory)),
+        None => lines.push(format!("PATH=%PATH%;{}", directory)),
+    }
+    Some(lines.join("\n"))
+}
+
+/// A property of the user file, e.g. LocalDebuggerCommandArguments for Debug|x64
+#[derive(Debug, Clone)]
+pub struct UserProperty {
+    /// The configuration (e.g. "Debug|x64") of its PropertyGroup, `None` for an unconditioned group
+    pub config: Option<String>,
+    pub name: String,
+    /// The value, unescaped; LocalDebuggerEnvironment has one variable per line
+    pub value: String,
+}
+
+/// The per-user settings of a project (`<project>.vcxproj.user`), where Visual Studio keeps the
+/// debugger settings of each configuration
+#[derive(Debug)]
+pub struct UserFile {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+/// The user file of a project: `foo.vcxproj` → `foo.vcxproj.user`
+pub fn user_path(project_path: &Path) -> PathBuf {
+    let mut path = project_path.as_os_str().to_owned();
+    path.push(".user");
+    PathBuf::from(path)
+}
+
+fn escape(value: &str) -> String {
+    value.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;")
+}
+
+fn unescape(value: &str) -> String {
+    value.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+/// The configuration a PropertyGroup line applies to: `Some(None)` for an unconditioned group,
+/// `None` for a line that doesn't open a group with content
+fn group_config(trimmed: &str) -> Option<Option<String>> {
+    if !trimmed.starts_with("<PropertyGroup") || trimmed.ends_with("/>") {
+        return None;
+    }
+    Some(attribute_value(trimmed, "Condition").map(|condition| condition_config(condition).into_owned()))
+}
+
+impl UserFile {
+    pub fn load(path: impl AsRef<Path>) -> Result<Self> {
+        let path = path.as_ref().to_path_buf();
+        let content = fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read user file: {}", path.display()))?;
+
+        Ok(Self { path, content })
+    }
+
+    /// An empty user file, laid out the way Visual Studio creates one
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            path,
+            content: "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Project ToolsVersion=\"Current\" xmlns=\"http://schemas.microsoft.com/developer/msbuild/2003\">\n  <PropertyGroup />\n</Project>\n".to_string(),
+        }
+    }
+
+    /// The user file of a project, or a new empty one when it has none yet
+    pub fn for_project(project_path: &Path) -> Result<Self> {
+        let path = user_path(project_path);
+        if path.exists() {
+            Self::load(path)
+        } else {
+            Ok(Self::new(path))
+        }
+    }
+
+    /// Writes the file as laid out here: the restyling of `write_file` tracks nesting line by line,
+    /// which multi-line values such as the environment would throw off
+    pub fn save(&self) -> Result<bool> {
+        crate::vcxproj::write_file_as_is(&self.path, &self.content)
+            .with_context(|| format!("Failed to write user file: {}", self.path.display()))
+    }
+
+    /// Every property of every PropertyGroup, in file order
+    pub fn properties(&self) -> Vec<UserProperty> {
+        let lines: Vec<&str> = self.content.lines().collect();
+        let mut properties = Vec::new();
+        let mut group: Option<Option<String>> = None;
+        let mut k = 0;
+        while k < lines.len() {
+            let trimmed = lines[k].trim();
+            if let Some(config) = group_config(trimmed) {
+                group = Some(config);
+            } else if trimmed.starts_with("</PropertyGroup>") {
+                group = None;
+            } else if let (Some(config), Some((start, end))) = (&group, self.element_at(&lines, k)) {
+                let (name, value) = element_value(&lines[start..=end]);
+                properties.push(UserProperty { config: config.clone(), name, value });
+                k = end;
+            }
+            k += 1;
+        }
+        properties
+    }
+
+    /// The value of `name` in the PropertyGroup of `config`
+    pub fn get(&self, name: &str, config: &str) -> Option<String> {
+        self.properties()
+            .into_iter()
+            .find(|p| p.name == name && p.config.as_deref().is_some_and(|c| c.eq_ignore_ascii_case(config)))
+            .map(|p| p.value)
+    }
+
+    /// The first and last line of the element starting at line `k`, which spans several lines when
+    /// its value does
+    fn element_at(&self, lines: &[&str], k: usize) -> Option<(usize, usize)> {
+        let trimmed = lines[k].trim();
+        let name = trimmed.strip_prefix('<')?.split(['>', ' ', '/']).next().filter(|n| !n.is_empty())?;
+        if trimmed.ends_with("/>") {
+            return Some((k, k));
+        }
+        let close = format!("</{}>", name);
+        (k..lines.len()).find(|&end| lines[end].contains(&close)).map(|end| (k, end))
+    }
+
+    /// Sets `name` in the PropertyGroup of `config` (e.g. "Debug|x64"), creating the group when the
+    /// file has none for it. `value` may span several lines. Returns the previous value (`None` when
+    /// it was not set) if it changed.
+    pub fn set(&mut self, name: &str, value: &str, config: &str) -> Option<Option<String>> {
+        let previous = self.get(name, config);
+        if previous.as_deref() == Some(value) {
+            return None;
+        }
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let element: Vec<String> = format!("    <{}>{}</{}>", name, escape(value), name).lines().map(str::to_string).collect();
+
+        match self.find(name, config) {
+            Some((_, Some((start, end)))) => {
+                lines.splice(start..=end, element);
+            }
+            Some((group_end, None)) => {
+                lines.splice(group_end..group_end, element);
+            }
+            None => {
+                let end = lines.iter().rposition(|line| line.trim().starts_with("</Project>")).unwrap_or(lines.len());
+                let mut group = vec![format!("  <PropertyGroup Condition=\"'$(Configuration)|$(Platform)'=='{}'\">", config)];
+                group.extend(element);
+                group.push("  </PropertyGroup>".to_string());
+                lines.splice(end..end, group);
+            }
+        }
+
+        self.replace_lines(lines);
+        Some(previous)
+    }
+
+    /// Removes `name` from the PropertyGroup of `config`, and the group when nothing is left in it.
+    /// Returns the value it had.
+    pub fn remove(&mut self, name: &str, config: &str) -> Option<String> {
+        let previous = self.get(name, config)?;
+        let Some((group_end, Some((start, end)))) = self.find(name, config) else {
+            return None;
+        };
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        lines.drain(start..=end);
+        let group_end = group_end - (end - start + 1);
+        if group_end > 0 && group_config(lines[group_end - 1].trim()).is_some() {
+            lines.drain(group_end - 1..=group_end);
+        }
+
+        self.replace_lines(lines);
+        Some(previous)
+    }
+
+    /// Replaces the content with `lines`, keeping the file's line endings
+    fn replace_lines(&mut self, lines: Vec<String>) {
+        let newline = if self.content.contains("\r\n") { "\r\n" } else { "\n" };
+        let trailing_newline = self.content.ends_with('\n');
+        self.content = lines.join(newline);
+        if trailing_newline {
+            self.content.push_str(newline);
+        }
+    }
+
+    /// The line of `</PropertyGroup>` of the group of `config`, and the lines of `name` in it
+    fn find(&self, name: &str, config: &str) -> Option<(usize, Option<(usize, usize)>)> {
+        let lines: Vec<&str> = self.content.lines().collect();
+        let open = format!("<{}>", name);
+        let mut found = None;
+        let mut in_group = false;
+        let mut k = 0;
+        while k < lines.len() {
+            let trimmed = lines[k].trim();
+            if let Some(group) = group_config(trimmed) {
+                in_group = group.is_some_and(|c| c.eq_ignore_ascii_case(config));
+            } else if trimmed.starts_with("</PropertyGroup>") {
+                if in_group {
+                    return Some((k, found));
+                }
+            } else if in_group {
+                if let Some((start, end)) = self.element_at(&lines, k) {
+                    if trimmed.starts_with(&open) || trimmed == format!("<{} />", name) {
+                        found = Some((start, end));
+                    }
+                    k = end;
+                }
+            }
+            k += 1;
+        }
+        None
+    }
+}
+
+/// The name and unescaped value of an element spanning `lines`
+fn element_value(lines: &[&str]) -> (String, String) {
+    let text = lines.join("\n");
+    let text = text.trim();
+    let name = text[1..].split(['>', ' ', '/']).next().unwrap_or_default().to_string();
+    let value = text
+        .split_once('>')
+        .map(|(_, rest)| rest)
+        .and_then(|rest| rest.strip_suffix(&format!("</{}>", name)))
+        .unwrap_or_default();
+    (name, unescape(value))
+}
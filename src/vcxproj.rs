@@ -1,7 +1,384 @@
 use anyhow::{Context, Result};
-use std::collections::{HashMap, HashSet, BTreeMap};
+use log::debug;
+use regex::Regex;
+use crate::condition::{self, ConfigSelection};
+use crate::history;
+use crate::layout::FilterMap;
+use crate::output::{self, Change};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet, BTreeMap, BTreeSet};
 use std::fs;
+use std::hash::{DefaultHasher, Hash, Hasher};
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// What the item paths of a shared items project (.vcxitems) start with, since they are imported into other projects
+const SHARED_ITEMS_ROOT: &str = "$(MSBuildThisFileDirectory)";
+
+/// Item types that are not files and never appear in Solution Explorer filters
+pub const NON_FILE_ITEM_TYPES: &[&str] = &["ProjectConfiguration", "ProjectReference", "Reference", "PackageReference", "ProjectCapability"];
+
+/// Item types `delete` removes: the files a build tool compiles or generates code from
+pub const COMPILED_ITEM_TYPES: &[&str] = &["ClCompile", "FXCompile", "CudaCompile", "QtMoc", "QtUic", "QtRcc", "ResourceCompile", "Manifest"];
+
+static PRESERVE_MTIME: AtomicBool = AtomicBool::new(false);
+static FORCE: AtomicBool = AtomicBool::new(false);
+static RANDOM_FILTER_GUIDS: AtomicBool = AtomicBool::new(false);
+
+/// Namespace of the name-based (UUIDv5) filter identifiers
+const FILTER_GUID_NAMESPACE: uuid::Uuid = uuid::Uuid::from_u128(0x6f2b_93c1_4d0e_4a57_9c36_e1a8_5b7d_20f4);
+
+/// Hash of each project and filters file as it was on disk when last loaded or written, to catch
+/// another process (a CI job, a watcher, Visual Studio) changing it before the save
+static LOADED: Mutex<BTreeMap<PathBuf, u64>> = Mutex::new(BTreeMap::new());
+
+/// Keep the modification time of project files whose rewrite only changes item metadata, so such
+/// edits don't make MSBuild re-evaluate and rebuild the project
+pub fn set_preserve_mtime(preserve: bool) {
+    PRESERVE_MTIME.store(preserve, Ordering::Relaxed);
+}
+
+/// Overwrite project files even if another process modified them since they were loaded
+pub fn set_force(force: bool) {
+    FORCE.store(force, Ordering::Relaxed);
+}
+
+/// Whether `--force` was given
+pub fn forced() -> bool {
+    FORCE.load(Ordering::Relaxed)
+}
+
+/// Give new filters random identifiers instead of ones derived from their path
+pub fn set_random_filter_guids(random: bool) {
+    RANDOM_FILTER_GUIDS.store(random, Ordering::Relaxed);
+}
+
+/// The `<UniqueIdentifier>` of a new filter, e.g. "{0B9D...}": a UUIDv5 of the filter path, ignoring
+/// case and separators, so regenerating filters writes the same GUIDs every time. Random with
+/// `--random-filter-guids`.
+pub fn filter_identifier(name: &str) -> String {
+    let uuid = if RANDOM_FILTER_GUIDS.load(Ordering::Relaxed) {
+        uuid::Uuid::new_v4()
+    } else {
+        uuid::Uuid::new_v5(&FILTER_GUID_NAMESPACE, include_key(name).as_bytes())
+    };
+    format!("{{{}}}", uuid.to_string().to_uppercase())
+}
+
+/// The filter of each new file: the first `map` rule matching its path, or else the directory it
+/// was found in relative to the scan root ("Source Files" at the root). Also returns the filters
+/// they need, including the parents of mapped filters.
+pub fn assign_filters(project_files: &[PathBuf], scan_relative_files: &[PathBuf], map: &FilterMap) -> (Vec<String>, BTreeSet<String>) {
+    let mut needed = BTreeSet::new();
+    let mut filters = Vec::new();
+    for (project_file, scan_relative_file) in project_files.iter().zip(scan_relative_files) {
+        if let Some(filter) = map.filter_for(&project_file.to_string_lossy()) {
+            let mut parent = String::new();
+            for part in filter.split('\\') {
+                if !parent.is_empty() {
+                    parent.push('\\');
+                }
+                parent.push_str(part);
+                needed.insert(parent.clone());
+            }
+            filters.push(filter.to_string());
+            continue;
+        }
+        let dir = scan_relative_file
+            .parent()
+            .map(|parent| include_path(&parent.to_string_lossy()))
+            .filter(|name| !name.is_empty());
+        match dir {
+            Some(dir) => {
+                needed.insert(dir.clone());
+                filters.push(dir);
+            }
+            None => filters.push("Source Files".to_string()),
+        }
+    }
+    (filters, needed)
+}
+
+fn content_hash(content: &str) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    content.hash(&mut hasher);
+    hasher.finish()
+}
+
+fn loaded_key(path: &Path) -> PathBuf {
+    fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf())
+}
+
+/// Remembers the on-disk content of `path` that the next save is based on
+fn track_loaded(path: &Path, content: &str) {
+    LOADED.lock().unwrap().insert(loaded_key(path), content_hash(content));
+}
+
+/// Fails if `path` no longer has the content it had when it was loaded, since writing would
+/// silently drop the other process's edits
+fn check_unmodified(path: &Path, current: &str) -> Result<()> {
+    let loaded = LOADED.lock().unwrap().get(&loaded_key(path)).copied();
+    match loaded {
+        Some(hash) if hash != content_hash(current) => {
+            if forced() {
+                crate::i18n::warn!("⚠️  {} was modified by another process since it was loaded, overwriting it (--force)", path.display());
+                return Ok(());
+            }
+            Err(anyhow::anyhow!(
+                "{} was modified by another process since it was loaded. Run the command again to apply it to the new content, or pass --force to overwrite the other changes",
+                path.display()
+            ))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Indentation and self-closing tag spacing of an XML file, detected from its content and
+/// overridden by the `[format]` table of `.vcprojm/config.toml`:
+///
+/// ```toml
+/// [format]
+/// indent = "4"                  # "2" or "4" spaces, or "tab"
+/// self_closing_space = false    # <ClCompile Include="a.c"/> instead of <ClCompile Include="a.c" />
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Style {
+    pub indent: String,
+    pub self_closing_space: bool,
+}
+
+impl Style {
+    /// The indentation of the first indented element and the more common self-closing spacing
+    pub fn detect(content: &str) -> Self {
+        let indent = content
+            .lines()
+            .find(|line| line.trim_start().starts_with('<') && line.starts_with([' ', '\t']))
+            .map(|line| line.chars().take_while(|c| *c == ' ' || *c == '\t').collect())
+            .unwrap_or_else(|| "  ".to_string());
+        let (spaced, tight) = content.lines().map(str::trim_end).fold((0, 0), |(spaced, tight), line| {
+            match (line.ends_with(" />"), line.ends_with("/>")) {
+                (true, _) => (spaced + 1, tight),
+                (false, true) => (spaced, tight + 1),
+                _ => (spaced, tight),
+            }
+        });
+        Style { indent, self_closing_space: spaced >= tight }
+    }
+
+    /// The style of the file at `path` with `content`, with the settings of the config file next to it applied
+    pub fn load(path: &Path, content: &str) -> Result<Self> {
+        let mut style = Style::detect(content);
+        let config_path = path
+            .parent()
+            .filter(|p| !p.as_os_str().is_empty())
+            .unwrap_or_else(|| Path::new("."))
+            .join(history::CONFIG_FILE);
+        let Ok(config) = fs::read_to_string(&config_path) else {
+            return Ok(style);
+        };
+        let config: toml::Table = config
+            .parse()
+            .with_context(|| format!("Invalid config file: {}", config_path.display()))?;
+        let Some(format) = config.get("format") else {
+            return Ok(style);
+        };
+        let format = format
+            .as_table()
+            .ok_or_else(|| anyhow::anyhow!("[format] must be a table in {}", config_path.display()))?;
+        for (key, value) in format {
+            match (key.as_str(), value) {
+                ("indent", toml::Value::String(indent)) if indent == "tab" => style.indent = "\t".to_string(),
+                ("indent", toml::Value::String(indent)) if indent.parse::<usize>().is_ok() => {
+                    style.indent = " ".repeat(indent.parse().unwrap_or(2));
+                }
+                ("indent", toml::Value::Integer(width)) if *width >= 0 => style.indent = " ".repeat(*width as usize),
+                ("self_closing_space", toml::Value::Boolean(space)) => style.self_closing_space = *space,
+                ("indent", _) => return Err(anyhow::anyhow!("format.indent must be a number of spaces or \"tab\" in {}", config_path.display())),
+                ("self_closing_space", _) => return Err(anyhow::anyhow!("format.self_closing_space must be true or false in {}", config_path.display())),
+                _ => return Err(anyhow::anyhow!("Unknown key 'format.{}' in {}", key, config_path.display())),
+            }
+        }
+        Ok(style)
+    }
+
+    /// Re-indents the element lines of `content` that are not in `original` (the lines a command
+    /// inserted) to their nesting depth in this style, and applies the self-closing tag spacing to them.
+    /// Lines kept from the original file are left exactly as they were.
+    pub fn apply(&self, original: &str, content: &str) -> String {
+        let mut remaining: HashMap<&str, usize> = HashMap::new();
+        for line in original.lines() {
+            *remaining.entry(line).or_default() += 1;
+        }
+        
+        let mut changed = false;
+        let mut depth = 0usize;
+        let mut output = Vec::new();
+        for line in content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("</") {
+                depth = depth.saturating_sub(1);
+            }
+            let kept = remaining.get_mut(line).filter(|count| **count > 0).map(|count| *count -= 1).is_some();
+            if kept || !trimmed.starts_with('<') {
+                output.push(line.to_string());
+            } else {
+                let mut styled = self.indent.repeat(depth);
+                match trimmed.strip_suffix("/>").filter(|_| !trimmed.starts_with("<?")) {
+                    Some(tag) => {
+                        styled.push_str(tag.trim_end());
+                        styled.push_str(if self.self_closing_space { " />" } else { "/>" });
+                    }
+                    None => styled.push_str(trimmed),
+                }
+                changed |= styled != line;
+                output.push(styled);
+            }
+            let opens = trimmed.starts_with('<')
+                && !trimmed.starts_with("</")
+                && !trimmed.starts_with("<?")
+                && !trimmed.starts_with("<!")
+                && !trimmed.ends_with("/>")
+                && !trimmed.contains("</");
+            if opens {
+                depth += 1;
+            }
+        }
+        
+        if !changed {
+            return content.to_string();
+        }
+        let newline = if content.contains("\r\n") { "\r\n" } else { "\n" };
+        let mut styled = output.join(newline);
+        if content.ends_with('\n') {
+            styled.push_str(newline);
+        }
+        styled
+    }
+}
+
+/// Journals and writes a project or filters file. Lines the command inserted follow the file's own
+/// `Style`. Nothing is written when the file already has the content, so no-op commands leave the
+/// file (and its timestamp) untouched. Returns whether the file was written.
+pub fn write_file(path: &Path, content: &str) -> Result<bool> {
+    Ok(write_files(&[(path, content)])?[0])
+}
+
+/// Like `write_file` for several files at once, as one transaction: every file is checked and
+/// staged before any of them is replaced, so a failure leaves all of them as they were. Returns
+/// whether each file was written.
+pub fn write_files(files: &[(&Path, &str)]) -> Result<Vec<bool>> {
+    let mut styled = Vec::new();
+    for &(path, content) in files {
+        let current = crate::pipe::read(path).and_then(Result::ok).map_or_else(|| fs::read_to_string(path), Ok);
+        styled.push(match current {
+            Ok(current) => Style::load(path, &current)?.apply(&current, content),
+            Err(_) => content.to_string(),
+        });
+    }
+    let files: Vec<(&Path, &str)> = files.iter().zip(&styled).map(|(&(path, _), content)| (path, content.as_str())).collect();
+    write_files_as_is(&files)
+}
+
+/// Like `write_file`, but writes `content` exactly, for content already laid out in the file's style
+pub fn write_file_as_is(path: &Path, content: &str) -> Result<bool> {
+    Ok(write_files_as_is(&[(path, content)])?[0])
+}
+
+/// Like `write_files`, but writes the contents exactly
+pub fn write_files_as_is(files: &[(&Path, &str)]) -> Result<Vec<bool>> {
+    let mut written = vec![false; files.len()];
+    // (index, modification time) of the files to replace on disk
+    let mut changed = Vec::new();
+    for &(path, _) in files {
+        crate::pipe::check_writable(path)?;
+    }
+    for (k, &(path, content)) in files.iter().enumerate() {
+        if let Some(piped) = crate::pipe::write(path, content) {
+            if piped {
+                output::report(Change::FileWritten, [path.display()]);
+            }
+            written[k] = piped;
+            continue;
+        }
+        crate::gitref::check_writable(path)?;
+        if fs::read_to_string(path).is_ok_and(|current| current == content) {
+            debug!("{} is unchanged, not rewriting it", path.display());
+            continue;
+        }
+        
+        crate::vs::wait_until_closed(path)?;
+        // Visual Studio may have saved the file while we waited
+        if let Ok(current) = fs::read_to_string(path) {
+            check_unmodified(path, &current)?;
+        }
+        // Only the time of a file whose items stay the same is kept: after items are added or
+        // removed, or any setting outside them changes, a build must see that the project changed
+        let mtime = fs::read_to_string(path)
+            .ok()
+            .filter(|_| PRESERVE_MTIME.load(Ordering::Relaxed))
+            .filter(|current| {
+                let metadata_only = is_metadata_only_change(current, content);
+                if !metadata_only {
+                    crate::i18n::warn!("⚠️  {} changed beyond per-item settings, so its modification time is updated despite --preserve-mtime", path.display());
+                }
+                metadata_only
+            })
+            .and_then(|_| fs::metadata(path).and_then(|m| m.modified()).ok());
+        changed.push((k, mtime));
+    }
+    
+    let mut changes = Vec::new();
+    for &(k, _) in &changed {
+        let (path, content) = files[k];
+        history::record(path, content)?;
+        changes.push((path.to_path_buf(), Some(content.to_string())));
+    }
+    history::replace_files(&changes)?;
+    
+    for (k, mtime) in changed {
+        let (path, content) = files[k];
+        if let Some(mtime) = mtime {
+            fs::File::options()
+                .write(true)
+                .open(path)
+                .and_then(|file| file.set_modified(mtime))
+                .with_context(|| format!("Failed to restore modification time: {}", path.display()))?;
+            debug!("Kept the modification time of {}", path.display());
+        }
+        track_loaded(path, content);
+        debug!("Wrote {} ({} bytes)", path.display(), content.len());
+        output::report(Change::FileWritten, [path.display()]);
+        written[k] = true;
+    }
+    Ok(written)
+}
+
+/// Whether `after` differs from `before` only in the metadata of items (per-file settings, filter
+/// assignments): the same items in the same order, and every line outside the ItemGroups unchanged
+fn is_metadata_only_change(before: &str, after: &str) -> bool {
+    let items = |content: &str| -> Vec<(String, String)> {
+        parse_items(content).into_iter().map(|item| (item.item_type, item.include)).collect()
+    };
+    let outside_item_groups = |content: &str| -> Vec<String> {
+        let mut in_item_group = false;
+        content
+            .lines()
+            .map(str::trim)
+            .filter(|line| {
+                if line.starts_with("<ItemGroup") && !line.ends_with("/>") {
+                    in_item_group = true;
+                } else if line.starts_with("</ItemGroup>") {
+                    in_item_group = false;
+                    return false;
+                }
+                !in_item_group && !line.is_empty()
+            })
+            .map(str::to_string)
+            .collect()
+    };
+    items(before) == items(after) && outside_item_groups(before) == outside_item_groups(after)
+}
 
 #[derive(Debug)]
 pub struct VcxprojFile {
@@ -19,109 +396,195 @@ pub struct FilterFile {
 pub struct ProjectFile {
     pub path: String,
     pub filter: Option<String>,
+    pub metadata: Vec<ItemMetadata>,
+}
+
+/// A single piece of per-item metadata, e.g. `<ExcludedFromBuild Condition="...">true</ExcludedFromBuild>`
+#[derive(Debug, Clone)]
+pub struct ItemMetadata {
+    pub name: String,
+    pub condition: Option<String>,
+    pub value: String,
+}
+
+/// Any item declared inside an `<ItemGroup>` (ClCompile, ClInclude, None, ...)
+#[derive(Debug, Clone)]
+pub struct ProjectItem {
+    pub item_type: String,
+    pub include: String,
+    pub metadata: Vec<ItemMetadata>,
+}
+
+/// A tool setting from an ItemDefinitionGroup, e.g. ClCompile/WarningLevel for Debug|x64
+#[derive(Debug, Clone)]
+pub struct ToolSetting {
+    pub condition: Option<String>,
+    pub tool: String,
+    pub name: String,
+    pub value: String,
+}
+
+/// An `<Import>` element, e.g. `<Import Project="$(VCTargetsPath)\Microsoft.Cpp.props" />`
+#[derive(Debug, Clone)]
+pub struct Import {
+    /// 1-based line number in the project file
+    pub line: usize,
+    pub project: String,
+    pub condition: Option<String>,
+    pub label: Option<String>,
+    /// Whether it is inside an `<ImportGroup>`, whose Label and Condition follow
+    pub in_group: bool,
+    pub group_label: Option<String>,
+    pub group_condition: Option<String>,
+}
+
+/// An item whose Include contains wildcards, e.g. `<ClCompile Include="src\**\*.cpp" Exclude="src\old\*.cpp" />`
+#[derive(Debug, Clone)]
+pub struct WildcardItem {
+    pub item_type: String,
+    /// The raw Include attribute
+    pub include: String,
+    /// The `;`-separated parts of the Include attribute
+    pub patterns: Vec<String>,
+    pub excludes: Vec<String>,
+}
+
+/// Where a value goes in a `;`-separated list such as AdditionalDependencies
+#[derive(Debug, Clone, PartialEq)]
+pub enum ListPosition {
+    Front,
+    /// Last, but before the inherited `%(...)` value
+    Back,
+    After(String),
+    Before(String),
+}
+
+impl std::str::FromStr for ListPosition {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<Self, Self::Err> {
+        match s.split_once(':') {
+            _ if s == "front" => Ok(ListPosition::Front),
+            _ if s == "back" => Ok(ListPosition::Back),
+            Some(("after", anchor)) if !anchor.is_empty() => Ok(ListPosition::After(anchor.to_string())),
+            Some(("before", anchor)) if !anchor.is_empty() => Ok(ListPosition::Before(anchor.to_string())),
+            _ => Err(format!("invalid position '{}' (expected front, back, after:<lib> or before:<lib>)", s)),
+        }
+    }
 }
 
 #[derive(Debug)]
 pub struct ProjectStructure {
+    /// File name of the project, e.g. "App.vcxproj" or "Shared.vcxitems"
     pub name: String,
     pub files: Vec<ProjectFile>,
     pub filters: HashMap<String, Vec<String>>, // filter name -> files in filter
+    /// Files whose path does not exist on disk, marked in the tree (`view --check-disk`)
+    pub missing: HashSet<String>,
 }
 
 impl VcxprojFile {
     pub fn load(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
-        let content = fs::read_to_string(&path)
-            .with_context(|| format!("Failed to read vcxproj file: {}", path.display()))?;
+        let content = match crate::pipe::read(&path).or_else(|| crate::gitref::read(&path)) {
+            Some(content) => content?,
+            None => fs::read_to_string(&path)
+                .inspect(|content| track_loaded(&path, content))
+                .with_context(|| format!("Failed to read vcxproj file: {}", path.display()))?,
+        };
+        let content = if is_shared_items(&path) { strip_shared_items_root(&content) } else { content };
         
         Ok(Self { path, content })
     }
 
+    /// Adds files as items of the type Visual Studio would use for them (ClCompile, ClInclude or None)
     pub fn add_source_files(&mut self, files: &[PathBuf]) -> Result<()> {
-        // Simple string-based approach to add files
-        let mut new_entries = String::new();
+        // Simple string-based approach to add files, one batch per item type
+        let mut new_entries: BTreeMap<&str, String> = BTreeMap::new();
         
+        let project_dir = self.path.parent().unwrap_or_else(|| Path::new("."));
         for file in files {
-            if let Some(ext) = file.extension() {
-                if ext == "c" || ext == "cpp" || ext == "cc" || ext == "cxx" {
-                    let include_path = file.to_string_lossy().replace('/', "\\");
-                    new_entries.push_str(&format!("    <ClCompile Include=\"{}\" />\n", include_path));
-                }
-            }
+            let include_path = include_path(&file.to_string_lossy());
+            let item_type = item_type_in(project_dir, &include_path);
+            new_entries
+                .entry(item_type)
+                .or_default()
+                .push_str(&format!("    <{} Include=\"{}\" />\n", item_type, include_path));
         }
 
-        if new_entries.is_empty() {
-            return Ok(());
+        for (item_type, entries) in new_entries {
+            self.insert_items(item_type, &entries);
         }
 
-        // Find the ClCompile ItemGroup or create one
-        if let Some(pos) = self.content.find("<ClCompile Include=") {
+        Ok(())
+    }
+
+    /// Appends item lines to the ItemGroup already holding `item_type` items, or to a new
+    /// ItemGroup before the closing Project tag
+    fn insert_items(&mut self, item_type: &str, entries: &str) {
+        if let Some(pos) = self.content.find(&format!("<{} Include=", item_type)) {
             // Find the end of this ItemGroup
             let before_pos = &self.content[..pos];
             if let Some(itemgroup_start) = before_pos.rfind("<ItemGroup>") {
                 let after_itemgroup = &self.content[itemgroup_start..];
                 if let Some(itemgroup_end) = after_itemgroup.find("</ItemGroup>") {
-                    let insertion_point = itemgroup_start + itemgroup_end;
-                    self.content.insert_str(insertion_point, &new_entries);
-                    return Ok(());
+                    // Insert at the start of the closing tag's line so indentation is kept
+                    let closing_tag = itemgroup_start + itemgroup_end;
+                    let insertion_point = self.content[..closing_tag].rfind('\n').map_or(closing_tag, |p| p + 1);
+                    self.content.insert_str(insertion_point, entries);
+                    return;
                 }
             }
         }
 
-        // If no ClCompile ItemGroup found, create one before the closing Project tag
         if let Some(pos) = self.content.rfind("</Project>") {
             let itemgroup = format!(
                 "  <ItemGroup>\n{}\n  </ItemGroup>\n",
-                new_entries.trim_end()
+                entries.trim_end()
             );
             self.content.insert_str(pos, &itemgroup);
         }
-
-        Ok(())
     }
 
-    pub fn delete_files(&mut self, target: &str, extension: Option<&str>) -> Result<Vec<String>> {
+    /// Deletes matching compiled items (see `COMPILED_ITEM_TYPES`). Matches are narrowed by `regex` (inverted with `negate`),
+    /// and `include_file` can veto individual matches by path.
+    pub fn delete_files(
+        &mut self,
+        target: &str,
+        extension: Option<&str>,
+        regex: Option<&Regex>,
+        negate: bool,
+        include_file: impl Fn(&str) -> bool,
+    ) -> Result<Vec<String>> {
+        let include_file = |path: &str| regex_allows(regex, negate, path) && include_file(path);
         let mut deleted_files = Vec::new();
         let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
         let mut i = 0;
         
         while i < lines.len() {
             let line = &lines[i];
+            let item_type = element_name(line.trim()).to_string();
             
-            // Look for ClCompile entries
-            if line.trim_start().starts_with("<ClCompile Include=\"") {
-                let should_delete = if let Some(ext) = extension {
-                    // Delete by extension
-                    line.contains(&format!(".{}", ext))
-                } else {
-                    // Delete by specific file path or folder
-                    if target.ends_with('/') || target.ends_with('\\') {
-                        // Folder deletion - check if file is in this folder
-                        let target_normalized = target.replace('/', "\\");
-                        line.contains(&target_normalized) || line.contains(&target.replace('\\', "/"))
-                    } else {
-                        // Specific file deletion
-                        line.contains(target)
-                    }
-                };
+            // Look for ClCompile, FXCompile, ... entries
+            if COMPILED_ITEM_TYPES.contains(&item_type.as_str()) && line.trim_start().starts_with(&format!("<{} Include=\"", item_type)) {
+                let should_delete = attribute_value(line, "Include")
+                    .is_some_and(|include| matches_delete_target(include, target, extension) && include_file(include));
                 
                 if should_delete {
                     // Extract filename for reporting
-                    if let Some(start) = line.find("Include=\"") {
-                        if let Some(end) = line[start + 9..].find('"') {
-                            let filename = &line[start + 9..start + 9 + end];
-                            deleted_files.push(filename.to_string());
-                        }
+                    if let Some(filename) = attribute_value(line, "Include") {
+                        deleted_files.push(filename.to_string());
                     }
                     
-                    // Remove the ClCompile line
+                    // Remove the item line
+                    let closing_tag = format!("</{}>", item_type);
                     if line.trim().ends_with("/>") {
                         // Self-closing tag
                         lines.remove(i);
                     } else {
                         // Multi-line entry, find the closing tag
                         lines.remove(i);
-                        while i < lines.len() && !lines[i].trim().ends_with("</ClCompile>") {
+                        while i < lines.len() && !lines[i].trim().ends_with(&closing_tag) {
                             lines.remove(i);
                         }
                         if i < lines.len() {
@@ -140,123 +603,883 @@ impl VcxprojFile {
         Ok(deleted_files)
     }
 
+    /// Returns every file item (sources, headers, None, CustomBuild, ...), so projects without
+    /// ClCompile items such as utility projects are listed too
     pub fn get_project_files(&self) -> Result<Vec<ProjectFile>> {
-        let mut files = Vec::new();
-        let lines: Vec<&str> = self.content.lines().collect();
+        let files = parse_items(&self.content)
+            .into_iter()
+            .filter(|item| !NON_FILE_ITEM_TYPES.contains(&item.item_type.as_str()))
+            .map(|item| ProjectFile {
+                path: item.include,
+                filter: None, // Will be populated from filter file
+                metadata: item.metadata,
+            })
+            .collect();
         
-        for line in &lines {
-            let trimmed = line.trim_start();
-            if trimmed.starts_with("<ClCompile Include=\"") {
-                if let Some(start) = line.find("Include=\"") {
-                    if let Some(end) = line[start + 9..].find('"') {
-                        let file_path = &line[start + 9..start + 9 + end];
-                        files.push(ProjectFile {
-                            path: file_path.to_string(),
-                            filter: None, // Will be populated from filter file
-                        });
+        Ok(files)
+    }
+
+    /// Returns every file item in the project regardless of item type
+    pub fn get_items(&self) -> Result<Vec<ProjectItem>> {
+        Ok(parse_items(&self.content)
+            .into_iter()
+            .filter(|item| item.item_type != "ProjectConfiguration")
+            .collect())
+    }
+
+    /// Returns the tool settings declared in ItemDefinitionGroups, e.g. the `<WarningLevel>` inside
+    /// `<ClCompile>` for each configuration condition
+    pub fn get_tool_settings(&self) -> Vec<ToolSetting> {
+        let mut settings = Vec::new();
+        let mut condition: Option<String> = None;
+        let mut in_definition_group = false;
+        let mut tool: Option<String> = None;
+
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("<ItemDefinitionGroup") {
+                in_definition_group = true;
+                condition = attribute_value(trimmed, "Condition").map(|c| c.to_string());
+            } else if trimmed.starts_with("</ItemDefinitionGroup>") {
+                in_definition_group = false;
+                tool = None;
+            } else if in_definition_group {
+                match &tool {
+                    None if trimmed.starts_with('<') && !trimmed.starts_with("</") && !trimmed.ends_with("/>") => {
+                        tool = Some(element_name(trimmed).to_string());
                     }
+                    Some(current) if trimmed == format!("</{}>", current) => {
+                        tool = None;
+                    }
+                    Some(current) => {
+                        if let Some(metadata) = parse_metadata_line(trimmed) {
+                            settings.push(ToolSetting {
+                                condition: condition.clone(),
+                                tool: current.clone(),
+                                name: metadata.name,
+                                value: metadata.value,
+                            });
+                        }
+                    }
+                    None => {}
                 }
             }
         }
-        
-        Ok(files)
+
+        settings
     }
 
-    pub fn add_include_directory(&mut self, include_path: &str) -> Result<Vec<String>> {
-        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
-        let mut modified_configs = Vec::new();
-        let mut i = 0;
+    /// Returns the configuration names declared in the ProjectConfigurations ItemGroup (e.g. "Debug|x64")
+    pub fn get_configurations(&self) -> Vec<String> {
+        parse_items(&self.content)
+            .into_iter()
+            .filter(|item| item.item_type == "ProjectConfiguration")
+            .map(|item| item.include)
+            .collect()
+    }
 
-        while i < lines.len() {
-            // Look for ItemDefinitionGroup with Condition
-            if lines[i].trim_start().starts_with("<ItemDefinitionGroup Condition=") {
-                // Extract configuration name
-                if let Some(condition_start) = lines[i].find("Condition=\"") {
-                    if let Some(condition_end) = lines[i][condition_start + 11..].find('"') {
-                        let condition = &lines[i][condition_start + 11..condition_start + 11 + condition_end];
-                        modified_configs.push(condition.to_string());
-                    }
+    /// Returns the value of the first `<name>value</name>` property, e.g. "ProjectGuid" from the Globals group
+    pub fn get_property(&self, name: &str) -> Option<String> {
+        let open = format!("<{}>", name);
+        let close = format!("</{}>", name);
+        self.content.lines().find_map(|line| {
+            let value = line.trim().strip_prefix(&open)?.strip_suffix(&close)?;
+            Some(value.trim().to_string())
+        })
+    }
+
+    /// Returns the value of a `<name>value</name>` property from the PropertyGroup conditioned on `config`
+    /// (e.g. "Debug|x64"), such as ConfigurationType or UseDebugLibraries
+    pub fn get_config_property(&self, name: &str, config: &str) -> Option<String> {
+        let open = format!("<{}>", name);
+        let close = format!("</{}>", name);
+        let mut in_group = false;
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("<PropertyGroup") {
+                in_group = attribute_value(trimmed, "Condition")
+                    .is_some_and(|condition| condition_matches(condition, config));
+            } else if trimmed.starts_with("</PropertyGroup>") {
+                in_group = false;
+            } else if in_group {
+                if let Some(value) = trimmed.strip_prefix(&open).and_then(|rest| rest.strip_suffix(&close)) {
+                    return Some(value.trim().to_string());
                 }
+            }
+        }
+        None
+    }
 
-                // Look for ClCompile section within this ItemDefinitionGroup
-                let mut j = i + 1;
-                let mut found_clcompile = false;
-                while j < lines.len() && !lines[j].trim().starts_with("</ItemDefinitionGroup>") {
-                    if lines[j].trim_start().starts_with("<ClCompile>") {
-                        found_clcompile = true;
-                        // Look for existing AdditionalIncludeDirectories or find where to insert
-                        let mut k = j + 1;
-                        let mut found_includes = false;
-                        while k < lines.len() && !lines[k].trim().starts_with("</ClCompile>") {
-                            if lines[k].trim_start().starts_with("<AdditionalIncludeDirectories>") {
-                                // Add to existing include directories
-                                if lines[k].contains("%(AdditionalIncludeDirectories)") {
-                                    lines[k] = lines[k].replace("%(AdditionalIncludeDirectories)", &format!("{};%(AdditionalIncludeDirectories)", include_path));
-                                } else {
-                                    lines[k] = lines[k].replace("</AdditionalIncludeDirectories>", &format!(";{}</AdditionalIncludeDirectories>", include_path));
-                                }
-                                found_includes = true;
-                                break;
-                            }
-                            k += 1;
-                        }
-                        if !found_includes {
-                            // Insert new AdditionalIncludeDirectories after ClCompile start
-                            lines.insert(j + 1, format!("      <AdditionalIncludeDirectories>{};%(AdditionalIncludeDirectories)</AdditionalIncludeDirectories>", include_path));
-                        }
-                        break;
+    /// Returns every `<Name>value</Name>` property of the PropertyGroups, with its own Condition or else its group's
+    pub fn get_properties(&self) -> Vec<ItemMetadata> {
+        let mut properties = Vec::new();
+        let mut group: Option<Option<String>> = None;
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("<PropertyGroup") {
+                group = (!trimmed.ends_with("/>")).then(|| attribute_value(trimmed, "Condition").map(str::to_string));
+            } else if trimmed.starts_with("</PropertyGroup>") {
+                group = None;
+            } else if let Some(condition) = &group {
+                if let Some(mut property) = parse_metadata_line(trimmed) {
+                    property.condition = property.condition.or_else(|| condition.clone());
+                    properties.push(property);
+                }
+            }
+        }
+        properties
+    }
+    
+    /// Whether a configuration links the debug runtime: its UseDebugLibraries property, or else
+    /// whether its name contains "Debug"
+    pub fn is_debug_configuration(&self, config: &str) -> bool {
+        match self.get_config_property("UseDebugLibraries", config) {
+            Some(value) => value.eq_ignore_ascii_case("true"),
+            None => config.to_lowercase().contains("debug"),
+        }
+    }
+
+    /// Whether the project builds with an external tool (`ConfigurationType` Makefile), keeping its
+    /// build settings in NMake* properties instead of ClCompile and Link sections
+    pub fn is_makefile_project(&self) -> bool {
+        self.get_properties()
+            .iter()
+            .any(|property| property.name == "ConfigurationType" && property.value.eq_ignore_ascii_case("Makefile"))
+    }
+
+    /// Sets a `<name>` property in the unlabeled PropertyGroup of each configuration matching `config`
+    /// (every configuration without one), where Visual Studio keeps OutDir and the NMake* properties.
+    /// Missing groups are created after the last such group, or else after the UserMacros group.
+    /// Returns each changed configuration with its previous value (`None` when added).
+    pub fn set_config_property(&mut self, name: &str, value: &str, config: Option<&str>) -> Result<Vec<(String, Option<String>)>> {
+        let open = format!("<{}>", name);
+        let close = format!("</{}>", name);
+        let mut changes = Vec::new();
+        for target in self.get_configurations() {
+            if config.is_some_and(|selector| !config_matches(&target, selector)) {
+                continue;
+            }
+            let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+            let is_group = |line: &str| {
+                let trimmed = line.trim();
+                trimmed.starts_with("<PropertyGroup") && !trimmed.ends_with("/>") && attribute_value(trimmed, "Label").is_none()
+                    && attribute_value(trimmed, "Condition").is_some_and(|condition| condition_config(condition) == target)
+            };
+            let existing = (0..lines.len()).filter(|&k| is_group(&lines[k])).find_map(|start| {
+                let end = (start..lines.len()).find(|&k| lines[k].trim().starts_with("</PropertyGroup>"))?;
+                let line = (start + 1..end).find(|&k| lines[k].trim().starts_with(&open));
+                Some((start, end, line))
+            });
+            let new_line = format!("    {}{}{}", open, value, close);
+            match existing {
+                Some((_, _, Some(k))) => {
+                    let current = lines[k].trim().strip_prefix(&open).and_then(|rest| rest.strip_suffix(&close)).unwrap_or_default().to_string();
+                    if current == value {
+                        continue;
                     }
-                    j += 1;
+                    lines[k] = new_line;
+                    changes.push((target, Some(current)));
                 }
-                
-                if !found_clcompile {
-                    // Insert new ClCompile section with include directory
-                    lines.insert(i + 1, format!("    <ClCompile>"));
-                    lines.insert(i + 2, format!("      <AdditionalIncludeDirectories>{};%(AdditionalIncludeDirectories)</AdditionalIncludeDirectories>", include_path));
-                    lines.insert(i + 3, format!("    </ClCompile>"));
+                Some((_, end, None)) => {
+                    lines.insert(end, new_line);
+                    changes.push((target, None));
+                }
+                None => {
+                    let pos = lines
+                        .iter()
+                        .rposition(|line| {
+                            let trimmed = line.trim();
+                            trimmed.starts_with("<PropertyGroup Condition=") && attribute_value(trimmed, "Label").is_none()
+                        })
+                        .and_then(|start| (start..lines.len()).find(|&k| lines[k].trim().starts_with("</PropertyGroup>")))
+                        .or_else(|| {
+                            let start = lines.iter().position(|line| line.trim().starts_with("<PropertyGroup Label=\"UserMacros\""))?;
+                            match lines[start].trim().ends_with("/>") {
+                                true => Some(start),
+                                false => (start..lines.len()).find(|&k| lines[k].trim().starts_with("</PropertyGroup>")),
+                            }
+                        })
+                        .or_else(|| lines.iter().position(|line| line.trim_start().starts_with("<ItemDefinitionGroup"))?.checked_sub(1))
+                        .ok_or_else(|| anyhow::anyhow!("No place for the properties of {} in {}", target, self.path.display()))?;
+                    let condition = format!("'$(Configuration)|$(Platform)'=='{}'", target);
+                    lines.splice(pos + 1..pos + 1, [
+                        format!("  <PropertyGroup Condition=\"{}\">", condition),
+                        new_line,
+                        "  </PropertyGroup>".to_string(),
+                    ]);
+                    changes.push((target, None));
                 }
             }
-            i += 1;
+            self.content = lines.join("\n");
         }
+        Ok(changes)
+    }
 
-        self.content = lines.join("\n");
-        Ok(modified_configs)
+    /// Adds `value` to a `;`-separated configuration property such as NMakeIncludeSearchPath, before
+    /// the inherited `$(name)` if the list has one. A new list inherits: `value;$(name)`.
+    /// Returns the configurations that changed.
+    pub fn add_config_list_value(&mut self, name: &str, value: &str, config: Option<&str>) -> Result<Vec<String>> {
+        let inherited = format!("$({})", name);
+        let mut changed = Vec::new();
+        for target in self.get_configurations() {
+            if config.is_some_and(|selector| !config_matches(&target, selector)) {
+                continue;
+            }
+            let current = self.get_config_property(name, &target).unwrap_or_default();
+            let mut entries: Vec<&str> = current.split(';').filter(|e| !e.trim().is_empty()).collect();
+            if entries.iter().any(|e| e.trim().eq_ignore_ascii_case(value.trim())) {
+                continue;
+            }
+            match entries.iter().position(|e| e.trim().eq_ignore_ascii_case(&inherited)) {
+                Some(index) => entries.insert(index, value),
+                None if entries.is_empty() => entries.extend([value, inherited.as_str()]),
+                None => entries.push(value),
+            }
+            changed.extend(self.set_config_property(name, &entries.join(";"), Some(&target))?.into_iter().map(|(config, _)| config));
+        }
+        Ok(changed)
     }
 
-    pub fn add_library_directory(&mut self, lib_path: &str) -> Result<Vec<String>> {
-        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
-        let mut modified_configs = Vec::new();
-        let mut i = 0;
+    /// The project's GUID as written in the file, e.g. "{4a2d70c3-3187-483e-af62-2968472318fc}"
+    pub fn project_guid(&self) -> Option<String> {
+        self.get_property("ProjectGuid")
+    }
 
-        while i < lines.len() {
-            // Look for ItemDefinitionGroup with Condition
-            if lines[i].trim_start().starts_with("<ItemDefinitionGroup Condition=") {
-                // Extract configuration name
-                if let Some(condition_start) = lines[i].find("Condition=\"") {
-                    if let Some(condition_end) = lines[i][condition_start + 11..].find('"') {
-                        let condition = &lines[i][condition_start + 11..condition_start + 11 + condition_end];
-                        modified_configs.push(condition.to_string());
-                    }
+    /// The name Visual Studio shows for the project: `ProjectName` if set, otherwise the file stem
+    pub fn project_name(&self) -> String {
+        self.get_property("ProjectName").unwrap_or_else(|| {
+            self.path
+                .file_stem()
+                .map(|s| s.to_string_lossy().into_owned())
+                .unwrap_or_default()
+        })
+    }
+
+    /// Replaces the value of the `<ProjectGuid>` property. Returns the previous GUID, or `None` when
+    /// the project has none.
+    pub fn set_project_guid(&mut self, guid: &str) -> Option<String> {
+        let previous = self.project_guid()?;
+        let element = format!("<ProjectGuid>{}</ProjectGuid>", previous);
+        self.content = self.content.replacen(&element, &format!("<ProjectGuid>{}</ProjectGuid>", guid), 1);
+        Some(previous)
+    }
+
+    /// Sets the `<Project>` GUID of the ProjectReferences whose Include `matches`, so they keep
+    /// pointing at a project whose GUID changed. Returns the Include of each reference updated.
+    pub fn set_project_reference_guid(&mut self, matches: impl Fn(&str) -> bool, guid: &str) -> Vec<String> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut updated = Vec::new();
+        let mut reference: Option<String> = None;
+        for line in &mut lines {
+            let trimmed = line.trim().to_string();
+            if element_name(&trimmed) == "ProjectReference" && !trimmed.ends_with("/>") {
+                reference = attribute_value(&trimmed, "Include").filter(|include| matches(include)).map(str::to_string);
+            } else if trimmed.starts_with("</ProjectReference>") {
+                reference = None;
+            } else if let Some(include) = &reference {
+                let current = trimmed.strip_prefix("<Project>").and_then(|rest| rest.strip_suffix("</Project>"));
+                if current.is_some_and(|current| !current.eq_ignore_ascii_case(guid)) {
+                    *line = line.replacen(&trimmed, &format!("<Project>{}</Project>", guid), 1);
+                    updated.push(include.clone());
                 }
+            }
+        }
+        if !updated.is_empty() {
+            self.content = lines.join("\n");
+        }
+        updated
+    }
 
-                // Look for Link section within this ItemDefinitionGroup
-                let mut j = i + 1;
-                let mut found_link = false;
-                while j < lines.len() && !lines[j].trim().starts_with("</ItemDefinitionGroup>") {
-                    if lines[j].trim_start().starts_with("<Link>") {
-                        found_link = true;
-                        // Look for existing AdditionalLibraryDirectories or find where to insert
-                        let mut k = j + 1;
-                        let mut found_lib_dirs = false;
-                        while k < lines.len() && !lines[k].trim().starts_with("</Link>") {
-                            if lines[k].trim_start().starts_with("<AdditionalLibraryDirectories>") {
-                                // Add to existing library directories
-                                if lines[k].contains("%(AdditionalLibraryDirectories)") {
-                                    lines[k] = lines[k].replace("%(AdditionalLibraryDirectories)", &format!("{};%(AdditionalLibraryDirectories)", lib_path));
-                                } else {
-                                    lines[k] = lines[k].replace("</AdditionalLibraryDirectories>", &format!(";{}</AdditionalLibraryDirectories>", lib_path));
-                                }
-                                found_lib_dirs = true;
+    /// Sets per-item metadata on every ClCompile item whose path matches `matcher`.
+    /// Self-closing items are expanded as needed. Returns the paths of items that changed.
+    pub fn set_item_metadata(
+        &mut self,
+        matcher: &Regex,
+        properties: &[(String, String)],
+        condition: Option<&str>,
+    ) -> Result<Vec<String>> {
+        self.set_typed_item_metadata("ClCompile", matcher, properties, condition)
+    }
+
+    /// Like `set_item_metadata`, for the items of `item_type` (e.g. CustomBuild) instead of ClCompile
+    pub fn set_typed_item_metadata(
+        &mut self,
+        item_type: &str,
+        matcher: &Regex,
+        properties: &[(String, String)],
+        condition: Option<&str>,
+    ) -> Result<Vec<String>> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut modified_files = Vec::new();
+        let mut i = 0;
+        
+        while i < lines.len() {
+            let trimmed = lines[i].trim().to_string();
+            let Some((_, include)) = item_key(&trimmed).filter(|_| element_name(&trimmed) == item_type) else {
+                i += 1;
+                continue;
+            };
+            let include = include.to_string();
+            
+            // Wildcard items cover many files; those get their own Update items instead
+            if include.contains('*') || !matcher.is_match(&include.replace('\\', "/")) {
+                i += 1;
+                continue;
+            }
+            
+            let indent: String = lines[i].chars().take_while(|c| c.is_whitespace()).collect();
+            let metadata_indent = format!("{}  ", indent);
+            
+            // Expand self-closing items so metadata can be added, keeping the other attributes of the tag
+            if trimmed.ends_with("/>") {
+                lines[i] = format!("{}{}>", indent, trimmed.trim_end_matches("/>").trim_end());
+                lines.insert(i + 1, format!("{}</{}>", indent, item_type));
+            }
+            
+            // Locate the closing tag of this item
+            let closing_tag = format!("</{}>", item_type);
+            let mut end = i + 1;
+            while end < lines.len() && !lines[end].trim().starts_with(&closing_tag) {
+                end += 1;
+            }
+            
+            let mut changed = false;
+            for (name, value) in properties {
+                let new_line = match condition {
+                    Some(cond) => format!("{}<{} Condition=\"{}\">{}</{}>", metadata_indent, name, cond, value, name),
+                    None => format!("{}<{}>{}</{}>", metadata_indent, name, value, name),
+                };
+                
+                let existing = (i + 1..end).find(|&k| {
+                    parse_metadata_line(&lines[k])
+                        .is_some_and(|m| m.name == *name && m.condition.as_deref() == condition)
+                });
+                
+                match existing {
+                    Some(k) => {
+                        if lines[k] != new_line {
+                            lines[k] = new_line;
+                            changed = true;
+                        }
+                    }
+                    None => {
+                        lines.insert(end, new_line);
+                        end += 1;
+                        changed = true;
+                    }
+                }
+            }
+            
+            if changed {
+                modified_files.push(include);
+            }
+            i = end + 1;
+        }
+        
+        self.content = lines.join("\n");
+        Ok(modified_files)
+    }
+
+    /// Removes the named per-item metadata from every item whose path matches `matcher`.
+    /// When `condition` is given only metadata with that exact condition is removed.
+    /// Items left without metadata are collapsed back to self-closing tags.
+    /// Returns the paths of items that changed.
+    pub fn clear_item_metadata(
+        &mut self,
+        matcher: &Regex,
+        names: &[String],
+        condition: Option<&str>,
+    ) -> Result<Vec<String>> {
+        self.remove_item_metadata_where(|include, metadata| {
+            matcher.is_match(&include.replace('\\', "/"))
+                && names.iter().any(|n| n.eq_ignore_ascii_case(&metadata.name))
+                && (condition.is_none() || metadata.condition.as_deref() == condition)
+        })
+    }
+
+    /// Removes every piece of item metadata for which `should_remove(include, metadata)` is true,
+    /// collapsing items without remaining metadata. Returns the paths of items that changed.
+    pub fn remove_item_metadata_where(
+        &mut self,
+        should_remove: impl Fn(&str, &ItemMetadata) -> bool,
+    ) -> Result<Vec<String>> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut modified_files = Vec::new();
+        let mut in_item_group = false;
+        let mut i = 0;
+
+        while i < lines.len() {
+            let trimmed = lines[i].trim().to_string();
+            if trimmed.starts_with("<ItemGroup") && !trimmed.ends_with("/>") {
+                in_item_group = true;
+            } else if trimmed.starts_with("</ItemGroup>") {
+                in_item_group = false;
+            }
+
+            let key = item_key(&trimmed).map(|(key, include)| (key, include.to_string()));
+            let is_multiline_item = in_item_group
+                && trimmed.starts_with('<')
+                && !trimmed.ends_with("/>")
+                && key.is_some();
+            if !is_multiline_item {
+                i += 1;
+                continue;
+            }
+
+            let (key, include) = key.unwrap_or_default();
+            let item_type = element_name(&trimmed).to_string();
+            let closing_tag = format!("</{}>", item_type);
+            if trimmed.ends_with(&closing_tag) {
+                i += 1;
+                continue;
+            }
+
+            let mut end = i + 1;
+            let mut changed = false;
+            while end < lines.len() && !lines[end].trim().starts_with(&closing_tag) {
+                let remove = parse_metadata_line(&lines[end]).is_some_and(|m| should_remove(&include, &m));
+                if remove {
+                    lines.remove(end);
+                    changed = true;
+                } else {
+                    end += 1;
+                }
+            }
+
+            if changed {
+                // Collapse to a self-closing tag when no metadata is left; an Update item
+                // without metadata does nothing, so it is dropped entirely
+                if end == i + 1 && end < lines.len() {
+                    let indent: String = lines[i].chars().take_while(|c| c.is_whitespace()).collect();
+                    lines.remove(end);
+                    if key == "Update" {
+                        lines.remove(i);
+                        modified_files.push(include);
+                        continue;
+                    }
+                    lines[i] = format!("{}{} />", indent, trimmed.trim_end_matches('>').trim_end());
+                    end = i;
+                }
+                modified_files.push(include);
+            }
+            i = end + 1;
+        }
+
+        self.content = lines.join("\n");
+        Ok(modified_files)
+    }
+
+    /// Returns the items whose Include uses wildcards, such as `<ClCompile Include="src\**\*.cpp" />`
+    pub fn get_wildcard_items(&self) -> Vec<WildcardItem> {
+        let split = |value: &str| -> Vec<String> {
+            value.split(';').map(str::trim).filter(|p| !p.is_empty()).map(|p| p.to_string()).collect()
+        };
+        
+        let mut items = Vec::new();
+        let mut in_item_group = false;
+        for line in self.content.lines().map(str::trim) {
+            if line.starts_with("<ItemGroup") && !line.ends_with("/>") {
+                in_item_group = true;
+            } else if line.starts_with("</ItemGroup>") {
+                in_item_group = false;
+            } else if in_item_group {
+                let Some(include) = attribute_value(line, "Include").filter(|i| i.contains('*')) else {
+                    continue;
+                };
+                items.push(WildcardItem {
+                    item_type: element_name(line).to_string(),
+                    include: include.to_string(),
+                    patterns: split(include),
+                    excludes: attribute_value(line, "Exclude").map(split).unwrap_or_default(),
+                });
+            }
+        }
+        items
+    }
+    
+    /// Replaces each wildcard item with explicit items for the files it expands to.
+    /// `expansions` maps (item type, raw Include) to the files; metadata of the wildcard item is
+    /// copied to every explicit item. Returns the number of wildcard items replaced.
+    pub fn expand_wildcard_items(&mut self, expansions: &HashMap<(String, String), Vec<String>>) -> usize {
+        let lines: Vec<&str> = self.content.lines().collect();
+        let mut output: Vec<String> = Vec::with_capacity(lines.len());
+        let mut replaced = 0;
+        let mut i = 0;
+        
+        while i < lines.len() {
+            let trimmed = lines[i].trim();
+            let item_type = element_name(trimmed);
+            let expansion = attribute_value(trimmed, "Include")
+                .filter(|include| include.contains('*'))
+                .and_then(|include| expansions.get(&(item_type.to_string(), include.to_string())));
+            let Some(files) = expansion else {
+                output.push(lines[i].to_string());
+                i += 1;
+                continue;
+            };
+            
+            let indent: String = lines[i].chars().take_while(|c| c.is_whitespace()).collect();
+            let closing_tag = format!("</{}>", item_type);
+            let mut metadata = Vec::new();
+            if !trimmed.ends_with("/>") && !trimmed.ends_with(&closing_tag) {
+                i += 1;
+                while i < lines.len() && !lines[i].trim().starts_with(&closing_tag) {
+                    metadata.push(lines[i]);
+                    i += 1;
+                }
+            }
+            
+            for file in files {
+                if metadata.is_empty() {
+                    output.push(format!("{}<{} Include=\"{}\" />", indent, item_type, file));
+                } else {
+                    output.push(format!("{}<{} Include=\"{}\">", indent, item_type, file));
+                    output.extend(metadata.iter().map(|m| m.to_string()));
+                    output.push(format!("{}{}", indent, closing_tag));
+                }
+            }
+            replaced += 1;
+            i += 1;
+        }
+        
+        self.content = output.join("\n");
+        replaced
+    }
+    
+    /// Replaces self-closing explicit items with wildcard items. `groups` maps
+    /// (item type, Include) to the wildcard pattern that covers it; the wildcard is written in
+    /// place of the first item of each group. Returns the number of items removed.
+    pub fn collapse_to_wildcards(&mut self, groups: &HashMap<(String, String), String>) -> usize {
+        let mut emitted = HashSet::new();
+        let mut output = Vec::new();
+        let mut removed = 0;
+        
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            let item_type = element_name(trimmed);
+            let pattern = attribute_value(trimmed, "Include")
+                .filter(|_| trimmed.ends_with("/>"))
+                .and_then(|include| groups.get(&(item_type.to_string(), include.to_string())));
+            let Some(pattern) = pattern else {
+                output.push(line.to_string());
+                continue;
+            };
+            
+            removed += 1;
+            if emitted.insert((item_type.to_string(), pattern.clone())) {
+                let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+                output.push(format!("{}<{} Include=\"{}\" />", indent, item_type, pattern));
+            }
+        }
+        
+        self.content = output.join("\n");
+        removed
+    }
+    
+    /// Adds `<ClCompile Update="..." />` items for files that are only covered by a wildcard item,
+    /// placing them in the ItemGroup of the first wildcard item. Returns the paths that were added.
+    pub fn add_update_items(&mut self, paths: &[String]) -> Result<Vec<String>> {
+        let existing: HashSet<String> = self
+            .content
+            .lines()
+            .filter_map(|line| attribute_value(line, "Update"))
+            .map(include_key)
+            .collect();
+        let new_paths: Vec<String> = paths
+            .iter()
+            .filter(|p| !existing.contains(&include_key(p)))
+            .cloned()
+            .collect();
+        if new_paths.is_empty() {
+            return Ok(new_paths);
+        }
+        
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let wildcard_line = lines
+            .iter()
+            .position(|line| {
+                let trimmed = line.trim();
+                element_name(trimmed) == "ClCompile" && attribute_value(trimmed, "Include").is_some_and(|p| p.contains('*'))
+            })
+            .ok_or_else(|| anyhow::anyhow!("No wildcard ClCompile item found in {}", self.path.display()))?;
+        let group_end = (wildcard_line..lines.len())
+            .find(|&k| lines[k].trim().starts_with("</ItemGroup>"))
+            .ok_or_else(|| anyhow::anyhow!("Unterminated ItemGroup in {}", self.path.display()))?;
+        
+        let indent: String = lines[wildcard_line].chars().take_while(|c| c.is_whitespace()).collect();
+        for (offset, path) in new_paths.iter().enumerate() {
+            lines.insert(group_end + offset, format!("{}<ClCompile Update=\"{}\" />", indent, path));
+        }
+        
+        self.content = lines.join("\n");
+        Ok(new_paths)
+    }
+    
+    /// Adds items in a new `<ItemGroup Label="...">`, so they can be found (and removed) as a unit later
+    pub fn add_labeled_item_group(&mut self, label: &str, items: &[(String, String)]) -> Result<()> {
+        let pos = self
+            .content
+            .rfind("</Project>")
+            .ok_or_else(|| anyhow::anyhow!("No closing </Project> tag in {}", self.path.display()))?;
+        
+        let mut group = format!("  <ItemGroup Label=\"{}\">\n", label);
+        for (item_type, include) in items {
+            group.push_str(&format!("    <{} Include=\"{}\" />\n", item_type, include));
+        }
+        group.push_str("  </ItemGroup>\n");
+        self.content.insert_str(pos, &group);
+        Ok(())
+    }
+
+    /// Removes the ItemGroup with the given Label together with everything inside it.
+    /// Returns false when no such group exists.
+    pub fn remove_labeled_item_group(&mut self, label: &str) -> bool {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let Some(start) = lines.iter().position(|line| {
+            let trimmed = line.trim();
+            trimmed.starts_with("<ItemGroup") && attribute_value(trimmed, "Label") == Some(label)
+        }) else {
+            return false;
+        };
+        
+        let end = (start..lines.len())
+            .find(|&k| lines[k].trim().starts_with("</ItemGroup>"))
+            .unwrap_or(lines.len() - 1);
+        lines.drain(start..=end);
+        
+        self.content = lines.join("\n");
+        true
+    }
+
+    /// Removes the items (of any type) whose Include equals one of `paths`, ignoring case and slash direction.
+    /// Returns the item type and the exact lines of every removed item.
+    pub fn remove_items(&mut self, paths: &[String]) -> Vec<(String, Vec<String>)> {
+        let wanted: HashSet<String> = paths.iter().map(|p| include_key(p)).collect();
+        let (content, removed) = remove_item_entries(&self.content, |_, include| wanted.contains(&include_key(include)));
+        self.content = content;
+        removed
+    }
+
+    /// Changes the item type of the item whose Include is `path` (e.g. None to CustomBuild), moving it
+    /// with its metadata next to the items of the new type. Returns the previous type when it changed.
+    pub fn set_item_type(&mut self, path: &str, item_type: &str) -> Result<Option<String>> {
+        let Some((content, previous, lines)) = take_item_entry(&self.content, |element, include| {
+            element != item_type && same_path(include, path)
+        }) else {
+            return Ok(None);
+        };
+        self.content = content;
+        self.insert_item_lines(item_type, &retyped_lines(&lines, &previous, item_type))?;
+        Ok(Some(previous))
+    }
+
+    /// Inserts previously removed item lines into the first unlabeled ItemGroup holding items of
+    /// the same type (or an empty one left behind by the removal), or into a new ItemGroup
+    pub fn insert_item_lines(&mut self, item_type: &str, item_lines: &[String]) -> Result<()> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut group_start = None;
+        let mut insert_at = None;
+        let mut empty_group_end = None;
+        
+        for (k, line) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("<ItemGroup") && !trimmed.ends_with("/>") {
+                group_start = attribute_value(trimmed, "Label").is_none().then_some(k);
+            } else if trimmed.starts_with("</ItemGroup>") {
+                if group_start == Some(k.saturating_sub(1)) && empty_group_end.is_none() {
+                    empty_group_end = Some(k);
+                }
+                group_start = None;
+            } else if group_start.is_some() && element_name(trimmed) == item_type {
+                insert_at = (k..lines.len()).find(|&end| lines[end].trim().starts_with("</ItemGroup>"));
+                break;
+            }
+        }
+        let insert_at = insert_at.or(empty_group_end);
+        
+        match insert_at {
+            Some(pos) => {
+                for (offset, line) in item_lines.iter().enumerate() {
+                    lines.insert(pos + offset, line.clone());
+                }
+            }
+            None => {
+                let pos = lines
+                    .iter()
+                    .rposition(|line| line.trim().starts_with("</Project>"))
+                    .ok_or_else(|| anyhow::anyhow!("No closing </Project> tag in {}", self.path.display()))?;
+                lines.insert(pos, "  <ItemGroup>".to_string());
+                for (offset, line) in item_lines.iter().enumerate() {
+                    lines.insert(pos + 1 + offset, line.clone());
+                }
+                lines.insert(pos + 1 + item_lines.len(), "  </ItemGroup>".to_string());
+            }
+        }
+        
+        self.content = lines.join("\n");
+        Ok(())
+    }
+
+    /// Removes one `value` entry from every `<element>a;b;%(element)</element>` list in the
+    /// ItemDefinitionGroups (e.g. an include directory added by `add_include_directory`).
+    /// Lists left with only the inherited `%(element)` are deleted, as are tool sections left empty.
+    /// With `config`, only the ItemDefinitionGroups matching it (see `config_matches`) are changed.
+    /// Returns the number of lists changed.
+    pub fn remove_list_value(&mut self, element: &str, value: &str, config: Option<&str>) -> usize {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let open = format!("<{}>", element);
+        let close = format!("</{}>", element);
+        let inherited = format!("%({})", element);
+        let mut changed = 0;
+        let mut selected = true;
+        let mut i = 0;
+        
+        while i < lines.len() {
+            let trimmed = lines[i].trim();
+            if trimmed.starts_with("<ItemDefinitionGroup") {
+                let condition = attribute_value(trimmed, "Condition").unwrap_or_default();
+                selected = config.is_none_or(|config| condition_matches(condition, config));
+            }
+            if !selected {
+                i += 1;
+                continue;
+            }
+            let Some(list) = trimmed.strip_prefix(&open).and_then(|rest| rest.strip_suffix(&close)) else {
+                i += 1;
+                continue;
+            };
+            
+            let mut entries: Vec<&str> = list.split(';').collect();
+            let Some(pos) = entries.iter().position(|e| e.trim().eq_ignore_ascii_case(value.trim())) else {
+                i += 1;
+                continue;
+            };
+            entries.remove(pos);
+            changed += 1;
+            
+            let remaining: Vec<&str> = entries.into_iter().filter(|e| !e.is_empty()).collect();
+            if remaining.is_empty() || remaining == [inherited.as_str()] {
+                lines.remove(i);
+                // Drop a tool section (e.g. <Link>) that only existed for this list
+                if i > 0 && i < lines.len() {
+                    let tool = element_name(lines[i - 1].trim()).to_string();
+                    if lines[i - 1].trim() == format!("<{}>", tool) && lines[i].trim() == format!("</{}>", tool) {
+                        lines.drain(i - 1..=i);
+                        i -= 1;
+                    }
+                }
+            } else {
+                let indent: String = lines[i].chars().take_while(|c| c.is_whitespace()).collect();
+                lines[i] = format!("{}{}{}{}", indent, open, remaining.join(";"), close);
+                i += 1;
+            }
+        }
+        
+        self.content = lines.join("\n");
+        changed
+    }
+
+    pub fn add_include_directory(&mut self, include_path: &str) -> Result<Vec<String>> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut modified_configs = Vec::new();
+        let mut i = 0;
+
+        while i < lines.len() {
+            // Look for ItemDefinitionGroup with Condition
+            if lines[i].trim_start().starts_with("<ItemDefinitionGroup Condition=") {
+                // Extract configuration name
+                if let Some(condition_start) = lines[i].find("Condition=\"") {
+                    if let Some(condition_end) = lines[i][condition_start + 11..].find('"') {
+                        let condition = &lines[i][condition_start + 11..condition_start + 11 + condition_end];
+                        modified_configs.push(condition.to_string());
+                    }
+                }
+
+                // Look for ClCompile section within this ItemDefinitionGroup
+                let mut j = i + 1;
+                let mut found_clcompile = false;
+                while j < lines.len() && !lines[j].trim().starts_with("</ItemDefinitionGroup>") {
+                    if lines[j].trim_start().starts_with("<ClCompile>") {
+                        found_clcompile = true;
+                        // Look for existing AdditionalIncludeDirectories or find where to insert
+                        let mut k = j + 1;
+                        let mut found_includes = false;
+                        while k < lines.len() && !lines[k].trim().starts_with("</ClCompile>") {
+                            if lines[k].trim_start().starts_with("<AdditionalIncludeDirectories>") {
+                                if list_contains(&lines[k], "AdditionalIncludeDirectories", include_path) {
+                                    // Already listed, so this configuration is left unchanged
+                                    modified_configs.pop();
+                                } else if lines[k].contains("%(AdditionalIncludeDirectories)") {
+                                    // Add to existing include directories
+                                    lines[k] = lines[k].replace("%(AdditionalIncludeDirectories)", &format!("{};%(AdditionalIncludeDirectories)", include_path));
+                                } else {
+                                    lines[k] = lines[k].replace("</AdditionalIncludeDirectories>", &format!(";{}</AdditionalIncludeDirectories>", include_path));
+                                }
+                                found_includes = true;
+                                break;
+                            }
+                            k += 1;
+                        }
+                        if !found_includes {
+                            // Insert new AdditionalIncludeDirectories after ClCompile start
+                            lines.insert(j + 1, format!("      <AdditionalIncludeDirectories>{};%(AdditionalIncludeDirectories)</AdditionalIncludeDirectories>", include_path));
+                        }
+                        break;
+                    }
+                    j += 1;
+                }
+                
+                if !found_clcompile {
+                    // Insert new ClCompile section with include directory
+                    lines.insert(i + 1, "    <ClCompile>".to_string());
+                    lines.insert(i + 2, format!("      <AdditionalIncludeDirectories>{};%(AdditionalIncludeDirectories)</AdditionalIncludeDirectories>", include_path));
+                    lines.insert(i + 3, "    </ClCompile>".to_string());
+                }
+            }
+            i += 1;
+        }
+
+        self.content = lines.join("\n");
+        Ok(modified_configs)
+    }
+
+    /// Adds a library directory to every configuration, or only to those matching `config` (see `config_matches`)
+    pub fn add_library_directory(&mut self, lib_path: &str, config: Option<&str>) -> Result<Vec<String>> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut modified_configs = Vec::new();
+        let mut i = 0;
+
+        while i < lines.len() {
+            // Look for ItemDefinitionGroup with Condition
+            if lines[i].trim_start().starts_with("<ItemDefinitionGroup Condition=") {
+                let condition = attribute_value(&lines[i], "Condition").unwrap_or_default().to_string();
+                if config.is_some_and(|config| !condition_matches(&condition, config)) {
+                    i += 1;
+                    continue;
+                }
+                modified_configs.push(condition);
+
+                // Look for Link section within this ItemDefinitionGroup
+                let mut j = i + 1;
+                let mut found_link = false;
+                while j < lines.len() && !lines[j].trim().starts_with("</ItemDefinitionGroup>") {
+                    if lines[j].trim_start().starts_with("<Link>") {
+                        found_link = true;
+                        // Look for existing AdditionalLibraryDirectories or find where to insert
+                        let mut k = j + 1;
+                        let mut found_lib_dirs = false;
+                        while k < lines.len() && !lines[k].trim().starts_with("</Link>") {
+                            if lines[k].trim_start().starts_with("<AdditionalLibraryDirectories>") {
+                                if list_contains(&lines[k], "AdditionalLibraryDirectories", lib_path) {
+                                    // Already listed, so this configuration is left unchanged
+                                    modified_configs.pop();
+                                } else if lines[k].contains("%(AdditionalLibraryDirectories)") {
+                                    // Add to existing library directories
+                                    lines[k] = lines[k].replace("%(AdditionalLibraryDirectories)", &format!("{};%(AdditionalLibraryDirectories)", lib_path));
+                                } else {
+                                    lines[k] = lines[k].replace("</AdditionalLibraryDirectories>", &format!(";{}</AdditionalLibraryDirectories>", lib_path));
+                                }
+                                found_lib_dirs = true;
                                 break;
                             }
                             k += 1;
@@ -267,146 +1490,876 @@ impl VcxprojFile {
                         }
                         break;
                     }
-                    j += 1;
+                    j += 1;
+                }
+                
+                if !found_link {
+                    // Insert new Link section with library directory
+                    lines.insert(i + 1, "    <Link>".to_string());
+                    lines.insert(i + 2, format!("      <AdditionalLibraryDirectories>{};%(AdditionalLibraryDirectories)</AdditionalLibraryDirectories>", lib_path));
+                    lines.insert(i + 3, "    </Link>".to_string());
+                }
+            }
+            i += 1;
+        }
+
+        self.content = lines.join("\n");
+        Ok(modified_configs)
+    }
+
+    /// Adds a library to AdditionalDependencies at `position`, in every configuration or only in
+    /// those matching `config` (see `config_matches`). Returns the conditions of the changed configurations.
+    pub fn add_library_dependency(&mut self, lib_name: &str, config: Option<&str>, position: &ListPosition) -> Result<Vec<String>> {
+        self.add_tool_list_value("Link", "AdditionalDependencies", lib_name, config, position)
+    }
+
+    /// Adds `value` to the `<element>` list of a tool (e.g. ClCompile/PreprocessorDefinitions) at
+    /// `position`, in every configuration or only in those matching `config`. Lists and tool sections
+    /// are created as needed. Returns the conditions of the changed configurations.
+    pub fn add_tool_list_value(&mut self, tool: &str, element: &str, value: &str, config: Option<&str>, position: &ListPosition) -> Result<Vec<String>> {
+        let open = format!("<{}>", element);
+        let close = format!("</{}>", element);
+        let tool_open = format!("<{}>", tool);
+        let tool_close = format!("</{}>", tool);
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut modified_configs = Vec::new();
+        let mut i = 0;
+
+        while i < lines.len() {
+            // Look for ItemDefinitionGroup with Condition
+            if lines[i].trim_start().starts_with("<ItemDefinitionGroup Condition=") {
+                let condition = attribute_value(&lines[i], "Condition").unwrap_or_default().to_string();
+                if config.is_some_and(|config| !condition_matches(&condition, config)) {
+                    i += 1;
+                    continue;
+                }
+                let new_list = || -> Result<String> {
+                    let line = insert_list_value(&format!("{}%({}){}", open, element, close), element, value, position)
+                        .with_context(|| format!("Cannot add {} to {}", value, condition_config(&condition)))?;
+                    Ok(format!("      {}", line.trim_start()))
+                };
+
+                // Look for the tool section within this ItemDefinitionGroup
+                let mut j = i + 1;
+                let mut found_tool = false;
+                while j < lines.len() && !lines[j].trim().starts_with("</ItemDefinitionGroup>") {
+                    if lines[j].trim_start().starts_with(&tool_open) {
+                        found_tool = true;
+                        // Look for the existing list or find where to insert
+                        let mut k = j + 1;
+                        let mut found_list = false;
+                        while k < lines.len() && !lines[k].trim().starts_with(&tool_close) {
+                            if lines[k].trim_start().starts_with(&open) {
+                                // Values already listed leave this configuration unchanged
+                                if !list_contains(&lines[k], element, value) {
+                                    lines[k] = insert_list_value(&lines[k], element, value, position)
+                                        .with_context(|| format!("Cannot add {} to {}", value, condition_config(&condition)))?;
+                                    modified_configs.push(condition.clone());
+                                }
+                                found_list = true;
+                                break;
+                            }
+                            k += 1;
+                        }
+                        if !found_list {
+                            // Insert the new list after the tool section start
+                            lines.insert(j + 1, new_list()?);
+                            modified_configs.push(condition.clone());
+                        }
+                        break;
+                    }
+                    j += 1;
+                }
+                
+                if !found_tool {
+                    // Insert a new tool section with the list
+                    let list = new_list()?;
+                    lines.insert(i + 1, format!("    {}", tool_open));
+                    lines.insert(i + 2, list);
+                    lines.insert(i + 3, format!("    {}", tool_close));
+                    modified_configs.push(condition.clone());
+                }
+            }
+            i += 1;
+        }
+
+        self.content = lines.join("\n");
+        Ok(modified_configs)
+    }
+
+    /// Sets `<name>value</name>` properties in the `<PropertyGroup Label="...">`, creating the group
+    /// after the UserMacros group (or before the first ItemDefinitionGroup) when missing.
+    /// Returns the names of the properties that were added or changed.
+    pub fn set_labeled_properties(&mut self, label: &str, properties: &[(&str, &str)]) -> Result<Vec<String>> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let start = match lines.iter().position(|line| {
+            let trimmed = line.trim();
+            trimmed.starts_with("<PropertyGroup") && attribute_value(trimmed, "Label") == Some(label)
+        }) {
+            Some(start) if lines[start].trim().ends_with("/>") => {
+                lines[start] = format!("{}>", lines[start].trim_end().trim_end_matches("/>").trim_end());
+                lines.insert(start + 1, "  </PropertyGroup>".to_string());
+                start
+            }
+            Some(start) => start,
+            None => {
+                let pos = lines
+                    .iter()
+                    .position(|line| line.trim().starts_with("<PropertyGroup Label=\"UserMacros\""))
+                    .map(|k| k + 1)
+                    .or_else(|| lines.iter().position(|line| line.trim_start().starts_with("<ItemDefinitionGroup")))
+                    .or_else(|| lines.iter().rposition(|line| line.trim_start().starts_with("</Project>")))
+                    .ok_or_else(|| anyhow::anyhow!("No closing </Project> tag in {}", self.path.display()))?;
+                lines.insert(pos, format!("  <PropertyGroup Label=\"{}\">", label));
+                lines.insert(pos + 1, "  </PropertyGroup>".to_string());
+                pos
+            }
+        };
+        
+        let mut changed = Vec::new();
+        for (name, value) in properties {
+            let open = format!("<{}>", name);
+            let new_line = format!("    <{}>{}</{}>", name, value, name);
+            let end = (start..lines.len())
+                .find(|&k| lines[k].trim().starts_with("</PropertyGroup>"))
+                .unwrap_or(lines.len());
+            match (start + 1..end).find(|&k| lines[k].trim_start().starts_with(&open)) {
+                Some(k) if lines[k].trim() == new_line.trim() => {}
+                Some(k) => {
+                    lines[k] = new_line;
+                    changed.push(name.to_string());
+                }
+                None => {
+                    lines.insert(end, new_line);
+                    changed.push(name.to_string());
+                }
+            }
+        }
+        
+        self.content = lines.join("\n");
+        Ok(changed)
+    }
+
+    /// Sets the `<name>` property wherever a PropertyGroup defines it or, when none does, in every
+    /// PropertyGroup labeled `label` (e.g. each configuration's "Configuration" group).
+    /// Returns the condition of every group that changed, with its previous value (`None` when added).
+    pub fn set_property_in_groups(&mut self, name: &str, value: &str, label: &str) -> Vec<(Option<String>, Option<String>)> {
+        let open = format!("<{}>", name);
+        let close = format!("</{}>", name);
+        let defined = self.get_properties().iter().any(|property| property.name == name);
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut changes = Vec::new();
+        let mut group: Option<(Option<String>, bool)> = None;
+        let mut i = 0;
+        while i < lines.len() {
+            let trimmed = lines[i].trim().to_string();
+            if trimmed.starts_with("<PropertyGroup") && !trimmed.ends_with("/>") {
+                let condition = attribute_value(&trimmed, "Condition").map(str::to_string);
+                group = Some((condition, attribute_value(&trimmed, "Label") == Some(label)));
+            } else if trimmed.starts_with("</PropertyGroup>") {
+                if let Some((condition, true)) = group.take().filter(|_| !defined) {
+                    lines.insert(i, format!("    {}{}{}", open, value, close));
+                    changes.push((condition, None));
+                    i += 1;
+                }
+            } else if let Some((condition, _)) = &group {
+                if let Some(current) = trimmed.strip_prefix(&open).and_then(|rest| rest.strip_suffix(&close)) {
+                    if current != value {
+                        changes.push((condition.clone(), Some(current.to_string())));
+                        lines[i] = lines[i].replacen(&trimmed, &format!("{}{}{}", open, value, close), 1);
+                    }
+                }
+            }
+            i += 1;
+        }
+        
+        self.content = lines.join("\n");
+        changes
+    }
+    
+    /// Sets the `<name>` property of configuration `config` (e.g. "Release|x64") in its PropertyGroups
+    /// that define it or, when none does, in its group labeled `label`. Returns the previous value
+    /// (`Some(None)` when added), or `None` when nothing changed or the configuration has no such group.
+    pub fn set_config_group_property(&mut self, name: &str, value: &str, label: &str, config: &str) -> Option<Option<String>> {
+        let open = format!("<{}>", name);
+        let close = format!("</{}>", name);
+        let own_group = |condition: Option<&str>| condition.is_some_and(|c| condition_config(c).eq_ignore_ascii_case(config));
+        let defined = self.get_properties().iter().any(|property| property.name == name && own_group(property.condition.as_deref()));
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut previous = None;
+        let mut group: Option<bool> = None;
+        let mut i = 0;
+        while i < lines.len() {
+            let trimmed = lines[i].trim().to_string();
+            if trimmed.starts_with("<PropertyGroup") && !trimmed.ends_with("/>") {
+                let labeled = attribute_value(&trimmed, "Label") == Some(label);
+                group = own_group(attribute_value(&trimmed, "Condition")).then_some(labeled);
+            } else if trimmed.starts_with("</PropertyGroup>") {
+                if group.take() == Some(true) && !defined && previous.is_none() {
+                    let indent: String = lines[i].chars().take_while(|c| c.is_whitespace()).collect();
+                    lines.insert(i, format!("{}  {}{}{}", indent, open, value, close));
+                    previous = Some(None);
+                    i += 1;
+                }
+            } else if group.is_some() {
+                if let Some(current) = trimmed.strip_prefix(&open).and_then(|rest| rest.strip_suffix(&close)) {
+                    if current != value {
+                        previous = Some(Some(current.to_string()));
+                        lines[i] = lines[i].replacen(&trimmed, &format!("{}{}{}", open, value, close), 1);
+                    }
+                }
+            }
+            i += 1;
+        }
+        
+        if previous.is_some() {
+            self.content = lines.join("\n");
+        }
+        previous
+    }
+    
+    /// Removes the `<name>` property from the PropertyGroups of configuration `config`. Returns the
+    /// value it had, or `None` when no group of the configuration defined it.
+    pub fn remove_config_group_property(&mut self, name: &str, config: &str) -> Option<String> {
+        let open = format!("<{}>", name);
+        let close = format!("</{}>", name);
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut previous = None;
+        let mut in_group = false;
+        let mut group_start = None;
+        let mut i = 0;
+        while i < lines.len() {
+            let trimmed = lines[i].trim().to_string();
+            if trimmed.starts_with("<PropertyGroup") && !trimmed.ends_with("/>") {
+                in_group = attribute_value(&trimmed, "Condition").is_some_and(|c| condition_config(c).eq_ignore_ascii_case(config));
+                group_start = (in_group && attribute_value(&trimmed, "Label").is_none()).then_some(i);
+            } else if trimmed.starts_with("</PropertyGroup>") {
+                // A plain group left empty goes too
+                if let Some(start) = group_start.filter(|start| *start + 1 == i && previous.is_some()) {
+                    lines.drain(start..=i);
+                    in_group = false;
+                    group_start = None;
+                    i = start;
+                    continue;
+                }
+                in_group = false;
+            } else if in_group {
+                if let Some(current) = trimmed.strip_prefix(&open).and_then(|rest| rest.strip_suffix(&close)) {
+                    previous = Some(current.to_string());
+                    lines.remove(i);
+                    continue;
+                }
+            }
+            i += 1;
+        }
+        
+        if previous.is_some() {
+            self.content = lines.join("\n");
+        }
+        previous
+    }
+    
+    /// Sets the ToolsVersion attribute of the root `<Project>` element, adding it when missing.
+    /// Returns the previous value (`None` when it was missing) if it changed.
+    pub fn set_tools_version(&mut self, version: &str) -> Option<Option<String>> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let index = lines.iter().position(|line| line.trim_start().starts_with("<Project "))?;
+        let previous = attribute_value(lines[index].trim(), "ToolsVersion").map(str::to_string);
+        match &previous {
+            Some(current) if current == version => return None,
+            Some(current) => {
+                lines[index] = lines[index].replacen(&format!("ToolsVersion=\"{}\"", current), &format!("ToolsVersion=\"{}\"", version), 1);
+            }
+            // Visual Studio writes it right before the namespace
+            None if lines[index].contains(" xmlns=") => {
+                lines[index] = lines[index].replacen(" xmlns=", &format!(" ToolsVersion=\"{}\" xmlns=", version), 1);
+            }
+            None => {
+                lines[index] = lines[index].replacen("<Project ", &format!("<Project ToolsVersion=\"{}\" ", version), 1);
+            }
+        }
+        
+        self.content = lines.join("\n");
+        Some(previous)
+    }
+    
+    /// Adds `<Import Project="..." Condition="Exists('...')" />` right before (or after) the Import of
+    /// the project whose path ends with `anchor`, e.g. "Microsoft.Cpp.props".
+    /// Returns false when the project is already imported.
+    pub fn add_import(&mut self, project: &str, anchor: &str, after: bool) -> Result<bool> {
+        self.add_import_next_to(project, anchor, after, true)
+    }
+    
+    /// Adds `<Import Project="..." />` right before (or after) the Import of the project whose path
+    /// ends with `anchor` (ignoring case), guarded with `Condition="Exists('...')"` when `optional`.
+    /// Returns false when the project is already imported.
+    pub fn add_import_next_to(&mut self, project: &str, anchor: &str, after: bool, optional: bool) -> Result<bool> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        if lines.iter().any(|line| {
+            line.trim_start().starts_with("<Import ")
+                && attribute_value(line.trim(), "Project").is_some_and(|p| p.eq_ignore_ascii_case(project))
+        }) {
+            return Ok(false);
+        }
+        
+        let anchor_key = anchor.to_lowercase();
+        let index = lines
+            .iter()
+            .position(|line| {
+                line.trim_start().starts_with("<Import ")
+                    && attribute_value(line.trim(), "Project").is_some_and(|p| p.to_lowercase().ends_with(&anchor_key))
+            })
+            .ok_or_else(|| anyhow::anyhow!("No import of {} in {}", anchor, self.path.display()))?;
+        let indent: String = lines[index].chars().take_while(|c| c.is_whitespace()).collect();
+        let import = match optional {
+            true => format!("{}<Import Project=\"{}\" Condition=\"Exists('{}')\" />", indent, project, project),
+            false => format!("{}<Import Project=\"{}\" />", indent, project),
+        };
+        lines.insert(if after { index + 1 } else { index }, import);
+        
+        self.content = lines.join("\n");
+        Ok(true)
+    }
+
+    /// Adds an empty `<PropertyGroup Label="...">` right before the Import of the project whose path
+    /// ends with `anchor`, so imports after it see its properties. Returns false when the group exists.
+    pub fn add_labeled_property_group(&mut self, label: &str, anchor: &str) -> Result<bool> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        if lines.iter().any(|line| line.trim_start().starts_with("<PropertyGroup") && attribute_value(line.trim(), "Label") == Some(label)) {
+            return Ok(false);
+        }
+        let index = lines
+            .iter()
+            .position(|line| {
+                line.trim_start().starts_with("<Import ")
+                    && attribute_value(line.trim(), "Project").is_some_and(|p| p.ends_with(anchor))
+            })
+            .ok_or_else(|| anyhow::anyhow!("No import of {} in {}", anchor, self.path.display()))?;
+        let indent: String = lines[index].chars().take_while(|c| c.is_whitespace()).collect();
+        lines.splice(index..index, [
+            format!("{}<PropertyGroup Label=\"{}\">", indent, label),
+            format!("{}</PropertyGroup>", indent),
+        ]);
+        
+        self.content = lines.join("\n");
+        Ok(true)
+    }
+
+    /// The NuGet packages of the `<PackageReference>` items, with their Version attribute or metadata
+    pub fn get_package_references(&self) -> Vec<(String, Option<String>)> {
+        let lines: Vec<&str> = self.content.lines().collect();
+        let mut packages = Vec::new();
+        for (k, line) in lines.iter().enumerate() {
+            let trimmed = line.trim();
+            if !trimmed.starts_with("<PackageReference ") {
+                continue;
+            }
+            let Some(id) = attribute_value(trimmed, "Include") else {
+                continue;
+            };
+            let version = attribute_value(trimmed, "Version").map(str::to_string).or_else(|| {
+                if trimmed.ends_with("/>") {
+                    return None;
+                }
+                lines[k + 1..]
+                    .iter()
+                    .take_while(|l| !l.trim().starts_with("</PackageReference>"))
+                    .find_map(|l| l.trim().strip_prefix("<Version>")?.strip_suffix("</Version>").map(str::to_string))
+            });
+            packages.push((id.to_string(), version));
+        }
+        packages
+    }
+
+    /// Every `<Import>` in the order MSBuild evaluates them, with the ImportGroup each is in
+    pub fn get_import_elements(&self) -> Vec<Import> {
+        let mut imports = Vec::new();
+        let mut group: Option<(Option<String>, Option<String>)> = None;
+        for (k, line) in self.content.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("<ImportGroup") && !trimmed.ends_with("/>") {
+                group = Some((attribute_value(trimmed, "Label").map(str::to_string), attribute_value(trimmed, "Condition").map(str::to_string)));
+            } else if trimmed.starts_with("</ImportGroup>") {
+                group = None;
+            } else if trimmed.starts_with("<Import ") {
+                let Some(project) = attribute_value(trimmed, "Project") else {
+                    continue;
+                };
+                let (group_label, group_condition) = group.clone().unwrap_or_default();
+                imports.push(Import {
+                    line: k + 1,
+                    project: project.to_string(),
+                    condition: attribute_value(trimmed, "Condition").map(str::to_string),
+                    label: attribute_value(trimmed, "Label").map(str::to_string),
+                    in_group: group.is_some(),
+                    group_label,
+                    group_condition,
+                });
+            }
+        }
+        imports
+    }
+    
+    /// The Project paths of every `<Import>`
+    pub fn get_imports(&self) -> Vec<String> {
+        self.content
+            .lines()
+            .filter(|line| line.trim_start().starts_with("<Import "))
+            .filter_map(|line| attribute_value(line.trim(), "Project").map(str::to_string))
+            .collect()
+    }
+
+    /// Adds `<Import Project="..." />` at the end of the `<ImportGroup Label="...">`, guarded with
+    /// `Condition="Exists('...')"` when `optional`. Returns false when the project is already imported.
+    pub fn add_import_to_group(&mut self, project: &str, label: &str, optional: bool) -> Result<bool> {
+        if self.get_imports().iter().any(|p| p.eq_ignore_ascii_case(project)) {
+            return Ok(false);
+        }
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let start = lines
+            .iter()
+            .position(|line| {
+                let trimmed = line.trim();
+                trimmed.starts_with("<ImportGroup") && attribute_value(trimmed, "Label") == Some(label)
+            })
+            .ok_or_else(|| anyhow::anyhow!("No <ImportGroup Label=\"{}\"> in {}", label, self.path.display()))?;
+        let indent: String = lines[start].chars().take_while(|c| c.is_whitespace()).collect();
+        let import = match optional {
+            true => format!("{}  <Import Project=\"{}\" Condition=\"Exists('{}')\" />", indent, project, project),
+            false => format!("{}  <Import Project=\"{}\" />", indent, project),
+        };
+        
+        if lines[start].trim().ends_with("/>") {
+            lines[start] = format!("{}>", lines[start].trim_end().trim_end_matches("/>").trim_end());
+            lines.insert(start + 1, import);
+            lines.insert(start + 2, format!("{}</ImportGroup>", indent));
+        } else {
+            let end = (start..lines.len())
+                .find(|&k| lines[k].trim().starts_with("</ImportGroup>"))
+                .unwrap_or(lines.len());
+            lines.insert(end, import);
+        }
+        
+        self.content = lines.join("\n");
+        Ok(true)
+    }
+
+    /// The shared items projects (.vcxitems) the project imports, as written
+    pub fn get_shared_items_imports(&self) -> Vec<String> {
+        self.get_imports()
+            .into_iter()
+            .filter(|project| project.to_lowercase().ends_with(".vcxitems"))
+            .collect()
+    }
+
+    /// Imports a shared items project the way Visual Studio's Add Shared Project Reference does, in
+    /// the `<ImportGroup Label="Shared">` that follows the ExtensionSettings group (created if missing).
+    /// Returns false when it is already imported.
+    pub fn add_shared_items_import(&mut self, project: &str) -> Result<bool> {
+        if self.get_imports().iter().any(|p| same_path(p, project)) {
+            return Ok(false);
+        }
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let import = format!("    <Import Project=\"{}\" Label=\"Shared\" />", project);
+        let is_group = |line: &str, label: &str| {
+            let trimmed = line.trim();
+            trimmed.starts_with("<ImportGroup") && attribute_value(trimmed, "Label") == Some(label)
+        };
+        
+        if let Some(start) = lines.iter().position(|line| is_group(line, "Shared")) {
+            if lines[start].trim().ends_with("/>") {
+                lines[start] = format!("{}>", lines[start].trim_end().trim_end_matches("/>").trim_end());
+                lines.splice(start + 1..start + 1, [import, "  </ImportGroup>".to_string()]);
+            } else {
+                let end = (start..lines.len())
+                    .find(|&k| lines[k].trim().starts_with("</ImportGroup>"))
+                    .unwrap_or(lines.len());
+                lines.insert(end, import);
+            }
+        } else {
+            // After the ExtensionSettings group, or else before the first PropertySheets group
+            let index = match lines.iter().position(|line| is_group(line, "ExtensionSettings")) {
+                Some(start) if lines[start].trim().ends_with("/>") => start + 1,
+                Some(start) => (start..lines.len()).find(|&k| lines[k].trim().starts_with("</ImportGroup>")).map_or(lines.len(), |k| k + 1),
+                None => lines
+                    .iter()
+                    .position(|line| is_group(line, "PropertySheets"))
+                    .ok_or_else(|| anyhow::anyhow!("No ExtensionSettings or PropertySheets <ImportGroup> in {}", self.path.display()))?,
+            };
+            lines.splice(index..index, ["  <ImportGroup Label=\"Shared\">".to_string(), import, "  </ImportGroup>".to_string()]);
+        }
+        
+        self.content = lines.join("\n");
+        Ok(true)
+    }
+
+    /// Removes every `<Import>` whose Project matches `predicate`, along with the `<Error>` checks
+    /// NuGet's EnsureNuGetPackageBuildImports target keeps for those files. Returns the removed paths.
+    pub fn remove_imports(&mut self, predicate: impl Fn(&str) -> bool) -> Vec<String> {
+        let mut removed = Vec::new();
+        let mut lines: Vec<String> = Vec::new();
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("<Import ") {
+                if let Some(project) = attribute_value(trimmed, "Project").filter(|p| predicate(p)) {
+                    removed.push(project.to_string());
+                    continue;
+                }
+            }
+            lines.push(line.to_string());
+        }
+        lines.retain(|line| {
+            let trimmed = line.trim();
+            !(trimmed.starts_with("<Error ") && removed.iter().any(|project| trimmed.contains(project.as_str())))
+        });
+        
+        self.content = lines.join("\n");
+        removed
+    }
+
+    /// The property sheets imported in the `<ImportGroup Label="PropertySheets">` groups, with each
+    /// group's condition (`None` for a group that applies to every configuration)
+    pub fn get_property_sheets(&self) -> Vec<(Option<String>, String)> {
+        let mut sheets = Vec::new();
+        let mut group: Option<Option<String>> = None;
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("<ImportGroup") && attribute_value(trimmed, "Label") == Some("PropertySheets") {
+                if !trimmed.ends_with("/>") {
+                    group = Some(attribute_value(trimmed, "Condition").map(str::to_string));
+                }
+            } else if trimmed.starts_with("</ImportGroup>") {
+                group = None;
+            } else if let (Some(condition), true) = (&group, trimmed.starts_with("<Import ")) {
+                if let Some(project) = attribute_value(trimmed, "Project") {
+                    sheets.push((condition.clone(), project.to_string()));
+                }
+            }
+        }
+        sheets
+    }
+
+    /// Imports a property sheet in every PropertySheets group, or only in those whose condition
+    /// matches `config` (see `config_matches`); groups without a condition match every configuration.
+    /// Without any PropertySheets group, an unconditioned one is added before the UserMacros group.
+    /// Returns the conditions of the groups that changed ("" for an unconditioned group).
+    pub fn add_property_sheet(&mut self, sheet: &str, config: Option<&str>) -> Result<Vec<String>> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let is_group = |line: &str| {
+            let trimmed = line.trim();
+            trimmed.starts_with("<ImportGroup") && attribute_value(trimmed, "Label") == Some("PropertySheets")
+        };
+        if !lines.iter().any(|line| is_group(line)) {
+            let pos = lines
+                .iter()
+                .position(|line| line.trim().starts_with("<PropertyGroup Label=\"UserMacros\""))
+                .or_else(|| lines.iter().position(|line| line.trim_start().starts_with("<ItemDefinitionGroup")))
+                .ok_or_else(|| anyhow::anyhow!("No PropertySheets ImportGroup or UserMacros PropertyGroup in {}", self.path.display()))?;
+            lines.insert(pos, "  <ImportGroup Label=\"PropertySheets\">".to_string());
+            lines.insert(pos + 1, "  </ImportGroup>".to_string());
+        }
+        
+        let mut changed = Vec::new();
+        let mut i = 0;
+        while i < lines.len() {
+            if !is_group(&lines[i]) {
+                i += 1;
+                continue;
+            }
+            let condition = attribute_value(lines[i].trim(), "Condition").unwrap_or_default().to_string();
+            if !condition.is_empty() && config.is_some_and(|config| !condition_matches(&condition, config)) {
+                i += 1;
+                continue;
+            }
+            let indent: String = lines[i].chars().take_while(|c| c.is_whitespace()).collect();
+            if lines[i].trim().ends_with("/>") {
+                lines[i] = format!("{}>", lines[i].trim_end().trim_end_matches("/>").trim_end());
+                lines.insert(i + 1, format!("{}</ImportGroup>", indent));
+            }
+            let end = (i..lines.len())
+                .find(|&k| lines[k].trim().starts_with("</ImportGroup>"))
+                .unwrap_or(lines.len());
+            let imported = lines[i + 1..end].iter().any(|line| {
+                attribute_value(line.trim(), "Project").is_some_and(|p| same_path(p, sheet))
+            });
+            if !imported {
+                lines.insert(end, format!("{}  <Import Project=\"{}\" />", indent, sheet));
+                changed.push(condition);
+            }
+            i = end + 1;
+        }
+        
+        self.content = lines.join("\n");
+        Ok(changed)
+    }
+
+    /// Removes the imports of a property sheet from the PropertySheets groups (all of them, or
+    /// those matching `config`). Returns the conditions of the groups that changed.
+    pub fn remove_property_sheet(&mut self, sheet: &str, config: Option<&str>) -> Vec<String> {
+        let mut changed = Vec::new();
+        let mut lines: Vec<String> = Vec::new();
+        let mut group: Option<String> = None;
+        for line in self.content.lines() {
+            let trimmed = line.trim();
+            if trimmed.starts_with("<ImportGroup") && attribute_value(trimmed, "Label") == Some("PropertySheets") {
+                if !trimmed.ends_with("/>") {
+                    group = Some(attribute_value(trimmed, "Condition").unwrap_or_default().to_string());
+                }
+            } else if trimmed.starts_with("</ImportGroup>") {
+                group = None;
+            } else if let Some(condition) = &group {
+                let selected = condition.is_empty()
+                    || config.is_none_or(|config| condition_matches(condition, config));
+                if selected && trimmed.starts_with("<Import ") && attribute_value(trimmed, "Project").is_some_and(|p| same_path(p, sheet)) {
+                    changed.push(condition.clone());
+                    continue;
+                }
+            }
+            lines.push(line.to_string());
+        }
+        
+        self.content = lines.join("\n");
+        changed
+    }
+
+    /// Removes the `<tool><name>` setting (e.g. ClCompile/PreprocessorDefinitions) from the
+    /// ItemDefinitionGroups matching `config`, or all of them, dropping tool sections left empty.
+    /// Returns the conditions of the groups that changed.
+    pub fn remove_tool_setting(&mut self, tool: &str, name: &str, config: Option<&str>) -> Vec<String> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut changed = Vec::new();
+        let mut condition: Option<String> = None;
+        let mut current_tool: Option<String> = None;
+        let mut i = 0;
+        
+        while i < lines.len() {
+            let trimmed = lines[i].trim().to_string();
+            if trimmed.starts_with("<ItemDefinitionGroup") {
+                condition = Some(attribute_value(&trimmed, "Condition").unwrap_or_default().to_string());
+            } else if trimmed.starts_with("</ItemDefinitionGroup>") {
+                condition = None;
+            } else if let Some(group_condition) = &condition {
+                if trimmed == format!("<{}>", tool) {
+                    current_tool = Some(tool.to_string());
+                } else if trimmed == format!("</{}>", tool) {
+                    current_tool = None;
+                } else if current_tool.is_some()
+                    && element_name(&trimmed) == name
+                    && config.is_none_or(|config| condition_matches(group_condition, config))
+                {
+                    changed.push(group_condition.clone());
+                    lines.remove(i);
+                    // Drop the tool section if this was its only setting
+                    if i > 0 && i < lines.len() && lines[i - 1].trim() == format!("<{}>", tool) && lines[i].trim() == format!("</{}>", tool) {
+                        lines.drain(i - 1..=i);
+                        current_tool = None;
+                        i -= 1;
+                    }
+                    continue;
+                }
+            }
+            i += 1;
+        }
+        
+        self.content = lines.join("\n");
+        changed
+    }
+
+    /// Sets the `<tool><name>` setting (e.g. ClCompile/RuntimeLibrary) in the configuration
+    /// ItemDefinitionGroups matching `config`, or all of them, adding the tool section where missing.
+    /// Returns the condition and previous value of every group that changed.
+    pub fn set_tool_setting(&mut self, tool: &str, name: &str, value: &str, config: Option<&str>) -> Vec<(String, Option<String>)> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut changed = Vec::new();
+        let open_tool = format!("<{}>", tool);
+        let close_tool = format!("</{}>", tool);
+        let setting = format!("<{}>{}</{}>", name, value, name);
+        let mut i = 0;
+        
+        while i < lines.len() {
+            if !lines[i].trim_start().starts_with("<ItemDefinitionGroup Condition=") {
+                i += 1;
+                continue;
+            }
+            let condition = attribute_value(&lines[i], "Condition").unwrap_or_default().to_string();
+            if config.is_some_and(|config| !condition_matches(&condition, config)) {
+                i += 1;
+                continue;
+            }
+            let group_indent: String = lines[i].chars().take_while(|c| c.is_whitespace()).collect();
+            let end = (i..lines.len()).find(|&j| lines[j].trim().starts_with("</ItemDefinitionGroup>")).unwrap_or(lines.len());
+            match (i + 1..end).find(|&j| lines[j].trim() == open_tool) {
+                Some(tool_start) => {
+                    let tool_end = (tool_start..end).find(|&j| lines[j].trim() == close_tool).unwrap_or(end);
+                    let existing = (tool_start + 1..tool_end).find(|&j| element_name(lines[j].trim()) == name);
+                    match existing {
+                        Some(j) => {
+                            let previous = parse_metadata_line(lines[j].trim()).map(|m| m.value);
+                            if previous.as_deref() != Some(value) {
+                                let indent: String = lines[j].chars().take_while(|c| c.is_whitespace()).collect();
+                                lines[j] = format!("{}{}", indent, setting);
+                                changed.push((condition, previous));
+                            }
+                        }
+                        None => {
+                            lines.insert(tool_start + 1, format!("{}    {}", group_indent, setting));
+                            changed.push((condition, None));
+                        }
+                    }
                 }
-                
-                if !found_link {
-                    // Insert new Link section with library directory
-                    lines.insert(i + 1, format!("    <Link>"));
-                    lines.insert(i + 2, format!("      <AdditionalLibraryDirectories>{};%(AdditionalLibraryDirectories)</AdditionalLibraryDirectories>", lib_path));
-                    lines.insert(i + 3, format!("    </Link>"));
+                None => {
+                    lines.insert(i + 1, format!("{}  {}", group_indent, open_tool));
+                    lines.insert(i + 2, format!("{}    {}", group_indent, setting));
+                    lines.insert(i + 3, format!("{}  {}", group_indent, close_tool));
+                    changed.push((condition, None));
                 }
             }
             i += 1;
         }
-
+        
         self.content = lines.join("\n");
-        Ok(modified_configs)
+        changed
     }
 
-    pub fn add_library_dependency(&mut self, lib_name: &str) -> Result<Vec<String>> {
+    /// Copies the tool sections (ClCompile, Link, ...) of the ItemDefinitionGroup of configuration
+    /// `from` to that of `to`, both exact names such as "Debug|x64": sections `from` has replace those
+    /// of `to` or are added, and sections only `to` has are removed. With `tools`, only those sections
+    /// are copied. Returns each tool section that changed with "added", "replaced" or "removed".
+    pub fn copy_item_definitions(&mut self, from: &str, to: &str, tools: &[String]) -> Result<Vec<(String, &'static str)>> {
         let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
-        let mut modified_configs = Vec::new();
-        let mut i = 0;
-
-        while i < lines.len() {
-            // Look for ItemDefinitionGroup with Condition
-            if lines[i].trim_start().starts_with("<ItemDefinitionGroup Condition=") {
-                // Extract configuration name
-                if let Some(condition_start) = lines[i].find("Condition=\"") {
-                    if let Some(condition_end) = lines[i][condition_start + 11..].find('"') {
-                        let condition = &lines[i][condition_start + 11..condition_start + 11 + condition_end];
-                        modified_configs.push(condition.to_string());
-                    }
+        let group = |lines: &[String], config: &str| {
+            let start = lines.iter().position(|line| {
+                let trimmed = line.trim();
+                trimmed.starts_with("<ItemDefinitionGroup")
+                    && attribute_value(trimmed, "Condition").is_some_and(|c| condition_config(c).eq_ignore_ascii_case(config))
+            })?;
+            let end = (start..lines.len()).find(|&j| lines[j].trim().starts_with("</ItemDefinitionGroup>"))?;
+            Some((start, end))
+        };
+        let (from_start, from_end) = group(&lines, from)
+            .ok_or_else(|| anyhow::anyhow!("No ItemDefinitionGroup for configuration '{}' in {}", from, self.path.display()))?;
+        let source = tool_sections(&lines[from_start + 1..from_end]);
+        
+        let (to_start, to_end) = match group(&lines, to) {
+            Some(range) => range,
+            None => {
+                if !self.get_configurations().iter().any(|c| c.eq_ignore_ascii_case(to)) {
+                    return Err(anyhow::anyhow!("No configuration '{}' in {}", to, self.path.display()));
                 }
-
-                // Look for Link section within this ItemDefinitionGroup
-                let mut j = i + 1;
-                let mut found_link = false;
-                while j < lines.len() && !lines[j].trim().starts_with("</ItemDefinitionGroup>") {
-                    if lines[j].trim_start().starts_with("<Link>") {
-                        found_link = true;
-                        // Look for existing AdditionalDependencies or find where to insert
-                        let mut k = j + 1;
-                        let mut found_deps = false;
-                        while k < lines.len() && !lines[k].trim().starts_with("</Link>") {
-                            if lines[k].trim_start().starts_with("<AdditionalDependencies>") {
-                                // Add to existing dependencies
-                                if lines[k].contains("%(AdditionalDependencies)") {
-                                    lines[k] = lines[k].replace("%(AdditionalDependencies)", &format!("{};%(AdditionalDependencies)", lib_name));
-                                } else {
-                                    lines[k] = lines[k].replace("</AdditionalDependencies>", &format!(";{}</AdditionalDependencies>", lib_name));
-                                }
-                                found_deps = true;
-                                break;
-                            }
-                            k += 1;
-                        }
-                        if !found_deps {
-                            // Insert new AdditionalDependencies after Link start
-                            lines.insert(j + 1, format!("      <AdditionalDependencies>{};%(AdditionalDependencies)</AdditionalDependencies>", lib_name));
-                        }
-                        break;
+                // Give the configuration an empty group of its own after the source one
+                let condition = attribute_value(lines[from_start].trim(), "Condition").unwrap_or_default().replace(from, to);
+                let indent: String = lines[from_start].chars().take_while(|c| c.is_whitespace()).collect();
+                lines.insert(from_end + 1, format!("{}<ItemDefinitionGroup Condition=\"{}\">", indent, condition));
+                lines.insert(from_end + 2, format!("{}</ItemDefinitionGroup>", indent));
+                (from_end + 1, from_end + 2)
+            }
+        };
+        let target = tool_sections(&lines[to_start + 1..to_end]);
+        
+        let wanted = |tool: &str| tools.is_empty() || tools.iter().any(|t| t.eq_ignore_ascii_case(tool));
+        let mut changes = Vec::new();
+        let mut body: Vec<String> = Vec::new();
+        for (tool, section) in &target {
+            match source.iter().find(|(name, _)| name == tool) {
+                _ if tool.is_empty() || !wanted(tool) => body.extend(section.iter().cloned()),
+                Some((_, copied)) => {
+                    if copied != section {
+                        changes.push((tool.clone(), "replaced"));
                     }
-                    j += 1;
-                }
-                
-                if !found_link {
-                    // Insert new Link section with library dependency
-                    lines.insert(i + 1, format!("    <Link>"));
-                    lines.insert(i + 2, format!("      <AdditionalDependencies>{};%(AdditionalDependencies)</AdditionalDependencies>", lib_name));
-                    lines.insert(i + 3, format!("    </Link>"));
+                    body.extend(copied.iter().cloned());
                 }
+                None => changes.push((tool.clone(), "removed")),
             }
-            i += 1;
         }
+        for (tool, section) in source.iter().filter(|(tool, _)| !tool.is_empty() && wanted(tool) && !target.iter().any(|(name, _)| name == tool)) {
+            changes.push((tool.clone(), "added"));
+            body.extend(section.iter().cloned());
+        }
+        
+        if !changes.is_empty() {
+            lines.splice(to_start + 1..to_end, body);
+            self.content = lines.join("\n");
+        }
+        Ok(changes)
+    }
 
-        self.content = lines.join("\n");
-        Ok(modified_configs)
+    /// Writes the project file. Returns false (and writes nothing) when the content is unchanged.
+    pub fn save(&self) -> Result<bool> {
+        write_file(&self.path, &saved_content(&self.path, &self.content))
+            .with_context(|| format!("Failed to write vcxproj file: {}", self.path.display()))
+    }
+    
+    /// Writes the project file and its filters file together: either both are updated or neither
+    /// is. Returns whether each was written.
+    pub fn save_with_filters(&self, filter_file: Option<&FilterFile>) -> Result<(bool, bool)> {
+        let Some(filter_file) = filter_file else {
+            return Ok((self.save()?, false));
+        };
+        let content = saved_content(&self.path, &self.content);
+        let filters_content = saved_content(&filter_file.path, &filter_file.content);
+        let written = write_files(&[(&self.path, &content), (&filter_file.path, &filters_content)])
+            .with_context(|| format!("Failed to write vcxproj file and its filters: {}", self.path.display()))?;
+        Ok((written[0], written[1]))
     }
+}
 
-    pub fn save(&self) -> Result<()> {
-        fs::write(&self.path, &self.content)
-            .with_context(|| format!("Failed to write vcxproj file: {}", self.path.display()))?;
-        Ok(())
+/// The content a project or filters file is written with: the item paths of a shared items project
+/// get their `$(MSBuildThisFileDirectory)` back
+fn saved_content(path: &Path, content: &str) -> String {
+    if is_shared_items(path) {
+        add_shared_items_root(content)
+    } else {
+        content.to_string()
     }
 }
 
 impl FilterFile {
     pub fn load(path: impl AsRef<Path>) -> Result<Self> {
         let path = path.as_ref().to_path_buf();
-        let content = fs::read_to_string(&path)
-            .with_context(|| format!("Failed to read filters file: {}", path.display()))?;
+        let content = match crate::pipe::read(&path).or_else(|| crate::gitref::read(&path)) {
+            Some(content) => content?,
+            None => fs::read_to_string(&path)
+                .inspect(|content| track_loaded(&path, content))
+                .with_context(|| format!("Failed to read filters file: {}", path.display()))?,
+        };
+        let content = if is_shared_items(&path) { strip_shared_items_root(&content) } else { content };
         
         Ok(Self { path, content })
     }
+    
+    /// A filters file without any filters or entries, for projects that have none yet
+    pub fn empty(path: PathBuf) -> Self {
+        Self {
+            path,
+            content: "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<Project ToolsVersion=\"4.0\" xmlns=\"http://schemas.microsoft.com/developer/msbuild/2003\">\n</Project>".to_string(),
+        }
+    }
 
 
-    pub fn add_source_files_with_hierarchy(&mut self, project_files: &[PathBuf], scan_relative_files: &[PathBuf]) -> Result<()> {
-        // Collect unique directories for filters using scan_relative_files for hierarchy
-        let mut dirs = HashSet::new();
-        for file in scan_relative_files {
-            if let Some(parent) = file.parent() {
-                let filter_name = parent.to_string_lossy().replace('/', "\\");
-                if !filter_name.is_empty() {
-                    dirs.insert(filter_name);
-                }
-            }
-        }
+    pub fn add_source_files_with_hierarchy(&mut self, project_files: &[PathBuf], scan_relative_files: &[PathBuf], map: &FilterMap) -> Result<()> {
+        let (filter_names, mut dirs) = assign_filters(project_files, scan_relative_files, map);
 
-        // Add filter entries
+        // Add filter entries for the directories without one
+        let existing = self.get_all_filters()?;
+        dirs.retain(|dir| !existing.keys().any(|filter| filter.eq_ignore_ascii_case(dir)));
         let mut new_filters = String::new();
         for dir in &dirs {
-            let uuid = uuid::Uuid::new_v4();
             new_filters.push_str(&format!(
-                "    <Filter Include=\"{}\">\n      <UniqueIdentifier>{{{}}}</UniqueIdentifier>\n    </Filter>\n",
-                dir, uuid.to_string().to_uppercase()
+                "    <Filter Include=\"{}\">\n      <UniqueIdentifier>{}</UniqueIdentifier>\n    </Filter>\n",
+                dir, filter_identifier(dir)
             ));
         }
 
-        // Add ClCompile entries using project_files for Include paths and scan_relative_files for Filter assignments
-        let mut new_clcompile = String::new();
-        for (i, project_file) in project_files.iter().enumerate() {
-            let scan_relative_file = &scan_relative_files[i];
-            if let Some(ext) = project_file.extension() {
-                if ext == "c" || ext == "cpp" || ext == "cc" || ext == "cxx" {
-                    let include_path = project_file.to_string_lossy().replace('/', "\\");
-                    new_clcompile.push_str(&format!("    <ClCompile Include=\"{}\">\n", include_path));
-                    
-                    if let Some(parent) = scan_relative_file.parent() {
-                        let filter_name = parent.to_string_lossy().replace('/', "\\");
-                        if !filter_name.is_empty() {
-                            new_clcompile.push_str(&format!("      <Filter>{}</Filter>\n", filter_name));
-                        } else {
-                            new_clcompile.push_str("      <Filter>Source Files</Filter>\n");
-                        }
-                    } else {
-                        new_clcompile.push_str("      <Filter>Source Files</Filter>\n");
-                    }
-                    
-                    new_clcompile.push_str("    </ClCompile>\n");
-                }
-            }
+        // Add item entries using project_files for Include paths and the assigned filters
+        let mut new_items: BTreeMap<&str, String> = BTreeMap::new();
+        let project_dir = self.path.parent().unwrap_or_else(|| Path::new("."));
+        for (project_file, filter_name) in project_files.iter().zip(&filter_names) {
+            let include_path = include_path(&project_file.to_string_lossy());
+            let item_type = item_type_in(project_dir, &include_path);
+            new_items.entry(item_type).or_default().push_str(&format!(
+                "    <{} Include=\"{}\">\n      <Filter>{}</Filter>\n    </{}>\n",
+                item_type, include_path, filter_name, item_type
+            ));
         }
 
         // Insert filters if we have new ones
@@ -422,8 +2375,8 @@ impl FilterFile {
                     }
                 }
             } else {
-                // Create new filter ItemGroup
-                if let Some(pos) = self.content.find("  </ItemGroup>") {
+                // Create new filter ItemGroup (filter files of utility projects may have no ItemGroup at all)
+                if let Some(pos) = self.content.rfind("</Project>") {
                     let itemgroup = format!(
                         "  <ItemGroup>\n{}\n  </ItemGroup>\n",
                         new_filters.trim_end()
@@ -433,59 +2386,39 @@ impl FilterFile {
             }
         }
 
-        // Insert ClCompile entries
-        if !new_clcompile.is_empty() {
-            if let Some(pos) = self.content.find("<ClCompile Include=") {
-                // Find the ItemGroup containing ClCompile
-                let before_pos = &self.content[..pos];
-                if let Some(itemgroup_start) = before_pos.rfind("<ItemGroup>") {
-                    let after_itemgroup = &self.content[itemgroup_start..];
-                    if let Some(itemgroup_end) = after_itemgroup.find("</ItemGroup>") {
-                        let insertion_point = itemgroup_start + itemgroup_end;
-                        self.content.insert_str(insertion_point, &new_clcompile);
-                    }
-                }
-            } else {
-                // Create new ClCompile ItemGroup before closing Project
-                if let Some(pos) = self.content.rfind("</Project>") {
-                    let itemgroup = format!(
-                        "  <ItemGroup>\n{}\n  </ItemGroup>\n",
-                        new_clcompile.trim_end()
-                    );
-                    self.content.insert_str(pos, &itemgroup);
-                }
-            }
+        // Insert item entries next to existing items of the same type
+        for (item_type, entries) in new_items {
+            self.insert_into_item_group(item_type, &entries);
         }
 
         Ok(())
     }
 
-    pub fn delete_files_and_filters(&mut self, target: &str, extension: Option<&str>) -> Result<(Vec<String>, Vec<String>)> {
+    /// Deletes matching file entries and the filters left without files. Files are narrowed by
+    /// `regex` (inverted with `negate`) exactly like `VcxprojFile::delete_files`, and `include_file`
+    /// can veto individual files by path.
+    pub fn delete_files_and_filters(
+        &mut self,
+        target: &str,
+        extension: Option<&str>,
+        regex: Option<&Regex>,
+        negate: bool,
+        include_file: impl Fn(&str) -> bool,
+    ) -> Result<(Vec<String>, Vec<String>)> {
+        let include_file = |path: &str| regex_allows(regex, negate, path) && include_file(path);
         let mut deleted_files = Vec::new();
         let mut deleted_filters = Vec::new();
         let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
-        let mut filters_to_delete = HashSet::new();
         
-        // First pass: delete ClCompile entries and collect filters that might need deletion
+        // First pass: delete ClCompile, FXCompile, ... entries
         let mut i = 0;
         while i < lines.len() {
             let line = &lines[i];
+            let item_type = element_name(line.trim()).to_string();
             
-            if line.trim_start().starts_with("<ClCompile Include=\"") {
-                let should_delete = if let Some(ext) = extension {
-                    // Delete by extension
-                    line.contains(&format!(".{}", ext))
-                } else {
-                    // Delete by specific file path or folder
-                    if target.ends_with('/') || target.ends_with('\\') {
-                        // Folder deletion - check if file is in this folder
-                        let target_normalized = target.replace('/', "\\");
-                        line.contains(&target_normalized) || line.contains(&target.replace('\\', "/"))
-                    } else {
-                        // Specific file deletion
-                        line.contains(target)
-                    }
-                };
+            if COMPILED_ITEM_TYPES.contains(&item_type.as_str()) && line.trim_start().starts_with(&format!("<{} Include=\"", item_type)) {
+                let should_delete = attribute_value(line, "Include")
+                    .is_some_and(|include| matches_delete_target(include, target, extension) && include_file(include));
                 
                 if should_delete {
                     // Extract filename for reporting
@@ -496,23 +2429,10 @@ impl FilterFile {
                         }
                     }
                     
-                    // Find the filter for this file to potentially delete later
-                    let mut j = i + 1;
-                    while j < lines.len() && !lines[j].trim().starts_with("</ClCompile>") {
-                        if lines[j].trim_start().starts_with("<Filter>") {
-                            if let Some(filter_start) = lines[j].find("<Filter>") {
-                                if let Some(filter_end) = lines[j].find("</Filter>") {
-                                    let filter_name = &lines[j][filter_start + 8..filter_end];
-                                    filters_to_delete.insert(filter_name.to_string());
-                                }
-                            }
-                        }
-                        j += 1;
-                    }
-                    
-                    // Remove the ClCompile entry
+                    // Remove the entry
+                    let closing_tag = format!("</{}>", item_type);
                     lines.remove(i);
-                    while i < lines.len() && !lines[i].trim().ends_with("</ClCompile>") {
+                    while i < lines.len() && !lines[i].trim().ends_with(&closing_tag) {
                         lines.remove(i);
                     }
                     if i < lines.len() {
@@ -527,10 +2447,10 @@ impl FilterFile {
         }
         
         // Handle direct filter deletion (e.g., "Header Files")
-        let is_filter_deletion = !target.contains('.') && !target.contains('/') && !target.contains('\\') && extension.is_none();
+        let is_filter_deletion = extension.is_none()
+            && ((!target.contains('.') && !target.contains('/') && !target.contains('\\'))
+                || self.filter_definitions().contains(target));
         if is_filter_deletion {
-            filters_to_delete.insert(target.to_string());
-            
             // Also delete all files in this filter
             let mut i = 0;
             while i < lines.len() {
@@ -541,19 +2461,23 @@ impl FilterFile {
                     let mut file_in_filter = false;
                     
                     while j < lines.len() && !lines[j].trim().starts_with("</ClCompile>") {
-                        if lines[j].trim_start().starts_with("<Filter>") {
-                            if lines[j].contains(&format!(">{}<", target)) {
-                                file_in_filter = true;
-                                
-                                // Extract filename for reporting
-                                if let Some(start) = line.find("Include=\"") {
-                                    if let Some(end) = line[start + 9..].find('"') {
-                                        let filename = &line[start + 9..start + 9 + end];
-                                        deleted_files.push(filename.to_string());
-                                    }
-                                }
+                        // Files in child filters (e.g. "Source Files\Engine") go with their parent
+                        let in_target_tree = parse_metadata_line(&lines[j])
+                            .is_some_and(|m| m.name == "Filter" && is_in_filter_tree(&m.value, target));
+                        if in_target_tree {
+                            file_in_filter = attribute_value(line, "Include").is_some_and(&include_file);
+                            if !file_in_filter {
                                 break;
                             }
+                            
+                            // Extract filename for reporting
+                            if let Some(start) = line.find("Include=\"") {
+                                if let Some(end) = line[start + 9..].find('"') {
+                                    let filename = &line[start + 9..start + 9 + end];
+                                    deleted_files.push(filename.to_string());
+                                }
+                            }
+                            break;
                         }
                         j += 1;
                     }
@@ -576,7 +2500,7 @@ impl FilterFile {
             }
         }
         
-        // Second pass: delete empty filters or specifically targeted filters
+        // Second pass: delete filters (including targeted ones) that no longer hold any files
         let mut i = 0;
         while i < lines.len() {
             let line = &lines[i];
@@ -587,10 +2511,9 @@ impl FilterFile {
                     if let Some(end) = line[start + 9..].find('"') {
                         let filter_name = &line[start + 9..start + 9 + end];
                         
-                        // Check if this filter should be deleted
-                        let should_delete_filter = filters_to_delete.contains(filter_name) || 
-                            (is_filter_deletion && filter_name == target) ||
-                            !self.filter_has_files(&lines, filter_name);
+                        // Filters still holding files (directly or through child filters) are kept,
+                        // so files the caller chose to keep never lose their filter
+                        let should_delete_filter = !self.filter_has_files(&lines, filter_name);
                         
                         if should_delete_filter {
                             deleted_filters.push(filter_name.to_string());
@@ -627,64 +2550,65 @@ impl FilterFile {
         Ok((deleted_files, deleted_filters))
     }
     
+    /// Whether any item is assigned to `filter_name` or one of its child filters
     fn filter_has_files(&self, lines: &[String], filter_name: &str) -> bool {
-        for line in lines {
-            if line.trim_start().starts_with("<ClCompile Include=\"") {
-                // Look ahead for filter tag
-                let line_index = lines.iter().position(|l| l == line).unwrap_or(0);
-                for j in (line_index + 1)..lines.len() {
-                    if lines[j].trim().starts_with("</ClCompile>") {
-                        break;
-                    }
-                    if lines[j].trim_start().starts_with("<Filter>") {
-                        if lines[j].contains(&format!(">{}<", filter_name)) {
-                            return true;
-                        }
-                    }
-                }
-            }
-        }
-        false
+        lines.iter().any(|line| {
+            parse_metadata_line(line).is_some_and(|m| m.name == "Filter" && is_in_filter_tree(&m.value, filter_name))
+        })
     }
 
+    /// Removes the file entries whose Include equals one of `paths`, ignoring case and slash direction.
+    /// Filter definitions are kept. Returns the removed paths as the filters file wrote them.
+    pub fn remove_items(&mut self, paths: &[String]) -> Vec<String> {
+        let wanted: HashSet<String> = paths.iter().map(|p| include_key(p)).collect();
+        let (content, removed) = remove_item_entries(&self.content, |element, include| {
+            element != "Filter" && wanted.contains(&include_key(include))
+        });
+        self.content = content;
+        removed
+            .iter()
+            .filter_map(|(_, lines)| attribute_value(lines[0].trim(), "Include").map(str::to_string))
+            .collect()
+    }
+    
+    /// Changes the element of the file entry for `path` to `item_type`, keeping its filter.
+    /// Returns whether the entry changed.
+    pub fn set_item_type(&mut self, path: &str, item_type: &str) -> bool {
+        let Some((content, previous, lines)) = take_item_entry(&self.content, |element, include| {
+            element != "Filter" && element != item_type && same_path(include, path)
+        }) else {
+            return false;
+        };
+        self.content = content;
+        let mut entry = retyped_lines(&lines, &previous, item_type).join("\n");
+        entry.push('\n');
+        self.insert_into_item_group(item_type, &entry);
+        true
+    }
+    
+    /// The filter of each ClCompile item
     pub fn get_file_filters(&self) -> Result<HashMap<String, String>> {
+        Ok(self.filters_of(|item_type| item_type == "ClCompile"))
+    }
+    
+    /// The filter of every item, whatever its type (ClInclude, None, ResourceCompile, ...)
+    pub fn get_item_filters(&self) -> Result<HashMap<String, String>> {
+        Ok(self.filters_of(|item_type| item_type != "Filter"))
+    }
+    
+    fn filters_of(&self, wanted: impl Fn(&str) -> bool) -> HashMap<String, String> {
         let mut file_to_filter = HashMap::new();
-        let lines: Vec<&str> = self.content.lines().collect();
-        let mut i = 0;
         
-        while i < lines.len() {
-            let line = lines[i].trim_start();
-            if line.starts_with("<ClCompile Include=\"") {
-                if let Some(start) = lines[i].find("Include=\"") {
-                    if let Some(end) = lines[i][start + 9..].find('"') {
-                        let file_path = &lines[i][start + 9..start + 9 + end];
-                        
-                        // Check if this is a self-closing tag
-                        if lines[i].trim().ends_with("/>") {
-                            // Self-closing tag, no filter - skip
-                        } else {
-                            // Look for the filter in subsequent lines until we find </ClCompile>
-                            let mut j = i + 1;
-                            while j < lines.len() && !lines[j].trim().starts_with("</ClCompile>") {
-                                if lines[j].trim_start().starts_with("<Filter>") {
-                                    if let Some(filter_start) = lines[j].find("<Filter>") {
-                                        if let Some(filter_end) = lines[j].find("</Filter>") {
-                                            let filter_name = &lines[j][filter_start + 8..filter_end];
-                                            file_to_filter.insert(file_path.to_string(), filter_name.to_string());
-                                            break;
-                                        }
-                                    }
-                                }
-                                j += 1;
-                            }
-                        }
-                    }
-                }
+        for item in parse_items(&self.content) {
+            if !wanted(&item.item_type) {
+                continue;
+            }
+            if let Some(filter) = item.metadata.iter().find(|m| m.name == "Filter") {
+                file_to_filter.insert(item.include, filter.value.clone());
             }
-            i += 1;
         }
         
-        Ok(file_to_filter)
+        file_to_filter
     }
     
     pub fn get_all_filters(&self) -> Result<HashMap<String, Vec<String>>> {
@@ -715,161 +2639,337 @@ impl FilterFile {
         Ok(filters)
     }
 
-    pub fn rename_filter(&mut self, from: &str, to: &str) -> Result<(bool, Vec<String>)> {
-        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
-        let mut renamed_files = Vec::new();
-        let mut filter_exists = false;
-        let mut target_filter_exists = false;
-        
-        // First pass: check if filters exist
-        for line in &lines {
-            let trimmed = line.trim_start();
-            if trimmed.starts_with("<Filter Include=\"") {
-                if let Some(start) = line.find("Include=\"") {
-                    if let Some(end) = line[start + 9..].find('"') {
-                        let filter_name = &line[start + 9..start + 9 + end];
-                        if filter_name == from {
-                            filter_exists = true;
-                        }
-                        if filter_name == to {
-                            target_filter_exists = true;
-                        }
-                    }
-                }
-            }
-        }
-        
-        if !filter_exists {
+    pub fn rename_filter(&mut self, from: &str, to: &str) -> Result<(bool, Vec<(String, String)>)> {
+        let defined = self.filter_definitions();
+        if !defined.contains(from) {
             return Err(anyhow::anyhow!("Filter '{}' not found in project", from));
         }
+        // A rename that only changes case is not a move into itself
+        if !same_path(to, from) && is_in_filter_tree(to, from) {
+            return Err(anyhow::anyhow!("Cannot move filter '{}' into itself ('{}')", from, to));
+        }
         
-        // Second pass: rename filter definition and file assignments
-        for i in 0..lines.len() {
-            let line_copy = lines[i].clone();
-            let trimmed = line_copy.trim_start();
-            
-            // Rename filter definition
+        let target_filter_exists = defined.contains(to);
+        Ok((target_filter_exists, self.move_filter_tree(from, to)))
+    }
+    
+    /// Moves the files and child filters of `from` into the existing filter `to`
+    pub fn merge_filters(&mut self, from: &str, to: &str) -> Result<Vec<(String, String)>> {
+        Ok(self.move_filter_tree(from, to))
+    }
+    
+    /// Puts every filter under `root`: `Source Files\Core` becomes `root\Source Files\Core`, with the
+    /// files assigned to them following, and defines `root` and its parents. Files without a filter
+    /// stay where they are. Returns the filters moved paired with their new name, and the files moved.
+    pub fn reroot(&mut self, root: &str) -> (Vec<(String, String)>, Vec<String>) {
+        let mut moved_filters = Vec::new();
+        let mut moved_files = Vec::new();
+        let mut current_item: Option<String> = None;
+        let mut output = Vec::new();
+        for line in self.content.lines() {
+            let trimmed = line.trim();
             if trimmed.starts_with("<Filter Include=\"") {
-                if let Some(start) = line_copy.find("Include=\"") {
-                    if let Some(end) = line_copy[start + 9..].find('"') {
-                        let filter_name = &line_copy[start + 9..start + 9 + end];
-                        if filter_name == from {
-                            lines[i] = line_copy.replace(&format!("Include=\"{}\"", from), &format!("Include=\"{}\"", to));
-                        }
-                    }
-                }
+                let name = attribute_value(trimmed, "Include").unwrap_or_default();
+                let new_name = format!("{}\\{}", root, name);
+                output.push(line.replace(&format!("Include=\"{}\"", name), &format!("Include=\"{}\"", new_name)));
+                moved_filters.push((name.to_string(), new_name));
+                continue;
             }
-            
-            // Rename filter assignments in ClCompile entries
-            if trimmed.starts_with("<Filter>") && trimmed.ends_with("</Filter>") {
-                if let Some(filter_start) = line_copy.find("<Filter>") {
-                    if let Some(filter_end) = line_copy.find("</Filter>") {
-                        let filter_name = &line_copy[filter_start + 8..filter_end];
-                        if filter_name == from {
-                            lines[i] = line_copy.replace(&format!(">{}<", from), &format!(">{}<", to));
-                        }
-                    }
-                }
+            if let Some(name) = trimmed.strip_prefix("<Filter>").and_then(|rest| rest.strip_suffix("</Filter>")) {
+                output.push(line.replace(&format!(">{}<", name), &format!(">{}\\{}<", root, name)));
+                moved_files.extend(current_item.take());
+                continue;
             }
+            if let Some(include) = attribute_value(trimmed, "Include") {
+                current_item = Some(include.to_string());
+            }
+            output.push(line.to_string());
         }
-        
-        // Collect files that were moved
+        if moved_filters.is_empty() && moved_files.is_empty() {
+            return (moved_filters, moved_files);
+        }
+        self.content = output.join("\n");
+        self.ensure_filter(root, None);
+        (moved_filters, moved_files)
+    }
+    
+    /// Moves filter `from` and all of its child filters (`from\Child`, ...) under `to`, remapping
+    /// the files assigned to them. Definitions that collide with an existing filter are dropped so
+    /// the two trees merge. Returns the moved files paired with their new filter.
+    fn move_filter_tree(&mut self, from: &str, to: &str) -> Vec<(String, String)> {
+        let defined = self.filter_definitions();
+        let lines: Vec<&str> = self.content.lines().collect();
+        let mut output = Vec::with_capacity(lines.len());
+        let mut moved_files = Vec::new();
+        let mut current_item: Option<&str> = None;
         let mut i = 0;
+        
         while i < lines.len() {
-            let line = &lines[i];
-            if line.trim_start().starts_with("<ClCompile Include=\"") {
-                if let Some(start) = line.find("Include=\"") {
-                    if let Some(end) = line[start + 9..].find('"') {
-                        let file_path = &line[start + 9..start + 9 + end];
-                        
-                        // Look for the filter in subsequent lines
-                        let mut j = i + 1;
-                        while j < lines.len() && !lines[j].trim().starts_with("</ClCompile>") {
-                            if lines[j].contains(&format!(">{}<", to)) {
-                                renamed_files.push(file_path.to_string());
-                                break;
+            let line = lines[i];
+            let trimmed = line.trim();
+            
+            if trimmed.starts_with("<Filter Include=\"") {
+                let name = attribute_value(trimmed, "Include").unwrap_or_default();
+                if let Some(new_name) = remap_filter_path(name, from, to) {
+                    if defined.contains(&new_name) {
+                        // The target tree already defines this filter, drop the whole definition
+                        if !trimmed.ends_with("/>") {
+                            while i < lines.len() && !lines[i].trim().ends_with("</Filter>") {
+                                i += 1;
                             }
-                            j += 1;
                         }
+                    } else {
+                        output.push(line.replace(&format!("Include=\"{}\"", name), &format!("Include=\"{}\"", new_name)));
+                    }
+                    i += 1;
+                    continue;
+                }
+            } else if trimmed.starts_with("<Filter>") && trimmed.ends_with("</Filter>") {
+                let name = &trimmed["<Filter>".len()..trimmed.len() - "</Filter>".len()];
+                if let Some(new_name) = remap_filter_path(name, from, to) {
+                    output.push(line.replace(&format!(">{}<", name), &format!(">{}<", new_name)));
+                    if let Some(file) = current_item {
+                        moved_files.push((file.to_string(), new_name));
                     }
+                    i += 1;
+                    continue;
                 }
+            } else if let Some(include) = attribute_value(trimmed, "Include") {
+                current_item = Some(include);
             }
+            
+            output.push(line.to_string());
             i += 1;
         }
         
-        self.content = lines.join("\n");
-        Ok((target_filter_exists, renamed_files))
+        self.content = output.join("\n");
+        moved_files
+    }
+    
+    /// Removes the definitions of `filter` and its child filters, leaving file entries alone.
+    /// Returns the names of the removed filters.
+    pub fn remove_filter_tree(&mut self, filter: &str) -> Vec<String> {
+        let (content, removed) = remove_item_entries(&self.content, |element, include| {
+            element == "Filter" && is_in_filter_tree(include, filter)
+        });
+        self.content = content;
+        removed
+            .iter()
+            .filter_map(|(_, lines)| attribute_value(lines[0].trim(), "Include").map(str::to_string))
+            .collect()
+    }
+    
+    /// Files assigned to `filter` or one of its child filters; `None` when no such filter is defined
+    pub fn files_in_filter_tree(&self, filter: &str) -> Result<Option<HashSet<String>>> {
+        if !self.filter_definitions().contains(filter) {
+            return Ok(None);
+        }
+        Ok(Some(
+            self.get_item_filters()?
+                .into_iter()
+                .filter(|(_, file_filter)| is_in_filter_tree(file_filter, filter))
+                .map(|(file, _)| file)
+                .collect(),
+        ))
     }
     
-    pub fn merge_filters(&mut self, from: &str, to: &str) -> Result<Vec<String>> {
+    /// Names of all filters defined with `<Filter Include="...">`
+    fn filter_definitions(&self) -> HashSet<String> {
+        self.content
+            .lines()
+            .filter(|line| line.trim_start().starts_with("<Filter Include=\""))
+            .filter_map(|line| attribute_value(line, "Include"))
+            .map(|name| name.to_string())
+            .collect()
+    }
+
+    /// All defined filters in file order, with their `Extensions` metadata if present
+    pub fn get_filter_extensions(&self) -> Vec<(String, Option<String>)> {
+        parse_items(&self.content)
+            .into_iter()
+            .filter(|item| item.item_type == "Filter")
+            .map(|item| {
+                let extensions = item.metadata.into_iter().find(|m| m.name == "Extensions").map(|m| m.value);
+                (item.include, extensions)
+            })
+            .collect()
+    }
+
+    /// Gives every filter definition without a `<UniqueIdentifier>` a new GUID, which Visual Studio
+    /// expects on each filter. Returns the names of the filters that got one.
+    pub fn add_missing_identifiers(&mut self) -> Vec<String> {
         let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
-        let mut moved_files = Vec::new();
-        
-        // First pass: Move all files from 'from' filter to 'to' filter
+        let mut updated = Vec::new();
         let mut i = 0;
         while i < lines.len() {
-            let line = lines[i].clone();
-            if line.trim_start().starts_with("<ClCompile Include=\"") {
-                if let Some(start) = line.find("Include=\"") {
-                    if let Some(end) = line[start + 9..].find('"') {
-                        let file_path = line[start + 9..start + 9 + end].to_string();
-                        
-                        // Look for the filter in subsequent lines
-                        let mut j = i + 1;
-                        while j < lines.len() && !lines[j].trim().starts_with("</ClCompile>") {
-                            if lines[j].contains(&format!(">{}<", from)) {
-                                let new_line = lines[j].replace(&format!(">{}<", from), &format!(">{}<", to));
-                                lines[j] = new_line;
-                                moved_files.push(file_path);
-                                break;
-                            }
-                            j += 1;
-                        }
-                    }
+            let trimmed = lines[i].trim().to_string();
+            let name = attribute_value(&trimmed, "Include").filter(|_| element_name(&trimmed) == "Filter").map(str::to_string);
+            let Some(name) = name else {
+                i += 1;
+                continue;
+            };
+            let indent: String = lines[i].chars().take_while(|c| c.is_whitespace()).collect();
+            let identifier = format!("{}  <UniqueIdentifier>{}</UniqueIdentifier>", indent, filter_identifier(&name));
+            if trimmed.ends_with("/>") {
+                lines[i] = format!("{}{}>", indent, trimmed.trim_end_matches("/>").trim_end());
+                lines.insert(i + 1, identifier);
+                lines.insert(i + 2, format!("{}</Filter>", indent));
+                updated.push(name);
+                i += 3;
+                continue;
+            }
+            let mut end = i + 1;
+            while end < lines.len() && !lines[end].trim().starts_with("</Filter>") {
+                end += 1;
+            }
+            let has_identifier = lines[i + 1..end].iter().any(|line| line.trim().starts_with("<UniqueIdentifier>"));
+            if !has_identifier {
+                lines.insert(i + 1, identifier);
+                updated.push(name);
+                end += 1;
+            }
+            i = end + 1;
+        }
+        if !updated.is_empty() {
+            self.content = lines.join("\n");
+        }
+        updated
+    }
+
+    /// Gives filters a new `<UniqueIdentifier>`: every filter a random one, or with `duplicates_only`
+    /// those reusing the identifier of an earlier filter the one derived from their path (random if
+    /// that is taken too). Returns the names of the filters changed.
+    pub fn regenerate_identifiers(&mut self, duplicates_only: bool) -> Vec<String> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let mut seen: HashSet<String> = HashSet::new();
+        let mut updated = Vec::new();
+        let mut filter: Option<String> = None;
+        for line in &mut lines {
+            let trimmed = line.trim().to_string();
+            if element_name(&trimmed) == "Filter" {
+                filter = attribute_value(&trimmed, "Include").map(str::to_string);
+            } else if trimmed.starts_with("</Filter>") {
+                filter = None;
+            } else if let Some(name) = &filter {
+                let Some(identifier) = trimmed.strip_prefix("<UniqueIdentifier>").and_then(|rest| rest.strip_suffix("</UniqueIdentifier>")) else {
+                    continue;
+                };
+                if seen.insert(identifier.to_lowercase()) && duplicates_only {
+                    continue;
                 }
+                let identifier = Some(filter_identifier(name))
+                    .filter(|identifier| duplicates_only && !seen.contains(&identifier.to_lowercase()))
+                    .unwrap_or_else(|| format!("{{{}}}", uuid::Uuid::new_v4().to_string().to_uppercase()));
+                seen.insert(identifier.to_lowercase());
+                *line = line.replacen(&trimmed, &format!("<UniqueIdentifier>{}</UniqueIdentifier>", identifier), 1);
+                updated.push(name.clone());
             }
-            i += 1;
+        }
+        if !updated.is_empty() {
+            self.content = lines.join("\n");
+        }
+        updated
+    }
+
+    /// Defines `name` and any missing parent filters. Returns the names of the filters created.
+    pub fn ensure_filter(&mut self, name: &str, extensions: Option<&str>) -> Vec<String> {
+        let existing = self.filter_definitions();
+        let mut missing = Vec::new();
+        let mut path = String::new();
+        for part in name.split('\\') {
+            if !path.is_empty() {
+                path.push('\\');
+            }
+            path.push_str(part);
+            if !existing.contains(&path) {
+                missing.push(path.clone());
+            }
+        }
+        if missing.is_empty() {
+            return missing;
         }
         
-        // Second pass: Remove the empty 'from' filter definition
-        let mut i = 0;
-        while i < lines.len() {
-            let line = &lines[i];
-            if line.trim_start().starts_with("<Filter Include=\"") {
-                if let Some(start) = line.find("Include=\"") {
-                    if let Some(end) = line[start + 9..].find('"') {
-                        let filter_name = &line[start + 9..start + 9 + end];
-                        if filter_name == from {
-                            // Remove the filter definition
-                            if line.trim().ends_with("/>") {
-                                // Self-closing tag
-                                lines.remove(i);
-                            } else {
-                                // Multi-line entry, find the closing tag
-                                lines.remove(i);
-                                while i < lines.len() && !lines[i].trim().ends_with("</Filter>") {
-                                    lines.remove(i);
-                                }
-                                if i < lines.len() {
-                                    lines.remove(i); // Remove closing tag
-                                }
-                            }
-                            break;
-                        }
-                    }
+        let mut definitions = String::new();
+        for filter in &missing {
+            definitions.push_str(&format!("    <Filter Include=\"{}\">\n", filter));
+            definitions.push_str(&format!("      <UniqueIdentifier>{}</UniqueIdentifier>\n", filter_identifier(filter)));
+            if let Some(extensions) = extensions.filter(|_| filter == name) {
+                definitions.push_str(&format!("      <Extensions>{}</Extensions>\n", extensions));
+            }
+            definitions.push_str("    </Filter>\n");
+        }
+        self.insert_into_item_group("Filter", &definitions);
+        missing
+    }
+
+    /// Assigns the item `file` to `filter`, adding an entry of `item_type` when the file is not listed yet.
+    /// Returns the previous filter (`None` if the file had none) when the assignment changed.
+    pub fn set_file_filter(&mut self, item_type: &str, file: &str, filter: &str) -> Option<Option<String>> {
+        let mut lines: Vec<String> = self.content.lines().map(|s| s.to_string()).collect();
+        let filter_line = format!("      <Filter>{}</Filter>", filter);
+        
+        let Some(i) = lines.iter().position(|line| {
+            let trimmed = line.trim();
+            element_name(trimmed) != "Filter"
+                && attribute_value(trimmed, "Include").is_some_and(|include| include.eq_ignore_ascii_case(file))
+        }) else {
+            let entry = format!("    <{} Include=\"{}\">\n{}\n    </{}>\n", item_type, file, filter_line, item_type);
+            self.insert_into_item_group(item_type, &entry);
+            return Some(None);
+        };
+        
+        let trimmed = lines[i].trim().to_string();
+        let element = element_name(&trimmed).to_string();
+        let indent: String = lines[i].chars().take_while(|c| c.is_whitespace()).collect();
+        if trimmed.ends_with("/>") {
+            let include = attribute_value(&trimmed, "Include").unwrap_or_default().to_string();
+            lines[i] = format!("{}<{} Include=\"{}\">", indent, element, include);
+            lines.insert(i + 1, filter_line);
+            lines.insert(i + 2, format!("{}</{}>", indent, element));
+            self.content = lines.join("\n");
+            return Some(None);
+        }
+        
+        let closing_tag = format!("</{}>", element);
+        let mut end = i + 1;
+        while end < lines.len() && !lines[end].trim().starts_with(&closing_tag) {
+            if let Some(current) = parse_metadata_line(&lines[end]).filter(|m| m.name == "Filter") {
+                if current.value == filter {
+                    return None;
                 }
+                lines[end] = filter_line;
+                self.content = lines.join("\n");
+                return Some(Some(current.value));
             }
-            i += 1;
+            end += 1;
         }
-        
+        lines.insert(end, filter_line);
         self.content = lines.join("\n");
-        Ok(moved_files)
+        Some(None)
     }
 
-    pub fn save(&self) -> Result<()> {
-        fs::write(&self.path, &self.content)
-            .with_context(|| format!("Failed to write filters file: {}", self.path.display()))?;
-        Ok(())
+    /// Inserts `entries` at the end of the first ItemGroup holding `item_type` elements,
+    /// or in a new ItemGroup before `</Project>`
+    fn insert_into_item_group(&mut self, item_type: &str, entries: &str) {
+        let opening = format!("<{} Include=", item_type);
+        if let Some(pos) = self.content.find(&opening) {
+            let group_end = self.content[pos..].find("</ItemGroup>").map(|end| pos + end);
+            if let Some(group_end) = group_end {
+                let line_start = self.content[..group_end].rfind('\n').map(|p| p + 1).unwrap_or(group_end);
+                self.content.insert_str(line_start, entries);
+                return;
+            }
+        }
+        
+        if let Some(pos) = self.content.rfind("</Project>") {
+            self.content.insert_str(pos, &format!("  <ItemGroup>\n{}  </ItemGroup>\n", entries));
+        }
+    }
+
+    /// Writes the filters file. Returns false (and writes nothing) when the content is unchanged.
+    pub fn save(&self) -> Result<bool> {
+        write_file(&self.path, &saved_content(&self.path, &self.content))
+            .with_context(|| format!("Failed to write filters file: {}", self.path.display()))
     }
 }
 
@@ -879,17 +2979,17 @@ impl ProjectStructure {
         let mut files = vcxproj.get_project_files()?;
         
         let project_name = vcxproj_path
-            .file_stem()
+            .file_name()
             .unwrap_or_default()
             .to_string_lossy()
             .to_string();
         
         // Try to load filter file
-        let filter_path = vcxproj_path.with_extension("vcxproj.filters");
-        let (filters, file_filters) = if filter_path.exists() {
+        let filter_path = filters_path(vcxproj_path);
+        let (filters, file_filters) = if crate::pipe::exists(&filter_path) {
             let filter_file = FilterFile::load(&filter_path)?;
             let filters = filter_file.get_all_filters()?;
-            let file_filters = filter_file.get_file_filters()?;
+            let file_filters = filter_file.get_item_filters()?;
             (filters, file_filters)
         } else {
             (HashMap::new(), HashMap::new())
@@ -904,15 +3004,18 @@ impl ProjectStructure {
             name: project_name,
             files,
             filters,
+            missing: HashSet::new(),
         })
     }
     
-    pub fn display_tree(&self, files_only: bool, _show_extensions: bool, level: Option<usize>) -> String {
+    /// With `show_props`, files are annotated with their notable per-file settings (see `notable_settings`).
+    /// `sort` orders the filters and files under each filter (see `sort_tree_entries`).
+    pub fn display_tree(&self, files_only: bool, _show_extensions: bool, level: Option<usize>, show_props: bool, sort: Option<&str>) -> String {
         let mut output = String::new();
         
         // Project root - always show extension
-        let project_display = format!("{}.vcxproj", self.name);
-        output.push_str(&format!("📁 {}\n", project_display));
+        let project_display = &self.name;
+        output.push_str(&format!("{}{}\n", tree_glyphs().project, project_display));
         
         if self.files.is_empty() && self.filters.is_empty() {
             output.push_str("   (empty project)\n");
@@ -932,11 +3035,12 @@ impl ProjectStructure {
         }
         
         // Build hierarchical tree structure
-        self.display_hierarchical_tree(&mut output, &filter_files, &unfiltered_files, level, files_only);
+        self.display_hierarchical_tree(&mut output, &filter_files, &unfiltered_files, level, files_only, show_props, sort);
         
         output
     }
     
+    #[allow(clippy::too_many_arguments)]
     fn display_hierarchical_tree(
         &self,
         output: &mut String,
@@ -944,6 +3048,8 @@ impl ProjectStructure {
         unfiltered_files: &[&ProjectFile],
         level: Option<usize>,
         files_only: bool,
+        show_props: bool,
+        sort: Option<&str>,
     ) {
         // Build a simple hierarchical structure
         use std::collections::BTreeMap;
@@ -980,30 +3086,25 @@ impl ProjectStructure {
             }
         }
         
-        // Display unfiltered files first at root level (unless level=0 which means folders only)
-        let show_root_files = level.map_or(true, |l| l > 0);
-        let unfiltered_count = if show_root_files { unfiltered_files.len() } else { 0 };
-        let total_root_items = unfiltered_count + filter_tree.get("").map_or(0, |v| v.len());
-        let mut current_index = 0;
-        
+        // Unfiltered files come first at root level (unless level=0 which means folders only)
+        let show_root_files = level.is_none_or(|l| l > 0);
+        let mut entries: Vec<TreeEntry> = Vec::new();
         if show_root_files {
-            for file in unfiltered_files {
-                let is_last = current_index == total_root_items - 1;
-                let symbol = if is_last { "└── " } else { "├── " };
-                let file_name = std::path::Path::new(&file.path)
-                    .file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy();
-                output.push_str(&format!("{}📄 {}\n", symbol, file_name));
-                current_index += 1;
-            }
-        }
-        
-        // Display root level filters
-        if let Some(root_filters) = filter_tree.get("") {
-            for filter_name in root_filters {
-                let is_last = current_index == total_root_items - 1;
-                self.display_filter_recursive(
+            entries.extend(unfiltered_files.iter().map(|file| TreeEntry::File(file)));
+        }
+        entries.extend(filter_tree.get("").into_iter().flatten().map(|filter| TreeEntry::Filter(filter)));
+        sort_tree_entries(&mut entries, sort);
+        
+        let glyphs = tree_glyphs();
+        let total_root_items = entries.len();
+        for (index, entry) in entries.into_iter().enumerate() {
+            let is_last = index == total_root_items - 1;
+            match entry {
+                TreeEntry::File(file) => {
+                    let symbol = if is_last { glyphs.last_branch } else { glyphs.branch };
+                    output.push_str(&format!("{}{}{}{}\n", symbol, glyphs.file, file_name(file), self.file_suffix(file, show_props)));
+                }
+                TreeEntry::Filter(filter_name) => self.display_filter_recursive(
                     output,
                     filter_name,
                     &filter_tree,
@@ -1013,12 +3114,14 @@ impl ProjectStructure {
                     1,
                     level,
                     files_only,
-                );
-                current_index += 1;
+                    show_props,
+                    sort,
+                ),
             }
         }
     }
     
+    #[allow(clippy::too_many_arguments)]
     fn display_filter_recursive(
         &self,
         output: &mut String,
@@ -1030,6 +3133,8 @@ impl ProjectStructure {
         depth: usize,
         max_level: Option<usize>,
         files_only: bool,
+        show_props: bool,
+        sort: Option<&str>,
     ) {
         // Check level restriction for folders
         // For level 0, we show all folders but no files
@@ -1054,60 +3159,957 @@ impl ProjectStructure {
         }
         
         // Display this filter
-        let symbol = if is_last { "└── " } else { "├── " };
+        let glyphs = tree_glyphs();
+        let symbol = if is_last { glyphs.last_branch } else { glyphs.branch };
         let display_name = if filter_name.contains('\\') {
-            filter_name.split('\\').last().unwrap()
+            filter_name.split('\\').next_back().unwrap()
         } else {
             filter_name
         };
-        output.push_str(&format!("{}{}📁 {}\n", prefix, symbol, display_name));
+        output.push_str(&format!("{}{}{}{}\n", prefix, symbol, glyphs.folder, display_name));
         
         // Prepare prefix for children
-        let child_prefix = format!("{}{}", prefix, if is_last { "    " } else { "│   " });
+        let child_prefix = format!("{}{}", prefix, if is_last { glyphs.blank } else { glyphs.pipe });
         
-        // Display children (sub-filters and files)
-        let total_children = children.len() + files.len();
-        let mut child_index = 0;
-        
-        // Display child filters first
-        for child_filter in &children {
-            let is_last_child = child_index == total_children - 1;
-            self.display_filter_recursive(
-                output,
-                child_filter,
-                filter_tree,
-                filter_files_map,
-                &child_prefix,
-                is_last_child,
-                depth + 1,
-                max_level,
-                files_only,
-            );
-            child_index += 1;
-        }
-        
-        // Display files in this filter (only if level allows and level > 0)
-        // Level 0 means folders only, so no files should be shown
-        // Files are considered to be at depth + 1 relative to their containing folder
+        // Child filters first, then the files in this filter, if the level allows. Level 0 means
+        // folders only, and files are considered to be at depth + 1 relative to their containing folder.
         let file_depth = depth + 1;
-        let show_files = max_level.map_or(true, |max| max > 0 && file_depth <= max);
+        let show_files = max_level.is_none_or(|max| max > 0 && file_depth <= max);
+        let mut entries: Vec<TreeEntry> = children.iter().map(|child| TreeEntry::Filter(child)).collect();
         if show_files {
             let mut sorted_files = files;
             sorted_files.sort_by_key(|f| &f.path);
-            
-            for file in &sorted_files {
-                let is_last_file = child_index == total_children - 1;
-                let file_symbol = if is_last_file { "└── " } else { "├── " };
+            entries.extend(sorted_files.into_iter().map(TreeEntry::File));
+        }
+        sort_tree_entries(&mut entries, sort);
+        
+        let total_children = entries.len();
+        for (child_index, entry) in entries.into_iter().enumerate() {
+            let is_last_child = child_index == total_children - 1;
+            match entry {
+                TreeEntry::Filter(child_filter) => self.display_filter_recursive(
+                    output,
+                    child_filter,
+                    filter_tree,
+                    filter_files_map,
+                    &child_prefix,
+                    is_last_child,
+                    depth + 1,
+                    max_level,
+                    files_only,
+                    show_props,
+                    sort,
+                ),
+                TreeEntry::File(file) => {
+                    let file_symbol = if is_last_child { glyphs.last_branch } else { glyphs.branch };
+                    output.push_str(&format!("{}{}{}{}{}\n", child_prefix, file_symbol, glyphs.file, file_name(file), self.file_suffix(file, show_props)));
+                }
+            }
+        }
+    }
+    
+    /// What follows a file in the tree: its settings, and the marker of a file missing on disk
+    fn file_suffix(&self, file: &ProjectFile, show_props: bool) -> String {
+        let mut suffix = settings_suffix(file, show_props);
+        if self.missing.contains(&file.path) {
+            suffix.push_str(tree_glyphs().missing);
+        }
+        suffix
+    }
+    
+    /// Keeps the files for which `keep` is true, and the filters leading to them
+    pub fn retain_files(&mut self, keep: impl Fn(&ProjectFile) -> bool) {
+        self.files.retain(|file| keep(file));
+        let used: HashSet<&str> = self.files.iter().filter_map(|file| file.filter.as_deref()).collect();
+        self.filters.retain(|filter, _| used.iter().any(|used| is_in_filter_tree(used, filter)));
+        let kept: HashSet<&str> = self.files.iter().map(|file| file.path.as_str()).collect();
+        for files in self.filters.values_mut() {
+            files.retain(|file| kept.contains(file.as_str()));
+        }
+    }
+}
+
+/// A filter or a file under a filter of the `view` tree
+enum TreeEntry<'a> {
+    Filter(&'a str),
+    File(&'a ProjectFile),
+}
+
+impl TreeEntry<'_> {
+    /// The name shown in the tree, in lowercase for sorting
+    fn sort_name(&self) -> String {
+        match self {
+            TreeEntry::Filter(filter) => filter.rsplit('\\').next().unwrap_or(filter).to_lowercase(),
+            TreeEntry::File(file) => file_name(file).to_lowercase(),
+        }
+    }
+}
+
+/// Orders the entries under a filter for `view --sort`: "name" mixes filters and files by name,
+/// "ext" lists the filters by name then the files by extension and name, and "filter" the filters
+/// then the files, each by name. Without `sort` they keep their order.
+fn sort_tree_entries(entries: &mut [TreeEntry], sort: Option<&str>) {
+    let is_file = |entry: &TreeEntry| matches!(entry, TreeEntry::File(_));
+    match sort {
+        Some("name") => entries.sort_by_cached_key(|entry| entry.sort_name()),
+        Some("ext") => entries.sort_by_cached_key(|entry| {
+            let name = entry.sort_name();
+            let extension = if is_file(entry) { name.rsplit_once('.').map_or("", |(_, ext)| ext).to_string() } else { String::new() };
+            (is_file(entry), extension, name)
+        }),
+        Some(_) => entries.sort_by_cached_key(|entry| (is_file(entry), entry.sort_name())),
+        None => {}
+    }
+}
+
+/// The file name of a file in the `view` tree, e.g. "main.cpp" for `src\main.cpp`
+fn file_name(file: &ProjectFile) -> std::borrow::Cow<'_, str> {
+    Path::new(&file.path).file_name().unwrap_or_default().to_string_lossy()
+}
+
+/// "  [excluded (Release|x64); creates PCH]" after a file in the tree, or nothing. The outputs of a
+/// custom build step are always shown: "  → a.pb.cc, a.pb.h".
+fn settings_suffix(file: &ProjectFile, show_props: bool) -> String {
+    let mut suffix = String::new();
+    let outputs = file.metadata.iter().filter(|entry| entry.name == "Outputs").map(|entry| {
+        let outputs: Vec<String> = entry
+            .value
+            .split(';')
+            .map(|output| expand_item_metadata(output.trim(), &file.path))
+            .filter(|output| !output.is_empty())
+            .collect();
+        (outputs.join(", "), entry.condition.as_deref().map(|c| condition_config(c).into_owned()))
+    });
+    let outputs = with_configs(outputs.collect());
+    if !outputs.is_empty() {
+        suffix.push_str(&format!("  → {}", outputs.join("; ")));
+    }
+    let notes = if show_props { notable_settings(&file.metadata) } else { Vec::new() };
+    if !notes.is_empty() {
+        suffix.push_str(&format!("  [{}]", notes.join("; ")));
+    }
+    suffix
+}
+
+/// Expands the well-known item metadata of `include` in a custom build value, e.g.
+/// `%(Filename).pb.h` → `addressbook.pb.h` for `proto\addressbook.proto`
+fn expand_item_metadata(text: &str, include: &str) -> String {
+    let path = Path::new(include.rsplit(['\\', '/']).next().unwrap_or(include));
+    let relative_dir = include.rfind(['\\', '/']).map_or("", |end| &include[..=end]);
+    text.replace("%(Filename)", &path.file_stem().unwrap_or_default().to_string_lossy())
+        .replace("%(Extension)", &path.extension().map(|e| format!(".{}", e.to_string_lossy())).unwrap_or_default())
+        .replace("%(RelativeDir)", relative_dir)
+        .replace("%(Identity)", include)
+}
+
+/// The per-file settings that make a file behave differently from its neighbours: exclusion from
+/// the build, precompiled header roles, extra options and any other metadata. Settings made for
+/// some configurations only are followed by those configurations.
+fn notable_settings(metadata: &[ItemMetadata]) -> Vec<String> {
+    let mut notes = Vec::new();
+    for entry in metadata {
+        let value = entry.value.trim();
+        let note = match entry.name.as_str() {
+            "Filter" | "DependentUpon" | "SubType" | "Outputs" | "Message" => continue,
+            "Command" => "custom build".to_string(),
+            "ShaderType" => format!("{} shader", value.to_lowercase()),
+            "ShaderModel" => format!("model {}", value),
+            "EntryPointName" => format!("entry: {}", value),
+            "ObjectFileOutput" => format!("object: {}", value),
+            "AdditionalInputs" => format!("inputs: {}", value.replace("%(AdditionalInputs)", "").trim_matches(';')),
+            "ExcludedFromBuild" if value.eq_ignore_ascii_case("true") => "excluded".to_string(),
+            "ExcludedFromBuild" => continue,
+            "PrecompiledHeader" if value == "Create" => "creates PCH".to_string(),
+            "PrecompiledHeader" if value == "NotUsing" => "no PCH".to_string(),
+            "AdditionalOptions" => match value.replace("%(AdditionalOptions)", "").trim() {
+                "" => continue,
+                options => format!("options: {}", options),
+            },
+            name => format!("{}={}", name, value),
+        };
+        notes.push((note, entry.condition.as_deref().map(|c| condition_config(c).into_owned())));
+    }
+    with_configs(notes)
+}
+
+/// Merges notes made for several configurations into one, followed by those configurations, e.g.
+/// "excluded (Debug|x64, Release|x64)". Notes made without a condition apply to all configurations.
+fn with_configs(entries: Vec<(String, Option<String>)>) -> Vec<String> {
+    // Each note with the configurations it applies to, or None when it applies to all
+    let mut notes: Vec<(String, Option<Vec<String>>)> = Vec::new();
+    for (note, config) in entries {
+        match (notes.iter_mut().find(|(n, _)| *n == note), config) {
+            (Some((_, configs)), None) => *configs = None,
+            (Some((_, Some(configs))), Some(config)) => configs.push(config),
+            (Some(_), Some(_)) => {}
+            (None, config) => notes.push((note, config.map(|c| vec![c]))),
+        }
+    }
+    notes
+        .into_iter()
+        .map(|(note, configs)| match configs {
+            Some(configs) => format!("{} ({})", note, configs.join(", ")),
+            None => note,
+        })
+        .collect()
+}
+
+/// The pieces `view` draws its tree with
+pub struct TreeGlyphs {
+    pub branch: &'static str,
+    pub last_branch: &'static str,
+    /// Continues the branch of an ancestor that has later siblings
+    pub pipe: &'static str,
+    pub blank: &'static str,
+    pub project: &'static str,
+    pub folder: &'static str,
+    pub file: &'static str,
+    /// After a file that does not exist on disk
+    pub missing: &'static str,
+}
+
+const BOX_TREE: TreeGlyphs = TreeGlyphs {
+    branch: "├── ",
+    last_branch: "└── ",
+    pipe: "│   ",
+    blank: "    ",
+    project: "📁 ",
+    folder: "📁 ",
+    file: "📄 ",
+    missing: "  ❌ missing",
+};
+
+/// With `--plain` the tree is plain indentation with a label on every line, which screen readers announce
+const PLAIN_TREE: TreeGlyphs = TreeGlyphs {
+    branch: "  ",
+    last_branch: "  ",
+    pipe: "  ",
+    blank: "  ",
+    project: "project: ",
+    folder: "folder: ",
+    file: "file: ",
+    missing: "  (missing on disk)",
+};
+
+pub fn tree_glyphs() -> &'static TreeGlyphs {
+    if output::is_plain() {
+        &PLAIN_TREE
+    } else {
+        &BOX_TREE
+    }
+}
+
+/// Whether a file path passes an optional regex filter (`negate` keeps the non-matching paths)
+fn regex_allows(regex: Option<&Regex>, negate: bool, path: &str) -> bool {
+    regex.is_none_or(|regex| regex.is_match(path) != negate)
+}
+
+/// Maps a filter in the tree rooted at `from` to the same place under `to`,
+/// e.g. `Source Files\Engine` → `Src\Engine` when moving `Source Files` to `Src`. Filter names
+/// compare like paths, ignoring case and slash direction.
+fn remap_filter_path(name: &str, from: &str, to: &str) -> Option<String> {
+    let (name, from) = (include_path(name), include_path(from));
+    if name.eq_ignore_ascii_case(&from) {
+        return Some(to.to_string());
+    }
+    name.get(..from.len())
+        .filter(|prefix| prefix.eq_ignore_ascii_case(&from))
+        .map(|_| &name[from.len()..])
+        .filter(|rest| rest.starts_with('\\'))
+        .map(|rest| format!("{}{}", to, rest))
+}
+
+/// Whether `name` is `root` or one of its child filters
+fn is_in_filter_tree(name: &str, root: &str) -> bool {
+    remap_filter_path(name, root, root).is_some()
+}
+
+/// The tool sections of an ItemDefinitionGroup's `body`, e.g. ("ClCompile", its lines from
+/// `<ClCompile>` to `</ClCompile>`), in order. Lines outside a section go with the one before.
+fn tool_sections(body: &[String]) -> Vec<(String, Vec<String>)> {
+    let mut sections: Vec<(String, Vec<String>)> = Vec::new();
+    let mut open: Option<String> = None;
+    for line in body {
+        let trimmed = line.trim();
+        match &open {
+            Some(tool) if trimmed == format!("</{}>", tool) => open = None,
+            Some(_) => {}
+            None if trimmed.starts_with('<') && !trimmed.starts_with("<!--") => {
+                let tool = element_name(trimmed).to_string();
+                if !trimmed.ends_with("/>") && !trimmed.contains(&format!("</{}>", tool)) {
+                    open = Some(tool.clone());
+                }
+                sections.push((tool, Vec::new()));
+            }
+            None => {}
+        }
+        match sections.last_mut() {
+            Some((_, lines)) => lines.push(line.clone()),
+            None => sections.push((String::new(), vec![line.clone()])),
+        }
+    }
+    sections
+}
+/// The item type Visual Studio uses for a file, based on its extension
+pub fn item_type_for(path: &str) -> &'static str {
+    let extension = path.rsplit('.').next().unwrap_or_default().to_lowercase();
+    match extension.as_str() {
+        "c" | "cpp" | "cc" | "cxx" => "ClCompile",
+        "h" | "hpp" | "hh" | "hxx" | "inl" | "cuh" => "ClInclude",
+        "hlsl" => "FXCompile",
+        "cu" => "CudaCompile",
+        "ui" => "QtUic",
+        "qrc" => "QtRcc",
+        "rc" => "ResourceCompile",
+        "manifest" => "Manifest",
+        "ico" | "cur" | "bmp" => "Image",
+        _ => "None",
+    }
+}
+
+/// Like `item_type_for`, but reads headers from `dir` (the project's directory) to add those
+/// declaring Q_OBJECT or Q_GADGET classes as QtMoc items, which moc has to process
+pub fn item_type_in(dir: &Path, include: &str) -> &'static str {
+    let item_type = item_type_for(include);
+    let mocable = || {
+        fs::read(dir.join(include.replace('\\', "/"))).is_ok_and(|bytes| {
+            let content = String::from_utf8_lossy(&bytes);
+            content.contains("Q_OBJECT") || content.contains("Q_GADGET")
+        })
+    };
+    if item_type == "ClInclude" && mocable() {
+        "QtMoc"
+    } else {
+        item_type
+    }
+}
+
+/// Returns the identifying attribute of an item line and its value: `Include` for regular items,
+/// `Update` for items that add metadata to files declared elsewhere (e.g. by a wildcard)
+fn item_key(line: &str) -> Option<(&'static str, &str)> {
+    attribute_value(line, "Include")
+        .map(|value| ("Include", value))
+        .or_else(|| attribute_value(line, "Update").map(|value| ("Update", value)))
+}
+
+/// Extracts the value of `name="..."` from a single XML line
+pub fn attribute_value<'a>(line: &'a str, name: &str) -> Option<&'a str> {
+    let needle = format!("{}=\"", name);
+    let mut search_from = 0;
+    while let Some(pos) = line[search_from..].find(&needle) {
+        let start = search_from + pos;
+        // Make sure we matched a whole attribute name, not a suffix of another one
+        let preceded_by_space = start == 0 || line[..start].ends_with(char::is_whitespace);
+        let value_start = start + needle.len();
+        if preceded_by_space {
+            return line[value_start..].find('"').map(|end| &line[value_start..value_start + end]);
+        }
+        search_from = value_start;
+    }
+    None
+}
+
+/// Parses all items declared inside `<ItemGroup>` elements, including their metadata
+pub fn parse_items(content: &str) -> Vec<ProjectItem> {
+    let mut items = Vec::new();
+    let lines: Vec<&str> = content.lines().collect();
+    let mut in_item_group = false;
+    let mut i = 0;
+    
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        
+        if trimmed.starts_with("<ItemGroup") && !trimmed.ends_with("/>") {
+            in_item_group = true;
+        } else if trimmed.starts_with("</ItemGroup>") {
+            in_item_group = false;
+        } else if in_item_group && trimmed.starts_with('<') && !trimmed.starts_with("</") {
+            let item_type = element_name(trimmed);
+            if let Some(include) = attribute_value(trimmed, "Include") {
+                let mut item = ProjectItem {
+                    item_type: item_type.to_string(),
+                    include: include.to_string(),
+                    metadata: Vec::new(),
+                };
+                
+                let closing_tag = format!("</{}>", item_type);
+                if !trimmed.ends_with("/>") && !trimmed.ends_with(&closing_tag) {
+                    // Multi-line item, collect metadata until the closing tag
+                    i += 1;
+                    while i < lines.len() && !lines[i].trim().starts_with(&closing_tag) {
+                        if let Some(metadata) = parse_metadata_line(lines[i]) {
+                            item.metadata.push(metadata);
+                        }
+                        i += 1;
+                    }
+                }
                 
-                let file_name = std::path::Path::new(&file.path)
-                    .file_name()
-                    .unwrap_or_default()
-                    .to_string_lossy();
+                items.push(item);
+            }
+        }
+        i += 1;
+    }
+    
+    items
+}
+
+/// Removes the ItemGroup entries for which `remove(element, include)` holds, together with their
+/// metadata. Returns the new content and the element name and exact lines of every removed entry.
+fn remove_item_entries(content: &str, remove: impl Fn(&str, &str) -> bool) -> (String, Vec<(String, Vec<String>)>) {
+    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let mut removed = Vec::new();
+    let mut in_item_group = false;
+    let mut i = 0;
+    
+    while i < lines.len() {
+        let trimmed = lines[i].trim().to_string();
+        if trimmed.starts_with("<ItemGroup") && !trimmed.ends_with("/>") {
+            in_item_group = true;
+        } else if trimmed.starts_with("</ItemGroup>") {
+            in_item_group = false;
+        }
+        
+        let matches = in_item_group
+            && !trimmed.starts_with("<ItemGroup")
+            && attribute_value(&trimmed, "Include").is_some_and(|include| remove(element_name(&trimmed), include));
+        if !matches {
+            i += 1;
+            continue;
+        }
+        
+        let item_type = element_name(&trimmed).to_string();
+        let closing_tag = format!("</{}>", item_type);
+        let mut end = i;
+        if !trimmed.ends_with("/>") && !trimmed.ends_with(&closing_tag) {
+            while end + 1 < lines.len() && !lines[end].trim().starts_with(&closing_tag) {
+                end += 1;
+            }
+        }
+        removed.push((item_type, lines.drain(i..=end).collect()));
+    }
+    
+    (lines.join("\n"), removed)
+}
+
+/// Removes the first ItemGroup entry for which `matches(element, include)` holds, with its metadata,
+/// and the ItemGroup too when that leaves it empty. Returns the new content and the element name
+/// and exact lines of the entry.
+fn take_item_entry(content: &str, matches: impl Fn(&str, &str) -> bool) -> Option<(String, String, Vec<String>)> {
+    let mut lines: Vec<String> = content.lines().map(|s| s.to_string()).collect();
+    let mut group = None;
+    let mut start = None;
+    for (k, line) in lines.iter().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.starts_with("<ItemGroup") && !trimmed.ends_with("/>") {
+            group = Some(k);
+        } else if trimmed.starts_with("</ItemGroup>") {
+            group = None;
+        } else if group.is_some() && attribute_value(trimmed, "Include").is_some_and(|include| matches(element_name(trimmed), include)) {
+            start = Some(k);
+            break;
+        }
+    }
+    let start = start?;
+    let group = group?;
+    
+    let trimmed = lines[start].trim().to_string();
+    let element = element_name(&trimmed).to_string();
+    let closing_tag = format!("</{}>", element);
+    let mut end = start;
+    if !trimmed.ends_with("/>") && !trimmed.ends_with(&closing_tag) {
+        while end + 1 < lines.len() && !lines[end].trim().starts_with(&closing_tag) {
+            end += 1;
+        }
+    }
+    let taken: Vec<String> = lines.drain(start..=end).collect();
+    if group + 1 == start && lines.get(start).is_some_and(|line| line.trim().starts_with("</ItemGroup>")) {
+        lines.drain(group..=start);
+    }
+    Some((lines.join("\n"), element, taken))
+}
+
+/// Item lines taken by `take_item_entry` with their element renamed from `from` to `to`
+fn retyped_lines(lines: &[String], from: &str, to: &str) -> Vec<String> {
+    let last = lines.len() - 1;
+    lines
+        .iter()
+        .enumerate()
+        .map(|(k, line)| {
+            let mut line = line.clone();
+            if k == 0 {
+                line = line.replacen(&format!("<{} ", from), &format!("<{} ", to), 1);
+            }
+            if k == last {
+                line = line.replace(&format!("</{}>", from), &format!("</{}>", to));
+            }
+            line
+        })
+        .collect()
+}
+
+/// What `merge_item_groups` changed
+#[derive(Debug, Default)]
+pub struct ItemGroupMerge {
+    /// Item type, number of groups folded into an earlier group, and number of items moved
+    pub merged: Vec<(String, usize, usize)>,
+    pub empty_removed: usize,
+}
+
+/// Merges fragmented ItemGroups: consecutive plain `<ItemGroup>`s holding a single item type are
+/// combined into the first of them, and empty plain groups are removed. Items move verbatim with
+/// their metadata and Condition attributes, keeping their relative order. Groups with a Label or
+/// Condition, groups mixing item types and groups with comments are left as they are, and an item
+/// type is never moved across such a group that also holds it.
+pub fn merge_item_groups(content: &str) -> (String, ItemGroupMerge) {
+    struct Group {
+        start: usize,
+        end: usize,
+        plain: bool,
+        item_types: Vec<String>,
+        items: Vec<Vec<String>>,
+    }
+    
+    let lines: Vec<&str> = content.lines().collect();
+    let mut groups = Vec::new();
+    let mut i = 0;
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        if trimmed.starts_with("<ItemGroup") && trimmed.ends_with("/>") {
+            groups.push(Group { start: i, end: i, plain: trimmed == "<ItemGroup/>" || trimmed == "<ItemGroup />", item_types: Vec::new(), items: Vec::new() });
+        } else if trimmed.starts_with("<ItemGroup") {
+            let mut group = Group { start: i, end: i, plain: trimmed == "<ItemGroup>", item_types: Vec::new(), items: Vec::new() };
+            i += 1;
+            while i < lines.len() && !lines[i].trim().starts_with("</ItemGroup>") {
+                let line = lines[i].trim();
+                if line.starts_with("<!--") || !line.starts_with('<') {
+                    if !line.is_empty() {
+                        group.plain = false;
+                    }
+                    i += 1;
+                    continue;
+                }
                 
-                output.push_str(&format!("{}{}📄 {}\n", child_prefix, file_symbol, file_name));
-                child_index += 1;
+                let item_type = element_name(line).to_string();
+                let closing_tag = format!("</{}>", item_type);
+                let mut item = vec![lines[i].to_string()];
+                if !line.ends_with("/>") && !line.ends_with(&closing_tag) {
+                    i += 1;
+                    while i < lines.len() && !lines[i].trim().starts_with(&closing_tag) {
+                        item.push(lines[i].to_string());
+                        i += 1;
+                    }
+                    if i < lines.len() {
+                        item.push(lines[i].to_string());
+                    }
+                }
+                if !group.item_types.contains(&item_type) {
+                    group.item_types.push(item_type);
+                }
+                group.items.push(item);
+                i += 1;
+            }
+            group.end = i.min(lines.len() - 1);
+            group.plain &= group.item_types.len() <= 1;
+            groups.push(group);
+        }
+        i += 1;
+    }
+    
+    // Each merged group's items are appended to the first group of its run
+    let mut summary = ItemGroupMerge::default();
+    let mut appended: HashMap<usize, Vec<String>> = HashMap::new();
+    let mut removed: HashSet<usize> = HashSet::new();
+    let mut run_start: HashMap<String, usize> = HashMap::new();
+    for (index, group) in groups.iter().enumerate() {
+        if group.plain && group.items.is_empty() {
+            removed.insert(index);
+            summary.empty_removed += 1;
+            continue;
+        }
+        if !group.plain {
+            for item_type in &group.item_types {
+                run_start.remove(item_type);
+            }
+            continue;
+        }
+        
+        let item_type = &group.item_types[0];
+        match run_start.get(item_type) {
+            Some(&target) => {
+                appended.entry(target).or_default().extend(group.items.iter().flatten().cloned());
+                removed.insert(index);
+                match summary.merged.iter_mut().find(|(t, _, _)| t == item_type) {
+                    Some(entry) => {
+                        entry.1 += 1;
+                        entry.2 += group.items.len();
+                    }
+                    None => summary.merged.push((item_type.clone(), 1, group.items.len())),
+                }
+            }
+            None => {
+                run_start.insert(item_type.clone(), index);
+            }
+        }
+    }
+    
+    let mut output: Vec<String> = Vec::with_capacity(lines.len());
+    let mut next_group = 0;
+    let mut k = 0;
+    while k < lines.len() {
+        match groups.get(next_group) {
+            Some(group) if group.start == k => {
+                if !removed.contains(&next_group) {
+                    output.extend(lines[group.start..group.end].iter().map(|l| l.to_string()));
+                    output.extend(appended.remove(&next_group).unwrap_or_default());
+                    output.push(lines[group.end].to_string());
+                }
+                k = group.end + 1;
+                next_group += 1;
+            }
+            _ => {
+                output.push(lines[k].to_string());
+                k += 1;
+            }
+        }
+    }
+    
+    (output.join("\n"), summary)
+}
+
+/// Sorts the entries of every ItemGroup by path (case-insensitive, slash direction ignored), then
+/// item type, moving each entry with its metadata. Labeled groups such as ProjectConfigurations,
+/// which Visual Studio keeps in its own order, and groups with comments or with `Update`/`Remove`
+/// entries, whose meaning depends on order, are left alone. Returns the new content and the number
+/// of groups reordered.
+pub fn sort_item_groups(content: &str) -> (String, usize) {
+    let lines: Vec<&str> = content.lines().collect();
+    let mut output: Vec<String> = Vec::with_capacity(lines.len());
+    let mut sorted_groups = 0;
+    let mut i = 0;
+    while i < lines.len() {
+        let trimmed = lines[i].trim();
+        output.push(lines[i].to_string());
+        i += 1;
+        if !trimmed.starts_with("<ItemGroup") || trimmed.ends_with("/>") {
+            continue;
+        }
+        
+        let mut sortable = attribute_value(trimmed, "Label").is_none();
+        let mut items: Vec<(String, String, Vec<&str>)> = Vec::new();
+        while i < lines.len() && !lines[i].trim().starts_with("</ItemGroup>") {
+            let line = lines[i].trim();
+            if line.starts_with("<!--") || !line.starts_with('<') {
+                sortable &= line.is_empty();
+                items.push((String::new(), String::new(), vec![lines[i]]));
+                i += 1;
+                continue;
+            }
+            sortable &= attribute_value(line, "Update").is_none() && attribute_value(line, "Remove").is_none();
+            let item_type = element_name(line).to_string();
+            let key = attribute_value(line, "Include").unwrap_or_default().replace('\\', "/").to_lowercase();
+            let closing_tag = format!("</{}>", item_type);
+            let mut item = vec![lines[i]];
+            if !line.ends_with("/>") && !line.ends_with(&closing_tag) {
+                i += 1;
+                while i < lines.len() && !lines[i].trim().starts_with(&closing_tag) {
+                    item.push(lines[i]);
+                    i += 1;
+                }
+                if i < lines.len() {
+                    item.push(lines[i]);
+                }
+            }
+            items.push((key, item_type.to_lowercase(), item));
+            i += 1;
+        }
+        
+        if sortable {
+            items.retain(|(_, _, item)| !item[0].trim().is_empty());
+            let mut sorted = items.clone();
+            sorted.sort_by(|a, b| (&a.0, &a.1).cmp(&(&b.0, &b.1)));
+            if sorted != items {
+                sorted_groups += 1;
+            }
+            items = sorted;
+        }
+        output.extend(items.into_iter().flat_map(|(_, _, item)| item).map(str::to_string));
+    }
+    (output.join("\n"), sorted_groups)
+}
+
+/// The filters file of a project: `App.vcxproj.filters`, or `Shared.vcxitems.filters` for a shared items project
+pub fn filters_path(project_path: &Path) -> PathBuf {
+    let mut name = project_path.file_name().unwrap_or_default().to_os_string();
+    name.push(".filters");
+    project_path.with_file_name(name)
+}
+
+/// Whether `path` is a shared items project (.vcxitems) or its filters file
+pub fn is_shared_items(path: &Path) -> bool {
+    let name = path.file_name().unwrap_or_default().to_string_lossy().to_lowercase();
+    name.ends_with(".vcxitems") || name.ends_with(".vcxitems.filters")
+}
+
+/// Rewrites the paths in the Include and Exclude attributes of items (not filter definitions or
+/// project capabilities), each part of a `;`-separated list on its own
+fn map_item_paths(content: &str, map: impl Fn(&str) -> Option<String>) -> String {
+    content
+        .split('\n')
+        .map(|line| {
+            let trimmed = line.trim_start();
+            let element = trimmed.strip_prefix('<').map(|rest| rest.split(|c: char| c.is_whitespace() || c == '>' || c == '/').next().unwrap_or_default());
+            if element.is_none_or(|e| e.is_empty() || ["Filter", "ProjectCapability", "ProjectConfiguration", "Import"].contains(&e)) {
+                return line.to_string();
+            }
+            let mut line = line.to_string();
+            for attribute in ["Include", "Exclude"] {
+                let Some(value) = attribute_value(&line, attribute).map(str::to_string) else {
+                    continue;
+                };
+                let mapped: Vec<String> = value.split(';').map(|part| map(part).unwrap_or_else(|| part.to_string())).collect();
+                let mapped = mapped.join(";");
+                if mapped != value {
+                    line = line.replacen(&format!("{}=\"{}\"", attribute, value), &format!("{}=\"{}\"", attribute, mapped), 1);
+                }
+            }
+            line
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Item paths of a shared items project as the other commands see them: relative to the .vcxitems,
+/// without the `$(MSBuildThisFileDirectory)` it is written with
+fn strip_shared_items_root(content: &str) -> String {
+    map_item_paths(content, |path| path.strip_prefix(SHARED_ITEMS_ROOT).map(str::to_string))
+}
+
+/// Writes the relative item paths of a shared items project with `$(MSBuildThisFileDirectory)` again,
+/// since they would otherwise resolve against each project importing it
+fn add_shared_items_root(content: &str) -> String {
+    map_item_paths(content, |path| {
+        let relative = !path.is_empty() && !path.contains("$(") && !path.contains(':') && !path.starts_with(['\\', '/']);
+        relative.then(|| format!("{}{}", SHARED_ITEMS_ROOT, path))
+    })
+}
+
+/// A path as MSBuild writes it in Include and Filter values: backslash separators, no doubled
+/// separators and no leading `.\`. Paths scanned on Linux, macOS or WSL come in with `/`.
+pub fn include_path(path: &str) -> String {
+    let unc = path.starts_with("\\\\") || path.starts_with("//");
+    let mut normalized = path.replace('/', "\\");
+    while normalized.contains("\\\\") {
+        normalized = normalized.replace("\\\\", "\\");
+    }
+    while let Some(rest) = normalized.strip_prefix(".\\") {
+        normalized = rest.to_string();
+    }
+    if unc {
+        normalized.insert(0, '\\');
+    }
+    normalized
+}
+
+/// The key paths from a project are compared by: `include_path` lowercased, since MSBuild paths
+/// are case-insensitive
+pub fn include_key(path: &str) -> String {
+    include_path(path).to_lowercase()
+}
+
+/// Whether two paths as written in a project name the same file, ignoring case and slash direction
+pub fn same_path(a: &str, b: &str) -> bool {
+    include_key(a) == include_key(b)
+}
+
+/// Whether an item matches a `delete` target: its extension is `extension`, or else its path
+/// contains `target` (a file, or a folder when it ends with a separator), ignoring case and slash direction
+fn matches_delete_target(include: &str, target: &str, extension: Option<&str>) -> bool {
+    let include = include_key(include);
+    match extension {
+        Some(ext) => include.ends_with(&format!(".{}", ext.trim_start_matches('.').to_lowercase())),
+        None => include.contains(&include_key(target)),
+    }
+}
+
+/// Rewrites paths in a project or filters file: the Include of every item, and each entry of the
+/// `lists` elements (e.g. `<AdditionalIncludeDirectories>`). `rewrite` returns the new path, or `None`
+/// to keep it. Returns the new content and each (old, new) path rewritten, in file order.
+pub fn rewrite_paths(content: &str, lists: &[&str], mut rewrite: impl FnMut(&str) -> Option<String>) -> (String, Vec<(String, String)>) {
+    let include = Regex::new(r#"(\sInclude=")([^"]*)(")"#).unwrap();
+    let mut rewritten = Vec::new();
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    for line in &mut lines {
+        let trimmed = line.trim();
+        if let Some(captures) = include.captures(line) {
+            let old = captures.get(2).unwrap();
+            if let Some(new) = rewrite(old.as_str()) {
+                rewritten.push((old.as_str().to_string(), new.clone()));
+                line.replace_range(old.range(), &new);
+            }
+            continue;
+        }
+        let name = element_name(trimmed);
+        if !trimmed.starts_with('<') || !lists.contains(&name) {
+            continue;
+        }
+        let close = format!("</{}>", name);
+        let (Some(start), Some(end)) = (line.find('>').map(|i| i + 1), line.rfind(&close)) else {
+            continue;
+        };
+        if start > end {
+            continue;
+        }
+        let mut changed = false;
+        let entries: Vec<String> = line[start..end]
+            .split(';')
+            .map(|entry| match rewrite(entry.trim()) {
+                Some(new) => {
+                    rewritten.push((entry.trim().to_string(), new.clone()));
+                    changed = true;
+                    new
+                }
+                None => entry.to_string(),
+            })
+            .collect();
+        if changed {
+            line.replace_range(start..end, &entries.join(";"));
+        }
+    }
+    (lines.join("\n"), rewritten)
+}
+
+/// `path` relative to the directory `from_dir`, with backslashes as written in project files
+/// (e.g. "..\\common.props"). Paths on another drive stay absolute.
+pub fn relative_path(from_dir: &Path, path: &Path) -> String {
+    let absolute = |p: &Path| -> PathBuf {
+        // A path that does not exist yet is resolved through its nearest existing ancestor
+        let mut existing = p;
+        let mut rest = Vec::new();
+        loop {
+            let dir = if existing.as_os_str().is_empty() { Path::new(".") } else { existing };
+            if let Ok(resolved) = fs::canonicalize(dir) {
+                return rest.iter().rev().fold(resolved, |acc: PathBuf, name| acc.join(name));
+            }
+            match (existing.parent(), existing.file_name()) {
+                (Some(parent), Some(name)) => {
+                    rest.push(name);
+                    existing = parent;
+                }
+                _ => return p.to_path_buf(),
             }
         }
+    };
+    let from_dir = absolute(if from_dir.as_os_str().is_empty() { Path::new(".") } else { from_dir });
+    let path_abs = absolute(path);
+    let from: Vec<_> = from_dir.components().collect();
+    let to: Vec<_> = path_abs.components().collect();
+    let common = from.iter().zip(&to).take_while(|(a, b)| a == b).count();
+    if common == 0 {
+        return path.display().to_string();
+    }
+    
+    let mut parts: Vec<String> = vec!["..".to_string(); from.len() - common];
+    parts.extend(to[common..].iter().map(|c| c.as_os_str().to_string_lossy().into_owned()));
+    parts.join("\\")
+}
+
+/// The configuration (e.g. "Debug|x64") named by a condition like `'$(Configuration)|$(Platform)'=='Debug|x64'`,
+/// however it is spaced, quoted or ordered (see `condition::config_selection`). Conditions that test
+/// anything else are returned as written.
+pub fn condition_config(condition: &str) -> Cow<'_, str> {
+    match condition::config_selection(condition) {
+        Some(selection) => Cow::Owned(selection.name()),
+        None => Cow::Borrowed(condition),
+    }
+}
+
+/// Whether `config` (e.g. "Debug|x64") is selected by `selector`: a full "Configuration|Platform"
+/// name, a configuration name alone ("Debug") matching every platform, or a platform alone ("*|x64")
+pub fn config_matches(config: &str, selector: &str) -> bool {
+    ConfigSelection::from_name(config).matches(selector)
+}
+
+/// Whether a group's condition applies to the configurations chosen by `selector` (see `config_matches`).
+/// Conditions that are not plain configuration tests, like `'$(Configuration)|$(Platform)'!='Release|x64'`,
+/// are evaluated for a full "Configuration|Platform" selector.
+pub fn condition_matches(condition: &str, selector: &str) -> bool {
+    if let Some(selection) = condition::config_selection(condition) {
+        return selection.matches(selector);
+    }
+    let selector = ConfigSelection::from_name(selector);
+    let (Some(configuration), Some(platform)) = (selector.configuration, selector.platform) else {
+        return false;
+    };
+    let property = |name: &str| match name {
+        _ if name.eq_ignore_ascii_case("Configuration") => Some(configuration.clone()),
+        _ if name.eq_ignore_ascii_case("Platform") => Some(platform.clone()),
+        _ => None,
+    };
+    condition::parse(condition).is_ok_and(|expr| condition::evaluate(&expr, &property) == Some(true))
+}
+
+/// Inserts `value` into the `;`-separated list of an `<element>...</element>` line at `position`
+fn insert_list_value(line: &str, element: &str, value: &str, position: &ListPosition) -> Result<String> {
+    let open = format!("<{}>", element);
+    let close = format!("</{}>", element);
+    let inherited = format!("%({})", element);
+    let indent: String = line.chars().take_while(|c| c.is_whitespace()).collect();
+    let list = line.trim().strip_prefix(&open).and_then(|rest| rest.strip_suffix(&close)).unwrap_or_default();
+    let mut entries: Vec<&str> = list.split(';').filter(|e| !e.trim().is_empty()).collect();
+
+    let find = |anchor: &str| {
+        entries
+            .iter()
+            .position(|e| e.trim().eq_ignore_ascii_case(anchor))
+            .ok_or_else(|| anyhow::anyhow!("{} is not in {}", anchor, element))
+    };
+    let index = match position {
+        ListPosition::Front => 0,
+        ListPosition::Back => entries.iter().position(|e| e.trim() == inherited).unwrap_or(entries.len()),
+        ListPosition::After(anchor) => find(anchor)? + 1,
+        ListPosition::Before(anchor) => find(anchor)?,
+    };
+    entries.insert(index, value);
+    Ok(format!("{}{}{}{}", indent, open, entries.join(";"), close))
+}
+
+/// Whether the `;`-separated list in an `<element>...</element>` line already holds `value`
+fn list_contains(line: &str, element: &str, value: &str) -> bool {
+    let open = format!("<{}>", element);
+    let close = format!("</{}>", element);
+    line.trim()
+        .strip_prefix(&open)
+        .and_then(|rest| rest.strip_suffix(&close))
+        .is_some_and(|list| list.split(';').any(|e| e.trim().eq_ignore_ascii_case(value.trim())))
+}
+
+/// Returns the element name of an opening tag line, e.g. `ClCompile` for `<ClCompile Include="a.c">`
+fn element_name(trimmed_line: &str) -> &str {
+    let without_bracket = trimmed_line.trim_start_matches('<');
+    let end = without_bracket
+        .find(|c: char| c.is_whitespace() || c == '>' || c == '/')
+        .unwrap_or(without_bracket.len());
+    &without_bracket[..end]
+}
+
+/// Parses a single-line metadata element such as `<Filter>Source Files</Filter>`
+fn parse_metadata_line(line: &str) -> Option<ItemMetadata> {
+    let trimmed = line.trim();
+    if !trimmed.starts_with('<') || trimmed.starts_with("</") {
+        return None;
+    }
+    
+    let name = element_name(trimmed);
+    let closing_tag = format!("</{}>", name);
+    let value_start = trimmed.find('>')? + 1;
+    let value_end = trimmed.rfind(&closing_tag)?;
+    if value_end < value_start {
+        return None;
     }
     
-}
\ No newline at end of file
+    Some(ItemMetadata {
+        name: name.to_string(),
+        condition: attribute_value(trimmed, "Condition").map(|c| c.to_string()),
+        value: trimmed[value_start..value_end].to_string(),
+    })
+}
@@ -0,0 +1,162 @@
+use anyhow::{Context, Result};
+use crate::i18n::info;
+use log::debug;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, SystemTime};
+
+static WAIT_FOR_CLOSE: AtomicBool = AtomicBool::new(false);
+
+/// Windows error codes for a file opened by another process without sharing
+const ERROR_SHARING_VIOLATION: i32 = 32;
+const ERROR_LOCK_VIOLATION: i32 = 33;
+
+/// Make the first write of the command wait until Visual Studio has closed the project
+pub fn set_wait_for_close(wait: bool) {
+    WAIT_FOR_CLOSE.store(wait, Ordering::Relaxed);
+}
+
+/// With `--wait-for-vs-close`, blocks until neither `path` nor the solution options (.suo) of a
+/// `.vs` directory next to it or above it are held open by another process. Checked once per command.
+pub fn wait_until_closed(path: &Path) -> Result<()> {
+    if !WAIT_FOR_CLOSE.swap(false, Ordering::Relaxed) {
+        return Ok(());
+    }
+
+    let mut watched = vec![path.to_path_buf()];
+    watched.extend(solution_option_files(path));
+    debug!("Checking for open handles on {} files", watched.len());
+
+    let mut announced = false;
+    while let Some(locked) = watched.iter().find(|file| is_locked(file)) {
+        if !announced {
+            info!("⏳ Waiting for Visual Studio to close ({} is in use) - press Ctrl+C to cancel", locked.display());
+            announced = true;
+        }
+        std::thread::sleep(Duration::from_secs(1));
+    }
+    if announced {
+        info!("Visual Studio was closed, continuing");
+    }
+    Ok(())
+}
+
+/// Visual Studio keeps `.vs/<solution>/v<version>/.suo` open while the solution is loaded
+fn solution_option_files(path: &Path) -> Vec<PathBuf> {
+    let mut files = Vec::new();
+    let start = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or_else(|| Path::new("."));
+    let Ok(start) = fs::canonicalize(start) else {
+        return files;
+    };
+    for dir in start.ancestors() {
+        let Ok(solutions) = fs::read_dir(dir.join(".vs")) else {
+            continue;
+        };
+        for solution in solutions.filter_map(|e| e.ok()) {
+            let Ok(versions) = fs::read_dir(solution.path()) else {
+                continue;
+            };
+            for version in versions.filter_map(|e| e.ok()) {
+                let suo = version.path().join(".suo");
+                if suo.is_file() {
+                    files.push(suo);
+                }
+            }
+        }
+    }
+    files
+}
+
+/// Whether another process holds `path` open without sharing it (only detectable on Windows)
+fn is_locked(path: &Path) -> bool {
+    match fs::OpenOptions::new().append(true).open(path) {
+        Ok(_) => false,
+        Err(e) => matches!(e.raw_os_error(), Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)),
+    }
+}
+
+/// Makes sure Visual Studio notices the written files and explains how to pick up the change.
+/// Visual Studio only offers to reload a project when the .vcxproj changes, so a project whose
+/// .filters file alone was written gets its .vcxproj touched.
+pub fn notify_reload(written: &[PathBuf]) -> Result<()> {
+    let mut projects: Vec<PathBuf> = Vec::new();
+    for file in written {
+        let name = file.to_string_lossy();
+        let project = match name.strip_suffix(".filters") {
+            Some(project) => PathBuf::from(project),
+            None if name.ends_with(".vcxproj") => file.clone(),
+            None => continue,
+        };
+        if !projects.contains(&project) {
+            projects.push(project);
+        }
+    }
+    if projects.is_empty() {
+        return Ok(());
+    }
+
+    for project in &projects {
+        if !written.contains(project) && project.exists() {
+            fs::File::options()
+                .write(true)
+                .open(project)
+                .and_then(|file| file.set_modified(SystemTime::now()))
+                .with_context(|| format!("Failed to touch {}", project.display()))?;
+            debug!("Touched {} so Visual Studio reloads it", project.display());
+        }
+    }
+
+    info!("\n🔄 If the solution is open, Visual Studio will offer to reload:");
+    for project in &projects {
+        info!("  - {}", project.file_name().unwrap_or_default().to_string_lossy());
+    }
+    info!("Choose \"Reload All\" (or close and reopen the solution if no prompt appears).");
+    Ok(())
+}
+
+/// Where the Windows 10/11 SDKs are installed: the KitsRoot10 registry value, then the default
+/// directories under Program Files
+fn windows_kits_roots() -> Vec<PathBuf> {
+    let mut roots = Vec::new();
+    for key in [r"HKLM\SOFTWARE\Microsoft\Windows Kits\Installed Roots", r"HKLM\SOFTWARE\WOW6432Node\Microsoft\Windows Kits\Installed Roots"] {
+        let Ok(output) = std::process::Command::new("reg").args(["query", key, "/v", "KitsRoot10"]).output() else {
+            break;
+        };
+        // "    KitsRoot10    REG_SZ    C:\Program Files (x86)\Windows Kits\10\"
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        if let Some((_, root)) = stdout.lines().find_map(|line| line.split_once("REG_SZ")) {
+            roots.push(PathBuf::from(root.trim()));
+        }
+    }
+    for variable in ["ProgramFiles(x86)", "ProgramFiles"] {
+        if let Some(program_files) = std::env::var_os(variable) {
+            roots.push(Path::new(&program_files).join("Windows Kits").join("10"));
+        }
+    }
+    roots
+}
+
+/// The newest Windows SDK installed on this machine, e.g. "10.0.22621.0": the highest version
+/// whose headers are in `Include\<version>\um` of a Windows Kits root
+pub fn latest_windows_sdk() -> Result<String> {
+    let mut versions: Vec<(Vec<u32>, String)> = Vec::new();
+    for root in windows_kits_roots() {
+        let Ok(entries) = fs::read_dir(root.join("Include")) else {
+            continue;
+        };
+        for entry in entries.filter_map(|e| e.ok()) {
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let parts: Option<Vec<u32>> = name.split('.').map(|part| part.parse().ok()).collect();
+            if let Some(parts) = parts.filter(|parts| parts.len() == 4 && entry.path().join("um").is_dir()) {
+                debug!("Found Windows SDK {} in {}", name, root.display());
+                versions.push((parts, name));
+            }
+        }
+    }
+    versions
+        .into_iter()
+        .max()
+        .map(|(_, version)| version)
+        .ok_or_else(|| anyhow::anyhow!("No Windows SDK is installed on this machine; pass the version with --value (or \"10.0\" for the newest one where the project is built)"))
+}